@@ -0,0 +1,48 @@
+/* Builds an engine, indexes a few documents, and prints a search response.
+ *
+ *   cargo build --release
+ *   cc examples/search.c -Iinclude -Ltarget/release -lsearus_c -o search
+ */
+
+#include <stdio.h>
+
+#include "searus.h"
+
+static int fail(char *error) {
+  fprintf(stderr, "error: %s\n", error);
+  searus_string_free(error);
+  return 1;
+}
+
+int main(void) {
+  char *error = NULL;
+  SearusEngine *engine = searus_engine_new(
+      "{\"semantic\": {\"fields\": {\"title\": {\"matcher\": \"bm25\"}}}, \"tags\": {}}", &error);
+  if (engine == NULL) {
+    return fail(error);
+  }
+
+  const char *documents = "["
+                          "{\"id\": \"1\", \"title\": \"Rust ownership\", \"price\": 30},"
+                          "{\"id\": \"2\", \"title\": \"Rust traits\", \"price\": 60},"
+                          "{\"id\": \"3\", \"title\": \"Python typing\", \"price\": 20}"
+                          "]";
+  if (searus_engine_put_many(engine, documents, "id", &error) != 0) {
+    searus_engine_free(engine);
+    return fail(error);
+  }
+
+  char *response = searus_engine_search(
+      engine,
+      "{\"text\": \"rust\", \"filters\": {\"Compare\": {\"field\": \"price\", \"op\": \"Lt\", \"value\": 50}}}",
+      &error);
+  if (response == NULL) {
+    searus_engine_free(engine);
+    return fail(error);
+  }
+
+  printf("%zu documents\n%s\n", searus_engine_len(engine), response);
+  searus_string_free(response);
+  searus_engine_free(engine);
+  return 0;
+}
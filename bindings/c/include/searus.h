@@ -0,0 +1,59 @@
+/*
+ * C bindings for the Searus search engine.
+ *
+ * Engines are built from a JSON engine config. Documents, queries, and
+ * responses are JSON strings. Functions that can fail take a `char **error`
+ * out-parameter: on failure they return NULL or -1 and, if `error` is not
+ * NULL, store a message in it to be freed with `searus_string_free`.
+ * Panics inside Searus are caught and reported the same way.
+ */
+
+#ifndef SEARUS_H
+#define SEARUS_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An engine over JSON documents, stored in memory by id. */
+typedef struct SearusEngine SearusEngine;
+
+/* Creates an engine from a JSON engine config. Returns NULL on error. */
+SearusEngine *searus_engine_new(const char *config, char **error);
+
+/* Frees an engine. Does nothing if `engine` is NULL. */
+void searus_engine_free(SearusEngine *engine);
+
+/* Adds or replaces the JSON document with the given id. Returns 0 on success. */
+int searus_engine_put(SearusEngine *engine, const char *id, const char *document, char **error);
+
+/*
+ * Adds a JSON array of documents, taking the id of each from its `id_field`,
+ * or from its position in the array if `id_field` is NULL. Returns 0 on
+ * success; documents before a failing one stay added.
+ */
+int searus_engine_put_many(SearusEngine *engine, const char *documents, const char *id_field,
+                           char **error);
+
+/* Removes the document with the given id. Returns 0 on success. */
+int searus_engine_remove(SearusEngine *engine, const char *id, char **error);
+
+/* Returns the number of stored documents. */
+size_t searus_engine_len(const SearusEngine *engine);
+
+/*
+ * Searches the stored documents with a JSON query. Returns the JSON search
+ * response, to be freed with `searus_string_free`, or NULL on error.
+ */
+char *searus_engine_search(const SearusEngine *engine, const char *query, char **error);
+
+/* Frees a string returned by Searus. Does nothing if `string` is NULL. */
+void searus_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SEARUS_H */
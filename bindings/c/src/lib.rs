@@ -0,0 +1,288 @@
+//! A C ABI for Searus, declared in `include/searus.h`.
+//!
+//! Engines are opaque pointers built from a JSON [`EngineConfig`]. Documents,
+//! queries, and responses cross the boundary as JSON strings, so the ABI
+//! stays small and any language with a C FFI can use it:
+//!
+//! ```c
+//! char *error = NULL;
+//! SearusEngine *engine = searus_engine_new(
+//!     "{\"semantic\": {\"fields\": {\"title\": {\"matcher\": \"bm25\"}}}}", &error);
+//! searus_engine_put(engine, "1", "{\"title\": \"Rust ownership\"}", &error);
+//!
+//! char *response = searus_engine_search(engine, "{\"text\": \"rust\"}", &error);
+//! puts(response);
+//! searus_string_free(response);
+//! searus_engine_free(engine);
+//! ```
+//!
+//! Functions that can fail take a `char **error` out-parameter. On failure
+//! they return `NULL` or `-1` and, if `error` is not `NULL`, store a message
+//! in it that the caller frees with [`searus_string_free`]. Panics are
+//! caught rather than unwound into C, and reported the same way. Strings are
+//! UTF-8 and NUL-terminated.
+
+use searus::config::EngineConfig;
+use searus::index::IndexAdapter;
+use searus::indexed::IndexedSearusEngine;
+use searus::types::Query;
+use serde_json::Value;
+use std::any::Any;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// An engine over JSON documents, stored in memory by id. Opaque to C.
+pub struct SearusEngine {
+  /// The engine and the documents it searches.
+  inner: IndexedSearusEngine<Value>,
+}
+
+/// Creates an engine from a JSON engine config. Returns `NULL` on error.
+///
+/// # Safety
+///
+/// `config` must be a valid NUL-terminated string, and `error` either
+/// `NULL` or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn searus_engine_new(
+  config: *const c_char,
+  error: *mut *mut c_char,
+) -> *mut SearusEngine {
+  let result = catch(|| {
+    let config = EngineConfig::from_json(read_str(config)?)?;
+    Ok(SearusEngine {
+      inner: IndexedSearusEngine::new(config.build()),
+    })
+  });
+
+  match result {
+    Ok(engine) => Box::into_raw(Box::new(engine)),
+    Err(e) => {
+      set_error(error, e);
+      ptr::null_mut()
+    }
+  }
+}
+
+/// Frees an engine. Does nothing if `engine` is `NULL`.
+///
+/// # Safety
+///
+/// `engine` must be `NULL` or a pointer returned by [`searus_engine_new`]
+/// that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn searus_engine_free(engine: *mut SearusEngine) {
+  if !engine.is_null() {
+    let _ = catch(|| {
+      drop(Box::from_raw(engine));
+      Ok(())
+    });
+  }
+}
+
+/// Adds or replaces the JSON document with the given id. Returns 0 on
+/// success and -1 on error.
+///
+/// # Safety
+///
+/// `engine` must be a live engine, `id` and `document` valid NUL-terminated
+/// strings, and `error` either `NULL` or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn searus_engine_put(
+  engine: *mut SearusEngine,
+  id: *const c_char,
+  document: *const c_char,
+  error: *mut *mut c_char,
+) -> c_int {
+  let result = catch(|| {
+    let engine = engine_mut(engine)?;
+    let id = read_str(id)?;
+    let document: Value = parse_json(document)?;
+    engine.inner.put(id, document)
+  });
+  status(result, error)
+}
+
+/// Adds a JSON array of documents, taking the id of each from its `id_field`,
+/// or from its position in the array if `id_field` is `NULL`. Returns 0 on
+/// success and -1 on error; documents before a failing one stay added.
+///
+/// # Safety
+///
+/// `engine` must be a live engine, `documents` a valid NUL-terminated
+/// string, `id_field` `NULL` or a valid NUL-terminated string, and `error`
+/// either `NULL` or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn searus_engine_put_many(
+  engine: *mut SearusEngine,
+  documents: *const c_char,
+  id_field: *const c_char,
+  error: *mut *mut c_char,
+) -> c_int {
+  let result = catch(|| {
+    let engine = engine_mut(engine)?;
+    let documents: Vec<Value> = parse_json(documents)?;
+    let id_field = if id_field.is_null() {
+      None
+    } else {
+      Some(read_str(id_field)?)
+    };
+
+    for (position, document) in documents.into_iter().enumerate() {
+      let id = match id_field {
+        None => position.to_string(),
+        Some(field) => match document.get(field) {
+          Some(Value::String(id)) => id.clone(),
+          Some(Value::Number(id)) => id.to_string(),
+          _ => return Err(format!("Document {} has no `{}` id", position, field)),
+        },
+      };
+      engine.inner.put(id, document)?;
+    }
+    Ok(())
+  });
+  status(result, error)
+}
+
+/// Removes the document with the given id. Returns 0 on success and -1 on
+/// error.
+///
+/// # Safety
+///
+/// `engine` must be a live engine, `id` a valid NUL-terminated string, and
+/// `error` either `NULL` or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn searus_engine_remove(
+  engine: *mut SearusEngine,
+  id: *const c_char,
+  error: *mut *mut c_char,
+) -> c_int {
+  let result = catch(|| engine_mut(engine)?.inner.remove(read_str(id)?));
+  status(result, error)
+}
+
+/// Returns the number of stored documents, or 0 if `engine` is `NULL`.
+///
+/// # Safety
+///
+/// `engine` must be `NULL` or a live engine.
+#[no_mangle]
+pub unsafe extern "C" fn searus_engine_len(engine: *const SearusEngine) -> usize {
+  catch(|| {
+    Ok(
+      engine
+        .as_ref()
+        .map_or(0, |engine| engine.inner.index().all().len()),
+    )
+  })
+  .unwrap_or(0)
+}
+
+/// Searches the stored documents with a JSON query, returning the JSON
+/// search response, or `NULL` on error. The response is freed with
+/// [`searus_string_free`].
+///
+/// # Safety
+///
+/// `engine` must be a live engine, `query` a valid NUL-terminated string,
+/// and `error` either `NULL` or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn searus_engine_search(
+  engine: *const SearusEngine,
+  query: *const c_char,
+  error: *mut *mut c_char,
+) -> *mut c_char {
+  let result = catch(|| {
+    let engine = engine
+      .as_ref()
+      .ok_or_else(|| "The engine is NULL".to_string())?;
+    let query: Query = parse_json(query)?;
+    let response = engine.inner.search(&query);
+    let json = serde_json::to_string(&response).map_err(|e| e.to_string())?;
+    CString::new(json).map_err(|e| e.to_string())
+  });
+
+  match result {
+    Ok(json) => json.into_raw(),
+    Err(e) => {
+      set_error(error, e);
+      ptr::null_mut()
+    }
+  }
+}
+
+/// Frees a string returned by Searus. Does nothing if `string` is `NULL`.
+///
+/// # Safety
+///
+/// `string` must be `NULL` or a string returned by Searus that was not freed
+/// yet.
+#[no_mangle]
+pub unsafe extern "C" fn searus_string_free(string: *mut c_char) {
+  if !string.is_null() {
+    let _ = catch(|| {
+      drop(CString::from_raw(string));
+      Ok(())
+    });
+  }
+}
+
+/// Runs the body of a function, turning a panic into an error so it doesn't
+/// unwind into C.
+fn catch<R>(body: impl FnOnce() -> Result<R, String>) -> Result<R, String> {
+  panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| Err(panic_message(payload)))
+}
+
+/// Describes a panic from its payload.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+  let message = match payload.downcast_ref::<&str>() {
+    Some(message) => message,
+    None => payload
+      .downcast_ref::<String>()
+      .map_or("unknown error", String::as_str),
+  };
+  format!("Searus panicked: {}", message)
+}
+
+/// Borrows a C string as UTF-8.
+unsafe fn read_str<'a>(string: *const c_char) -> Result<&'a str, String> {
+  if string.is_null() {
+    return Err("Unexpected NULL string".to_string());
+  }
+  CStr::from_ptr(string)
+    .to_str()
+    .map_err(|e| format!("Invalid UTF-8: {}", e))
+}
+
+/// Parses a C string as JSON.
+unsafe fn parse_json<T: serde::de::DeserializeOwned>(string: *const c_char) -> Result<T, String> {
+  serde_json::from_str(read_str(string)?).map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+/// Borrows an engine mutably.
+unsafe fn engine_mut<'a>(engine: *mut SearusEngine) -> Result<&'a mut SearusEngine, String> {
+  engine
+    .as_mut()
+    .ok_or_else(|| "The engine is NULL".to_string())
+}
+
+/// Converts a result to a status code, storing the error message if any.
+unsafe fn status(result: Result<(), String>, error: *mut *mut c_char) -> c_int {
+  match result {
+    Ok(()) => 0,
+    Err(e) => {
+      set_error(error, e);
+      -1
+    }
+  }
+}
+
+/// Stores an error message in the `error` out-parameter, if it is not `NULL`.
+unsafe fn set_error(error: *mut *mut c_char, message: String) {
+  if error.is_null() {
+    return;
+  }
+  let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+  *error = message.into_raw();
+}
@@ -0,0 +1,82 @@
+use searus_c::*;
+use serde_json::Value;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+/// Takes ownership of a string returned by Searus.
+unsafe fn take(string: *mut c_char) -> String {
+  assert!(!string.is_null());
+  let owned = CStr::from_ptr(string).to_str().unwrap().to_string();
+  searus_string_free(string);
+  owned
+}
+
+#[test]
+fn test_index_and_search() {
+  let config =
+    CString::new(r#"{ "semantic": { "fields": { "title": { "matcher": "bm25" } } } }"#).unwrap();
+  let documents = CString::new(
+    r#"[{ "id": "a", "title": "Rust ownership" }, { "id": "b", "title": "Python typing" }]"#,
+  )
+  .unwrap();
+  let id_field = CString::new("id").unwrap();
+  let query = CString::new(r#"{ "text": "rust" }"#).unwrap();
+  let a = CString::new("a").unwrap();
+
+  unsafe {
+    let mut error = ptr::null_mut();
+    let engine = searus_engine_new(config.as_ptr(), &mut error);
+    assert!(!engine.is_null());
+
+    assert_eq!(
+      searus_engine_put_many(engine, documents.as_ptr(), id_field.as_ptr(), &mut error),
+      0
+    );
+    assert_eq!(searus_engine_len(engine), 2);
+
+    let response: Value = serde_json::from_str(&take(searus_engine_search(
+      engine,
+      query.as_ptr(),
+      &mut error,
+    )))
+    .unwrap();
+    assert_eq!(response["total_matches"], 1);
+    assert_eq!(response["results"][0]["item"]["id"], "a");
+
+    assert_eq!(searus_engine_remove(engine, a.as_ptr(), &mut error), 0);
+    let response: Value = serde_json::from_str(&take(searus_engine_search(
+      engine,
+      query.as_ptr(),
+      &mut error,
+    )))
+    .unwrap();
+    assert_eq!(response["total_matches"], 0);
+
+    searus_engine_free(engine);
+  }
+}
+
+#[test]
+fn test_errors() {
+  let config = CString::new(r#"{ "semantics": {} }"#).unwrap();
+  let valid = CString::new("{}").unwrap();
+  let invalid = CString::new("{ not json").unwrap();
+
+  unsafe {
+    let mut error = ptr::null_mut();
+    assert!(searus_engine_new(config.as_ptr(), &mut error).is_null());
+    assert!(take(error).contains("unknown field"));
+
+    let engine = searus_engine_new(valid.as_ptr(), ptr::null_mut());
+    let mut error = ptr::null_mut();
+    assert!(searus_engine_search(engine, invalid.as_ptr(), &mut error).is_null());
+    assert!(take(error).starts_with("Invalid JSON"));
+
+    // Errors are optional to collect.
+    assert_eq!(
+      searus_engine_put(engine, valid.as_ptr(), invalid.as_ptr(), ptr::null_mut()),
+      -1
+    );
+    searus_engine_free(engine);
+  }
+}
@@ -0,0 +1,220 @@
+//! Node.js bindings for Searus.
+//!
+//! The `searus` npm package exposes an `Engine`, built from the same JSON
+//! engine config as the Rust [`EngineConfig`], storing JavaScript objects by
+//! id, and a chainable `Query` builder. `napi build` generates the
+//! TypeScript definitions from the annotated items below.
+//!
+//! ```js
+//! const { Engine, Query } = require("searus");
+//!
+//! const engine = new Engine(
+//!   { semantic: { fields: { title: { matcher: "bm25" } } }, tags: { field: "tags" } },
+//!   ["title"],
+//! );
+//! engine.put("1", { title: "Rust ownership", tags: ["rust"], price: 30 });
+//!
+//! const query = new Query()
+//!   .text("rust")
+//!   .filter({ Compare: { field: "price", op: "Lt", value: 50 } })
+//!   .limit(5);
+//! const { results, totalMatches } = engine.search(query);
+//! ```
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use searus::config::EngineConfig;
+use searus::filter::FilterExpr;
+use searus::index::IndexAdapter;
+use searus::indexed::IndexedSearusEngine;
+use searus::types::Query as SearusQuery;
+use serde_json::Value;
+
+/// A search engine over JavaScript objects, stored in memory by id.
+#[napi]
+pub struct Engine {
+  /// The engine and the items it searches.
+  inner: IndexedSearusEngine<Value>,
+}
+
+#[napi]
+impl Engine {
+  /// Creates an engine from an engine config object, offering the values of
+  /// `completionFields` as completions.
+  #[napi(constructor)]
+  pub fn new(config: Value, completion_fields: Option<Vec<String>>) -> Result<Self> {
+    let config: EngineConfig = from_json(config)?;
+    let mut inner = IndexedSearusEngine::new(config.build());
+    if let Some(fields) = completion_fields {
+      inner = inner.with_completion_fields(fields);
+    }
+    Ok(Self { inner })
+  }
+
+  /// Adds or replaces the item with the given id.
+  #[napi]
+  pub fn put(&mut self, id: String, item: Value) -> Result<()> {
+    self.inner.put(id, item).map_err(Error::from_reason)
+  }
+
+  /// Returns the item with the given id.
+  #[napi]
+  pub fn get(&self, id: String) -> Option<Value> {
+    self.inner.get(&id).cloned()
+  }
+
+  /// Removes the item with the given id.
+  #[napi]
+  pub fn remove(&mut self, id: String) -> Result<()> {
+    self.inner.remove(&id).map_err(Error::from_reason)
+  }
+
+  /// The number of stored items.
+  #[napi(getter)]
+  pub fn size(&self) -> u32 {
+    self.inner.index().all().len() as u32
+  }
+
+  /// Searches the stored items.
+  #[napi]
+  pub fn search(&self, query: &Query) -> SearchResponse {
+    let response = self.inner.search(&query.inner);
+    SearchResponse {
+      results: response
+        .results
+        .into_iter()
+        .map(|m| SearchResult {
+          item: m.item,
+          score: m.score as f64,
+        })
+        .collect(),
+      total_matches: response.total_matches as u32,
+      suggestion: response.suggestion,
+    }
+  }
+
+  /// Returns up to `limit` completions of a search-box prefix, 10 by
+  /// default.
+  #[napi]
+  pub fn suggest(&self, prefix: String, limit: Option<u32>) -> Vec<Completion> {
+    self
+      .inner
+      .suggest(&prefix, limit.unwrap_or(10) as usize)
+      .into_iter()
+      .map(|c| Completion {
+        text: c.text,
+        weight: c.weight as f64,
+      })
+      .collect()
+  }
+}
+
+/// A chainable search query.
+#[napi]
+pub struct Query {
+  /// The query being built.
+  inner: SearusQuery,
+}
+
+#[napi]
+impl Query {
+  /// Creates an empty query.
+  #[napi(constructor)]
+  #[allow(clippy::new_without_default)]
+  pub fn new() -> Self {
+    Self {
+      inner: SearusQuery::default(),
+    }
+  }
+
+  /// Creates a query from its JSON representation.
+  #[napi(factory)]
+  pub fn from_json(query: Value) -> Result<Self> {
+    Ok(Self {
+      inner: from_json(query)?,
+    })
+  }
+
+  /// Sets the text to search for.
+  #[napi]
+  pub fn text(&mut self, this: This, text: String) -> This {
+    self.inner.text = Some(text);
+    this
+  }
+
+  /// Sets the tags to search for.
+  #[napi]
+  pub fn tags(&mut self, this: This, tags: Vec<String>) -> This {
+    self.inner.tags = Some(tags);
+    this
+  }
+
+  /// Sets the tags that exclude an item.
+  #[napi]
+  pub fn exclude_tags(&mut self, this: This, tags: Vec<String>) -> This {
+    self.inner.exclude_tags = Some(tags);
+    this
+  }
+
+  /// Sets the filter expression items must match.
+  #[napi]
+  pub fn filter(&mut self, this: This, filter: Value) -> Result<This> {
+    let filter: FilterExpr = from_json(filter)?;
+    self.inner.filters = Some(filter);
+    Ok(this)
+  }
+
+  /// Sets the number of results to skip.
+  #[napi]
+  pub fn skip(&mut self, this: This, skip: u32) -> This {
+    self.inner.options.skip = skip as usize;
+    this
+  }
+
+  /// Sets the maximum number of results.
+  #[napi]
+  pub fn limit(&mut self, this: This, limit: u32) -> This {
+    self.inner.options.limit = limit as usize;
+    this
+  }
+
+  /// Returns the JSON representation of the query.
+  #[napi(js_name = "toJSON")]
+  pub fn to_json(&self) -> Result<Value> {
+    serde_json::to_value(&self.inner).map_err(|e| Error::from_reason(e.to_string()))
+  }
+}
+
+/// The results of a search.
+#[napi(object)]
+pub struct SearchResponse {
+  /// The ranked and paginated matches.
+  pub results: Vec<SearchResult>,
+  /// The number of matches before pagination was applied.
+  pub total_matches: u32,
+  /// A corrected query text, when a spell corrector suggested one.
+  pub suggestion: Option<String>,
+}
+
+/// A matched item and its score.
+#[napi(object)]
+pub struct SearchResult {
+  /// The matched item.
+  pub item: Value,
+  /// The merged score of the item.
+  pub score: f64,
+}
+
+/// A completion of a search-box prefix.
+#[napi(object)]
+pub struct Completion {
+  /// The completed text.
+  pub text: String,
+  /// The weight of the completion.
+  pub weight: f64,
+}
+
+/// Converts a JSON value from JavaScript to a Rust value.
+fn from_json<T: serde::de::DeserializeOwned>(value: Value) -> Result<T> {
+  serde_json::from_value(value).map_err(|e| Error::new(Status::InvalidArg, e.to_string()))
+}
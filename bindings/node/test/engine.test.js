@@ -0,0 +1,43 @@
+const test = require("node:test");
+const assert = require("node:assert");
+const { Engine, Query } = require("..");
+
+function makeEngine() {
+  const engine = new Engine(
+    { semantic: { fields: { title: { matcher: "bm25" } } }, tags: { field: "tags" } },
+    ["title"],
+  );
+  engine.put("1", { title: "Rust ownership", tags: ["rust"], price: 30 });
+  engine.put("2", { title: "Python typing", tags: ["python"], price: 60 });
+  return engine;
+}
+
+test("searches with a query builder", () => {
+  const engine = makeEngine();
+  const { results, totalMatches } = engine.search(new Query().text("rust").limit(5));
+  assert.strictEqual(totalMatches, 1);
+  assert.strictEqual(results[0].item.title, "Rust ownership");
+
+  const cheap = engine.search(
+    new Query().tags(["rust", "python"]).filter({ Compare: { field: "price", op: "Lt", value: 50 } }),
+  );
+  assert.deepStrictEqual(cheap.results.map((r) => r.item.title), ["Rust ownership"]);
+});
+
+test("builds queries from JSON", () => {
+  const query = Query.fromJson({ text: "python", options: { limit: 1 } });
+  assert.strictEqual(query.toJSON().text, "python");
+  assert.strictEqual(makeEngine().search(query).results[0].item.title, "Python typing");
+});
+
+test("updates the index", () => {
+  const engine = makeEngine();
+  assert.strictEqual(engine.size, 2);
+  engine.remove("2");
+  assert.strictEqual(engine.get("2"), null);
+  assert.deepStrictEqual(engine.suggest("py"), []);
+});
+
+test("rejects invalid configs", () => {
+  assert.throws(() => new Engine({ semantics: {} }), /unknown field/);
+});
@@ -0,0 +1,206 @@
+//! Python bindings for Searus.
+//!
+//! The `searus` Python module exposes an `Engine`, built from the same JSON
+//! engine config as the Rust [`EngineConfig`], storing Python dicts by id and
+//! searching them with query dicts, and a `Filter` for checking filter
+//! expressions against items:
+//!
+//! ```python
+//! import searus
+//!
+//! engine = searus.Engine(
+//!     {"semantic": {"fields": {"title": {"matcher": "bm25"}}}, "tags": {"field": "tags"}},
+//!     completion_fields=["title"],
+//! )
+//! engine.put("1", {"title": "Rust ownership", "tags": ["rust"], "price": 30})
+//!
+//! response = engine.search({"text": "rust", "options": {"limit": 5}})
+//! cheap = searus.Filter({"Compare": {"field": "price", "op": "Lt", "value": 50}})
+//! assert cheap.matches(response["results"][0]["item"])
+//! ```
+//!
+//! Values cross the boundary as JSON, so items, queries, and responses are
+//! plain dicts and lists with the same shape as their serde representation.
+//!
+//! Items are stored in an in-memory index, which `save` and `load` persist
+//! as a JSON file. The other index adapters, such as Qdrant and Postgres,
+//! are not exposed.
+
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+use searus::config::EngineConfig;
+use searus::filter::FilterExpr;
+use searus::index::IndexAdapter;
+use searus::indexed::IndexedSearusEngine;
+use searus::types::Query;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// A search engine over Python dicts, stored in memory by id.
+#[pyclass(module = "searus")]
+struct Engine {
+  /// The engine and the items it searches.
+  inner: IndexedSearusEngine<Value>,
+  /// The ids of the stored items.
+  ids: BTreeSet<String>,
+}
+
+#[pymethods]
+impl Engine {
+  /// Creates an engine from an engine config, given as a dict or a JSON
+  /// string, offering the values of `completion_fields` as completions.
+  #[new]
+  #[pyo3(signature = (config, completion_fields = None))]
+  fn new(config: &Bound<'_, PyAny>, completion_fields: Option<Vec<String>>) -> PyResult<Self> {
+    let config: EngineConfig = from_py(config)?;
+    let mut inner = IndexedSearusEngine::new(config.build());
+    if let Some(fields) = completion_fields {
+      inner = inner.with_completion_fields(fields);
+    }
+    Ok(Self {
+      inner,
+      ids: BTreeSet::new(),
+    })
+  }
+
+  /// Adds or replaces the item with the given id.
+  fn put(&mut self, id: String, item: &Bound<'_, PyAny>) -> PyResult<()> {
+    let item = from_py(item)?;
+    self.put_value(id, item)
+  }
+
+  /// Returns the item with the given id, or `None`.
+  fn get<'py>(&self, py: Python<'py>, id: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+    self.inner.get(id).map(|item| to_py(py, item)).transpose()
+  }
+
+  /// Removes the item with the given id.
+  fn remove(&mut self, id: &str) -> PyResult<()> {
+    self.inner.remove(id).map_err(PyValueError::new_err)?;
+    self.ids.remove(id);
+    Ok(())
+  }
+
+  /// Writes the stored items to a JSON file, as an object of items by id.
+  fn save(&self, path: &str) -> PyResult<()> {
+    let items: Map<String, Value> = self
+      .ids
+      .iter()
+      .filter_map(|id| Some((id.clone(), self.inner.get(id)?.clone())))
+      .collect();
+    let json = serde_json::to_string(&items).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    std::fs::write(path, json).map_err(|e| PyIOError::new_err(e.to_string()))
+  }
+
+  /// Adds the items of a JSON file written by `save`, replacing stored items
+  /// with the same ids.
+  fn load(&mut self, path: &str) -> PyResult<()> {
+    let json = std::fs::read_to_string(path).map_err(|e| PyIOError::new_err(e.to_string()))?;
+    let items: Map<String, Value> =
+      serde_json::from_str(&json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    for (id, item) in items {
+      self.put_value(id, item)?;
+    }
+    Ok(())
+  }
+
+  /// Searches the stored items with a query dict, returning the search
+  /// response as a dict.
+  fn search<'py>(&self, py: Python<'py>, query: &Bound<'_, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let query: Query = from_py(query)?;
+    to_py(py, &self.inner.search(&query))
+  }
+
+  /// Searches a list of items instead of the stored ones, to try a
+  /// configuration against a corpus without indexing it.
+  fn search_items<'py>(
+    &self,
+    py: Python<'py>,
+    items: &Bound<'_, PyAny>,
+    query: &Bound<'_, PyAny>,
+  ) -> PyResult<Bound<'py, PyAny>> {
+    let items: Vec<Value> = from_py(items)?;
+    let query: Query = from_py(query)?;
+    to_py(py, &self.inner.engine().search_response(&items, &query))
+  }
+
+  /// Returns up to `limit` completions of a search-box prefix.
+  #[pyo3(signature = (prefix, limit = 10))]
+  fn suggest<'py>(
+    &self,
+    py: Python<'py>,
+    prefix: &str,
+    limit: usize,
+  ) -> PyResult<Bound<'py, PyAny>> {
+    to_py(py, &self.inner.suggest(prefix, limit))
+  }
+
+  /// Returns the number of stored items.
+  fn __len__(&self) -> usize {
+    self.inner.index().all().len()
+  }
+}
+
+impl Engine {
+  /// Adds or replaces the item with the given id.
+  fn put_value(&mut self, id: String, item: Value) -> PyResult<()> {
+    self
+      .inner
+      .put(id.clone(), item)
+      .map_err(PyValueError::new_err)?;
+    self.ids.insert(id);
+    Ok(())
+  }
+}
+
+/// A filter expression, for checking which items a query filter keeps.
+#[pyclass(module = "searus")]
+struct Filter {
+  /// The parsed expression.
+  expr: FilterExpr,
+}
+
+#[pymethods]
+impl Filter {
+  /// Parses a filter expression, given as a dict or a JSON string.
+  #[new]
+  fn new(filter: &Bound<'_, PyAny>) -> PyResult<Self> {
+    Ok(Self {
+      expr: from_py(filter)?,
+    })
+  }
+
+  /// Returns `True` if the item matches the filter.
+  fn matches(&self, item: &Bound<'_, PyAny>) -> PyResult<bool> {
+    let item: Value = from_py(item)?;
+    Ok(self.expr.evaluate(&item))
+  }
+}
+
+/// Converts a Python object, or a JSON string, to a Rust value through JSON.
+fn from_py<T: DeserializeOwned>(object: &Bound<'_, PyAny>) -> PyResult<T> {
+  let json: String = if object.is_instance_of::<PyString>() {
+    object.extract()?
+  } else {
+    let json = object.py().import("json")?;
+    json.call_method1("dumps", (object,))?.extract()?
+  };
+  serde_json::from_str(&json).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Converts a Rust value to plain Python dicts and lists through JSON.
+fn to_py<'py, T: Serialize + ?Sized>(py: Python<'py>, value: &T) -> PyResult<Bound<'py, PyAny>> {
+  let json = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+  py.import("json")?.call_method1("loads", (json,))
+}
+
+/// The `searus` Python module.
+#[pymodule(name = "searus")]
+fn searus_py(module: &Bound<'_, PyModule>) -> PyResult<()> {
+  module.add_class::<Engine>()?;
+  module.add_class::<Filter>()?;
+  Ok(())
+}
@@ -0,0 +1,67 @@
+import pytest
+
+import searus
+
+CONFIG = {
+    "semantic": {"fields": {"title": {"matcher": "bm25"}}},
+    "tags": {"field": "tags"},
+}
+
+
+def make_engine():
+    engine = searus.Engine(CONFIG, completion_fields=["title"])
+    engine.put("1", {"title": "Rust ownership", "tags": ["rust"], "price": 30})
+    engine.put("2", {"title": "Python typing", "tags": ["python"], "price": 60})
+    return engine
+
+
+def test_search():
+    engine = make_engine()
+    response = engine.search({"text": "rust", "options": {"limit": 5}})
+    assert response["total_matches"] == 1
+    assert response["results"][0]["item"]["title"] == "Rust ownership"
+
+    by_tag = engine.search({"tags": ["python"]})
+    assert by_tag["results"][0]["item"]["title"] == "Python typing"
+
+
+def test_index():
+    engine = make_engine()
+    assert len(engine) == 2
+    assert engine.get("2")["price"] == 60
+
+    engine.remove("2")
+    assert engine.get("2") is None
+    assert engine.suggest("py") == []
+
+
+def test_save_and_load(tmp_path):
+    path = str(tmp_path / "items.json")
+    make_engine().save(path)
+
+    engine = searus.Engine(CONFIG, completion_fields=["title"])
+    engine.load(path)
+    assert len(engine) == 2
+    assert engine.get("2")["price"] == 60
+    assert engine.search({"text": "rust"})["total_matches"] == 1
+    assert engine.suggest("py")[0]["text"] == "Python typing"
+
+    with pytest.raises(OSError):
+        engine.load(str(tmp_path / "missing.json"))
+
+
+def test_search_items():
+    engine = searus.Engine('{"semantic": {"fields": {"title": {}}}}')
+    response = engine.search_items([{"title": "a rust book"}, {"title": "a cake"}], {"text": "rust"})
+    assert response["total_matches"] == 1
+
+
+def test_filter():
+    cheap = searus.Filter({"Compare": {"field": "price", "op": "Lt", "value": 50}})
+    assert cheap.matches({"price": 30})
+    assert not cheap.matches({"price": 60})
+
+
+def test_invalid_config():
+    with pytest.raises(ValueError, match="unknown field"):
+        searus.Engine({"semantics": {}})
@@ -47,6 +47,7 @@ fn main() {
       field: "views".to_string(),
       op: CompareOp::Gt,
       value: FilterValue::Number(1000.0),
+      options: StringOptions::default(),
     })
     .build();
 
@@ -78,10 +79,11 @@ fn main() {
           matched_term,
           original_term,
           similarity,
+          field,
         } => {
           println!(
-            "   ✓ Fuzzy: {} → {} (similarity: {:.2})",
-            original_term, matched_term, similarity
+            "   ✓ Fuzzy: {} → {} in {} (similarity: {:.2})",
+            original_term, matched_term, field, similarity
           );
         }
         _ => {}
@@ -89,11 +89,8 @@ fn main() {
 
       if !result.details.is_empty() {
         for detail in &result.details {
-          match detail {
-            SearchDetail::Semantic { matched_terms, .. } => {
-              println!("     Matched terms: {}", matched_terms.join(", "));
-            }
-            _ => {}
+          if let SearchDetail::Semantic { matched_terms, .. } = detail {
+            println!("     Matched terms: {}", matched_terms.join(", "));
           }
         }
       }
@@ -0,0 +1,328 @@
+//! Aggregations computed over the matched result set.
+//!
+//! Aggregations are requested through [`SearchOptions`] and returned in the
+//! [`SearchResponse`](crate::types::SearchResponse) of
+//! [`SearusEngine::search_response`](crate::engine::SearusEngine::search_response).
+//! They are computed over every match, before pagination is applied, in a
+//! single pass over the results.
+//!
+//! - **Facets**: Counts of the distinct values of a field, e.g., for filter UIs
+//!   showing "Electronics (42), Sports (7)".
+//! - **Histograms**: Counts of a numeric field in fixed-width buckets, e.g.,
+//!   prices in steps of 50 for a price slider.
+//! - **Ranges**: Counts of a numeric field in user-defined ranges, e.g.,
+//!   "under 50", "50 to 100", and "over 100".
+//! - **Stats**: The count, minimum, maximum, sum, and mean of a numeric field.
+
+use crate::document::{get_field_value, to_document};
+use crate::types::{SearchOptions, Searchable, SearusMatch};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The number of matches that have a given value in a faceted field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FacetCount {
+  /// The field value, converted to a string.
+  pub value: String,
+  /// The number of matches with this value.
+  pub count: usize,
+}
+
+/// A request for a histogram of a numeric field, with fixed-width buckets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Histogram {
+  /// The path of the numeric field.
+  pub field: String,
+  /// The width of each bucket. Bucket boundaries are multiples of it.
+  pub interval: f64,
+}
+
+/// A request for the counts of a numeric field within a set of ranges.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RangeAggregation {
+  /// The path of the numeric field.
+  pub field: String,
+  /// The ranges to count values in. Ranges may overlap.
+  pub ranges: Vec<NumericRange>,
+}
+
+/// A range of numbers, including `from` and excluding `to`.
+///
+/// A missing bound leaves that side of the range open.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct NumericRange {
+  /// The inclusive lower bound.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub from: Option<f64>,
+  /// The exclusive upper bound.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub to: Option<f64>,
+}
+
+impl NumericRange {
+  /// Creates a range from `from` (inclusive) to `to` (exclusive).
+  pub fn between(from: f64, to: f64) -> Self {
+    Self {
+      from: Some(from),
+      to: Some(to),
+    }
+  }
+
+  /// Creates a range of every value below `to`.
+  pub fn below(to: f64) -> Self {
+    Self {
+      from: None,
+      to: Some(to),
+    }
+  }
+
+  /// Creates a range of every value from `from` upwards.
+  pub fn above(from: f64) -> Self {
+    Self {
+      from: Some(from),
+      to: None,
+    }
+  }
+
+  /// Returns `true` if the value lies within the range.
+  pub fn contains(&self, value: f64) -> bool {
+    self.from.is_none_or(|from| value >= from) && self.to.is_none_or(|to| value < to)
+  }
+}
+
+/// The number of matches with a numeric value in a range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bucket {
+  /// The inclusive lower bound of the bucket.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub from: Option<f64>,
+  /// The exclusive upper bound of the bucket.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub to: Option<f64>,
+  /// The number of values within the bucket.
+  pub count: usize,
+}
+
+/// Summary statistics of a numeric field over the matches.
+///
+/// Array fields contribute each of their numbers. `min`, `max`, and `avg` are
+/// `None` when no match has a numeric value in the field.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldStats {
+  /// The number of values.
+  pub count: usize,
+  /// The smallest value.
+  pub min: Option<f64>,
+  /// The largest value.
+  pub max: Option<f64>,
+  /// The sum of the values.
+  pub sum: f64,
+  /// The mean of the values.
+  pub avg: Option<f64>,
+}
+
+impl FieldStats {
+  /// Adds a value to the statistics, except for the mean.
+  fn add(&mut self, value: f64) {
+    self.count += 1;
+    self.sum += value;
+    self.min = Some(self.min.map_or(value, |min| min.min(value)));
+    self.max = Some(self.max.map_or(value, |max| max.max(value)));
+  }
+
+  /// Computes the mean once all values have been added.
+  fn finish(mut self) -> Self {
+    if self.count > 0 {
+      self.avg = Some(self.sum / self.count as f64);
+    }
+    self
+  }
+}
+
+/// The aggregations computed for a search.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Aggregations {
+  /// The value counts of each faceted field.
+  pub facets: HashMap<String, Vec<FacetCount>>,
+  /// The histogram buckets of each field.
+  pub histograms: HashMap<String, Vec<Bucket>>,
+  /// The range buckets of each field.
+  pub ranges: HashMap<String, Vec<Bucket>>,
+  /// The statistics of each field.
+  pub stats: HashMap<String, FieldStats>,
+}
+
+impl Aggregations {
+  /// Returns `true` if the options request any aggregation.
+  pub fn requested(options: &SearchOptions) -> bool {
+    !options.facets.is_empty()
+      || !options.histograms.is_empty()
+      || !options.ranges.is_empty()
+      || !options.stats.is_empty()
+  }
+
+  /// Computes the aggregations requested by the options over the matches.
+  pub fn compute<T>(options: &SearchOptions, matches: &[SearusMatch<T>]) -> Self
+  where
+    T: Searchable + Serialize,
+  {
+    if !Self::requested(options) {
+      return Self::default();
+    }
+
+    let mut facets: HashMap<&str, HashMap<String, usize>> = options
+      .facets
+      .iter()
+      .map(|field| (field.as_str(), HashMap::new()))
+      .collect();
+
+    // Bucket index (value / interval, rounded down) to count
+    let mut histograms: Vec<HashMap<i64, usize>> = vec![HashMap::new(); options.histograms.len()];
+    let mut ranges: Vec<Vec<usize>> = options
+      .ranges
+      .iter()
+      .map(|aggregation| vec![0; aggregation.ranges.len()])
+      .collect();
+    let mut stats: HashMap<&str, FieldStats> = options
+      .stats
+      .iter()
+      .map(|field| (field.as_str(), FieldStats::default()))
+      .collect();
+
+    for m in matches {
+      let Ok(item) = to_document(&m.item) else {
+        continue;
+      };
+
+      for (field, counts) in facets.iter_mut() {
+        if let Some(value) = get_field_value(&item, field) {
+          count_facet_value(value, counts);
+        }
+      }
+
+      for (histogram, buckets) in options.histograms.iter().zip(&mut histograms) {
+        if histogram.interval <= 0.0 {
+          continue;
+        }
+        if let Some(value) = get_field_value(&item, &histogram.field) {
+          for_each_number(value, &mut |n| {
+            *buckets
+              .entry((n / histogram.interval).floor() as i64)
+              .or_insert(0) += 1;
+          });
+        }
+      }
+
+      for (aggregation, counts) in options.ranges.iter().zip(&mut ranges) {
+        if let Some(value) = get_field_value(&item, &aggregation.field) {
+          for_each_number(value, &mut |n| {
+            for (range, count) in aggregation.ranges.iter().zip(counts.iter_mut()) {
+              if range.contains(n) {
+                *count += 1;
+              }
+            }
+          });
+        }
+      }
+
+      for (field, stats) in stats.iter_mut() {
+        if let Some(value) = get_field_value(&item, field) {
+          for_each_number(value, &mut |n| stats.add(n));
+        }
+      }
+    }
+
+    Self {
+      facets: facets
+        .into_iter()
+        .map(|(field, counts)| (field.to_string(), sort_facet_counts(counts)))
+        .collect(),
+      histograms: options
+        .histograms
+        .iter()
+        .zip(histograms)
+        .map(|(histogram, buckets)| {
+          let mut buckets: Vec<(i64, usize)> = buckets.into_iter().collect();
+          buckets.sort_by_key(|(index, _)| *index);
+          let buckets = buckets
+            .into_iter()
+            .map(|(index, count)| Bucket {
+              from: Some(index as f64 * histogram.interval),
+              to: Some((index + 1) as f64 * histogram.interval),
+              count,
+            })
+            .collect();
+          (histogram.field.clone(), buckets)
+        })
+        .collect(),
+      ranges: options
+        .ranges
+        .iter()
+        .zip(ranges)
+        .map(|(aggregation, counts)| {
+          let buckets = aggregation
+            .ranges
+            .iter()
+            .zip(counts)
+            .map(|(range, count)| Bucket {
+              from: range.from,
+              to: range.to,
+              count,
+            })
+            .collect();
+          (aggregation.field.clone(), buckets)
+        })
+        .collect(),
+      stats: stats
+        .into_iter()
+        .map(|(field, stats)| (field.to_string(), stats.finish()))
+        .collect(),
+    }
+  }
+}
+
+/// Calls `f` with a numeric field value, or with each number of an array.
+fn for_each_number(value: &Value, f: &mut impl FnMut(f64)) {
+  match value {
+    Value::Number(n) => {
+      if let Some(n) = n.as_f64() {
+        f(n);
+      }
+    }
+    Value::Array(values) => {
+      for value in values {
+        for_each_number(value, f);
+      }
+    }
+    _ => {}
+  }
+}
+
+/// Counts a field value, counting each element of an array separately.
+fn count_facet_value(value: &Value, counts: &mut HashMap<String, usize>) {
+  let key = match value {
+    Value::String(s) => s.clone(),
+    Value::Number(n) => n.to_string(),
+    Value::Bool(b) => b.to_string(),
+    Value::Array(values) => {
+      for value in values {
+        count_facet_value(value, counts);
+      }
+      return;
+    }
+    Value::Null | Value::Object(_) => return,
+  };
+
+  *counts.entry(key).or_insert(0) += 1;
+}
+
+/// Sorts facet counts by descending count, then by value.
+fn sort_facet_counts(counts: HashMap<String, usize>) -> Vec<FacetCount> {
+  let mut counts: Vec<FacetCount> = counts
+    .into_iter()
+    .map(|(value, count)| FacetCount { value, count })
+    .collect();
+  counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+  counts
+}
@@ -0,0 +1,374 @@
+//! The `searus` command line tool, for indexing and querying local files.
+//!
+//! ```text
+//! searus index <FILE>... --out <INDEX> [--fields a,b] [--tags-field tags]
+//! searus query <INDEX> [--text TEXT] [--tags a,b] [--filter EXPR]... [--limit N] [--json]
+//! ```
+//!
+//! `index` reads JSON (an array or a single object), JSONL, and CSV files,
+//! and writes the documents and the fields to search to an index file.
+//! `query` loads an index file and searches it.
+
+use searus::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
+use searus::ingest::{FieldMapping, Format, Loader};
+use searus::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::env;
+use std::fs::{self, File};
+use std::path::Path;
+use std::process;
+
+const USAGE: &str = "\
+Usage:
+  searus index <FILE>... --out <INDEX> [--fields a,b] [--tags-field tags]
+  searus query <INDEX> [--text TEXT] [--tags a,b] [--filter EXPR]... [--limit N] [--json]
+
+Index options:
+  -o, --out <INDEX>       The index file to write
+  --fields <a,b>          The fields to search (default: every top-level string field)
+  --tags-field <FIELD>    The field holding the tags of a document (default: tags)
+
+Query options:
+  --text <TEXT>           Searches the text fields
+  --tags <a,b>            Searches the tags
+  --filter <EXPR>         Keeps documents matching `field<op>value`, with an op among
+                          = != < <= > >= ~ (contains), or a JSON filter expression.
+                          Repeated filters must all match.
+  --limit <N>             The maximum number of results (default: 10)
+  --json                  Prints the search response as JSON";
+
+/// The contents of an index file.
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexFile {
+  /// The text fields searched.
+  fields: Vec<String>,
+  /// The field holding the tags of the documents.
+  tags_field: String,
+  /// The indexed documents.
+  documents: Vec<Value>,
+}
+
+fn main() {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let result = match args.first().map(String::as_str) {
+    Some("index") => index(&args[1..]),
+    Some("query") => query(&args[1..]),
+    Some("-h" | "--help") => {
+      println!("{}", USAGE);
+      Ok(())
+    }
+    _ => Err(USAGE.to_string()),
+  };
+
+  if let Err(e) = result {
+    eprintln!("{}", e);
+    process::exit(1);
+  }
+}
+
+/// Parsed command line arguments: positional values and `--option value` pairs.
+struct Args {
+  positional: Vec<String>,
+  options: Vec<(String, String)>,
+  flags: Vec<String>,
+}
+
+impl Args {
+  /// Splits the arguments, given the options that are flags without a value.
+  fn parse(args: &[String], flags: &[&str]) -> Result<Self, String> {
+    let mut parsed = Args {
+      positional: Vec::new(),
+      options: Vec::new(),
+      flags: Vec::new(),
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+      if !arg.starts_with('-') {
+        parsed.positional.push(arg.clone());
+      } else if flags.contains(&arg.as_str()) {
+        parsed.flags.push(arg.clone());
+      } else {
+        let value = args
+          .next()
+          .ok_or_else(|| format!("Missing value for {}", arg))?;
+        parsed.options.push((arg.clone(), value.clone()));
+      }
+    }
+    Ok(parsed)
+  }
+
+  /// Returns the last value of an option, under any of its names.
+  fn get(&self, names: &[&str]) -> Option<&str> {
+    self
+      .options
+      .iter()
+      .rev()
+      .find(|(name, _)| names.contains(&name.as_str()))
+      .map(|(_, value)| value.as_str())
+  }
+
+  /// Returns every value of an option.
+  fn all(&self, name: &str) -> Vec<&str> {
+    self
+      .options
+      .iter()
+      .filter(|(n, _)| n == name)
+      .map(|(_, value)| value.as_str())
+      .collect()
+  }
+
+  /// Returns `true` if the flag was given.
+  fn flag(&self, name: &str) -> bool {
+    self.flags.iter().any(|flag| flag == name)
+  }
+
+  /// Fails on options that the command doesn't know.
+  fn check(&self, known: &[&str]) -> Result<(), String> {
+    match self
+      .options
+      .iter()
+      .find(|(name, _)| !known.contains(&name.as_str()))
+    {
+      Some((name, _)) => Err(format!("Unknown option {}\n\n{}", name, USAGE)),
+      None => Ok(()),
+    }
+  }
+}
+
+/// Splits a comma-separated list.
+fn list(value: &str) -> Vec<String> {
+  value
+    .split(',')
+    .map(str::trim)
+    .filter(|s| !s.is_empty())
+    .map(String::from)
+    .collect()
+}
+
+/// Runs `searus index`.
+fn index(args: &[String]) -> Result<(), String> {
+  let args = Args::parse(args, &[])?;
+  args.check(&["-o", "--out", "--fields", "--tags-field"])?;
+  let out = args
+    .get(&["-o", "--out"])
+    .ok_or_else(|| format!("Missing --out\n\n{}", USAGE))?;
+  if args.positional.is_empty() {
+    return Err(format!("Missing input files\n\n{}", USAGE));
+  }
+
+  let tags_field = args.get(&["--tags-field"]).unwrap_or("tags").to_string();
+  let mut documents = Vec::new();
+  for path in &args.positional {
+    documents.extend(read_documents(Path::new(path), &tags_field)?);
+  }
+
+  let fields = match args.get(&["--fields"]) {
+    Some(fields) => list(fields),
+    None => string_fields(&documents, &tags_field),
+  };
+
+  let count = documents.len();
+  let file = IndexFile {
+    fields,
+    tags_field,
+    documents,
+  };
+  let json = serde_json::to_string(&file).map_err(|e| e.to_string())?;
+  fs::write(out, json).map_err(|e| format!("Cannot write {}: {}", out, e))?;
+  eprintln!(
+    "Indexed {} documents, searching {}",
+    count,
+    file.fields.join(", ")
+  );
+  Ok(())
+}
+
+/// Reads the documents of a JSON, JSONL, or CSV file, by its extension,
+/// splitting comma-separated tags into lists. Rows that can't be read are
+/// reported and skipped.
+fn read_documents(path: &Path, tags_field: &str) -> Result<Vec<Value>, String> {
+  let name = path.display();
+  let file = File::open(path).map_err(|e| format!("Cannot read {}: {}", name, e))?;
+  let loader = Loader::new(FieldMapping::new().tags(tags_field));
+  let records = loader
+    .records(Format::from_path(path), file)
+    .map_err(|e| format!("{}: {}", name, e))?;
+
+  let mut documents = Vec::new();
+  for record in records {
+    match record {
+      Ok(record) => documents.push(record.item),
+      Err(e) => eprintln!("Skipped {}:{}: {}", name, e.row, e.message),
+    }
+  }
+  Ok(documents)
+}
+
+/// Returns the top-level fields holding a string in any document, in the
+/// order they are first seen.
+fn string_fields(documents: &[Value], tags_field: &str) -> Vec<String> {
+  let mut fields: Vec<String> = Vec::new();
+  for document in documents {
+    let Some(object) = document.as_object() else {
+      continue;
+    };
+    for (key, value) in object {
+      if value.is_string() && key != tags_field && !fields.contains(key) {
+        fields.push(key.clone());
+      }
+    }
+  }
+  fields
+}
+
+/// Runs `searus query`.
+fn query(args: &[String]) -> Result<(), String> {
+  let args = Args::parse(args, &["--json"])?;
+  args.check(&["--text", "--tags", "--filter", "--limit"])?;
+  let [path] = args.positional.as_slice() else {
+    return Err(format!("Expected one index file\n\n{}", USAGE));
+  };
+
+  let content = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+  let file: IndexFile =
+    serde_json::from_str(&content).map_err(|e| format!("Invalid index {}: {}", path, e))?;
+
+  let mut rules = SemanticRules::builder();
+  for field in &file.fields {
+    rules = rules.field(field, FieldRule::bm25());
+  }
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules.build())))
+    .with(Box::new(TaggedSearch::with_field(&file.tags_field)))
+    .build();
+
+  let limit = match args.get(&["--limit"]) {
+    Some(limit) => limit
+      .parse()
+      .map_err(|_| format!("Invalid limit {}", limit))?,
+    None => 10,
+  };
+  let mut query = Query::builder().options(SearchOptions::default().limit(limit));
+  if let Some(text) = args.get(&["--text"]) {
+    query = query.text(text);
+  }
+  if let Some(tags) = args.get(&["--tags"]) {
+    query = query.tags(list(tags));
+  }
+  let filters = args
+    .all("--filter")
+    .into_iter()
+    .map(parse_filter)
+    .collect::<Result<Vec<_>, _>>()?;
+  if !filters.is_empty() {
+    query = query.filters(FilterExpr::And(filters));
+  }
+  let query = query.build();
+
+  // Without text or tags, nothing would match, so list the filtered documents.
+  let response = if query.text.is_none() && query.tags.is_none() {
+    let documents: Vec<SearusMatch<Value>> = file
+      .documents
+      .iter()
+      .enumerate()
+      .filter(|(_, document)| query.filters.as_ref().is_none_or(|f| f.evaluate(document)))
+      .map(|(i, document)| SearusMatch::new(document.clone(), 1.0, i))
+      .collect();
+    SearchResponse {
+      total_matches: documents.len(),
+      results: documents.into_iter().take(limit).collect(),
+      ..engine.search_response(&[], &query)
+    }
+  } else {
+    engine.search_response(&file.documents, &query)
+  };
+
+  if args.flag("--json") {
+    let json = serde_json::to_string_pretty(&response).map_err(|e| e.to_string())?;
+    println!("{}", json);
+    return Ok(());
+  }
+
+  for (rank, m) in response.results.iter().enumerate() {
+    println!(
+      "{:>3}. [{:.3}] {}",
+      rank + 1,
+      m.score,
+      summary(&m.item, &file.fields)
+    );
+  }
+  println!(
+    "{} of {} matches",
+    response.results.len(),
+    response.total_matches
+  );
+  if let Some(suggestion) = &response.suggestion {
+    println!("Did you mean: {}?", suggestion);
+  }
+  Ok(())
+}
+
+/// Parses a `--filter` argument: a JSON filter expression, or a single
+/// `field<op>value` comparison.
+fn parse_filter(filter: &str) -> Result<FilterExpr, String> {
+  if filter.trim_start().starts_with('{') {
+    return serde_json::from_str(filter).map_err(|e| format!("Invalid filter {}: {}", filter, e));
+  }
+
+  let start = filter
+    .find(['=', '!', '<', '>', '~'])
+    .ok_or_else(|| format!("Invalid filter {}: expected field<op>value", filter))?;
+  let (field, rest) = filter.split_at(start);
+  let (op, value) = [
+    ("!=", CompareOp::Ne),
+    ("<=", CompareOp::Le),
+    (">=", CompareOp::Ge),
+    ("==", CompareOp::Eq),
+    ("=", CompareOp::Eq),
+    ("<", CompareOp::Lt),
+    (">", CompareOp::Gt),
+    ("~", CompareOp::Contains),
+  ]
+  .into_iter()
+  .find_map(|(symbol, op)| rest.strip_prefix(symbol).map(|value| (op, value)))
+  .ok_or_else(|| format!("Invalid filter {}: unknown operator", filter))?;
+
+  let field = field.trim();
+  if field.is_empty() {
+    return Err(format!("Invalid filter {}: missing field", filter));
+  }
+  let value = value.trim();
+  let value = match serde_json::from_str::<Value>(value) {
+    Ok(Value::Number(n)) => FilterValue::Number(n.as_f64().unwrap_or_default()),
+    Ok(Value::Bool(b)) => FilterValue::Bool(b),
+    Ok(Value::String(s)) => FilterValue::String(s),
+    _ => FilterValue::String(value.to_string()),
+  };
+
+  Ok(FilterExpr::Compare {
+    field: field.to_string(),
+    op,
+    value,
+    options: StringOptions::default(),
+  })
+}
+
+/// Returns a one-line summary of a document: its searched fields, or the
+/// whole document if they are missing.
+fn summary(document: &Value, fields: &[String]) -> String {
+  let values: Vec<&str> = fields
+    .iter()
+    .filter_map(|field| document.get(field)?.as_str())
+    .collect();
+  let summary = if values.is_empty() {
+    document.to_string()
+  } else {
+    values.join(" | ")
+  };
+
+  match summary.char_indices().nth(100) {
+    Some((end, _)) => format!("{}...", &summary[..end]),
+    None => summary,
+  }
+}
@@ -0,0 +1,243 @@
+//! Search-as-you-type completion.
+//!
+//! A [`CompletionIndex`] holds a set of completion entries (e.g., product names
+//! or article titles) and answers prefix lookups for search-box
+//! autocompletion. It is separate from document search: completions are
+//! looked up directly in an edge n-gram index, without running any searcher.
+//!
+//! Every entry is indexed under the leading characters (edge n-grams) of each
+//! position where a word starts, so the prefix `"rust pro"` completes both
+//! `"Rust programming"` and `"Advanced Rust programming"`.
+
+use crate::document::{get_field_value, to_document};
+use crate::searchers::tokenizer::tokenize_with_offsets;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// A completion returned by [`CompletionIndex::suggest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Completion {
+  /// The completed text, as it was added to the index.
+  pub text: String,
+  /// The weight of the entry. Entries added more than once accumulate weight.
+  pub weight: f32,
+}
+
+/// An edge n-gram index of completion entries.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::completion::CompletionIndex;
+///
+/// #[derive(serde::Serialize)]
+/// struct Post {
+///     title: String,
+/// }
+///
+/// let posts = vec![
+///     Post { title: "Rust programming".into() },
+///     Post { title: "Advanced Rust programming".into() },
+///     Post { title: "Ruby on Rails".into() },
+/// ];
+///
+/// let mut index = CompletionIndex::from_corpus(&posts, &["title"]);
+/// index.add("Rust programming", 2.0);
+///
+/// let completions = index.suggest("rust pro", 5);
+/// assert_eq!(completions[0].text, "Rust programming");
+/// assert_eq!(completions[0].weight, 3.0);
+/// assert_eq!(completions[1].text, "Advanced Rust programming");
+///
+/// assert_eq!(index.suggest("ru", 5).len(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompletionIndex {
+  /// The longest prefix stored in the index, in characters.
+  max_gram: usize,
+  /// The completion entries.
+  entries: Vec<Completion>,
+  /// The position of each entry, by its lowercase text.
+  positions: HashMap<String, usize>,
+  /// Edge n-grams mapped to the entries they occur in.
+  grams: HashMap<String, Vec<usize>>,
+}
+
+impl Default for CompletionIndex {
+  /// Creates an empty `CompletionIndex` storing prefixes of up to 20 characters.
+  fn default() -> Self {
+    Self::new(20)
+  }
+}
+
+impl CompletionIndex {
+  /// Creates an empty `CompletionIndex`.
+  ///
+  /// # Arguments
+  ///
+  /// * `max_gram` - The longest prefix stored in the index, in characters.
+  ///   Longer prefixes are looked up by their first `max_gram` characters and
+  ///   then checked against the entries, so this only trades memory for speed.
+  pub fn new(max_gram: usize) -> Self {
+    Self {
+      max_gram: max_gram.max(1),
+      entries: Vec::new(),
+      positions: HashMap::new(),
+      grams: HashMap::new(),
+    }
+  }
+
+  /// Builds an index from the values of the given fields of a corpus.
+  ///
+  /// Fields may be nested (dot notation) and may contain strings or arrays
+  /// of strings. Each value becomes an entry with a weight of 1 per
+  /// occurrence.
+  pub fn from_corpus<T: Serialize>(items: &[T], fields: &[&str]) -> Self {
+    let mut index = Self::default();
+    index.add_corpus(items, fields);
+    index
+  }
+
+  /// Adds the values of the given fields of a corpus to the index.
+  pub fn add_corpus<T: Serialize>(&mut self, items: &[T], fields: &[&str]) {
+    for item in items {
+      let value = match to_document(item) {
+        Ok(v) => v,
+        Err(_) => continue,
+      };
+
+      for field in fields {
+        match get_field_value(&value, field) {
+          Some(Value::String(text)) => self.add(text, 1.0),
+          Some(Value::Array(values)) => {
+            for text in values.iter().filter_map(Value::as_str) {
+              self.add(text, 1.0);
+            }
+          }
+          _ => {}
+        }
+      }
+    }
+  }
+
+  /// Adds an entry to the index, or increases its weight if it exists.
+  ///
+  /// Entries are matched case-insensitively; the first spelling added is
+  /// the one returned in completions.
+  pub fn add(&mut self, text: &str, weight: f32) {
+    let text = text.trim();
+    let key = text.to_lowercase();
+    if key.is_empty() {
+      return;
+    }
+
+    if let Some(&position) = self.positions.get(&key) {
+      self.entries[position].weight += weight;
+      return;
+    }
+
+    let position = self.entries.len();
+    let mut grams = HashSet::new();
+    for (_, range) in tokenize_with_offsets(&key) {
+      let mut gram = String::new();
+      for c in key[range.start..].chars().take(self.max_gram) {
+        gram.push(c);
+        grams.insert(gram.clone());
+      }
+    }
+    for gram in grams {
+      self.grams.entry(gram).or_default().push(position);
+    }
+
+    self.positions.insert(key, position);
+    self.entries.push(Completion {
+      text: text.to_string(),
+      weight,
+    });
+  }
+
+  /// Returns the number of entries in the index.
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  /// Returns `true` if the index has no entries.
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  /// Finds the entries with a word starting with the given prefix.
+  ///
+  /// # Arguments
+  ///
+  /// * `prefix` - The text typed so far. Matching is case-insensitive.
+  /// * `k` - The maximum number of completions to return.
+  ///
+  /// # Returns
+  ///
+  /// The completions sorted by descending weight, then alphabetically.
+  pub fn suggest(&self, prefix: &str, k: usize) -> Vec<Completion> {
+    let prefix = prefix.trim_start().to_lowercase();
+    if prefix.is_empty() || k == 0 {
+      return Vec::new();
+    }
+
+    let gram: String = prefix.chars().take(self.max_gram).collect();
+    let Some(positions) = self.grams.get(&gram) else {
+      return Vec::new();
+    };
+
+    let mut completions: Vec<&Completion> = positions
+      .iter()
+      .map(|&position| &self.entries[position])
+      .filter(|entry| gram.len() == prefix.len() || self.matches(&entry.text, &prefix))
+      .collect();
+
+    completions.sort_by(|a, b| {
+      b.weight
+        .partial_cmp(&a.weight)
+        .unwrap_or(std::cmp::Ordering::Equal)
+        .then_with(|| a.text.cmp(&b.text))
+    });
+    completions.into_iter().take(k).cloned().collect()
+  }
+
+  /// Checks a prefix longer than `max_gram` against the word starts of an entry.
+  fn matches(&self, text: &str, prefix: &str) -> bool {
+    let key = text.to_lowercase();
+    tokenize_with_offsets(&key)
+      .into_iter()
+      .any(|(_, range)| key[range.start..].starts_with(prefix))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_prefix_longer_than_max_gram() {
+    let mut index = CompletionIndex::new(3);
+    index.add("Search engine", 1.0);
+    index.add("Searching for answers", 1.0);
+
+    let completions = index.suggest("search e", 5);
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].text, "Search engine");
+    assert_eq!(index.suggest("sea", 5).len(), 2);
+  }
+
+  #[test]
+  fn test_duplicate_entries_accumulate_weight() {
+    let mut index = CompletionIndex::default();
+    index.add("Rust", 1.0);
+    index.add("rust", 2.0);
+    index.add("Ruby", 2.5);
+
+    let completions = index.suggest("r", 1);
+    assert_eq!(index.len(), 2);
+    assert_eq!(completions[0].text, "Rust");
+    assert_eq!(completions[0].weight, 3.0);
+  }
+}
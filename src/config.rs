@@ -0,0 +1,132 @@
+//! A serializable description of an engine, for building engines from JSON.
+//!
+//! An [`EngineConfig`] names the built-in searchers an engine runs and how
+//! each is configured. It lets an engine be described outside of Rust, in a
+//! config file or by the language bindings, and built with
+//! [`EngineConfig::build`]:
+//!
+//! ```json
+//! {
+//!   "semantic": { "fields": { "title": { "matcher": "bm25", "priority": 2 } } },
+//!   "fuzzy": { "fields": ["title"], "threshold": 0.75 },
+//!   "tags": { "field": "tags" },
+//!   "weights": { "Semantic": 0.7, "Tags": 0.3 }
+//! }
+//! ```
+
+use crate::engine::SearusEngine;
+#[cfg(feature = "semantic")]
+use crate::rules::SemanticRules;
+#[cfg(feature = "fuzzy")]
+use crate::searchers::FuzzySearch;
+#[cfg(feature = "semantic")]
+use crate::searchers::SemanticSearch;
+#[cfg(feature = "tagged")]
+use crate::searchers::TaggedSearch;
+use crate::types::SearcherKind;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The searchers of an engine and their configuration.
+///
+/// Searchers left out are not added; an empty config builds an engine that
+/// matches nothing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EngineConfig {
+  /// The rules of a `SemanticSearch`.
+  #[cfg(feature = "semantic")]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub semantic: Option<SemanticRules>,
+  /// The configuration of a `FuzzySearch`.
+  #[cfg(feature = "fuzzy")]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub fuzzy: Option<FuzzyConfig>,
+  /// The configuration of a `TaggedSearch`.
+  #[cfg(feature = "tagged")]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub tags: Option<TagsConfig>,
+  /// The default weights of the kinds of searchers, used by queries that
+  /// don't weight them.
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub weights: HashMap<SearcherKind, f32>,
+}
+
+/// The configuration of a `FuzzySearch`.
+#[cfg(feature = "fuzzy")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FuzzyConfig {
+  /// The fields to search.
+  pub fields: Vec<String>,
+  /// The minimum similarity of a match, 0.8 by default.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub threshold: Option<f64>,
+}
+
+/// The configuration of a `TaggedSearch`.
+#[cfg(feature = "tagged")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TagsConfig {
+  /// The field holding the tags of an item, `tags` by default.
+  #[serde(default = "default_tags_field")]
+  pub field: String,
+}
+
+/// Returns the default field holding the tags of an item.
+#[cfg(feature = "tagged")]
+fn default_tags_field() -> String {
+  "tags".to_string()
+}
+
+impl EngineConfig {
+  /// Parses an `EngineConfig` from JSON.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::config::EngineConfig;
+  ///
+  /// let config = EngineConfig::from_json(r#"{
+  ///     "semantic": { "fields": { "title": { "matcher": "bm25" } } }
+  /// }"#).unwrap();
+  /// let engine = config.build::<serde_json::Value>();
+  ///
+  /// let error = EngineConfig::from_json(r#"{ "semantics": {} }"#);
+  /// assert!(error.is_err());
+  /// ```
+  pub fn from_json(json: &str) -> Result<Self, String> {
+    serde_json::from_str(json).map_err(|e| format!("Invalid engine config: {}", e))
+  }
+
+  /// Builds an engine running the configured searchers.
+  pub fn build<T>(&self) -> SearusEngine<T>
+  where
+    T: serde::Serialize + Clone + Send + Sync + 'static,
+  {
+    #[allow(unused_mut)]
+    let mut builder = SearusEngine::builder().default_weights(self.weights.clone());
+
+    #[cfg(feature = "semantic")]
+    if let Some(rules) = &self.semantic {
+      builder = builder.with(Box::new(SemanticSearch::new(rules.clone())));
+    }
+
+    #[cfg(feature = "fuzzy")]
+    if let Some(fuzzy) = &self.fuzzy {
+      let mut searcher = FuzzySearch::new(fuzzy.fields.clone());
+      if let Some(threshold) = fuzzy.threshold {
+        searcher = searcher.with_threshold(threshold);
+      }
+      builder = builder.with(Box::new(searcher));
+    }
+
+    #[cfg(feature = "tagged")]
+    if let Some(tags) = &self.tags {
+      builder = builder.with(Box::new(TaggedSearch::with_field(&tags.field)));
+    }
+
+    builder.build()
+  }
+}
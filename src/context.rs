@@ -1,7 +1,20 @@
 //! Context provided to searchers during a search operation.
 
 use std::any::Any;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::document::to_document;
+use crate::filter::FilterExpr;
+use crate::searcher::TopMatches;
+use crate::types::{Query, Searchable, SearusMatch};
 
 /// A context object that provides access to the items being searched and other shared resources.
 ///
@@ -16,6 +29,16 @@ pub struct SearchContext<'a, T> {
   /// For example, a searcher could store pre-computed statistics here to be shared
   /// across multiple calls or with other searchers.
   pub cache: HashMap<String, Box<dyn Any + Send + Sync>>,
+  /// The items serialized to JSON, each built the first time it is needed and
+  /// shared by every searcher. `serde_json::Value` items are borrowed rather
+  /// than copied. `None` marks an item that failed to serialize.
+  documents: OnceLock<Vec<OnceLock<Option<Cow<'a, Value>>>>>,
+  /// Whether a searcher dropped matches to stay within its budget.
+  truncated: AtomicBool,
+  /// Whether searchers may match the items in parallel.
+  parallel: bool,
+  /// The fewest items matched by each parallel task.
+  chunk_size: usize,
 }
 
 impl<'a, T> SearchContext<'a, T> {
@@ -24,11 +47,56 @@ impl<'a, T> SearchContext<'a, T> {
     Self {
       items,
       cache: HashMap::new(),
+      documents: OnceLock::new(),
+      truncated: AtomicBool::new(false),
+      parallel: cfg!(feature = "parallel"),
+      chunk_size: 1,
     }
   }
 
+  /// Sets whether searchers may match the items in parallel. Without the
+  /// `parallel` feature, searches always run sequentially.
+  ///
+  /// Engines search sequentially over fewer items than their
+  /// [`parallel_threshold`](crate::engine::SearusEngineBuilder::parallel_threshold).
+  pub fn with_parallel(mut self, parallel: bool) -> Self {
+    self.parallel = parallel && cfg!(feature = "parallel");
+    self
+  }
+
+  /// Returns `true` if searchers may match the items in parallel.
+  ///
+  /// Custom searchers that iterate over the items in parallel themselves
+  /// should do so only when this is `true`.
+  pub fn is_parallel(&self) -> bool {
+    self.parallel
+  }
+
+  /// Sets the fewest items each parallel task matches. The default is 1,
+  /// which lets rayon split the items as finely as it sees fit.
+  ///
+  /// Larger chunks lower the cost of splitting the work for cheap items (a
+  /// few short fields), and keep the items a thread matches next to each
+  /// other in memory.
+  pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+    self.chunk_size = chunk_size.max(1);
+    self
+  }
+
+  /// Returns the fewest items each parallel task matches.
+  ///
+  /// Custom searchers that iterate over the items in parallel themselves
+  /// should pass it to rayon's `with_min_len`.
+  pub fn chunk_size(&self) -> usize {
+    self.chunk_size
+  }
+
   /// Adds a value to the context's cache.
-  pub fn with_cache_value<V: Any + Send + Sync>(mut self, key: impl Into<String>, value: V) -> Self {
+  pub fn with_cache_value<V: Any + Send + Sync>(
+    mut self,
+    key: impl Into<String>,
+    value: V,
+  ) -> Self {
     self.cache.insert(key.into(), Box::new(value));
     self
   }
@@ -37,4 +105,199 @@ impl<'a, T> SearchContext<'a, T> {
   pub fn get_cache_value<V: Any + 'static>(&self, key: &str) -> Option<&V> {
     self.cache.get(key).and_then(|v| v.downcast_ref::<V>())
   }
+
+  /// Records that a searcher dropped matches to stay within
+  /// [`SearchOptions::max_matches_per_searcher`](crate::types::SearchOptions::max_matches_per_searcher),
+  /// which marks the response as truncated.
+  pub fn mark_truncated(&self) {
+    self.truncated.store(true, Ordering::Relaxed);
+  }
+
+  /// Returns `true` if a searcher dropped matches during this search.
+  pub fn is_truncated(&self) -> bool {
+    self.truncated.load(Ordering::Relaxed)
+  }
+
+  /// Keeps the best `limit` matches of a searcher, marking the search as
+  /// truncated if any were dropped.
+  pub(crate) fn limit_matches(
+    &self,
+    matches: Vec<SearusMatch<T>>,
+    limit: Option<usize>,
+  ) -> Vec<SearusMatch<T>>
+  where
+    T: Searchable,
+  {
+    match limit {
+      Some(limit) if matches.len() > limit => {
+        let mut top = TopMatches::new(Some(limit));
+        top.extend(matches);
+        self.mark_truncated();
+        top.into_vec()
+      }
+      _ => matches,
+    }
+  }
+}
+
+impl<T: Searchable + Serialize> SearchContext<'_, T> {
+  /// Returns the JSON form of the item at `index`, or `None` if it could not be serialized.
+  ///
+  /// Each item is serialized the first time its document is needed, and the
+  /// document is reused for the rest of the search. Searches matching only
+  /// some candidates, such as tagged searches over a warmed index, serialize
+  /// just those items.
+  pub fn document(&self, index: usize) -> Option<&Value> {
+    let documents = self
+      .documents
+      .get_or_init(|| self.items.iter().map(|_| OnceLock::new()).collect());
+    documents
+      .get(index)?
+      .get_or_init(|| to_document(&self.items[index]).ok())
+      .as_deref()
+  }
+
+  /// Checks whether the item at `index` passes the given filters.
+  ///
+  /// Unlike calling [`FilterExpr::evaluate`] on each item, this serializes every
+  /// item at most once per search, however many searchers apply the filters.
+  pub fn matches_filters(&self, index: usize, filters: Option<&FilterExpr>) -> bool {
+    match filters {
+      Some(filters) => self
+        .document(index)
+        .is_some_and(|document| filters.evaluate_json(document)),
+      None => true,
+    }
+  }
+
+  /// Matches the items passing the filters of the query with `match_item`,
+  /// in parallel with the `parallel` feature, unless the search runs
+  /// sequentially (see [`is_parallel`](Self::is_parallel)). Each parallel
+  /// task matches at least [`chunk_size`](Self::chunk_size) items.
+  ///
+  /// Only the items at `candidates` are matched if given, and every item
+  /// otherwise. At most
+  /// [`max_matches_per_searcher`](crate::types::SearchOptions::max_matches_per_searcher)
+  /// matches are kept at a time, the best-scoring ones (see [`TopMatches`]),
+  /// and the search is marked as truncated if any were dropped. The matches
+  /// are returned in no particular order.
+  #[cfg(feature = "parallel")]
+  pub fn scan<F>(
+    &self,
+    candidates: Option<&[usize]>,
+    query: &Query,
+    match_item: F,
+  ) -> Vec<SearusMatch<T>>
+  where
+    F: Fn(usize, &T) -> Option<SearusMatch<T>> + Sync,
+  {
+    if !self.parallel {
+      return self.scan_sequential(candidates, query, match_item);
+    }
+
+    let filters = query.filters.as_ref();
+    let limit = query.options.max_matches_per_searcher;
+    let match_index = |index: usize| {
+      if self.matches_filters(index, filters) {
+        match_item(index, &self.items[index])
+      } else {
+        None
+      }
+    };
+
+    let top = match candidates {
+      Some(candidates) => top_matches(
+        candidates
+          .par_iter()
+          .with_min_len(self.chunk_size)
+          .filter_map(|&i| match_index(i)),
+        limit,
+      ),
+      None => top_matches(
+        (0..self.items.len())
+          .into_par_iter()
+          .with_min_len(self.chunk_size)
+          .filter_map(match_index),
+        limit,
+      ),
+    };
+    if top.is_truncated() {
+      self.mark_truncated();
+    }
+    top.into_vec()
+  }
+
+  /// Matches the items passing the filters of the query with `match_item`.
+  ///
+  /// Only the items at `candidates` are matched if given, and every item
+  /// otherwise. At most
+  /// [`max_matches_per_searcher`](crate::types::SearchOptions::max_matches_per_searcher)
+  /// matches are kept at a time, the best-scoring ones (see [`TopMatches`]),
+  /// and the search is marked as truncated if any were dropped. The matches
+  /// are returned in no particular order.
+  #[cfg(not(feature = "parallel"))]
+  pub fn scan<F>(
+    &self,
+    candidates: Option<&[usize]>,
+    query: &Query,
+    match_item: F,
+  ) -> Vec<SearusMatch<T>>
+  where
+    F: Fn(usize, &T) -> Option<SearusMatch<T>>,
+  {
+    self.scan_sequential(candidates, query, match_item)
+  }
+
+  /// Matches the items passing the filters of the query with `match_item`,
+  /// one at a time.
+  fn scan_sequential<F>(
+    &self,
+    candidates: Option<&[usize]>,
+    query: &Query,
+    match_item: F,
+  ) -> Vec<SearusMatch<T>>
+  where
+    F: Fn(usize, &T) -> Option<SearusMatch<T>>,
+  {
+    let filters = query.filters.as_ref();
+    let mut top = TopMatches::new(query.options.max_matches_per_searcher);
+    let mut match_index = |index: usize| {
+      if self.matches_filters(index, filters) {
+        top.extend(match_item(index, &self.items[index]));
+      }
+    };
+
+    match candidates {
+      Some(candidates) => candidates.iter().for_each(|&i| match_index(i)),
+      None => (0..self.items.len()).for_each(match_index),
+    }
+    if top.is_truncated() {
+      self.mark_truncated();
+    }
+    top.into_vec()
+  }
+}
+
+/// Collects matches in parallel, keeping the best `limit` of them in each
+/// thread.
+#[cfg(feature = "parallel")]
+fn top_matches<T, I>(matches: I, limit: Option<usize>) -> TopMatches<T>
+where
+  T: Searchable,
+  I: ParallelIterator<Item = SearusMatch<T>>,
+{
+  if limit.is_none() {
+    let mut top = TopMatches::new(None);
+    top.extend(matches.collect::<Vec<_>>());
+    return top;
+  }
+  matches
+    .fold(
+      || TopMatches::new(limit),
+      |mut top, m| {
+        top.push(m);
+        top
+      },
+    )
+    .reduce(|| TopMatches::new(limit), TopMatches::merge)
 }
@@ -0,0 +1,177 @@
+//! Items as JSON documents, and the field paths addressing their values.
+//!
+//! Searchers, filters, and extensions read items through their JSON form,
+//! so any `Serialize` type can be searched. `serde_json::Value` is a
+//! first-class item type: values are searched as they are, without being
+//! serialized again, which lets applications with dynamic or user-defined
+//! schemas use Searus without generating Rust structs.
+//!
+//! # Field paths
+//!
+//! Fields are addressed by paths of segments separated by dots:
+//!
+//! * A segment names a key of an object, as in `author.name`.
+//! * A numeric segment indexes an array, as in `comments.0.text`.
+//! * An empty path refers to the whole document.
+//!
+//! Semantic fields also accept `*` segments, matching every element of an
+//! array or every value of an object (`comments.*.text`), and wildcard
+//! segments matching the keys of an object (`*_title`).
+//!
+//! # Arrays and objects
+//!
+//! * Text searchers search each string, number, or boolean of an array
+//!   field, so `tags` searches every tag. Objects are not text, and their
+//!   values are searched through nested paths instead.
+//! * `In`, `NotIn`, `Contains`, and the prefix and suffix comparisons of a
+//!   filter match an array field if any of its elements does. Other
+//!   comparisons never match arrays or objects; use `FilterExpr::Any` and
+//!   `FilterExpr::All` to apply a filter to the elements of an array.
+//! * Facets count each element of an array field.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use searus::prelude::*;
+//! use serde_json::json;
+//!
+//! let items = vec![
+//!     json!({ "title": "Rust ownership", "meta": { "author": "Ann", "tags": ["rust", "memory"] } }),
+//!     json!({ "title": "Python typing", "meta": { "author": "Bob", "tags": ["python"] } }),
+//! ];
+//!
+//! let rules = SemanticRules::builder()
+//!     .field("title", FieldRule::bm25())
+//!     .field("meta.tags", FieldRule::bm25())
+//!     .build();
+//! let engine = SearusEngine::builder()
+//!     .with(Box::new(SemanticSearch::new(rules)))
+//!     .build();
+//!
+//! let query = Query::builder()
+//!     .text("memory")
+//!     .filters(Query::filter(Query::COMPARE).eq("meta.author", "Ann").build())
+//!     .build();
+//! let results = engine.search(&items, &query);
+//! assert_eq!(results[0].item["title"], "Rust ownership");
+//! ```
+
+use serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+
+#[cfg(feature = "semantic")]
+use crate::searchers::pattern::wildcard_match;
+
+/// Returns the JSON form of an item.
+///
+/// `serde_json::Value` items are borrowed as they are; other items are
+/// serialized.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::document::to_document;
+/// use serde_json::json;
+/// use std::borrow::Cow;
+///
+/// let value = json!({ "title": "Rust" });
+/// assert!(matches!(to_document(&value).unwrap(), Cow::Borrowed(_)));
+///
+/// let document = to_document(&("Rust", 2024)).unwrap();
+/// assert_eq!(*document, json!(["Rust", 2024]));
+/// ```
+pub fn to_document<T: Serialize>(item: &T) -> serde_json::Result<Cow<'_, Value>> {
+  if typeid::of::<T>() == typeid::of::<Value>() {
+    // SAFETY: `T` is `Value`, checked above, so the reference has the right
+    // type, and `Value` has no lifetimes for the cast to extend.
+    let value = unsafe { &*(item as *const T as *const Value) };
+    return Ok(Cow::Borrowed(value));
+  }
+  serde_json::to_value(item).map(Cow::Owned)
+}
+
+/// Gets a value from a document by its path, with dot notation.
+///
+/// Numeric segments index into arrays (e.g., `comments.0.text`), and an
+/// empty path refers to the value itself.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::document::get_field_value;
+/// use serde_json::json;
+///
+/// let post = json!({ "author": { "name": "Ann" }, "comments": [{ "text": "Nice" }] });
+/// assert_eq!(get_field_value(&post, "author.name"), Some(&json!("Ann")));
+/// assert_eq!(get_field_value(&post, "comments.0.text"), Some(&json!("Nice")));
+/// assert_eq!(get_field_value(&post, "comments.1.text"), None);
+/// ```
+pub fn get_field_value<'a>(item: &'a Value, path: &str) -> Option<&'a Value> {
+  if path.is_empty() {
+    return Some(item);
+  }
+  let mut current = item;
+  for part in path.split('.') {
+    current = match current {
+      Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+      _ => current.get(part)?,
+    };
+  }
+  Some(current)
+}
+
+/// Gets all the values matching a path with dot notation, each with its
+/// concrete path.
+///
+/// In addition to the segments supported by [`get_field_value`], a `*`
+/// segment matches every element of an array (or every value of an object),
+/// so `comments.*.text` returns the text of each comment as `comments.0.text`,
+/// `comments.1.text`, and so on. Other segments containing `*` or `?` match
+/// the keys of objects as wildcard patterns, so `*_title` returns both
+/// `en_title` and `fr_title`.
+#[cfg(feature = "semantic")]
+pub fn get_field_values<'a>(item: &'a Value, path: &str) -> Vec<(String, &'a Value)> {
+  let mut current = vec![(String::new(), item)];
+
+  for part in path.split('.') {
+    let mut next = Vec::new();
+    for (prefix, value) in current {
+      let join = |key: &str| {
+        if prefix.is_empty() {
+          key.to_string()
+        } else {
+          format!("{}.{}", prefix, key)
+        }
+      };
+      match (part, value) {
+        ("*", Value::Array(items)) => next.extend(
+          items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (join(&i.to_string()), item)),
+        ),
+        ("*", Value::Object(map)) => next.extend(map.iter().map(|(key, item)| (join(key), item))),
+        (_, Value::Array(items)) => {
+          if let Some(item) = part.parse::<usize>().ok().and_then(|i| items.get(i)) {
+            next.push((join(part), item));
+          }
+        }
+        (_, Value::Object(map)) if part.contains(['*', '?']) => next.extend(
+          map
+            .iter()
+            .filter(|(key, _)| wildcard_match(part, key))
+            .map(|(key, item)| (join(key), item)),
+        ),
+        _ => {
+          if let Some(item) = value.get(part) {
+            next.push((join(part), item));
+          }
+        }
+      }
+    }
+    current = next;
+  }
+
+  current
+}
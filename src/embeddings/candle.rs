@@ -0,0 +1,315 @@
+//! A pure-Rust local text embedder running BERT sentence-transformers with candle.
+
+use std::path::{Path, PathBuf};
+
+use candle_core::{Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+use hf_hub::api::sync::ApiBuilder;
+use hf_hub::{Repo, RepoType};
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+use crate::embeddings::TextEmbedder;
+
+/// The device a [`CandleTextEmbedder`] runs its model on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CandleDevice {
+  /// The CPU.
+  #[default]
+  Cpu,
+  /// The CUDA GPU with the given ordinal. Requires the `candle-cuda` feature.
+  Cuda(usize),
+  /// The Metal GPU with the given ordinal. Requires the `candle-metal` feature.
+  Metal(usize),
+  /// The first CUDA or Metal GPU if one is available, and the CPU otherwise.
+  BestAvailable,
+}
+
+impl CandleDevice {
+  /// Opens the device.
+  fn open(self) -> Result<Device, String> {
+    let device = match self {
+      CandleDevice::Cpu => Ok(Device::Cpu),
+      CandleDevice::Cuda(ordinal) => Device::new_cuda(ordinal),
+      CandleDevice::Metal(ordinal) => Device::new_metal(ordinal),
+      CandleDevice::BestAvailable if candle_core::utils::cuda_is_available() => Device::new_cuda(0),
+      CandleDevice::BestAvailable if candle_core::utils::metal_is_available() => {
+        Device::new_metal(0)
+      }
+      CandleDevice::BestAvailable => Ok(Device::Cpu),
+    };
+    device.map_err(|e| format!("Failed to open device {:?}: {}", self, e))
+  }
+}
+
+/// A `TextEmbedder` that runs a BERT sentence-transformer model with
+/// [candle](https://github.com/huggingface/candle), in pure Rust.
+///
+/// Unlike [`OnnxTextEmbedder`](crate::embeddings::OnnxTextEmbedder), nothing
+/// has to be installed besides the crate. The model (`config.json`,
+/// `tokenizer.json` and `model.safetensors`) is downloaded from Hugging Face
+/// and cached the first time it is used, or read from a local directory.
+///
+/// Texts are embedded in batches, mean-pooled over their tokens, and
+/// L2-normalized unless disabled, as sentence-transformers does.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use searus::embeddings::{CandleDevice, CandleTextEmbedder, TextEmbedder};
+///
+/// let embedder = CandleTextEmbedder::builder()
+///     .model("sentence-transformers/all-MiniLM-L6-v2")
+///     .device(CandleDevice::BestAvailable)
+///     .batch_size(64)
+///     .build()
+///     .unwrap();
+///
+/// let vectors = embedder.embed_batch(&["rust search engine", "vector search"]).unwrap();
+/// assert_eq!(vectors[0].len(), embedder.dimension());
+/// ```
+pub struct CandleTextEmbedder {
+  /// The loaded model.
+  model: BertModel,
+  /// The tokenizer, padding each batch to its longest text.
+  tokenizer: Tokenizer,
+  /// The device the model runs on.
+  device: Device,
+  /// The length of the vectors produced by the model.
+  dimension: usize,
+  /// The number of texts run through the model at once.
+  batch_size: usize,
+  /// Whether vectors are L2-normalized.
+  normalize: bool,
+}
+
+impl CandleTextEmbedder {
+  /// Loads the default model, `sentence-transformers/all-MiniLM-L6-v2`
+  /// (384 dimensions), on the CPU.
+  pub fn new() -> Result<Self, String> {
+    Self::builder().build()
+  }
+
+  /// Creates a builder to choose the model and the device.
+  pub fn builder() -> CandleTextEmbedderBuilder {
+    CandleTextEmbedderBuilder::new()
+  }
+
+  /// Returns the length of the vectors produced by the model.
+  pub fn dimension(&self) -> usize {
+    self.dimension
+  }
+
+  /// Embeds a batch of texts no larger than the batch size.
+  fn embed_chunk(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+    let encodings = self
+      .tokenizer
+      .encode_batch(texts.to_vec(), true)
+      .map_err(|e| format!("Failed to tokenize text: {}", e))?;
+
+    let embed = || -> candle_core::Result<Vec<Vec<f32>>> {
+      let ids = encodings
+        .iter()
+        .map(|encoding| Tensor::new(encoding.get_ids(), &self.device))
+        .collect::<candle_core::Result<Vec<_>>>()?;
+      let masks = encodings
+        .iter()
+        .map(|encoding| Tensor::new(encoding.get_attention_mask(), &self.device))
+        .collect::<candle_core::Result<Vec<_>>>()?;
+      let input_ids = Tensor::stack(&ids, 0)?;
+      let attention_mask = Tensor::stack(&masks, 0)?;
+      let token_type_ids = input_ids.zeros_like()?;
+
+      // (batch, tokens, hidden), averaged over the tokens that aren't padding.
+      let output = self
+        .model
+        .forward(&input_ids, &token_type_ids, Some(&attention_mask))?;
+      let mask = attention_mask.to_dtype(DTYPE)?.unsqueeze(2)?;
+      let pooled = output
+        .broadcast_mul(&mask)?
+        .sum(1)?
+        .broadcast_div(&mask.sum(1)?)?;
+      let pooled = if self.normalize {
+        pooled.broadcast_div(&pooled.sqr()?.sum_keepdim(1)?.sqrt()?)?
+      } else {
+        pooled
+      };
+      pooled.to_vec2::<f32>()
+    };
+    embed().map_err(|e| format!("Failed to embed text: {}", e))
+  }
+}
+
+impl TextEmbedder for CandleTextEmbedder {
+  fn embed(&self, text: &str) -> Result<Vec<f32>, String> {
+    self
+      .embed_chunk(&[text])?
+      .pop()
+      .ok_or_else(|| "Embedding model returned no vector".to_string())
+  }
+
+  /// Embeds the texts in batches of the configured size.
+  fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+    let mut embeddings = Vec::with_capacity(texts.len());
+    for chunk in texts.chunks(self.batch_size) {
+      embeddings.extend(self.embed_chunk(chunk)?);
+    }
+    Ok(embeddings)
+  }
+}
+
+/// A builder for [`CandleTextEmbedder`].
+pub struct CandleTextEmbedderBuilder {
+  model: String,
+  revision: String,
+  model_dir: Option<PathBuf>,
+  cache_dir: Option<PathBuf>,
+  device: CandleDevice,
+  max_length: Option<usize>,
+  batch_size: usize,
+  normalize: bool,
+}
+
+impl CandleTextEmbedderBuilder {
+  /// Creates a builder for `sentence-transformers/all-MiniLM-L6-v2` on the CPU.
+  pub fn new() -> Self {
+    Self {
+      model: "sentence-transformers/all-MiniLM-L6-v2".to_string(),
+      revision: "main".to_string(),
+      model_dir: None,
+      cache_dir: None,
+      device: CandleDevice::Cpu,
+      max_length: None,
+      batch_size: 32,
+      normalize: true,
+    }
+  }
+
+  /// Sets the Hugging Face repository of the model, which must be a BERT
+  /// model with safetensors weights.
+  pub fn model(mut self, model: impl Into<String>) -> Self {
+    self.model = model.into();
+    self
+  }
+
+  /// Sets the revision (branch, tag or commit) of the model repository.
+  pub fn revision(mut self, revision: impl Into<String>) -> Self {
+    self.revision = revision.into();
+    self
+  }
+
+  /// Loads the model from a local directory instead of Hugging Face.
+  pub fn model_dir(mut self, model_dir: impl Into<PathBuf>) -> Self {
+    self.model_dir = Some(model_dir.into());
+    self
+  }
+
+  /// Sets the directory where downloaded models are cached.
+  pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+    self.cache_dir = Some(cache_dir.into());
+    self
+  }
+
+  /// Sets the device the model runs on.
+  pub fn device(mut self, device: CandleDevice) -> Self {
+    self.device = device;
+    self
+  }
+
+  /// Sets the maximum number of tokens per text. Longer texts are truncated.
+  /// Defaults to the model's maximum.
+  pub fn max_length(mut self, max_length: usize) -> Self {
+    self.max_length = Some(max_length);
+    self
+  }
+
+  /// Sets the number of texts run through the model at once (32 by default).
+  pub fn batch_size(mut self, batch_size: usize) -> Self {
+    self.batch_size = batch_size.max(1);
+    self
+  }
+
+  /// Sets whether vectors are L2-normalized (the default).
+  pub fn normalize(mut self, normalize: bool) -> Self {
+    self.normalize = normalize;
+    self
+  }
+
+  /// Loads the model, downloading it first if needed.
+  pub fn build(self) -> Result<CandleTextEmbedder, String> {
+    let [config, tokenizer, weights] = self.model_files()?;
+    let device = self.device.open()?;
+
+    let config =
+      std::fs::read_to_string(config).map_err(|e| format!("Failed to read model config: {}", e))?;
+    let config: Config =
+      serde_json::from_str(&config).map_err(|e| format!("Invalid model config: {}", e))?;
+
+    let mut tokenizer =
+      Tokenizer::from_file(tokenizer).map_err(|e| format!("Failed to load tokenizer: {}", e))?;
+    tokenizer.with_padding(Some(PaddingParams {
+      strategy: PaddingStrategy::BatchLongest,
+      ..Default::default()
+    }));
+    tokenizer
+      .with_truncation(Some(TruncationParams {
+        max_length: self
+          .max_length
+          .unwrap_or(config.max_position_embeddings)
+          .min(config.max_position_embeddings),
+        ..Default::default()
+      }))
+      .map_err(|e| format!("Failed to configure tokenizer: {}", e))?;
+
+    let weights =
+      std::fs::read(weights).map_err(|e| format!("Failed to read model weights: {}", e))?;
+    let model = VarBuilder::from_buffered_safetensors(weights, DTYPE, &device)
+      .and_then(|vb| BertModel::load(vb, &config))
+      .map_err(|e| format!("Failed to load embedding model: {}", e))?;
+
+    Ok(CandleTextEmbedder {
+      model,
+      tokenizer,
+      device,
+      dimension: config.hidden_size,
+      batch_size: self.batch_size,
+      normalize: self.normalize,
+    })
+  }
+
+  /// Returns the paths of the config, tokenizer and weights files.
+  fn model_files(&self) -> Result<[PathBuf; 3], String> {
+    const FILES: [&str; 3] = ["config.json", "tokenizer.json", "model.safetensors"];
+
+    if let Some(dir) = &self.model_dir {
+      return Ok(FILES.map(|file| Path::new(dir).join(file)));
+    }
+
+    let mut api = ApiBuilder::new().with_progress(false);
+    if let Some(cache_dir) = &self.cache_dir {
+      api = api.with_cache_dir(cache_dir.clone());
+    }
+    let api = api
+      .build()
+      .map_err(|e| format!("Failed to reach Hugging Face: {}", e))?;
+    let repo = api.repo(Repo::with_revision(
+      self.model.clone(),
+      RepoType::Model,
+      self.revision.clone(),
+    ));
+
+    let mut paths = FILES.map(PathBuf::from);
+    for (path, file) in paths.iter_mut().zip(FILES) {
+      *path = repo
+        .get(file)
+        .map_err(|e| format!("Failed to download {} of {}: {}", file, self.model, e))?;
+    }
+    Ok(paths)
+  }
+}
+
+impl Default for CandleTextEmbedderBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,272 @@
+//! Splitting long documents into chunks, and pooling the embeddings of the
+//! chunks into one vector.
+
+use crate::embeddings::TextEmbedder;
+
+/// The unit a [`Chunker`] counts in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkUnit {
+  /// Runs of non-whitespace characters. Embedding models split words into
+  /// several tokens, so leave room below the model's token limit.
+  Words,
+  /// Sentences, ending at `.`, `!` or `?` followed by whitespace, at their
+  /// full-width forms, or at a blank line.
+  Sentences,
+}
+
+/// Splits text into overlapping chunks of words or sentences, so that long
+/// documents can be embedded without being truncated by the model.
+///
+/// Chunks are slices of the original text, from the start of their first
+/// unit to the end of their last, so they keep its spacing and punctuation.
+/// Consecutive chunks share `overlap` units, which keeps a passage that
+/// straddles a boundary whole in at least one of them.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::embeddings::Chunker;
+///
+/// let chunker = Chunker::words(4).with_overlap(1);
+/// let chunks = chunker.chunk("Searus blends semantic, tagged, and fuzzy search in one engine.");
+/// assert_eq!(
+///     chunks,
+///     vec!["Searus blends semantic, tagged,", "tagged, and fuzzy search", "search in one engine."]
+/// );
+///
+/// let chunker = Chunker::sentences(2);
+/// let chunks = chunker.chunk("One. Two! Three? Four.");
+/// assert_eq!(chunks, vec!["One. Two!", "Three? Four."]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunker {
+  /// The unit chunks are measured in.
+  unit: ChunkUnit,
+  /// The number of units in a chunk.
+  size: usize,
+  /// The number of units shared by consecutive chunks.
+  overlap: usize,
+}
+
+impl Chunker {
+  /// Creates a chunker for the given unit and chunk size, without overlap.
+  /// A size of 0 is treated as 1.
+  pub fn new(unit: ChunkUnit, size: usize) -> Self {
+    Self {
+      unit,
+      size: size.max(1),
+      overlap: 0,
+    }
+  }
+
+  /// Creates a chunker of `size` words per chunk.
+  pub fn words(size: usize) -> Self {
+    Self::new(ChunkUnit::Words, size)
+  }
+
+  /// Creates a chunker of `size` sentences per chunk.
+  pub fn sentences(size: usize) -> Self {
+    Self::new(ChunkUnit::Sentences, size)
+  }
+
+  /// Sets the number of units shared by consecutive chunks. It is capped
+  /// below the chunk size, so that every chunk moves forward.
+  pub fn with_overlap(mut self, overlap: usize) -> Self {
+    self.overlap = overlap.min(self.size - 1);
+    self
+  }
+
+  /// Splits a text into chunks. Text without any words has no chunks.
+  pub fn chunk<'a>(&self, text: &'a str) -> Vec<&'a str> {
+    let spans = match self.unit {
+      ChunkUnit::Words => word_spans(text),
+      ChunkUnit::Sentences => sentence_spans(text),
+    };
+
+    let step = self.size - self.overlap;
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < spans.len() {
+      let end = (start + self.size).min(spans.len());
+      chunks.push(&text[spans[start].0..spans[end - 1].1]);
+      if end == spans.len() {
+        break;
+      }
+      start += step;
+    }
+    chunks
+  }
+
+  /// Splits a text into chunks and embeds them in one batch.
+  ///
+  /// The vectors can be stored as several vectors of the same document, or
+  /// combined into one with [`Pooling::pool`].
+  pub fn embed(&self, embedder: &dyn TextEmbedder, text: &str) -> Result<Vec<Vec<f32>>, String> {
+    let chunks = self.chunk(text);
+    if chunks.is_empty() {
+      return Ok(Vec::new());
+    }
+    embedder.embed_batch(&chunks)
+  }
+
+  /// Splits a text into chunks, embeds them, and pools their vectors into
+  /// one.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::embeddings::{Chunker, Pooling, StubTextEmbedder};
+  ///
+  /// let embedder = StubTextEmbedder::new(8);
+  /// let document = "First sentence. Second sentence. Third sentence.";
+  /// let vector = Chunker::sentences(1)
+  ///     .embed_pooled(&embedder, document, Pooling::Mean)
+  ///     .unwrap();
+  /// assert_eq!(vector.len(), 8);
+  /// ```
+  pub fn embed_pooled(
+    &self,
+    embedder: &dyn TextEmbedder,
+    text: &str,
+    pooling: Pooling,
+  ) -> Result<Vec<f32>, String> {
+    pooling.pool(&self.embed(embedder, text)?)
+  }
+}
+
+/// How the vectors of a document's chunks are combined into one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pooling {
+  /// The average of the vectors, which represents the document as a whole.
+  #[default]
+  Mean,
+  /// The largest value of each dimension, which keeps strong signals from
+  /// any chunk.
+  Max,
+  /// The vector of the first chunk, for documents whose opening (a title or
+  /// a summary) describes them best.
+  First,
+}
+
+impl Pooling {
+  /// Combines vectors into one.
+  ///
+  /// # Returns
+  ///
+  /// An error if there are no vectors, or if they don't all have the same
+  /// number of dimensions.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::embeddings::Pooling;
+  ///
+  /// let vectors = vec![vec![1.0, 4.0], vec![3.0, 0.0]];
+  /// assert_eq!(Pooling::Mean.pool(&vectors).unwrap(), vec![2.0, 2.0]);
+  /// assert_eq!(Pooling::Max.pool(&vectors).unwrap(), vec![3.0, 4.0]);
+  /// assert_eq!(Pooling::First.pool(&vectors).unwrap(), vec![1.0, 4.0]);
+  /// ```
+  pub fn pool(&self, vectors: &[Vec<f32>]) -> Result<Vec<f32>, String> {
+    let first = vectors
+      .first()
+      .ok_or_else(|| "Cannot pool an empty list of vectors".to_string())?;
+    for vector in vectors {
+      crate::embeddings::check_dimension(vector, first.len())?;
+    }
+
+    let pooled = match self {
+      Pooling::Mean => {
+        let mut sum = vec![0.0; first.len()];
+        for vector in vectors {
+          for (total, x) in sum.iter_mut().zip(vector) {
+            *total += x;
+          }
+        }
+        sum
+          .iter()
+          .map(|total| total / vectors.len() as f32)
+          .collect()
+      }
+      Pooling::Max => {
+        let mut max = first.clone();
+        for vector in &vectors[1..] {
+          for (largest, &x) in max.iter_mut().zip(vector) {
+            *largest = largest.max(x);
+          }
+        }
+        max
+      }
+      Pooling::First => first.clone(),
+    };
+    Ok(pooled)
+  }
+}
+
+/// Returns the byte ranges of the words of a text.
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+  let mut spans = Vec::new();
+  let mut start = None;
+  for (i, c) in text.char_indices() {
+    match (c.is_whitespace(), start) {
+      (true, Some(s)) => {
+        spans.push((s, i));
+        start = None;
+      }
+      (false, None) => start = Some(i),
+      _ => {}
+    }
+  }
+  if let Some(s) = start {
+    spans.push((s, text.len()));
+  }
+  spans
+}
+
+/// Returns the byte ranges of the sentences of a text.
+fn sentence_spans(text: &str) -> Vec<(usize, usize)> {
+  let mut spans = Vec::new();
+  // The start of the current sentence, and the end of its last character.
+  let mut current: Option<(usize, usize)> = None;
+  // Whether the sentence ends with a terminator, and whether it is full-width.
+  let mut terminator: Option<bool> = None;
+  let mut newlines = 0;
+
+  for (i, c) in text.char_indices() {
+    if c.is_whitespace() {
+      if c == '\n' {
+        newlines += 1;
+      }
+      if terminator.is_some() {
+        spans.extend(current.take());
+      }
+      continue;
+    }
+
+    let closing = matches!(
+      c,
+      '"' | '\'' | ')' | ']' | '”' | '’' | '»' | '」' | '』' | '）'
+    );
+    // Full-width terminators end a sentence even without whitespace after
+    // them, and a blank line ends one without any terminator.
+    if newlines >= 2 || (terminator == Some(true) && !closing) {
+      spans.extend(current.take());
+    }
+    newlines = 0;
+
+    let end = i + c.len_utf8();
+    match &mut current {
+      Some((_, last)) => *last = end,
+      None => current = Some((i, end)),
+    }
+    terminator = match c {
+      '.' | '!' | '?' => Some(false),
+      '。' | '！' | '？' => Some(true),
+      // Closing quotes and brackets belong to the sentence they end.
+      _ if closing => terminator,
+      _ => None,
+    };
+  }
+
+  spans.extend(current);
+  spans
+}
@@ -0,0 +1,149 @@
+//! A local image embedder running the CLIP vision model with ONNX Runtime.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use fastembed::{ImageEmbedding, ImageInitOptions};
+
+pub use fastembed::ImageEmbeddingModel;
+
+use crate::embeddings::ImageEmbedder;
+
+/// An `ImageEmbedder` that runs the vision tower of CLIP (or another image
+/// model supported by `fastembed`) locally with ONNX Runtime.
+///
+/// Images are given as encoded files (PNG, JPEG, WebP, ...), and are resized
+/// and normalized as the model expects. CLIP places images and texts in the
+/// same space, so pairing this embedder with an
+/// [`OnnxTextEmbedder`](crate::embeddings::OnnxTextEmbedder) for
+/// `EmbeddingModel::ClipVitB32` enables text-to-image search.
+///
+/// As with `OnnxTextEmbedder`, the model is downloaded and cached on first
+/// use, and the `onnxruntime` shared library must be installed.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use searus::embeddings::{ClipImageEmbedder, ImageEmbedder};
+///
+/// let embedder = ClipImageEmbedder::new().unwrap();
+///
+/// let photo = std::fs::read("photo.jpg").unwrap();
+/// let vector = embedder.embed(&photo).unwrap();
+/// assert_eq!(vector.len(), embedder.dimension());
+/// ```
+pub struct ClipImageEmbedder {
+  /// The loaded model.
+  model: Mutex<ImageEmbedding>,
+  /// The length of the vectors produced by the model.
+  dimension: usize,
+  /// The number of images run through the model at once.
+  batch_size: Option<usize>,
+}
+
+impl ClipImageEmbedder {
+  /// Loads the vision model of CLIP ViT-B/32 (512 dimensions).
+  pub fn new() -> Result<Self, String> {
+    Self::builder().build()
+  }
+
+  /// Creates a builder to choose the model and how it is loaded.
+  pub fn builder() -> ClipImageEmbedderBuilder {
+    ClipImageEmbedderBuilder::new()
+  }
+
+  /// Returns the length of the vectors produced by the model.
+  pub fn dimension(&self) -> usize {
+    self.dimension
+  }
+}
+
+impl ImageEmbedder for ClipImageEmbedder {
+  fn embed(&self, image_data: &[u8]) -> Result<Vec<f32>, String> {
+    self
+      .embed_batch(&[image_data])?
+      .pop()
+      .ok_or_else(|| "Embedding model returned no vector".to_string())
+  }
+
+  /// Embeds the images in batches of the configured size.
+  fn embed_batch(&self, images: &[&[u8]]) -> Result<Vec<Vec<f32>>, String> {
+    let mut model = self
+      .model
+      .lock()
+      .map_err(|_| "Embedding model is poisoned".to_string())?;
+    model
+      .embed_bytes(images, self.batch_size)
+      .map_err(|e| format!("Failed to embed image: {}", e))
+  }
+}
+
+/// A builder for [`ClipImageEmbedder`].
+pub struct ClipImageEmbedderBuilder {
+  model: ImageEmbeddingModel,
+  cache_dir: Option<PathBuf>,
+  batch_size: Option<usize>,
+  show_download_progress: bool,
+}
+
+impl ClipImageEmbedderBuilder {
+  /// Creates a builder for CLIP ViT-B/32.
+  pub fn new() -> Self {
+    Self {
+      model: ImageEmbeddingModel::ClipVitB32,
+      cache_dir: None,
+      batch_size: None,
+      show_download_progress: false,
+    }
+  }
+
+  /// Sets the model to load.
+  pub fn model(mut self, model: ImageEmbeddingModel) -> Self {
+    self.model = model;
+    self
+  }
+
+  /// Sets the directory where models are downloaded and cached.
+  pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+    self.cache_dir = Some(cache_dir.into());
+    self
+  }
+
+  /// Sets the number of images run through the model at once (256 by default).
+  pub fn batch_size(mut self, batch_size: usize) -> Self {
+    self.batch_size = Some(batch_size.max(1));
+    self
+  }
+
+  /// Shows a progress bar while the model downloads.
+  pub fn show_download_progress(mut self, show_download_progress: bool) -> Self {
+    self.show_download_progress = show_download_progress;
+    self
+  }
+
+  /// Loads the model, downloading it first if it isn't cached.
+  pub fn build(self) -> Result<ClipImageEmbedder, String> {
+    let dimension = ImageEmbedding::get_model_info(&self.model).dim;
+
+    let mut options =
+      ImageInitOptions::new(self.model).with_show_download_progress(self.show_download_progress);
+    if let Some(cache_dir) = self.cache_dir {
+      options = options.with_cache_dir(cache_dir);
+    }
+
+    let model = ImageEmbedding::try_new(options)
+      .map_err(|e| format!("Failed to load embedding model: {}", e))?;
+
+    Ok(ClipImageEmbedder {
+      model: Mutex::new(model),
+      dimension,
+      batch_size: self.batch_size,
+    })
+  }
+}
+
+impl Default for ClipImageEmbedderBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -4,6 +4,42 @@
 //! which create a common interface for different embedding models. Embeddings
 //! are vector representations of data that capture semantic meaning, and they
 //! are the foundation of vector-based search.
+//!
+//! With the `onnx` feature, [`OnnxTextEmbedder`] runs sentence-transformer
+//! models locally. The `candle` feature provides `CandleTextEmbedder`, which
+//! does the same in pure Rust, and the `ollama` feature provides
+//! `OllamaEmbedder`, which calls a local Ollama server. With the `clip`
+//! feature, [`ClipImageEmbedder`] embeds images for image search.
+//!
+//! Documents longer than a model's input can be split with a [`Chunker`] and
+//! embedded chunk by chunk, keeping one vector per chunk or [`Pooling`] them.
+
+use crate::types::ImageData;
+
+/// Provides a local text embedder written in pure Rust with candle.
+#[cfg(feature = "candle")]
+pub mod candle;
+/// Provides chunking of long documents and pooling of their embeddings.
+pub mod chunk;
+/// Provides a local image embedder running CLIP on ONNX Runtime.
+#[cfg(feature = "clip")]
+pub mod clip;
+/// Provides a text embedder backed by a local Ollama server.
+#[cfg(feature = "ollama")]
+pub mod ollama;
+/// Provides a local text embedder running on ONNX Runtime.
+#[cfg(feature = "onnx")]
+pub mod onnx;
+
+#[cfg(feature = "candle")]
+pub use candle::{CandleDevice, CandleTextEmbedder};
+pub use chunk::{ChunkUnit, Chunker, Pooling};
+#[cfg(feature = "clip")]
+pub use clip::{ClipImageEmbedder, ImageEmbeddingModel};
+#[cfg(feature = "ollama")]
+pub use ollama::OllamaEmbedder;
+#[cfg(feature = "onnx")]
+pub use onnx::{EmbeddingModel, OnnxTextEmbedder};
 
 /// A trait for providers that can generate embeddings from text.
 ///
@@ -55,6 +91,32 @@ pub trait ImageEmbedder: Send + Sync {
   /// A `Result` containing the embedding as a `Vec<f32>` on success, or an
   /// error string on failure.
   fn embed(&self, image_data: &[u8]) -> Result<Vec<f32>, String>;
+
+  /// Generates embeddings for a batch of images.
+  ///
+  /// The default implementation calls `embed` for each image. Implementors
+  /// can override it if their model supports batching.
+  ///
+  /// # Arguments
+  ///
+  /// * `images` - The raw data of each image.
+  ///
+  /// # Returns
+  ///
+  /// A `Result` containing a vector of embeddings on success, or an error
+  /// string on failure.
+  fn embed_batch(&self, images: &[&[u8]]) -> Result<Vec<Vec<f32>>, String> {
+    images.iter().map(|image| self.embed(image)).collect()
+  }
+
+  /// Generates an embedding vector for the image of a query.
+  ///
+  /// # Arguments
+  ///
+  /// * `image` - The image, whose `bytes` hold the encoded image file.
+  fn embed_image(&self, image: &ImageData) -> Result<Vec<f32>, String> {
+    self.embed(&image.bytes)
+  }
 }
 
 /// A stub implementation of `TextEmbedder` for testing and demonstration.
@@ -107,3 +169,92 @@ impl TextEmbedder for StubTextEmbedder {
     Ok(vec)
   }
 }
+
+/// Checks that a vector has the expected number of dimensions.
+///
+/// Vectors of different lengths can't be compared meaningfully, so checking
+/// the output of an embedder before storing it turns a misconfigured model
+/// into an error instead of meaningless distances.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::embeddings::check_dimension;
+///
+/// assert!(check_dimension(&[0.1, 0.2, 0.3], 3).is_ok());
+///
+/// let error = check_dimension(&[0.1, 0.2], 3).unwrap_err();
+/// assert_eq!(error, "Expected a vector of 3 dimensions, got 2");
+/// ```
+pub fn check_dimension(vector: &[f32], dimension: usize) -> Result<(), String> {
+  if vector.len() != dimension {
+    return Err(format!(
+      "Expected a vector of {} dimensions, got {}",
+      dimension,
+      vector.len()
+    ));
+  }
+  Ok(())
+}
+
+/// Scales a vector in place to a length (L2 norm) of 1.
+///
+/// Once normalized, the Euclidean distance between two vectors ranks them the
+/// same way as their cosine similarity, and their dot product is their
+/// cosine similarity.
+///
+/// # Returns
+///
+/// An error if the vector is all zeros or has non-finite values, as it has
+/// no direction.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::embeddings::l2_normalize;
+///
+/// let mut vector = vec![3.0, 4.0];
+/// l2_normalize(&mut vector).unwrap();
+/// assert_eq!(vector, vec![0.6, 0.8]);
+///
+/// assert!(l2_normalize(&mut [0.0, 0.0]).is_err());
+/// ```
+pub fn l2_normalize(vector: &mut [f32]) -> Result<(), String> {
+  let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+  if norm == 0.0 || !norm.is_finite() {
+    return Err(format!("Cannot normalize a vector of length {}", norm));
+  }
+  for x in vector.iter_mut() {
+    *x /= norm;
+  }
+  Ok(())
+}
+
+/// Returns the cosine similarity of two vectors, from -1.0 (opposite) to 1.0
+/// (same direction).
+///
+/// # Returns
+///
+/// `None` if the vectors have different lengths, or if either is all zeros.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::embeddings::cosine_similarity;
+///
+/// assert_eq!(cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]), Some(1.0));
+/// assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]), Some(0.0));
+/// assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0, 0.0, 0.0]), None);
+/// ```
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f32> {
+  if a.len() != b.len() {
+    return None;
+  }
+  let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+  let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+  let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+  if norm_a == 0.0 || norm_b == 0.0 {
+    return None;
+  }
+  Some(dot / (norm_a * norm_b))
+}
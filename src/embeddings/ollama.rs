@@ -0,0 +1,174 @@
+//! A text embedder backed by a local Ollama server.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::embeddings::TextEmbedder;
+
+/// The body of a request to Ollama's `/api/embed` endpoint.
+#[derive(Serialize)]
+struct EmbedRequest<'a> {
+  model: &'a str,
+  input: &'a [&'a str],
+  #[serde(skip_serializing_if = "Option::is_none")]
+  dimensions: Option<usize>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  keep_alive: Option<&'a str>,
+}
+
+/// The body of a successful response from `/api/embed`.
+#[derive(Deserialize)]
+struct EmbedResponse {
+  embeddings: Vec<Vec<f32>>,
+}
+
+/// The body of an error response from Ollama.
+#[derive(Deserialize)]
+struct ErrorResponse {
+  error: String,
+}
+
+/// A `TextEmbedder` that calls the embeddings API of an
+/// [Ollama](https://ollama.com) server, so models running locally (such as
+/// `nomic-embed-text` or `mxbai-embed-large`) can be used for vector search
+/// without any cloud service.
+///
+/// The model must have been pulled on the server (`ollama pull <model>`).
+/// Batches are embedded with a single request.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use searus::embeddings::{OllamaEmbedder, TextEmbedder};
+/// use std::time::Duration;
+///
+/// let embedder = OllamaEmbedder::new("nomic-embed-text")
+///     .with_url("http://localhost:11434")
+///     .with_timeout(Duration::from_secs(10));
+///
+/// let vectors = embedder.embed_batch(&["rust search engine", "vector search"]).unwrap();
+/// ```
+pub struct OllamaEmbedder {
+  /// The HTTP client.
+  agent: ureq::Agent,
+  /// The base URL of the server.
+  url: String,
+  /// The name of the embedding model.
+  model: String,
+  /// The length vectors are truncated to, for models that support it.
+  dimensions: Option<usize>,
+  /// How long the server keeps the model loaded after a request.
+  keep_alive: Option<String>,
+}
+
+impl OllamaEmbedder {
+  /// The address of an Ollama server running on the same machine.
+  pub const DEFAULT_URL: &'static str = "http://localhost:11434";
+
+  /// Creates an embedder for a model served by a local Ollama server, with a
+  /// timeout of 60 seconds per request.
+  ///
+  /// # Arguments
+  ///
+  /// * `model` - The name of the model, such as `nomic-embed-text`.
+  pub fn new(model: impl Into<String>) -> Self {
+    Self {
+      agent: Self::agent(Duration::from_secs(60)),
+      url: Self::DEFAULT_URL.to_string(),
+      model: model.into(),
+      dimensions: None,
+      keep_alive: None,
+    }
+  }
+
+  /// Sets the base URL of the Ollama server.
+  pub fn with_url(mut self, url: impl Into<String>) -> Self {
+    self.url = url.into().trim_end_matches('/').to_string();
+    self
+  }
+
+  /// Sets the timeout of each request, which includes loading the model on
+  /// the first request.
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.agent = Self::agent(timeout);
+    self
+  }
+
+  /// Truncates vectors to a number of dimensions, for models trained to
+  /// support it (such as Matryoshka embeddings).
+  pub fn with_dimensions(mut self, dimensions: usize) -> Self {
+    self.dimensions = Some(dimensions);
+    self
+  }
+
+  /// Sets how long the server keeps the model loaded after a request, as an
+  /// Ollama duration such as `"10m"` or `"-1"` (forever).
+  pub fn with_keep_alive(mut self, keep_alive: impl Into<String>) -> Self {
+    self.keep_alive = Some(keep_alive.into());
+    self
+  }
+
+  /// Creates an HTTP client that reports error statuses as responses.
+  fn agent(timeout: Duration) -> ureq::Agent {
+    ureq::Agent::config_builder()
+      .timeout_global(Some(timeout))
+      .http_status_as_error(false)
+      .build()
+      .new_agent()
+  }
+}
+
+impl TextEmbedder for OllamaEmbedder {
+  fn embed(&self, text: &str) -> Result<Vec<f32>, String> {
+    self
+      .embed_batch(&[text])?
+      .pop()
+      .ok_or_else(|| "Ollama returned no embedding".to_string())
+  }
+
+  /// Embeds all the texts with a single request.
+  fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+    if texts.is_empty() {
+      return Ok(Vec::new());
+    }
+
+    let request = EmbedRequest {
+      model: &self.model,
+      input: texts,
+      dimensions: self.dimensions,
+      keep_alive: self.keep_alive.as_deref(),
+    };
+    let mut response = self
+      .agent
+      .post(format!("{}/api/embed", self.url))
+      .send_json(&request)
+      .map_err(|e| format!("Failed to reach Ollama at {}: {}", self.url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+      let message = response
+        .body_mut()
+        .read_json::<ErrorResponse>()
+        .map(|body| body.error)
+        .unwrap_or_else(|_| status.to_string());
+      return Err(format!(
+        "Ollama failed to embed with {}: {}",
+        self.model, message
+      ));
+    }
+
+    let body: EmbedResponse = response
+      .body_mut()
+      .read_json()
+      .map_err(|e| format!("Invalid response from Ollama: {}", e))?;
+    if body.embeddings.len() != texts.len() {
+      return Err(format!(
+        "Ollama returned {} embeddings for {} texts",
+        body.embeddings.len(),
+        texts.len()
+      ));
+    }
+    Ok(body.embeddings)
+  }
+}
@@ -0,0 +1,167 @@
+//! A local text embedder running sentence-transformer models with ONNX Runtime.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use fastembed::{TextEmbedding, TextInitOptions};
+
+pub use fastembed::EmbeddingModel;
+
+use crate::embeddings::TextEmbedder;
+
+/// A `TextEmbedder` that runs a sentence-transformer model locally with ONNX
+/// Runtime, through `fastembed`.
+///
+/// The model is downloaded from Hugging Face the first time it is used and
+/// cached on disk (in `.fastembed_cache`, `FASTEMBED_CACHE_DIR`, or the
+/// directory given to the builder), so embedding works offline afterwards.
+///
+/// ONNX Runtime itself is loaded at runtime: the `onnxruntime` shared library
+/// must be installed, and is found through the `ORT_DYLIB_PATH` environment
+/// variable or the system library path.
+///
+/// Inference needs exclusive access to the model, so concurrent calls to
+/// `embed` run one at a time. Prefer `embed_batch` for many texts.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use searus::embeddings::{EmbeddingModel, OnnxTextEmbedder, TextEmbedder};
+///
+/// let embedder = OnnxTextEmbedder::builder()
+///     .model(EmbeddingModel::BGESmallENV15)
+///     .batch_size(64)
+///     .build()
+///     .unwrap();
+///
+/// let vector = embedder.embed("rust search engine").unwrap();
+/// assert_eq!(vector.len(), embedder.dimension());
+/// ```
+pub struct OnnxTextEmbedder {
+  /// The loaded model.
+  model: Mutex<TextEmbedding>,
+  /// The length of the vectors produced by the model.
+  dimension: usize,
+  /// The number of texts run through the model at once.
+  batch_size: Option<usize>,
+}
+
+impl OnnxTextEmbedder {
+  /// Loads the default model, `all-MiniLM-L6-v2` (384 dimensions).
+  pub fn new() -> Result<Self, String> {
+    Self::builder().build()
+  }
+
+  /// Creates a builder to choose the model and how it is loaded.
+  pub fn builder() -> OnnxTextEmbedderBuilder {
+    OnnxTextEmbedderBuilder::new()
+  }
+
+  /// Returns the length of the vectors produced by the model.
+  pub fn dimension(&self) -> usize {
+    self.dimension
+  }
+}
+
+impl TextEmbedder for OnnxTextEmbedder {
+  fn embed(&self, text: &str) -> Result<Vec<f32>, String> {
+    self
+      .embed_batch(&[text])?
+      .pop()
+      .ok_or_else(|| "Embedding model returned no vector".to_string())
+  }
+
+  /// Embeds the texts in batches of the configured size.
+  fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+    let mut model = self
+      .model
+      .lock()
+      .map_err(|_| "Embedding model is poisoned".to_string())?;
+    model
+      .embed(texts, self.batch_size)
+      .map_err(|e| format!("Failed to embed text: {}", e))
+  }
+}
+
+/// A builder for [`OnnxTextEmbedder`].
+pub struct OnnxTextEmbedderBuilder {
+  model: EmbeddingModel,
+  cache_dir: Option<PathBuf>,
+  max_length: Option<usize>,
+  batch_size: Option<usize>,
+  show_download_progress: bool,
+}
+
+impl OnnxTextEmbedderBuilder {
+  /// Creates a builder for `all-MiniLM-L6-v2`.
+  pub fn new() -> Self {
+    Self {
+      model: EmbeddingModel::AllMiniLML6V2,
+      cache_dir: None,
+      max_length: None,
+      batch_size: None,
+      show_download_progress: false,
+    }
+  }
+
+  /// Sets the model to load.
+  pub fn model(mut self, model: EmbeddingModel) -> Self {
+    self.model = model;
+    self
+  }
+
+  /// Sets the directory where models are downloaded and cached.
+  pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+    self.cache_dir = Some(cache_dir.into());
+    self
+  }
+
+  /// Sets the maximum number of tokens per text. Longer texts are truncated.
+  pub fn max_length(mut self, max_length: usize) -> Self {
+    self.max_length = Some(max_length);
+    self
+  }
+
+  /// Sets the number of texts run through the model at once (256 by default).
+  pub fn batch_size(mut self, batch_size: usize) -> Self {
+    self.batch_size = Some(batch_size.max(1));
+    self
+  }
+
+  /// Shows a progress bar while the model downloads.
+  pub fn show_download_progress(mut self, show_download_progress: bool) -> Self {
+    self.show_download_progress = show_download_progress;
+    self
+  }
+
+  /// Loads the model, downloading it first if it isn't cached.
+  pub fn build(self) -> Result<OnnxTextEmbedder, String> {
+    let dimension = TextEmbedding::get_model_info(&self.model)
+      .map_err(|e| format!("Unsupported embedding model: {}", e))?
+      .dim;
+
+    let mut options =
+      TextInitOptions::new(self.model).with_show_download_progress(self.show_download_progress);
+    if let Some(cache_dir) = self.cache_dir {
+      options = options.with_cache_dir(cache_dir);
+    }
+    if let Some(max_length) = self.max_length {
+      options = options.with_max_length(max_length);
+    }
+
+    let model = TextEmbedding::try_new(options)
+      .map_err(|e| format!("Failed to load embedding model: {}", e))?;
+
+    Ok(OnnxTextEmbedder {
+      model: Mutex::new(model),
+      dimension,
+      batch_size: self.batch_size,
+    })
+  }
+}
+
+impl Default for OnnxTextEmbedderBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
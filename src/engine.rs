@@ -1,13 +1,29 @@
 //! The main search engine that coordinates multiple searchers.
 
+use crate::aggregations::Aggregations;
 use crate::context::SearchContext;
-use crate::extension::SearusExtension;
-use crate::searcher::Searcher;
-use crate::types::{Query, Searchable, SearcherKind, SearusMatch};
+use crate::document::to_document;
+use crate::embeddings::TextEmbedder;
+use crate::explain::Explanation;
+use crate::extension::{SearchState, SearusExtension};
+use crate::filter::select_fields;
+use crate::history::{QuerySuggestion, SearchHistory, SearchRecord};
+use crate::scoring::ScoreModifier;
+use crate::searcher::{Searcher, SearcherConfig, TopMatches};
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+use crate::spelling::SpellCorrector;
+use crate::types::{
+  Principal, Query, SearchOptions, SearchResponse, Searchable, SearcherKind, SearusMatch,
+};
 use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
+#[cfg(feature = "parallel")]
+use rayon::{ThreadPool, ThreadPoolBuilder};
+#[cfg(feature = "parallel")]
+use std::sync::Arc;
 
 /// The main search engine that coordinates multiple searchers.
 ///
@@ -54,6 +70,54 @@ pub struct SearusEngine<T> {
   normalization: NormalizationMethod,
   /// The collection of registered extensions that hook into the search lifecycle.
   extensions: Vec<Box<dyn SearusExtension<T>>>,
+  /// Modifiers applied to the merged score of every result.
+  score_modifiers: Vec<Box<dyn ScoreModifier<T>>>,
+  /// The weights of the kinds of searchers a query doesn't weight itself.
+  default_weights: HashMap<SearcherKind, f32>,
+  /// Embeds the query text for vector searchers when no vector is given.
+  embedder: Option<Box<dyn TextEmbedder>>,
+  /// Records the text of every search, to suggest past queries.
+  history: Option<SearchHistory>,
+  /// The most matches kept from each searcher, whatever the query asks for.
+  max_matches_per_searcher: Option<usize>,
+  /// The most merged matches kept, whatever the query asks for.
+  max_matches: Option<usize>,
+  /// The thread pool parallel searches run in, instead of the global one.
+  #[cfg(feature = "parallel")]
+  thread_pool: Option<Arc<ThreadPool>>,
+  /// Searches over fewer items than this run sequentially.
+  parallel_threshold: usize,
+  /// The fewest items matched by each parallel task of a searcher.
+  parallel_chunk_size: usize,
+  /// The corrector used to produce "did you mean" suggestions.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  spell_corrector: Option<SpellCorrector>,
+  /// Suggestions are produced when a query matches fewer items than this.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  suggestion_threshold: usize,
+  /// The maximum number of alternative queries to suggest.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  max_suggestions: usize,
+}
+
+/// The state precomputed by [`SearusEngine::warm_up`] for a slice of items.
+///
+/// The slice stays borrowed while the `WarmUp` lives, and the searchers drop
+/// the state along with it.
+#[must_use = "the precomputed state is dropped along with the `WarmUp`"]
+pub struct WarmUp<'a, T: Searchable> {
+  /// The engine whose searchers were warmed up.
+  engine: &'a SearusEngine<T>,
+  /// The items the searchers were warmed up with.
+  items: &'a [T],
+}
+
+impl<T: Searchable> Drop for WarmUp<'_, T> {
+  fn drop(&mut self) {
+    for searcher in &self.engine.searchers {
+      searcher.cool_down(self.items);
+    }
+  }
 }
 
 impl<T: Searchable> SearusEngine<T> {
@@ -66,6 +130,144 @@ impl<T: Searchable> SearusEngine<T> {
     SearusEngineBuilder::new()
   }
 
+  /// Precomputes what the searchers need to search a slice of items, so the
+  /// first searches over it are as fast as later ones.
+  ///
+  /// Each searcher computes its own state: [`SemanticSearch`] analyzes the
+  /// fields of every item and computes the corpus statistics, and
+  /// [`TaggedSearch`] extracts the tags of every item, indexes the items by
+  /// tag, and expands the tags of its Tag Relationship Tree. Searches over
+  /// the same slice then reuse that state instead of computing it again.
+  ///
+  /// The state is tied to the slice, not to its contents: call `warm_up`
+  /// again after changing the items, and search the same slice (not a copy
+  /// of it). Engines with extensions search a copy of the items that the
+  /// `before_items` hooks may change, so their searchers don't use the
+  /// state. A searcher keeps the state of the last slice it was warmed up
+  /// with.
+  ///
+  /// The state lasts as long as the returned [`WarmUp`], which borrows the
+  /// slice so that it can't be freed, and another slice allocated in its
+  /// place, while the state is in use. Dropping the `WarmUp` drops the
+  /// state.
+  ///
+  /// [`SemanticSearch`]: crate::searchers::SemanticSearch
+  /// [`TaggedSearch`]: crate::searchers::TaggedSearch
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Post { title: String, tags: Vec<String> }
+  ///
+  /// let posts = vec![
+  ///     Post { title: "Rust ownership".into(), tags: vec!["rust".into()] },
+  ///     Post { title: "Python typing".into(), tags: vec!["python".into()] },
+  /// ];
+  ///
+  /// let rules = SemanticRules::builder().field("title", FieldRule::bm25()).build();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(SemanticSearch::new(rules)))
+  ///     .with(Box::new(TaggedSearch::new()))
+  ///     .build();
+  ///
+  /// let _warm = engine.warm_up(&posts);
+  ///
+  /// let query = Query::builder().text("ownership").tags(vec!["rust".into()]).build();
+  /// assert_eq!(engine.search(&posts, &query)[0].item.title, "Rust ownership");
+  /// ```
+  pub fn warm_up<'a>(&'a self, items: &'a [T]) -> WarmUp<'a, T> {
+    self.precompute(items);
+    WarmUp {
+      engine: self,
+      items,
+    }
+  }
+
+  /// Warms the searchers up with a slice of items, which the caller keeps
+  /// alive for as long as the state is used.
+  pub(crate) fn precompute(&self, items: &[T]) {
+    #[cfg(feature = "parallel")]
+    if self.is_parallel(items) {
+      return self.install(|| {
+        self
+          .searchers
+          .par_iter()
+          .for_each(|searcher| searcher.warm_up(items))
+      });
+    }
+
+    for searcher in &self.searchers {
+      searcher.warm_up(items);
+    }
+  }
+
+  /// Returns `true` if searches over `items` run in parallel: with the
+  /// `parallel` feature, over at least the parallel threshold of items.
+  fn is_parallel(&self, items: &[T]) -> bool {
+    cfg!(feature = "parallel") && items.len() >= self.parallel_threshold
+  }
+
+  /// Runs `f` in the thread pool of the engine, if it has one, so the
+  /// parallel work of the searchers is spread over its threads.
+  #[cfg(feature = "parallel")]
+  fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+    match &self.thread_pool {
+      Some(pool) => pool.install(f),
+      None => f(),
+    }
+  }
+
+  #[cfg(not(feature = "parallel"))]
+  fn install<R>(&self, f: impl FnOnce() -> R) -> R {
+    f()
+  }
+
+  /// Returns the search history of the engine, if it has one.
+  pub fn history(&self) -> Option<&SearchHistory> {
+    self.history.as_ref()
+  }
+
+  /// Returns up to `limit` past queries starting with a prefix, the most
+  /// frequent and recent first, for "recent" and "popular" searches.
+  ///
+  /// Only successful searches are suggested (see [`SearchHistory`]), and
+  /// engines without a history suggest nothing.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::history::SearchHistory;
+  /// use searus::prelude::*;
+  ///
+  /// let posts = vec![
+  ///     serde_json::json!({ "title": "Rust ownership" }),
+  ///     serde_json::json!({ "title": "Rust macros" }),
+  /// ];
+  /// let rules = SemanticRules::builder().field("title", FieldRule::bm25()).build();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(SemanticSearch::new(rules)))
+  ///     .history(SearchHistory::new())
+  ///     .build();
+  ///
+  /// for text in ["rust macros", "rust", "rust macros", "ruby"] {
+  ///     engine.search(&posts, &Query::builder().text(text).build());
+  /// }
+  ///
+  /// let suggestions = engine.suggest_queries("ru", 5);
+  /// let texts: Vec<&str> = suggestions.iter().map(|s| s.text.as_str()).collect();
+  /// assert_eq!(texts, ["rust macros", "rust"]);
+  /// ```
+  pub fn suggest_queries(&self, prefix: &str, limit: usize) -> Vec<QuerySuggestion> {
+    self
+      .history
+      .as_ref()
+      .map(|history| history.suggest_queries(prefix, limit))
+      .unwrap_or_default()
+  }
+
   /// Searches for items using all registered searchers and merges the results.
   ///
   /// The search process follows a well-defined lifecycle, with hooks for `SearusExtension`
@@ -76,24 +278,32 @@ impl<T: Searchable> SearusEngine<T> {
   /// 1.  **Query Initialization**: The initial `Query` is received.
   /// 2.  **`before_query` Hook**: Extensions can modify the `Query` before it's sent to any searcher.
   ///     For example, an extension could rewrite query text (e.g., "ml" -> "machine learning").
+  ///     Then, if the engine has an embedder and a vector searcher but the query has no vector,
+  ///     the query text is embedded into `query.vector`.
   /// 3.  **`before_items` Hook**: Extensions can modify the collection of items to be searched.
   ///     This allows for dynamically adding or removing items from the search context.
   /// 4.  **Parallel Search Execution**: The query is dispatched to all registered `Searcher` instances.
-  ///     If the `parallel` feature is enabled, this happens concurrently.
+  ///     If the `parallel` feature is enabled, this happens concurrently. Before each searcher runs,
+  ///     the **`before_searcher` Hook** lets extensions adjust its `SearcherConfig` for this search
+  ///     (e.g., its query, a minimum score, or whether it runs at all). Each searcher keeps at most
+  ///     `max_matches_per_searcher` matches, the best-scoring ones.
   /// 5.  **`after_searcher` Hook**: After each searcher returns its results, extensions can modify
-  ///     the list of matches (e.g., boosting scores, filtering).
+  ///     the list of matches (e.g., boosting scores, filtering), knowing the kind of the searcher.
   /// 6.  **`before_merge` Hook**: Extensions can inspect or modify the collected results from all
   ///     searchers before they are normalized and merged.
   /// 7.  **Score Normalization**: Scores from each searcher are normalized to a common scale (e.g., 0.0 to 1.0)
   ///     using the configured `NormalizationMethod`.
   /// 8.  **Result Merging**: The normalized results are merged. If multiple searchers match the same
-  ///     item, their scores are combined using a weighted sum based on `SearchOptions`.
-  /// 9.  **`after_merge` Hook**: Extensions can modify the final, merged list of results before sorting.
-  /// 10. **Sorting**: The merged list is sorted by score in descending order.
-  /// 11. **`before_limit` Hook**: Extensions can access the sorted list before pagination is applied.
-  /// 12. **Pagination**: `skip` and `limit` from `SearchOptions` are applied.
-  /// 13. **`after_limit` Hook**: The final, paginated list of results can be modified by extensions.
-  /// 14. **Return**: The final `Vec<SearusMatch<T>>` is returned.
+  ///     item, their scores are combined using a weighted sum based on `SearchOptions`, falling back
+  ///     to the engine's default weights.
+  /// 9.  **Score Modifiers**: Registered `ScoreModifier`s adjust each merged score (e.g., recency decay).
+  /// 10. **`after_merge` Hook**: Extensions can modify the final, merged list of results before sorting.
+  ///     Then, at most `max_matches` of the merged results are kept, the best-scoring ones.
+  /// 11. **Sorting**: The merged list is sorted by score in descending order.
+  /// 12. **`before_limit` Hook**: Extensions can access the sorted list before pagination is applied.
+  /// 13. **Pagination**: `skip` and `limit` from `SearchOptions` are applied.
+  /// 14. **`after_limit` Hook**: The final, paginated list of results can be modified by extensions.
+  /// 15. **Return**: The final `Vec<SearusMatch<T>>` is returned.
   ///
   /// # Arguments
   ///
@@ -129,114 +339,592 @@ impl<T: Searchable> SearusEngine<T> {
   /// ```
   pub fn search(&self, items: &[T], query: &Query) -> Vec<SearusMatch<T>>
   where
-    T: Clone,
+    T: Clone + serde::Serialize,
   {
+    self
+      .respond(items, query, None, &mut SearchState::new(), |_, _| {
+        Aggregations::default()
+      })
+      .results
+  }
+
+  /// Searches for items, passing the given state to every extension hook.
+  ///
+  /// This runs the same lifecycle as [`search`](Self::search), but lets the
+  /// caller seed the [`SearchState`] shared by the extension hooks, and read
+  /// what the extensions recorded in it afterwards.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use searus::prelude::*;
+  /// #[derive(Default)]
+  /// struct SearcherRuns(usize);
+  ///
+  /// struct CountRuns;
+  ///
+  /// impl SearusExtension<String> for CountRuns {
+  ///     fn after_searcher(
+  ///         &self,
+  ///         _query: &Query,
+  ///         _kind: SearcherKind,
+  ///         _results: &mut Vec<SearusMatch<String>>,
+  ///         state: &mut SearchState,
+  ///     ) {
+  ///         state.get_or_default::<SearcherRuns>().0 += 1;
+  ///     }
+  /// }
+  ///
+  /// let rules = SemanticRules::builder().field("", FieldRule::tokenized()).build();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(SemanticSearch::new(rules)))
+  ///     .with_extension(Box::new(CountRuns))
+  ///     .build();
+  ///
+  /// let mut state = SearchState::new();
+  /// let items = vec!["rust".to_string()];
+  /// engine.search_with_state(&items, &Query::builder().text("rust").build(), &mut state);
+  /// assert_eq!(state.get::<SearcherRuns>().unwrap().0, 1);
+  /// ```
+  pub fn search_with_state(
+    &self,
+    items: &[T],
+    query: &Query,
+    state: &mut SearchState,
+  ) -> Vec<SearusMatch<T>>
+  where
+    T: Clone + serde::Serialize,
+  {
+    self
+      .respond(items, query, None, state, |_, _| Aggregations::default())
+      .results
+  }
+
+  /// Searches for items and returns the results along with response metadata.
+  ///
+  /// This runs the same lifecycle as [`search`](Self::search), but wraps the
+  /// paginated results in a [`SearchResponse`] that also reports the total
+  /// number of matches before pagination, the aggregations requested in the
+  /// `SearchOptions` (such as facets) and, if a spell corrector is
+  /// configured and the query matched fewer items than the suggestion
+  /// threshold, a corrected "did you mean" query.
+  ///
+  /// Aggregations are computed over all matches, after the `before_limit`
+  /// hook and before pagination.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use searus::prelude::*;
+  /// # use searus::searchers::SemanticSearch;
+  /// use searus::spelling::SpellCorrector;
+  ///
+  /// # #[derive(Debug, Clone, serde::Serialize)]
+  /// # struct Product { name: String }
+  /// let products = vec![Product { name: "Wireless keyboard".into() }];
+  ///
+  /// let rules = SemanticRules::builder().field("name", FieldRule::tokenized()).build();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(SemanticSearch::new(rules)))
+  ///     .spell_corrector(SpellCorrector::from_corpus(&products, &["name"]))
+  ///     .build();
+  ///
+  /// let response = engine.search_response(&products, &Query::builder().text("keybaord").build());
+  ///
+  /// assert!(response.results.is_empty());
+  /// assert_eq!(response.suggestion.as_deref(), Some("keyboard"));
+  /// assert_eq!(response.suggestions, vec!["keyboard"]);
+  /// ```
+  ///
+  /// Facets count the distinct values of fields among the matches:
+  ///
+  /// ```rust
+  /// # use searus::prelude::*;
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Product { name: String, category: String }
+  ///
+  /// let products = vec![
+  ///     Product { name: "Phone".into(), category: "Electronics".into() },
+  ///     Product { name: "Phone charger".into(), category: "Electronics".into() },
+  ///     Product { name: "Phone armband".into(), category: "Sports".into() },
+  /// ];
+  ///
+  /// let rules = SemanticRules::builder().field("name", FieldRule::tokenized()).build();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(SemanticSearch::new(rules)))
+  ///     .build();
+  ///
+  /// let query = Query::builder()
+  ///     .text("phone")
+  ///     .options(SearchOptions::default().limit(1).facets(["category"]))
+  ///     .build();
+  ///
+  /// let response = engine.search_response(&products, &query);
+  /// let categories = &response.facets["category"];
+  ///
+  /// assert_eq!(response.results.len(), 1);
+  /// assert_eq!(categories[0], FacetCount { value: "Electronics".into(), count: 2 });
+  /// assert_eq!(categories[1], FacetCount { value: "Sports".into(), count: 1 });
+  /// ```
+  pub fn search_response(&self, items: &[T], query: &Query) -> SearchResponse<T>
+  where
+    T: Clone + serde::Serialize,
+  {
+    self.respond(
+      items,
+      query,
+      None,
+      &mut SearchState::new(),
+      |query, matches| Aggregations::compute(&query.options, matches),
+    )
+  }
+
+  /// Searches for items and returns only the fields selected in the query.
+  ///
+  /// The fields listed in [`SearchOptions::select`](crate::types::SearchOptions::select)
+  /// are copied out of each paginated result into a new item of type `P`,
+  /// which can be a `serde_json::Value` or a struct with a subset of the
+  /// item's fields. This keeps response payloads small for large documents.
+  /// If no fields are selected, whole items are converted.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if a projected item cannot be deserialized into `P`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use searus::prelude::*;
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Article { id: u32, title: String, body: String }
+  ///
+  /// #[derive(Debug, Clone, serde::Deserialize)]
+  /// struct Summary { id: u32, title: String }
+  ///
+  /// let articles = vec![Article { id: 7, title: "Rust".into(), body: "A long body...".into() }];
+  /// let rules = SemanticRules::builder().field("title", FieldRule::tokenized()).build();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(SemanticSearch::new(rules)))
+  ///     .build();
+  ///
+  /// let query = Query::builder()
+  ///     .text("rust")
+  ///     .options(SearchOptions::default().select(["id", "title"]))
+  ///     .build();
+  ///
+  /// let summaries = engine.search_projected::<Summary>(&articles, &query).unwrap();
+  /// assert_eq!(summaries[0].item.id, 7);
+  ///
+  /// let values = engine.search_projected::<serde_json::Value>(&articles, &query).unwrap();
+  /// assert_eq!(values[0].item, serde_json::json!({ "id": 7, "title": "Rust" }));
+  /// ```
+  pub fn search_projected<P>(
+    &self,
+    items: &[T],
+    query: &Query,
+  ) -> Result<Vec<SearusMatch<P>>, String>
+  where
+    T: Clone + serde::Serialize,
+    P: Searchable + serde::de::DeserializeOwned,
+  {
+    let fields = &query.options.select;
+
+    self
+      .search(items, query)
+      .into_iter()
+      .map(|m| {
+        let value = to_document(&m.item).map_err(|e| e.to_string())?;
+        let item = if fields.is_empty() {
+          P::deserialize(&*value)
+        } else {
+          P::deserialize(&select_fields(&value, fields))
+        }
+        .map_err(|e| e.to_string())?;
+        Ok(m.map_item(|_| item))
+      })
+      .collect()
+  }
+
+  /// Searches for items, applying an additional score modifier to this search only.
+  ///
+  /// The modifier runs after the engine's own score modifiers, so it can be
+  /// used for per-request ranking rules (e.g., boosting items owned by the
+  /// current user) without rebuilding the engine. Any `Fn(&T, f32) -> f32`
+  /// closure can be used as a modifier.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use searus::prelude::*;
+  /// # use searus::searchers::SemanticSearch;
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Product { name: String, sponsored: bool }
+  ///
+  /// let products = vec![
+  ///     Product { name: "Phone".into(), sponsored: false },
+  ///     Product { name: "Phone case".into(), sponsored: true },
+  /// ];
+  ///
+  /// let rules = SemanticRules::builder().field("name", FieldRule::tokenized()).build();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(SemanticSearch::new(rules)))
+  ///     .build();
+  ///
+  /// let query = Query::builder().text("phone").build();
+  /// let pin_sponsored = |product: &Product, score: f32| {
+  ///     if product.sponsored { score + 10.0 } else { score }
+  /// };
+  ///
+  /// let results = engine.search_with_modifier(&products, &query, &pin_sponsored);
+  /// assert_eq!(results[0].item.name, "Phone case");
+  /// ```
+  pub fn search_with_modifier(
+    &self,
+    items: &[T],
+    query: &Query,
+    modifier: &dyn ScoreModifier<T>,
+  ) -> Vec<SearusMatch<T>>
+  where
+    T: Clone + serde::Serialize,
+  {
+    self
+      .respond(
+        items,
+        query,
+        Some(modifier),
+        &mut SearchState::new(),
+        |_, _| Aggregations::default(),
+      )
+      .results
+  }
+
+  /// Returns `true` if searches on behalf of `principal` may return the item.
+  ///
+  /// The item is checked against the filters that the `before_query` hooks
+  /// add to a query of the principal, such as the security filter of an
+  /// [`AccessControlExtension`](crate::extensions::AccessControlExtension),
+  /// so that items can be fetched by id under the same policy as searches.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::extensions::AccessControlExtension;
+  /// use serde_json::json;
+  ///
+  /// let engine: SearusEngine<serde_json::Value> = SearusEngine::builder()
+  ///     .with_extension(Box::new(
+  ///         AccessControlExtension::owner_or_public("owner", "visibility", "public"),
+  ///     ))
+  ///     .build();
+  ///
+  /// let notes = json!({ "owner": "alice", "visibility": "private" });
+  /// assert!(engine.is_visible(&notes, Some(&Principal::new("alice"))));
+  /// assert!(!engine.is_visible(&notes, None));
+  /// ```
+  pub fn is_visible(&self, item: &T, principal: Option<&Principal>) -> bool
+  where
+    T: serde::Serialize,
+  {
+    let mut query = Query {
+      principal: principal.cloned(),
+      ..Query::default()
+    };
+    let mut state = SearchState::new();
+    for ext in &self.extensions {
+      ext.before_query(&mut query, &mut state);
+    }
+    query
+      .filters
+      .as_ref()
+      .is_none_or(|filters| filters.evaluate(item))
+  }
+
+  /// Runs the full search lifecycle, with an optional per-search score modifier.
+  ///
+  /// `state` is passed to every extension hook, and `aggregate` computes the
+  /// aggregations over all matches before pagination.
+  fn respond<A>(
+    &self,
+    items: &[T],
+    query: &Query,
+    modifier: Option<&dyn ScoreModifier<T>>,
+    state: &mut SearchState,
+    aggregate: A,
+  ) -> SearchResponse<T>
+  where
+    T: Clone + serde::Serialize,
+    A: FnOnce(&Query, &[SearusMatch<T>]) -> Aggregations,
+  {
+    let original = query;
+
     // Clone query to allow modification by extensions
     let mut query = query.clone();
 
     // Hook: before_query
     for ext in &self.extensions {
-      ext.before_query(&mut query);
+      ext.before_query(&mut query, state);
+    }
+
+    if query.vector.is_none() {
+      query.vector = self.embed_text(&query);
     }
 
+    // The engine's budgets cap the query's, however the hooks changed them.
+    let options = &mut query.options;
+    options.max_matches_per_searcher = min_budget(
+      options.max_matches_per_searcher,
+      self.max_matches_per_searcher,
+    );
+    options.max_matches = min_budget(options.max_matches, self.max_matches);
+
     // Prepare items, potentially modified by extensions
-    let mut items_vec = if !self.extensions.is_empty() {
-      items.to_vec()
-    } else {
-      Vec::new()
-    };
+    let mut items_vec = Vec::new();
 
     // If we have extensions, populate items_vec and run hooks
     let items_slice = if !self.extensions.is_empty() {
       items_vec.extend_from_slice(items);
       for ext in &self.extensions {
-        ext.before_items(&query, &mut items_vec);
+        ext.before_items(&query, &mut items_vec, state);
       }
       &items_vec[..]
     } else {
       items
     };
 
+    let (mut merged, truncated) = self.install(|| self.rank(items_slice, &query, modifier, state));
+    let total_matches = merged.len();
+
+    if let (Some(history), Some(text)) = (&self.history, &original.text) {
+      history.record(SearchRecord::new(text.clone(), total_matches));
+    }
+
+    // Hook: before_limit
+    for ext in &self.extensions {
+      ext.before_limit(&query, &mut merged, state);
+    }
+
+    let aggregations = aggregate(&query, &merged);
+
+    // Apply pagination
+    let skip = query.options.skip;
+    let limit = query.options.limit;
+
+    let mut final_results: Vec<SearusMatch<T>> =
+      merged.into_iter().skip(skip).take(limit).collect();
+
+    // Hook: after_limit
+    for ext in &self.extensions {
+      ext.after_limit(&query, &mut final_results, state);
+    }
+
+    let suggestions = self.suggest(original, total_matches);
+
+    SearchResponse {
+      results: final_results,
+      total_matches,
+      truncated,
+      suggestion: suggestions.first().cloned(),
+      suggestions,
+      facets: aggregations.facets,
+      histograms: aggregations.histograms,
+      ranges: aggregations.ranges,
+      stats: aggregations.stats,
+    }
+  }
+
+  /// Runs all searchers and returns the merged results sorted by score, and
+  /// whether matches were dropped to stay within the budgets of the query.
+  ///
+  /// This covers the lifecycle from searcher execution up to (and including)
+  /// sorting, before any pagination is applied.
+  fn rank(
+    &self,
+    items: &[T],
+    query: &Query,
+    modifier: Option<&dyn ScoreModifier<T>>,
+    state: &mut SearchState,
+  ) -> (Vec<SearusMatch<T>>, bool)
+  where
+    T: Clone + serde::Serialize,
+  {
     if self.searchers.is_empty() {
-      return Vec::new();
+      return (Vec::new(), false);
     }
 
-    let context = SearchContext::new(items_slice);
+    let context = SearchContext::new(items)
+      .with_parallel(self.is_parallel(items))
+      .with_chunk_size(self.parallel_chunk_size);
+    // Searchers may run in parallel, so their hooks take turns with the state.
+    let shared = Mutex::new(&mut *state);
 
     // Collect results from all searchers
+    let run = |searcher: &dyn Searcher<T>| self.run_searcher(searcher, &context, query, &shared);
     #[cfg(feature = "parallel")]
-    let mut all_results: Vec<(SearcherKind, Vec<SearusMatch<T>>)> = self
-      .searchers
-      .par_iter()
-      .map(|searcher| {
-        let mut results = searcher.search(&context, &query);
-        for ext in &self.extensions {
-          ext.after_searcher(&query, &mut results);
-        }
-        (searcher.kind(), results)
-      })
-      .filter(|(_, results)| !results.is_empty())
-      .collect();
-
+    let mut all_results: Vec<(SearcherKind, Vec<SearusMatch<T>>)> = if context.is_parallel() {
+      self.searchers.par_iter().map(|s| run(s.as_ref())).collect()
+    } else {
+      self.searchers.iter().map(|s| run(s.as_ref())).collect()
+    };
     #[cfg(not(feature = "parallel"))]
-    let mut all_results: Vec<(SearcherKind, Vec<SearusMatch<T>>)> = self
-      .searchers
-      .iter()
-      .map(|searcher| {
-        let mut results = searcher.search(&context, &query);
-        for ext in &self.extensions {
-          ext.after_searcher(&query, &mut results);
-        }
-        (searcher.kind(), results)
-      })
-      .filter(|(_, results)| !results.is_empty())
-      .collect();
+    let mut all_results: Vec<(SearcherKind, Vec<SearusMatch<T>>)> =
+      self.searchers.iter().map(|s| run(s.as_ref())).collect();
+    all_results.retain(|(_, results)| !results.is_empty());
 
+    let mut truncated = context.is_truncated();
     if all_results.is_empty() {
-      return Vec::new();
+      return (Vec::new(), truncated);
     }
 
     // Hook: before_merge
     for ext in &self.extensions {
-      ext.before_merge(&query, &mut all_results);
+      ext.before_merge(query, &mut all_results, state);
     }
 
     // Normalize scores for each searcher's results
-    let normalized_results = self.normalize_results(all_results);
+    let normalized_results =
+      self.normalize_results(all_results, query.options.explain, context.is_parallel());
 
     // Merge and rank results
-    let mut merged = self.merge_results(normalized_results, &query);
+    let mut merged = self.merge_results(normalized_results, query);
+
+    // Every match passes the filters of the query, such as the security
+    // filter of an `AccessControlExtension`, even from searchers that don't
+    // apply them or that a `before_searcher` hook gave other filters.
+    if query.filters.is_some() {
+      merged.retain(|m| context.matches_filters(m.id, query.filters.as_ref()));
+    }
+
+    // Apply score modifiers to the merged scores
+    let modifiers = self
+      .score_modifiers
+      .iter()
+      .map(|modifier| modifier.as_ref())
+      .chain(modifier);
+    for modifier in modifiers {
+      for m in &mut merged {
+        let score = modifier.modify(&m.item, m.score);
+        if score != m.score {
+          if let Some(explanation) = m.explanation.take() {
+            m.explanation =
+              Some(Explanation::new(score, "adjusted by score modifier:").with_detail(explanation));
+          }
+        }
+        m.score = score;
+      }
+    }
 
     // Hook: after_merge
     for ext in &self.extensions {
-      ext.after_merge(&query, &mut merged);
+      ext.after_merge(query, &mut merged, state);
+    }
+
+    // Keep the best matches within the total budget
+    if let Some(max_matches) = query.options.max_matches {
+      if merged.len() > max_matches {
+        let mut top = TopMatches::new(Some(max_matches));
+        top.extend(merged);
+        merged = top.into_vec();
+        truncated = true;
+      }
     }
 
     // Sort before applying limit
     merged.sort_by(|a, b| {
-        b.score
-            .partial_cmp(&a.score)
-            .unwrap_or(std::cmp::Ordering::Equal)
+      b.score
+        .partial_cmp(&a.score)
+        .unwrap_or(std::cmp::Ordering::Equal)
     });
 
-    // Hook: before_limit
-    for ext in &self.extensions {
-      ext.before_limit(&query, &mut merged);
+    (merged, truncated)
+  }
+
+  /// Runs one searcher, as configured by the `before_searcher` hooks, and
+  /// passes its results through the `after_searcher` hooks.
+  ///
+  /// The state is locked while the hooks run, not while the searcher does.
+  fn run_searcher(
+    &self,
+    searcher: &dyn Searcher<T>,
+    context: &SearchContext<T>,
+    query: &Query,
+    state: &Mutex<&mut SearchState>,
+  ) -> (SearcherKind, Vec<SearusMatch<T>>) {
+    let kind = searcher.kind();
+    // Searchers that don't collect their matches with `SearchContext::scan`
+    // are held to their budget here.
+    let limit = query.options.max_matches_per_searcher;
+    if self.extensions.is_empty() {
+      let results = searcher.search(context, query);
+      return (kind, context.limit_matches(results, limit));
     }
 
-    // Apply pagination
-    let skip = query.options.skip;
-    let limit = query.options.limit;
+    // Hook: before_searcher
+    let mut config = SearcherConfig::new(kind, query.clone());
+    {
+      let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+      for ext in &self.extensions {
+        ext.before_searcher(query, &mut config, &mut state);
+      }
+    }
+    if !config.enabled {
+      return (kind, Vec::new());
+    }
 
-    let mut final_results: Vec<SearusMatch<T>> =
-      merged.into_iter().skip(skip).take(limit).collect();
+    let mut results = searcher.search(context, &config.query);
+    if let Some(min_score) = config.min_score {
+      results.retain(|m| m.score >= min_score);
+    }
+    let mut results = context.limit_matches(results, limit);
 
-    // Hook: after_limit
+    // Hook: after_searcher
+    let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
     for ext in &self.extensions {
-      ext.after_limit(&query, &mut final_results);
+      ext.after_searcher(&config.query, kind, &mut results, &mut state);
+    }
+    (kind, results)
+  }
+
+  /// Embeds the query text if a vector searcher needs it.
+  ///
+  /// Texts that can't be embedded leave the query without a vector, so that
+  /// the other searchers still run.
+  fn embed_text(&self, query: &Query) -> Option<Vec<f32>> {
+    let embedder = self.embedder.as_ref()?;
+    let text = query
+      .text
+      .as_deref()
+      .filter(|text| !text.trim().is_empty())?;
+    if !self
+      .searchers
+      .iter()
+      .any(|searcher| searcher.kind() == SearcherKind::Vector)
+    {
+      return None;
+    }
+    embedder.embed(text).ok()
+  }
+
+  /// Produces corrected queries when the original query matched too few items.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  fn suggest(&self, query: &Query, total_matches: usize) -> Vec<String> {
+    let (Some(corrector), Some(text)) = (&self.spell_corrector, &query.text) else {
+      return Vec::new();
+    };
+    if total_matches >= self.suggestion_threshold {
+      return Vec::new();
     }
 
-    final_results
+    corrector
+      .suggest_text(text, 2, self.max_suggestions)
+      .into_iter()
+      .map(|suggestion| suggestion.text)
+      .collect()
+  }
+
+  #[cfg(not(any(feature = "semantic", feature = "fuzzy")))]
+  fn suggest(&self, _query: &Query, _total_matches: usize) -> Vec<String> {
+    Vec::new()
   }
 
   /// Normalizes the scores from each searcher to a common scale.
@@ -247,50 +935,108 @@ impl<T: Searchable> SearusEngine<T> {
   fn normalize_results(
     &self,
     results: Vec<(SearcherKind, Vec<SearusMatch<T>>)>,
+    explain: bool,
+    parallel: bool,
   ) -> Vec<(SearcherKind, Vec<SearusMatch<T>>)> {
     // OPTIMIZATION: Normalize each searcher's results in parallel
     #[cfg(feature = "parallel")]
-    let iter = results.into_par_iter();
+    if parallel {
+      return results
+        .into_par_iter()
+        .map(|(kind, matches)| self.normalize_matches(kind, matches, explain))
+        .collect();
+    }
     #[cfg(not(feature = "parallel"))]
-    let iter = results.into_iter();
+    let _ = parallel;
 
-    iter
-      .map(|(kind, mut matches)| {
-        if matches.is_empty() {
-          return (kind, matches);
-        }
+    results
+      .into_iter()
+      .map(|(kind, matches)| self.normalize_matches(kind, matches, explain))
+      .collect()
+  }
+
+  /// Normalizes the scores from one searcher.
+  fn normalize_matches(
+    &self,
+    kind: SearcherKind,
+    mut matches: Vec<SearusMatch<T>>,
+    explain: bool,
+  ) -> (SearcherKind, Vec<SearusMatch<T>>) {
+    if matches.is_empty() {
+      return (kind, matches);
+    }
+
+    // Find min and max scores
+    let scores: Vec<f32> = matches.iter().map(|m| m.score).collect();
+    let min_score = scores.iter().copied().fold(f32::INFINITY, f32::min);
+    let max_score = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
 
-        // Find min and max scores
-        let scores: Vec<f32> = matches.iter().map(|m| m.score).collect();
-        let min_score = scores.iter().copied().fold(f32::INFINITY, f32::min);
-        let max_score = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
-
-        // Normalize based on method
-        match self.normalization {
-          NormalizationMethod::MinMax => {
-            let range = max_score - min_score;
-            if range > 0.0 {
-              for m in &mut matches {
-                m.score = (m.score - min_score) / range;
-              }
-            } else {
-              // All scores are the same, so we can set them all to 1.0
-              for m in &mut matches {
-                m.score = 1.0;
-              }
-            }
+    // Keep the raw scores around to explain the normalization
+    let raw: Vec<Explanation> = if explain {
+      matches
+        .iter_mut()
+        .map(|m| {
+          m.explanation
+            .take()
+            .unwrap_or_else(|| Explanation::new(m.score, format!("raw {:?} score", kind)))
+        })
+        .collect()
+    } else {
+      Vec::new()
+    };
+
+    // Normalize based on method
+    match self.normalization {
+      NormalizationMethod::MinMax => {
+        let range = max_score - min_score;
+        if range > 0.0 {
+          for m in &mut matches {
+            m.score = (m.score - min_score) / range;
           }
-          NormalizationMethod::InverseDistance => {
-            // Assumes scores are distances; converts them to similarities.
-            for m in &mut matches {
-              m.score = 1.0 / (1.0 + m.score);
-            }
+        } else {
+          // All scores are the same, so we can set them all to 1.0
+          for m in &mut matches {
+            m.score = 1.0;
           }
         }
+      }
+      NormalizationMethod::InverseDistance => {
+        // Assumes scores are distances; converts them to similarities.
+        for m in &mut matches {
+          m.score = 1.0 / (1.0 + m.score);
+        }
+      }
+    }
 
-        (kind, matches)
-      })
-      .collect()
+    if explain {
+      let description = match self.normalization {
+        NormalizationMethod::MinMax => format!(
+          "{:?} score, min-max normalized over [{:.4}, {:.4}]:",
+          kind, min_score, max_score
+        ),
+        NormalizationMethod::InverseDistance => {
+          format!("{:?} score, normalized as 1 / (1 + distance):", kind)
+        }
+      };
+      for (m, raw) in matches.iter_mut().zip(raw) {
+        m.explanation = Some(Explanation::new(m.score, description.clone()).with_detail(raw));
+      }
+    }
+
+    (kind, matches)
+  }
+
+  /// Returns the weight of a kind of searcher in a search: the weight set in
+  /// the options, then the weight of their preset, then the engine's default
+  /// weight, and 1.0 otherwise.
+  fn weight(&self, kind: SearcherKind, options: &SearchOptions) -> f32 {
+    if let Some(weight) = options.weights.get(&kind) {
+      return *weight;
+    }
+    if let Some(preset) = options.preset {
+      return preset.weight(kind);
+    }
+    self.default_weights.get(&kind).copied().unwrap_or(1.0)
   }
 
   /// Merges results from multiple searchers using a weighted scoring model.
@@ -305,9 +1051,10 @@ impl<T: Searchable> SearusEngine<T> {
     T: Clone,
   {
     let mut merged: HashMap<usize, SearusMatch<T>> = HashMap::new();
+    let explain = query.options.explain;
 
     for (kind, matches) in results {
-      let weight = query.options.weights.get(&kind).copied().unwrap_or(1.0);
+      let weight = self.weight(kind, &query.options);
 
       for m in matches {
         let item_id = m.id;
@@ -318,10 +1065,22 @@ impl<T: Searchable> SearusEngine<T> {
           score: 0.0,
           field_scores: HashMap::new(),
           details: Vec::new(),
+          searcher_scores: HashMap::new(),
+          highlights: Vec::new(),
+          explanation: explain.then(|| Explanation::new(0.0, "sum of weighted searcher scores:")),
         });
 
         // Add the weighted score to the total.
         entry.score += m.score * weight;
+        *entry.searcher_scores.entry(kind).or_insert(0.0) += m.score;
+
+        if let (Some(total), Some(explanation)) = (&mut entry.explanation, m.explanation) {
+          total.value = entry.score;
+          total.details.push(
+            Explanation::new(m.score * weight, format!("weight {} times:", weight))
+              .with_detail(explanation),
+          );
+        }
 
         // Merge field scores.
         for (field, score) in m.field_scores {
@@ -330,6 +1089,17 @@ impl<T: Searchable> SearusEngine<T> {
 
         // Merge details from all searchers.
         entry.details.extend(m.details);
+
+        // Merge highlights, skipping positions already highlighted.
+        for highlight in m.highlights {
+          let seen = entry
+            .highlights
+            .iter()
+            .any(|h| h.field == highlight.field && h.start == highlight.start);
+          if !seen {
+            entry.highlights.push(highlight);
+          }
+        }
       }
     }
 
@@ -402,6 +1172,24 @@ pub struct SearusEngineBuilder<T> {
   searchers: Vec<Box<dyn Searcher<T>>>,
   normalization: Option<NormalizationMethod>,
   extensions: Vec<Box<dyn SearusExtension<T>>>,
+  score_modifiers: Vec<Box<dyn ScoreModifier<T>>>,
+  default_weights: HashMap<SearcherKind, f32>,
+  embedder: Option<Box<dyn TextEmbedder>>,
+  history: Option<SearchHistory>,
+  max_matches_per_searcher: Option<usize>,
+  max_matches: Option<usize>,
+  #[cfg(feature = "parallel")]
+  thread_pool: Option<Arc<ThreadPool>>,
+  #[cfg(feature = "parallel")]
+  max_threads: Option<usize>,
+  parallel_threshold: usize,
+  parallel_chunk_size: usize,
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  spell_corrector: Option<SpellCorrector>,
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  suggestion_threshold: Option<usize>,
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  max_suggestions: Option<usize>,
 }
 
 impl<T> SearusEngineBuilder<T> {
@@ -411,6 +1199,24 @@ impl<T> SearusEngineBuilder<T> {
       searchers: Vec::new(),
       normalization: None,
       extensions: Vec::new(),
+      score_modifiers: Vec::new(),
+      default_weights: HashMap::new(),
+      embedder: None,
+      history: None,
+      max_matches_per_searcher: None,
+      max_matches: None,
+      #[cfg(feature = "parallel")]
+      thread_pool: None,
+      #[cfg(feature = "parallel")]
+      max_threads: None,
+      parallel_threshold: 0,
+      parallel_chunk_size: 1,
+      #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+      spell_corrector: None,
+      #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+      suggestion_threshold: None,
+      #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+      max_suggestions: None,
     }
   }
 
@@ -452,6 +1258,267 @@ impl<T> SearusEngineBuilder<T> {
     self
   }
 
+  /// Sets the default weight of a kind of searcher.
+  ///
+  /// The weight applies to every query that doesn't weight the kind itself,
+  /// with [`SearchOptions::weight`] or a [`SearchOptions::preset`], so call
+  /// sites don't have to repeat the same weights. Kinds without any weight
+  /// have a weight of 1.0.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let engine: SearusEngine<serde_json::Value> = SearusEngine::builder()
+  ///     .default_weight(SearcherKind::Semantic, 0.7)
+  ///     .default_weight(SearcherKind::Tags, 0.3)
+  ///     .build();
+  /// ```
+  pub fn default_weight(mut self, kind: SearcherKind, weight: f32) -> Self {
+    self.default_weights.insert(kind, weight);
+    self
+  }
+
+  /// Sets the default weights of several kinds of searchers, as with
+  /// [`default_weight`](Self::default_weight).
+  pub fn default_weights<I>(mut self, weights: I) -> Self
+  where
+    I: IntoIterator<Item = (SearcherKind, f32)>,
+  {
+    self.default_weights.extend(weights);
+    self
+  }
+
+  /// Adds a score modifier to the engine.
+  ///
+  /// Score modifiers adjust the merged score of every result based on the
+  /// item itself, e.g., to favor recent or popular items. They run after
+  /// merging and before the `after_merge` extension hook, in the order they
+  /// were added.
+  ///
+  /// # Arguments
+  ///
+  /// * `modifier` - A `Box<dyn ScoreModifier<T>>` instance.
+  pub fn score_modifier(mut self, modifier: Box<dyn ScoreModifier<T>>) -> Self {
+    self.score_modifiers.push(modifier);
+    self
+  }
+
+  /// Adds a score function to the engine.
+  ///
+  /// This is a convenience for [`score_modifier`](Self::score_modifier) that
+  /// accepts a closure receiving the item and its merged score, and returning
+  /// the new score.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use searus::prelude::*;
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Product { name: String, in_stock: bool }
+  ///
+  /// // Push out-of-stock products down the results.
+  /// let engine: SearusEngine<Product> = SearusEngine::builder()
+  ///     .score_fn(|product: &Product, score| if product.in_stock { score } else { score * 0.5 })
+  ///     .build();
+  /// ```
+  pub fn score_fn<F>(self, score_fn: F) -> Self
+  where
+    F: Fn(&T, f32) -> f32 + Send + Sync + 'static,
+  {
+    self.score_modifier(Box::new(score_fn))
+  }
+
+  /// Sets the embedder used to turn query text into a vector.
+  ///
+  /// When a query has text but no vector, and a searcher of kind
+  /// [`SearcherKind::Vector`] is registered, the engine embeds the text
+  /// (after the `before_query` hooks) and searches with it, so callers don't
+  /// have to embed every query themselves. Queries that already have a
+  /// vector are left as they are.
+  ///
+  /// The embedder must be the one the items were embedded with.
+  ///
+  /// # Arguments
+  ///
+  /// * `embedder` - A `Box<dyn TextEmbedder>` instance.
+  pub fn embedder(mut self, embedder: Box<dyn TextEmbedder>) -> Self {
+    self.embedder = Some(embedder);
+    self
+  }
+
+  /// Sets the history recording the text of every search.
+  ///
+  /// The engine records each search with its query text as given (before
+  /// the `before_query` hooks rewrite it) and its total number of matches,
+  /// and suggests the successful ones with [`SearusEngine::suggest_queries`].
+  /// Searches without text are not recorded.
+  pub fn history(mut self, history: SearchHistory) -> Self {
+    self.history = Some(history);
+    self
+  }
+
+  /// Sets the maximum number of matches kept from each searcher in every
+  /// search.
+  ///
+  /// This caps [`SearchOptions::max_matches_per_searcher`], so queries can
+  /// lower the budget but not raise it. Past the budget, searchers keep only
+  /// their best-scoring matches, and the response is marked as
+  /// [`truncated`](SearchResponse::truncated). This bounds the memory a
+  /// search can take, however many items its query matches.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let posts: Vec<serde_json::Value> = (0..100)
+  ///     .map(|i| serde_json::json!({ "title": format!("Rust post {}", i), "tags": ["rust"] }))
+  ///     .collect();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(TaggedSearch::new()))
+  ///     .max_matches_per_searcher(10)
+  ///     .build();
+  ///
+  /// let query = Query::builder().tags(vec!["rust".into()]).build();
+  /// let response = engine.search_response(&posts, &query);
+  /// assert_eq!(response.total_matches, 10);
+  /// assert!(response.truncated);
+  /// ```
+  pub fn max_matches_per_searcher(mut self, max_matches: usize) -> Self {
+    self.max_matches_per_searcher = Some(max_matches);
+    self
+  }
+
+  /// Sets the maximum number of merged matches kept in every search, before
+  /// pagination.
+  ///
+  /// This caps [`SearchOptions::max_matches`], so queries can lower the
+  /// budget but not raise it.
+  pub fn max_matches(mut self, max_matches: usize) -> Self {
+    self.max_matches = Some(max_matches);
+    self
+  }
+
+  /// Sets the thread pool that parallel searches run in.
+  ///
+  /// By default, searches run in the global rayon thread pool, shared with
+  /// the rest of the application. A dedicated pool keeps searches from
+  /// competing with other parallel work, and can be shared by several
+  /// engines.
+  #[cfg(feature = "parallel")]
+  pub fn thread_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+    self.thread_pool = Some(pool);
+    self
+  }
+
+  /// Sets the maximum number of threads parallel searches run on.
+  ///
+  /// The engine builds a thread pool of that many threads, unless one is set
+  /// with [`thread_pool`](Self::thread_pool). If the pool cannot be built,
+  /// searches run in the global thread pool.
+  #[cfg(feature = "parallel")]
+  pub fn max_threads(mut self, threads: usize) -> Self {
+    self.max_threads = Some(threads.max(1));
+    self
+  }
+
+  /// Sets the number of items below which searches run sequentially. The
+  /// default is 0, so every search runs in parallel with the `parallel`
+  /// feature.
+  ///
+  /// Splitting a search over threads costs more than it saves on small
+  /// corpora. Below the threshold, the searchers run one after another, and
+  /// each matches the items on the calling thread (see
+  /// [`SearchContext::is_parallel`](crate::context::SearchContext::is_parallel)).
+  /// Without the `parallel` feature, searches always run sequentially.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let posts = vec![serde_json::json!({ "title": "Rust ownership", "tags": ["rust"] })];
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(TaggedSearch::new()))
+  ///     .parallel_threshold(1_000)
+  ///     .build();
+  ///
+  /// let query = Query::builder().tags(vec!["rust".into()]).build();
+  /// assert_eq!(engine.search(&posts, &query).len(), 1);
+  /// ```
+  pub fn parallel_threshold(mut self, items: usize) -> Self {
+    self.parallel_threshold = items;
+    self
+  }
+
+  /// Sets the fewest items each parallel task of a searcher matches. The
+  /// default is 1, which lets rayon split the items as finely as it sees
+  /// fit.
+  ///
+  /// Matching an item with a few short fields takes less time than handing
+  /// it to another thread: larger chunks cut that overhead, and keep the
+  /// items a thread matches next to each other in memory. Items with long
+  /// texts are better split finely, to balance the work between threads.
+  /// Sequential searches (see [`parallel_threshold`](Self::parallel_threshold))
+  /// are not chunked.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let posts: Vec<serde_json::Value> = (0..100)
+  ///     .map(|i| serde_json::json!({ "title": format!("Post {}", i), "tags": ["rust"] }))
+  ///     .collect();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(TaggedSearch::new()))
+  ///     .parallel_chunk_size(256)
+  ///     .build();
+  ///
+  /// let query = Query::builder().tags(vec!["rust".into()]).build();
+  /// assert_eq!(engine.search_response(&posts, &query).total_matches, 100);
+  /// ```
+  pub fn parallel_chunk_size(mut self, items: usize) -> Self {
+    self.parallel_chunk_size = items.max(1);
+    self
+  }
+
+  /// Sets the spell corrector used for "did you mean" suggestions.
+  ///
+  /// When a query matches fewer items than the suggestion threshold,
+  /// [`SearusEngine::search_response`] reports a corrected version of the
+  /// query text in [`SearchResponse::suggestion`].
+  ///
+  /// # Arguments
+  ///
+  /// * `corrector` - A `SpellCorrector`, typically built from the corpus.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  pub fn spell_corrector(mut self, corrector: SpellCorrector) -> Self {
+    self.spell_corrector = Some(corrector);
+    self
+  }
+
+  /// Sets the result count below which suggestions are produced.
+  ///
+  /// If not set, suggestions are produced for queries with fewer than 3 matches.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  pub fn suggestion_threshold(mut self, threshold: usize) -> Self {
+    self.suggestion_threshold = Some(threshold);
+    self
+  }
+
+  /// Sets the maximum number of alternative queries reported in
+  /// [`SearchResponse::suggestions`].
+  ///
+  /// If not set, up to 3 alternatives are reported.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  pub fn max_suggestions(mut self, count: usize) -> Self {
+    self.max_suggestions = Some(count);
+    self
+  }
+
   /// Builds the `SearusEngine` with the configured components.
   ///
   /// # Returns
@@ -462,10 +1529,38 @@ impl<T> SearusEngineBuilder<T> {
       searchers: self.searchers,
       normalization: self.normalization.unwrap_or(NormalizationMethod::MinMax),
       extensions: self.extensions,
+      score_modifiers: self.score_modifiers,
+      default_weights: self.default_weights,
+      embedder: self.embedder,
+      history: self.history,
+      max_matches_per_searcher: self.max_matches_per_searcher,
+      max_matches: self.max_matches,
+      #[cfg(feature = "parallel")]
+      thread_pool: self.thread_pool.or_else(|| {
+        let threads = self.max_threads?;
+        let pool = ThreadPoolBuilder::new().num_threads(threads).build().ok()?;
+        Some(Arc::new(pool))
+      }),
+      parallel_threshold: self.parallel_threshold,
+      parallel_chunk_size: self.parallel_chunk_size,
+      #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+      spell_corrector: self.spell_corrector,
+      #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+      suggestion_threshold: self.suggestion_threshold.unwrap_or(3),
+      #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+      max_suggestions: self.max_suggestions.unwrap_or(3),
     }
   }
 }
 
+/// Returns the smaller of two budgets, where `None` is no budget.
+fn min_budget(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+  match (a, b) {
+    (Some(a), Some(b)) => Some(a.min(b)),
+    (a, b) => a.or(b),
+  }
+}
+
 /// Defines the methods for normalizing scores from different searchers.
 ///
 /// Normalization is crucial when combining results from multiple searchers,
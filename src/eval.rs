@@ -0,0 +1,300 @@
+//! Offline relevance evaluation.
+//!
+//! This module measures the quality of an engine's rankings against a set of
+//! queries with graded relevance judgments, so that changes to rules, weights,
+//! or modifiers can be compared with numbers instead of by eye.
+//!
+//! Supported metrics, all computed at a cutoff `k`:
+//!
+//! - **NDCG@k**: Normalized discounted cumulative gain, using `2^grade - 1` gains.
+//! - **MRR**: The reciprocal rank of the first relevant result.
+//! - **Precision@k**: The fraction of the top `k` results that are relevant.
+//! - **Recall@k**: The fraction of all relevant items found in the top `k`.
+//!
+//! An item is relevant when its grade is greater than zero.
+
+use crate::engine::SearusEngine;
+use crate::types::{Query, Searchable};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A query together with the relevance grades of the items it should find.
+///
+/// Items are identified by a string key, produced for each result by the
+/// [`Evaluator`]'s key function. Items without a judgment have a grade of 0.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JudgedQuery {
+  /// The query to run.
+  pub query: Query,
+  /// The relevance grade of each judged item, by key.
+  #[serde(default)]
+  pub judgments: HashMap<String, f32>,
+}
+
+impl JudgedQuery {
+  /// Creates a new `JudgedQuery` without any judgments.
+  pub fn new(query: Query) -> Self {
+    Self {
+      query,
+      judgments: HashMap::new(),
+    }
+  }
+
+  /// Adds the relevance grade of an item.
+  pub fn judge(mut self, key: impl Into<String>, grade: f32) -> Self {
+    self.judgments.insert(key.into(), grade);
+    self
+  }
+
+  /// Returns the grade of an item, or 0 if it is not judged.
+  pub fn grade(&self, key: &str) -> f32 {
+    self.judgments.get(key).copied().unwrap_or(0.0)
+  }
+}
+
+/// The metrics of a single query, or their mean over several queries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metrics {
+  /// Normalized discounted cumulative gain at `k`.
+  pub ndcg: f32,
+  /// The reciprocal rank of the first relevant result.
+  pub mrr: f32,
+  /// The fraction of the top `k` results that are relevant.
+  pub precision: f32,
+  /// The fraction of all relevant items found in the top `k` results.
+  pub recall: f32,
+}
+
+/// The outcome of evaluating an engine on a set of judged queries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvalReport {
+  /// The cutoff the metrics were computed at.
+  pub k: usize,
+  /// The metrics of each query, in the order the queries were given.
+  pub queries: Vec<Metrics>,
+  /// The mean of each metric over all queries.
+  pub mean: Metrics,
+}
+
+/// Runs judged queries through an engine and computes relevance metrics.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::eval::{Evaluator, JudgedQuery};
+/// use searus::prelude::*;
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Doc {
+///     id: String,
+///     title: String,
+/// }
+///
+/// let docs = vec![
+///     Doc { id: "a".into(), title: "Rust programming".into() },
+///     Doc { id: "b".into(), title: "Rust belt travel guide".into() },
+///     Doc { id: "c".into(), title: "Python programming".into() },
+/// ];
+///
+/// let rules = SemanticRules::builder().field("title", FieldRule::bm25()).build();
+/// let engine = SearusEngine::builder()
+///     .with(Box::new(SemanticSearch::new(rules)))
+///     .build();
+///
+/// let judged = vec![
+///     JudgedQuery::new(Query::builder().text("rust programming").build())
+///         .judge("a", 2.0)
+///         .judge("c", 1.0),
+/// ];
+///
+/// let report = Evaluator::new(|doc: &Doc| doc.id.clone())
+///     .k(3)
+///     .evaluate(&engine, &docs, &judged);
+///
+/// assert_eq!(report.mean.mrr, 1.0);
+/// assert_eq!(report.mean.recall, 1.0);
+/// assert!(report.mean.ndcg > 0.9);
+/// ```
+pub struct Evaluator<T> {
+  /// Produces the judgment key of an item.
+  key: Box<dyn Fn(&T) -> String + Send + Sync>,
+  /// The cutoff for the metrics.
+  k: usize,
+}
+
+impl<T: Searchable> Evaluator<T> {
+  /// Creates a new `Evaluator` with a cutoff of 10.
+  ///
+  /// # Arguments
+  ///
+  /// * `key` - A function returning the key used in the judgments for an item.
+  pub fn new<F>(key: F) -> Self
+  where
+    F: Fn(&T) -> String + Send + Sync + 'static,
+  {
+    Self {
+      key: Box::new(key),
+      k: 10,
+    }
+  }
+
+  /// Sets the cutoff `k` for the metrics.
+  pub fn k(mut self, k: usize) -> Self {
+    self.k = k.max(1);
+    self
+  }
+
+  /// Runs every judged query through the engine and computes the metrics.
+  ///
+  /// The pagination options of the queries are overridden so that the top
+  /// `k` results are evaluated.
+  pub fn evaluate(
+    &self,
+    engine: &SearusEngine<T>,
+    items: &[T],
+    queries: &[JudgedQuery],
+  ) -> EvalReport
+  where
+    T: Clone + Serialize,
+  {
+    let metrics: Vec<Metrics> = queries
+      .iter()
+      .map(|judged| {
+        let grades: Vec<f32> = self
+          .rank(engine, items, &judged.query)
+          .iter()
+          .map(|key| judged.grade(key))
+          .collect();
+
+        self.metrics(&grades, judged)
+      })
+      .collect();
+
+    EvalReport {
+      k: self.k,
+      mean: mean(&metrics),
+      queries: metrics,
+    }
+  }
+
+  /// Returns the cutoff `k` for the metrics.
+  pub(crate) fn cutoff(&self) -> usize {
+    self.k
+  }
+
+  /// Runs a query through the engine and returns the keys of the top `k`
+  /// results.
+  ///
+  /// The pagination options of the query are overridden so that the top `k`
+  /// results are returned.
+  pub(crate) fn rank(&self, engine: &SearusEngine<T>, items: &[T], query: &Query) -> Vec<String>
+  where
+    T: Clone + Serialize,
+  {
+    let mut query = query.clone();
+    query.options.skip = 0;
+    query.options.limit = self.k;
+
+    engine
+      .search(items, &query)
+      .iter()
+      .map(|m| (self.key)(&m.item))
+      .collect()
+  }
+
+  /// Computes the metrics of a ranked list of grades.
+  pub(crate) fn metrics(&self, grades: &[f32], judged: &JudgedQuery) -> Metrics {
+    let mut ideal: Vec<f32> = judged.judgments.values().copied().collect();
+    ideal.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+    let relevant = ideal.iter().filter(|&&grade| grade > 0.0).count();
+    let found = grades
+      .iter()
+      .take(self.k)
+      .filter(|&&grade| grade > 0.0)
+      .count();
+
+    Metrics {
+      ndcg: ndcg_at_k(grades, &ideal, self.k),
+      mrr: reciprocal_rank(grades),
+      precision: found as f32 / self.k as f32,
+      recall: if relevant > 0 {
+        found as f32 / relevant as f32
+      } else {
+        0.0
+      },
+    }
+  }
+}
+
+/// Calculates the discounted cumulative gain of the top `k` grades.
+pub fn dcg_at_k(grades: &[f32], k: usize) -> f32 {
+  grades
+    .iter()
+    .take(k)
+    .enumerate()
+    .map(|(rank, &grade)| (2f32.powf(grade) - 1.0) / ((rank + 2) as f32).log2())
+    .sum()
+}
+
+/// Calculates the normalized discounted cumulative gain of the top `k` grades.
+///
+/// # Arguments
+///
+/// * `grades` - The grades of the results, in ranked order.
+/// * `ideal` - All known grades, sorted in descending order.
+/// * `k` - The cutoff.
+pub fn ndcg_at_k(grades: &[f32], ideal: &[f32], k: usize) -> f32 {
+  let ideal_dcg = dcg_at_k(ideal, k);
+  if ideal_dcg > 0.0 {
+    dcg_at_k(grades, k) / ideal_dcg
+  } else {
+    0.0
+  }
+}
+
+/// Returns the reciprocal rank of the first relevant grade, or 0 if none is.
+pub fn reciprocal_rank(grades: &[f32]) -> f32 {
+  grades
+    .iter()
+    .position(|&grade| grade > 0.0)
+    .map(|rank| 1.0 / (rank + 1) as f32)
+    .unwrap_or(0.0)
+}
+
+/// Averages each metric over several queries.
+pub(crate) fn mean(metrics: &[Metrics]) -> Metrics {
+  if metrics.is_empty() {
+    return Metrics::default();
+  }
+
+  let n = metrics.len() as f32;
+  Metrics {
+    ndcg: metrics.iter().map(|m| m.ndcg).sum::<f32>() / n,
+    mrr: metrics.iter().map(|m| m.mrr).sum::<f32>() / n,
+    precision: metrics.iter().map(|m| m.precision).sum::<f32>() / n,
+    recall: metrics.iter().map(|m| m.recall).sum::<f32>() / n,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_ndcg() {
+    let ideal = [3.0, 2.0, 1.0];
+
+    assert!((ndcg_at_k(&[3.0, 2.0, 1.0], &ideal, 3) - 1.0).abs() < 1e-6);
+    assert!(ndcg_at_k(&[1.0, 2.0, 3.0], &ideal, 3) < 1.0);
+    assert_eq!(ndcg_at_k(&[0.0, 0.0], &ideal, 2), 0.0);
+    assert_eq!(ndcg_at_k(&[1.0], &[], 1), 0.0);
+  }
+
+  #[test]
+  fn test_reciprocal_rank() {
+    assert_eq!(reciprocal_rank(&[0.0, 0.0, 1.0]), 1.0 / 3.0);
+    assert_eq!(reciprocal_rank(&[2.0]), 1.0);
+    assert_eq!(reciprocal_rank(&[0.0]), 0.0);
+  }
+}
@@ -0,0 +1,303 @@
+//! A/B experiments between two engine configurations.
+//!
+//! An [`Experiment`] runs the same queries through a *control* engine (the
+//! current configuration) and a *treatment* engine (the candidate change),
+//! and reports how the rankings differ, so that changes to rules, weights, or
+//! modifiers can be validated before they are rolled out.
+//!
+//! For every query, the report lists the items that moved, appeared, or
+//! disappeared in the top `k` results, and their overlap@k. Queries with
+//! relevance judgments are also evaluated with the metrics of the
+//! [`eval`](crate::eval) module, and the report gives the mean change of each
+//! metric.
+
+use crate::engine::SearusEngine;
+use crate::eval::{mean, Evaluator, JudgedQuery, Metrics};
+use crate::types::Searchable;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// The rank of an item in the control and treatment results, if it changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RankChange {
+  /// The key of the item.
+  pub key: String,
+  /// The 1-based rank of the item in the control results, if in the top `k`.
+  pub control: Option<usize>,
+  /// The 1-based rank of the item in the treatment results, if in the top `k`.
+  pub treatment: Option<usize>,
+}
+
+/// How the results of a single query differ between the two engines.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryComparison {
+  /// The keys of the top `k` control results, in ranked order.
+  pub control: Vec<String>,
+  /// The keys of the top `k` treatment results, in ranked order.
+  pub treatment: Vec<String>,
+  /// The fraction of the top `k` results both engines returned (1.0 when
+  /// both returned nothing).
+  pub overlap: f32,
+  /// The items whose rank changed, ordered by their control rank and then
+  /// by their treatment rank.
+  pub changes: Vec<RankChange>,
+  /// The metrics of the control results, if the query has judgments.
+  pub control_metrics: Option<Metrics>,
+  /// The metrics of the treatment results, if the query has judgments.
+  pub treatment_metrics: Option<Metrics>,
+}
+
+impl QueryComparison {
+  /// Returns `true` if both engines returned the same top `k` results in the
+  /// same order.
+  pub fn is_unchanged(&self) -> bool {
+    self.changes.is_empty()
+  }
+}
+
+/// The outcome of an experiment over a set of queries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExperimentReport {
+  /// The cutoff the rankings were compared at.
+  pub k: usize,
+  /// The comparison of each query, in the order the queries were given.
+  pub queries: Vec<QueryComparison>,
+  /// The mean overlap@k over all queries.
+  pub mean_overlap: f32,
+  /// The mean metrics of the control engine over the judged queries.
+  pub control: Option<Metrics>,
+  /// The mean metrics of the treatment engine over the judged queries.
+  pub treatment: Option<Metrics>,
+  /// The change of each mean metric, treatment minus control. Positive
+  /// values mean the treatment ranks better.
+  pub delta: Option<Metrics>,
+}
+
+impl ExperimentReport {
+  /// Returns the number of queries whose top `k` results changed.
+  pub fn changed(&self) -> usize {
+    self.queries.iter().filter(|q| !q.is_unchanged()).count()
+  }
+}
+
+/// Runs queries through a control and a treatment engine and compares their
+/// rankings.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::eval::JudgedQuery;
+/// use searus::experiments::Experiment;
+/// use searus::prelude::*;
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Doc {
+///     id: String,
+///     title: String,
+///     body: String,
+/// }
+///
+/// let docs = vec![
+///     Doc { id: "a".into(), title: "Rust".into(), body: "Python tips".into() },
+///     Doc { id: "b".into(), title: "Python".into(), body: "Rust tips".into() },
+/// ];
+///
+/// let engine = |title_boost: f32| {
+///     let rules = SemanticRules::builder()
+///         .field("title", FieldRule::tokenized().boost(title_boost))
+///         .field("body", FieldRule::tokenized())
+///         .build();
+///     SearusEngine::builder()
+///         .with(Box::new(SemanticSearch::new(rules)))
+///         .build()
+/// };
+/// let (control, treatment) = (engine(0.1), engine(10.0));
+///
+/// let queries = vec![JudgedQuery::new(Query::builder().text("rust").build()).judge("a", 1.0)];
+/// let report = Experiment::new(|doc: &Doc| doc.id.clone())
+///     .k(2)
+///     .run(&control, &treatment, &docs, &queries);
+///
+/// assert_eq!(report.queries[0].control, vec!["b", "a"]);
+/// assert_eq!(report.queries[0].treatment, vec!["a", "b"]);
+/// assert_eq!(report.mean_overlap, 1.0);
+/// assert_eq!(report.delta.unwrap().mrr, 0.5);
+/// ```
+pub struct Experiment<T> {
+  /// Ranks the queries and computes their metrics.
+  evaluator: Evaluator<T>,
+}
+
+impl<T: Searchable> Experiment<T> {
+  /// Creates a new `Experiment` with a cutoff of 10.
+  ///
+  /// # Arguments
+  ///
+  /// * `key` - A function returning the key identifying an item in the
+  ///   results and in the judgments.
+  pub fn new<F>(key: F) -> Self
+  where
+    F: Fn(&T) -> String + Send + Sync + 'static,
+  {
+    Self {
+      evaluator: Evaluator::new(key),
+    }
+  }
+
+  /// Sets the cutoff `k` the rankings are compared at.
+  pub fn k(mut self, k: usize) -> Self {
+    self.evaluator = self.evaluator.k(k);
+    self
+  }
+
+  /// Runs every query through both engines and compares the results.
+  ///
+  /// Queries without judgments are only compared by their rankings; the
+  /// metrics are averaged over the judged queries. The pagination options of
+  /// the queries are overridden so that the top `k` results are compared.
+  pub fn run(
+    &self,
+    control: &SearusEngine<T>,
+    treatment: &SearusEngine<T>,
+    items: &[T],
+    queries: &[JudgedQuery],
+  ) -> ExperimentReport
+  where
+    T: Clone + Serialize,
+  {
+    let comparisons: Vec<QueryComparison> = queries
+      .iter()
+      .map(|judged| {
+        let control = self.evaluator.rank(control, items, &judged.query);
+        let treatment = self.evaluator.rank(treatment, items, &judged.query);
+        self.compare(judged, control, treatment)
+      })
+      .collect();
+
+    let judged = |metrics: fn(&QueryComparison) -> Option<Metrics>| {
+      let metrics: Vec<Metrics> = comparisons.iter().filter_map(metrics).collect();
+      (!metrics.is_empty()).then(|| mean(&metrics))
+    };
+    let control = judged(|q| q.control_metrics);
+    let treatment = judged(|q| q.treatment_metrics);
+
+    let mean_overlap = if comparisons.is_empty() {
+      0.0
+    } else {
+      comparisons.iter().map(|q| q.overlap).sum::<f32>() / comparisons.len() as f32
+    };
+
+    ExperimentReport {
+      k: self.evaluator.cutoff(),
+      mean_overlap,
+      delta: control.zip(treatment).map(|(c, t)| Metrics {
+        ndcg: t.ndcg - c.ndcg,
+        mrr: t.mrr - c.mrr,
+        precision: t.precision - c.precision,
+        recall: t.recall - c.recall,
+      }),
+      control,
+      treatment,
+      queries: comparisons,
+    }
+  }
+
+  /// Compares the ranked keys of both engines for one query.
+  fn compare(
+    &self,
+    judged: &JudgedQuery,
+    control: Vec<String>,
+    treatment: Vec<String>,
+  ) -> QueryComparison {
+    let rank_in = |keys: &[String], key: &str| keys.iter().position(|k| k == key).map(|r| r + 1);
+
+    let mut changes: Vec<RankChange> = Vec::new();
+    let mut seen = HashSet::new();
+    for key in control.iter().chain(&treatment) {
+      if !seen.insert(key) {
+        continue;
+      }
+      let change = RankChange {
+        key: key.clone(),
+        control: rank_in(&control, key),
+        treatment: rank_in(&treatment, key),
+      };
+      if change.control != change.treatment {
+        changes.push(change);
+      }
+    }
+
+    let metrics = |keys: &[String]| {
+      let grades: Vec<f32> = keys.iter().map(|key| judged.grade(key)).collect();
+      self.evaluator.metrics(&grades, judged)
+    };
+    let is_judged = !judged.judgments.is_empty();
+
+    QueryComparison {
+      overlap: overlap(&control, &treatment),
+      changes,
+      control_metrics: is_judged.then(|| metrics(&control)),
+      treatment_metrics: is_judged.then(|| metrics(&treatment)),
+      control,
+      treatment,
+    }
+  }
+}
+
+/// Calculates the fraction of results two rankings have in common, relative
+/// to the longer ranking. Two empty rankings fully overlap.
+pub fn overlap(a: &[String], b: &[String]) -> f32 {
+  let len = a.len().max(b.len());
+  if len == 0 {
+    return 1.0;
+  }
+  let a: HashSet<&String> = a.iter().collect();
+  let common = b.iter().collect::<HashSet<_>>().intersection(&a).count();
+  common as f32 / len as f32
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn keys(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|k| k.to_string()).collect()
+  }
+
+  #[test]
+  fn test_overlap() {
+    assert_eq!(overlap(&keys(&["a", "b"]), &keys(&["b", "a"])), 1.0);
+    assert_eq!(overlap(&keys(&["a", "b"]), &keys(&["b", "c"])), 0.5);
+    assert_eq!(overlap(&keys(&["a", "b"]), &keys(&["a"])), 0.5);
+    assert_eq!(overlap(&[], &[]), 1.0);
+  }
+
+  #[test]
+  fn test_rank_changes() {
+    let experiment = Experiment::<String>::new(|item| item.clone()).k(3);
+    let judged = JudgedQuery::default();
+    let comparison = experiment.compare(&judged, keys(&["a", "b", "c"]), keys(&["a", "c", "d"]));
+
+    assert_eq!(
+      comparison.changes,
+      vec![
+        RankChange {
+          key: "b".into(),
+          control: Some(2),
+          treatment: None
+        },
+        RankChange {
+          key: "c".into(),
+          control: Some(3),
+          treatment: Some(2)
+        },
+        RankChange {
+          key: "d".into(),
+          control: None,
+          treatment: Some(3)
+        },
+      ]
+    );
+    assert!(comparison.control_metrics.is_none());
+  }
+}
@@ -0,0 +1,91 @@
+//! Structured explanations of how a match was scored.
+//!
+//! When [`SearchOptions::explain`](crate::types::SearchOptions::explain) is
+//! enabled, every returned [`SearusMatch`](crate::types::SearusMatch) carries an
+//! [`Explanation`] tree describing how its final score was computed: the raw
+//! score reported by each searcher (including per-field contributions and BM25
+//! components), the normalization applied to it, the searcher weight, and the
+//! effect of any score modifiers.
+//!
+//! Explanations are skipped entirely when the option is off, so they cost
+//! nothing in regular searches.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A node in a score explanation tree.
+///
+/// Each node holds a value, a human-readable description of how that value was
+/// obtained, and the child explanations it was computed from.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::explain::Explanation;
+///
+/// let explanation = Explanation::new(3.0, "sum of:")
+///     .with_detail(Explanation::new(1.0, "title"))
+///     .with_detail(Explanation::new(2.0, "content"));
+///
+/// assert_eq!(explanation.details.len(), 2);
+/// assert_eq!(
+///     explanation.to_string(),
+///     "3.0000 = sum of:\n  1.0000 = title\n  2.0000 = content\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Explanation {
+  /// The value of this node.
+  pub value: f32,
+  /// A description of how the value was computed.
+  pub description: String,
+  /// The explanations this value was computed from.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub details: Vec<Explanation>,
+}
+
+impl Explanation {
+  /// Creates a new explanation node without any details.
+  pub fn new(value: f32, description: impl Into<String>) -> Self {
+    Self {
+      value,
+      description: description.into(),
+      details: Vec::new(),
+    }
+  }
+
+  /// Adds a child explanation.
+  pub fn with_detail(mut self, detail: Explanation) -> Self {
+    self.details.push(detail);
+    self
+  }
+
+  /// Adds several child explanations.
+  pub fn with_details(mut self, details: impl IntoIterator<Item = Explanation>) -> Self {
+    self.details.extend(details);
+    self
+  }
+
+  /// Writes this node and its children, indented by `depth` levels.
+  fn write_tree(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+    writeln!(
+      f,
+      "{:indent$}{:.4} = {}",
+      "",
+      self.value,
+      self.description,
+      indent = depth * 2
+    )?;
+    for detail in &self.details {
+      detail.write_tree(f, depth + 1)?;
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for Explanation {
+  /// Formats the explanation as an indented tree, one node per line.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.write_tree(f, 0)
+  }
+}
@@ -1,7 +1,10 @@
 //! Defines the extension system for Searus.
 
-use crate::searcher::Searcher;
-use crate::types::{Query, Searchable, SearusMatch};
+use crate::searcher::SearcherConfig;
+use crate::types::{Query, Searchable, SearcherKind, SearusMatch};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
 
 /// A trait for extensions that can hook into the search lifecycle.
 ///
@@ -9,6 +12,9 @@ use crate::types::{Query, Searchable, SearusMatch};
 /// of the search process. They can be used for caching, query rewriting,
 /// data fetching, filtering, and more.
 ///
+/// Every hook of one search receives the same [`SearchState`], where
+/// extensions can keep what they accumulate during the search.
+///
 /// # Examples
 ///
 /// Implementing a simple logging extension:
@@ -19,13 +25,18 @@ use crate::types::{Query, Searchable, SearusMatch};
 /// struct LoggingExtension;
 ///
 /// impl<T: Searchable> SearusExtension<T> for LoggingExtension {
-///     fn before_query(&self, query: &mut Query) {
+///     fn before_query(&self, query: &mut Query, _state: &mut SearchState) {
 ///         if let Some(text) = &query.text {
 ///             println!("Processing query: {}", text);
 ///         }
 ///     }
 ///
-///     fn after_limit(&self, _query: &Query, results: &mut Vec<SearusMatch<T>>) {
+///     fn after_limit(
+///         &self,
+///         _query: &Query,
+///         results: &mut Vec<SearusMatch<T>>,
+///         _state: &mut SearchState,
+///     ) {
 ///         println!("Returning {} results", results.len());
 ///     }
 /// }
@@ -42,7 +53,7 @@ pub trait SearusExtension<T: Searchable>: Send + Sync {
   /// # use searus::prelude::*;
   /// # struct MyExt;
   /// # impl<T: Searchable> SearusExtension<T> for MyExt {
-  /// fn before_query(&self, query: &mut Query) {
+  /// fn before_query(&self, query: &mut Query, _state: &mut SearchState) {
   ///     // Force all queries to be lowercase
   ///     if let Some(text) = &mut query.text {
   ///         *text = text.to_lowercase();
@@ -50,29 +61,84 @@ pub trait SearusExtension<T: Searchable>: Send + Sync {
   /// }
   /// # }
   /// ```
-  fn before_query(&self, _query: &mut Query) {}
+  fn before_query(&self, _query: &mut Query, _state: &mut SearchState) {}
 
   /// Called before the items are passed to the searchers.
   ///
   /// This hook allows modifying the list of items to be searched.
   /// For example, an extension could fetch additional items from an external source
   /// or filter out items based on permissions.
-  fn before_items(&self, _query: &Query, _items: &mut Vec<T>) {}
+  fn before_items(&self, _query: &Query, _items: &mut Vec<T>, _state: &mut SearchState) {}
 
   /// Called before a specific searcher is executed.
   ///
-  /// This hook allows inspecting or modifying the searcher before it runs.
-  /// Note: Replacing the searcher is not directly supported via this hook in this signature,
-  /// but internal state of the searcher could potentially be modified if `Searcher` exposed mutability,
-  /// which it currently doesn't (it's `&self` in `search`).
-  /// So this hook is mostly for side effects or logging in the current design,
-  /// unless we change `Searcher` to be mutable or `Box<dyn Searcher>` to be mutable here.
-  fn before_searcher(&self, _query: &Query, _searcher: &mut Box<dyn Searcher<T>>) {}
+  /// This hook allows adjusting how the searcher runs for this search only,
+  /// for example raising its minimum score, changing the query it sees, or
+  /// disabling it. See [`SearcherConfig`].
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use searus::prelude::*;
+  /// # struct MyExt;
+  /// # impl<T: Searchable> SearusExtension<T> for MyExt {
+  /// fn before_searcher(
+  ///     &self,
+  ///     _query: &Query,
+  ///     config: &mut SearcherConfig,
+  ///     _state: &mut SearchState,
+  /// ) {
+  ///     // Skip custom searchers for anonymous queries
+  ///     if config.kind == SearcherKind::Custom && config.query.principal.is_none() {
+  ///         config.enabled = false;
+  ///     }
+  /// }
+  /// # }
+  /// ```
+  fn before_searcher(
+    &self,
+    _query: &Query,
+    _config: &mut SearcherConfig,
+    _state: &mut SearchState,
+  ) {
+  }
 
   /// Called after a specific searcher has executed.
   ///
-  /// This hook allows modifying the raw results returned by a searcher.
-  fn after_searcher(&self, _query: &Query, _results: &mut Vec<SearusMatch<T>>) {}
+  /// This hook allows modifying the raw results returned by a searcher. The
+  /// query is the one the searcher ran with, as configured in
+  /// `before_searcher`, and `kind` is the kind of the searcher, e.g. to boost
+  /// the matches of one kind only.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use searus::prelude::*;
+  /// # struct MyExt;
+  /// # impl<T: Searchable> SearusExtension<T> for MyExt {
+  /// fn after_searcher(
+  ///     &self,
+  ///     _query: &Query,
+  ///     kind: SearcherKind,
+  ///     results: &mut Vec<SearusMatch<T>>,
+  ///     _state: &mut SearchState,
+  /// ) {
+  ///     // Only keep the ten best vector matches
+  ///     if kind == SearcherKind::Vector {
+  ///         results.sort_by(|a, b| b.score.total_cmp(&a.score));
+  ///         results.truncate(10);
+  ///     }
+  /// }
+  /// # }
+  /// ```
+  fn after_searcher(
+    &self,
+    _query: &Query,
+    _kind: SearcherKind,
+    _results: &mut Vec<SearusMatch<T>>,
+    _state: &mut SearchState,
+  ) {
+  }
 
   /// Called before the results from all searchers are merged.
   ///
@@ -82,22 +148,148 @@ pub trait SearusExtension<T: Searchable>: Send + Sync {
   fn before_merge(
     &self,
     _query: &Query,
-    _results: &mut Vec<(crate::types::SearcherKind, Vec<SearusMatch<T>>)>,
+    _results: &mut Vec<(SearcherKind, Vec<SearusMatch<T>>)>,
+    _state: &mut SearchState,
   ) {
   }
 
   /// Called after the results have been merged.
   ///
   /// This hook allows modifying the merged and scored results.
-  fn after_merge(&self, _query: &Query, _results: &mut Vec<SearusMatch<T>>) {}
+  fn after_merge(
+    &self,
+    _query: &Query,
+    _results: &mut Vec<SearusMatch<T>>,
+    _state: &mut SearchState,
+  ) {
+  }
 
   /// Called before pagination (skip/limit) is applied.
   ///
   /// This is a good place for final sorting or filtering.
-  fn before_limit(&self, _query: &Query, _results: &mut Vec<SearusMatch<T>>) {}
+  fn before_limit(
+    &self,
+    _query: &Query,
+    _results: &mut Vec<SearusMatch<T>>,
+    _state: &mut SearchState,
+  ) {
+  }
 
   /// Called after pagination is applied.
   ///
   /// This hook allows modifying the final set of results that will be returned to the user.
-  fn after_limit(&self, _query: &Query, _results: &mut Vec<SearusMatch<T>>) {}
+  fn after_limit(
+    &self,
+    _query: &Query,
+    _results: &mut Vec<SearusMatch<T>>,
+    _state: &mut SearchState,
+  ) {
+  }
+}
+
+/// State shared by the hooks of all extensions during one search.
+///
+/// The engine creates an empty `SearchState` for every search (or uses the
+/// one given to [`search_with_state`](crate::engine::SearusEngine::search_with_state))
+/// and passes it to every hook, so extensions can accumulate timings,
+/// counters, or records of their rewrites without synchronizing themselves.
+/// Values are keyed by their type, so each extension should store a type of
+/// its own.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+///
+/// /// Counts the matches of each searcher.
+/// #[derive(Default)]
+/// struct MatchCounts(Vec<(SearcherKind, usize)>);
+///
+/// struct CountingExtension;
+///
+/// impl<T: Searchable> SearusExtension<T> for CountingExtension {
+///     fn after_searcher(
+///         &self,
+///         _query: &Query,
+///         kind: SearcherKind,
+///         results: &mut Vec<SearusMatch<T>>,
+///         state: &mut SearchState,
+///     ) {
+///         state.get_or_default::<MatchCounts>().0.push((kind, results.len()));
+///     }
+/// }
+///
+/// let mut state = SearchState::new();
+/// state.insert(42u32);
+/// assert_eq!(state.get::<u32>(), Some(&42));
+/// assert!(state.get::<MatchCounts>().is_none());
+/// ```
+#[derive(Default)]
+pub struct SearchState {
+  /// The values, keyed by their type.
+  values: HashMap<TypeId, Box<dyn Any + Send>>,
+}
+
+impl SearchState {
+  /// Creates a new, empty `SearchState`.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Stores a value, returning the previous value of the same type.
+  pub fn insert<S: Any + Send>(&mut self, value: S) -> Option<S> {
+    self
+      .values
+      .insert(TypeId::of::<S>(), Box::new(value))
+      .and_then(|previous| previous.downcast().ok())
+      .map(|previous| *previous)
+  }
+
+  /// Returns the value of the given type, if stored.
+  pub fn get<S: Any + Send>(&self) -> Option<&S> {
+    self.values.get(&TypeId::of::<S>())?.downcast_ref()
+  }
+
+  /// Returns the value of the given type mutably, if stored.
+  pub fn get_mut<S: Any + Send>(&mut self) -> Option<&mut S> {
+    self.values.get_mut(&TypeId::of::<S>())?.downcast_mut()
+  }
+
+  /// Returns the value of the given type mutably, storing its default value
+  /// first if there is none.
+  pub fn get_or_default<S: Any + Send + Default>(&mut self) -> &mut S {
+    self
+      .values
+      .entry(TypeId::of::<S>())
+      .or_insert_with(|| Box::new(S::default()))
+      .downcast_mut()
+      .expect("values are stored under their own type")
+  }
+
+  /// Removes and returns the value of the given type, if stored.
+  pub fn remove<S: Any + Send>(&mut self) -> Option<S> {
+    self
+      .values
+      .remove(&TypeId::of::<S>())
+      .and_then(|value| value.downcast().ok())
+      .map(|value| *value)
+  }
+
+  /// Returns the number of values stored.
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+
+  /// Returns `true` if no values are stored.
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+}
+
+impl fmt::Debug for SearchState {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("SearchState")
+      .field("len", &self.values.len())
+      .finish()
+  }
 }
@@ -0,0 +1,165 @@
+//! An extension that restricts search results to items the requester may see.
+
+use crate::extension::{SearchState, SearusExtension};
+use crate::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
+use crate::types::{Principal, Query, Searchable};
+
+/// A function that builds the security filter for a principal.
+type PolicyFn = Box<dyn Fn(Option<&Principal>) -> Option<FilterExpr> + Send + Sync>;
+
+/// An extension that injects per-request security filters into every query.
+///
+/// In the `before_query` hook, the extension evaluates its policy against the
+/// query's [`Principal`] and combines the resulting filter with any filters
+/// already present on the query using a logical AND. The engine then drops
+/// every match that fails the filters of the query, so items the principal may
+/// not see are never returned, even by custom searchers that ignore
+/// `Query::filters` or searchers whose query a `before_searcher` hook changed.
+///
+/// The policy receives `None` for anonymous queries (no principal set). It may
+/// return `None` to grant unrestricted access, e.g., for administrators.
+///
+/// Register this extension after any extension that rewrites `Query::filters`,
+/// so the security filter cannot be dropped by a later hook.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::extensions::AccessControlExtension;
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Doc {
+///     title: String,
+///     owner_id: String,
+///     visibility: String,
+/// }
+///
+/// let docs = vec![
+///     Doc { title: "rust notes".into(), owner_id: "alice".into(), visibility: "private".into() },
+///     Doc { title: "rust guide".into(), owner_id: "bob".into(), visibility: "public".into() },
+/// ];
+///
+/// let rules = SemanticRules::builder()
+///     .field("title", FieldRule::tokenized())
+///     .build();
+///
+/// let engine = SearusEngine::builder()
+///     .with(Box::new(SemanticSearch::new(rules)))
+///     .with_extension(Box::new(
+///         AccessControlExtension::owner_or_public("owner_id", "visibility", "public"),
+///     ))
+///     .build();
+///
+/// // Anonymous users only see public documents.
+/// let anonymous = Query::builder().text("rust").build();
+/// assert_eq!(engine.search(&docs, &anonymous).len(), 1);
+///
+/// // Alice also sees her own private documents.
+/// let alice = Query::builder()
+///     .text("rust")
+///     .principal(Principal::new("alice"))
+///     .build();
+/// assert_eq!(engine.search(&docs, &alice).len(), 2);
+/// ```
+pub struct AccessControlExtension {
+  /// Builds the security filter for the query's principal.
+  policy: PolicyFn,
+  /// Roles that bypass the policy entirely.
+  bypass_roles: Vec<String>,
+}
+
+impl AccessControlExtension {
+  /// Creates a new `AccessControlExtension` from a custom policy.
+  ///
+  /// # Arguments
+  ///
+  /// * `policy` - A closure that returns the filter an item must satisfy to be
+  ///   visible to the given principal, or `None` for unrestricted access. To
+  ///   deny everything, return an empty `FilterExpr::Or`.
+  pub fn new<F>(policy: F) -> Self
+  where
+    F: Fn(Option<&Principal>) -> Option<FilterExpr> + Send + Sync + 'static,
+  {
+    Self {
+      policy: Box::new(policy),
+      bypass_roles: Vec::new(),
+    }
+  }
+
+  /// Creates a policy where items are visible to their owner or to everyone
+  /// when marked as public.
+  ///
+  /// This is equivalent to the filter
+  /// `owner_field == principal.id || visibility_field == public_value`.
+  /// Anonymous queries only see public items.
+  ///
+  /// # Arguments
+  ///
+  /// * `owner_field` - The field holding the owner's id.
+  /// * `visibility_field` - The field holding the item's visibility.
+  /// * `public_value` - The visibility value that makes an item public.
+  pub fn owner_or_public(
+    owner_field: impl Into<String>,
+    visibility_field: impl Into<String>,
+    public_value: impl Into<FilterValue>,
+  ) -> Self {
+    let owner_field = owner_field.into();
+    let public = FilterExpr::Compare {
+      field: visibility_field.into(),
+      op: CompareOp::Eq,
+      value: public_value.into(),
+      options: StringOptions::default(),
+    };
+
+    Self::new(move |principal| {
+      let mut allowed = vec![public.clone()];
+      if let Some(principal) = principal {
+        allowed.push(FilterExpr::Compare {
+          field: owner_field.clone(),
+          op: CompareOp::Eq,
+          value: FilterValue::String(principal.id.clone()),
+          options: StringOptions::default(),
+        });
+      }
+      Some(FilterExpr::Or(allowed))
+    })
+  }
+
+  /// Allows principals with the given role to bypass the policy.
+  pub fn bypass_role(mut self, role: impl Into<String>) -> Self {
+    self.bypass_roles.push(role.into());
+    self
+  }
+}
+
+impl<T: Searchable> SearusExtension<T> for AccessControlExtension {
+  /// Combines the principal's security filter with the query's filters.
+  fn before_query(&self, query: &mut Query, _state: &mut SearchState) {
+    let principal = query.principal.as_ref();
+
+    if let Some(principal) = principal {
+      if self
+        .bypass_roles
+        .iter()
+        .any(|role| principal.has_role(role))
+      {
+        return;
+      }
+    }
+
+    let security = match (self.policy)(principal) {
+      Some(filter) => filter,
+      None => return,
+    };
+
+    query.filters = Some(match query.filters.take() {
+      Some(FilterExpr::And(mut exprs)) => {
+        exprs.push(security);
+        FilterExpr::And(exprs)
+      }
+      Some(existing) => FilterExpr::And(vec![existing, security]),
+      None => security,
+    });
+  }
+}
@@ -0,0 +1,114 @@
+//! An extension that removes duplicate results after merging.
+
+use crate::document::{get_field_value, to_document};
+use crate::extension::{SearchState, SearusExtension};
+use crate::types::{Query, Searchable, SearusMatch};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// A function that derives a deduplication key from an item.
+type KeyFn<T> = Box<dyn Fn(&T) -> Option<String> + Send + Sync>;
+
+/// An extension that removes duplicate results in the `after_merge` hook.
+///
+/// Two results are considered duplicates when they produce the same key. The
+/// key is derived either from a (possibly nested) field path or from a custom
+/// closure. When duplicates are found, only the highest-scoring match is kept.
+/// Items that produce no key (e.g., the field is missing) are never removed.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::extensions::DedupExtension;
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Page {
+///     url: String,
+///     title: String,
+/// }
+///
+/// let pages = vec![
+///     Page { url: "/rust".into(), title: "Rust".into() },
+///     Page { url: "/rust".into(), title: "Rust (mirror)".into() },
+/// ];
+///
+/// let rules = SemanticRules::builder()
+///     .field("title", FieldRule::tokenized())
+///     .build();
+///
+/// let engine = SearusEngine::builder()
+///     .with(Box::new(SemanticSearch::new(rules)))
+///     .with_extension(Box::new(DedupExtension::by_field("url")))
+///     .build();
+///
+/// let results = engine.search(&pages, &Query::builder().text("rust").build());
+/// assert_eq!(results.len(), 1);
+/// ```
+pub struct DedupExtension<T> {
+  /// The function used to derive the deduplication key of an item.
+  key: KeyFn<T>,
+}
+
+impl<T> DedupExtension<T> {
+  /// Creates a `DedupExtension` that deduplicates by a field path.
+  ///
+  /// The path supports dot notation for nested fields (e.g., `"source.url"`).
+  /// Values are compared by their JSON representation, so the string `"1"`
+  /// and the number `1` are different keys. Null values produce no key.
+  ///
+  /// # Arguments
+  ///
+  /// * `field` - The path of the field to deduplicate by.
+  pub fn by_field(field: impl Into<String>) -> Self
+  where
+    T: serde::Serialize,
+  {
+    let field = field.into();
+    Self::by_key(move |item: &T| {
+      let value = to_document(item).ok()?;
+      match get_field_value(&value, &field)? {
+        Value::Null => None,
+        value => Some(value.to_string()),
+      }
+    })
+  }
+
+  /// Creates a `DedupExtension` that deduplicates by a custom key function.
+  ///
+  /// Returning `None` from the closure exempts the item from deduplication.
+  ///
+  /// # Arguments
+  ///
+  /// * `key` - A closure that derives the deduplication key from an item.
+  pub fn by_key<F>(key: F) -> Self
+  where
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
+  {
+    Self { key: Box::new(key) }
+  }
+}
+
+impl<T: Searchable> SearusExtension<T> for DedupExtension<T> {
+  /// Removes duplicates from the merged results, keeping the best-scoring match.
+  fn after_merge(
+    &self,
+    _query: &Query,
+    results: &mut Vec<SearusMatch<T>>,
+    _state: &mut SearchState,
+  ) {
+    // Merged results are not sorted yet, so order them by score to make sure
+    // the first occurrence of each key is the one worth keeping.
+    results.sort_by(|a, b| {
+      b.score
+        .partial_cmp(&a.score)
+        .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut seen = HashSet::new();
+    results.retain(|m| match (self.key)(&m.item) {
+      Some(key) => seen.insert(key),
+      None => true,
+    });
+  }
+}
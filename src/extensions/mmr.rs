@@ -0,0 +1,235 @@
+//! An extension that diversifies results with Maximal Marginal Relevance.
+
+use crate::document::{get_field_value, to_document};
+use crate::extension::{SearchState, SearusExtension};
+use crate::types::{Query, Searchable, SearusMatch};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// The representation of an item used to compare it with other items.
+enum Features {
+  /// An embedding vector, compared with cosine similarity.
+  Vector(Vec<f32>),
+  /// A set of lowercase tokens, compared with Jaccard similarity.
+  Tokens(HashSet<String>),
+  /// The item has no usable representation and is never similar to others.
+  Missing,
+}
+
+/// A function that extracts the comparable features of an item.
+type FeatureFn<T> = Box<dyn Fn(&T) -> Features + Send + Sync>;
+
+/// An extension that re-ranks results with Maximal Marginal Relevance (MMR).
+///
+/// MMR greedily builds the result list by picking, at each step, the candidate
+/// that maximizes `lambda * relevance - (1 - lambda) * max_similarity`, where
+/// `max_similarity` is the candidate's highest similarity to any result that
+/// was already picked. This keeps the top of the list from being filled with
+/// near-identical items.
+///
+/// Re-ranking runs in the `before_limit` hook over the best `window` results;
+/// results beyond the window keep their original order. Scores are left
+/// unchanged, only the order is affected.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::extensions::MmrExtension;
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Post {
+///     title: String,
+/// }
+///
+/// // Compare items by the tokens of their title.
+/// let mmr = MmrExtension::<Post>::by_fields(&["title"]).lambda(0.5);
+///
+/// // Or compare items by an embedding stored on the item.
+/// #[derive(Debug, Clone)]
+/// struct Doc {
+///     embedding: Vec<f32>,
+/// }
+/// let mmr = MmrExtension::by_vector(|doc: &Doc| Some(doc.embedding.clone()));
+/// ```
+pub struct MmrExtension<T> {
+  /// Extracts the comparable features of an item.
+  features: FeatureFn<T>,
+  /// The trade-off between relevance (1.0) and diversity (0.0).
+  lambda: f32,
+  /// The number of top results considered for re-ranking.
+  window: usize,
+}
+
+impl<T> MmrExtension<T> {
+  /// Creates an `MmrExtension` that compares items by embedding vectors.
+  ///
+  /// Items for which the closure returns `None` are treated as dissimilar to
+  /// every other item.
+  pub fn by_vector<F>(vector: F) -> Self
+  where
+    F: Fn(&T) -> Option<Vec<f32>> + Send + Sync + 'static,
+  {
+    Self::with_features(Box::new(move |item| match vector(item) {
+      Some(v) => Features::Vector(v),
+      None => Features::Missing,
+    }))
+  }
+
+  /// Creates an `MmrExtension` that compares items by the token overlap
+  /// (Jaccard similarity) of the given fields.
+  pub fn by_fields(fields: &[&str]) -> Self
+  where
+    T: serde::Serialize,
+  {
+    let fields: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+    Self::with_features(Box::new(move |item| {
+      let value = match to_document(item) {
+        Ok(v) => v,
+        Err(_) => return Features::Missing,
+      };
+
+      let mut tokens = HashSet::new();
+      for field in &fields {
+        if let Some(Value::String(text)) = get_field_value(&value, field) {
+          tokens.extend(
+            text
+              .split(|c: char| !c.is_alphanumeric())
+              .filter(|t| !t.is_empty())
+              .map(str::to_lowercase),
+          );
+        }
+      }
+      Features::Tokens(tokens)
+    }))
+  }
+
+  fn with_features(features: FeatureFn<T>) -> Self {
+    Self {
+      features,
+      lambda: 0.7,
+      window: 100,
+    }
+  }
+
+  /// Sets the trade-off between relevance and diversity.
+  ///
+  /// `1.0` keeps the original ranking, `0.0` only optimizes for diversity.
+  /// The default is `0.7`.
+  pub fn lambda(mut self, lambda: f32) -> Self {
+    self.lambda = lambda.clamp(0.0, 1.0);
+    self
+  }
+
+  /// Sets how many of the top results are re-ranked. The default is 100.
+  pub fn window(mut self, window: usize) -> Self {
+    self.window = window;
+    self
+  }
+}
+
+impl<T: Searchable> SearusExtension<T> for MmrExtension<T> {
+  /// Re-orders the top results to balance relevance and diversity.
+  fn before_limit(
+    &self,
+    _query: &Query,
+    results: &mut Vec<SearusMatch<T>>,
+    _state: &mut SearchState,
+  ) {
+    let window = self.window.min(results.len());
+    if window < 2 {
+      return;
+    }
+
+    let rest = results.split_off(window);
+    // Each candidate keeps its highest similarity to the selected results,
+    // updated against each newly selected one.
+    let mut candidates: Vec<(SearusMatch<T>, Features, f32)> = results
+      .drain(..)
+      .map(|m| {
+        let features = (self.features)(&m.item);
+        (m, features, 0.0)
+      })
+      .collect();
+
+    let max_score = candidates
+      .iter()
+      .map(|(m, _, _)| m.score)
+      .fold(f32::MIN, f32::max);
+    let max_score = if max_score > 0.0 { max_score } else { 1.0 };
+
+    let mut selected: Vec<SearusMatch<T>> = Vec::with_capacity(window);
+    while !candidates.is_empty() {
+      let mut best = 0;
+      let mut best_value = f32::NEG_INFINITY;
+
+      for (i, (m, _, redundancy)) in candidates.iter().enumerate() {
+        let relevance = m.score / max_score;
+        let value = self.lambda * relevance - (1.0 - self.lambda) * redundancy;
+
+        if value > best_value {
+          best_value = value;
+          best = i;
+        }
+      }
+
+      let (m, features, _) = candidates.remove(best);
+      for (_, other, redundancy) in &mut candidates {
+        *redundancy = redundancy.max(similarity(other, &features));
+      }
+      selected.push(m);
+    }
+
+    results.extend(selected);
+    results.extend(rest);
+  }
+}
+
+/// Calculates the similarity between two feature representations.
+fn similarity(a: &Features, b: &Features) -> f32 {
+  match (a, b) {
+    (Features::Vector(a), Features::Vector(b)) => {
+      if a.len() != b.len() {
+        return 0.0;
+      }
+      let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+      let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+      let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+      if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+      } else {
+        dot / (norm_a * norm_b)
+      }
+    }
+    (Features::Tokens(a), Features::Tokens(b)) => {
+      let union = a.union(b).count();
+      if union == 0 {
+        0.0
+      } else {
+        a.intersection(b).count() as f32 / union as f32
+      }
+    }
+    _ => 0.0,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_mmr_demotes_near_duplicates() {
+    let vectors = [vec![1.0, 0.0], vec![0.99, 0.01], vec![0.0, 1.0]];
+    let mut results: Vec<SearusMatch<usize>> = vec![
+      SearusMatch::new(0, 1.0, 0),
+      SearusMatch::new(1, 0.95, 1),
+      SearusMatch::new(2, 0.8, 2),
+    ];
+
+    let mmr = MmrExtension::by_vector(move |i: &usize| Some(vectors[*i].clone())).lambda(0.5);
+    mmr.before_limit(&Query::default(), &mut results, &mut SearchState::new());
+
+    let order: Vec<usize> = results.iter().map(|m| m.item).collect();
+    assert_eq!(order, vec![0, 2, 1]);
+  }
+}
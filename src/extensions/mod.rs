@@ -0,0 +1,32 @@
+//! A collection of built-in `SearusExtension` implementations.
+//!
+//! These extensions cover lifecycle tweaks that most applications end up
+//! writing themselves. They are registered like any other extension through
+//! [`SearusEngineBuilder::with_extension`](crate::engine::SearusEngineBuilder::with_extension).
+//!
+//! # Available Extensions
+//!
+//! - [`DedupExtension`](crate::extensions::DedupExtension): Removes duplicate results by a field path or key function.
+//! - [`AccessControlExtension`](crate::extensions::AccessControlExtension): Injects per-request security filters into every query.
+//! - [`SynonymExtension`](crate::extensions::SynonymExtension): Expands query text with synonyms from a `SynonymMap`.
+//! - [`MmrExtension`](crate::extensions::MmrExtension): Diversifies the top results with Maximal Marginal Relevance.
+//! - [`SpellCorrectionExtension`](crate::extensions::SpellCorrectionExtension): Rewrites misspelled query terms.
+
+/// Implements an extension that restricts results to what the requester may see.
+pub mod access;
+/// Implements an extension that removes duplicate results.
+pub mod dedup;
+/// Implements an extension that diversifies results with Maximal Marginal Relevance.
+pub mod mmr;
+/// Implements an extension that corrects misspelled query terms.
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+pub mod spelling;
+/// Implements an extension that expands query text with synonyms.
+pub mod synonym;
+
+pub use access::AccessControlExtension;
+pub use dedup::DedupExtension;
+pub use mmr::MmrExtension;
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+pub use spelling::SpellCorrectionExtension;
+pub use synonym::SynonymExtension;
@@ -0,0 +1,61 @@
+//! An extension that corrects misspelled query terms.
+
+use crate::extension::{SearchState, SearusExtension};
+use crate::spelling::SpellCorrector;
+use crate::types::{Query, Searchable};
+
+/// An extension that rewrites misspelled terms in `Query::text` before searching.
+///
+/// Only terms that are not part of the corrector's vocabulary and that have a
+/// vocabulary term within `max_distance` edits are replaced. The default
+/// distance of 1 keeps rewrites limited to obvious typos.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::extensions::SpellCorrectionExtension;
+/// use searus::spelling::SpellCorrector;
+///
+/// let mut corrector = SpellCorrector::default();
+/// corrector.add_text("fearless concurrency in rust");
+///
+/// let ext = SpellCorrectionExtension::new(corrector);
+///
+/// let mut query = Query::builder().text("fearles concurrency").build();
+/// SearusExtension::<String>::before_query(&ext, &mut query, &mut SearchState::new());
+/// assert_eq!(query.text.as_deref(), Some("fearless concurrency"));
+/// ```
+pub struct SpellCorrectionExtension {
+  /// The corrector used to rewrite query terms.
+  corrector: SpellCorrector,
+  /// The maximum edit distance of a rewrite.
+  max_distance: usize,
+}
+
+impl SpellCorrectionExtension {
+  /// Creates a new `SpellCorrectionExtension` with a maximum distance of 1.
+  pub fn new(corrector: SpellCorrector) -> Self {
+    Self {
+      corrector,
+      max_distance: 1,
+    }
+  }
+
+  /// Sets the maximum edit distance of a rewrite.
+  pub fn max_distance(mut self, max_distance: usize) -> Self {
+    self.max_distance = max_distance;
+    self
+  }
+}
+
+impl<T: Searchable> SearusExtension<T> for SpellCorrectionExtension {
+  /// Replaces misspelled terms in the query text with their corrections.
+  fn before_query(&self, query: &mut Query, _state: &mut SearchState) {
+    if let Some(text) = &query.text {
+      if let Some(corrected) = self.corrector.correct_text(text, self.max_distance) {
+        query.text = Some(corrected);
+      }
+    }
+  }
+}
@@ -0,0 +1,57 @@
+//! An extension that expands query text with synonyms.
+
+use crate::extension::{SearchState, SearusExtension};
+use crate::rewrite::{QueryRewriter, SynonymExpansion};
+use crate::synonyms::SynonymMap;
+use crate::types::{Query, Searchable};
+
+/// An extension that rewrites `Query::text` to include synonyms of its terms.
+///
+/// This is the searcher-agnostic way of using a [`SynonymMap`]: synonyms are
+/// appended to the query text, so every text searcher sees them. Because plain
+/// text cannot carry weights, appended synonyms count as much as the original
+/// terms. Use `min_weight` to only append strong synonyms, or configure the
+/// map directly on `SemanticSearch` for weighted scoring.
+///
+/// This is a standalone version of the
+/// [`SynonymExpansion`](crate::rewrite::SynonymExpansion) rewrite stage.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::extensions::SynonymExtension;
+/// use searus::synonyms::SynonymMap;
+///
+/// let ext = SynonymExtension::new(SynonymMap::new().with("car", "automobile", 0.8));
+///
+/// let mut query = Query::builder().text("red car").build();
+/// SearusExtension::<String>::before_query(&ext, &mut query, &mut SearchState::new());
+/// assert_eq!(query.text.as_deref(), Some("red car automobile"));
+/// ```
+pub struct SynonymExtension {
+  /// The rewrite stage performing the expansion.
+  expansion: SynonymExpansion,
+}
+
+impl SynonymExtension {
+  /// Creates a new `SynonymExtension` that appends all synonyms.
+  pub fn new(synonyms: SynonymMap) -> Self {
+    Self {
+      expansion: SynonymExpansion::new(synonyms),
+    }
+  }
+
+  /// Sets the minimum weight a synonym needs to be appended to the query.
+  pub fn min_weight(mut self, min_weight: f32) -> Self {
+    self.expansion = self.expansion.min_weight(min_weight);
+    self
+  }
+}
+
+impl<T: Searchable> SearusExtension<T> for SynonymExtension {
+  /// Appends the synonyms of the query terms to the query text.
+  fn before_query(&self, query: &mut Query, _state: &mut SearchState) {
+    self.expansion.rewrite(query);
+  }
+}
@@ -5,10 +5,14 @@
 //! for more complex, structured filtering beyond simple keyword matching.
 
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cmp::Ordering;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+use crate::document::{get_field_value, to_document};
+use crate::scoring::{parse_timestamp, timestamp_of};
 use crate::types::Searchable;
 
 /// An enum representing the nodes of a filter expression AST.
@@ -28,6 +32,27 @@ pub enum FilterExpr {
     op: CompareOp,
     /// The value to compare against.
     value: FilterValue,
+    /// How strings are compared.
+    #[serde(default, skip_serializing_if = "StringOptions::is_default")]
+    options: StringOptions,
+  },
+  /// A range check on a field, such as "10 <= price <= 50".
+  ///
+  /// Equivalent to an `And` of two comparisons, but kept as a single node so
+  /// that ranges are easy to recognize.
+  Between {
+    /// The name of the field to compare, which can be nested (e.g., "author.name").
+    field: String,
+    /// The lower bound of the range.
+    low: FilterValue,
+    /// The upper bound of the range.
+    high: FilterValue,
+    /// Whether the bounds themselves are in the range.
+    #[serde(default = "default_inclusive")]
+    inclusive: bool,
+    /// How strings are compared.
+    #[serde(default, skip_serializing_if = "StringOptions::is_default")]
+    options: StringOptions,
   },
   /// A logical AND operation.
   ///
@@ -41,6 +66,18 @@ pub enum FilterExpr {
   ///
   /// The expression inverts the result of the sub-expression.
   Not(Box<FilterExpr>),
+  /// True if the field is an array with at least one element matching the
+  /// sub-expression, such as "any review has rating >= 4".
+  ///
+  /// Field names in the sub-expression are relative to each element, and an
+  /// empty field name refers to the element itself.
+  Any(String, Box<FilterExpr>),
+  /// True if the field is an array whose elements all match the
+  /// sub-expression (including an empty array).
+  ///
+  /// Field names in the sub-expression are relative to each element, and an
+  /// empty field name refers to the element itself.
+  All(String, Box<FilterExpr>),
 }
 
 /// Helper to create a comparison filter.
@@ -48,15 +85,15 @@ pub enum FilterExpr {
 /// # Examples
 ///
 /// ```rust
-/// use searus::filter::{FilterExpr, CompareOp, FilterValue};
+/// use searus::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
 ///
 /// let filter = FilterExpr::Compare {
 ///     field: "price".to_string(),
 ///     op: CompareOp::Lt,
 ///     value: FilterValue::Number(50.0),
+///     options: StringOptions::default(),
 /// };
 /// ```
-
 impl FilterExpr {
   /// Evaluates the filter expression against a given item.
   ///
@@ -66,7 +103,7 @@ impl FilterExpr {
   /// # Examples
   ///
   /// ```rust
-  /// use searus::filter::{FilterExpr, CompareOp, FilterValue};
+  /// use searus::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
   /// use serde::Serialize;
   ///
   /// #[derive(Serialize)]
@@ -84,39 +121,151 @@ impl FilterExpr {
   ///     field: "price".to_string(),
   ///     op: CompareOp::Lt,
   ///     value: FilterValue::Number(50.0),
+  ///     options: StringOptions::default(),
   /// };
   ///
   /// assert!(filter.evaluate(&book));
   /// ```
   pub fn evaluate<T: serde::Serialize>(&self, item: &T) -> bool {
-    let json_value = match serde_json::to_value(item) {
-      Ok(v) => v,
-      Err(_) => return false,
-    };
-
-    self.evaluate_value(&json_value)
+    match to_document(item) {
+      Ok(document) => self.evaluate_json(&document),
+      Err(_) => false,
+    }
   }
 
-  fn evaluate_value(&self, item: &serde_json::Value) -> bool {
+  /// Evaluates the filter expression against an already serialized item.
+  ///
+  /// Use this to avoid serializing the same item again when it is checked
+  /// against several filters, or by several searchers.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// let filter = Query::filter(Query::COMPARE).lt("price", 50).build();
+  ///
+  /// assert!(filter.evaluate_json(&json!({ "price": 40 })));
+  /// assert!(!filter.evaluate_json(&json!({ "price": 60 })));
+  /// ```
+  pub fn evaluate_json(&self, item: &serde_json::Value) -> bool {
     match self {
-      FilterExpr::Compare { field, op, value } => {
+      FilterExpr::Compare {
+        field,
+        op,
+        value,
+        options,
+      } => {
+        let field_value = get_field_value(item, field);
+        compare_values(field_value, op, value, options)
+      }
+      FilterExpr::Between {
+        field,
+        low,
+        high,
+        inclusive,
+        options,
+      } => {
         let field_value = get_field_value(item, field);
-        compare_values(field_value, op, value)
+        let (low_op, high_op) = if *inclusive {
+          (CompareOp::Ge, CompareOp::Le)
+        } else {
+          (CompareOp::Gt, CompareOp::Lt)
+        };
+        compare_values(field_value, &low_op, low, options)
+          && compare_values(field_value, &high_op, high, options)
+      }
+      FilterExpr::And(exprs) => exprs.iter().all(|e| e.evaluate_json(item)),
+      FilterExpr::Or(exprs) => exprs.iter().any(|e| e.evaluate_json(item)),
+      FilterExpr::Not(expr) => !expr.evaluate_json(item),
+      FilterExpr::Any(field, expr) => match get_field_value(item, field) {
+        Some(serde_json::Value::Array(elements)) => {
+          elements.iter().any(|element| expr.evaluate_json(element))
+        }
+        _ => false,
+      },
+      FilterExpr::All(field, expr) => match get_field_value(item, field) {
+        Some(serde_json::Value::Array(elements)) => {
+          elements.iter().all(|element| expr.evaluate_json(element))
+        }
+        _ => false,
+      },
+    }
+  }
+
+  /// Sets how strings are compared by every comparison in the expression,
+  /// including those nested in `And`, `Or`, `Not`, `Any` and `All`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// let filter = Query::filter(Query::COMPARE)
+  ///     .eq("city", "Malmo")
+  ///     .build()
+  ///     .with_string_options(StringOptions::new().case_sensitive(false).fold_diacritics(true));
+  ///
+  /// assert!(filter.evaluate_json(&json!({ "city": "Malmö" })));
+  /// assert!(filter.evaluate_json(&json!({ "city": "MALMÖ" })));
+  /// ```
+  pub fn with_string_options(mut self, string_options: StringOptions) -> Self {
+    self.set_string_options(&string_options);
+    self
+  }
+
+  fn set_string_options(&mut self, string_options: &StringOptions) {
+    match self {
+      FilterExpr::Compare { options, .. } | FilterExpr::Between { options, .. } => {
+        *options = string_options.clone();
+      }
+      FilterExpr::And(exprs) | FilterExpr::Or(exprs) => {
+        for expr in exprs {
+          expr.set_string_options(string_options);
+        }
+      }
+      FilterExpr::Not(expr) | FilterExpr::Any(_, expr) | FilterExpr::All(_, expr) => {
+        expr.set_string_options(string_options)
       }
-      FilterExpr::And(exprs) => exprs.iter().all(|e| e.evaluate_value(item)),
-      FilterExpr::Or(exprs) => exprs.iter().any(|e| e.evaluate_value(item)),
-      FilterExpr::Not(expr) => !expr.evaluate_value(item),
     }
   }
 }
 
-/// Helper function to get a value from a nested JSON object using dot notation.
-fn get_field_value<'a>(item: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
-  let mut current = item;
-  for part in path.split('.') {
-    current = current.get(part)?;
+fn default_inclusive() -> bool {
+  true
+}
+
+/// Builds a JSON object containing only the given field paths of a value.
+///
+/// Nested paths (e.g., `author.name`) keep their nesting in the result.
+/// Missing fields are omitted.
+pub(crate) fn select_fields(item: &serde_json::Value, fields: &[String]) -> serde_json::Value {
+  let mut selected = serde_json::Value::Object(serde_json::Map::new());
+
+  for field in fields {
+    let Some(value) = get_field_value(item, field) else {
+      continue;
+    };
+
+    let mut target = &mut selected;
+    let mut parts = field.split('.').peekable();
+    while let Some(part) = parts.next() {
+      let Some(object) = target.as_object_mut() else {
+        break;
+      };
+      if parts.peek().is_none() {
+        object.insert(part.to_string(), value.clone());
+        break;
+      }
+      target = object
+        .entry(part)
+        .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+    }
   }
-  Some(current)
+
+  selected
 }
 
 /// Helper function to compare a JSON value from the item against a filter value.
@@ -124,17 +273,64 @@ fn compare_values(
   field_value: Option<&serde_json::Value>,
   op: &CompareOp,
   target_value: &FilterValue,
+  options: &StringOptions,
 ) -> bool {
   let field_value = match field_value {
     Some(v) => v,
     None => return false,
   };
 
+  if let CompareOp::In | CompareOp::NotIn = op {
+    let FilterValue::List(targets) = target_value else {
+      return false;
+    };
+    // Array fields are in the list if any of their elements is.
+    let found = match field_value {
+      serde_json::Value::Array(arr) => arr
+        .iter()
+        .any(|elem| targets.iter().any(|t| value_eq(elem, t, options))),
+      value => targets.iter().any(|t| value_eq(value, t, options)),
+    };
+    return found == (*op == CompareOp::In);
+  }
+
+  if op.is_affix() {
+    let FilterValue::String(affix) = target_value else {
+      return false;
+    };
+    // Array fields match if any of their strings does.
+    return match field_value {
+      serde_json::Value::String(s) => affix_match(s, op, affix, options),
+      serde_json::Value::Array(arr) => arr.iter().any(|elem| {
+        elem
+          .as_str()
+          .is_some_and(|s| affix_match(s, op, affix, options))
+      }),
+      _ => false,
+    };
+  }
+
+  if let FilterValue::Date(date) = target_value {
+    return match (timestamp_of(field_value), parse_timestamp(date)) {
+      (Some(a), Some(b)) => compare_ord(&a, op, &b),
+      _ => false,
+    };
+  }
+
   match (field_value, target_value) {
     (serde_json::Value::String(s), FilterValue::String(t)) if *op == CompareOp::Contains => {
-      s.to_lowercase().contains(&t.to_lowercase())
+      options.key(s, op).contains(options.key(t, op).as_ref())
+    }
+    (serde_json::Value::String(s), FilterValue::String(t)) => {
+      // Dates order chronologically, whatever their offsets and precision.
+      // Strings that aren't RFC 3339 timestamps compare as strings.
+      if let CompareOp::Lt | CompareOp::Le | CompareOp::Gt | CompareOp::Ge = op {
+        if let (Some(a), Some(b)) = (parse_timestamp(s), parse_timestamp(t)) {
+          return compare_ord(&a, op, &b);
+        }
+      }
+      compare_ord(&options.compare(s, t, op), op, &Ordering::Equal)
     }
-    (serde_json::Value::String(s), FilterValue::String(t)) => compare_ord(s, op, t),
     (serde_json::Value::Number(n), FilterValue::Number(t)) => {
       if let Some(f) = n.as_f64() {
         compare_ord(&f, op, t)
@@ -148,18 +344,35 @@ fn compare_values(
       _ => false,
     },
     (serde_json::Value::Array(arr), target) => match op {
-      CompareOp::Contains => arr.iter().any(|elem| match (elem, target) {
-        (serde_json::Value::String(s), FilterValue::String(t)) => s == t,
-        (serde_json::Value::Number(n), FilterValue::Number(t)) => n.as_f64() == Some(*t),
-        (serde_json::Value::Bool(b), FilterValue::Bool(t)) => b == t,
-        _ => false,
-      }),
+      CompareOp::Contains => arr.iter().any(|elem| value_eq(elem, target, options)),
       _ => false,
     },
     _ => false,
   }
 }
 
+/// Checks a string against a prefix or suffix operator.
+fn affix_match(s: &str, op: &CompareOp, affix: &str, options: &StringOptions) -> bool {
+  let (s, affix) = (options.key(s, op), options.key(affix, op));
+  match op {
+    CompareOp::StartsWith | CompareOp::StartsWithIgnoreCase => s.starts_with(affix.as_ref()),
+    CompareOp::EndsWith | CompareOp::EndsWithIgnoreCase => s.ends_with(affix.as_ref()),
+    _ => false,
+  }
+}
+
+/// Checks whether a JSON scalar equals a filter value.
+fn value_eq(value: &serde_json::Value, target: &FilterValue, options: &StringOptions) -> bool {
+  match (value, target) {
+    (serde_json::Value::String(s), FilterValue::String(t)) => {
+      options.compare(s, t, &CompareOp::Eq) == Ordering::Equal
+    }
+    (serde_json::Value::Number(n), FilterValue::Number(t)) => n.as_f64() == Some(*t),
+    (serde_json::Value::Bool(b), FilterValue::Bool(t)) => b == t,
+    _ => false,
+  }
+}
+
 fn compare_ord<T: PartialOrd>(a: &T, op: &CompareOp, b: &T) -> bool {
   match op {
     CompareOp::Eq => a == b,
@@ -168,7 +381,7 @@ fn compare_ord<T: PartialOrd>(a: &T, op: &CompareOp, b: &T) -> bool {
     CompareOp::Le => a <= b,
     CompareOp::Gt => a > b,
     CompareOp::Ge => a >= b,
-    CompareOp::Contains => false,
+    _ => false,
   }
 }
 
@@ -187,24 +400,205 @@ pub enum CompareOp {
   Gt,
   /// Greater than or equal to (`>=`)
   Ge,
-  /// Contains (for strings and arrays)
+  /// Contains (for strings and arrays). Substrings ignore case unless
+  /// [`StringOptions::case_sensitive`] says otherwise.
   Contains,
+  /// Equal to one of the values of a [`FilterValue::List`]. Array fields
+  /// match if any of their elements is in the list.
+  In,
+  /// Present and not equal to any of the values of a [`FilterValue::List`].
+  NotIn,
+  /// Starts with a string (for strings and arrays of strings)
+  StartsWith,
+  /// Ends with a string (for strings and arrays of strings)
+  EndsWith,
+  /// Starts with a string, ignoring case
+  StartsWithIgnoreCase,
+  /// Ends with a string, ignoring case
+  EndsWithIgnoreCase,
+}
+
+impl CompareOp {
+  /// Returns `true` for the prefix and suffix operators.
+  fn is_affix(&self) -> bool {
+    matches!(
+      self,
+      CompareOp::StartsWith
+        | CompareOp::EndsWith
+        | CompareOp::StartsWithIgnoreCase
+        | CompareOp::EndsWithIgnoreCase
+    )
+  }
+}
+
+/// Options controlling how a comparison treats strings.
+///
+/// By default, strings are compared exactly, except that `Contains` ignores
+/// case, as do the `*IgnoreCase` operators whatever the options say.
+///
+/// * Case-insensitive comparisons lowercase both strings first.
+/// * Folding maps Latin letters with diacritics to their base letter,
+///   expands ligatures such as `ß` and `æ`, and maps full-width forms to
+///   ASCII, so `Crème Brûlée` equals `Creme Brulee`.
+/// * Collation orders strings as a dictionary does for `Lt`, `Le`, `Gt`,
+///   `Ge` and `Between`: by their letters first, ignoring case and
+///   diacritics, which then only break ties. Without it, strings are ordered
+///   by code point, so `Zebra` sorts before `apple` and `éclair` after `zoo`.
+///   Language-specific tailorings (such as `ä` sorting after `z` in Swedish)
+///   are not applied.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::filter::StringOptions;
+///
+/// let options = StringOptions::new()
+///     .case_sensitive(false)
+///     .fold_diacritics(true)
+///     .collate(true);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StringOptions {
+  /// Whether case matters. `None` uses the operator's default.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub case_sensitive: Option<bool>,
+  /// Whether diacritics, ligatures and full-width forms are folded.
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub fold_diacritics: bool,
+  /// Whether ordering comparisons use dictionary order.
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub collate: bool,
+}
+
+impl StringOptions {
+  /// Creates options that keep each operator's default behavior.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets whether case matters.
+  pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+    self.case_sensitive = Some(case_sensitive);
+    self
+  }
+
+  /// Sets whether diacritics, ligatures and full-width forms are folded.
+  pub fn fold_diacritics(mut self, fold_diacritics: bool) -> Self {
+    self.fold_diacritics = fold_diacritics;
+    self
+  }
+
+  /// Sets whether ordering comparisons use dictionary order.
+  pub fn collate(mut self, collate: bool) -> Self {
+    self.collate = collate;
+    self
+  }
+
+  fn is_default(&self) -> bool {
+    *self == Self::default()
+  }
+
+  /// Returns the form of a string that is compared under these options.
+  fn key<'a>(&self, s: &'a str, op: &CompareOp) -> Cow<'a, str> {
+    let case_sensitive = match op {
+      CompareOp::StartsWithIgnoreCase | CompareOp::EndsWithIgnoreCase => false,
+      CompareOp::Contains => self.case_sensitive.unwrap_or(false),
+      _ => self.case_sensitive.unwrap_or(true),
+    };
+    let s = if self.fold_diacritics {
+      Cow::Owned(fold_diacritics(s))
+    } else {
+      Cow::Borrowed(s)
+    };
+    if case_sensitive {
+      s
+    } else {
+      Cow::Owned(s.to_lowercase())
+    }
+  }
+
+  /// Orders two strings under these options.
+  fn compare(&self, a: &str, b: &str, op: &CompareOp) -> Ordering {
+    let ordering = self.key(a, op).cmp(&self.key(b, op));
+    if self.collate {
+      let letters = |s: &str| fold_diacritics(s).to_lowercase();
+      letters(a).cmp(&letters(b)).then(ordering)
+    } else {
+      ordering
+    }
+  }
+}
+
+/// Maps Latin letters with diacritics to their base letters, expands
+/// ligatures, maps full-width forms to ASCII and drops combining marks.
+fn fold_diacritics(s: &str) -> String {
+  // Base letters of U+00C0..U+00FF and U+0100..U+017F. Ligatures are
+  // expanded before the tables are consulted.
+  const LATIN_1: &str = "AAAAAAACEEEEIIIIDNOOOOO×OUUUUYTsaaaaaaaceeeeiiiidnooooo÷ouuuuyty";
+  const LATIN_EXTENDED_A: &str = "AaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIiIi\
+    IiJjKkkLlLlLlLlLlNnNnNnnNnOoOoOoOoRrRrRrSsSsSsSsTtTtTtUuUuUuUuUuUuWwYyYZzZzZzs";
+
+  let mut folded = String::with_capacity(s.len());
+  for c in s.chars() {
+    let expanded = match c {
+      'Æ' => "AE",
+      'æ' => "ae",
+      'Þ' => "TH",
+      'þ' => "th",
+      'ß' => "ss",
+      'ẞ' => "SS",
+      'Ĳ' => "IJ",
+      'ĳ' => "ij",
+      'Œ' => "OE",
+      'œ' => "oe",
+      'ﬀ' => "ff",
+      'ﬁ' => "fi",
+      'ﬂ' => "fl",
+      'ﬃ' => "ffi",
+      'ﬄ' => "ffl",
+      'ﬅ' | 'ﬆ' => "st",
+      _ => "",
+    };
+    if !expanded.is_empty() {
+      folded.push_str(expanded);
+      continue;
+    }
+    let code = c as u32;
+    match code {
+      0x0300..=0x036F => {}
+      0x00C0..=0x00FF => folded.extend(LATIN_1.chars().nth((code - 0xC0) as usize)),
+      0x0100..=0x017F => folded.extend(LATIN_EXTENDED_A.chars().nth((code - 0x100) as usize)),
+      0xFF01..=0xFF5E => folded.extend(char::from_u32(code - 0xFEE0)),
+      _ => folded.push(c),
+    }
+  }
+  folded
 }
 
 /// Represents the possible types of values used in filter expressions.
 ///
-/// The `#[serde(untagged)]` attribute allows for flexible deserialization from
-/// JSON, as it will try to match the value to one of the variants without
-/// requiring a specific tag.
+/// Values are deserialized from plain JSON strings, numbers, booleans, and
+/// arrays without requiring a tag. Dates are the exception: they are written
+/// as `{"Date": "2024-05-01T12:00:00Z"}`, since they would otherwise read as
+/// strings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
 pub enum FilterValue {
+  /// An RFC 3339 date or date-time, compared chronologically
+  /// with date strings or epoch seconds in the field. Date-times without an
+  /// offset are interpreted as UTC.
+  Date(String),
   /// A string value.
+  #[serde(untagged)]
   String(String),
   /// A floating-point number value.
+  #[serde(untagged)]
   Number(f64),
   /// A boolean value.
+  #[serde(untagged)]
   Bool(bool),
+  /// A list of values, for the `In` and `NotIn` operators.
+  #[serde(untagged)]
+  List(Vec<FilterValue>),
 }
 
 impl From<String> for FilterValue {
@@ -249,6 +643,12 @@ impl From<f64> for FilterValue {
   }
 }
 
+impl<T: Into<FilterValue>> From<Vec<T>> for FilterValue {
+  fn from(values: Vec<T>) -> Self {
+    FilterValue::List(values.into_iter().map(Into::into).collect())
+  }
+}
+
 pub fn filter_items<T>(items: &[T], filters: &FilterExpr) -> Vec<T>
 where
   T: Searchable + Clone + Serialize,
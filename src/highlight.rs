@@ -0,0 +1,358 @@
+//! Match highlighting.
+//!
+//! When [`SearchOptions::highlight`](crate::types::SearchOptions::highlight) is
+//! enabled, the text searchers record where each matched term occurs in the
+//! matched fields. The positions are exposed as [`Highlight`]s on
+//! [`SearusMatch::highlights`](crate::types::SearusMatch::highlights), and
+//! [`highlight_text`] can wrap them in markup such as `<em>` tags.
+//!
+//! For long fields, a [`SnippetExtractor`] extracts a short excerpt around the
+//! densest cluster of matched terms, to be used as a result preview.
+
+use serde::{Deserialize, Serialize};
+
+/// The position of a matched term within a field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Highlight {
+  /// The field in which the term was matched.
+  pub field: String,
+  /// The (lowercase) term that matched.
+  pub term: String,
+  /// The byte offset where the match starts in the field text.
+  pub start: usize,
+  /// The byte offset where the match ends (exclusive) in the field text.
+  pub end: usize,
+  /// The character offset where the match starts in the field text.
+  pub char_start: usize,
+  /// The character offset where the match ends (exclusive) in the field text.
+  pub char_end: usize,
+}
+
+/// Finds the occurrences of the given terms among the tokens of a field.
+///
+/// The tokens are produced by the searcher's own analysis of `text`, each
+/// paired with its byte range, so that analyzed terms (e.g., stems) are
+/// highlighted at the words they originate from.
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+pub(crate) fn find_terms(
+  field: &str,
+  text: &str,
+  tokens: Vec<(String, std::ops::Range<usize>)>,
+  terms: &[String],
+) -> Vec<Highlight> {
+  let mut highlights = Vec::new();
+  if terms.is_empty() {
+    return highlights;
+  }
+
+  // Count characters incrementally, as tokens come in order.
+  let mut bytes = 0;
+  let mut chars = 0;
+
+  for (token, range) in tokens {
+    if !terms.contains(&token) || range.start < bytes {
+      continue;
+    }
+
+    chars += text[bytes..range.start].chars().count();
+    let char_start = chars;
+    chars += text[range.clone()].chars().count();
+    bytes = range.end;
+
+    highlights.push(Highlight {
+      field: field.to_string(),
+      term: token,
+      start: range.start,
+      end: range.end,
+      char_start,
+      char_end: chars,
+    });
+  }
+
+  highlights
+}
+
+/// Wraps the highlights of a field in the given markup.
+///
+/// Only the highlights for `field` are applied. Overlapping or out-of-bounds
+/// highlights are skipped.
+///
+/// # Arguments
+///
+/// * `text` - The text of the field, as it was searched.
+/// * `field` - The field the text belongs to.
+/// * `highlights` - The highlights of a match.
+/// * `pre` - The markup inserted before each match (e.g., `<em>`).
+/// * `post` - The markup inserted after each match (e.g., `</em>`).
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::highlight::highlight_text;
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Post { title: String }
+///
+/// let posts = vec![Post { title: "Fast search in Rust".into() }];
+/// let rules = SemanticRules::builder().field("title", FieldRule::bm25()).build();
+/// let engine = SearusEngine::builder()
+///     .with(Box::new(SemanticSearch::new(rules)))
+///     .build();
+///
+/// let query = Query::builder()
+///     .text("rust search")
+///     .options(SearchOptions::default().highlight(true))
+///     .build();
+/// let results = engine.search(&posts, &query);
+///
+/// let html = highlight_text(&posts[0].title, "title", &results[0].highlights, "<em>", "</em>");
+/// assert_eq!(html, "Fast <em>search</em> in <em>Rust</em>");
+/// ```
+pub fn highlight_text(
+  text: &str,
+  field: &str,
+  highlights: &[Highlight],
+  pre: &str,
+  post: &str,
+) -> String {
+  let mut spans: Vec<(usize, usize)> = highlights
+    .iter()
+    .filter(|h| h.field == field)
+    .map(|h| (h.start, h.end))
+    .collect();
+  spans.sort();
+
+  let mut out = String::with_capacity(text.len());
+  let mut last = 0;
+
+  for (start, end) in spans {
+    if start < last
+      || end > text.len()
+      || !text.is_char_boundary(start)
+      || !text.is_char_boundary(end)
+    {
+      continue;
+    }
+    out.push_str(&text[last..start]);
+    out.push_str(pre);
+    out.push_str(&text[start..end]);
+    out.push_str(post);
+    last = end;
+  }
+
+  out.push_str(&text[last..]);
+  out
+}
+
+/// Extracts short excerpts of long fields around the matched terms.
+///
+/// The excerpt is a window of at most `length` characters centered on the
+/// part of the text with the most highlights. It is widened or narrowed to
+/// word boundaries, and an ellipsis marks text cut at either end. Matched
+/// terms within the excerpt can optionally be wrapped in markup.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::highlight::{Highlight, SnippetExtractor};
+///
+/// let text = "Rust is a language. It is fast. Searching with a search engine in \
+///             Rust is fun, and search results are quick to show.";
+///
+/// // Highlights as recorded by a search for "search".
+/// let highlights: Vec<Highlight> = ["Searching", "search"]
+///     .iter()
+///     .flat_map(|word| text.match_indices(word))
+///     .map(|(start, word)| Highlight {
+///         field: "content".into(),
+///         term: word.to_lowercase(),
+///         start,
+///         end: start + word.len(),
+///         char_start: start,
+///         char_end: start + word.len(),
+///     })
+///     .collect();
+///
+/// let snippet = SnippetExtractor::new()
+///     .length(40)
+///     .markup("[", "]")
+///     .extract(text, "content", &highlights);
+///
+/// assert_eq!(snippet, "…fast. [Searching] with a [search] engine…");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SnippetExtractor {
+  /// The maximum length of the excerpt, in characters, excluding markup.
+  length: usize,
+  /// The marker added where text was cut.
+  ellipsis: String,
+  /// The markup wrapped around matched terms, if any.
+  markup: Option<(String, String)>,
+}
+
+impl Default for SnippetExtractor {
+  fn default() -> Self {
+    Self {
+      length: 160,
+      ellipsis: "…".to_string(),
+      markup: None,
+    }
+  }
+}
+
+impl SnippetExtractor {
+  /// Creates a new `SnippetExtractor` producing excerpts of up to 160 characters.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the maximum length of the excerpt, in characters.
+  pub fn length(mut self, length: usize) -> Self {
+    self.length = length.max(1);
+    self
+  }
+
+  /// Sets the marker added where text was cut (`…` by default).
+  pub fn ellipsis(mut self, ellipsis: impl Into<String>) -> Self {
+    self.ellipsis = ellipsis.into();
+    self
+  }
+
+  /// Wraps matched terms within the excerpt in the given markup.
+  pub fn markup(mut self, pre: impl Into<String>, post: impl Into<String>) -> Self {
+    self.markup = Some((pre.into(), post.into()));
+    self
+  }
+
+  /// Extracts an excerpt of a field around its matched terms.
+  ///
+  /// If the field has no highlights, the excerpt is taken from the start of
+  /// the text.
+  ///
+  /// # Arguments
+  ///
+  /// * `text` - The text of the field, as it was searched.
+  /// * `field` - The field the text belongs to.
+  /// * `highlights` - The highlights of a match.
+  pub fn extract(&self, text: &str, field: &str, highlights: &[Highlight]) -> String {
+    // Byte offset of every character, plus the end of the text.
+    let offsets: Vec<usize> = text
+      .char_indices()
+      .map(|(offset, _)| offset)
+      .chain(std::iter::once(text.len()))
+      .collect();
+    let total = offsets.len() - 1;
+
+    let mut hits: Vec<&Highlight> = highlights
+      .iter()
+      .filter(|h| h.field == field && h.char_end <= total)
+      .collect();
+    hits.sort_by_key(|h| h.char_start);
+
+    let (start, end) = if total <= self.length {
+      (0, total)
+    } else {
+      let (first, last) = self.densest_cluster(&hits);
+      let padding = self.length.saturating_sub(last - first) / 2;
+      let start = first.saturating_sub(padding).min(total - self.length);
+      let (start, end) = (start, start + self.length);
+      snap_to_words(text, &offsets, start, end, first, last)
+    };
+
+    let (start_byte, end_byte) = (offsets[start], offsets[end]);
+    let excerpt = &text[start_byte..end_byte];
+
+    let body = match &self.markup {
+      Some((pre, post)) => {
+        let shifted: Vec<Highlight> = hits
+          .iter()
+          .filter(|h| h.start >= start_byte && h.end <= end_byte)
+          .map(|h| Highlight {
+            start: h.start - start_byte,
+            end: h.end - start_byte,
+            ..(*h).clone()
+          })
+          .collect();
+        highlight_text(excerpt, field, &shifted, pre, post)
+      }
+      None => excerpt.to_string(),
+    };
+
+    let mut snippet = String::with_capacity(body.len() + 2 * self.ellipsis.len());
+    if start > 0 {
+      snippet.push_str(&self.ellipsis);
+    }
+    snippet.push_str(body.trim());
+    if end < total {
+      snippet.push_str(&self.ellipsis);
+    }
+    snippet
+  }
+
+  /// Finds the character span of the cluster of highlights with the most
+  /// members fitting within the excerpt length.
+  fn densest_cluster(&self, hits: &[&Highlight]) -> (usize, usize) {
+    let mut best = (0, 0);
+    let mut best_count = 0;
+
+    for (i, first) in hits.iter().enumerate() {
+      let window_end = first.char_start + self.length;
+      let members = hits[i..]
+        .iter()
+        .take_while(|h| h.char_end <= window_end)
+        .collect::<Vec<_>>();
+
+      if members.len() > best_count {
+        best_count = members.len();
+        best = (
+          first.char_start,
+          members.last().map_or(first.char_end, |h| h.char_end),
+        );
+      }
+    }
+
+    best
+  }
+}
+
+/// Moves the bounds of an excerpt inwards to the nearest whitespace, as long
+/// as the matched span between `first` and `last` stays within it.
+fn snap_to_words(
+  text: &str,
+  offsets: &[usize],
+  mut start: usize,
+  mut end: usize,
+  first: usize,
+  last: usize,
+) -> (usize, usize) {
+  let total = offsets.len() - 1;
+  let is_space = |index: usize| {
+    text[offsets[index]..]
+      .chars()
+      .next()
+      .is_some_and(char::is_whitespace)
+  };
+
+  if start > 0 {
+    let mut s = start;
+    while s < first && !is_space(s - 1) {
+      s += 1;
+    }
+    if s <= first {
+      start = s;
+    }
+  }
+
+  if end < total {
+    let mut e = end;
+    while e > last && !is_space(e) {
+      e -= 1;
+    }
+    if e >= last {
+      end = e;
+    }
+  }
+
+  (start, end)
+}
@@ -0,0 +1,332 @@
+//! Search history and query suggestions.
+//!
+//! A [`SearchHistory`] remembers the texts of past searches, and suggests
+//! them back for "recent" and "popular" searches in a search box with
+//! [`SearchHistory::suggest_queries`]. Only successful searches, that
+//! matched at least one item by default, are suggested.
+//!
+//! Suggestions are ranked by how often and how recently a query was
+//! searched: every search of a query counts for 1, halved every half-life
+//! since (a week by default). Queries are compared case-insensitively, with
+//! their whitespace collapsed, and suggested as they were last searched.
+//!
+//! Every search is also passed to the [`HistorySink`]s of the history, which
+//! can store it elsewhere (a log file, a database) for analytics or to
+//! restore the history with [`SearchHistory::replay`] after a restart. Any
+//! `Fn(&SearchRecord)` closure is a sink.
+//!
+//! Engines built with a history
+//! ([`SearusEngineBuilder::history`](crate::engine::SearusEngineBuilder::history))
+//! record the text of every search they run.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use searus::history::{SearchHistory, SearchRecord};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let log = Arc::new(Mutex::new(Vec::new()));
+//! let sink = log.clone();
+//! let history = SearchHistory::new()
+//!     .sink(move |record: &SearchRecord| sink.lock().unwrap().push(record.clone()));
+//!
+//! history.record(SearchRecord::new("rust async", 12));
+//! history.record(SearchRecord::new("Rust  Async", 9));
+//! history.record(SearchRecord::new("rust macros", 4));
+//! history.record(SearchRecord::new("rust quantum", 0));
+//!
+//! let suggestions = history.suggest_queries("rust", 5);
+//! assert_eq!(suggestions.len(), 2);
+//! assert_eq!(suggestions[0].text, "Rust Async");
+//! assert_eq!(suggestions[0].count, 2);
+//! assert_eq!(suggestions[1].text, "rust macros");
+//!
+//! // Unsuccessful searches are not suggested, but still logged.
+//! assert_eq!(log.lock().unwrap().len(), 4);
+//! ```
+
+use crate::scoring::now;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+/// A search, as recorded in a [`SearchHistory`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchRecord {
+  /// The text of the query.
+  pub text: String,
+  /// The number of items the search matched.
+  pub total_matches: usize,
+  /// When the search ran, in seconds since the Unix epoch.
+  pub timestamp: f64,
+}
+
+impl SearchRecord {
+  /// Creates a record of a search that runs now.
+  pub fn new(text: impl Into<String>, total_matches: usize) -> Self {
+    Self {
+      text: text.into(),
+      total_matches,
+      timestamp: now(),
+    }
+  }
+
+  /// Sets when the search ran, in seconds since the Unix epoch.
+  pub fn at(mut self, timestamp: f64) -> Self {
+    self.timestamp = timestamp;
+    self
+  }
+}
+
+/// A destination for the searches recorded in a [`SearchHistory`].
+///
+/// Any closure of the form `Fn(&SearchRecord)` is also a `HistorySink`.
+pub trait HistorySink: Send + Sync {
+  /// Stores a search.
+  fn record(&self, record: &SearchRecord);
+}
+
+impl<F> HistorySink for F
+where
+  F: Fn(&SearchRecord) + Send + Sync,
+{
+  fn record(&self, record: &SearchRecord) {
+    self(record)
+  }
+}
+
+/// A past query returned by [`SearchHistory::suggest_queries`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuerySuggestion {
+  /// The query, as it was last searched.
+  pub text: String,
+  /// The number of successful searches of the query.
+  pub count: usize,
+  /// When the query was last searched, in seconds since the Unix epoch.
+  pub last_searched: f64,
+  /// The rank of the query: its searches, each halved every half-life since.
+  pub score: f64,
+}
+
+/// The successful searches of a query.
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+  /// The query, as it was last searched.
+  text: String,
+  /// The number of searches.
+  count: usize,
+  /// When the query was last searched.
+  last_searched: f64,
+  /// The searches, each decayed to `last_searched`.
+  weight: f64,
+}
+
+/// A store of past searches that suggests the popular and recent ones.
+///
+/// The history is safe to share between threads: searches are recorded
+/// through a shared reference.
+pub struct SearchHistory {
+  /// The successful searches, by lowercase query.
+  entries: Mutex<HashMap<String, HistoryEntry>>,
+  /// The destinations of every recorded search.
+  sinks: Vec<Box<dyn HistorySink>>,
+  /// The time, in seconds, after which a search counts for half.
+  half_life: f64,
+  /// The maximum number of distinct queries remembered.
+  capacity: usize,
+  /// The number of matches a search needs to be suggested.
+  min_matches: usize,
+}
+
+impl Default for SearchHistory {
+  fn default() -> Self {
+    Self {
+      entries: Mutex::new(HashMap::new()),
+      sinks: Vec::new(),
+      half_life: 7.0 * 24.0 * 60.0 * 60.0,
+      capacity: 10_000,
+      min_matches: 1,
+    }
+  }
+}
+
+impl SearchHistory {
+  /// Creates an empty history.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a sink receiving every recorded search, successful or not.
+  pub fn sink(mut self, sink: impl HistorySink + 'static) -> Self {
+    self.sinks.push(Box::new(sink));
+    self
+  }
+
+  /// Sets the time, in seconds, after which a search counts for half as
+  /// much. The default is a week.
+  ///
+  /// Shorter half-lives favor recent searches, and longer ones frequent
+  /// searches. A half-life of 0 ranks queries by frequency alone.
+  pub fn half_life(mut self, seconds: f64) -> Self {
+    self.half_life = seconds;
+    self
+  }
+
+  /// Sets the maximum number of distinct queries remembered. The default is
+  /// 10,000.
+  ///
+  /// Past that, the lowest-ranked query is forgotten for every new one.
+  pub fn capacity(mut self, capacity: usize) -> Self {
+    self.capacity = capacity.max(1);
+    self
+  }
+
+  /// Sets the number of items a search must match to be suggested. The
+  /// default is 1.
+  pub fn min_matches(mut self, min_matches: usize) -> Self {
+    self.min_matches = min_matches;
+    self
+  }
+
+  /// Records a search, passing it to the sinks.
+  ///
+  /// Searches without text are ignored.
+  pub fn record(&self, record: SearchRecord) {
+    if record.text.trim().is_empty() {
+      return;
+    }
+    for sink in &self.sinks {
+      sink.record(&record);
+    }
+    self.remember(&record);
+  }
+
+  /// Records past searches, without passing them to the sinks.
+  ///
+  /// This restores a history from the searches its sinks stored.
+  pub fn replay(&self, records: impl IntoIterator<Item = SearchRecord>) {
+    for record in records {
+      if !record.text.trim().is_empty() {
+        self.remember(&record);
+      }
+    }
+  }
+
+  /// Returns the number of distinct queries that can be suggested.
+  pub fn len(&self) -> usize {
+    self.lock().len()
+  }
+
+  /// Returns `true` if no query can be suggested.
+  pub fn is_empty(&self) -> bool {
+    self.lock().is_empty()
+  }
+
+  /// Forgets every search.
+  pub fn clear(&self) {
+    self.lock().clear();
+  }
+
+  /// Returns up to `limit` past queries starting with a prefix, the most
+  /// frequent and recent first.
+  ///
+  /// The prefix is compared case-insensitively. An empty prefix suggests
+  /// the top queries overall.
+  pub fn suggest_queries(&self, prefix: &str, limit: usize) -> Vec<QuerySuggestion> {
+    let prefix = collapse_whitespace(prefix).to_lowercase();
+    let now = now();
+
+    let mut suggestions: Vec<QuerySuggestion> = self
+      .lock()
+      .iter()
+      .filter(|(key, _)| key.starts_with(&prefix))
+      .map(|(_, entry)| QuerySuggestion {
+        text: entry.text.clone(),
+        count: entry.count,
+        last_searched: entry.last_searched,
+        score: self.score(entry, now),
+      })
+      .collect();
+
+    suggestions.sort_by(|a, b| {
+      b.score
+        .total_cmp(&a.score)
+        .then_with(|| b.last_searched.total_cmp(&a.last_searched))
+        .then_with(|| a.text.cmp(&b.text))
+    });
+    suggestions.truncate(limit);
+    suggestions
+  }
+
+  /// Locks the entries, recovering them if a thread panicked with the lock.
+  fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, HistoryEntry>> {
+    self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Returns the weight of an entry decayed to a time.
+  fn score(&self, entry: &HistoryEntry, time: f64) -> f64 {
+    entry.weight * self.decay(time - entry.last_searched)
+  }
+
+  /// Returns the factor by which a search counts after some time.
+  fn decay(&self, age: f64) -> f64 {
+    if self.half_life <= 0.0 {
+      return 1.0;
+    }
+    0.5f64.powf(age.max(0.0) / self.half_life)
+  }
+
+  /// Adds a successful search to the entries.
+  fn remember(&self, record: &SearchRecord) {
+    if record.total_matches < self.min_matches {
+      return;
+    }
+
+    let text = collapse_whitespace(&record.text);
+    let key = text.to_lowercase();
+    let mut entries = self.lock();
+
+    if let Some(entry) = entries.get_mut(&key) {
+      entry.count += 1;
+      if record.timestamp >= entry.last_searched {
+        entry.weight = entry.weight * self.decay(record.timestamp - entry.last_searched) + 1.0;
+        entry.last_searched = record.timestamp;
+        entry.text = text;
+      } else {
+        // Replayed searches may come out of order.
+        entry.weight += self.decay(entry.last_searched - record.timestamp);
+      }
+      return;
+    }
+
+    if entries.len() >= self.capacity {
+      let lowest = entries
+        .iter()
+        .min_by(|(_, a), (_, b)| {
+          self
+            .score(a, record.timestamp)
+            .total_cmp(&self.score(b, record.timestamp))
+        })
+        .map(|(key, _)| key.clone());
+      if let Some(lowest) = lowest {
+        entries.remove(&lowest);
+      }
+    }
+
+    entries.insert(
+      key,
+      HistoryEntry {
+        text,
+        count: 1,
+        last_searched: record.timestamp,
+        weight: 1.0,
+      },
+    );
+  }
+}
+
+/// Returns a text with its runs of whitespace replaced by single spaces, and
+/// trimmed.
+fn collapse_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -1,6 +1,11 @@
 //! Defines the `IndexAdapter` trait for creating pluggable storage backends.
 
 use crate::types::EntityId;
+use std::collections::HashMap;
+
+/// The name of the vector given to [`IndexAdapter::put`] and searched by
+/// [`IndexAdapter::knn`], among the named vectors of an item.
+pub const DEFAULT_VECTOR: &str = "default";
 
 /// A trait that defines the common interface for a search index.
 ///
@@ -43,6 +48,36 @@ pub trait IndexAdapter<T>: Send + Sync {
   /// A `Result` indicating success or failure.
   fn remove(&mut self, id: &EntityId) -> Result<(), String>;
 
+  /// Adds or updates an item in the index, with several named vectors, such
+  /// as one embedding of its text and one of its image.
+  ///
+  /// The default implementation supports only the vector named
+  /// [`DEFAULT_VECTOR`], which it stores with `put`.
+  ///
+  /// # Arguments
+  ///
+  /// * `id` - The unique `EntityId` for the item.
+  /// * `item` - The item to be stored.
+  /// * `vectors` - The vector embeddings of the item, keyed by their name.
+  /// * `tags` - An optional list of tags associated with the item.
+  ///
+  /// # Returns
+  ///
+  /// A `Result` indicating success or failure.
+  fn put_named(
+    &mut self,
+    id: EntityId,
+    item: T,
+    mut vectors: HashMap<String, Vec<f32>>,
+    tags: Option<Vec<String>>,
+  ) -> Result<(), String> {
+    let vector = vectors.remove(DEFAULT_VECTOR);
+    if let Some(name) = vectors.keys().next() {
+      return Err(format!("Named vectors are not supported, got {}", name));
+    }
+    self.put(id, item, vector, tags)
+  }
+
   /// Retrieves an item from the index by its ID.
   ///
   /// # Arguments
@@ -70,6 +105,25 @@ pub trait IndexAdapter<T>: Send + Sync {
   /// and its distance from the query vector.
   fn knn(&self, vector: &[f32], k: usize) -> Vec<(EntityId, f32)>;
 
+  /// Performs a k-nearest neighbors search among the vectors of the given
+  /// name.
+  ///
+  /// The default implementation searches the vectors given to `put` when
+  /// the name is [`DEFAULT_VECTOR`], and finds no neighbors otherwise.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - The name of the vectors to search, e.g. `"image"`.
+  /// * `vector` - The query vector to find neighbors for.
+  /// * `k` - The number of nearest neighbors to return.
+  fn knn_named(&self, name: &str, vector: &[f32], k: usize) -> Vec<(EntityId, f32)> {
+    if name == DEFAULT_VECTOR {
+      self.knn(vector, k)
+    } else {
+      Vec::new()
+    }
+  }
+
   /// Retrieves all items currently in the index.
   ///
   /// # Returns
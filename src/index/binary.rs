@@ -0,0 +1,97 @@
+//! Bit-packed binary vectors compared by Hamming distance.
+
+/// A vector quantized to one bit per dimension.
+///
+/// Each dimension is 1 if the original value is positive, and 0 otherwise,
+/// which keeps the direction of embeddings centered around zero while taking
+/// 32 times less memory than `f32` values. Binary vectors are compared by
+/// their Hamming distance, the number of dimensions whose bits differ.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::index::BinaryVector;
+///
+/// let a = BinaryVector::quantize(&[0.3, -0.1, 0.8, -0.5]);
+/// let b = BinaryVector::quantize(&[0.1, 0.2, 0.9, -0.4]);
+/// assert_eq!(a.dimension(), 4);
+/// assert_eq!(a.hamming_distance(&b), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BinaryVector {
+  /// The bits, 64 dimensions per word, starting from the lowest bit.
+  words: Vec<u64>,
+  /// The number of dimensions.
+  dimension: usize,
+}
+
+impl BinaryVector {
+  /// Quantizes a vector, setting the bit of every positive dimension.
+  pub fn quantize(vector: &[f32]) -> Self {
+    let mut words = vec![0u64; vector.len().div_ceil(64)];
+    for (i, &x) in vector.iter().enumerate() {
+      if x > 0.0 {
+        words[i / 64] |= 1 << (i % 64);
+      }
+    }
+    Self {
+      words,
+      dimension: vector.len(),
+    }
+  }
+
+  /// Creates a binary vector from packed words, as returned by
+  /// [`as_words`](Self::as_words).
+  ///
+  /// # Returns
+  ///
+  /// An error if the number of words doesn't match the dimension, or if bits
+  /// past the dimension are set.
+  pub fn from_words(words: Vec<u64>, dimension: usize) -> Result<Self, String> {
+    if words.len() != dimension.div_ceil(64) {
+      return Err(format!(
+        "Expected {} words for {} dimensions, got {}",
+        dimension.div_ceil(64),
+        dimension,
+        words.len()
+      ));
+    }
+    let padding = words.len() * 64 - dimension;
+    if padding > 0
+      && words
+        .last()
+        .is_some_and(|&last| last >> (64 - padding) != 0)
+    {
+      return Err(format!("Bits are set past dimension {}", dimension));
+    }
+    Ok(Self { words, dimension })
+  }
+
+  /// Returns the number of dimensions.
+  pub fn dimension(&self) -> usize {
+    self.dimension
+  }
+
+  /// Returns the packed bits, 64 dimensions per word, starting from the
+  /// lowest bit of the first word.
+  pub fn as_words(&self) -> &[u64] {
+    &self.words
+  }
+
+  /// Returns the number of dimensions whose bits differ.
+  ///
+  /// Vectors of different dimensions are compared as if the shorter one was
+  /// padded with zeros.
+  pub fn hamming_distance(&self, other: &BinaryVector) -> u32 {
+    let (long, short) = if self.words.len() >= other.words.len() {
+      (&self.words, &other.words)
+    } else {
+      (&other.words, &self.words)
+    };
+    long
+      .iter()
+      .enumerate()
+      .map(|(i, word)| (word ^ short.get(i).copied().unwrap_or(0)).count_ones())
+      .sum()
+  }
+}
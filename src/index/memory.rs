@@ -1,6 +1,8 @@
 //! An in-memory implementation of the `IndexAdapter` trait.
 
-use crate::index::adapter::IndexAdapter;
+use crate::embeddings::{check_dimension, l2_normalize};
+use crate::index::adapter::{IndexAdapter, DEFAULT_VECTOR};
+use crate::index::binary::BinaryVector;
 use crate::types::EntityId;
 use std::collections::HashMap;
 
@@ -10,13 +12,58 @@ use std::collections::HashMap;
 /// testing, prototyping, or for applications with small to medium-sized datasets
 /// that can comfortably fit in memory. It is not persistent and all data will
 /// be lost when the index is dropped.
+///
+/// All vectors of the index must have the same number of dimensions: either
+/// the one set with [`with_dimension`](Self::with_dimension), or that of the
+/// first vector inserted. `put` rejects vectors of any other length. Named
+/// vectors, added with [`put_named`](IndexAdapter::put_named), have a
+/// dimension per name.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::index::{InMemIndex, IndexAdapter};
+///
+/// let mut index = InMemIndex::new().with_dimension(2).with_normalization(true);
+/// index.put("a".to_string(), "A", Some(vec![3.0, 4.0]), None).unwrap();
+///
+/// let error = index.put("b".to_string(), "B", Some(vec![1.0, 2.0, 3.0]), None);
+/// assert_eq!(error.unwrap_err(), "Invalid vector for b: Expected a vector of 2 dimensions, got 3");
+/// assert!(index.get(&"b".to_string()).is_none());
+///
+/// // Vectors are normalized, so only their direction matters.
+/// let neighbors = index.knn(&[30.0, 40.0], 1);
+/// assert_eq!(neighbors[0].0, "a");
+/// assert!(neighbors[0].1 < 1e-6);
+/// ```
+///
+/// For large catalogs, [`VectorStorage::Binary`] keeps one bit per dimension
+/// instead of a full `f32`, at the cost of approximate neighbors.
 pub struct InMemIndex<T: Send + Sync> {
   /// Stores the actual items, keyed by their `EntityId`.
   items: HashMap<EntityId, T>,
-  /// Stores vector embeddings, keyed by their `EntityId`.
-  vectors: HashMap<EntityId, Vec<f32>>,
+  /// Stores vector embeddings, keyed by their name.
+  spaces: HashMap<String, VectorSpace>,
+  /// How vectors are stored and searched.
+  storage: VectorStorage,
   /// Stores tags, keyed by their `EntityId`.
   tags: HashMap<EntityId, Vec<String>>,
+  /// Whether vectors are L2-normalized when inserted and queried.
+  normalize: bool,
+}
+
+/// The vectors of one name, such as the text or the image embeddings of the
+/// items.
+#[derive(Default)]
+struct VectorSpace {
+  /// Full-precision vectors, keyed by their `EntityId`. Empty with
+  /// [`VectorStorage::Binary`].
+  vectors: HashMap<EntityId, Vec<f32>>,
+  /// Binary-quantized vectors, keyed by their `EntityId`. Empty with
+  /// [`VectorStorage::Full`].
+  codes: HashMap<EntityId, BinaryVector>,
+  /// The number of dimensions of every vector, once known.
+  dimension: Option<usize>,
 }
 
 impl<T: Send + Sync> InMemIndex<T> {
@@ -24,9 +71,92 @@ impl<T: Send + Sync> InMemIndex<T> {
   pub fn new() -> Self {
     Self {
       items: HashMap::new(),
-      vectors: HashMap::new(),
+      spaces: HashMap::new(),
+      storage: VectorStorage::Full,
       tags: HashMap::new(),
+      normalize: false,
+    }
+  }
+
+  /// Sets the number of dimensions every vector must have, usually the
+  /// dimension of the embedder producing them.
+  pub fn with_dimension(self, dimension: usize) -> Self {
+    self.with_vector_dimension(DEFAULT_VECTOR, dimension)
+  }
+
+  /// Sets the number of dimensions every vector of the given name must have.
+  pub fn with_vector_dimension(mut self, name: impl Into<String>, dimension: usize) -> Self {
+    self.spaces.entry(name.into()).or_default().dimension = Some(dimension);
+    self
+  }
+
+  /// Sets whether vectors are L2-normalized when inserted and queried, so
+  /// that distances rank neighbors by cosine similarity. Off by default.
+  pub fn with_normalization(mut self, normalize: bool) -> Self {
+    self.normalize = normalize;
+    self
+  }
+
+  /// Sets how vectors are stored and searched. Vectors already in the index
+  /// are converted, except that vectors stored only in binary form can't
+  /// regain full precision.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::index::{InMemIndex, IndexAdapter, VectorStorage};
+  ///
+  /// // Find 10 candidates by Hamming distance, and re-rank them exactly.
+  /// let mut index = InMemIndex::new().with_storage(VectorStorage::BinaryRescored { oversample: 5 });
+  /// index.put("a".to_string(), "A", Some(vec![0.9, -0.2, 0.4]), None).unwrap();
+  /// index.put("b".to_string(), "B", Some(vec![0.1, -0.8, 0.3]), None).unwrap();
+  ///
+  /// let neighbors = index.knn(&[0.8, -0.3, 0.5], 2);
+  /// assert_eq!(neighbors[0].0, "a");
+  /// ```
+  pub fn with_storage(mut self, storage: VectorStorage) -> Self {
+    self.storage = storage;
+    for space in self.spaces.values_mut() {
+      if storage == VectorStorage::Full {
+        space.codes.clear();
+        continue;
+      }
+      if !space.vectors.is_empty() {
+        space.codes = space
+          .vectors
+          .iter()
+          .map(|(id, v)| (id.clone(), BinaryVector::quantize(v)))
+          .collect();
+      }
+      if storage == VectorStorage::Binary {
+        space.vectors.clear();
+      }
+    }
+    self
+  }
+
+  /// Returns the number of dimensions of the vectors, if set or inferred
+  /// from the first vector inserted.
+  pub fn dimension(&self) -> Option<usize> {
+    self.vector_dimension(DEFAULT_VECTOR)
+  }
+
+  /// Returns the number of dimensions of the vectors of the given name, if
+  /// set or inferred from the first vector inserted.
+  pub fn vector_dimension(&self, name: &str) -> Option<usize> {
+    self.spaces.get(name).and_then(|space| space.dimension)
+  }
+
+  /// Checks a vector against the dimension of its name, and normalizes it if
+  /// enabled.
+  fn prepare(&self, name: &str, mut vector: Vec<f32>) -> Result<Vec<f32>, String> {
+    if let Some(dimension) = self.vector_dimension(name) {
+      check_dimension(&vector, dimension)?;
+    }
+    if self.normalize {
+      l2_normalize(&mut vector)?;
     }
+    Ok(vector)
   }
 }
 
@@ -46,10 +176,42 @@ impl<T: Send + Sync> IndexAdapter<T> for InMemIndex<T> {
     vectors: Option<Vec<f32>>,
     tags: Option<Vec<String>>,
   ) -> Result<(), String> {
+    let vectors = vectors
+      .map(|v| HashMap::from([(DEFAULT_VECTOR.to_string(), v)]))
+      .unwrap_or_default();
+    self.put_named(id, item, vectors, tags)
+  }
+
+  /// Adds or updates an item in the index, with its vectors of each name.
+  /// Vectors of other names already in the index for the item are kept.
+  fn put_named(
+    &mut self,
+    id: EntityId,
+    item: T,
+    vectors: HashMap<String, Vec<f32>>,
+    tags: Option<Vec<String>>,
+  ) -> Result<(), String> {
+    // Validate first, so that a rejected item leaves the index unchanged.
+    let mut prepared = Vec::with_capacity(vectors.len());
+    for (name, v) in vectors {
+      let v = self.prepare(&name, v).map_err(|e| match name.as_str() {
+        DEFAULT_VECTOR => format!("Invalid vector for {}: {}", id, e),
+        _ => format!("Invalid {} vector for {}: {}", name, id, e),
+      })?;
+      prepared.push((name, v));
+    }
+
     self.items.insert(id.clone(), item);
 
-    if let Some(v) = vectors {
-      self.vectors.insert(id.clone(), v);
+    for (name, v) in prepared {
+      let space = self.spaces.entry(name).or_default();
+      space.dimension.get_or_insert(v.len());
+      if self.storage != VectorStorage::Full {
+        space.codes.insert(id.clone(), BinaryVector::quantize(&v));
+      }
+      if self.storage != VectorStorage::Binary {
+        space.vectors.insert(id.clone(), v);
+      }
     }
 
     if let Some(t) = tags {
@@ -62,7 +224,10 @@ impl<T: Send + Sync> IndexAdapter<T> for InMemIndex<T> {
   /// Removes an item from the index by its ID.
   fn remove(&mut self, id: &EntityId) -> Result<(), String> {
     self.items.remove(id);
-    self.vectors.remove(id);
+    for space in self.spaces.values_mut() {
+      space.vectors.remove(id);
+      space.codes.remove(id);
+    }
     self.tags.remove(id);
     Ok(())
   }
@@ -76,7 +241,12 @@ impl<T: Send + Sync> IndexAdapter<T> for InMemIndex<T> {
   ///
   /// This implementation iterates through all vectors in the index, calculates
   /// the Euclidean distance to the query vector for each one, and then sorts
-  /// them to find the `k` nearest neighbors.
+  /// them to find the `k` nearest neighbors. A query vector with the wrong
+  /// number of dimensions has no neighbors.
+  ///
+  /// With binary storage, the query is quantized and distances are Hamming
+  /// distances, unless the candidates are rescored, in which case they are
+  /// exact Euclidean distances again.
   ///
   /// # Warning
   ///
@@ -84,20 +254,40 @@ impl<T: Send + Sync> IndexAdapter<T> for InMemIndex<T> {
   /// production use with many vectors, a more optimized index structure
   /// (e.g., an HNSW index) is recommended.
   fn knn(&self, vector: &[f32], k: usize) -> Vec<(EntityId, f32)> {
-    let mut distances: Vec<(EntityId, f32)> = self
-      .vectors
-      .iter()
-      .map(|(id, v)| {
-        let dist = euclidean_distance(vector, v);
-        (id.clone(), dist)
-      })
-      .collect();
+    self.knn_named(DEFAULT_VECTOR, vector, k)
+  }
+
+  /// Performs a k-nearest neighbors search among the vectors of the given
+  /// name, in the same way as `knn`.
+  fn knn_named(&self, name: &str, vector: &[f32], k: usize) -> Vec<(EntityId, f32)> {
+    let Some(space) = self.spaces.get(name) else {
+      return Vec::new();
+    };
+    let Ok(vector) = self.prepare(name, vector.to_vec()) else {
+      return Vec::new();
+    };
 
-    // Sort by distance in ascending order.
-    distances.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+    let exact = |id: &EntityId, v: &Vec<f32>| (id.clone(), euclidean_distance(&vector, v));
+    let hamming = || {
+      let code = BinaryVector::quantize(&vector);
+      space
+        .codes
+        .iter()
+        .map(move |(id, c)| (id.clone(), code.hamming_distance(c) as f32))
+    };
 
-    // Return the top k results.
-    distances.into_iter().take(k).collect()
+    match self.storage {
+      VectorStorage::Full => nearest(space.vectors.iter().map(|(id, v)| exact(id, v)), k),
+      VectorStorage::Binary => nearest(hamming(), k),
+      VectorStorage::BinaryRescored { oversample } => {
+        let candidates = nearest(hamming(), k.saturating_mul(oversample.max(1)));
+        let rescored = candidates
+          .into_iter()
+          .filter_map(|(id, _)| space.vectors.get_key_value(&id))
+          .map(|(id, v)| exact(id, v));
+        nearest(rescored, k)
+      }
+    }
   }
 
   /// Retrieves all items currently in the index.
@@ -106,6 +296,36 @@ impl<T: Send + Sync> IndexAdapter<T> for InMemIndex<T> {
   }
 }
 
+/// How an [`InMemIndex`] stores and searches vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VectorStorage {
+  /// Full-precision vectors, searched by Euclidean distance.
+  #[default]
+  Full,
+  /// Binary-quantized vectors only, searched by Hamming distance. Takes 32
+  /// times less memory, but neighbors are approximate.
+  Binary,
+  /// Binary-quantized vectors to find `k * oversample` candidates by Hamming
+  /// distance, and full-precision vectors to re-rank them exactly. Faster
+  /// than `Full` on large indexes, but keeps both.
+  BinaryRescored {
+    /// How many candidates per neighbor are rescored.
+    oversample: usize,
+  },
+}
+
+/// Returns the `k` entries with the smallest distances, closest first.
+fn nearest(distances: impl Iterator<Item = (EntityId, f32)>, k: usize) -> Vec<(EntityId, f32)> {
+  let mut distances: Vec<(EntityId, f32)> = distances.collect();
+
+  // Sort by distance in ascending order.
+  distances.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+  // Return the top k results.
+  distances.truncate(k);
+  distances
+}
+
 /// Calculates the Euclidean distance between two vectors (slices of f32).
 ///
 /// Euclidean distance is the straight-line distance between two points in
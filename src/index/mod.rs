@@ -14,8 +14,16 @@
 
 /// Defines the `IndexAdapter` trait, the core abstraction for an index.
 pub mod adapter;
+/// Provides bit-packed binary vectors for compact storage.
+pub mod binary;
 /// Provides an in-memory implementation of the `IndexAdapter`.
 pub mod memory;
+/// Provides an `IndexAdapter` storing vectors in a Qdrant collection.
+#[cfg(feature = "qdrant")]
+pub mod qdrant;
 
-pub use adapter::IndexAdapter;
-pub use memory::InMemIndex;
+pub use adapter::{IndexAdapter, DEFAULT_VECTOR};
+pub use binary::BinaryVector;
+pub use memory::{InMemIndex, VectorStorage};
+#[cfg(feature = "qdrant")]
+pub use qdrant::{QdrantCollection, QdrantIndex};
@@ -0,0 +1,513 @@
+//! An `IndexAdapter` storing vectors in a Qdrant collection.
+
+use crate::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
+use crate::index::{IndexAdapter, DEFAULT_VECTOR};
+use crate::types::EntityId;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The payload key holding the id of the item a point stores.
+pub const ID_PAYLOAD_KEY: &str = "_searus_id";
+
+/// The payload key holding the tags of the item a point stores.
+pub const TAGS_PAYLOAD_KEY: &str = "_searus_tags";
+
+/// A collection of a [Qdrant](https://qdrant.tech) server, reached through
+/// its REST API.
+///
+/// Each item is stored as a point whose payload is the serialized item, so
+/// filters can run on the server, and whose vectors are named like those of
+/// [`IndexAdapter::put_named`], the default one being [`DEFAULT_VECTOR`].
+/// Item ids are kept in the payload; point ids are the item ids that are
+/// integers, and UUIDs derived from the others.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use searus::index::QdrantCollection;
+/// use serde_json::json;
+/// use std::collections::HashMap;
+///
+/// let collection = QdrantCollection::new("http://localhost:6333", "posts");
+/// collection.create(&[("default", 384)]).unwrap();
+///
+/// let vectors = HashMap::from([("default".to_string(), vec![0.1; 384])]);
+/// collection.upsert("1", &vectors, json!({ "title": "Rust ownership" })).unwrap();
+///
+/// let neighbors = collection.search("default", &[0.1; 384], 10, None).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct QdrantCollection {
+  /// The HTTP client.
+  agent: ureq::Agent,
+  /// The base URL of the server.
+  url: String,
+  /// The name of the collection.
+  name: String,
+  /// The API key sent with each request.
+  api_key: Option<String>,
+}
+
+impl QdrantCollection {
+  /// Creates a handle to a collection of a Qdrant server, with a timeout of
+  /// 30 seconds per request. No request is made until the collection is used.
+  ///
+  /// # Arguments
+  ///
+  /// * `url` - The base URL of the server's REST API, such as
+  ///   `http://localhost:6333`.
+  /// * `name` - The name of the collection.
+  pub fn new(url: impl Into<String>, name: impl Into<String>) -> Self {
+    Self {
+      agent: Self::agent(Duration::from_secs(30)),
+      url: url.into().trim_end_matches('/').to_string(),
+      name: name.into(),
+      api_key: None,
+    }
+  }
+
+  /// Sets the API key of the server, for Qdrant Cloud or secured servers.
+  pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+    self.api_key = Some(api_key.into());
+    self
+  }
+
+  /// Sets the timeout of each request.
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.agent = Self::agent(timeout);
+    self
+  }
+
+  /// Returns the name of the collection.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Creates the collection, with the given named vectors and their number
+  /// of dimensions. Vectors are compared by cosine similarity.
+  pub fn create(&self, vectors: &[(&str, usize)]) -> Result<(), String> {
+    let vectors: Map<String, Value> = vectors
+      .iter()
+      .map(|(name, size)| {
+        (
+          name.to_string(),
+          json!({ "size": size, "distance": "Cosine" }),
+        )
+      })
+      .collect();
+    self.request("PUT", "", &json!({ "vectors": vectors }))?;
+    Ok(())
+  }
+
+  /// Adds or replaces the point of an item, waiting until it is searchable.
+  ///
+  /// # Arguments
+  ///
+  /// * `id` - The id of the item.
+  /// * `vectors` - The vectors of the item, by name.
+  /// * `payload` - The serialized item. Items that aren't JSON objects are
+  ///   stored without a payload, other than their id.
+  pub fn upsert(
+    &self,
+    id: &str,
+    vectors: &HashMap<String, Vec<f32>>,
+    payload: Value,
+  ) -> Result<(), String> {
+    let mut payload = match payload {
+      Value::Object(payload) => payload,
+      _ => Map::new(),
+    };
+    payload.insert(ID_PAYLOAD_KEY.to_string(), Value::String(id.to_string()));
+
+    let point = json!({ "id": point_id(id), "vector": vectors, "payload": payload });
+    self.request("PUT", "/points?wait=true", &json!({ "points": [point] }))?;
+    Ok(())
+  }
+
+  /// Deletes the point of an item, waiting until it is gone.
+  pub fn delete(&self, id: &str) -> Result<(), String> {
+    self.request(
+      "POST",
+      "/points/delete?wait=true",
+      &json!({ "points": [point_id(id)] }),
+    )?;
+    Ok(())
+  }
+
+  /// Finds the `k` points whose vector of the given name is most similar to
+  /// the query vector, returning their item ids and cosine similarities,
+  /// most similar first.
+  ///
+  /// Parts of the filter with a Qdrant equivalent (comparisons of strings,
+  /// numbers, and booleans with the default string options, their
+  /// conjunctions and disjunctions, and negations of `In`) run on the
+  /// server, so they narrow the points before the `k` nearest are taken.
+  /// The others aren't checked, which callers do on the items they get
+  /// back.
+  pub fn search(
+    &self,
+    name: &str,
+    vector: &[f32],
+    k: usize,
+    filter: Option<&FilterExpr>,
+  ) -> Result<Vec<(EntityId, f32)>, String> {
+    if k == 0 {
+      return Ok(Vec::new());
+    }
+
+    let mut request = json!({
+      "vector": { "name": name, "vector": vector },
+      "limit": k,
+      "with_payload": [ID_PAYLOAD_KEY],
+    });
+    if let Some(filter) = filter.and_then(superset) {
+      request["filter"] = json!({ "must": [filter] });
+    }
+
+    let result = self.request("POST", "/points/search", &request)?;
+    let points = result
+      .as_array()
+      .ok_or_else(|| "Invalid response from Qdrant: expected a list of points".to_string())?;
+    Ok(
+      points
+        .iter()
+        .filter_map(|point| {
+          let id = match point.pointer(&format!("/payload/{}", ID_PAYLOAD_KEY)) {
+            Some(Value::String(id)) => id.clone(),
+            _ => match point.get("id")? {
+              Value::String(id) => id.clone(),
+              id => id.to_string(),
+            },
+          };
+          Some((id, point.get("score")?.as_f64()? as f32))
+        })
+        .collect(),
+    )
+  }
+
+  /// Sends a request about the collection, returning the `result` of the
+  /// response.
+  fn request(&self, method: &str, path: &str, body: &Value) -> Result<Value, String> {
+    let url = format!("{}/collections/{}{}", self.url, self.name, path);
+    let request = match method {
+      "PUT" => self.agent.put(&url),
+      _ => self.agent.post(&url),
+    };
+    let request = match &self.api_key {
+      Some(api_key) => request.header("api-key", api_key),
+      None => request,
+    };
+    let mut response = request
+      .send_json(body)
+      .map_err(|e| format!("Failed to reach Qdrant at {}: {}", self.url, e))?;
+
+    let status = response.status();
+    let mut body: Value = response
+      .body_mut()
+      .read_json()
+      .map_err(|e| format!("Invalid response from Qdrant: {}", e))?;
+    if !status.is_success() {
+      let message = body
+        .pointer("/status/error")
+        .and_then(Value::as_str)
+        .map(String::from)
+        .unwrap_or_else(|| status.to_string());
+      return Err(format!(
+        "Qdrant failed on collection {}: {}",
+        self.name, message
+      ));
+    }
+    Ok(body["result"].take())
+  }
+
+  /// Creates an HTTP client that reports error statuses as responses.
+  fn agent(timeout: Duration) -> ureq::Agent {
+    ureq::Agent::config_builder()
+      .timeout_global(Some(timeout))
+      .http_status_as_error(false)
+      .build()
+      .new_agent()
+  }
+}
+
+/// Converts an item id to a Qdrant point id: the id itself if it is an
+/// unsigned integer, and otherwise a UUID hashed from it, which stays the
+/// same across processes.
+fn point_id(id: &str) -> Value {
+  if let Ok(n) = id.parse::<u64>() {
+    if n.to_string() == id {
+      return json!(n);
+    }
+  }
+
+  // 128-bit FNV-1a, with the version (8) and variant bits of a UUID set.
+  let mut hash: u128 = 0x6c62272e07bb014262b821756295c58d;
+  for byte in id.bytes() {
+    hash ^= byte as u128;
+    hash = hash.wrapping_mul(0x0000000001000000000000000000013b);
+  }
+  hash = (hash & !(0xf << 76)) | (0x8 << 76);
+  hash = (hash & !(0x3 << 62)) | (0x2 << 62);
+
+  let hex = format!("{:032x}", hash);
+  json!(format!(
+    "{}-{}-{}-{}-{}",
+    &hex[..8],
+    &hex[8..12],
+    &hex[12..16],
+    &hex[16..20],
+    &hex[20..]
+  ))
+}
+
+/// Translates a filter to a Qdrant condition matching at least the points
+/// the filter keeps, or `None` if no part of it can be translated.
+fn superset(filter: &FilterExpr) -> Option<Value> {
+  match filter {
+    // Dropping the parts of a conjunction that can't be translated only
+    // keeps more points.
+    FilterExpr::And(filters) => {
+      let conditions: Vec<Value> = filters.iter().filter_map(superset).collect();
+      (!conditions.is_empty()).then(|| json!({ "must": conditions }))
+    }
+    FilterExpr::Or(filters) => {
+      let conditions = filters.iter().map(superset).collect::<Option<Vec<_>>>()?;
+      Some(json!({ "should": conditions }))
+    }
+    filter => condition(filter),
+  }
+}
+
+/// Translates a filter to a Qdrant condition matching at least the points
+/// the filter keeps, or `None` if it has no translation. Qdrant matches
+/// arrays with any element satisfying a condition, which the engine only
+/// does for `In`, so the other conditions can keep more points.
+fn condition(filter: &FilterExpr) -> Option<Value> {
+  match filter {
+    FilterExpr::Compare {
+      field,
+      op,
+      value,
+      options,
+    } if *options == StringOptions::default() => match (op, value) {
+      (CompareOp::Eq, value) => equals(field, value),
+      (CompareOp::In, FilterValue::List(values)) => {
+        match values.iter().map(keyword).collect::<Option<Vec<_>>>() {
+          Some(values) => Some(json!({ "key": field, "match": { "any": values } })),
+          None => {
+            let conditions = values
+              .iter()
+              .map(|value| equals(field, value))
+              .collect::<Option<Vec<_>>>()?;
+            Some(json!({ "should": conditions }))
+          }
+        }
+      }
+      (CompareOp::Lt, FilterValue::Number(n)) => {
+        Some(json!({ "key": field, "range": { "lt": n } }))
+      }
+      (CompareOp::Le, FilterValue::Number(n)) => {
+        Some(json!({ "key": field, "range": { "lte": n } }))
+      }
+      (CompareOp::Gt, FilterValue::Number(n)) => {
+        Some(json!({ "key": field, "range": { "gt": n } }))
+      }
+      (CompareOp::Ge, FilterValue::Number(n)) => {
+        Some(json!({ "key": field, "range": { "gte": n } }))
+      }
+      _ => None,
+    },
+    FilterExpr::Between {
+      field,
+      low: FilterValue::Number(low),
+      high: FilterValue::Number(high),
+      inclusive,
+      options,
+    } if *options == StringOptions::default() => {
+      let range = if *inclusive {
+        json!({ "gte": low, "lte": high })
+      } else {
+        json!({ "gt": low, "lt": high })
+      };
+      Some(json!({ "key": field, "range": range }))
+    }
+    // Only `In` matches the same points in Qdrant as in the engine, so it's
+    // the only condition whose negation keeps every point it should.
+    FilterExpr::Not(inner) => match inner.as_ref() {
+      FilterExpr::Compare {
+        op: CompareOp::In, ..
+      } => Some(json!({ "must_not": [condition(inner)?] })),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/// Translates an equality to a Qdrant condition. Numbers are matched with
+/// a range, since payloads may store whole numbers as floats, which `match`
+/// misses.
+fn equals(field: &str, value: &FilterValue) -> Option<Value> {
+  match value {
+    FilterValue::Number(n) => Some(json!({ "key": field, "range": { "gte": n, "lte": n } })),
+    value => Some(json!({ "key": field, "match": { "value": keyword(value)? } })),
+  }
+}
+
+/// Converts a filter value to a value Qdrant matches exactly: a string or a
+/// boolean.
+fn keyword(value: &FilterValue) -> Option<Value> {
+  match value {
+    FilterValue::String(text) => Some(json!(text)),
+    FilterValue::Bool(b) => Some(json!(b)),
+    _ => None,
+  }
+}
+
+/// An `IndexAdapter` that stores vectors in a Qdrant collection, so vector
+/// storage and k-NN search scale beyond a single process.
+///
+/// Items are kept in memory as well, since [`get`](IndexAdapter::get) and
+/// [`all`](IndexAdapter::all) lend them, and every write goes to the
+/// collection before the local copy is changed. [`knn`](IndexAdapter::knn)
+/// returns cosine distances, and no neighbors when Qdrant fails; use
+/// [`knn_filtered`](Self::knn_filtered) to see the error, or to narrow the
+/// neighbors with a filter.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use searus::index::{IndexAdapter, QdrantCollection, QdrantIndex};
+/// use searus::prelude::*;
+/// use serde::Serialize;
+///
+/// #[derive(Debug, Clone, Serialize)]
+/// struct Post {
+///     title: String,
+///     year: u32,
+/// }
+///
+/// let collection = QdrantCollection::new("http://localhost:6333", "posts");
+/// collection.create(&[("default", 3)]).unwrap();
+///
+/// let mut index = QdrantIndex::new(collection);
+/// let post = Post { title: "Rust ownership".into(), year: 2024 };
+/// index.put("1".to_string(), post, Some(vec![0.9, 0.1, 0.3]), None).unwrap();
+///
+/// let recent = Query::filter(Query::COMPARE).ge("year", 2020).build();
+/// let neighbors = index
+///     .knn_filtered(DEFAULT_VECTOR, &[0.8, 0.2, 0.3], 5, Some(&recent))
+///     .unwrap();
+/// ```
+pub struct QdrantIndex<T> {
+  /// The collection storing the vectors and payloads.
+  collection: QdrantCollection,
+  /// The items, by id.
+  items: HashMap<EntityId, T>,
+}
+
+impl<T> QdrantIndex<T> {
+  /// Creates an index over an existing collection, with no items. Points
+  /// already in the collection are only found by searches, not by `get`.
+  pub fn new(collection: QdrantCollection) -> Self {
+    Self {
+      collection,
+      items: HashMap::new(),
+    }
+  }
+
+  /// Returns the collection storing the vectors.
+  pub fn collection(&self) -> &QdrantCollection {
+    &self.collection
+  }
+
+  /// Finds the `k` items whose vector of the given name is nearest to the
+  /// query vector, keeping only items that match the filter, and returns
+  /// their ids and cosine distances, nearest first.
+  ///
+  /// The filter runs in Qdrant where it can (see
+  /// [`QdrantCollection::search`]), and is checked again on the local items,
+  /// which can leave fewer than `k` neighbors when part of it ran locally
+  /// only.
+  pub fn knn_filtered(
+    &self,
+    name: &str,
+    vector: &[f32],
+    k: usize,
+    filter: Option<&FilterExpr>,
+  ) -> Result<Vec<(EntityId, f32)>, String>
+  where
+    T: Serialize,
+  {
+    let neighbors = self.collection.search(name, vector, k, filter)?;
+    Ok(
+      neighbors
+        .into_iter()
+        .filter(|(id, _)| match (self.items.get(id), filter) {
+          (Some(item), Some(filter)) => filter.evaluate(item),
+          (Some(_), None) => true,
+          (None, _) => false,
+        })
+        .map(|(id, similarity)| (id, 1.0 - similarity))
+        .collect(),
+    )
+  }
+}
+
+impl<T: Serialize + Send + Sync> IndexAdapter<T> for QdrantIndex<T> {
+  /// Adds or updates an item, storing its point in the collection first.
+  fn put(
+    &mut self,
+    id: EntityId,
+    item: T,
+    vectors: Option<Vec<f32>>,
+    tags: Option<Vec<String>>,
+  ) -> Result<(), String> {
+    let vectors = vectors
+      .map(|v| HashMap::from([(DEFAULT_VECTOR.to_string(), v)]))
+      .unwrap_or_default();
+    self.put_named(id, item, vectors, tags)
+  }
+
+  /// Adds or updates an item, with its vectors of each name. The point
+  /// replaces any previous one, so vectors of other names are dropped.
+  fn put_named(
+    &mut self,
+    id: EntityId,
+    item: T,
+    vectors: HashMap<String, Vec<f32>>,
+    tags: Option<Vec<String>>,
+  ) -> Result<(), String> {
+    let mut payload = serde_json::to_value(&item).map_err(|e| e.to_string())?;
+    if let (Value::Object(payload), Some(tags)) = (&mut payload, tags) {
+      payload.insert(TAGS_PAYLOAD_KEY.to_string(), json!(tags));
+    }
+    self.collection.upsert(&id, &vectors, payload)?;
+    self.items.insert(id, item);
+    Ok(())
+  }
+
+  /// Removes an item, deleting its point from the collection first.
+  fn remove(&mut self, id: &EntityId) -> Result<(), String> {
+    self.collection.delete(id)?;
+    self.items.remove(id);
+    Ok(())
+  }
+
+  fn get(&self, id: &EntityId) -> Option<&T> {
+    self.items.get(id)
+  }
+
+  fn knn(&self, vector: &[f32], k: usize) -> Vec<(EntityId, f32)> {
+    self.knn_named(DEFAULT_VECTOR, vector, k)
+  }
+
+  fn knn_named(&self, name: &str, vector: &[f32], k: usize) -> Vec<(EntityId, f32)> {
+    self.knn_filtered(name, vector, k, None).unwrap_or_default()
+  }
+
+  fn all(&self) -> Vec<&T> {
+    self.items.values().collect()
+  }
+}
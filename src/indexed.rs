@@ -0,0 +1,364 @@
+//! A search engine bound to the index that stores its items.
+
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+use crate::completion::{Completion, CompletionIndex};
+use crate::engine::SearusEngine;
+use crate::history::SearchRecord;
+use crate::index::{InMemIndex, IndexAdapter};
+use crate::types::{EntityId, Query, SearchResponse, Searchable, SearusMatch};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A [`SearusEngine`] together with the [`IndexAdapter`] holding the items it
+/// searches.
+///
+/// `SearusEngine` searches whatever slice of items it is given; an
+/// `IndexedSearusEngine` owns its items instead, so they can be added,
+/// updated, and removed by id between searches. This is the shape a
+/// long-running search service needs, and what the
+/// [`server`](crate::server) router (behind the `server` feature) exposes.
+///
+/// Every change to the items increments the [`generation`](Self::generation)
+/// of the engine. Searches run over a copy of the items taken at the current
+/// generation, which the searchers are warmed up with (see
+/// [`SearusEngine::warm_up`]), so that the copy and the precomputed state are
+/// reused until the items change. Responses can also be cached with
+/// [`with_result_cache`](Self::with_result_cache); cached responses are
+/// dropped when the generation changes, so stale results are never served
+/// after a write.
+///
+/// The copy clones every item, so once searched, the items take twice their
+/// memory: once in the index and once in the copy. For large items, index
+/// `Arc<T>`s instead, so that the copy only clones pointers.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Post {
+///     title: String,
+/// }
+///
+/// let rules = SemanticRules::builder().field("title", FieldRule::tokenized()).build();
+/// let engine = SearusEngine::builder()
+///     .with(Box::new(SemanticSearch::new(rules)))
+///     .build();
+///
+/// let mut indexed = IndexedSearusEngine::new(engine).with_completion_fields(["title"]);
+/// indexed.put("1", Post { title: "Rust ownership".into() }).unwrap();
+/// indexed.put("2", Post { title: "Python typing".into() }).unwrap();
+///
+/// let response = indexed.search(&Query::builder().text("rust").build());
+/// assert_eq!(response.results[0].item.title, "Rust ownership");
+///
+/// indexed.remove("1").unwrap();
+/// assert!(indexed.search(&Query::builder().text("rust").build()).results.is_empty());
+/// assert_eq!(indexed.suggest("pyt", 5)[0].text, "Python typing");
+/// ```
+pub struct IndexedSearusEngine<T: Send + Sync, I = InMemIndex<T>> {
+  /// The engine running the searches.
+  engine: SearusEngine<T>,
+  /// The index storing the items.
+  index: I,
+  /// The fields whose values are offered as completions.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  completion_fields: Vec<String>,
+  /// The completions of the indexed items.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  completions: CompletionIndex,
+  /// The number of changes made to the items.
+  generation: u64,
+  /// The items searched, copied out of the index at a generation. The copy
+  /// holds a clone of every item, in addition to the index's.
+  snapshot: Mutex<Option<(u64, Arc<Vec<T>>)>>,
+  /// The cached responses, if responses are cached.
+  results: Option<Mutex<ResultCache<T>>>,
+}
+
+/// The responses of recent searches, all at the same generation.
+struct ResultCache<T: Searchable> {
+  /// The maximum number of responses kept.
+  capacity: usize,
+  /// The generation of the items the responses were computed over.
+  generation: u64,
+  /// The responses, by query (see [`cache_key`]).
+  responses: HashMap<String, SearchResponse<T>>,
+  /// The queries of the responses, oldest first.
+  order: VecDeque<String>,
+}
+
+impl<T: Searchable + Clone> ResultCache<T> {
+  /// Returns the cached response to a query at a generation, if any.
+  ///
+  /// The cache is emptied when it is asked about another generation.
+  fn get(&mut self, key: &str, generation: u64) -> Option<SearchResponse<T>> {
+    if self.generation != generation {
+      self.generation = generation;
+      self.responses.clear();
+      self.order.clear();
+    }
+    self.responses.get(key).cloned()
+  }
+
+  /// Caches the response to a query, dropping the oldest response if the
+  /// cache is full.
+  fn insert(&mut self, key: String, generation: u64, response: SearchResponse<T>) {
+    if self.generation != generation || self.responses.contains_key(&key) {
+      return;
+    }
+    if self.responses.len() >= self.capacity {
+      if let Some(oldest) = self.order.pop_front() {
+        self.responses.remove(&oldest);
+      }
+    }
+    self.order.push_back(key.clone());
+    self.responses.insert(key, response);
+  }
+}
+
+impl<T: Searchable + Send + Sync> IndexedSearusEngine<T> {
+  /// Creates a new `IndexedSearusEngine` storing its items in an empty
+  /// [`InMemIndex`].
+  pub fn new(engine: SearusEngine<T>) -> Self {
+    Self::with_index(engine, InMemIndex::new())
+  }
+}
+
+impl<T, I> IndexedSearusEngine<T, I>
+where
+  T: Searchable + Send + Sync,
+  I: IndexAdapter<T>,
+{
+  /// Creates a new `IndexedSearusEngine` searching the items of the given
+  /// index.
+  pub fn with_index(engine: SearusEngine<T>, index: I) -> Self {
+    Self {
+      engine,
+      index,
+      #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+      completion_fields: Vec::new(),
+      #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+      completions: CompletionIndex::default(),
+      generation: 0,
+      snapshot: Mutex::new(None),
+      results: None,
+    }
+  }
+
+  /// Caches the responses of up to `capacity` recent searches.
+  ///
+  /// A search for the same query at the same generation returns the cached
+  /// response instead of searching again. Any change to the items empties
+  /// the cache. Searches answered from the cache are still recorded in the
+  /// engine's [`SearchHistory`](crate::history::SearchHistory).
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let rules = SemanticRules::builder().field("title", FieldRule::tokenized()).build();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(SemanticSearch::new(rules)))
+  ///     .build();
+  /// let mut indexed = IndexedSearusEngine::new(engine).with_result_cache(100);
+  ///
+  /// let query = Query::builder().text("rust").build();
+  /// indexed.put("1", serde_json::json!({ "title": "rust" })).unwrap();
+  /// assert_eq!(indexed.search(&query).total_matches, 1);
+  ///
+  /// indexed.put("2", serde_json::json!({ "title": "rust" })).unwrap();
+  /// assert_eq!(indexed.generation(), 2);
+  /// assert_eq!(indexed.search(&query).total_matches, 2);
+  /// ```
+  pub fn with_result_cache(mut self, capacity: usize) -> Self {
+    self.results = (capacity > 0).then(|| {
+      Mutex::new(ResultCache {
+        capacity,
+        generation: self.generation,
+        responses: HashMap::new(),
+        order: VecDeque::new(),
+      })
+    });
+    self
+  }
+
+  /// Sets the fields whose values are offered by [`suggest`](Self::suggest),
+  /// such as titles or names.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  pub fn with_completion_fields<I2, S>(mut self, fields: I2) -> Self
+  where
+    I2: IntoIterator<Item = S>,
+    S: Into<String>,
+    T: Serialize,
+  {
+    self.completion_fields = fields.into_iter().map(Into::into).collect();
+    self.rebuild_completions();
+    self
+  }
+
+  /// Returns the engine running the searches.
+  pub fn engine(&self) -> &SearusEngine<T> {
+    &self.engine
+  }
+
+  /// Returns the index storing the items.
+  pub fn index(&self) -> &I {
+    &self.index
+  }
+
+  /// Returns the generation of the items: the number of changes made to
+  /// them through [`put`](Self::put) and [`remove`](Self::remove).
+  ///
+  /// Caching layers outside the engine can key their entries by generation,
+  /// so that writes invalidate them.
+  pub fn generation(&self) -> u64 {
+    self.generation
+  }
+
+  /// Adds or replaces the item with the given id.
+  ///
+  /// Returns an error if the index rejects the item.
+  pub fn put(&mut self, id: impl Into<EntityId>, item: T) -> Result<(), String>
+  where
+    T: Serialize,
+  {
+    let id = id.into();
+    #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+    let replaced = self.index.get(&id).is_some();
+
+    self.index.put(id.clone(), item, None, None)?;
+    self.generation += 1;
+
+    // The completions of a replaced item can't be taken back one by one.
+    #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+    if replaced {
+      self.rebuild_completions();
+    } else if let Some(item) = self.index.get(&id) {
+      let fields: Vec<&str> = self.completion_fields.iter().map(String::as_str).collect();
+      self.completions.add_corpus(&[item], &fields);
+    }
+    Ok(())
+  }
+
+  /// Returns the item with the given id, if any.
+  pub fn get(&self, id: &str) -> Option<&T> {
+    self.index.get(&id.to_string())
+  }
+
+  /// Removes the item with the given id. Removing a missing item is not an
+  /// error.
+  pub fn remove(&mut self, id: &str) -> Result<(), String>
+  where
+    T: Serialize,
+  {
+    let id = id.to_string();
+    if self.index.get(&id).is_none() {
+      return Ok(());
+    }
+
+    self.index.remove(&id)?;
+    self.generation += 1;
+
+    #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+    self.rebuild_completions();
+    Ok(())
+  }
+
+  /// Searches the indexed items, as
+  /// [`SearusEngine::search_response`] does for a slice of items.
+  pub fn search(&self, query: &Query) -> SearchResponse<T>
+  where
+    T: Clone + Serialize,
+  {
+    // Queries without a key aren't cached.
+    let cache = self
+      .results
+      .as_ref()
+      .and_then(|results| Some((results, cache_key(query)?)));
+    let Some((results, key)) = cache else {
+      return self.engine.search_response(&self.items(), query);
+    };
+
+    let cached = results
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .get(&key, self.generation);
+    if let Some(response) = cached {
+      if let (Some(history), Some(text)) = (self.engine.history(), &query.text) {
+        history.record(SearchRecord::new(text.clone(), response.total_matches));
+      }
+      return response;
+    }
+
+    let response = self.engine.search_response(&self.items(), query);
+    results
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .insert(key, self.generation, response.clone());
+    response
+  }
+
+  /// Searches the indexed items, as [`SearusEngine::search`] does for a
+  /// slice of items.
+  pub fn search_matches(&self, query: &Query) -> Vec<SearusMatch<T>>
+  where
+    T: Clone + Serialize,
+  {
+    self.engine.search(&self.items(), query)
+  }
+
+  /// Returns up to `k` completions of a search-box prefix, from the values
+  /// of the completion fields of the indexed items.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  pub fn suggest(&self, prefix: &str, k: usize) -> Vec<Completion> {
+    self.completions.suggest(prefix, k)
+  }
+
+  /// Returns the items to search at the current generation, copying them
+  /// out of the index and warming the engine up with them if they changed
+  /// since the last search.
+  fn items(&self) -> Arc<Vec<T>>
+  where
+    T: Clone,
+  {
+    let mut snapshot = self.snapshot.lock().unwrap_or_else(PoisonError::into_inner);
+    match &*snapshot {
+      Some((generation, items)) if *generation == self.generation => items.clone(),
+      _ => {
+        let items: Arc<Vec<T>> = Arc::new(self.index.all().into_iter().cloned().collect());
+        self.engine.precompute(&items);
+        *snapshot = Some((self.generation, items.clone()));
+        items
+      }
+    }
+  }
+
+  /// Rebuilds the completions from the indexed items, after items were
+  /// replaced or removed.
+  #[cfg(any(feature = "semantic", feature = "fuzzy"))]
+  fn rebuild_completions(&mut self)
+  where
+    T: Serialize,
+  {
+    let fields: Vec<&str> = self.completion_fields.iter().map(String::as_str).collect();
+    let mut completions = CompletionIndex::default();
+    if !fields.is_empty() {
+      completions.add_corpus(&self.index.all(), &fields);
+    }
+    self.completions = completions;
+  }
+}
+
+/// Returns the key of a query in the result cache: its JSON form, with the
+/// keys of every object sorted, so that equal queries share a key whatever
+/// order their maps (such as `metadata`) iterate in. Returns `None` if the
+/// query can't be serialized.
+fn cache_key(query: &Query) -> Option<String> {
+  let mut value = serde_json::to_value(query).ok()?;
+  value.sort_all_objects();
+  Some(value.to_string())
+}
@@ -0,0 +1,656 @@
+//! Loads items from JSON, JSONL, and CSV files into an index.
+//!
+//! A [`Loader`] streams the rows of a file into an [`IndexAdapter`],
+//! following a [`FieldMapping`] that says which field holds the id of each
+//! row, which its tags, and which its vector. The types of the fields are
+//! inferred from the first rows, so CSV cells become numbers, booleans, and
+//! lists where the column holds them. Rows that can't be loaded are skipped
+//! and reported with their line, rather than failing the whole load.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use searus::index::{InMemIndex, IndexAdapter};
+//! use searus::ingest::{FieldMapping, FieldType, Format, Loader};
+//! use serde_json::Value;
+//!
+//! let csv = "sku,name,price,labels\n\
+//!            A1,Rust mug,12.5,\"kitchen,rust\"\n\
+//!            ,Tea pot,8,kitchen\n";
+//!
+//! let loader = Loader::new(FieldMapping::new().id("sku").tags("labels"));
+//! let mut index: InMemIndex<Value> = InMemIndex::new();
+//! let report = loader.load(Format::Csv, csv.as_bytes(), &mut index).unwrap();
+//!
+//! assert_eq!(report.loaded, 1);
+//! assert_eq!(report.errors[0].row, 3);
+//! assert_eq!(report.schema.get("price"), Some(FieldType::Float));
+//! assert_eq!(index.get(&"A1".to_string()).unwrap()["price"], 12.5);
+//! ```
+
+use crate::index::IndexAdapter;
+use crate::types::EntityId;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+/// The format of a file of items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  /// A JSON array of objects, or a single object.
+  Json,
+  /// One JSON object per line (JSON Lines, or NDJSON).
+  Jsonl,
+  /// Comma-separated values, with a header row naming the fields.
+  Csv,
+}
+
+impl Format {
+  /// Returns the format of a file by its extension: `.jsonl` and `.ndjson`
+  /// files are JSONL, `.csv` files are CSV, and other files are JSON.
+  pub fn from_path(path: &Path) -> Self {
+    let extension = path
+      .extension()
+      .and_then(|e| e.to_str())
+      .unwrap_or_default()
+      .to_lowercase();
+    match extension.as_str() {
+      "jsonl" | "ndjson" => Format::Jsonl,
+      "csv" => Format::Csv,
+      _ => Format::Json,
+    }
+  }
+}
+
+/// The type of a field, inferred from its values or set in a
+/// [`FieldMapping`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+  /// `true` or `false`.
+  Bool,
+  /// A whole number.
+  Integer,
+  /// A number.
+  Float,
+  /// Text. Fields holding values of several types are text too.
+  String,
+  /// A list. In CSV cells, its elements are separated by commas.
+  List,
+  /// A JSON object. In CSV cells, it is written as JSON.
+  Object,
+}
+
+/// The fields of the rows of a file and their types, in the order of the
+/// CSV header, or of the field names in other files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schema {
+  /// The name and type of each field.
+  pub fields: Vec<(String, FieldType)>,
+}
+
+impl Schema {
+  /// Returns the type of a field, if it is in the schema.
+  pub fn get(&self, field: &str) -> Option<FieldType> {
+    self
+      .fields
+      .iter()
+      .find(|(name, _)| name == field)
+      .map(|(_, field_type)| *field_type)
+  }
+
+  /// Sets the type of a field, overriding the inferred one.
+  fn set(&mut self, field: &str, field_type: FieldType) {
+    match self.fields.iter_mut().find(|(name, _)| name == field) {
+      Some((_, current)) => *current = field_type,
+      None => self.fields.push((field.to_string(), field_type)),
+    }
+  }
+
+  /// Adds a value's type to the type of its field.
+  fn observe(&mut self, field: &str, field_type: FieldType) {
+    match self.fields.iter_mut().find(|(name, _)| name == field) {
+      Some((_, current)) => *current = merge(*current, field_type),
+      None => self.fields.push((field.to_string(), field_type)),
+    }
+  }
+}
+
+/// Returns the type holding the values of two types.
+fn merge(a: FieldType, b: FieldType) -> FieldType {
+  match (a, b) {
+    (a, b) if a == b => a,
+    (FieldType::Integer, FieldType::Float) | (FieldType::Float, FieldType::Integer) => {
+      FieldType::Float
+    }
+    _ => FieldType::String,
+  }
+}
+
+/// The number of rows the types of the fields are inferred from by default.
+const DEFAULT_SAMPLE_SIZE: usize = 100;
+
+/// Returns the default number of rows the types of the fields are inferred
+/// from.
+fn default_sample_size() -> usize {
+  DEFAULT_SAMPLE_SIZE
+}
+
+/// Says how the fields of a file map to the id, tags, and vector of each
+/// item, and sets the types of fields that shouldn't be inferred.
+///
+/// It can be built in code or read from JSON:
+///
+/// ```json
+/// { "id": "sku", "tags": "labels", "types": { "zip": "string" } }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldMapping {
+  /// The field holding the id of each row. Rows are numbered from 0 when
+  /// unset.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub id: Option<String>,
+  /// The field holding the tags of each row, as a list or comma-separated
+  /// text. The tags stay in the item and are also given to the index.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub tags: Option<String>,
+  /// The field holding the vector of each row, as a list of numbers (in CSV,
+  /// written as JSON). It is removed from the item and given to the index.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub vector: Option<String>,
+  /// The types of fields, overriding the inferred ones.
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub types: BTreeMap<String, FieldType>,
+  /// The number of rows the types of the fields are inferred from, 100 by
+  /// default.
+  #[serde(default = "default_sample_size")]
+  pub sample_size: usize,
+}
+
+impl Default for FieldMapping {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl FieldMapping {
+  /// Creates a mapping that numbers the rows and infers every type.
+  pub fn new() -> Self {
+    Self {
+      id: None,
+      tags: None,
+      vector: None,
+      types: BTreeMap::new(),
+      sample_size: DEFAULT_SAMPLE_SIZE,
+    }
+  }
+
+  /// Sets the field holding the id of each row.
+  pub fn id(mut self, field: impl Into<String>) -> Self {
+    self.id = Some(field.into());
+    self
+  }
+
+  /// Sets the field holding the tags of each row.
+  pub fn tags(mut self, field: impl Into<String>) -> Self {
+    self.tags = Some(field.into());
+    self
+  }
+
+  /// Sets the field holding the vector of each row.
+  pub fn vector(mut self, field: impl Into<String>) -> Self {
+    self.vector = Some(field.into());
+    self
+  }
+
+  /// Sets the type of a field, such as `String` for zip codes that would be
+  /// inferred as integers.
+  pub fn field_type(mut self, field: impl Into<String>, field_type: FieldType) -> Self {
+    self.types.insert(field.into(), field_type);
+    self
+  }
+
+  /// Sets the number of rows the types of the fields are inferred from.
+  pub fn sample_size(mut self, rows: usize) -> Self {
+    self.sample_size = rows;
+    self
+  }
+}
+
+/// A row of a file, ready to be put in an index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+  /// The line of the row in the file, or its position from 1 in a JSON
+  /// array.
+  pub row: usize,
+  /// The id of the item.
+  pub id: EntityId,
+  /// The item, with the fields converted to their types.
+  pub item: Value,
+  /// The tags of the item, if it has any.
+  pub tags: Option<Vec<String>>,
+  /// The vector of the item, if it has one.
+  pub vector: Option<Vec<f32>>,
+}
+
+/// A row that couldn't be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+  /// The line of the row in the file, or its position from 1 in a JSON
+  /// array.
+  pub row: usize,
+  /// What went wrong.
+  pub message: String,
+}
+
+impl fmt::Display for RowError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "row {}: {}", self.row, self.message)
+  }
+}
+
+/// The outcome of loading a file into an index.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+  /// The number of rows put in the index.
+  pub loaded: usize,
+  /// The rows that were skipped, and why.
+  pub errors: Vec<RowError>,
+  /// The fields of the rows and their types.
+  pub schema: Schema,
+}
+
+/// Loads the rows of files into an index, following a [`FieldMapping`].
+#[derive(Debug, Clone, Default)]
+pub struct Loader {
+  /// How fields map to ids, tags, and vectors.
+  mapping: FieldMapping,
+}
+
+impl Loader {
+  /// Creates a loader following the mapping.
+  pub fn new(mapping: FieldMapping) -> Self {
+    Self { mapping }
+  }
+
+  /// Reads the rows of a file as records, streaming them after the first
+  /// [`sample_size`](FieldMapping::sample_size) rows, from which the schema
+  /// is inferred.
+  ///
+  /// Returns an error if the file can't be read at all, such as a CSV file
+  /// without a header, or a JSON file that isn't JSON. Rows that can't be
+  /// read are yielded as errors.
+  pub fn records<'a, R: Read + 'a>(
+    &self,
+    format: Format,
+    reader: R,
+  ) -> Result<Records<'a>, String> {
+    // The fields of CSV files keep the order of their header.
+    let mut header = Vec::new();
+    let rows: Box<dyn Iterator<Item = Row> + 'a> = match format {
+      Format::Jsonl => Box::new(BufReader::new(reader).lines().enumerate().filter_map(
+        |(index, line)| {
+          let row = index + 1;
+          match line {
+            Ok(line) if line.trim().is_empty() => None,
+            Ok(line) => Some((row, object(serde_json::from_str(&line)))),
+            Err(e) => Some((row, Err(e.to_string()))),
+          }
+        },
+      )),
+      Format::Json => {
+        let value: Value =
+          serde_json::from_reader(reader).map_err(|e| format!("Invalid JSON: {}", e))?;
+        let values = match value {
+          Value::Array(values) => values,
+          value => vec![value],
+        };
+        Box::new(
+          values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| (index + 1, object(Ok(value)))),
+        )
+      }
+      Format::Csv => {
+        let mut reader = csv::Reader::from_reader(reader);
+        let headers = reader
+          .headers()
+          .map_err(|e| format!("Invalid CSV header: {}", e))?
+          .clone();
+        header = headers.iter().map(String::from).collect();
+        Box::new(
+          reader
+            .into_records()
+            .enumerate()
+            .map(move |(index, record)| {
+              let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                  let row = e.position().map_or(index + 2, |p| p.line() as usize);
+                  return (row, Err(e.to_string()));
+                }
+              };
+              let row = record.position().map_or(index + 2, |p| p.line() as usize);
+              // Empty cells are left out, like missing fields.
+              let fields = headers
+                .iter()
+                .zip(record.iter())
+                .filter(|(_, cell)| !cell.is_empty())
+                .map(|(header, cell)| (header.to_string(), Value::String(cell.to_string())))
+                .collect();
+              (row, Ok(fields))
+            }),
+        )
+      }
+    };
+
+    let mut records = Records {
+      rows,
+      sample: VecDeque::new(),
+      schema: Schema::default(),
+      mapping: self.mapping.clone(),
+      csv: format == Format::Csv,
+      position: 0,
+    };
+    records.infer(&header);
+    Ok(records)
+  }
+
+  /// Loads the rows of a file into an index, deserializing each item.
+  ///
+  /// Rows that can't be read, converted, deserialized, or put in the index
+  /// are skipped and reported.
+  pub fn load<T, I, R>(
+    &self,
+    format: Format,
+    reader: R,
+    index: &mut I,
+  ) -> Result<LoadReport, String>
+  where
+    T: DeserializeOwned,
+    I: IndexAdapter<T>,
+    R: Read,
+  {
+    let mut records = self.records(format, reader)?;
+    let mut report = LoadReport::default();
+    for record in records.by_ref() {
+      let result = record.and_then(|record| {
+        let row = record.row;
+        let error = |message: String| RowError { row, message };
+        let item = serde_json::from_value(record.item).map_err(|e| error(e.to_string()))?;
+        index
+          .put(record.id, item, record.vector, record.tags)
+          .map_err(error)
+      });
+      match result {
+        Ok(()) => report.loaded += 1,
+        Err(e) => report.errors.push(e),
+      }
+    }
+    report.schema = records.schema;
+    Ok(report)
+  }
+
+  /// Loads a file into an index, reading it in the format given by its
+  /// extension (see [`Format::from_path`]).
+  pub fn load_path<T, I>(&self, path: impl AsRef<Path>, index: &mut I) -> Result<LoadReport, String>
+  where
+    T: DeserializeOwned,
+    I: IndexAdapter<T>,
+  {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    self
+      .load(Format::from_path(path), file, index)
+      .map_err(|e| format!("{}: {}", path.display(), e))
+  }
+}
+
+/// A row of a file: its line, and its fields or why they couldn't be read.
+type Row = (usize, Result<Map<String, Value>, String>);
+
+/// Checks that a row is a JSON object.
+fn object(value: serde_json::Result<Value>) -> Result<Map<String, Value>, String> {
+  match value {
+    Ok(Value::Object(fields)) => Ok(fields),
+    Ok(_) => Err("Expected a JSON object".to_string()),
+    Err(e) => Err(e.to_string()),
+  }
+}
+
+/// The rows of a file, as records. See [`Loader::records`].
+pub struct Records<'a> {
+  /// The rows not read yet.
+  rows: Box<dyn Iterator<Item = Row> + 'a>,
+  /// The rows read to infer the schema, not yielded yet.
+  sample: VecDeque<Row>,
+  /// The inferred schema.
+  schema: Schema,
+  /// How fields map to ids, tags, and vectors.
+  mapping: FieldMapping,
+  /// Whether the fields are CSV cells, all read as text.
+  csv: bool,
+  /// The position of the next row, counting rows with errors.
+  position: usize,
+}
+
+impl Records<'_> {
+  /// Returns the fields of the rows and their types, as inferred from the
+  /// first rows and set by the mapping.
+  pub fn schema(&self) -> &Schema {
+    &self.schema
+  }
+
+  /// Reads the sample rows and infers the schema from them, ordering its
+  /// fields like the header, if any.
+  fn infer(&mut self, header: &[String]) {
+    self.sample = self.rows.by_ref().take(self.mapping.sample_size).collect();
+    for (_, fields) in &self.sample {
+      let Ok(fields) = fields else {
+        continue;
+      };
+      for (field, value) in fields {
+        if Some(field) == self.mapping.vector.as_ref() {
+          continue;
+        }
+        let field_type = if self.csv {
+          value.as_str().map(infer_cell)
+        } else {
+          infer_value(value)
+        };
+        if let Some(field_type) = field_type {
+          self.schema.observe(field, field_type);
+        }
+      }
+    }
+
+    if let Some(tags) = &self.mapping.tags {
+      self.schema.set(tags, FieldType::List);
+    }
+    for (field, field_type) in &self.mapping.types {
+      self.schema.set(field, *field_type);
+    }
+    if !header.is_empty() {
+      self
+        .schema
+        .fields
+        .sort_by_key(|(name, _)| header.iter().position(|h| h == name).unwrap_or(usize::MAX));
+    }
+  }
+
+  /// Converts the fields of a row to their types, and reads its id, tags,
+  /// and vector.
+  fn record(
+    &self,
+    row: usize,
+    position: usize,
+    mut fields: Map<String, Value>,
+  ) -> Result<Record, String> {
+    for (field, value) in fields.iter_mut() {
+      // JSON values keep their types unless the mapping sets them.
+      let field_type = if self.csv || self.mapping.types.contains_key(field) {
+        self.schema.get(field)
+      } else if Some(field) == self.mapping.tags.as_ref() {
+        Some(FieldType::List)
+      } else {
+        None
+      };
+      if let Some(field_type) = field_type {
+        let converted =
+          convert(value.take(), field_type).map_err(|e| format!("Field `{}`: {}", field, e))?;
+        *value = converted;
+      }
+    }
+
+    let vector = match &self.mapping.vector {
+      Some(field) => fields
+        .remove(field)
+        .map(|value| vector(value).map_err(|e| format!("Field `{}`: {}", field, e)))
+        .transpose()?,
+      None => None,
+    };
+    let id = match &self.mapping.id {
+      Some(field) => match fields.get(field) {
+        Some(Value::String(id)) => id.clone(),
+        Some(Value::Number(id)) => id.to_string(),
+        _ => return Err(format!("Missing id `{}`", field)),
+      },
+      None => position.to_string(),
+    };
+    let tags = match &self.mapping.tags {
+      Some(field) => match fields.get(field) {
+        Some(Value::Array(tags)) => Some(
+          tags
+            .iter()
+            .map(|tag| tag.as_str().map(String::from))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| format!("Field `{}`: tags must be strings", field))?,
+        ),
+        _ => None,
+      },
+      None => None,
+    };
+
+    Ok(Record {
+      row,
+      id,
+      item: Value::Object(fields),
+      tags,
+      vector,
+    })
+  }
+}
+
+impl Iterator for Records<'_> {
+  type Item = Result<Record, RowError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (row, fields) = match self.sample.pop_front() {
+      Some(row) => row,
+      None => self.rows.next()?,
+    };
+    let position = self.position;
+    self.position += 1;
+    Some(
+      fields
+        .and_then(|fields| self.record(row, position, fields))
+        .map_err(|message| RowError { row, message }),
+    )
+  }
+}
+
+/// Infers the type of a CSV cell.
+fn infer_cell(cell: &str) -> FieldType {
+  if cell.eq_ignore_ascii_case("true") || cell.eq_ignore_ascii_case("false") {
+    FieldType::Bool
+  } else if cell.parse::<i64>().is_ok() {
+    FieldType::Integer
+  } else if cell.parse::<f64>().is_ok_and(f64::is_finite) {
+    FieldType::Float
+  } else {
+    FieldType::String
+  }
+}
+
+/// Infers the type of a JSON value, or `None` for `null`.
+fn infer_value(value: &Value) -> Option<FieldType> {
+  Some(match value {
+    Value::Null => return None,
+    Value::Bool(_) => FieldType::Bool,
+    Value::Number(n) if n.is_f64() => FieldType::Float,
+    Value::Number(_) => FieldType::Integer,
+    Value::String(_) => FieldType::String,
+    Value::Array(_) => FieldType::List,
+    Value::Object(_) => FieldType::Object,
+  })
+}
+
+/// Converts a value to a type, parsing text as CSV cells are written.
+fn convert(value: Value, field_type: FieldType) -> Result<Value, String> {
+  let invalid = |value: &Value, expected: &str| format!("expected {}, got {}", expected, value);
+  match (field_type, value) {
+    (_, Value::Null) => Ok(Value::Null),
+    (FieldType::String, Value::String(text)) => Ok(Value::String(text)),
+    (FieldType::String, value @ (Value::Number(_) | Value::Bool(_))) => {
+      Ok(Value::String(value.to_string()))
+    }
+    (FieldType::Bool, Value::String(text)) if text.eq_ignore_ascii_case("true") => Ok(true.into()),
+    (FieldType::Bool, Value::String(text)) if text.eq_ignore_ascii_case("false") => {
+      Ok(false.into())
+    }
+    (FieldType::Bool, value @ Value::Bool(_)) => Ok(value),
+    (FieldType::Integer, Value::String(text)) => match text.trim().parse::<i64>() {
+      Ok(n) => Ok(n.into()),
+      Err(_) => Err(invalid(&Value::String(text), "an integer")),
+    },
+    (FieldType::Integer, Value::Number(n)) if n.is_i64() || n.is_u64() => Ok(Value::Number(n)),
+    (FieldType::Float, Value::String(text)) => match text.trim().parse::<f64>() {
+      Ok(n) if n.is_finite() => Ok(n.into()),
+      _ => Err(invalid(&Value::String(text), "a number")),
+    },
+    (FieldType::Float, value @ Value::Number(_)) => Ok(value),
+    (FieldType::List, Value::String(text)) => Ok(
+      text
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| Value::String(s.to_string()))
+        .collect(),
+    ),
+    (FieldType::List, value @ Value::Array(_)) => Ok(value),
+    (FieldType::Object, Value::String(text)) => match serde_json::from_str(&text) {
+      Ok(value @ Value::Object(_)) => Ok(value),
+      _ => Err(invalid(&Value::String(text), "a JSON object")),
+    },
+    (FieldType::Object, value @ Value::Object(_)) => Ok(value),
+    (field_type, value) => {
+      let expected = match field_type {
+        FieldType::Bool => "a boolean",
+        FieldType::Integer => "an integer",
+        FieldType::Float => "a number",
+        FieldType::String => "text",
+        FieldType::List => "a list",
+        FieldType::Object => "an object",
+      };
+      Err(invalid(&value, expected))
+    }
+  }
+}
+
+/// Reads a vector from a list of numbers, or from its JSON text.
+fn vector(value: Value) -> Result<Vec<f32>, String> {
+  let value = match value {
+    Value::String(text) => {
+      serde_json::from_str(&text).map_err(|_| format!("expected a vector, got {:?}", text))?
+    }
+    value => value,
+  };
+  serde_json::from_value::<Vec<f32>>(value).map_err(|e| format!("expected a vector: {}", e))
+}
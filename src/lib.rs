@@ -19,6 +19,18 @@
 //! - `fuzzy` (default): Enables fuzzy search capabilities.
 //! - `tagged` (default): Enables tag-based search capabilities.
 //! - `parallel`: Enables parallel execution using `rayon`.
+//! - `stemming`: Enables Snowball stemming using `rust-stemmers`.
+//! - `cjk`: Enables Chinese, Japanese, and Korean word segmentation using `jieba-rs`.
+//! - `language-detection`: Enables detecting the language of documents using `whatlang`.
+//! - `regex`: Enables the regular expression matcher using `regex`.
+//! - `yaml`: Enables loading `SemanticRules` from YAML using `serde_yaml`.
+//! - `server`: Enables an HTTP search service using `axum`.
+//! - `ingest`: Enables loading JSON, JSONL, and CSV files into an index, with `csv`.
+//! - `cli`: Builds the `searus` command line tool.
+//! - `wasm`: Enables `wasm-bindgen` bindings for searching in the browser.
+//! - `tantivy`: Enables the `TantivySearch` searcher, backed by a `tantivy` index.
+//! - `qdrant`: Enables the `QdrantIndex` index and `QdrantSearch` searcher, backed by a Qdrant server.
+//! - `postgres`: Enables `PostgresSource`, fetching candidate items from a PostgreSQL table.
 //! - `serde`: Enables serialization support (required for most features).
 //!
 //! ## Getting Started
@@ -104,40 +116,100 @@
 //!
 //! This example demonstrates the basic workflow: defining data, configuring rules, building an engine, and executing a query. For more advanced use cases, such as combining multiple searchers or using filters, see the documentation for `SearusEngine`, `Query`, and the specific `Searcher` implementations.
 
+/// Facet and other aggregations computed over the matched result set.
+pub mod aggregations;
+/// Provides the `CompletionIndex`, an edge n-gram index for search-as-you-type completions.
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+pub mod completion;
+/// Provides the `EngineConfig`, a serializable description of an engine's searchers.
+pub mod config;
 /// Provides the `SearchContext`, which holds the state of the items being searched.
 pub mod context;
+/// Items as JSON documents, including schemaless `serde_json::Value` items, and their field paths.
+pub mod document;
 /// Contains components for generating embeddings, used in vector or semantic search.
 /// (Currently experimental).
 pub mod embeddings;
 /// The core `SearusEngine`, which orchestrates the search process across multiple searchers.
 pub mod engine;
+/// Offline relevance evaluation (NDCG, MRR, precision and recall at k) against judged queries.
+pub mod eval;
+/// Provides the `Experiment` runner, comparing the rankings and metrics of two engine configurations.
+pub mod experiments;
+/// Provides the `Explanation` tree describing how a match was scored.
+pub mod explain;
 /// Defines the `SearusExtension` trait for hooking into the search lifecycle to modify queries or results.
 pub mod extension;
+/// A collection of built-in `SearusExtension` implementations, such as `DedupExtension`.
+pub mod extensions;
 /// Provides powerful filtering capabilities with `FilterExpr` to refine search results.
 pub mod filter;
+/// Provides match `Highlight`s and helpers to wrap matched terms in markup.
+pub mod highlight;
+/// Provides the `SearchHistory`, suggesting popular and recent past queries.
+pub mod history;
+/// Provides the `Loader`, streaming JSON, JSONL, and CSV files into an index.
+#[cfg(feature = "ingest")]
+pub mod ingest;
+/// Provides the `IndexedSearusEngine`, a `SearusEngine` bound to the index storing its items.
+pub mod indexed;
 /// Defines indexing structures for optimizing search performance.
 /// (Currently includes in-memory adapters).
 pub mod index;
+/// Provides `PostgresSource`, fetching candidate items from a PostgreSQL table with filters pushed down to SQL.
+#[cfg(feature = "postgres")]
+pub mod postgres;
+/// Provides the `QueryRewriter` trait and a composable `RewritePipeline` of common rewriting stages.
+pub mod rewrite;
+/// Provides the `FeatureExtractor` for exporting learning-to-rank feature vectors.
+pub mod ltr;
 /// Implements the `SemanticRules` and `FieldRule` for fine-grained control over text-based searching.
 pub mod rules;
+/// Provides the `SavedQueries` registry of named query templates with placeholders.
+pub mod saved;
+/// Provides the `ScoreModifier` trait and built-in modifiers such as `RecencyDecay`.
+pub mod scoring;
+/// Provides an axum router exposing an `IndexedSearusEngine` as an HTTP search service.
+#[cfg(feature = "server")]
+pub mod server;
 /// Contains the fundamental `Searcher` trait and the multi-searcher implementation.
 pub mod searcher;
 /// A collection of built-in `Searcher` implementations, including `SemanticSearch`, `TaggedSearch`, and `FuzzySearch`.
 pub mod searchers;
+/// Implements a SymSpell-style `SpellCorrector` built from a corpus vocabulary.
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+pub mod spelling;
+/// Provides the `SynonymMap`, a weighted synonym dictionary for query expansion.
+pub mod synonyms;
+/// Test helpers for asserting ordering, membership, and score properties of result sets.
+pub mod testing;
+/// Tuning of searcher weights and field boosts from logged clicks.
+pub mod tuning;
 /// Defines the core data structures used throughout the library, such as `Query`, `SearusMatch`, and `SearchOptions`.
 pub mod types;
+/// Provides wasm-bindgen bindings exporting the engine to JavaScript.
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub mod prelude {
   //! Convenient re-exports for common types and traits.
 
+  pub use crate::aggregations::*;
   pub use crate::context::*;
   pub use crate::embeddings::*;
   pub use crate::engine::*;
+  pub use crate::explain::*;
   pub use crate::extension::*;
+  pub use crate::extensions::*;
   pub use crate::filter::*;
+  pub use crate::highlight::*;
   pub use crate::index::*;
+  pub use crate::indexed::*;
+  pub use crate::rewrite::*;
   pub use crate::rules::*;
+  pub use crate::scoring::*;
   pub use crate::searcher::*;
   pub use crate::searchers::*;
+  pub use crate::synonyms::*;
   pub use crate::types::*;
 }
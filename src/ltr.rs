@@ -0,0 +1,269 @@
+//! Feature extraction for learning-to-rank.
+//!
+//! A [`FeatureExtractor`] turns each [`SearusMatch`] into a feature vector
+//! with a fixed, stable order: per-searcher scores, per-field scores, tag
+//! overlap, score modifiers such as recency and popularity, and any custom
+//! features. The vectors can be fed to an external ranking model with
+//! [`FeatureExtractor::rerank`], or exported as training data in the
+//! SVMlight / LETOR text format with [`FeatureExtractor::to_svmlight`].
+
+use crate::scoring::ScoreModifier;
+#[cfg(feature = "tagged")]
+use crate::types::SearchDetail;
+use crate::types::{Searchable, SearcherKind, SearusMatch};
+
+/// A single feature, extracted from a match.
+enum Feature<T> {
+  /// The final score of the match.
+  Score,
+  /// The normalized score given by a searcher, or 0 if it did not match.
+  SearcherScore(SearcherKind),
+  /// The score of a field, or 0 if the field did not match.
+  FieldScore(String),
+  /// The number of query tags matched by the item.
+  #[cfg(feature = "tagged")]
+  TagOverlap,
+  /// The factor a score modifier applies to a score of 1.0.
+  Modifier(Box<dyn ScoreModifier<T>>),
+  /// A user-defined feature computed from the item.
+  Custom(Box<dyn Fn(&T) -> f32 + Send + Sync>),
+}
+
+/// Extracts feature vectors from search matches in a stable order.
+///
+/// Features are extracted in the order they were added to the extractor, and
+/// [`names`](Self::names) returns their names in that same order.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::ltr::FeatureExtractor;
+/// use searus::prelude::*;
+/// use searus::scoring::{FieldValueBoost, ValueScaling};
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Post {
+///     title: String,
+///     views: u32,
+/// }
+///
+/// let posts = vec![
+///     Post { title: "Rust search".into(), views: 10 },
+///     Post { title: "Rust".into(), views: 1000 },
+/// ];
+///
+/// let rules = SemanticRules::builder().field("title", FieldRule::bm25()).build();
+/// let engine = SearusEngine::builder()
+///     .with(Box::new(SemanticSearch::new(rules)))
+///     .build();
+/// let results = engine.search(&posts, &Query::builder().text("rust").build());
+///
+/// let extractor = FeatureExtractor::new()
+///     .searcher_score(SearcherKind::Semantic)
+///     .field_score("title")
+///     .modifier("popularity", FieldValueBoost::new("views").scaling(ValueScaling::Log1p))
+///     .custom("title_length", |post: &Post| post.title.len() as f32);
+///
+/// assert_eq!(
+///     extractor.names(),
+///     vec!["searcher:Semantic", "field:title", "popularity", "title_length"]
+/// );
+///
+/// let features = extractor.extract(&results[0]);
+/// assert_eq!(features.len(), 4);
+///
+/// // Re-rank with an external model, here a simple linear one.
+/// let mut reranked = results.clone();
+/// extractor.rerank(&mut reranked, |features| features[0] + 0.5 * features[2]);
+/// assert_eq!(reranked[0].item.title, "Rust");
+/// ```
+pub struct FeatureExtractor<T> {
+  /// The features and their names, in extraction order.
+  features: Vec<(String, Feature<T>)>,
+}
+
+impl<T> Default for FeatureExtractor<T> {
+  fn default() -> Self {
+    Self {
+      features: Vec::new(),
+    }
+  }
+}
+
+impl<T: Searchable> FeatureExtractor<T> {
+  /// Creates a new, empty `FeatureExtractor`.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds the final score of the match, named `score`.
+  pub fn score(mut self) -> Self {
+    self.features.push(("score".to_string(), Feature::Score));
+    self
+  }
+
+  /// Adds the normalized score given by a searcher, named `searcher:<Kind>`.
+  pub fn searcher_score(mut self, kind: SearcherKind) -> Self {
+    self
+      .features
+      .push((format!("searcher:{:?}", kind), Feature::SearcherScore(kind)));
+    self
+  }
+
+  /// Adds the score of a field, named `field:<field>`.
+  pub fn field_score(mut self, field: impl Into<String>) -> Self {
+    let field = field.into();
+    self
+      .features
+      .push((format!("field:{}", field), Feature::FieldScore(field)));
+    self
+  }
+
+  /// Adds the number of query tags matched by the item, named `tag_overlap`.
+  #[cfg(feature = "tagged")]
+  pub fn tag_overlap(mut self) -> Self {
+    self
+      .features
+      .push(("tag_overlap".to_string(), Feature::TagOverlap));
+    self
+  }
+
+  /// Adds the factor a score modifier applies to a score of 1.0.
+  ///
+  /// This turns modifiers such as [`RecencyDecay`](crate::scoring::RecencyDecay)
+  /// or [`FieldValueBoost`](crate::scoring::FieldValueBoost) into recency and
+  /// popularity features.
+  pub fn modifier(
+    mut self,
+    name: impl Into<String>,
+    modifier: impl ScoreModifier<T> + 'static,
+  ) -> Self {
+    self
+      .features
+      .push((name.into(), Feature::Modifier(Box::new(modifier))));
+    self
+  }
+
+  /// Adds a user-defined feature computed from the item.
+  pub fn custom<F>(mut self, name: impl Into<String>, feature: F) -> Self
+  where
+    F: Fn(&T) -> f32 + Send + Sync + 'static,
+  {
+    self
+      .features
+      .push((name.into(), Feature::Custom(Box::new(feature))));
+    self
+  }
+
+  /// Returns the names of the features, in extraction order.
+  pub fn names(&self) -> Vec<&str> {
+    self
+      .features
+      .iter()
+      .map(|(name, _)| name.as_str())
+      .collect()
+  }
+
+  /// Returns the number of features.
+  pub fn len(&self) -> usize {
+    self.features.len()
+  }
+
+  /// Returns `true` if no features have been added.
+  pub fn is_empty(&self) -> bool {
+    self.features.is_empty()
+  }
+
+  /// Extracts the feature vector of a match.
+  pub fn extract(&self, m: &SearusMatch<T>) -> Vec<f32> {
+    self
+      .features
+      .iter()
+      .map(|(_, feature)| match feature {
+        Feature::Score => m.score,
+        Feature::SearcherScore(kind) => m.searcher_scores.get(kind).copied().unwrap_or(0.0),
+        Feature::FieldScore(field) => m.field_scores.get(field).copied().unwrap_or(0.0),
+        #[cfg(feature = "tagged")]
+        Feature::TagOverlap => m
+          .details
+          .iter()
+          .map(|detail| match detail {
+            SearchDetail::Tag { matched_tags, .. } => matched_tags.len() as f32,
+            #[cfg(feature = "semantic")]
+            SearchDetail::Semantic { .. } => 0.0,
+            #[cfg(feature = "fuzzy")]
+            SearchDetail::Fuzzy { .. } => 0.0,
+            SearchDetail::Vector { .. } | SearchDetail::Image { .. } => 0.0,
+          })
+          .sum(),
+        Feature::Modifier(modifier) => modifier.modify(&m.item, 1.0),
+        Feature::Custom(feature) => feature(&m.item),
+      })
+      .collect()
+  }
+
+  /// Re-scores matches with an external model and sorts them by the new score.
+  ///
+  /// # Arguments
+  ///
+  /// * `matches` - The matches to re-rank.
+  /// * `model` - A function from a feature vector to a score.
+  pub fn rerank<F>(&self, matches: &mut [SearusMatch<T>], model: F)
+  where
+    F: Fn(&[f32]) -> f32,
+  {
+    for m in matches.iter_mut() {
+      m.score = model(&self.extract(m));
+    }
+
+    matches.sort_by(|a, b| {
+      b.score
+        .partial_cmp(&a.score)
+        .unwrap_or(std::cmp::Ordering::Equal)
+    });
+  }
+
+  /// Exports matches as SVMlight / LETOR training data.
+  ///
+  /// Each match produces one line of the form
+  /// `<label> qid:<query_id> 1:<f1> 2:<f2> ... # <id>`, where the label comes
+  /// from the relevance judgment function and `id` is the match's item index.
+  ///
+  /// # Arguments
+  ///
+  /// * `query_id` - The identifier of the query the matches belong to.
+  /// * `matches` - The matches to export.
+  /// * `label` - A function returning the relevance label of an item.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::ltr::FeatureExtractor;
+  /// use searus::prelude::*;
+  ///
+  /// let extractor = FeatureExtractor::new()
+  ///     .score()
+  ///     .custom("length", |item: &String| item.len() as f32);
+  ///
+  /// let matches = vec![SearusMatch::new("rust".to_string(), 0.5, 3)];
+  /// let data = extractor.to_svmlight(7, &matches, |item| if item == "rust" { 2.0 } else { 0.0 });
+  ///
+  /// assert_eq!(data, "2 qid:7 1:0.5 2:4 # 3\n");
+  /// ```
+  pub fn to_svmlight<F>(&self, query_id: usize, matches: &[SearusMatch<T>], label: F) -> String
+  where
+    F: Fn(&T) -> f32,
+  {
+    let mut out = String::new();
+
+    for m in matches {
+      out.push_str(&format!("{} qid:{}", label(&m.item), query_id));
+      for (index, value) in self.extract(m).iter().enumerate() {
+        out.push_str(&format!(" {}:{}", index + 1, value));
+      }
+      out.push_str(&format!(" # {}\n", m.id));
+    }
+
+    out
+  }
+}
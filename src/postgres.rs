@@ -0,0 +1,329 @@
+//! Fetches candidate items from a PostgreSQL table.
+//!
+//! [`PostgresSource`] turns the filters of a query into a SQL `WHERE`
+//! clause, fetches the matching rows as items, and runs an engine over
+//! them, so only the candidates are loaded into memory for each query
+//! rather than the whole table.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use postgres::{Client, NoTls};
+//! use searus::postgres::PostgresSource;
+//! use searus::prelude::*;
+//!
+//! #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+//! struct Post {
+//!     id: i64,
+//!     title: String,
+//!     year: i32,
+//! }
+//!
+//! let mut client = Client::connect("host=localhost user=postgres", NoTls).unwrap();
+//!
+//! let rules = SemanticRules::builder().field("title", FieldRule::bm25()).build();
+//! let engine: SearusEngine<Post> = SearusEngine::builder()
+//!     .with(Box::new(SemanticSearch::new(rules)))
+//!     .build();
+//!
+//! // Only the posts from 2020 on are fetched, then ranked by Searus.
+//! let query = Query::builder()
+//!     .text("rust ownership")
+//!     .filters(Query::filter(Query::COMPARE).ge("year", 2020).build())
+//!     .build();
+//! let source = PostgresSource::new("posts").with_limit(10_000);
+//! let response = source.search(&mut client, &engine, &query).unwrap();
+//! ```
+
+use crate::engine::SearusEngine;
+use crate::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
+use crate::types::{Query, SearchResponse, Searchable};
+use ::postgres::types::ToSql;
+use ::postgres::GenericClient;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// A PostgreSQL table whose rows are the items searched.
+///
+/// Each row is read as a JSON object of its columns (with `to_jsonb`) and
+/// deserialized into an item. The parts of a filter that have a SQL
+/// equivalent are pushed down to the `WHERE` clause:
+///
+/// * `Eq` and `Ne` comparisons with strings, numbers, and booleans.
+/// * `Lt`, `Le`, `Gt`, `Ge`, and `Between` comparisons with numbers.
+/// * `In` and `NotIn` with lists of strings or of numbers.
+/// * `And`, `Or`, and `Not` of those.
+///
+/// Only fields naming a column are pushed down (not nested fields), and
+/// only with the default string options. Other parts of the filter are
+/// dropped from the SQL, so the rows fetched are a superset of the matches,
+/// and the engine checks the whole filter on them. Strings are sent as
+/// query parameters; the columns compared must have types matching the
+/// values (text, numeric, or boolean), or Postgres rejects the query.
+pub struct PostgresSource {
+  /// The quoted name of the table.
+  table: String,
+  /// The maximum number of rows fetched.
+  limit: Option<usize>,
+}
+
+impl PostgresSource {
+  /// Creates a source reading the rows of a table. The name can be
+  /// qualified with its schema, as in `public.posts`.
+  pub fn new(table: &str) -> Self {
+    Self {
+      table: table.split('.').map(quote).collect::<Vec<_>>().join("."),
+      limit: None,
+    }
+  }
+
+  /// Sets the maximum number of rows fetched per query, which bounds the
+  /// memory a query uses when its filters match many rows. Unlimited by
+  /// default.
+  pub fn with_limit(mut self, limit: usize) -> Self {
+    self.limit = Some(limit);
+    self
+  }
+
+  /// Returns the SQL statement fetching the rows that can match the filter,
+  /// and its text parameters.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::postgres::PostgresSource;
+  /// use searus::prelude::*;
+  ///
+  /// let filter = Query::filter(Query::COMPARE)
+  ///     .eq("category", "tech")
+  ///     .lt("price", 30)
+  ///     .build();
+  /// let (sql, params) = PostgresSource::new("books").sql(Some(&filter));
+  ///
+  /// assert_eq!(
+  ///     sql,
+  ///     r#"SELECT to_jsonb(t) FROM "books" AS t WHERE (t."category" = $1::text AND t."price" < 30)"#
+  /// );
+  /// assert_eq!(params, ["tech"]);
+  /// ```
+  pub fn sql(&self, filter: Option<&FilterExpr>) -> (String, Vec<String>) {
+    let mut sql = format!("SELECT to_jsonb(t) FROM {} AS t", self.table);
+    let mut params = Vec::new();
+    if let Some(condition) = filter.and_then(|filter| superset(filter, &mut params)) {
+      sql.push_str(" WHERE ");
+      sql.push_str(&condition);
+    }
+    if let Some(limit) = self.limit {
+      sql.push_str(&format!(" LIMIT {}", limit));
+    }
+    (sql, params)
+  }
+
+  /// Fetches the rows that can match the filter, as items.
+  pub fn fetch<T, C>(&self, client: &mut C, filter: Option<&FilterExpr>) -> Result<Vec<T>, String>
+  where
+    T: DeserializeOwned,
+    C: GenericClient,
+  {
+    let (sql, params) = self.sql(filter);
+    let params: Vec<&(dyn ToSql + Sync)> = params
+      .iter()
+      .map(|param| param as &(dyn ToSql + Sync))
+      .collect();
+    let rows = client
+      .query(sql.as_str(), &params)
+      .map_err(|e| format!("Failed to fetch from {}: {}", self.table, e))?;
+
+    rows
+      .iter()
+      .map(|row| {
+        let row: Value = row.try_get(0).map_err(|e| e.to_string())?;
+        serde_json::from_value(row).map_err(|e| format!("Invalid row in {}: {}", self.table, e))
+      })
+      .collect()
+  }
+
+  /// Fetches the rows that can match the filters of the query, and searches
+  /// them with the engine.
+  pub fn search<T, C>(
+    &self,
+    client: &mut C,
+    engine: &SearusEngine<T>,
+    query: &Query,
+  ) -> Result<SearchResponse<T>, String>
+  where
+    T: Searchable + DeserializeOwned + Serialize + Clone,
+    C: GenericClient,
+  {
+    let items: Vec<T> = self.fetch(client, query.filters.as_ref())?;
+    Ok(engine.search_response(&items, query))
+  }
+}
+
+/// Quotes a SQL identifier.
+fn quote(identifier: &str) -> String {
+  format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Translates a filter to a SQL condition true for at least the rows the
+/// filter keeps, or `None` if no part of it can be translated.
+fn superset(filter: &FilterExpr, params: &mut Vec<String>) -> Option<String> {
+  match filter {
+    // Dropping the parts of a conjunction that can't be translated only
+    // keeps more rows.
+    FilterExpr::And(filters) => {
+      let conditions: Vec<String> = filters
+        .iter()
+        .filter_map(|filter| superset(filter, params))
+        .collect();
+      (!conditions.is_empty()).then(|| format!("({})", conditions.join(" AND ")))
+    }
+    FilterExpr::Or(filters) => {
+      let start = params.len();
+      let conditions = filters
+        .iter()
+        .map(|filter| superset(filter, params))
+        .collect::<Option<Vec<_>>>();
+      match conditions {
+        Some(conditions) => Some(format!("({})", conditions.join(" OR "))),
+        None => {
+          params.truncate(start);
+          None
+        }
+      }
+    }
+    filter => condition(filter, params),
+  }
+}
+
+/// Translates a filter to a SQL condition true for exactly the rows the
+/// filter keeps, or `None` if it has no exact translation. Strings are
+/// added to the parameters.
+fn condition(filter: &FilterExpr, params: &mut Vec<String>) -> Option<String> {
+  let start = params.len();
+  let condition = match filter {
+    FilterExpr::Compare {
+      field,
+      op,
+      value,
+      options,
+    } if *options == StringOptions::default() => {
+      let column = column(field)?;
+      match (op, value) {
+        (CompareOp::Eq | CompareOp::Ne, value) => {
+          let op = if *op == CompareOp::Eq { "=" } else { "<>" };
+          Some(format!("{} {} {}", column, op, scalar(value, params)?))
+        }
+        (CompareOp::Lt | CompareOp::Le | CompareOp::Gt | CompareOp::Ge, value) => {
+          let op = match op {
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            _ => ">=",
+          };
+          Some(format!("{} {} {}", column, op, number(value)?))
+        }
+        (CompareOp::In | CompareOp::NotIn, FilterValue::List(values)) => {
+          if values.is_empty() {
+            return (*op == CompareOp::In).then(|| "FALSE".to_string());
+          }
+          let strings = values
+            .iter()
+            .all(|value| matches!(value, FilterValue::String(_)));
+          let numbers = values
+            .iter()
+            .all(|value| matches!(value, FilterValue::Number(_)));
+          if !strings && !numbers {
+            return None;
+          }
+          let values = values
+            .iter()
+            .map(|value| scalar(value, params))
+            .collect::<Option<Vec<_>>>();
+          let Some(values) = values else {
+            params.truncate(start);
+            return None;
+          };
+          if *op == CompareOp::In {
+            Some(format!("{} IN ({})", column, values.join(", ")))
+          } else {
+            // Searus keeps null values, which `NOT IN` rejects.
+            Some(format!(
+              "({} NOT IN ({}) OR {} IS NULL)",
+              column,
+              values.join(", "),
+              column
+            ))
+          }
+        }
+        _ => None,
+      }
+    }
+    FilterExpr::Between {
+      field,
+      low,
+      high,
+      inclusive,
+      options,
+    } if *options == StringOptions::default() => {
+      let column = column(field)?;
+      let (low, high) = (number(low)?, number(high)?);
+      if *inclusive {
+        Some(format!("{} BETWEEN {} AND {}", column, low, high))
+      } else {
+        Some(format!("({} > {} AND {} < {})", column, low, column, high))
+      }
+    }
+    FilterExpr::And(filters) | FilterExpr::Or(filters) => {
+      let conditions = filters
+        .iter()
+        .map(|filter| condition(filter, params))
+        .collect::<Option<Vec<_>>>();
+      conditions.map(|conditions| {
+        let op = if matches!(filter, FilterExpr::And(_)) {
+          " AND "
+        } else {
+          " OR "
+        };
+        format!("({})", conditions.join(op))
+      })
+    }
+    // Searus keeps items the inner filter rejects because a field is
+    // missing, which SQL sees as NULL.
+    FilterExpr::Not(filter) => condition(filter, params).map(|c| format!("({}) IS NOT TRUE", c)),
+    _ => None,
+  };
+
+  if condition.is_none() {
+    params.truncate(start);
+  }
+  condition
+}
+
+/// Returns the column a field names, or `None` for nested fields.
+fn column(field: &str) -> Option<String> {
+  (!field.is_empty() && !field.contains('.')).then(|| format!("t.{}", quote(field)))
+}
+
+/// Converts a filter value to a SQL expression: a parameter for strings, and
+/// a literal for numbers and booleans.
+fn scalar(value: &FilterValue, params: &mut Vec<String>) -> Option<String> {
+  match value {
+    FilterValue::String(text) => {
+      params.push(text.clone());
+      Some(format!("${}::text", params.len()))
+    }
+    FilterValue::Number(_) => number(value),
+    FilterValue::Bool(b) => Some(if *b { "TRUE" } else { "FALSE" }.to_string()),
+    _ => None,
+  }
+}
+
+/// Converts a finite number to a SQL literal.
+fn number(value: &FilterValue) -> Option<String> {
+  match value {
+    FilterValue::Number(n) if n.is_finite() => Some(n.to_string()),
+    _ => None,
+  }
+}
@@ -0,0 +1,322 @@
+//! Reusable building blocks for rewriting queries before they are executed.
+//!
+//! A [`QueryRewriter`] transforms a [`Query`] in place. Rewriters are composed
+//! into a [`RewritePipeline`], which runs its stages in order and can be
+//! registered on the engine as an extension, so that it runs in the
+//! `before_query` hook.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use searus::prelude::*;
+//! use searus::rewrite::{AcronymExpansion, Lowercase, RewritePipeline};
+//!
+//! let pipeline = RewritePipeline::new()
+//!     .then(Lowercase)
+//!     .then(AcronymExpansion::new().with("ml", "machine learning"));
+//!
+//! let mut query = Query::builder().text("Intro to ML").build();
+//! pipeline.rewrite(&mut query);
+//! assert_eq!(query.text.as_deref(), Some("intro to ml machine learning"));
+//!
+//! // Run the pipeline on every search. The text before rewriting is kept in
+//! // the query metadata, under `ORIGINAL_TEXT`.
+//! let engine: SearusEngine<String> = SearusEngine::builder()
+//!     .with_extension(Box::new(pipeline))
+//!     .build();
+//! ```
+
+use crate::extension::{SearchState, SearusExtension};
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+use crate::searchers::stopwords::StopWords;
+use crate::synonyms::SynonymMap;
+use crate::types::{Query, Searchable};
+use std::collections::HashMap;
+
+/// The [`Query::metadata`] key under which a [`RewritePipeline`] running as
+/// an extension records the query text it rewrote.
+pub const ORIGINAL_TEXT: &str = "rewrite.original_text";
+
+/// A single query rewriting stage.
+///
+/// Any closure of the form `Fn(&mut Query)` is also a `QueryRewriter`.
+pub trait QueryRewriter: Send + Sync {
+  /// Rewrites the query in place.
+  fn rewrite(&self, query: &mut Query);
+}
+
+impl<F> QueryRewriter for F
+where
+  F: Fn(&mut Query) + Send + Sync,
+{
+  fn rewrite(&self, query: &mut Query) {
+    self(query)
+  }
+}
+
+/// An ordered sequence of `QueryRewriter` stages.
+///
+/// The pipeline is itself a `QueryRewriter`, so pipelines can be nested, and
+/// a `SearusExtension` that runs all stages in the `before_query` hook.
+#[derive(Default)]
+pub struct RewritePipeline {
+  /// The stages, in the order they are applied.
+  stages: Vec<Box<dyn QueryRewriter>>,
+}
+
+impl RewritePipeline {
+  /// Creates a new, empty `RewritePipeline`.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Appends a stage to the pipeline.
+  pub fn then(mut self, stage: impl QueryRewriter + 'static) -> Self {
+    self.stages.push(Box::new(stage));
+    self
+  }
+
+  /// Returns the number of stages in the pipeline.
+  pub fn len(&self) -> usize {
+    self.stages.len()
+  }
+
+  /// Returns `true` if the pipeline has no stages.
+  pub fn is_empty(&self) -> bool {
+    self.stages.is_empty()
+  }
+}
+
+impl QueryRewriter for RewritePipeline {
+  /// Applies every stage in order.
+  fn rewrite(&self, query: &mut Query) {
+    for stage in &self.stages {
+      stage.rewrite(query);
+    }
+  }
+}
+
+impl<T: Searchable> SearusExtension<T> for RewritePipeline {
+  /// Rewrites the query, recording its original text under
+  /// [`ORIGINAL_TEXT`] if the text changed.
+  fn before_query(&self, query: &mut Query, _state: &mut SearchState) {
+    let original = query.text.clone();
+    self.rewrite(query);
+    if let Some(original) = original.filter(|text| query.text.as_ref() != Some(text)) {
+      query
+        .metadata
+        .entry(ORIGINAL_TEXT.to_string())
+        .or_insert(original.into());
+    }
+  }
+}
+
+/// Lowercases the query text.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lowercase;
+
+impl QueryRewriter for Lowercase {
+  fn rewrite(&self, query: &mut Query) {
+    if let Some(text) = &mut query.text {
+      *text = text.to_lowercase();
+    }
+  }
+}
+
+/// Appends weighted synonyms of the query terms to the query text.
+///
+/// Since plain text cannot carry weights, only synonyms with a weight of at
+/// least `min_weight` are appended.
+#[derive(Debug, Clone)]
+pub struct SynonymExpansion {
+  /// The synonym dictionary used for expansion.
+  synonyms: SynonymMap,
+  /// The minimum weight a synonym needs to be appended.
+  min_weight: f32,
+}
+
+impl SynonymExpansion {
+  /// Creates a new `SynonymExpansion` stage that appends all synonyms.
+  pub fn new(synonyms: SynonymMap) -> Self {
+    Self {
+      synonyms,
+      min_weight: 0.0,
+    }
+  }
+
+  /// Sets the minimum weight a synonym needs to be appended.
+  pub fn min_weight(mut self, min_weight: f32) -> Self {
+    self.min_weight = min_weight;
+    self
+  }
+}
+
+impl QueryRewriter for SynonymExpansion {
+  fn rewrite(&self, query: &mut Query) {
+    let text = match &mut query.text {
+      Some(text) => text,
+      None => return,
+    };
+
+    let terms: Vec<String> = text
+      .split(|c: char| !c.is_alphanumeric())
+      .filter(|t| !t.is_empty())
+      .map(str::to_lowercase)
+      .collect();
+
+    for (synonym, weight) in self.synonyms.expand(&terms) {
+      if weight >= self.min_weight {
+        text.push(' ');
+        text.push_str(&synonym);
+      }
+    }
+  }
+}
+
+/// Expands acronyms in the query text using a dictionary.
+///
+/// By default the acronym is kept and its expansion is inserted right after
+/// it, so items mentioning either form can match. Use `replace` to substitute
+/// the acronym instead.
+#[derive(Debug, Clone, Default)]
+pub struct AcronymExpansion {
+  /// Lowercase acronyms mapped to their expansions.
+  acronyms: HashMap<String, String>,
+  /// Whether the acronym is replaced rather than kept.
+  replace: bool,
+}
+
+impl AcronymExpansion {
+  /// Creates a new, empty acronym dictionary.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds an acronym and its expansion.
+  pub fn with(mut self, acronym: impl AsRef<str>, expansion: impl Into<String>) -> Self {
+    self
+      .acronyms
+      .insert(acronym.as_ref().to_lowercase(), expansion.into());
+    self
+  }
+
+  /// Replaces acronyms with their expansion instead of keeping both.
+  pub fn replace(mut self) -> Self {
+    self.replace = true;
+    self
+  }
+}
+
+impl QueryRewriter for AcronymExpansion {
+  fn rewrite(&self, query: &mut Query) {
+    let text = match &query.text {
+      Some(text) => text,
+      None => return,
+    };
+
+    let words: Vec<String> = text
+      .split_whitespace()
+      .flat_map(|word| {
+        let key = word
+          .trim_matches(|c: char| !c.is_alphanumeric())
+          .to_lowercase();
+        match self.acronyms.get(&key) {
+          Some(expansion) if self.replace => vec![expansion.clone()],
+          Some(expansion) => vec![word.to_string(), expansion.clone()],
+          None => vec![word.to_string()],
+        }
+      })
+      .collect();
+
+    query.text = Some(words.join(" "));
+  }
+}
+
+/// Removes stopwords from the query text.
+///
+/// If every word of the query is a stopword (e.g., "the who"), the query is
+/// left untouched rather than emptied.
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+#[derive(Debug, Clone)]
+pub struct TrimStopwords {
+  /// The stopwords to remove.
+  stopwords: StopWords,
+}
+
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+impl TrimStopwords {
+  /// Creates a new `TrimStopwords` stage with the given stopwords.
+  pub fn new(stopwords: StopWords) -> Self {
+    Self { stopwords }
+  }
+}
+
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+impl QueryRewriter for TrimStopwords {
+  fn rewrite(&self, query: &mut Query) {
+    let text = match &query.text {
+      Some(text) => text,
+      None => return,
+    };
+
+    let kept: Vec<&str> = text
+      .split_whitespace()
+      .filter(|word| {
+        let key = word
+          .trim_matches(|c: char| !c.is_alphanumeric())
+          .to_lowercase();
+        !self.stopwords.contains(&key)
+      })
+      .collect();
+
+    if !kept.is_empty() {
+      query.text = Some(kept.join(" "));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rewritten(rewriter: &impl QueryRewriter, text: &str) -> String {
+    let mut query = Query::builder().text(text).build();
+    rewriter.rewrite(&mut query);
+    query.text.unwrap()
+  }
+
+  #[test]
+  fn test_acronym_expansion() {
+    let acronyms = AcronymExpansion::new().with("ml", "machine learning");
+    assert_eq!(
+      rewritten(&acronyms, "ml basics"),
+      "ml machine learning basics"
+    );
+    assert_eq!(rewritten(&acronyms.replace(), "ML!"), "machine learning");
+  }
+
+  #[test]
+  fn test_trim_stopwords() {
+    let trim = TrimStopwords::new(StopWords::english());
+    assert_eq!(rewritten(&trim, "the rust book"), "rust book");
+    assert_eq!(rewritten(&trim, "to be or not to be"), "to be or not to be");
+  }
+
+  #[test]
+  fn test_pipeline_order() {
+    let pipeline = RewritePipeline::new()
+      .then(Lowercase)
+      .then(SynonymExpansion::new(SynonymMap::new().with(
+        "car",
+        "automobile",
+        0.9,
+      )))
+      .then(|query: &mut Query| query.options.limit = 5);
+
+    let mut query = Query::builder().text("Red CAR").build();
+    pipeline.rewrite(&mut query);
+
+    assert_eq!(query.text.as_deref(), Some("red car automobile"));
+    assert_eq!(query.options.limit, 5);
+  }
+}
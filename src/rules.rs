@@ -13,10 +13,13 @@ use std::collections::HashMap;
 /// `SemanticRules` acts as the top-level configuration for the `SemanticSearch`
 /// searcher. It holds rules for both top-level fields and nested objects.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SemanticRules {
   /// A map of field names to the `FieldRule` that should be applied to them.
+  #[serde(default)]
   pub fields: HashMap<String, FieldRule>,
   /// A map of nested object names to the `ObjectRule` that should be applied.
+  #[serde(default)]
   pub objects: HashMap<String, ObjectRule>,
 }
 
@@ -36,6 +39,102 @@ impl SemanticRules {
   pub fn builder() -> SemanticRulesBuilder {
     SemanticRulesBuilder::default()
   }
+
+  /// Loads and validates rules from JSON.
+  ///
+  /// Every part of a rule except the field name is optional: the matcher
+  /// defaults to `tokenized`, priority and boost to 1, and object access to
+  /// `direct`. Matchers are named `exact`, `bm25`, `tfidf`, `tokenized`,
+  /// `fuzzy`, `prefix`, `numeric`, `wildcard`, or `regex`. Unknown keys are
+  /// rejected, so that typos do not silently fall back to defaults.
+  ///
+  /// # Returns
+  ///
+  /// The rules, or a description of the first syntax or validation error.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::rules::{Matcher, ScoreMode, SemanticRules};
+  ///
+  /// let rules = SemanticRules::from_json(r#"{
+  ///     "fields": {
+  ///         "title": { "matcher": "bm25", "priority": 3, "boost": 2.0 },
+  ///         "sku": { "matcher": "regex", "pattern": "^[A-Z]{2}-\\d+$" },
+  ///         "body": {}
+  ///     },
+  ///     "objects": {
+  ///         "comments": {
+  ///             "access": "array",
+  ///             "score_mode": "max",
+  ///             "fields": { "text": { "matcher": "bm25" } }
+  ///         }
+  ///     }
+  /// }"#).unwrap();
+  ///
+  /// assert_eq!(rules.fields["title"].matcher, Matcher::BM25);
+  /// assert_eq!(rules.fields["body"].matcher, Matcher::Tokenized);
+  /// assert_eq!(rules.objects["comments"].score_mode, ScoreMode::Max);
+  ///
+  /// let error = SemanticRules::from_json(r#"{ "fields": { "title": { "boots": 2 } } }"#);
+  /// assert!(error.unwrap_err().contains("unknown field `boots`"));
+  /// ```
+  pub fn from_json(json: &str) -> Result<Self, String> {
+    let rules: Self = serde_json::from_str(json).map_err(|e| format!("Invalid rules: {}", e))?;
+    rules.validate()?;
+    Ok(rules)
+  }
+
+  /// Loads and validates rules from YAML, in the same format as
+  /// [`from_json`](Self::from_json). Requires the `yaml` feature.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::rules::{Matcher, SemanticRules};
+  ///
+  /// let rules = SemanticRules::from_yaml("
+  /// fields:
+  ///   title:
+  ///     matcher: bm25
+  ///     priority: 2
+  ///   name:
+  ///     matcher: prefix
+  /// ").unwrap();
+  ///
+  /// assert_eq!(rules.fields["name"].matcher, Matcher::Prefix);
+  /// ```
+  #[cfg(feature = "yaml")]
+  pub fn from_yaml(yaml: &str) -> Result<Self, String> {
+    let rules: Self = serde_yaml::from_str(yaml).map_err(|e| format!("Invalid rules: {}", e))?;
+    rules.validate()?;
+    Ok(rules)
+  }
+
+  /// Checks that the rules are consistent.
+  ///
+  /// Field and object names must not be empty, boosts must be finite and
+  /// non-negative, BM25 variant deltas must be non-negative, patterns are
+  /// only allowed on `Regex` fields, and objects must have fields.
+  pub fn validate(&self) -> Result<(), String> {
+    for (name, rule) in &self.fields {
+      rule.validate(name)?;
+    }
+
+    for (name, object) in &self.objects {
+      if name.is_empty() {
+        return Err("Object names must not be empty".to_string());
+      }
+      if object.fields.is_empty() {
+        return Err(format!("Object \"{}\" has no fields", name));
+      }
+      for (field, rule) in &object.fields {
+        rule.validate(&format!("{}.{}", name, field))?;
+      }
+    }
+
+    Ok(())
+  }
 }
 
 /// A builder for creating `SemanticRules` instances.
@@ -69,8 +168,10 @@ impl SemanticRulesBuilder {
 
 /// Defines the search behavior for a single field.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct FieldRule {
   /// The matching strategy to use for this field.
+  #[serde(default)]
   pub matcher: Matcher,
   /// The priority of this field relative to others. A higher priority gives
   /// the field more influence on the final score.
@@ -80,6 +181,24 @@ pub struct FieldRule {
   /// its importance.
   #[serde(default = "default_boost")]
   pub boost: f32,
+  /// The name of the analyzer used for this field, as registered with
+  /// `SemanticSearch::with_named_analyzer`. Fields without one use the
+  /// searcher's default analyzer.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub analyzer: Option<String>,
+  /// The name of the analyzer applied to the query text for this field, if
+  /// it differs from [`analyzer`](Self::analyzer) (e.g., when the field is
+  /// indexed as edge n-grams but queries should stay whole words).
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub search_analyzer: Option<String>,
+  /// The BM25 variant used to score this field with the `BM25` matcher.
+  /// Fields without one use the variant of the searcher's scorer.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub bm25_variant: Option<BM25Variant>,
+  /// The regular expression matched against this field with the `Regex`
+  /// matcher. Fields without one use the query text as the pattern.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub pattern: Option<String>,
 }
 
 /// Returns the default priority for a field (1).
@@ -99,6 +218,10 @@ impl Default for FieldRule {
       matcher: Matcher::Tokenized,
       priority: default_priority(),
       boost: default_boost(),
+      analyzer: None,
+      search_analyzer: None,
+      bm25_variant: None,
+      pattern: None,
     }
   }
 }
@@ -121,6 +244,11 @@ impl FieldRule {
   }
 
   /// Creates a `FieldRule` for exact, case-insensitive matching.
+  ///
+  /// The query must appear in the field as a phrase of analyzed terms, so
+  /// punctuation between terms is ignored ("rust-lang" matches "rust lang")
+  /// and stopwords are skipped. Use the `keyword` analyzer to match whole
+  /// values instead.
   pub fn exact() -> Self {
     Self::new(Matcher::Exact)
   }
@@ -138,11 +266,42 @@ impl FieldRule {
     Self::new(Matcher::BM25)
   }
 
+  /// Creates a `FieldRule` for relevance scoring using TF-IDF, without
+  /// document length normalization.
+  pub fn tfidf() -> Self {
+    Self::new(Matcher::TfIdf)
+  }
+
   /// Creates a `FieldRule` for simple token-based matching.
   pub fn tokenized() -> Self {
     Self::new(Matcher::Tokenized)
   }
 
+  /// Creates a `FieldRule` matching the tokens of the field that start with
+  /// a query term.
+  pub fn prefix() -> Self {
+    Self::new(Matcher::Prefix)
+  }
+
+  /// Creates a `FieldRule` scoring numeric fields by their closeness to the
+  /// numbers in the query.
+  pub fn numeric() -> Self {
+    Self::new(Matcher::Numeric)
+  }
+
+  /// Creates a `FieldRule` matching the words of the query as wildcard
+  /// patterns (e.g., `"lap*"`).
+  pub fn wildcard() -> Self {
+    Self::new(Matcher::Wildcard)
+  }
+
+  /// Creates a `FieldRule` matching the query text as a regular expression.
+  ///
+  /// Requires the `regex` feature.
+  pub fn regex() -> Self {
+    Self::new(Matcher::Regex)
+  }
+
   /// Creates a `FieldRule` for fuzzy (approximate) matching.
   pub fn fuzzy() -> Self {
     Self::new(Matcher::Fuzzy)
@@ -159,30 +318,198 @@ impl FieldRule {
     self.boost = boost;
     self
   }
+
+  /// Sets the named analyzer used to analyze this field.
+  ///
+  /// The name refers to an analyzer registered with
+  /// [`SemanticSearch::with_named_analyzer`](crate::searchers::SemanticSearch::with_named_analyzer)
+  /// or to a [built-in analyzer](crate::searchers::analyzer::Analyzer::builtin)
+  /// such as `"keyword"` or `"edge_ngram"`. Unknown names fall back to the
+  /// default analyzer.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let rules = SemanticRules::builder()
+  ///     .field("sku", FieldRule::exact().analyzer("keyword"))
+  ///     .field("title", FieldRule::bm25().analyzer("standard"))
+  ///     .field("name", FieldRule::tokenized().analyzer("edge_ngram").search_analyzer("standard"))
+  ///     .build();
+  /// ```
+  pub fn analyzer(mut self, name: impl Into<String>) -> Self {
+    self.analyzer = Some(name.into());
+    self
+  }
+
+  /// Sets the named analyzer used to analyze the query text for this field.
+  pub fn search_analyzer(mut self, name: impl Into<String>) -> Self {
+    self.search_analyzer = Some(name.into());
+    self
+  }
+
+  /// Sets the BM25 variant used to score this field.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::rules::{BM25Variant, FieldRule};
+  ///
+  /// let rule = FieldRule::bm25().bm25_variant(BM25Variant::bm25_plus());
+  /// ```
+  pub fn bm25_variant(mut self, variant: BM25Variant) -> Self {
+    self.bm25_variant = Some(variant);
+    self
+  }
+
+  /// Sets the regular expression matched against this field, instead of the
+  /// query text.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::rules::FieldRule;
+  ///
+  /// let rule = FieldRule::regex().pattern(r"^ERROR\b");
+  /// ```
+  pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+    self.pattern = Some(pattern.into());
+    self
+  }
+
+  /// Checks that the rule of the named field is consistent.
+  fn validate(&self, name: &str) -> Result<(), String> {
+    if name.is_empty() {
+      return Err("Field names must not be empty".to_string());
+    }
+    if !self.boost.is_finite() || self.boost < 0.0 {
+      return Err(format!(
+        "Field \"{}\" has an invalid boost: {}",
+        name, self.boost
+      ));
+    }
+    if let Some(BM25Variant::Plus { delta } | BM25Variant::L { delta }) = self.bm25_variant {
+      if !delta.is_finite() || delta < 0.0 {
+        return Err(format!(
+          "Field \"{}\" has an invalid BM25 delta: {}",
+          name, delta
+        ));
+      }
+    }
+    if self.pattern.is_some() && self.matcher != Matcher::Regex {
+      return Err(format!(
+        "Field \"{}\" has a pattern but does not use the regex matcher",
+        name
+      ));
+    }
+    Ok(())
+  }
 }
 
 /// Defines the matching strategy to be used for a field.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// In configuration files, matchers may also be written in lowercase
+/// (e.g., `"bm25"` or `"tfidf"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum Matcher {
-  /// Requires an exact, case-insensitive match of the query within the field's text.
+  /// Requires an exact, case-insensitive match of the query within the field's text,
+  /// compared as phrases of analyzed terms.
+  #[serde(alias = "exact")]
   Exact,
   /// Uses the BM25 algorithm to score the relevance of the field based on term
   /// frequency and inverse document frequency.
+  #[serde(alias = "bm25")]
   BM25,
+  /// Scores the field by term frequency times inverse document frequency,
+  /// without normalizing by document length. Suited to short fields of
+  /// uniform length.
+  #[serde(alias = "tfidf", alias = "tf_idf")]
+  TfIdf,
   /// A simple strategy that scores based on the frequency of query tokens in the field.
+  #[default]
+  #[serde(alias = "tokenized")]
   Tokenized,
   /// Uses a fuzzy matching algorithm (like Jaro-Winkler) to find approximate matches.
   /// Note: This is typically handled by the `FuzzySearch` searcher.
+  #[serde(alias = "fuzzy")]
   Fuzzy,
+  /// Scores the number of tokens of the field that start with a query term,
+  /// for partial identifiers and model numbers (e.g., `"rx7"` matches
+  /// `"RX7900"`).
+  #[serde(alias = "prefix")]
+  Prefix,
+  /// Parses the numbers in the query (e.g., `"1000"` or `"$1,000"`) and scores
+  /// the numbers of the field by their relative closeness to them, so that a
+  /// query for 1000 ranks 999 above 5000.
+  #[serde(alias = "numeric")]
+  Numeric,
+  /// Treats each word of the query as a case-insensitive wildcard pattern,
+  /// where `*` matches any sequence of characters and `?` matches a single
+  /// character. A pattern matches the tokens of the field or its whole value.
+  #[serde(alias = "wildcard")]
+  Wildcard,
+  /// Treats the query text, or the rule's [`pattern`](FieldRule::pattern), as
+  /// a case-insensitive regular expression and scores the number of matches
+  /// in the field text. Requires the `regex` feature; without it, the field
+  /// never matches.
+  #[serde(alias = "regex")]
+  Regex,
+}
+
+/// The variants of the BM25 scoring function.
+///
+/// Classic BM25 normalizes term frequencies by document length so strongly
+/// that a match in a very long document can score barely more than no match
+/// at all. BM25+ and BM25L raise this lower bound, which helps long documents
+/// in corpora of varying lengths.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum BM25Variant {
+  /// The classic Okapi BM25 function.
+  #[default]
+  #[serde(alias = "classic")]
+  Classic,
+  /// BM25+, which adds `delta` to the normalized frequency of every matched
+  /// term, so that a match always contributes at least `delta` times its IDF.
+  #[serde(alias = "plus")]
+  Plus {
+    /// The lower bound of the normalized term frequency, usually 1.0.
+    delta: f32,
+  },
+  /// BM25L, which shifts the length-normalized term frequency by `delta`
+  /// before saturating it, reducing the penalty of long documents.
+  #[serde(alias = "l")]
+  L {
+    /// The shift of the length-normalized term frequency, usually 0.5.
+    delta: f32,
+  },
+}
+
+impl BM25Variant {
+  /// Returns BM25+ with the usual `delta` of 1.0.
+  pub fn bm25_plus() -> Self {
+    BM25Variant::Plus { delta: 1.0 }
+  }
+
+  /// Returns BM25L with the usual `delta` of 0.5.
+  pub fn bm25l() -> Self {
+    BM25Variant::L { delta: 0.5 }
+  }
 }
 
 /// Defines the search behavior for a nested object.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ObjectRule {
   /// The method used to access the nested object.
+  #[serde(default)]
   pub access: ObjectAccess,
   /// A map of field names within the nested object to their corresponding `FieldRule`.
+  #[serde(default)]
   pub fields: HashMap<String, FieldRule>,
+  /// How the scores of the elements of an `Array` object are combined.
+  #[serde(default)]
+  pub score_mode: ScoreMode,
 }
 
 impl ObjectRule {
@@ -191,6 +518,36 @@ impl ObjectRule {
     ObjectRuleBuilder {
       access: ObjectAccess::Direct,
       fields: HashMap::new(),
+      score_mode: ScoreMode::default(),
+    }
+  }
+
+  /// Creates a builder for an `ObjectRule` applied to each element of an
+  /// array of objects.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::rules::{FieldRule, ObjectRule, ScoreMode, SemanticRules};
+  ///
+  /// // Rank posts by their best matching comment.
+  /// let rules = SemanticRules::builder()
+  ///     .field("title", FieldRule::bm25())
+  ///     .object(
+  ///         "comments",
+  ///         ObjectRule::array()
+  ///             .field("author", FieldRule::exact())
+  ///             .field("text", FieldRule::bm25())
+  ///             .score_mode(ScoreMode::Max)
+  ///             .build(),
+  ///     )
+  ///     .build();
+  /// ```
+  pub fn array() -> ObjectRuleBuilder {
+    ObjectRuleBuilder {
+      access: ObjectAccess::Array,
+      fields: HashMap::new(),
+      score_mode: ScoreMode::default(),
     }
   }
 }
@@ -200,6 +557,7 @@ impl ObjectRule {
 pub struct ObjectRuleBuilder {
   access: ObjectAccess,
   fields: HashMap<String, FieldRule>,
+  score_mode: ScoreMode,
 }
 
 impl ObjectRuleBuilder {
@@ -209,18 +567,79 @@ impl ObjectRuleBuilder {
     self
   }
 
+  /// Sets how the scores of the elements of an array are combined.
+  pub fn score_mode(mut self, score_mode: ScoreMode) -> Self {
+    self.score_mode = score_mode;
+    self
+  }
+
   /// Builds the final `ObjectRule` object.
   pub fn build(self) -> ObjectRule {
     ObjectRule {
       access: self.access,
       fields: self.fields,
+      score_mode: self.score_mode,
     }
   }
 }
 
 /// Defines how a nested object is accessed within a parent object.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ObjectAccess {
   /// The object is a direct property of its parent.
+  #[default]
+  #[serde(alias = "direct")]
   Direct,
+  /// The property is an array of objects, and the rules apply to each
+  /// element. The scores of the elements are combined according to the
+  /// rule's [`ScoreMode`].
+  #[serde(alias = "array")]
+  Array,
+}
+
+/// Defines how the scores of the elements of an array are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ScoreMode {
+  /// The sum of the scores, favoring documents with many matching elements.
+  #[default]
+  #[serde(alias = "sum")]
+  Sum,
+  /// The highest score, ranking documents by their best matching element.
+  #[serde(alias = "max")]
+  Max,
+  /// The average score of the matching elements.
+  #[serde(alias = "avg")]
+  Avg,
+}
+
+impl ScoreMode {
+  /// Combines the scores of the elements of an array.
+  ///
+  /// Elements that do not match (with a score of zero) are ignored by `Avg`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::rules::ScoreMode;
+  ///
+  /// let scores = [1.0, 0.0, 3.0];
+  /// assert_eq!(ScoreMode::Sum.combine(&scores), 4.0);
+  /// assert_eq!(ScoreMode::Max.combine(&scores), 3.0);
+  /// assert_eq!(ScoreMode::Avg.combine(&scores), 2.0);
+  /// ```
+  pub fn combine(&self, scores: &[f32]) -> f32 {
+    let matched = scores.iter().copied().filter(|&score| score > 0.0);
+    match self {
+      ScoreMode::Sum => matched.sum(),
+      ScoreMode::Max => matched.fold(0.0, f32::max),
+      ScoreMode::Avg => {
+        let (sum, count) = matched.fold((0.0, 0), |(sum, count), score| (sum + score, count + 1));
+        if count > 0 {
+          sum / count as f32
+        } else {
+          0.0
+        }
+      }
+    }
+  }
 }
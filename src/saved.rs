@@ -0,0 +1,375 @@
+//! Saved, named queries with placeholders.
+//!
+//! A [`SavedQueries`] registry keeps the canned searches of an application
+//! ("trending", "new this week") in one place. Each [`SavedQuery`] is a
+//! query template whose strings may contain `{{name}}` placeholders, filled
+//! in from parameters when the query is run by name.
+//!
+//! A string that is a single placeholder is replaced by the parameter's
+//! value as it is, so a number stays a number and a list stays a list (for
+//! the `In` operator, say). Placeholders inside longer strings are replaced
+//! by the text of their value.
+//!
+//! The registry serializes as a map of names to saved queries, with the
+//! queries in their JSON form, and loads from that format with
+//! [`SavedQueries::from_json`] and [`SavedQueries::from_yaml`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use searus::prelude::*;
+//! use searus::saved::{SavedQueries, SavedQuery};
+//! use serde_json::json;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Debug, Clone, serde::Serialize)]
+//! struct Post { title: String, category: String, published: u64 }
+//!
+//! let posts = vec![
+//!     Post { title: "Rust 2024".into(), category: "news".into(), published: 1_000 },
+//!     Post { title: "Rust 2015".into(), category: "news".into(), published: 10 },
+//!     Post { title: "Rust tips".into(), category: "guides".into(), published: 900 },
+//! ];
+//!
+//! let saved = SavedQueries::new().with(
+//!     "new-in-category",
+//!     SavedQuery::new(
+//!         &Query::builder()
+//!             .text("rust")
+//!             .filters(
+//!                 Query::filter(Query::COMPARE)
+//!                     .eq("category", "{{category}}")
+//!                     .ge("published", "{{since}}")
+//!                     .build(),
+//!             )
+//!             .build(),
+//!     )
+//!     .description("Posts in a category published since a timestamp")
+//!     .default_value("category", "news"),
+//! );
+//!
+//! let rules = SemanticRules::builder().field("title", FieldRule::tokenized()).build();
+//! let engine = SearusEngine::builder()
+//!     .with(Box::new(SemanticSearch::new(rules)))
+//!     .build();
+//!
+//! let params = HashMap::from([("since".to_string(), json!(500))]);
+//! let results = saved.search(&engine, &posts, "new-in-category", &params).unwrap();
+//! assert_eq!(results.len(), 1);
+//! assert_eq!(results[0].item.title, "Rust 2024");
+//!
+//! // Saved queries round-trip through JSON.
+//! let json = serde_json::to_string(&saved).unwrap();
+//! assert_eq!(SavedQueries::from_json(&json).unwrap(), saved);
+//! ```
+
+use crate::engine::SearusEngine;
+use crate::types::{Query, SearchResponse, Searchable, SearusMatch};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A query template, with the default values of its placeholders.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedQuery {
+  /// What the query finds, for listings.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+  /// The query, in its JSON form, whose strings may contain `{{name}}`
+  /// placeholders.
+  pub query: Value,
+  /// The values of the placeholders that parameters may leave out.
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub defaults: BTreeMap<String, Value>,
+}
+
+impl SavedQuery {
+  /// Creates a saved query from a query whose strings may contain
+  /// `{{name}}` placeholders.
+  ///
+  /// Placeholders can stand for any string of the query, such as its text,
+  /// tags, or filter values. Templates loaded from JSON may also use them
+  /// in place of numbers, like the limit of the options.
+  pub fn new(query: &Query) -> Self {
+    Self {
+      description: None,
+      query: serde_json::to_value(query).expect("queries serialize to JSON"),
+      defaults: BTreeMap::new(),
+    }
+  }
+
+  /// Sets the description of the query.
+  pub fn description(mut self, description: impl Into<String>) -> Self {
+    self.description = Some(description.into());
+    self
+  }
+
+  /// Sets the value of a placeholder when the parameters leave it out.
+  pub fn default_value(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+    self.defaults.insert(name.into(), value.into());
+    self
+  }
+
+  /// Returns the names of the placeholders of the query, in order.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::saved::SavedQuery;
+  ///
+  /// let saved = SavedQuery::new(
+  ///     &Query::builder()
+  ///         .text("{{topic}} for {{ level }}")
+  ///         .tags(vec!["{{topic}}".into()])
+  ///         .build(),
+  /// );
+  /// assert_eq!(saved.placeholders(), ["level", "topic"]);
+  /// ```
+  pub fn placeholders(&self) -> Vec<String> {
+    let mut names = BTreeSet::new();
+    collect_placeholders(&self.query, &mut names);
+    names.into_iter().map(str::to_string).collect()
+  }
+
+  /// Fills in the placeholders of the query with the given parameters, or
+  /// their default values.
+  ///
+  /// Returns an error if a placeholder has neither, if a parameter matches
+  /// no placeholder, or if the filled-in template is not a valid query.
+  pub fn bind(&self, params: &HashMap<String, Value>) -> Result<Query, String> {
+    let placeholders = self.placeholders();
+    if let Some(name) = params.keys().find(|name| !placeholders.contains(name)) {
+      return Err(format!("Unknown parameter \"{}\"", name));
+    }
+
+    let value = |name: &str| {
+      params
+        .get(name)
+        .or_else(|| self.defaults.get(name))
+        .ok_or_else(|| format!("Missing parameter \"{}\"", name))
+    };
+    let query = substitute(&self.query, &value)?;
+    serde_json::from_value(query).map_err(|e| format!("Invalid query: {}", e))
+  }
+}
+
+/// A registry of saved queries, by name.
+///
+/// Names are listed in alphabetical order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SavedQueries {
+  queries: BTreeMap<String, SavedQuery>,
+}
+
+impl SavedQueries {
+  /// Creates an empty registry.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Loads saved queries from JSON: an object mapping names to saved
+  /// queries.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::saved::SavedQueries;
+  /// use serde_json::json;
+  /// use std::collections::HashMap;
+  ///
+  /// let saved = SavedQueries::from_json(r#"{
+  ///     "top": {
+  ///         "description": "The best matches of a text",
+  ///         "query": { "text": "{{text}}", "options": { "limit": "{{limit}}" } },
+  ///         "defaults": { "limit": 3 }
+  ///     }
+  /// }"#).unwrap();
+  ///
+  /// let params = HashMap::from([("text".to_string(), json!("rust"))]);
+  /// let query = saved.query("top", &params).unwrap();
+  /// assert_eq!(query.text.as_deref(), Some("rust"));
+  /// assert_eq!(query.options.limit, 3);
+  /// ```
+  pub fn from_json(json: &str) -> Result<Self, String> {
+    serde_json::from_str(json).map_err(|e| format!("Invalid saved queries: {}", e))
+  }
+
+  /// Loads saved queries from YAML, in the same format as
+  /// [`from_json`](Self::from_json). Requires the `yaml` feature.
+  #[cfg(feature = "yaml")]
+  pub fn from_yaml(yaml: &str) -> Result<Self, String> {
+    serde_yaml::from_str(yaml).map_err(|e| format!("Invalid saved queries: {}", e))
+  }
+
+  /// Adds a saved query, replacing any query of the same name.
+  pub fn with(mut self, name: impl Into<String>, query: SavedQuery) -> Self {
+    self.insert(name, query);
+    self
+  }
+
+  /// Adds a saved query, returning the query it replaced, if any.
+  pub fn insert(&mut self, name: impl Into<String>, query: SavedQuery) -> Option<SavedQuery> {
+    self.queries.insert(name.into(), query)
+  }
+
+  /// Removes a saved query, returning it if it existed.
+  pub fn remove(&mut self, name: &str) -> Option<SavedQuery> {
+    self.queries.remove(name)
+  }
+
+  /// Returns the saved query with the given name, if any.
+  pub fn get(&self, name: &str) -> Option<&SavedQuery> {
+    self.queries.get(name)
+  }
+
+  /// Returns the saved queries with their names, in alphabetical order.
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &SavedQuery)> {
+    self
+      .queries
+      .iter()
+      .map(|(name, query)| (name.as_str(), query))
+  }
+
+  /// Returns the names of the saved queries, in alphabetical order.
+  pub fn names(&self) -> Vec<&str> {
+    self.queries.keys().map(String::as_str).collect()
+  }
+
+  /// Returns the number of saved queries.
+  pub fn len(&self) -> usize {
+    self.queries.len()
+  }
+
+  /// Returns `true` if there are no saved queries.
+  pub fn is_empty(&self) -> bool {
+    self.queries.is_empty()
+  }
+
+  /// Returns the saved query with the given name, with its placeholders
+  /// filled in (see [`SavedQuery::bind`]).
+  pub fn query(&self, name: &str, params: &HashMap<String, Value>) -> Result<Query, String> {
+    let saved = self
+      .get(name)
+      .ok_or_else(|| format!("No saved query named \"{}\"", name))?;
+    saved
+      .bind(params)
+      .map_err(|e| format!("Saved query \"{}\": {}", name, e))
+  }
+
+  /// Runs the saved query with the given name, as
+  /// [`SearusEngine::search`] does.
+  pub fn search<T>(
+    &self,
+    engine: &SearusEngine<T>,
+    items: &[T],
+    name: &str,
+    params: &HashMap<String, Value>,
+  ) -> Result<Vec<SearusMatch<T>>, String>
+  where
+    T: Searchable + Clone + Serialize,
+  {
+    Ok(engine.search(items, &self.query(name, params)?))
+  }
+
+  /// Runs the saved query with the given name, as
+  /// [`SearusEngine::search_response`] does.
+  pub fn search_response<T>(
+    &self,
+    engine: &SearusEngine<T>,
+    items: &[T],
+    name: &str,
+    params: &HashMap<String, Value>,
+  ) -> Result<SearchResponse<T>, String>
+  where
+    T: Searchable + Clone + Serialize,
+  {
+    Ok(engine.search_response(items, &self.query(name, params)?))
+  }
+}
+
+/// Returns the first placeholder of a text: its start, its end, and its
+/// name.
+///
+/// Names are trimmed, and made of letters, digits, `_`, and `-`; anything
+/// else between braces is text.
+fn next_placeholder(text: &str) -> Option<(usize, usize, &str)> {
+  let mut from = 0;
+  while let Some(offset) = text[from..].find("{{") {
+    let start = from + offset;
+    let close = text[start + 2..].find("}}")? + start + 2;
+    let name = text[start + 2..close].trim();
+    let valid = !name.is_empty()
+      && name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    if valid {
+      return Some((start, close + 2, name));
+    }
+    from = start + 2;
+  }
+  None
+}
+
+/// Adds the names of the placeholders in the strings of a value.
+fn collect_placeholders<'a>(value: &'a Value, names: &mut BTreeSet<&'a str>) {
+  match value {
+    Value::String(text) => {
+      let mut rest = text.as_str();
+      while let Some((_, end, name)) = next_placeholder(rest) {
+        names.insert(name);
+        rest = &rest[end..];
+      }
+    }
+    Value::Array(values) => values
+      .iter()
+      .for_each(|value| collect_placeholders(value, names)),
+    Value::Object(map) => map
+      .values()
+      .for_each(|value| collect_placeholders(value, names)),
+    _ => {}
+  }
+}
+
+/// Replaces the placeholders in the strings of a value with the values
+/// returned by `lookup`.
+fn substitute<'a, F>(value: &Value, lookup: &F) -> Result<Value, String>
+where
+  F: Fn(&str) -> Result<&'a Value, String>,
+{
+  match value {
+    Value::String(text) => {
+      // A string that is a single placeholder takes the value as it is.
+      if let Some((0, end, name)) = next_placeholder(text) {
+        if end == text.len() {
+          return lookup(name).cloned();
+        }
+      }
+
+      let mut result = String::new();
+      let mut rest = text.as_str();
+      while let Some((start, end, name)) = next_placeholder(rest) {
+        result.push_str(&rest[..start]);
+        match lookup(name)? {
+          Value::String(value) => result.push_str(value),
+          value => result.push_str(&value.to_string()),
+        }
+        rest = &rest[end..];
+      }
+      result.push_str(rest);
+      Ok(Value::String(result))
+    }
+    Value::Array(values) => values
+      .iter()
+      .map(|value| substitute(value, lookup))
+      .collect::<Result<_, _>>()
+      .map(Value::Array),
+    Value::Object(map) => map
+      .iter()
+      .map(|(key, value)| Ok((key.clone(), substitute(value, lookup)?)))
+      .collect::<Result<_, String>>()
+      .map(Value::Object),
+    value => Ok(value.clone()),
+  }
+}
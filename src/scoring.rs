@@ -0,0 +1,501 @@
+//! Score modifiers that adjust the merged score of each result.
+//!
+//! A [`ScoreModifier`] is applied by the engine to every merged result, right
+//! after the results of all searchers have been combined and before the
+//! `after_merge` extension hook runs. Modifiers are registered with
+//! [`SearusEngineBuilder::score_modifier`](crate::engine::SearusEngineBuilder::score_modifier)
+//! and applied in registration order.
+//!
+//! Any `Fn(&T, f32) -> f32` closure is also a score modifier, which makes it
+//! easy to express business ranking rules (e.g., pinning sponsored items)
+//! without implementing a full `Searcher`. A modifier can also be applied to
+//! a single search with
+//! [`SearusEngine::search_with_modifier`](crate::engine::SearusEngine::search_with_modifier).
+//!
+//! Built-in modifiers:
+//!
+//! - [`RecencyDecay`]: Favors recent items based on a timestamp field.
+//! - [`FieldValueBoost`]: Boosts items by a numeric field such as a popularity count.
+
+use crate::document::{get_field_value, to_document};
+use serde_json::Value;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A function that adjusts the merged score of a result based on its item.
+pub trait ScoreModifier<T>: Send + Sync {
+  /// Returns the new score for the item, given its current score.
+  fn modify(&self, item: &T, score: f32) -> f32;
+}
+
+impl<T, F> ScoreModifier<T> for F
+where
+  F: Fn(&T, f32) -> f32 + Send + Sync,
+{
+  fn modify(&self, item: &T, score: f32) -> f32 {
+    self(item, score)
+  }
+}
+
+/// The shape of the curve used to decay scores with age.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecayFunction {
+  /// Halves the score every half-life: `0.5^(age / half_life)`.
+  Exponential,
+  /// A bell curve that is flat for recent items and drops sharply
+  /// afterwards: `0.5^((age / half_life)^2)`.
+  Gaussian,
+  /// Decreases linearly, reaching zero at twice the half-life.
+  Linear,
+}
+
+impl DecayFunction {
+  /// Calculates the decay factor for an age expressed in half-lives.
+  fn factor(&self, half_lives: f64) -> f64 {
+    let x = half_lives.max(0.0);
+    match self {
+      DecayFunction::Exponential => 0.5f64.powf(x),
+      DecayFunction::Gaussian => 0.5f64.powf(x * x),
+      DecayFunction::Linear => (1.0 - 0.5 * x).max(0.0),
+    }
+  }
+}
+
+/// A score modifier that favors recent items.
+///
+/// The age of an item is read from a timestamp field, which may hold either a
+/// number of seconds since the Unix epoch or an RFC 3339 string
+/// (e.g., `"2024-05-01T12:00:00Z"` or `"2024-05-01"`). The score is multiplied
+/// by a decay factor which is `1.0` for items dated at the origin (now, by
+/// default) and `0.5` for items one half-life old. Items dated in the future
+/// are not decayed.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::scoring::{DecayFunction, RecencyDecay, ScoreModifier};
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Post {
+///     title: String,
+///     published_at: String,
+/// }
+///
+/// let decay = RecencyDecay::new("published_at", 7.0 * 24.0 * 3600.0) // one week
+///     .function(DecayFunction::Exponential)
+///     .origin_from_str("2024-05-15T00:00:00Z");
+///
+/// let post = Post { title: "Rust".into(), published_at: "2024-05-08T00:00:00Z".into() };
+/// assert!((decay.modify(&post, 1.0) - 0.5).abs() < 1e-6);
+///
+/// let engine: SearusEngine<Post> = SearusEngine::builder()
+///     .score_modifier(Box::new(decay))
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecencyDecay {
+  /// The path of the timestamp field.
+  field: String,
+  /// The age, in seconds, at which the decay factor reaches 0.5.
+  half_life: f64,
+  /// The shape of the decay curve.
+  function: DecayFunction,
+  /// The reference time in seconds since the epoch; `None` means "now".
+  origin: Option<f64>,
+  /// How strongly the decay affects the score, from 0.0 (not at all) to 1.0.
+  weight: f32,
+  /// The factor applied to items without a readable timestamp.
+  missing: f32,
+}
+
+impl RecencyDecay {
+  /// Creates a new exponential `RecencyDecay` on the given field.
+  ///
+  /// # Arguments
+  ///
+  /// * `field` - The path of the timestamp field (dot notation supported).
+  /// * `half_life_secs` - The age, in seconds, at which scores are halved.
+  pub fn new(field: impl Into<String>, half_life_secs: f64) -> Self {
+    Self {
+      field: field.into(),
+      half_life: half_life_secs,
+      function: DecayFunction::Exponential,
+      origin: None,
+      weight: 1.0,
+      missing: 1.0,
+    }
+  }
+
+  /// Sets the shape of the decay curve.
+  pub fn function(mut self, function: DecayFunction) -> Self {
+    self.function = function;
+    self
+  }
+
+  /// Sets the reference time, in seconds since the Unix epoch.
+  ///
+  /// By default, ages are measured from the time of the search.
+  pub fn origin(mut self, secs: f64) -> Self {
+    self.origin = Some(secs);
+    self
+  }
+
+  /// Sets the reference time from an RFC 3339 string.
+  ///
+  /// Invalid strings leave the origin unchanged.
+  pub fn origin_from_str(mut self, timestamp: &str) -> Self {
+    if let Some(secs) = parse_timestamp(timestamp) {
+      self.origin = Some(secs);
+    }
+    self
+  }
+
+  /// Sets how strongly the decay affects the score.
+  ///
+  /// With a weight `w`, the score is multiplied by `(1 - w) + w * decay`, so
+  /// a weight of 0.3 never lowers a score by more than 30%. The default is 1.0.
+  pub fn weight(mut self, weight: f32) -> Self {
+    self.weight = weight.clamp(0.0, 1.0);
+    self
+  }
+
+  /// Sets the decay factor applied to items without a readable timestamp.
+  ///
+  /// The default is 1.0, which leaves such items unchanged.
+  pub fn missing(mut self, factor: f32) -> Self {
+    self.missing = factor;
+    self
+  }
+
+  /// Calculates the decay factor of an item.
+  fn decay<T: serde::Serialize>(&self, item: &T) -> f32 {
+    let timestamp = to_document(item)
+      .ok()
+      .and_then(|value| get_field_value(&value, &self.field).and_then(timestamp_of));
+
+    let timestamp = match timestamp {
+      Some(t) => t,
+      None => return self.missing,
+    };
+
+    let origin = self.origin.unwrap_or_else(now);
+
+    if self.half_life <= 0.0 {
+      return 1.0;
+    }
+
+    self.function.factor((origin - timestamp) / self.half_life) as f32
+  }
+}
+
+/// Returns the current Unix time in seconds.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) fn now() -> f64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs_f64())
+    .unwrap_or(0.0)
+}
+
+/// Returns the current Unix time in seconds. `SystemTime` is unsupported in
+/// the browser, so the time comes from JavaScript.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) fn now() -> f64 {
+  js_sys::Date::now() / 1000.0
+}
+
+impl<T: serde::Serialize> ScoreModifier<T> for RecencyDecay {
+  fn modify(&self, item: &T, score: f32) -> f32 {
+    score * ((1.0 - self.weight) + self.weight * self.decay(item))
+  }
+}
+
+/// How a numeric field value is scaled before it boosts a score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueScaling {
+  /// Uses the value as-is.
+  Linear,
+  /// Uses `ln(1 + value)`, which dampens very large values (e.g., view counts).
+  Log1p,
+  /// Uses the square root of the value.
+  Sqrt,
+}
+
+impl ValueScaling {
+  /// Scales a (non-negative) value.
+  fn apply(&self, value: f64) -> f64 {
+    let value = value.max(0.0);
+    match self {
+      ValueScaling::Linear => value,
+      ValueScaling::Log1p => value.ln_1p(),
+      ValueScaling::Sqrt => value.sqrt(),
+    }
+  }
+}
+
+/// A score modifier that boosts results by a numeric field, such as a
+/// popularity signal like `views` or `stars`.
+///
+/// The new score is `score * (1 + weight * scale(value))`, so an item with a
+/// value of zero keeps its relevance score, and more popular items are
+/// boosted proportionally to their relevance. Negative values are treated as
+/// zero, and items without the field use the `missing` value (0 by default).
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::scoring::{FieldValueBoost, ScoreModifier, ValueScaling};
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Repo {
+///     name: String,
+///     stars: u32,
+/// }
+///
+/// let boost = FieldValueBoost::new("stars")
+///     .scaling(ValueScaling::Log1p)
+///     .weight(0.1);
+///
+/// let popular = Repo { name: "searus".into(), stars: 1000 };
+/// let unknown = Repo { name: "searus-fork".into(), stars: 0 };
+/// assert!(boost.modify(&popular, 1.0) > boost.modify(&unknown, 1.0));
+/// assert_eq!(boost.modify(&unknown, 1.0), 1.0);
+///
+/// let engine: SearusEngine<Repo> = SearusEngine::builder()
+///     .score_modifier(Box::new(boost))
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FieldValueBoost {
+  /// The path of the numeric field.
+  field: String,
+  /// How the value is scaled.
+  scaling: ValueScaling,
+  /// The multiplier applied to the scaled value.
+  weight: f32,
+  /// The value used for items without a numeric field.
+  missing: f64,
+}
+
+impl FieldValueBoost {
+  /// Creates a new `FieldValueBoost` on the given field.
+  ///
+  /// The default scaling is `Log1p` with a weight of 1.0.
+  pub fn new(field: impl Into<String>) -> Self {
+    Self {
+      field: field.into(),
+      scaling: ValueScaling::Log1p,
+      weight: 1.0,
+      missing: 0.0,
+    }
+  }
+
+  /// Sets how the field value is scaled.
+  pub fn scaling(mut self, scaling: ValueScaling) -> Self {
+    self.scaling = scaling;
+    self
+  }
+
+  /// Sets the multiplier applied to the scaled value.
+  pub fn weight(mut self, weight: f32) -> Self {
+    self.weight = weight;
+    self
+  }
+
+  /// Sets the value used for items without a numeric field.
+  pub fn missing(mut self, value: f64) -> Self {
+    self.missing = value;
+    self
+  }
+}
+
+impl<T: serde::Serialize> ScoreModifier<T> for FieldValueBoost {
+  fn modify(&self, item: &T, score: f32) -> f32 {
+    let value = to_document(item)
+      .ok()
+      .and_then(|value| get_field_value(&value, &self.field).and_then(Value::as_f64))
+      .unwrap_or(self.missing);
+
+    score * (1.0 + self.weight * self.scaling.apply(value) as f32)
+  }
+}
+
+/// Reads a timestamp, in seconds since the epoch, from a JSON value.
+pub(crate) fn timestamp_of(value: &Value) -> Option<f64> {
+  match value {
+    Value::Number(n) => n.as_f64(),
+    Value::String(s) => parse_timestamp(s),
+    _ => None,
+  }
+}
+
+/// Parses an RFC 3339 timestamp into seconds since the epoch.
+///
+/// Supports full dates (`2024-05-01`) and date-times with an optional
+/// fractional second and a `Z` or `±HH:MM` offset (`2024-05-01T12:30:00.5Z`).
+/// Date-times without an offset are interpreted as UTC. Anything else is not
+/// a timestamp, including strings that merely look like one, such as
+/// `1-2-3`, `2024-02-31`, or `2024-05-01T24:00:00Z`.
+///
+/// Timestamps are parsed here rather than with `chrono` or `time`: filters
+/// and [`RecencyDecay`] only need these formats, so dates work without a
+/// feature flag or extra dependency.
+pub(crate) fn parse_timestamp(s: &str) -> Option<f64> {
+  let s = s.trim();
+  if !s.is_ascii() {
+    return None;
+  }
+  let (date, time) = match s.find(['T', 't', ' ']) {
+    Some(i) => (&s[..i], Some(&s[i + 1..])),
+    None => (s, None),
+  };
+
+  // YYYY-MM-DD
+  if date.len() != 10 || &date[4..5] != "-" || &date[7..8] != "-" {
+    return None;
+  }
+  let year = fixed_digits(&date[..4])?;
+  let month = fixed_digits(&date[5..7])?;
+  let day = fixed_digits(&date[8..])?;
+  if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+    return None;
+  }
+
+  let mut secs = days_from_civil(year as i64, month, day) as f64 * 86_400.0;
+
+  if let Some(time) = time {
+    let (clock, offset) = match time.find(['Z', 'z', '+', '-']) {
+      Some(i) => (&time[..i], &time[i..]),
+      None => (time, ""),
+    };
+
+    // HH:MM:SS, then an optional fraction of a second.
+    if clock.len() < 8 || &clock[2..3] != ":" || &clock[5..6] != ":" {
+      return None;
+    }
+    let hours = fixed_digits(&clock[..2])?;
+    let minutes = fixed_digits(&clock[3..5])?;
+    // 60 is a leap second.
+    let seconds = fixed_digits(&clock[6..8])?;
+    if hours > 23 || minutes > 59 || seconds > 60 {
+      return None;
+    }
+    let fraction = match &clock[8..] {
+      "" => 0.0,
+      fraction => {
+        let digits = fraction.strip_prefix('.')?;
+        fixed_digits(digits)?;
+        format!("0.{}", digits).parse::<f64>().ok()?
+      }
+    };
+    secs += (hours * 3600 + minutes * 60 + seconds) as f64 + fraction;
+
+    match offset {
+      "" | "Z" | "z" => {}
+      _ => {
+        // ±HH:MM
+        if offset.len() != 6 || &offset[3..4] != ":" {
+          return None;
+        }
+        let h = fixed_digits(&offset[1..3])?;
+        let m = fixed_digits(&offset[4..])?;
+        if h > 23 || m > 59 {
+          return None;
+        }
+        let offset_secs = (h * 3600 + m * 60) as f64;
+        if offset.starts_with('+') {
+          secs -= offset_secs;
+        } else {
+          secs += offset_secs;
+        }
+      }
+    }
+  }
+
+  Some(secs)
+}
+
+/// Parses a run of ASCII digits, rejecting signs and empty strings.
+fn fixed_digits(s: &str) -> Option<u32> {
+  if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+  s.parse().ok()
+}
+
+/// Returns the number of days in the given month.
+fn days_in_month(year: u32, month: u32) -> u32 {
+  match month {
+    2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    _ => 31,
+  }
+}
+
+/// Returns the number of days between 1970-01-01 and the given civil date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+  let y = if month <= 2 { year - 1 } else { year };
+  let era = if y >= 0 { y } else { y - 399 } / 400;
+  let yoe = y - era * 400;
+  let m = month as i64;
+  let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+  let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+  era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_timestamp() {
+    assert_eq!(parse_timestamp("1970-01-01"), Some(0.0));
+    assert_eq!(parse_timestamp("1970-01-02T00:00:00Z"), Some(86_400.0));
+    assert_eq!(parse_timestamp("2000-03-01T00:00:00Z"), Some(951_868_800.0));
+    assert_eq!(parse_timestamp("1970-01-01T01:00:00+01:00"), Some(0.0));
+    assert_eq!(
+      parse_timestamp("2024-02-29T12:30:00.25Z"),
+      Some(1_709_209_800.25)
+    );
+    assert_eq!(parse_timestamp("not a date"), None);
+  }
+
+  #[test]
+  fn test_parse_timestamp_rejects_near_misses() {
+    for s in [
+      "1-2-3",
+      "12-05-07",
+      "2024-5-01",
+      "+024-05-01",
+      "2024-02-31",
+      "2023-02-29",
+      "2024-13-01",
+      "2024-05-00",
+      "2024-05-01T99:00:00Z",
+      "2024-05-01T12:99:00Z",
+      "2024-05-01T12:30Z",
+      "2024-05-01T12:30:00.Z",
+      "2024-05-01T12:30:00+1",
+      "2024-05-01T12:30:00+01:99",
+      "2024-05-01T12:30:00ZZ",
+    ] {
+      assert_eq!(parse_timestamp(s), None, "{}", s);
+    }
+  }
+
+  #[test]
+  fn test_decay_functions() {
+    for function in [
+      DecayFunction::Exponential,
+      DecayFunction::Gaussian,
+      DecayFunction::Linear,
+    ] {
+      assert!((function.factor(0.0) - 1.0).abs() < 1e-9);
+      assert!((function.factor(1.0) - 0.5).abs() < 1e-9);
+      assert!((function.factor(-1.0) - 1.0).abs() < 1e-9);
+    }
+  }
+}
@@ -2,6 +2,10 @@
 
 use crate::context::SearchContext;
 use crate::types::{Query, Searchable, SearcherKind, SearusMatch};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+#[cfg(any(feature = "semantic", feature = "tagged"))]
+use std::sync::{PoisonError, RwLock};
 
 /// A trait for searcher plugins that can perform a search operation.
 ///
@@ -67,4 +71,283 @@ pub trait Searcher<T: Searchable>: Send + Sync {
   /// have not yet been normalized. The `SearusEngine` will handle normalization
   /// before merging results.
   fn search(&self, context: &SearchContext<T>, query: &Query) -> Vec<SearusMatch<T>>;
+
+  /// Precomputes what the searcher needs to search a slice of items, such as
+  /// corpus statistics or analyzed fields, so that searches over that slice
+  /// don't compute it again.
+  ///
+  /// Searchers keep the state of the last slice they were warmed up with,
+  /// and search other slices as if they were never warmed up. Searchers
+  /// implementing `warm_up` should also implement
+  /// [`cool_down`](Searcher::cool_down), since another slice may later take
+  /// the place of this one in memory. The default implementation does
+  /// nothing.
+  fn warm_up(&self, _items: &[T]) {}
+
+  /// Drops the state precomputed by [`warm_up`](Searcher::warm_up) for a
+  /// slice of items, which is no longer searched. The default implementation
+  /// does nothing.
+  fn cool_down(&self, _items: &[T]) {}
+}
+
+/// The state a searcher precomputed in [`Searcher::warm_up`], along with the
+/// slice of items it was computed for.
+///
+/// Slices are told apart by their item type, address, and length, so the
+/// state is only used by searches over the slice that was warmed up. The
+/// state must be cleared before the slice is freed, or a new slice taking
+/// its place would be searched with it; [`WarmUp`](crate::engine::WarmUp)
+/// does this when dropped.
+#[cfg(any(feature = "semantic", feature = "tagged"))]
+pub(crate) struct WarmState<S> {
+  state: RwLock<Option<(SliceKey, S)>>,
+}
+
+/// Identifies a slice of items: its item type, address, and length.
+#[cfg(any(feature = "semantic", feature = "tagged"))]
+type SliceKey = (&'static str, usize, usize);
+
+#[cfg(any(feature = "semantic", feature = "tagged"))]
+fn slice_key<T>(items: &[T]) -> SliceKey {
+  (
+    std::any::type_name::<T>(),
+    items.as_ptr() as usize,
+    items.len(),
+  )
+}
+
+#[cfg(any(feature = "semantic", feature = "tagged"))]
+impl<S> WarmState<S> {
+  /// Creates an empty state, used by no slice.
+  pub(crate) fn new() -> Self {
+    Self {
+      state: RwLock::new(None),
+    }
+  }
+
+  /// Stores the state precomputed for a slice, replacing any previous one.
+  pub(crate) fn set<T>(&self, items: &[T], state: S) {
+    *self.state.write().unwrap_or_else(PoisonError::into_inner) = Some((slice_key(items), state));
+  }
+
+  /// Drops the state precomputed for a slice, if it is the current one.
+  pub(crate) fn clear<T>(&self, items: &[T]) {
+    let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+    if state
+      .as_ref()
+      .is_some_and(|(key, _)| *key == slice_key(items))
+    {
+      *state = None;
+    }
+  }
+
+  /// Calls `f` with the state precomputed for a slice, or `None` if the
+  /// searcher wasn't warmed up with it.
+  pub(crate) fn with<T, R>(&self, items: &[T], f: impl FnOnce(Option<&S>) -> R) -> R {
+    let state = self.state.read().unwrap_or_else(PoisonError::into_inner);
+    let key = slice_key(items);
+    f(state
+      .as_ref()
+      .filter(|(warm_key, _)| *warm_key == key)
+      .map(|(_, state)| state))
+  }
+}
+
+/// The configuration of one searcher for one search, which extensions can
+/// adjust in the [`before_searcher`](crate::extension::SearusExtension::before_searcher)
+/// hook.
+///
+/// Searchers are shared between searches, so rather than mutating them,
+/// extensions change what a searcher runs with: its own copy of the query,
+/// whether it runs at all, and the raw score its matches must reach.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+///
+/// /// Only keeps close vector matches for short queries.
+/// struct StrictShortQueries;
+///
+/// impl<T: Searchable> SearusExtension<T> for StrictShortQueries {
+///     fn before_searcher(&self, query: &Query, config: &mut SearcherConfig, _state: &mut SearchState) {
+///         let short = query.text.as_ref().is_some_and(|text| text.len() < 4);
+///         if config.kind == SearcherKind::Vector && short {
+///             config.min_score = Some(0.9);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SearcherConfig {
+  /// The kind of the searcher being configured.
+  pub kind: SearcherKind,
+  /// The query the searcher runs with, initially a copy of the search query.
+  /// Changing it only affects this searcher; merging, scoring, and
+  /// pagination use the search query. Matches must still pass the filters
+  /// of the search query to be returned.
+  pub query: Query,
+  /// Whether the searcher runs. A disabled searcher contributes no matches.
+  pub enabled: bool,
+  /// The raw score a match of the searcher must reach to be kept, if any.
+  pub min_score: Option<f32>,
+}
+
+impl SearcherConfig {
+  /// Creates the default configuration of a searcher for a query: enabled,
+  /// with no minimum score.
+  pub fn new(kind: SearcherKind, query: Query) -> Self {
+    Self {
+      kind,
+      query,
+      enabled: true,
+      min_score: None,
+    }
+  }
+}
+
+/// The best-scoring matches of a searcher, up to a limit.
+///
+/// Matches are pushed one at a time; once the limit is reached, each new
+/// match replaces the worst match kept if it scores higher, so at most
+/// `limit` matches are held in memory however many items match. Matches
+/// with the same score are kept in the order of their ids. Without a limit,
+/// every match is kept, in the order it was pushed.
+///
+/// [`SearchContext::scan`] collects the matches of the built-in searchers
+/// this way, limited by [`SearchOptions::max_matches_per_searcher`](crate::types::SearchOptions::max_matches_per_searcher).
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+///
+/// let mut top = TopMatches::new(Some(2));
+/// for (id, score) in [(0, 0.5), (1, 0.9), (2, 0.1), (3, 0.7)] {
+///     top.push(SearusMatch::new(id, score, id));
+/// }
+///
+/// assert!(top.is_truncated());
+/// let ids: Vec<usize> = top.into_sorted_vec().iter().map(|m| m.id).collect();
+/// assert_eq!(ids, [1, 3]);
+/// ```
+pub struct TopMatches<T: Searchable> {
+  /// The maximum number of matches kept, if any.
+  limit: Option<usize>,
+  /// The matches kept, worst first when limited.
+  matches: BinaryHeap<Ranked<T>>,
+  /// The matches kept when unlimited, in the order they were pushed.
+  all: Vec<SearusMatch<T>>,
+  /// Whether any match was dropped.
+  truncated: bool,
+}
+
+/// A match ordered so that the worst match is the greatest.
+struct Ranked<T: Searchable>(SearusMatch<T>);
+
+impl<T: Searchable> Ord for Ranked<T> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    best_first(&self.0, &other.0)
+  }
+}
+
+impl<T: Searchable> PartialOrd for Ranked<T> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<T: Searchable> PartialEq for Ranked<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl<T: Searchable> Eq for Ranked<T> {}
+
+impl<T: Searchable> TopMatches<T> {
+  /// Creates an empty collection keeping up to `limit` matches, or every
+  /// match if `limit` is `None`.
+  pub fn new(limit: Option<usize>) -> Self {
+    Self {
+      limit,
+      matches: BinaryHeap::new(),
+      all: Vec::new(),
+      truncated: false,
+    }
+  }
+
+  /// Adds a match, dropping the worst match kept if the limit is exceeded.
+  pub fn push(&mut self, m: SearusMatch<T>) {
+    let Some(limit) = self.limit else {
+      self.all.push(m);
+      return;
+    };
+
+    let m = Ranked(m);
+    if self.matches.len() < limit {
+      self.matches.push(m);
+      return;
+    }
+    self.truncated = true;
+    if let Some(mut worst) = self.matches.peek_mut() {
+      if m < *worst {
+        *worst = m;
+      }
+    }
+  }
+
+  /// Adds the matches of another collection with the same limit.
+  pub fn merge(mut self, other: Self) -> Self {
+    self.truncated |= other.truncated;
+    self.all.extend(other.all);
+    for m in other.matches {
+      self.push(m.0);
+    }
+    self
+  }
+
+  /// Returns the number of matches kept.
+  pub fn len(&self) -> usize {
+    self.all.len() + self.matches.len()
+  }
+
+  /// Returns `true` if no match is kept.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns `true` if matches were dropped to stay within the limit.
+  pub fn is_truncated(&self) -> bool {
+    self.truncated
+  }
+
+  /// Returns the matches kept: in the order they were pushed without a
+  /// limit, and in no particular order with one.
+  pub fn into_vec(self) -> Vec<SearusMatch<T>> {
+    match self.limit {
+      Some(_) => self.matches.into_iter().map(|m| m.0).collect(),
+      None => self.all,
+    }
+  }
+
+  /// Returns the matches kept, best first.
+  pub fn into_sorted_vec(self) -> Vec<SearusMatch<T>> {
+    let mut matches = self.into_vec();
+    matches.sort_by(best_first);
+    matches
+  }
+}
+
+/// Orders matches best first: by score descending, then by id.
+fn best_first<T: Searchable>(a: &SearusMatch<T>, b: &SearusMatch<T>) -> Ordering {
+  b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id))
+}
+
+impl<T: Searchable> Extend<SearusMatch<T>> for TopMatches<T> {
+  fn extend<I: IntoIterator<Item = SearusMatch<T>>>(&mut self, matches: I) {
+    for m in matches {
+      self.push(m);
+    }
+  }
 }
@@ -0,0 +1,405 @@
+//! Text analysis pipelines.
+//!
+//! An [`Analyzer`] turns a text into the terms that are indexed and matched.
+//! It runs a [`Tokenizer`] followed by an ordered chain of [`TokenFilter`]s,
+//! such as lowercasing, stopword removal, stemming, synonym injection, or
+//! length filtering.
+//!
+//! Text searchers use a default analyzer for every field, and
+//! [`SemanticSearch`](crate::searchers::SemanticSearch) can additionally use
+//! named analyzers for the fields whose
+//! [`FieldRule::analyzer`](crate::rules::FieldRule::analyzer) refers to them.
+//! A few common analyzers are available by name without registration; see
+//! [`Analyzer::builtin`].
+
+use crate::searchers::stopwords::StopWords;
+use crate::searchers::tokenizer::{KeywordTokenizer, Tokenizer, UnicodeTokenizer};
+use crate::synonyms::SynonymMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// The names of the analyzers returned by [`Analyzer::builtin`].
+pub const BUILTIN_ANALYZERS: [&str; 3] = ["standard", "keyword", "edge_ngram"];
+
+/// A token paired with the byte range of the text it originates from.
+pub type Token = (String, Range<usize>);
+
+/// Transforms the token stream of an [`Analyzer`].
+///
+/// Filters may change, remove, or add tokens. Added tokens should reuse the
+/// range of the token they are derived from, so that they are highlighted at
+/// the right place.
+pub trait TokenFilter: Send + Sync {
+  /// Transforms a list of tokens.
+  fn filter(&self, tokens: Vec<Token>) -> Vec<Token>;
+}
+
+/// A filter that lowercases every token.
+///
+/// The default [`UnicodeTokenizer`] already lowercases its output; this filter
+/// is meant for custom tokenizers that preserve case.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LowercaseFilter;
+
+impl TokenFilter for LowercaseFilter {
+  fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+    tokens
+      .into_iter()
+      .map(|(token, range)| (token.to_lowercase(), range))
+      .collect()
+  }
+}
+
+/// A filter that removes tokens shorter or longer than the given bounds,
+/// measured in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthFilter {
+  /// The minimum length of a token to keep.
+  min: usize,
+  /// The maximum length of a token to keep.
+  max: usize,
+}
+
+impl LengthFilter {
+  /// Creates a filter keeping tokens of `min` to `max` characters.
+  pub fn new(min: usize, max: usize) -> Self {
+    Self { min, max }
+  }
+}
+
+impl TokenFilter for LengthFilter {
+  fn filter(&self, mut tokens: Vec<Token>) -> Vec<Token> {
+    tokens.retain(|(token, _)| (self.min..=self.max).contains(&token.chars().count()));
+    tokens
+  }
+}
+
+/// A filter that replaces each token with its leading n-grams, so that
+/// fields can be matched on the prefix of any word.
+///
+/// For example, with a `min_gram` of 2 and a `max_gram` of 4, "search" is
+/// indexed as "se", "sea", "sear", and (as the original is preserved by
+/// default) "search". Queries should not be split into n-grams, so this
+/// filter is meant for index analyzers combined with a separate
+/// [`FieldRule::search_analyzer`](crate::rules::FieldRule::search_analyzer).
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::searchers::analyzer::{Analyzer, EdgeNGramFilter};
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct User { username: String }
+///
+/// let users = vec![User { username: "rustacean".into() }, User { username: "gopher".into() }];
+///
+/// let rules = SemanticRules::builder()
+///     .field("username", FieldRule::tokenized().analyzer("prefix").search_analyzer("standard"))
+///     .build();
+/// let searcher = SemanticSearch::new(rules)
+///     .with_named_analyzer("prefix", Analyzer::default().filter(Box::new(EdgeNGramFilter::new(2, 10))))
+///     .with_named_analyzer("standard", Analyzer::default());
+/// let engine = SearusEngine::builder().with(Box::new(searcher)).build();
+///
+/// let results = engine.search(&users, &Query::builder().text("rust").build());
+/// assert_eq!(results.len(), 1);
+/// assert_eq!(results[0].item.username, "rustacean");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeNGramFilter {
+  /// The length of the shortest prefix, in characters.
+  min_gram: usize,
+  /// The length of the longest prefix, in characters.
+  max_gram: usize,
+  /// Whether the original token is kept when it is longer than `max_gram`.
+  preserve_original: bool,
+}
+
+impl EdgeNGramFilter {
+  /// Creates a filter emitting prefixes of `min_gram` to `max_gram` characters.
+  pub fn new(min_gram: usize, max_gram: usize) -> Self {
+    let min_gram = min_gram.max(1);
+    Self {
+      min_gram,
+      max_gram: max_gram.max(min_gram),
+      preserve_original: true,
+    }
+  }
+
+  /// Sets whether tokens longer than `max_gram` are also kept whole
+  /// (`true` by default).
+  pub fn preserve_original(mut self, preserve_original: bool) -> Self {
+    self.preserve_original = preserve_original;
+    self
+  }
+}
+
+impl TokenFilter for EdgeNGramFilter {
+  fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+    let mut grams = Vec::with_capacity(tokens.len() * (self.max_gram - self.min_gram + 1));
+
+    for (token, range) in tokens {
+      let length = token.chars().count();
+      let mut prefix = String::with_capacity(token.len());
+
+      for (i, c) in token.chars().take(self.max_gram).enumerate() {
+        prefix.push(c);
+        if i + 1 >= self.min_gram {
+          grams.push((prefix.clone(), range.clone()));
+        }
+      }
+
+      if length > self.max_gram && self.preserve_original {
+        grams.push((token, range));
+      }
+    }
+
+    grams
+  }
+}
+
+impl TokenFilter for StopWords {
+  /// Removes stopwords from the tokens.
+  fn filter(&self, mut tokens: Vec<Token>) -> Vec<Token> {
+    tokens.retain(|(token, _)| !self.contains(token));
+    tokens
+  }
+}
+
+#[cfg(feature = "stemming")]
+impl TokenFilter for crate::searchers::stemmer::Stemmer {
+  /// Reduces every token to its stem.
+  fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+    tokens
+      .into_iter()
+      .map(|(token, range)| (self.stem(&token), range))
+      .collect()
+  }
+}
+
+impl TokenFilter for SynonymMap {
+  /// Adds the synonyms of each token after it, at the same position in the text.
+  ///
+  /// Synonym weights are ignored; use
+  /// [`SemanticSearch::with_synonyms`](crate::searchers::SemanticSearch::with_synonyms)
+  /// for weighted query expansion.
+  fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+    let mut expanded = Vec::with_capacity(tokens.len());
+    for (token, range) in tokens {
+      let mut synonyms: Vec<&String> = self
+        .get(&token)
+        .map(|synonyms| synonyms.keys().collect())
+        .unwrap_or_default();
+      synonyms.sort();
+      let synonyms: Vec<Token> = synonyms
+        .into_iter()
+        .map(|synonym| (synonym.clone(), range.clone()))
+        .collect();
+
+      expanded.push((token, range));
+      expanded.extend(synonyms);
+    }
+    expanded
+  }
+}
+
+/// A tokenizer followed by an ordered chain of token filters.
+///
+/// Analyzers are cheap to clone; the tokenizer and filters are shared.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::searchers::analyzer::{Analyzer, LengthFilter};
+///
+/// let analyzer = Analyzer::default()
+///     .filter(Box::new(StopWords::english()))
+///     .filter(Box::new(LengthFilter::new(3, 20)))
+///     .filter(Box::new(SynonymMap::new().with("car", "automobile", 1.0)));
+///
+/// assert_eq!(analyzer.analyze("The car is red"), vec!["car", "automobile", "red"]);
+/// ```
+#[derive(Clone)]
+pub struct Analyzer {
+  /// The tokenizer splitting the text.
+  tokenizer: Arc<dyn Tokenizer>,
+  /// The filters applied to the tokens, in order.
+  filters: Vec<Arc<dyn TokenFilter>>,
+}
+
+impl Default for Analyzer {
+  /// Creates an analyzer using the [`UnicodeTokenizer`] and no filters.
+  fn default() -> Self {
+    Self {
+      tokenizer: Arc::new(UnicodeTokenizer),
+      filters: Vec::new(),
+    }
+  }
+}
+
+impl std::fmt::Debug for Analyzer {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Analyzer")
+      .field("filters", &self.filters.len())
+      .finish_non_exhaustive()
+  }
+}
+
+impl Analyzer {
+  /// Creates an analyzer using the given tokenizer and no filters.
+  pub fn new(tokenizer: Box<dyn Tokenizer>) -> Self {
+    Self {
+      tokenizer: Arc::from(tokenizer),
+      filters: Vec::new(),
+    }
+  }
+
+  /// Creates an analyzer for a language, with the language's bundled
+  /// stopword list and, with the `stemming` feature, its stemmer.
+  ///
+  /// Languages are identified by their ISO 639-1 code or English name
+  /// (e.g., `"en"` or `"english"`). Parts that are not available for the
+  /// language are left out.
+  pub fn for_language(language: &str) -> Self {
+    let mut analyzer = Self::default();
+    if let Some(stopwords) = StopWords::for_language(language) {
+      analyzer = analyzer.filter(Box::new(stopwords));
+    }
+    #[cfg(feature = "stemming")]
+    if let Some(stemmer) = crate::searchers::stemmer::Stemmer::for_language(language) {
+      analyzer = analyzer.filter(Box::new(stemmer));
+    }
+    analyzer
+  }
+
+  /// Returns a built-in analyzer by name.
+  ///
+  /// The built-in analyzers are:
+  ///
+  /// * `"standard"` - The default analyzer, splitting text on Unicode word
+  ///   boundaries and lowercasing each word.
+  /// * `"keyword"` - The [`KeywordTokenizer`], keeping the whole value as a
+  ///   single term, for fields such as SKUs or codes.
+  /// * `"edge_ngram"` - The default analyzer followed by an
+  ///   [`EdgeNGramFilter`] of 2 to 20 characters, for prefix matching. Fields
+  ///   using it should analyze the query with `"standard"`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::searchers::analyzer::Analyzer;
+  ///
+  /// let keyword = Analyzer::builtin("keyword").unwrap();
+  /// assert_eq!(keyword.analyze("AB-1234 XL"), vec!["ab-1234 xl"]);
+  ///
+  /// let ngram = Analyzer::builtin("edge_ngram").unwrap();
+  /// assert_eq!(ngram.analyze("rust"), vec!["ru", "rus", "rust"]);
+  ///
+  /// assert!(Analyzer::builtin("unknown").is_none());
+  /// ```
+  pub fn builtin(name: &str) -> Option<Self> {
+    match name {
+      "standard" => Some(Self::default()),
+      "keyword" => Some(Self::new(Box::new(KeywordTokenizer))),
+      "edge_ngram" => Some(Self::default().filter(Box::new(EdgeNGramFilter::new(2, 20)))),
+      _ => None,
+    }
+  }
+
+  /// Replaces the tokenizer of the analyzer, keeping its filters.
+  pub fn tokenizer(mut self, tokenizer: Box<dyn Tokenizer>) -> Self {
+    self.tokenizer = Arc::from(tokenizer);
+    self
+  }
+
+  /// Appends a filter to the chain. Filters run in the order they are added.
+  pub fn filter(mut self, filter: Box<dyn TokenFilter>) -> Self {
+    self.filters.push(Arc::from(filter));
+    self
+  }
+
+  /// Analyzes a text, keeping the byte range of each term in the text.
+  pub fn analyze_with_offsets(&self, text: &str) -> Vec<Token> {
+    self.filters.iter().fold(
+      self.tokenizer.tokenize_with_offsets(text),
+      |tokens, filter| filter.filter(tokens),
+    )
+  }
+
+  /// Analyzes a text into its terms.
+  pub fn analyze(&self, text: &str) -> Vec<String> {
+    if self.filters.is_empty() {
+      return self.tokenizer.tokenize(text);
+    }
+    self
+      .analyze_with_offsets(text)
+      .into_iter()
+      .map(|(token, _)| token)
+      .collect()
+  }
+
+  /// Returns `true` if both analyzers share their tokenizer and filters, as
+  /// an analyzer and its clones do.
+  #[cfg(feature = "semantic")]
+  pub(crate) fn ptr_eq(&self, other: &Analyzer) -> bool {
+    Arc::ptr_eq(&self.tokenizer, &other.tokenizer)
+      && self.filters.len() == other.filters.len()
+      && self
+        .filters
+        .iter()
+        .zip(&other.filters)
+        .all(|(a, b)| Arc::ptr_eq(a, b))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_filters_keep_offsets() {
+    let analyzer = Analyzer::default()
+      .filter(Box::new(StopWords::new(["a"])))
+      .filter(Box::new(LengthFilter::new(1, 3)));
+    assert_eq!(analyzer.analyze("a big cat"), vec!["big", "cat"]);
+
+    let tokens = analyzer.analyze_with_offsets("a big cat");
+    assert_eq!(tokens[1], ("cat".to_string(), 6..9));
+  }
+
+  #[test]
+  fn test_lowercase_filter() {
+    struct Whitespace;
+    impl Tokenizer for Whitespace {
+      fn tokenize_with_offsets(&self, text: &str) -> Vec<Token> {
+        text
+          .split(' ')
+          .scan(0, |offset, word| {
+            let start = *offset;
+            *offset += word.len() + 1;
+            Some((word.to_string(), start..start + word.len()))
+          })
+          .collect()
+      }
+    }
+
+    let analyzer = Analyzer::new(Box::new(Whitespace)).filter(Box::new(LowercaseFilter));
+    assert_eq!(analyzer.analyze("Hello World"), vec!["hello", "world"]);
+  }
+
+  #[test]
+  fn test_edge_ngram_filter() {
+    let analyzer = Analyzer::default().filter(Box::new(EdgeNGramFilter::new(2, 4)));
+    assert_eq!(
+      analyzer.analyze("a search"),
+      vec!["se", "sea", "sear", "search"]
+    );
+
+    let analyzer = Analyzer::default().filter(Box::new(
+      EdgeNGramFilter::new(2, 3).preserve_original(false),
+    ));
+    assert_eq!(analyzer.analyze("rust"), vec!["ru", "rus"]);
+  }
+}
@@ -0,0 +1,183 @@
+//! A BK-tree index of terms for approximate lookups.
+//!
+//! A BK-tree organizes terms by their edit distance to each other. Since edit
+//! distance is a metric, a lookup only needs to visit the children whose
+//! distance to their parent is within `max_distance` of the distance between
+//! the parent and the searched term, which skips most of the vocabulary.
+
+use strsim::damerau_levenshtein;
+
+/// A node of the tree: a term and its children, keyed by their distance to it.
+#[derive(Debug, Clone)]
+struct Node {
+  /// The term stored at this node.
+  term: String,
+  /// The children of the node, with their distance to `term`.
+  children: Vec<(usize, Node)>,
+}
+
+/// A BK-tree of terms, queried by Damerau-Levenshtein distance.
+///
+/// Distances count insertions, deletions, substitutions, and transpositions
+/// of adjacent characters.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::bktree::BkTree;
+///
+/// let tree: BkTree = ["rust", "trust", "crust", "python"].into_iter().collect();
+///
+/// assert_eq!(tree.len(), 4);
+/// assert_eq!(tree.find("rsut", 1), vec![("rust", 1)]);
+/// assert_eq!(
+///     tree.find("rust", 1),
+///     vec![("rust", 0), ("crust", 1), ("trust", 1)]
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BkTree {
+  /// The root of the tree, if it has any terms.
+  root: Option<Node>,
+  /// The number of distinct terms in the tree.
+  len: usize,
+}
+
+impl BkTree {
+  /// Creates an empty tree.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a term to the tree. Terms that are already present are ignored.
+  ///
+  /// # Returns
+  ///
+  /// `true` if the term was added.
+  pub fn insert(&mut self, term: &str) -> bool {
+    let Some(mut node) = self.root.as_mut() else {
+      self.root = Some(Node {
+        term: term.to_string(),
+        children: Vec::new(),
+      });
+      self.len = 1;
+      return true;
+    };
+
+    loop {
+      let distance = damerau_levenshtein(term, &node.term);
+      if distance == 0 {
+        return false;
+      }
+
+      match node.children.iter().position(|(d, _)| *d == distance) {
+        Some(position) => node = &mut node.children[position].1,
+        None => {
+          node.children.push((
+            distance,
+            Node {
+              term: term.to_string(),
+              children: Vec::new(),
+            },
+          ));
+          self.len += 1;
+          return true;
+        }
+      }
+    }
+  }
+
+  /// Returns the number of terms in the tree.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns `true` if the tree has no terms.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Finds the terms within an edit distance of a term.
+  ///
+  /// # Arguments
+  ///
+  /// * `term` - The term to look up.
+  /// * `max_distance` - The maximum edit distance of the returned terms.
+  ///
+  /// # Returns
+  ///
+  /// The matching terms with their distance to `term`, sorted by distance,
+  /// then alphabetically.
+  pub fn find(&self, term: &str, max_distance: usize) -> Vec<(&str, usize)> {
+    let mut found = Vec::new();
+    let mut stack: Vec<&Node> = self.root.iter().collect();
+
+    while let Some(node) = stack.pop() {
+      let distance = damerau_levenshtein(term, &node.term);
+      if distance <= max_distance {
+        found.push((node.term.as_str(), distance));
+      }
+
+      // By the triangle inequality, only these children can be close enough.
+      let range = distance.saturating_sub(max_distance)..=distance + max_distance;
+      stack.extend(
+        node
+          .children
+          .iter()
+          .filter(|(d, _)| range.contains(d))
+          .map(|(_, child)| child),
+      );
+    }
+
+    found.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+    found
+  }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for BkTree {
+  fn from_iter<I: IntoIterator<Item = S>>(terms: I) -> Self {
+    let mut tree = Self::new();
+    tree.extend(terms);
+    tree
+  }
+}
+
+impl<S: AsRef<str>> Extend<S> for BkTree {
+  fn extend<I: IntoIterator<Item = S>>(&mut self, terms: I) {
+    for term in terms {
+      self.insert(term.as_ref());
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_find_matches_linear_scan() {
+    let terms = [
+      "search", "searches", "research", "reach", "peach", "beach", "teach", "speech", "seat", "sea",
+    ];
+    let tree: BkTree = terms.iter().collect();
+    assert_eq!(tree.len(), terms.len());
+
+    for max_distance in 0..4 {
+      let mut expected: Vec<(&str, usize)> = terms
+        .iter()
+        .map(|term| (*term, damerau_levenshtein("seach", term)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .collect();
+      expected.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+      assert_eq!(tree.find("seach", max_distance), expected);
+    }
+  }
+
+  #[test]
+  fn test_insert_ignores_duplicates() {
+    let mut tree = BkTree::new();
+    assert!(tree.insert("rust"));
+    assert!(!tree.insert("rust"));
+    assert_eq!(tree.len(), 1);
+  }
+}
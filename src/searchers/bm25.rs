@@ -1,15 +1,38 @@
 //! An implementation of the Okapi BM25 scoring algorithm.
 //!
 //! BM25 (Best Matching 25) is a ranking function used by search engines to
-//! estimate the relevance of documents to a given search query.
+//! estimate the relevance of documents to a given search query. The BM25+
+//! and BM25L variants are also supported, see [`BM25Variant`].
 
+use crate::explain::Explanation;
 use std::collections::HashMap;
 
+pub use crate::rules::BM25Variant;
+
 /// A scorer for ranking documents using the BM25 algorithm.
 ///
 /// This struct holds the configuration parameters for BM25 and provides the
 /// method to calculate the score.
-#[derive(Debug, Clone)]
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::bm25::{BM25Scorer, BM25Variant};
+/// use std::collections::HashMap;
+///
+/// let query = vec!["rust".to_string()];
+/// let doc = HashMap::from([("rust".to_string(), 1)]);
+/// let doc_freq = HashMap::from([("rust".to_string(), 1)]);
+///
+/// // A single match in a document ten times longer than average.
+/// let classic = BM25Scorer::new().score(&query, &doc, 100, 10.0, &doc_freq, 10);
+/// let plus = BM25Scorer::new()
+///     .with_variant(BM25Variant::bm25_plus())
+///     .score(&query, &doc, 100, 10.0, &doc_freq, 10);
+///
+/// assert!(plus > classic * 2.0);
+/// ```
+#[derive(Debug, Clone, Copy)]
 pub struct BM25Scorer {
   /// The `k1` parameter controls the term frequency saturation. A higher value
   /// means that the score continues to increase with term frequency, while a
@@ -19,12 +42,18 @@ pub struct BM25Scorer {
   /// 0.0 means no length normalization, while a value of 1.0 means full
   /// normalization. The default is 0.75.
   pub b: f32,
+  /// The variant of the scoring function. The default is classic BM25.
+  pub variant: BM25Variant,
 }
 
 impl Default for BM25Scorer {
-  /// Creates a `BM25Scorer` with the default `k1` and `b` parameters.
+  /// Creates a classic `BM25Scorer` with the default `k1` and `b` parameters.
   fn default() -> Self {
-    Self { k1: 1.5, b: 0.75 }
+    Self {
+      k1: 1.5,
+      b: 0.75,
+      variant: BM25Variant::Classic,
+    }
   }
 }
 
@@ -34,6 +63,12 @@ impl BM25Scorer {
     Self::default()
   }
 
+  /// Sets the variant of the scoring function.
+  pub fn with_variant(mut self, variant: BM25Variant) -> Self {
+    self.variant = variant;
+    self
+  }
+
   /// Calculates the BM25 score of a document for a given query.
   ///
   /// The BM25 score is a sum of the scores for each query term. The score for
@@ -62,6 +97,7 @@ impl BM25Scorer {
     total_docs: usize,
   ) -> f32 {
     let mut score = 0.0;
+    let length_norm = self.length_norm(doc_length, avg_doc_length);
 
     for term in query_terms {
       let tf = *doc_terms.get(term).unwrap_or(&0) as f32;
@@ -72,16 +108,86 @@ impl BM25Scorer {
       let df = *doc_freq.get(term).unwrap_or(&1) as f32;
       let idf = self.idf(df, total_docs);
 
-      // Calculate the normalized term frequency component.
-      let norm_tf = (tf * (self.k1 + 1.0))
-        / (tf + self.k1 * (1.0 - self.b + self.b * (doc_length as f32 / avg_doc_length)));
-
-      score += idf * norm_tf;
+      score += idf * self.norm_tf(tf, length_norm);
     }
 
     score
   }
 
+  /// Explains the BM25 score of a document for a given query.
+  ///
+  /// The returned explanation has the same value as [`score`](Self::score)
+  /// and breaks it down per matched term into its IDF, term frequency, and
+  /// length normalization components. The arguments are the same as for
+  /// `score`.
+  pub fn explain(
+    &self,
+    query_terms: &[String],
+    doc_terms: &HashMap<String, usize>,
+    doc_length: usize,
+    avg_doc_length: f32,
+    doc_freq: &HashMap<String, usize>,
+    total_docs: usize,
+  ) -> Explanation {
+    let length_norm = self.length_norm(doc_length, avg_doc_length);
+    let name = match self.variant {
+      BM25Variant::Classic => "bm25".to_string(),
+      BM25Variant::Plus { delta } => format!("bm25+ with delta = {}", delta),
+      BM25Variant::L { delta } => format!("bm25l with delta = {}", delta),
+    };
+    let mut explanation = Explanation::new(0.0, format!("{}, sum of:", name));
+
+    for term in query_terms {
+      let tf = *doc_terms.get(term).unwrap_or(&0) as f32;
+      if tf == 0.0 {
+        continue;
+      }
+
+      let df = *doc_freq.get(term).unwrap_or(&1) as f32;
+      let idf = self.idf(df, total_docs);
+      let norm_tf = self.norm_tf(tf, length_norm);
+
+      explanation.value += idf * norm_tf;
+      explanation.details.push(
+        Explanation::new(idf * norm_tf, format!("term \"{}\", idf times tf:", term))
+          .with_detail(Explanation::new(
+            idf,
+            format!("idf, {} of {} documents contain the term", df, total_docs),
+          ))
+          .with_detail(
+            Explanation::new(norm_tf, format!("tf, saturated with k1 = {}", self.k1))
+              .with_detail(Explanation::new(tf, "term frequency"))
+              .with_detail(Explanation::new(
+                length_norm,
+                format!(
+                  "length norm with b = {}, document length {} / average {:.2}",
+                  self.b, doc_length, avg_doc_length
+                ),
+              )),
+          ),
+      );
+    }
+
+    explanation
+  }
+
+  /// Calculates the length normalization factor of a document.
+  fn length_norm(&self, doc_length: usize, avg_doc_length: f32) -> f32 {
+    1.0 - self.b + self.b * (doc_length as f32 / avg_doc_length)
+  }
+
+  /// Calculates the saturated, length-normalized term frequency component.
+  fn norm_tf(&self, tf: f32, length_norm: f32) -> f32 {
+    match self.variant {
+      BM25Variant::Classic => (tf * (self.k1 + 1.0)) / (tf + self.k1 * length_norm),
+      BM25Variant::Plus { delta } => (tf * (self.k1 + 1.0)) / (tf + self.k1 * length_norm) + delta,
+      BM25Variant::L { delta } => {
+        let shifted = tf / length_norm + delta;
+        (shifted * (self.k1 + 1.0)) / (shifted + self.k1)
+      }
+    }
+  }
+
   /// Calculates the Inverse Document Frequency (IDF) for a term.
   ///
   /// IDF is a measure of how much information a word provides, i.e., whether
@@ -116,4 +222,49 @@ mod tests {
 
     assert!(score > 0.0);
   }
+
+  #[test]
+  fn test_bm25_explain_matches_score() {
+    let scorer = BM25Scorer::new();
+
+    let query_terms = vec!["rust".to_string(), "missing".to_string()];
+
+    let mut doc_terms = HashMap::new();
+    doc_terms.insert("rust".to_string(), 2);
+
+    let mut doc_freq = HashMap::new();
+    doc_freq.insert("rust".to_string(), 4);
+
+    let score = scorer.score(&query_terms, &doc_terms, 8, 10.0, &doc_freq, 10);
+    let explanation = scorer.explain(&query_terms, &doc_terms, 8, 10.0, &doc_freq, 10);
+
+    assert!((explanation.value - score).abs() < 1e-6);
+    assert_eq!(explanation.details.len(), 1);
+  }
+
+  #[test]
+  fn test_bm25_variants_reduce_length_penalty() {
+    let query_terms = vec!["rust".to_string()];
+
+    let mut doc_terms = HashMap::new();
+    doc_terms.insert("rust".to_string(), 1);
+
+    let mut doc_freq = HashMap::new();
+    doc_freq.insert("rust".to_string(), 2);
+
+    for variant in [BM25Variant::bm25_plus(), BM25Variant::bm25l()] {
+      let scorer = BM25Scorer::new().with_variant(variant);
+      let short = BM25Scorer::new().score(&query_terms, &doc_terms, 10, 10.0, &doc_freq, 10);
+      let long = BM25Scorer::new().score(&query_terms, &doc_terms, 200, 10.0, &doc_freq, 10);
+      let variant_long = scorer.score(&query_terms, &doc_terms, 200, 10.0, &doc_freq, 10);
+      let explanation = scorer.explain(&query_terms, &doc_terms, 200, 10.0, &doc_freq, 10);
+
+      assert!(variant_long > long);
+      assert!(
+        variant_long / scorer.score(&query_terms, &doc_terms, 10, 10.0, &doc_freq, 10)
+          > long / short
+      );
+      assert!((explanation.value - variant_long).abs() < 1e-6);
+    }
+  }
 }
@@ -0,0 +1,146 @@
+//! Word segmentation for Chinese, Japanese, and Korean text.
+//!
+//! CJK scripts do not separate words with spaces, so Unicode word boundaries
+//! either split them into single characters or keep whole phrases together.
+//! The [`CjkTokenizer`] segments runs of Han characters into words with the
+//! `jieba` dictionary, and splits runs of kana and Hangul into overlapping
+//! character bigrams. Other text is tokenized like the default
+//! [`UnicodeTokenizer`](crate::searchers::tokenizer::UnicodeTokenizer).
+//!
+//! This module is available behind the `cjk` feature.
+
+use crate::searchers::tokenizer::{tokenize_with_offsets, Tokenizer};
+use jieba_rs::Jieba;
+use std::ops::Range;
+
+/// The script classes that are segmented differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+  /// Chinese characters (also used in Japanese kanji and Korean hanja).
+  Han,
+  /// Japanese kana and Korean Hangul.
+  Syllabic,
+  /// Any other text.
+  Other,
+}
+
+impl Script {
+  /// Returns the script class of a character.
+  fn of(c: char) -> Self {
+    match c as u32 {
+      0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F => Script::Han,
+      0x3040..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F => Script::Syllabic,
+      0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Syllabic,
+      _ => Script::Other,
+    }
+  }
+}
+
+/// A tokenizer that segments Chinese, Japanese, and Korean text into words.
+///
+/// Runs of Han characters are segmented with the `jieba` Chinese dictionary,
+/// in search mode: long words are followed by the shorter words they contain.
+/// Runs of kana or Hangul are split into overlapping bigrams, a simple
+/// dictionary-free approach that lets any two adjacent characters of a query
+/// match the same characters in a document.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::cjk::CjkTokenizer;
+/// use searus::searchers::tokenizer::Tokenizer;
+///
+/// let tokenizer = CjkTokenizer::new();
+///
+/// assert_eq!(
+///     tokenizer.tokenize("我们学习编程语言"),
+///     vec!["我们", "学习", "编程", "语言", "编程语言"]
+/// );
+/// assert_eq!(tokenizer.tokenize("カタカナ"), vec!["カタ", "タカ", "カナ"]);
+/// assert_eq!(tokenizer.tokenize("Rust 编程"), vec!["rust", "编程"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CjkTokenizer {
+  /// The Chinese word segmenter, loaded with the default dictionary.
+  jieba: Jieba,
+}
+
+impl Default for CjkTokenizer {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl CjkTokenizer {
+  /// Creates a tokenizer with the default `jieba` dictionary.
+  ///
+  /// Loading the dictionary takes a moment, so the tokenizer should be
+  /// created once and reused.
+  pub fn new() -> Self {
+    Self {
+      jieba: Jieba::new(),
+    }
+  }
+
+  /// Segments a run of Han characters into words.
+  ///
+  /// Long words are also split into the shorter dictionary words they
+  /// contain, so that "编程语言" (programming language) matches a query for
+  /// "编程" (programming).
+  fn segment_han(&self, run: &str, offset: usize, tokens: &mut Vec<(String, Range<usize>)>) {
+    for word in self.jieba.cut_for_search(run, false) {
+      if !word.chars().any(char::is_alphanumeric) {
+        continue;
+      }
+      // Words are slices of the run, so their offsets follow from their addresses.
+      let start = offset + (word.as_ptr() as usize - run.as_ptr() as usize);
+      tokens.push((word.to_lowercase(), start..start + word.len()));
+    }
+  }
+
+  /// Splits a run of kana or Hangul into overlapping character bigrams.
+  fn segment_bigrams(run: &str, offset: usize, tokens: &mut Vec<(String, Range<usize>)>) {
+    let chars: Vec<(usize, char)> = run.char_indices().collect();
+    if chars.len() == 1 {
+      tokens.push((run.to_string(), offset..offset + run.len()));
+      return;
+    }
+
+    for pair in chars.windows(2) {
+      let (start, _) = pair[0];
+      let (last, c) = pair[1];
+      let end = last + c.len_utf8();
+      tokens.push((run[start..end].to_string(), offset + start..offset + end));
+    }
+  }
+}
+
+impl Tokenizer for CjkTokenizer {
+  fn tokenize_with_offsets(&self, text: &str) -> Vec<(String, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut runs: Vec<(Script, usize, usize)> = Vec::new();
+
+    for (i, c) in text.char_indices() {
+      let script = Script::of(c);
+      match runs.last_mut() {
+        Some((last, _, end)) if *last == script => *end = i + c.len_utf8(),
+        _ => runs.push((script, i, i + c.len_utf8())),
+      }
+    }
+
+    for (script, start, end) in runs {
+      let run = &text[start..end];
+      match script {
+        Script::Han => self.segment_han(run, start, &mut tokens),
+        Script::Syllabic => Self::segment_bigrams(run, start, &mut tokens),
+        Script::Other => tokens.extend(
+          tokenize_with_offsets(run)
+            .into_iter()
+            .map(|(token, range)| (token, start + range.start..start + range.end)),
+        ),
+      }
+    }
+
+    tokens
+  }
+}
@@ -1,10 +1,19 @@
 //! A `Searcher` implementation for fuzzy (approximate) string matching.
 
 use crate::context::SearchContext;
+use crate::document::{get_field_value, to_document};
+use crate::highlight::find_terms;
 use crate::prelude::*;
-use crate::searchers::tokenizer::tokenize;
+use crate::searchers::analyzer::Analyzer;
+use crate::searchers::bktree::BkTree;
+use crate::searchers::ngram::NGramIndex;
+#[cfg(feature = "stemming")]
+use crate::searchers::stemmer::Stemmer;
+use crate::searchers::stopwords::StopWords;
+use crate::searchers::tokenizer::Tokenizer;
 use serde_json::Value;
-use strsim::jaro_winkler;
+use std::collections::HashMap;
+use strsim::{damerau_levenshtein, jaro_winkler};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -39,11 +48,29 @@ pub struct FuzzySearch {
   /// The minimum similarity threshold required to consider a term a match.
   /// This value should be between 0.0 (no similarity) and 1.0 (exact match).
   threshold: f64,
+  /// The maximum number of edits between two matching terms. When set, it
+  /// replaces the similarity threshold.
+  max_edits: Option<usize>,
   /// The names of the fields to search within the items. The items are expected
   /// to be serializable to a JSON-like structure to allow for field extraction.
   fields: Vec<String>,
+  /// The weights of the fields, by name. Fields without a weight have a
+  /// weight of 1.0.
+  weights: HashMap<String, f32>,
+  /// The analyzer turning queries and documents into comparable terms.
+  analyzer: Analyzer,
+  /// The vocabulary of the corpus, used to look up the terms within
+  /// `max_edits` of each query term.
+  index: Option<BkTree>,
+  /// The n-gram index of the vocabulary of the corpus, used to find the
+  /// terms worth comparing with each query term for similarity.
+  ngrams: Option<NGramIndex>,
 }
 
+/// The document terms matching the query, each with the query term it
+/// matches best and their similarity.
+type Candidates = HashMap<String, (String, f64)>;
+
 impl FuzzySearch {
   /// Creates a new `FuzzySearch` instance with a default threshold of 0.8.
   ///
@@ -54,7 +81,12 @@ impl FuzzySearch {
   pub fn new(fields: Vec<String>) -> Self {
     Self {
       threshold: 0.8,
+      max_edits: None,
       fields,
+      weights: HashMap::new(),
+      analyzer: Analyzer::default(),
+      index: None,
+      ngrams: None,
     }
   }
 
@@ -68,19 +100,218 @@ impl FuzzySearch {
     self
   }
 
+  /// Sets the weight of a field, adding it to the searched fields if needed.
+  ///
+  /// The similarity of a match is multiplied by the weight of the field it
+  /// occurs in, so a typo in a title can outrank the same typo in the body.
+  ///
+  /// # Arguments
+  ///
+  /// * `field` - The name of the field.
+  /// * `weight` - The weight of the field (1.0 by default).
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::FuzzySearch;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Post { title: String, content: String }
+  ///
+  /// let posts = vec![
+  ///     Post { title: "Cooking".into(), content: "A recipe for pasta".into() },
+  ///     Post { title: "Pasta".into(), content: "Cooking tips".into() },
+  /// ];
+  ///
+  /// let searcher = FuzzySearch::new(vec!["content".to_string()]).with_field_weight("title", 2.0);
+  /// let engine = SearusEngine::builder().with(Box::new(searcher)).build();
+  ///
+  /// let results = engine.search(&posts, &Query::builder().text("psta").build());
+  /// assert_eq!(results[0].item.title, "Pasta");
+  /// assert!(results[0].field_scores.contains_key("title"));
+  /// assert!(results[1].field_scores.contains_key("content"));
+  /// ```
+  pub fn with_field_weight(mut self, field: impl Into<String>, weight: f32) -> Self {
+    let field = field.into();
+    if !self.fields.contains(&field) {
+      self.fields.push(field.clone());
+    }
+    self.weights.insert(field, weight);
+    self
+  }
+
+  /// Matches terms within a maximum number of edits instead of a
+  /// similarity threshold.
+  ///
+  /// Edits are insertions, deletions, substitutions, and transpositions of
+  /// adjacent characters (Damerau-Levenshtein distance). This is more
+  /// predictable than a similarity threshold for short terms, where a single
+  /// typo already causes a large drop in similarity. Matches are scored by
+  /// their normalized distance, from 1.0 for identical terms down.
+  ///
+  /// # Arguments
+  ///
+  /// * `max_edits` - The maximum number of edits, usually 1 or 2.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::FuzzySearch;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Tag { name: String }
+  ///
+  /// let tags = vec![Tag { name: "form".into() }, Tag { name: "farm".into() }];
+  ///
+  /// let searcher = FuzzySearch::new(vec!["name".to_string()]).with_max_edits(1);
+  /// let engine = SearusEngine::builder().with(Box::new(searcher)).build();
+  ///
+  /// // "from" is one transposition away from "form", and two edits from "farm".
+  /// let results = engine.search(&tags, &Query::builder().text("from").build());
+  /// assert_eq!(results.len(), 1);
+  /// assert_eq!(results[0].item.name, "form");
+  /// ```
+  pub fn with_max_edits(mut self, max_edits: usize) -> Self {
+    self.max_edits = Some(max_edits);
+    self
+  }
+
+  /// Sets the vocabulary index used in max-edit-distance mode.
+  ///
+  /// With an index, the terms within [`with_max_edits`](Self::with_max_edits)
+  /// of each query term are looked up once per search, instead of comparing
+  /// every query term with every term of every document. Document terms
+  /// missing from the index never match, so the index should contain the
+  /// analyzed terms of the whole corpus; see
+  /// [`build_index`](Self::build_index). The index is not used with a
+  /// similarity threshold, as Jaro-Winkler similarity is not a distance
+  /// metric; see [`with_ngram_index`](Self::with_ngram_index) instead.
+  pub fn with_index(mut self, index: BkTree) -> Self {
+    self.index = Some(index);
+    self
+  }
+
+  /// Sets the n-gram index used to prefilter terms with a similarity
+  /// threshold.
+  ///
+  /// With an index, only the terms sharing at least one n-gram with a query
+  /// term are compared with it, once per search, and documents without any
+  /// of those terms are skipped. This is much faster on large corpora, at
+  /// the cost of missing rare matches that share no n-gram, such as
+  /// transpositions in two-letter terms. Document terms missing from the
+  /// index never match.
+  pub fn with_ngram_index(mut self, ngrams: NGramIndex) -> Self {
+    self.ngrams = Some(ngrams);
+    self
+  }
+
+  /// Builds the vocabulary indexes from the searched fields of a corpus: a
+  /// BK-tree for max-edit-distance mode and a trigram index for similarity
+  /// thresholds.
+  ///
+  /// Terms are produced by the searcher's analyzer, so this should be
+  /// called after configuring it. The indexes must be rebuilt (or extended)
+  /// when documents with new terms are added to the corpus.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::FuzzySearch;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Post { title: String }
+  ///
+  /// let posts = vec![
+  ///     Post { title: "Rust programming".into() },
+  ///     Post { title: "Python programming".into() },
+  /// ];
+  ///
+  /// let searcher = FuzzySearch::new(vec!["title".to_string()])
+  ///     .with_max_edits(2)
+  ///     .build_index(&posts);
+  /// let engine = SearusEngine::builder().with(Box::new(searcher)).build();
+  ///
+  /// let results = engine.search(&posts, &Query::builder().text("pyhton").build());
+  /// assert_eq!(results.len(), 1);
+  /// assert_eq!(results[0].item.title, "Python programming");
+  /// ```
+  pub fn build_index<T: serde::Serialize>(mut self, items: &[T]) -> Self {
+    let mut index = BkTree::new();
+    let mut ngrams = NGramIndex::default();
+    for item in items {
+      for field in &self.fields {
+        if let Some(text) = Self::extract_field(item, field) {
+          for term in self.analyzer.analyze(&text) {
+            if ngrams.insert(&term) {
+              index.insert(&term);
+            }
+          }
+        }
+      }
+    }
+    self.index = Some(index);
+    self.ngrams = Some(ngrams);
+    self
+  }
+
+  /// Removes stopwords from queries and documents before matching.
+  ///
+  /// This prevents short, common words from producing spurious fuzzy matches.
+  pub fn with_stopwords(mut self, stopwords: StopWords) -> Self {
+    self.analyzer = self.analyzer.filter(Box::new(stopwords));
+    self
+  }
+
+  /// Reduces query and document tokens to their stems before matching.
+  ///
+  /// Similarity is then measured between word roots, so inflections do not
+  /// count as typos. Requires the `stemming` feature.
+  #[cfg(feature = "stemming")]
+  pub fn with_stemmer(mut self, stemmer: Stemmer) -> Self {
+    self.analyzer = self.analyzer.filter(Box::new(stemmer));
+    self
+  }
+
+  /// Replaces the tokenizer, which determines the units that are compared
+  /// for similarity.
+  pub fn with_tokenizer(mut self, tokenizer: Box<dyn Tokenizer>) -> Self {
+    self.analyzer = self.analyzer.tokenizer(tokenizer);
+    self
+  }
+
+  /// Replaces the analyzer applied to queries and documents.
+  pub fn with_analyzer(mut self, analyzer: Analyzer) -> Self {
+    self.analyzer = analyzer;
+    self
+  }
+
   /// Extracts the value of a specified field from a serializable item.
   ///
   /// This helper function serializes the item to a `serde_json::Value` and then
-  /// extracts the text from the specified field. It can handle string and
-  /// number fields (by converting numbers to strings).
+  /// extracts the text from the field at the specified path. It can handle
+  /// string and number fields (by converting numbers to strings), and arrays
+  /// of them, whose elements are joined with spaces.
   fn extract_field<T>(item: &T, field: &str) -> Option<String>
   where
     T: serde::Serialize,
   {
-    let value = serde_json::to_value(item).ok()?;
-    let field_value = value.get(field)?;
+    let value = to_document(item).ok()?;
+
+    match get_field_value(&value, field)? {
+      Value::Array(elements) => {
+        let texts: Vec<String> = elements.iter().filter_map(Self::value_text).collect();
+        (!texts.is_empty()).then(|| texts.join(" "))
+      }
+      value => Self::value_text(value),
+    }
+  }
 
-    match field_value {
+  /// Returns the text of a string or number.
+  fn value_text(value: &Value) -> Option<String> {
+    match value {
       Value::String(s) => Some(s.clone()),
       Value::Number(n) => Some(n.to_string()),
       _ => None,
@@ -88,80 +319,270 @@ impl FuzzySearch {
   }
 }
 
+/// Returns the similarity of two terms at an edit distance, from 1.0 for
+/// identical terms down.
+fn edit_similarity(a: &str, b: &str, distance: usize) -> f64 {
+  let max_len = a.chars().count().max(b.chars().count()).max(1);
+  1.0 - distance as f64 / max_len as f64
+}
+
+/// The best fuzzy match in a field of an entity.
+struct FieldMatch<'a> {
+  /// The name of the field.
+  field: &'a str,
+  /// The text of the field.
+  text: String,
+  /// The query term that matched.
+  query_term: String,
+  /// The term of the field it matched.
+  doc_term: String,
+  /// The similarity of the two terms.
+  similarity: f64,
+  /// The weight of the field.
+  weight: f32,
+}
+
+impl FieldMatch<'_> {
+  /// Returns the weighted similarity of the match.
+  fn score(&self) -> f32 {
+    self.similarity as f32 * self.weight
+  }
+}
+
 impl FuzzySearch {
+  /// Returns the weight of a field.
+  fn field_weight(&self, field: &str) -> f32 {
+    self.weights.get(field).copied().unwrap_or(1.0)
+  }
+
+  /// Compares a query term with a document term.
+  ///
+  /// # Returns
+  ///
+  /// The similarity of the terms, from 0.0 to 1.0, or `None` if they are too
+  /// different to match.
+  fn similarity(&self, query_term: &str, doc_term: &str) -> Option<f64> {
+    match self.max_edits {
+      Some(max_edits) => {
+        let query_len = query_term.chars().count();
+        let doc_len = doc_term.chars().count();
+        if query_len.abs_diff(doc_len) > max_edits {
+          return None;
+        }
+
+        let distance = damerau_levenshtein(query_term, doc_term);
+        if distance > max_edits {
+          return None;
+        }
+        Some(edit_similarity(query_term, doc_term, distance))
+      }
+      None => {
+        // OPTIMIZATION: Length-based pruning
+        // Skip if length difference is too large (>50% different)
+        let query_len = query_term.len();
+        let doc_len = doc_term.len();
+        let len_diff = query_len.abs_diff(doc_len);
+        let max_len = query_len.max(doc_len);
+        if max_len > 0 && (len_diff * 2) > max_len {
+          return None;
+        }
+
+        let similarity = jaro_winkler(query_term, doc_term);
+        (similarity >= self.threshold).then_some(similarity)
+      }
+    }
+  }
+
+  /// Looks up the document terms matching the query terms in the indexes.
+  ///
+  /// # Returns
+  ///
+  /// `None` if no index is used for the current configuration.
+  fn candidates(&self, query_terms: &[String]) -> Option<Candidates> {
+    let mut candidates = Candidates::new();
+    let mut add = |query_term: &String, doc_term: &str, similarity: f64| {
+      if candidates
+        .get(doc_term)
+        .is_none_or(|(_, best)| similarity > *best)
+      {
+        candidates.insert(doc_term.to_string(), (query_term.clone(), similarity));
+      }
+    };
+
+    match (self.max_edits, &self.index, &self.ngrams) {
+      (Some(max_edits), Some(index), _) => {
+        for query_term in query_terms {
+          for (doc_term, distance) in index.find(query_term, max_edits) {
+            let similarity = edit_similarity(query_term, doc_term, distance);
+            add(query_term, doc_term, similarity);
+          }
+        }
+      }
+      (None, _, Some(ngrams)) => {
+        for query_term in query_terms {
+          for (doc_term, _) in ngrams.candidates(query_term) {
+            if let Some(similarity) = self.similarity(query_term, doc_term) {
+              add(query_term, doc_term, similarity);
+            }
+          }
+        }
+      }
+      _ => return None,
+    }
+    Some(candidates)
+  }
+
   /// Match a single entity against the query.
+  ///
+  /// Each field is scored by the best similarity between a query term and
+  /// one of its terms, multiplied by the field's weight. The entity is
+  /// scored by its best field.
   pub fn match_entity<T>(
     &self,
     item: &T,
     index: usize,
-    _query: &Query,
+    query: &Query,
     query_terms: &[String],
   ) -> Option<SearusMatch<T>>
   where
     T: FuzzySearchable,
   {
-    let mut max_similarity = 0.0;
-    let mut best_query_term = String::new();
-    let mut best_doc_term = String::new();
+    self.match_candidates(item, index, query, query_terms, None)
+  }
 
-    // Check each configured field for a fuzzy match.
-    'outer: for field_name in &self.fields {
-      if let Some(text) = Self::extract_field(item, field_name) {
-        let doc_terms = tokenize(&text);
+  /// Matches a single entity against the query, looking its terms up in the
+  /// candidates found in the index if there are any.
+  fn match_candidates<T>(
+    &self,
+    item: &T,
+    index: usize,
+    query: &Query,
+    query_terms: &[String],
+    candidates: Option<&Candidates>,
+  ) -> Option<SearusMatch<T>>
+  where
+    T: FuzzySearchable,
+  {
+    let mut field_matches: Vec<FieldMatch> = Vec::new();
 
-        // Find the best fuzzy match between query terms and document terms.
-        for query_term in query_terms {
-          let query_len = query_term.len();
-          
-          for doc_term in &doc_terms {
-            // OPTIMIZATION: Length-based pruning
-            // Skip if length difference is too large (>50% different)
-            let doc_len = doc_term.len();
-            let len_diff = if query_len > doc_len {
-              query_len - doc_len
-            } else {
-              doc_len - query_len
-            };
-            let max_len = query_len.max(doc_len);
-            if max_len > 0 && (len_diff * 2) > max_len {
-              continue;
-            }
+    // Check each configured field for a fuzzy match.
+    for field_name in &self.fields {
+      let Some(text) = Self::extract_field(item, field_name) else {
+        continue;
+      };
+      let doc_terms = self.analyzer.analyze(&text);
+      let best = match candidates {
+        Some(candidates) => doc_terms
+          .iter()
+          .filter_map(|doc_term| {
+            let (query_term, similarity) = candidates.get(doc_term)?;
+            Some((query_term, doc_term, *similarity))
+          })
+          .reduce(|best, current| if current.2 > best.2 { current } else { best }),
+        None => self.best_pair(query_terms, &doc_terms),
+      };
 
-            let similarity = jaro_winkler(query_term, doc_term);
-
-            if similarity > max_similarity && similarity >= self.threshold {
-              max_similarity = similarity;
-              best_query_term = query_term.clone();
-              best_doc_term = doc_term.clone();
-              
-              // OPTIMIZATION: Early cutoff if we find a near-perfect match
-              if similarity > 0.95 {
-                break 'outer;
-              }
-            }
-          }
-        }
+      if let Some((query_term, doc_term, similarity)) = best {
+        field_matches.push(FieldMatch {
+          field: field_name,
+          text,
+          query_term: query_term.clone(),
+          doc_term: doc_term.clone(),
+          similarity,
+          weight: self.field_weight(field_name),
+        });
       }
     }
 
-    // If a match was found above the threshold, create a SearusMatch.
-    if max_similarity >= self.threshold {
-      let mut m = SearusMatch::new(item.clone(), max_similarity as f32, index);
+    // If a match was found, create a SearusMatch scored by the best field.
+    let score = field_matches
+      .iter()
+      .map(FieldMatch::score)
+      .fold(None, |best: Option<f32>, score| {
+        Some(best.map_or(score, |best| best.max(score)))
+      })?;
+
+    let mut m = SearusMatch::new(item.clone(), score, index);
+    if query.options.explain {
+      let measure = match self.max_edits {
+        Some(_) => "normalized edit distance",
+        None => "jaro-winkler",
+      };
+      m.explanation = Some(
+        Explanation::new(score, "fuzzy, max of fields:").with_details(field_matches.iter().map(
+          |field_match| {
+            Explanation::new(
+              field_match.score(),
+              format!(
+                "field \"{}\", weight {} times:",
+                field_match.field, field_match.weight
+              ),
+            )
+            .with_detail(Explanation::new(
+              field_match.similarity as f32,
+              format!(
+                "{} similarity of \"{}\" and \"{}\"",
+                measure, field_match.query_term, field_match.doc_term
+              ),
+            ))
+          },
+        )),
+      );
+    }
+
+    for field_match in field_matches {
+      m.field_scores
+        .insert(field_match.field.to_string(), field_match.score());
+      if query.options.highlight {
+        m.highlights.extend(find_terms(
+          field_match.field,
+          &field_match.text,
+          self.analyzer.analyze_with_offsets(&field_match.text),
+          std::slice::from_ref(&field_match.doc_term),
+        ));
+      }
       m.details.push(SearchDetail::Fuzzy {
-        matched_term: best_doc_term,
-        original_term: best_query_term,
-        similarity: max_similarity as f32,
+        matched_term: field_match.doc_term,
+        original_term: field_match.query_term,
+        similarity: field_match.similarity as f32,
+        field: field_match.field.to_string(),
       });
+    }
 
-      Some(m)
-    } else {
-      None
+    Some(m)
+  }
+
+  /// Finds the most similar pair of a query term and a document term.
+  fn best_pair<'a>(
+    &self,
+    query_terms: &'a [String],
+    doc_terms: &'a [String],
+  ) -> Option<(&'a String, &'a String, f64)> {
+    let mut best: Option<(&String, &String, f64)> = None;
+
+    for query_term in query_terms {
+      for doc_term in doc_terms {
+        let Some(similarity) = self.similarity(query_term, doc_term) else {
+          continue;
+        };
+
+        if best.is_none_or(|(_, _, best)| similarity > best) {
+          best = Some((query_term, doc_term, similarity));
+
+          // OPTIMIZATION: Early cutoff if we find a near-perfect match
+          if similarity > 0.95 {
+            return best;
+          }
+        }
+      }
     }
+
+    best
   }
 
   /// Sort the search results.
   #[cfg(feature = "parallel")]
-  pub fn sort_results<T: Send + Sync>(&self, results: &mut Vec<SearusMatch<T>>) {
+  pub fn sort_results<T: Send + Sync>(&self, results: &mut [SearusMatch<T>]) {
     results.par_sort_by(|a, b| {
       b.score
         .partial_cmp(&a.score)
@@ -171,7 +592,7 @@ impl FuzzySearch {
 
   /// Sort the search results.
   #[cfg(not(feature = "parallel"))]
-  pub fn sort_results<T>(&self, results: &mut Vec<SearusMatch<T>>) {
+  pub fn sort_results<T>(&self, results: &mut [SearusMatch<T>]) {
     results.sort_by(|a, b| {
       b.score
         .partial_cmp(&a.score)
@@ -198,57 +619,24 @@ where
   /// threshold, it is considered a match. The highest similarity score found
   /// for an item is used as its raw score.
   fn search(&self, context: &SearchContext<T>, query: &Query) -> Vec<SearusMatch<T>> {
-    let items = context.items;
     let query_text = match &query.text {
       Some(text) => text,
       None => return Vec::new(),
     };
 
-    let query_terms = tokenize(query_text);
+    let query_terms = self.analyzer.analyze(query_text);
     if query_terms.is_empty() {
       return Vec::new();
     }
 
-    #[cfg(feature = "parallel")]
-    let mut results: Vec<SearusMatch<T>> = {
-      // OPTIMIZATION: Pre-allocate result vector
-      let matches: Vec<_> = items
-        .par_iter()
-        .enumerate()
-        .filter(|(_, item)| {
-           if let Some(filters) = &query.filters {
-             filters.evaluate(item)
-           } else {
-             true
-           }
-        })
-        .filter_map(|(index, item)| self.match_entity(item, index, query, &query_terms))
-        .collect();
-      
-      let mut results = Vec::with_capacity(matches.len());
-      results.extend(matches);
-      results
-    };
+    let candidates = self.candidates(&query_terms);
+    if candidates.as_ref().is_some_and(HashMap::is_empty) {
+      return Vec::new();
+    }
 
-    #[cfg(not(feature = "parallel"))]
-    let mut results: Vec<SearusMatch<T>> = {
-      // OPTIMIZATION: Pre-allocate with estimated capacity
-      let mut results = Vec::with_capacity(items.len() / 20); // Fuzzy matches are typically rare
-      results.extend(
-        items
-          .iter()
-          .enumerate()
-          .filter(|(_, item)| {
-             if let Some(filters) = &query.filters {
-               filters.evaluate(item)
-             } else {
-               true
-             }
-          })
-          .filter_map(|(index, item)| self.match_entity(item, index, query, &query_terms))
-      );
-      results
-    };
+    let mut results = context.scan(None, query, |index, item| {
+      self.match_candidates(item, index, query, &query_terms, candidates.as_ref())
+    });
 
     // Sort results by score in descending order.
     self.sort_results(&mut results);
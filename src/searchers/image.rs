@@ -0,0 +1,156 @@
+//! A `Searcher` implementation for finding items similar to an image.
+
+use crate::context::SearchContext;
+use crate::embeddings::{cosine_similarity, ImageEmbedder};
+use crate::prelude::*;
+use serde::Serialize;
+
+/// Reads the image embedding of an item, if it has one.
+type VectorFn<T> = Box<dyn Fn(&T) -> Option<Vec<f32>> + Send + Sync>;
+
+/// A searcher that ranks items by the cosine similarity of their image
+/// embedding to the embedding of `query.image`.
+///
+/// Items carry their own vectors, usually computed ahead of time with the
+/// same [`ImageEmbedder`] (such as
+/// [`ClipImageEmbedder`](crate::embeddings::ClipImageEmbedder) with the
+/// `clip` feature); the query image is embedded once per search.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::embeddings::ImageEmbedder;
+/// use searus::prelude::*;
+/// use searus::searchers::ImageSearch;
+///
+/// /// Embeds an image as its first two bytes, for the sake of the example.
+/// struct Bytes;
+///
+/// impl ImageEmbedder for Bytes {
+///     fn embed(&self, image_data: &[u8]) -> Result<Vec<f32>, String> {
+///         Ok(image_data.iter().take(2).map(|&b| b as f32).collect())
+///     }
+/// }
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Photo {
+///     name: String,
+///     embedding: Vec<f32>,
+/// }
+///
+/// let photos = vec![
+///     Photo { name: "beach".into(), embedding: vec![1.0, 0.0] },
+///     Photo { name: "forest".into(), embedding: vec![0.0, 1.0] },
+/// ];
+///
+/// let engine = SearusEngine::builder()
+///     .with(Box::new(
+///         ImageSearch::new(Box::new(Bytes), |photo: &Photo| Some(photo.embedding.clone()))
+///             .with_min_similarity(0.5),
+///     ))
+///     .build();
+///
+/// let query = Query::builder().image(ImageData::new(vec![9, 1])).build();
+/// let results = engine.search(&photos, &query);
+/// assert_eq!(results.len(), 1);
+/// assert_eq!(results[0].item.name, "beach");
+/// ```
+pub struct ImageSearch<T> {
+  /// Embeds the query image.
+  embedder: Box<dyn ImageEmbedder>,
+  /// Reads the embedding of an item.
+  vector: VectorFn<T>,
+  /// The similarity an item must exceed to match.
+  min_similarity: f32,
+}
+
+impl<T> ImageSearch<T> {
+  /// Creates an image searcher.
+  ///
+  /// # Arguments
+  ///
+  /// * `embedder` - Embeds the query image, in the same space as the items.
+  /// * `vector` - Returns the image embedding of an item, or `None` for items
+  ///   without an image.
+  pub fn new<F>(embedder: Box<dyn ImageEmbedder>, vector: F) -> Self
+  where
+    F: Fn(&T) -> Option<Vec<f32>> + Send + Sync + 'static,
+  {
+    Self {
+      embedder,
+      vector: Box::new(vector),
+      min_similarity: 0.0,
+    }
+  }
+
+  /// Sets the cosine similarity an item must exceed to match (0.0 by default,
+  /// which leaves out dissimilar items).
+  pub fn with_min_similarity(mut self, min_similarity: f32) -> Self {
+    self.min_similarity = min_similarity;
+    self
+  }
+
+  /// Scores a single item against the query vector.
+  fn match_item(
+    &self,
+    item: &T,
+    index: usize,
+    query: &Query,
+    query_vector: &[f32],
+  ) -> Option<SearusMatch<T>>
+  where
+    T: Searchable + Clone,
+  {
+    let vector = (self.vector)(item)?;
+    let similarity = cosine_similarity(query_vector, &vector)?;
+    if similarity <= self.min_similarity {
+      return None;
+    }
+
+    let mut m = SearusMatch::new(item.clone(), similarity, index)
+      .with_detail(SearchDetail::Image { similarity });
+    if query.options.explain {
+      m.explanation = Some(Explanation::new(
+        similarity,
+        "image, cosine similarity to the query image",
+      ));
+    }
+    Some(m)
+  }
+}
+
+impl<T> Searcher<T> for ImageSearch<T>
+where
+  T: Searchable + Serialize + Clone,
+{
+  fn kind(&self) -> SearcherKind {
+    SearcherKind::Image
+  }
+
+  /// Performs a search by comparing the embedding of `query.image` with the
+  /// embedding of each item.
+  ///
+  /// The raw score of a match is its cosine similarity to the query image.
+  /// Items without an embedding, or whose embedding has a different length
+  /// than the query's, don't match. Nothing matches if the query has no
+  /// image or the image can't be embedded.
+  fn search(&self, context: &SearchContext<T>, query: &Query) -> Vec<SearusMatch<T>> {
+    let Some(image) = &query.image else {
+      return Vec::new();
+    };
+    let Ok(query_vector) = self.embedder.embed_image(image) else {
+      return Vec::new();
+    };
+
+    let mut results = context.scan(None, query, |index, item| {
+      self.match_item(item, index, query, &query_vector)
+    });
+
+    results.sort_by(|a, b| {
+      b.score
+        .partial_cmp(&a.score)
+        .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results
+  }
+}
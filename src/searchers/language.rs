@@ -0,0 +1,64 @@
+//! Language detection for routing documents to language-specific analyzers.
+//!
+//! This module is available behind the `language-detection` feature and
+//! uses the trigram models of the `whatlang` crate.
+
+/// Detects the language of a text.
+///
+/// # Returns
+///
+/// The ISO 639-1 code of the language (e.g., `"en"`) when it has one, or its
+/// ISO 639-3 code otherwise. Returns `None` if the language cannot be
+/// reliably detected, which is common for very short texts.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::language::detect_language;
+///
+/// assert_eq!(
+///     detect_language("The weather was warm, so we walked along the river until the evening."),
+///     Some("en".to_string())
+/// );
+/// assert_eq!(
+///     detect_language("Le renard brun rapide saute par-dessus le chien paresseux qui dort dans le jardin."),
+///     Some("fr".to_string())
+/// );
+/// ```
+pub fn detect_language(text: &str) -> Option<String> {
+  let info = whatlang::detect(text)?;
+  if !info.is_reliable() {
+    return None;
+  }
+
+  let code = info.lang().code();
+  let short = match code {
+    "ara" => "ar",
+    "cmn" => "zh",
+    "dan" => "da",
+    "deu" => "de",
+    "ell" => "el",
+    "eng" => "en",
+    "fin" => "fi",
+    "fra" => "fr",
+    "heb" => "he",
+    "hin" => "hi",
+    "hun" => "hu",
+    "ita" => "it",
+    "jpn" => "ja",
+    "kor" => "ko",
+    "nld" => "nl",
+    "nob" => "no",
+    "pol" => "pl",
+    "por" => "pt",
+    "ron" => "ro",
+    "rus" => "ru",
+    "spa" => "es",
+    "swe" => "sv",
+    "tam" => "ta",
+    "tur" => "tr",
+    "ukr" => "uk",
+    _ => code,
+  };
+  Some(short.to_string())
+}
@@ -8,6 +8,10 @@
 //! - [`SemanticSearch`](crate::searchers::SemanticSearch): Best for natural language queries. Uses BM25 and tokenization.
 //! - [`TaggedSearch`](crate::searchers::TaggedSearch): Best for exact tag matching and hierarchical tag expansion.
 //! - [`FuzzySearch`](crate::searchers::FuzzySearch): Best for handling typos and approximate string matching.
+//! - `ImageSearch`: Best for finding items that look like an image. Requires the `image` feature.
+//! - `VectorSearch`: Best for finding items by meaning with embeddings. Requires the `vector` feature.
+//! - `QdrantSearch`: Best for finding items by meaning among vectors kept in a Qdrant collection. Requires the `qdrant` feature.
+//! - `TantivySearch`: Best for full-text search of large corpora kept in a Tantivy index. Requires the `tantivy` feature.
 //!
 //! # Example: Combining Searchers
 //!
@@ -37,25 +41,77 @@
 //!     .build();
 //! ```
 
+/// Provides analyzers chaining a tokenizer with token filters.
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+pub mod analyzer;
+/// Provides a BK-tree index of terms for approximate lookups.
+#[cfg(feature = "fuzzy")]
+pub mod bktree;
 /// Implements the BM25 relevance scoring algorithm.
 #[cfg(feature = "semantic")]
 pub mod bm25;
+/// Provides word segmentation for Chinese, Japanese, and Korean text.
+#[cfg(all(feature = "cjk", any(feature = "semantic", feature = "fuzzy")))]
+pub mod cjk;
 /// Implements a fuzzy (approximate) string searcher.
 #[cfg(feature = "fuzzy")]
 pub mod fuzzy;
+/// Implements a searcher for items similar to an image.
+#[cfg(feature = "image")]
+pub mod image;
+/// Provides language detection for routing documents to analyzers.
+#[cfg(all(
+  feature = "language-detection",
+  any(feature = "semantic", feature = "fuzzy")
+))]
+pub mod language;
+/// Provides a character n-gram index of terms for candidate generation.
+#[cfg(feature = "fuzzy")]
+pub mod ngram;
+/// Provides pattern matching of field values.
+#[cfg(feature = "semantic")]
+pub mod pattern;
 /// Implements a semantic searcher that uses BM25.
 #[cfg(feature = "semantic")]
 pub mod semantic;
+/// Implements a searcher delegating vector queries to a Qdrant collection.
+#[cfg(feature = "qdrant")]
+pub mod qdrant_search;
+/// Provides Snowball stemming of token streams.
+#[cfg(feature = "stemming")]
+pub mod stemmer;
+/// Provides stopword lists for filtering common words out of token streams.
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+pub mod stopwords;
 /// Implements a searcher for matching tags.
 #[cfg(feature = "tagged")]
 pub mod tagged;
+/// Implements a searcher delegating full-text queries to a Tantivy index.
+#[cfg(feature = "tantivy")]
+pub mod tantivy_search;
+/// Implements the TF-IDF relevance scoring algorithm.
+#[cfg(feature = "semantic")]
+pub mod tfidf;
 /// Provides text tokenization utilities for searchers.
 #[cfg(any(feature = "semantic", feature = "fuzzy"))]
 pub mod tokenizer;
+/// Implements a searcher for items similar to a query vector.
+#[cfg(feature = "vector")]
+pub mod vector;
 
 #[cfg(feature = "fuzzy")]
 pub use fuzzy::FuzzySearch;
+#[cfg(feature = "image")]
+pub use image::ImageSearch;
+#[cfg(feature = "qdrant")]
+pub use qdrant_search::QdrantSearch;
 #[cfg(feature = "semantic")]
 pub use semantic::SemanticSearch;
+#[cfg(any(feature = "semantic", feature = "fuzzy"))]
+pub use stopwords::StopWords;
 #[cfg(feature = "tagged")]
 pub use tagged::TaggedSearch;
+#[cfg(feature = "tantivy")]
+pub use tantivy_search::TantivySearch;
+#[cfg(feature = "vector")]
+pub use vector::VectorSearch;
@@ -0,0 +1,156 @@
+//! A character n-gram index of terms for candidate generation.
+//!
+//! Similar terms usually share some of their character n-grams, so looking
+//! up the terms that share an n-gram with a query term narrows down the terms
+//! worth comparing with a costly similarity measure. Terms are padded with a
+//! boundary marker, so that their first and last characters form n-grams of
+//! their own and short terms are indexed too.
+
+use std::collections::{HashMap, HashSet};
+
+/// The character marking the start and end of a term in its n-grams.
+const BOUNDARY: char = '\0';
+
+/// An inverted index from character n-grams to the terms containing them.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::ngram::NGramIndex;
+///
+/// let mut index = NGramIndex::new(3);
+/// index.extend(["programming", "program", "pasta"]);
+///
+/// assert_eq!(index.len(), 3);
+/// assert_eq!(
+///     index.candidates("progrmaming"),
+///     vec![("programming", 7), ("program", 4)]
+/// );
+/// assert!(index.candidates("xyz").is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct NGramIndex {
+  /// The length of the n-grams, in characters.
+  n: usize,
+  /// The indexed terms.
+  terms: Vec<String>,
+  /// The position of each term in `terms`.
+  positions: HashMap<String, usize>,
+  /// The n-grams mapped to the positions of the terms containing them.
+  grams: HashMap<String, Vec<usize>>,
+}
+
+impl Default for NGramIndex {
+  /// Creates an empty trigram index.
+  fn default() -> Self {
+    Self::new(3)
+  }
+}
+
+impl NGramIndex {
+  /// Creates an empty index of n-grams of `n` characters.
+  pub fn new(n: usize) -> Self {
+    Self {
+      n: n.max(1),
+      terms: Vec::new(),
+      positions: HashMap::new(),
+      grams: HashMap::new(),
+    }
+  }
+
+  /// Adds a term to the index. Terms that are already present are ignored.
+  ///
+  /// # Returns
+  ///
+  /// `true` if the term was added.
+  pub fn insert(&mut self, term: &str) -> bool {
+    if term.is_empty() || self.positions.contains_key(term) {
+      return false;
+    }
+
+    let position = self.terms.len();
+    for gram in self.ngrams(term) {
+      self.grams.entry(gram).or_default().push(position);
+    }
+    self.positions.insert(term.to_string(), position);
+    self.terms.push(term.to_string());
+    true
+  }
+
+  /// Returns the number of terms in the index.
+  pub fn len(&self) -> usize {
+    self.terms.len()
+  }
+
+  /// Returns `true` if the index has no terms.
+  pub fn is_empty(&self) -> bool {
+    self.terms.is_empty()
+  }
+
+  /// Finds the terms sharing at least one n-gram with a term.
+  ///
+  /// # Returns
+  ///
+  /// The candidate terms with the number of n-grams they share with `term`,
+  /// sorted by descending number of shared n-grams, then alphabetically.
+  pub fn candidates(&self, term: &str) -> Vec<(&str, usize)> {
+    let mut shared: HashMap<usize, usize> = HashMap::new();
+    for gram in self.ngrams(term) {
+      for &position in self.grams.get(&gram).into_iter().flatten() {
+        *shared.entry(position).or_insert(0) += 1;
+      }
+    }
+
+    let mut candidates: Vec<(&str, usize)> = shared
+      .into_iter()
+      .map(|(position, count)| (self.terms[position].as_str(), count))
+      .collect();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    candidates
+  }
+
+  /// Returns the distinct n-grams of a term, padded with boundary markers.
+  fn ngrams(&self, term: &str) -> HashSet<String> {
+    let chars: Vec<char> = std::iter::once(BOUNDARY)
+      .chain(term.chars())
+      .chain(std::iter::once(BOUNDARY))
+      .collect();
+
+    if chars.len() <= self.n {
+      return HashSet::from([chars.into_iter().collect()]);
+    }
+    chars
+      .windows(self.n)
+      .map(|window| window.iter().collect())
+      .collect()
+  }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for NGramIndex {
+  fn from_iter<I: IntoIterator<Item = S>>(terms: I) -> Self {
+    let mut index = Self::default();
+    index.extend(terms);
+    index
+  }
+}
+
+impl<S: AsRef<str>> Extend<S> for NGramIndex {
+  fn extend<I: IntoIterator<Item = S>>(&mut self, terms: I) {
+    for term in terms {
+      self.insert(term.as_ref());
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_short_terms_are_indexed() {
+    let index: NGramIndex = ["a", "ab", "abc"].into_iter().collect();
+
+    assert_eq!(index.candidates("a"), vec![("a", 1)]);
+    assert_eq!(index.candidates("ab"), vec![("ab", 2), ("abc", 1)]);
+  }
+}
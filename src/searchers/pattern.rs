@@ -0,0 +1,172 @@
+//! Pattern matching of field values.
+//!
+//! These functions back the pattern matchers of
+//! [`FieldRule`](crate::rules::FieldRule), which match the words of the query
+//! as patterns rather than as analyzed terms. Regular expressions are
+//! available behind the `regex` feature.
+
+#[cfg(feature = "regex")]
+use regex::{Regex, RegexBuilder};
+#[cfg(feature = "regex")]
+use std::collections::HashMap;
+#[cfg(feature = "regex")]
+use std::sync::Mutex;
+
+/// Checks whether a text matches a wildcard pattern.
+///
+/// `*` matches any sequence of characters (including none) and `?` matches
+/// exactly one character. The whole text must match; matching is
+/// case-sensitive, so both should be lowercased for case-insensitive
+/// matching.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::pattern::wildcard_match;
+///
+/// assert!(wildcard_match("lap*", "laptop"));
+/// assert!(wildcard_match("l?p*p", "laptop"));
+/// assert!(!wildcard_match("lap", "laptop"));
+/// ```
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+  let pattern: Vec<char> = pattern.chars().collect();
+  let text: Vec<char> = text.chars().collect();
+
+  let (mut p, mut t) = (0, 0);
+  // The position of the last `*` in the pattern, and of the text it was
+  // matched against, to backtrack to on a mismatch.
+  let mut star: Option<(usize, usize)> = None;
+
+  while t < text.len() {
+    match pattern.get(p) {
+      Some('*') => {
+        star = Some((p, t));
+        p += 1;
+      }
+      Some(&c) if c == '?' || c == text[t] => {
+        p += 1;
+        t += 1;
+      }
+      _ => match star {
+        // Let the last `*` absorb one more character.
+        Some((star_p, star_t)) => {
+          p = star_p + 1;
+          t = star_t + 1;
+          star = Some((star_p, star_t + 1));
+        }
+        None => return false,
+      },
+    }
+  }
+
+  pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Parses a number written in free text, such as `"1000"`, `"$1,299.99"`,
+/// or `"-4.5"`.
+///
+/// Surrounding symbols (e.g., currency signs or units) and thousands
+/// separators are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::pattern::parse_number;
+///
+/// assert_eq!(parse_number("$1,299.99"), Some(1299.99));
+/// assert_eq!(parse_number("42kg"), Some(42.0));
+/// assert_eq!(parse_number("laptop"), None);
+/// ```
+pub fn parse_number(text: &str) -> Option<f64> {
+  let number: String = text
+    .trim_matches(|c: char| !c.is_ascii_digit() && c != '-' && c != '.')
+    .chars()
+    .filter(|&c| c != ',')
+    .collect();
+  number.parse().ok().filter(|n: &f64| n.is_finite())
+}
+
+/// Returns how close a value is to a target, from 1.0 (equal) towards 0.0.
+///
+/// The distance is relative to the magnitude of the target, so 999 is as
+/// close to 1000 as 9.99 is to 10.
+pub fn numeric_closeness(value: f64, target: f64) -> f32 {
+  let distance = (value - target).abs() / target.abs().max(1.0);
+  (1.0 / (1.0 + distance)) as f32
+}
+
+/// A cache of compiled, case-insensitive regular expressions.
+///
+/// Patterns are compiled on first use; invalid patterns are cached as such
+/// and never match. The cache is cleared when it grows past a fixed number of
+/// patterns, so that arbitrary query patterns cannot grow it without bound.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::pattern::RegexCache;
+///
+/// let cache = RegexCache::new();
+/// assert!(cache.get(r"^lp-\d+").unwrap().is_match("LP-1400"));
+/// assert!(cache.get("(unclosed").is_none());
+/// ```
+#[cfg(feature = "regex")]
+#[derive(Debug, Default)]
+pub struct RegexCache {
+  /// The compiled patterns, or `None` for invalid ones.
+  regexes: Mutex<HashMap<String, Option<Regex>>>,
+}
+
+#[cfg(feature = "regex")]
+impl RegexCache {
+  /// The number of patterns after which the cache is cleared.
+  const CAPACITY: usize = 256;
+
+  /// Creates an empty cache.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns the compiled regular expression of a pattern, or `None` if the
+  /// pattern is invalid.
+  pub fn get(&self, pattern: &str) -> Option<Regex> {
+    let mut regexes = self.regexes.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(regex) = regexes.get(pattern) {
+      return regex.clone();
+    }
+
+    if regexes.len() >= Self::CAPACITY {
+      regexes.clear();
+    }
+    let regex = RegexBuilder::new(pattern)
+      .case_insensitive(true)
+      .build()
+      .ok();
+    regexes.insert(pattern.to_string(), regex.clone());
+    regex
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_wildcard_match() {
+    assert!(wildcard_match("*", ""));
+    assert!(wildcard_match("*top", "laptop"));
+    assert!(wildcard_match("*p*p*", "laptop"));
+    assert!(wildcard_match("????", "café"));
+    assert!(!wildcard_match("?", ""));
+    assert!(!wildcard_match("*x*", "laptop"));
+    assert!(!wildcard_match("lap?", "laptop"));
+  }
+
+  #[test]
+  fn test_numeric_closeness() {
+    assert_eq!(numeric_closeness(1000.0, 1000.0), 1.0);
+    assert!(numeric_closeness(999.0, 1000.0) > numeric_closeness(1100.0, 1000.0));
+    assert!(numeric_closeness(5000.0, 1000.0) < 0.25);
+    assert!((numeric_closeness(9.99, 10.0) - numeric_closeness(999.0, 1000.0)).abs() < 1e-6);
+  }
+}
@@ -0,0 +1,171 @@
+//! A `Searcher` delegating vector queries to a Qdrant collection.
+
+use crate::context::SearchContext;
+use crate::document::get_field_value;
+use crate::index::{QdrantCollection, DEFAULT_VECTOR};
+use crate::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The number of neighbors fetched from Qdrant by default.
+const DEFAULT_LIMIT: usize = 100;
+
+/// A searcher that finds the items nearest to `query.vector` in a Qdrant
+/// collection.
+///
+/// The vectors live in Qdrant, typically written by a
+/// [`QdrantIndex`](crate::index::QdrantIndex), while Searus merges the
+/// neighbors with the results of its other searchers. Neighbors are mapped
+/// back to the searched items by their id, read from the item field
+/// `key_field`; neighbors missing from the searched slice are skipped.
+///
+/// Filters are translated to Qdrant payload filters where possible, so the
+/// nearest neighbors are taken among matching points, and are always
+/// checked again on the matched items. Queries without a vector match
+/// nothing, and queries naming a vector search Qdrant's vector of that
+/// name. The raw score of a match is its cosine similarity.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use searus::index::QdrantCollection;
+/// use searus::prelude::*;
+/// use searus::searchers::QdrantSearch;
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Post {
+///     id: String,
+///     title: String,
+/// }
+///
+/// let posts = vec![Post { id: "1".into(), title: "Rust ownership".into() }];
+///
+/// let collection = QdrantCollection::new("http://localhost:6333", "posts");
+/// let engine = SearusEngine::builder()
+///     .with(Box::new(QdrantSearch::new(collection, "id")))
+///     .build();
+///
+/// let query = Query::builder().vector(vec![0.9, 0.1, 0.3]).build();
+/// let results = engine.search(&posts, &query);
+/// ```
+pub struct QdrantSearch {
+  /// The collection holding the vectors.
+  collection: QdrantCollection,
+  /// The item field holding the id of each item.
+  key_field: String,
+  /// The number of neighbors fetched.
+  limit: usize,
+  /// The similarity a neighbor must exceed to match.
+  min_similarity: f32,
+}
+
+impl QdrantSearch {
+  /// Creates a searcher over a collection.
+  ///
+  /// # Arguments
+  ///
+  /// * `collection` - The Qdrant collection holding the vectors.
+  /// * `key_field` - The name of the item field holding the id each item was
+  ///   stored with.
+  pub fn new(collection: QdrantCollection, key_field: &str) -> Self {
+    Self {
+      collection,
+      key_field: key_field.to_string(),
+      limit: DEFAULT_LIMIT,
+      min_similarity: 0.0,
+    }
+  }
+
+  /// Sets the number of neighbors fetched from Qdrant, 100 by default.
+  pub fn with_limit(mut self, limit: usize) -> Self {
+    self.limit = limit.max(1);
+    self
+  }
+
+  /// Sets the cosine similarity a neighbor must exceed to match (0.0 by
+  /// default, which leaves out unrelated items).
+  pub fn with_min_similarity(mut self, min_similarity: f32) -> Self {
+    self.min_similarity = min_similarity;
+    self
+  }
+
+  /// Returns the collection holding the vectors.
+  pub fn collection(&self) -> &QdrantCollection {
+    &self.collection
+  }
+}
+
+/// Reads the id of an item from its key field.
+fn key_of(value: &Value) -> Option<String> {
+  match value {
+    Value::String(key) => Some(key.clone()),
+    Value::Number(key) => Some(key.to_string()),
+    _ => None,
+  }
+}
+
+impl<T> Searcher<T> for QdrantSearch
+where
+  T: Searchable + Serialize + Clone,
+{
+  fn kind(&self) -> SearcherKind {
+    SearcherKind::Vector
+  }
+
+  fn search(&self, context: &SearchContext<T>, query: &Query) -> Vec<SearusMatch<T>> {
+    let Some(vector) = &query.vector else {
+      return Vec::new();
+    };
+    let name = query.vector_name.as_deref().unwrap_or(DEFAULT_VECTOR);
+
+    // A failing server matches nothing, like a query without a vector.
+    let hits: HashMap<String, f32> = self
+      .collection
+      .search(name, vector, self.limit, query.filters.as_ref())
+      .unwrap_or_default()
+      .into_iter()
+      .filter(|(_, similarity)| *similarity > self.min_similarity)
+      .collect();
+    if hits.is_empty() {
+      return Vec::new();
+    }
+
+    let mut results: Vec<SearusMatch<T>> = context
+      .items
+      .iter()
+      .enumerate()
+      .filter_map(|(index, item)| {
+        let document = context.document(index)?;
+        let key = get_field_value(document, &self.key_field).and_then(key_of)?;
+        let similarity = *hits.get(&key)?;
+        if !context.matches_filters(index, query.filters.as_ref()) {
+          return None;
+        }
+
+        let mut m =
+          SearusMatch::new(item.clone(), similarity, index).with_detail(SearchDetail::Vector {
+            distance: 1.0 - similarity,
+            similarity,
+          });
+        if query.vector_name.is_some() {
+          m.field_scores.insert(name.to_string(), similarity);
+        }
+        if query.options.explain {
+          m.explanation = Some(Explanation::new(
+            similarity,
+            format!("qdrant, cosine similarity to the query {} vector", name),
+          ));
+        }
+        Some(m)
+      })
+      .collect();
+
+    results.sort_by(|a, b| {
+      b.score
+        .partial_cmp(&a.score)
+        .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results
+  }
+}
@@ -1,13 +1,25 @@
 //! Semantic text search implementation.
 
 use crate::context::SearchContext;
+use crate::document::{get_field_value, get_field_values, to_document};
+use crate::highlight::find_terms;
 use crate::prelude::*;
+use crate::searcher::WarmState;
+use crate::searchers::analyzer::{Analyzer, Token, BUILTIN_ANALYZERS};
 use crate::searchers::bm25::BM25Scorer;
-use crate::searchers::tokenizer::{term_frequencies, tokenize};
+#[cfg(feature = "regex")]
+use crate::searchers::pattern::RegexCache;
+use crate::searchers::pattern::{numeric_closeness, parse_number, wildcard_match};
+#[cfg(feature = "stemming")]
+use crate::searchers::stemmer::Stemmer;
+use crate::searchers::stopwords::StopWords;
+use crate::searchers::tfidf::TfIdfScorer;
+use crate::searchers::tokenizer::{count_terms, Tokenizer};
+use crate::synonyms::SynonymMap;
 use serde_json::Value;
-use std::collections::HashMap;
-#[cfg(not(feature = "parallel"))]
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
 #[cfg(feature = "parallel")]
 use dashmap::DashMap;
@@ -43,6 +55,27 @@ impl<T: serde::Serialize + Clone> SemanticSearchable for T {}
 pub struct SemanticSearch {
   rules: SemanticRules,
   bm25: BM25Scorer,
+  /// Optional synonym dictionary used to expand query terms.
+  synonyms: Option<SynonymMap>,
+  /// The synonyms analyzed by each analyzer of query terms they were
+  /// needed for.
+  analyzed_synonyms: Mutex<Vec<(Analyzer, Arc<AnalyzedSynonyms>)>>,
+  /// The analyzer used for fields without a named analyzer.
+  analyzer: Analyzer,
+  /// Named analyzers, used by fields whose rule refers to them.
+  analyzers: HashMap<String, Analyzer>,
+  /// Analyzers for documents in a given language, by language code.
+  language_analyzers: HashMap<String, Analyzer>,
+  /// The field holding the language code of each document.
+  language_field: Option<String>,
+  /// Whether to detect the language of documents without a language field.
+  #[cfg(feature = "language-detection")]
+  detect_language: bool,
+  /// The compiled patterns of `Regex` fields.
+  #[cfg(feature = "regex")]
+  regexes: RegexCache,
+  /// The corpus statistics and analyzed documents precomputed by `warm_up`.
+  warm: WarmState<WarmCorpus>,
 }
 
 impl SemanticSearch {
@@ -55,29 +88,425 @@ impl SemanticSearch {
     Self {
       rules,
       bm25: BM25Scorer::new(),
+      synonyms: None,
+      analyzed_synonyms: Mutex::default(),
+      analyzer: Analyzer::default(),
+      analyzers: BUILTIN_ANALYZERS
+        .into_iter()
+        .filter_map(|name| Some((name.to_string(), Analyzer::builtin(name)?)))
+        .collect(),
+      language_analyzers: HashMap::new(),
+      language_field: None,
+      #[cfg(feature = "language-detection")]
+      detect_language: false,
+      #[cfg(feature = "regex")]
+      regexes: RegexCache::new(),
+      warm: WarmState::new(),
+    }
+  }
+
+  /// Adds a synonym dictionary to expand query terms.
+  ///
+  /// Matches on a synonym contribute to the field score multiplied by the
+  /// synonym's weight, so "automobile" can match a query for "car" with a
+  /// reduced score. Terms and synonyms are analyzed like the query terms of
+  /// each field, so they match stemmed fields, and synonyms of several words
+  /// (e.g., "machine learning" for "ml") only match as a phrase. Fields using
+  /// the `Wildcard`, `Numeric`, and `Regex` matchers don't use synonyms.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::SemanticSearch;
+  ///
+  /// let rules = SemanticRules::builder()
+  ///     .field("title", FieldRule::bm25())
+  ///     .build();
+  ///
+  /// let searcher = SemanticSearch::new(rules)
+  ///     .with_synonyms(SynonymMap::new().with("car", "automobile", 0.8));
+  /// ```
+  pub fn with_synonyms(mut self, synonyms: SynonymMap) -> Self {
+    self.synonyms = Some(synonyms);
+    self.analyzed_synonyms = Mutex::default();
+    self
+  }
+
+  /// Sets the BM25 scorer of the fields using the `BM25` matcher.
+  ///
+  /// Fields with a [`bm25_variant`](FieldRule::bm25_variant) keep the `k1`
+  /// and `b` parameters of this scorer but use their own variant.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::bm25::{BM25Scorer, BM25Variant};
+  ///
+  /// let rules = SemanticRules::builder()
+  ///     .field("title", FieldRule::bm25().bm25_variant(BM25Variant::Classic))
+  ///     .field("body", FieldRule::bm25())
+  ///     .build();
+  ///
+  /// let searcher = SemanticSearch::new(rules)
+  ///     .with_bm25(BM25Scorer::new().with_variant(BM25Variant::bm25l()));
+  /// ```
+  pub fn with_bm25(mut self, bm25: BM25Scorer) -> Self {
+    self.bm25 = bm25;
+    self
+  }
+
+  /// Removes stopwords from queries and documents before scoring.
+  ///
+  /// This appends a stopword filter to the default analyzer.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::SemanticSearch;
+  ///
+  /// let rules = SemanticRules::builder()
+  ///     .field("title", FieldRule::bm25())
+  ///     .build();
+  ///
+  /// let searcher = SemanticSearch::new(rules).with_stopwords(StopWords::english());
+  /// ```
+  pub fn with_stopwords(mut self, stopwords: StopWords) -> Self {
+    self.analyzer = self.analyzer.filter(Box::new(stopwords));
+    self
+  }
+
+  /// Reduces query and document tokens to their stems before scoring.
+  ///
+  /// This makes morphological variants match, e.g., "running" matches "run"
+  /// and "searches" matches "search". It appends a stemming filter to the
+  /// default analyzer, so stopwords should be added first. Requires the
+  /// `stemming` feature.
+  #[cfg(feature = "stemming")]
+  pub fn with_stemmer(mut self, stemmer: Stemmer) -> Self {
+    self.analyzer = self.analyzer.filter(Box::new(stemmer));
+    self
+  }
+
+  /// Replaces the tokenizer of the default analyzer, which is a
+  /// [`UnicodeTokenizer`](crate::searchers::tokenizer::UnicodeTokenizer)
+  /// unless configured otherwise.
+  pub fn with_tokenizer(mut self, tokenizer: Box<dyn Tokenizer>) -> Self {
+    self.analyzer = self.analyzer.tokenizer(tokenizer);
+    self
+  }
+
+  /// Replaces the default analyzer, used for the query and for every field
+  /// without a named analyzer.
+  pub fn with_analyzer(mut self, analyzer: Analyzer) -> Self {
+    self.analyzer = analyzer;
+    self
+  }
+
+  /// Registers a named analyzer for the fields whose
+  /// [`FieldRule::analyzer`] refers to it.
+  ///
+  /// The query text is analyzed separately for each of those fields, so
+  /// that it produces the same kind of terms as the field. The
+  /// [built-in analyzers](Analyzer::builtin) are registered by default and
+  /// can be replaced under the same name.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::analyzer::{Analyzer, LengthFilter};
+  ///
+  /// let rules = SemanticRules::builder()
+  ///     .field("title", FieldRule::bm25())
+  ///     .field("tags", FieldRule::tokenized().analyzer("short"))
+  ///     .build();
+  ///
+  /// let searcher = SemanticSearch::new(rules).with_named_analyzer(
+  ///     "short",
+  ///     Analyzer::default().filter(Box::new(LengthFilter::new(1, 10))),
+  /// );
+  /// ```
+  pub fn with_named_analyzer(mut self, name: impl Into<String>, analyzer: Analyzer) -> Self {
+    self.analyzers.insert(name.into(), analyzer);
+    self
+  }
+
+  /// Registers the analyzer for documents in a language.
+  ///
+  /// The language of each document is read from the
+  /// [language field](Self::with_language_field) or, with the
+  /// `language-detection` feature, [detected](Self::with_language_detection)
+  /// from its text. Fields without a named analyzer are then analyzed with
+  /// the analyzer of the document's language, and the query is analyzed the
+  /// same way when it is matched against them. Documents in other languages
+  /// use the default analyzer.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::analyzer::Analyzer;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Post { title: String, lang: String }
+  ///
+  /// let posts = vec![
+  ///     Post { title: "The art of the deal".into(), lang: "en".into() },
+  ///     Post { title: "Die Kunst des Krieges".into(), lang: "de".into() },
+  /// ];
+  ///
+  /// let rules = SemanticRules::builder().field("title", FieldRule::tokenized()).build();
+  /// let searcher = SemanticSearch::new(rules)
+  ///     .with_language_field("lang")
+  ///     .with_language_analyzer("en", Analyzer::for_language("en"))
+  ///     .with_language_analyzer("de", Analyzer::default().filter(Box::new(StopWords::new(["die", "des"]))));
+  /// let engine = SearusEngine::builder().with(Box::new(searcher)).build();
+  ///
+  /// // "die" is a stopword in German documents only.
+  /// let results = engine.search(&posts, &Query::builder().text("die").build());
+  /// assert!(results.is_empty());
+  ///
+  /// let query = Query::builder().text("art kunst").language("de").build();
+  /// let results = engine.search(&posts, &query);
+  /// assert_eq!(results.len(), 1);
+  /// assert_eq!(results[0].item.lang, "de");
+  /// ```
+  pub fn with_language_analyzer(mut self, language: impl Into<String>, analyzer: Analyzer) -> Self {
+    self
+      .language_analyzers
+      .insert(language.into().to_lowercase(), analyzer);
+    self
+  }
+
+  /// Sets the field holding the language code of each document (e.g., `"en"`).
+  pub fn with_language_field(mut self, field: impl Into<String>) -> Self {
+    self.language_field = Some(field.into());
+    self
+  }
+
+  /// Detects the language of documents that have no language field.
+  ///
+  /// The language is detected from the text of the fields in the rules, and
+  /// reported as an ISO 639-1 code where one exists (see
+  /// [`detect_language`](crate::searchers::language::detect_language)).
+  /// Requires the `language-detection` feature.
+  #[cfg(feature = "language-detection")]
+  pub fn with_language_detection(mut self) -> Self {
+    self.detect_language = true;
+    self
+  }
+
+  /// Returns the language of a document, if it is known.
+  fn document_language<T: serde::Serialize>(&self, item: &T) -> Option<String> {
+    #[cfg(feature = "language-detection")]
+    let detect = self.detect_language;
+    #[cfg(not(feature = "language-detection"))]
+    let detect = false;
+
+    if self.language_field.is_none() && !detect {
+      return None;
     }
+
+    let value = to_document(item).ok()?;
+    let field = self
+      .language_field
+      .as_ref()
+      .and_then(|field| Self::get_nested_field(&value, field));
+    if let Some(language) = field {
+      return Some(language.to_lowercase());
+    }
+
+    #[cfg(feature = "language-detection")]
+    if detect {
+      let text: Vec<String> = self
+        .scored_fields()
+        .iter()
+        .flat_map(|field| get_field_values(&value, &field.path))
+        .filter_map(|(_, value)| Self::value_text(value))
+        .collect();
+      return crate::searchers::language::detect_language(&text.join("\n"));
+    }
+
+    None
+  }
+
+  /// Returns the analyzer of a field in a document of the given language.
+  ///
+  /// A named analyzer of the field takes precedence over the analyzer of the
+  /// language, and the default analyzer is used if neither is registered.
+  fn analyzer_for(&self, rule: &FieldRule, language: Option<&str>) -> &Analyzer {
+    rule
+      .analyzer
+      .as_ref()
+      .and_then(|name| self.analyzers.get(name))
+      .or_else(|| language.and_then(|language| self.language_analyzers.get(language)))
+      .unwrap_or(&self.analyzer)
+  }
+
+  /// Returns the analyzer of the query text, for the query's language if it
+  /// has one.
+  fn query_analyzer(&self, query: &Query) -> &Analyzer {
+    query
+      .language
+      .as_ref()
+      .and_then(|language| self.language_analyzers.get(language))
+      .unwrap_or(&self.analyzer)
+  }
+
+  /// Returns the query terms for a field, analyzing the query text again if
+  /// the field (in a document of the given language) uses another analyzer
+  /// than the query.
+  ///
+  /// Pattern and numeric matchers use the lowercase words of the query text
+  /// as they are, and regular expressions use the rule's pattern or the whole
+  /// query text.
+  fn field_query_terms<'a>(
+    &self,
+    rule: &FieldRule,
+    language: Option<&str>,
+    query: &Query,
+    query_terms: &'a [String],
+  ) -> Cow<'a, [String]> {
+    match (rule.matcher, &rule.pattern, &query.text) {
+      (Matcher::Wildcard | Matcher::Numeric, _, Some(text)) => {
+        return Cow::Owned(text.split_whitespace().map(str::to_lowercase).collect());
+      }
+      (Matcher::Regex, Some(pattern), _) => return Cow::Owned(vec![pattern.clone()]),
+      (Matcher::Regex, None, Some(text)) => return Cow::Owned(vec![text.trim().to_string()]),
+      _ => {}
+    }
+
+    let analyzer = self.field_query_analyzer(rule, language);
+
+    // Analyzers are compared by identity: the query terms were produced by
+    // the query analyzer.
+    match &query.text {
+      Some(text) if !std::ptr::eq(analyzer, self.query_analyzer(query)) => {
+        Cow::Owned(analyzer.analyze(text))
+      }
+      _ => Cow::Borrowed(query_terms),
+    }
+  }
+
+  /// Returns the analyzer of the query text for a field, in a document of
+  /// the given language.
+  fn field_query_analyzer(&self, rule: &FieldRule, language: Option<&str>) -> &Analyzer {
+    rule
+      .search_analyzer
+      .as_ref()
+      .and_then(|name| self.analyzers.get(name))
+      .unwrap_or_else(|| self.analyzer_for(rule, language))
+  }
+
+  /// Returns the weighted synonyms of the query terms of a field, analyzed
+  /// like those terms.
+  fn field_synonyms(
+    &self,
+    rule: &FieldRule,
+    language: Option<&str>,
+    field_query_terms: &[String],
+  ) -> Vec<(Vec<String>, f32)> {
+    let Some(synonyms) = &self.synonyms else {
+      return Vec::new();
+    };
+    // These matchers don't use analyzed query terms.
+    if let Matcher::Wildcard | Matcher::Numeric | Matcher::Regex = rule.matcher {
+      return Vec::new();
+    }
+
+    let analyzer = self.field_query_analyzer(rule, language);
+    let analyzed = {
+      let mut cache = self
+        .analyzed_synonyms
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+      match cache.iter().find(|(cached, _)| cached.ptr_eq(analyzer)) {
+        Some((_, analyzed)) => analyzed.clone(),
+        None => {
+          let analyzed = Arc::new(AnalyzedSynonyms::new(synonyms, analyzer));
+          cache.push((analyzer.clone(), analyzed.clone()));
+          analyzed
+        }
+      }
+    };
+    analyzed.expand(field_query_terms)
+  }
+
+  /// Returns the fields scored by the searcher: the top-level fields of the
+  /// rules, followed by the fields of their nested objects.
+  fn scored_fields(&self) -> Vec<ScoredField<'_>> {
+    let fields = self.rules.fields.iter().map(|(name, rule)| ScoredField {
+      path: name.clone(),
+      rule,
+      array: None,
+    });
+
+    let objects = self.rules.objects.iter().flat_map(|(object, object_rule)| {
+      object_rule
+        .fields
+        .iter()
+        .map(move |(name, rule)| match object_rule.access {
+          ObjectAccess::Direct => ScoredField {
+            path: format!("{}.{}", object, name),
+            rule,
+            array: None,
+          },
+          ObjectAccess::Array => ScoredField {
+            path: format!("{}.*.{}", object, name),
+            rule,
+            array: Some(object.as_str()),
+          },
+        })
+    });
+
+    fields.chain(objects).collect()
   }
 
-  /// Extract field value from an item using serde_json.
-  fn extract_field<T>(item: &T, field: &str) -> Option<String>
+  /// Extract the values of a field from an item using serde_json.
+  ///
+  /// Field paths may contain array indices (`comments.0.text`) and `*`
+  /// segments matching every element (`comments.*.text`). Each value is
+  /// returned with its concrete path.
+  fn extract_fields<T>(item: &T, field: &str) -> Vec<(String, String)>
   where
     T: serde::Serialize,
   {
     // Serialize to JSON value for field access
-    let value = serde_json::to_value(item).ok()?;
-    Self::get_nested_field(&value, field)
+    let Ok(value) = to_document(item) else {
+      return Vec::new();
+    };
+    get_field_values(&value, field)
+      .into_iter()
+      .flat_map(|(path, value)| -> Vec<(String, String)> {
+        match value {
+          // Each element of an array is searched, with its own path.
+          Value::Array(elements) => elements
+            .iter()
+            .enumerate()
+            .filter_map(|(i, element)| {
+              Some((format!("{}.{}", path, i), Self::value_text(element)?))
+            })
+            .collect(),
+          value => Self::value_text(value)
+            .map(|text| (path, text))
+            .into_iter()
+            .collect(),
+        }
+      })
+      .collect()
   }
 
   /// Get a nested field from a JSON value.
   fn get_nested_field(value: &Value, path: &str) -> Option<String> {
-    let parts: Vec<&str> = path.split('.').collect();
-    let mut current = value;
-
-    for part in parts {
-      current = current.get(part)?;
-    }
+    get_field_value(value, path).and_then(Self::value_text)
+  }
 
-    match current {
+  /// Returns the text of a scalar JSON value.
+  fn value_text(value: &Value) -> Option<String> {
+    match value {
       Value::String(s) => Some(s.clone()),
       Value::Number(n) => Some(n.to_string()),
       Value::Bool(b) => Some(b.to_string()),
@@ -85,42 +514,109 @@ impl SemanticSearch {
     }
   }
 
-  /// Calculate corpus statistics for BM25.
-  fn calculate_corpus_stats<T>(items: &[T], rules: &SemanticRules) -> CorpusStats
+  /// Analyzes the values of the scored fields of a document in the given
+  /// language.
+  fn analyze_document<T>(&self, item: &T, language: Option<String>) -> AnalyzedDocument
+  where
+    T: serde::Serialize,
+  {
+    let fields = self
+      .scored_fields()
+      .iter()
+      .map(|field| {
+        let analyzer = self.analyzer_for(field.rule, language.as_deref());
+        Self::extract_fields(item, &field.path)
+          .into_iter()
+          .map(|(path, text)| AnalyzedValue {
+            tokens: analyzer.analyze(&text),
+            path,
+            text,
+          })
+          .collect()
+      })
+      .collect();
+
+    AnalyzedDocument { language, fields }
+  }
+
+  /// Calculates corpus statistics from analyzed documents, as
+  /// `calculate_corpus_stats` does from items.
+  fn document_stats(documents: &[AnalyzedDocument]) -> CorpusStats {
+    let mut doc_freq: HashMap<String, usize> = HashMap::new();
+    let mut total_length = 0;
+    let mut doc_count = 0;
+
+    for document in documents {
+      let mut doc_terms = std::collections::HashSet::new();
+      for value in document.fields.iter().flatten() {
+        total_length += value.tokens.len();
+        doc_count += 1;
+        doc_terms.extend(&value.tokens);
+      }
+
+      for term in doc_terms {
+        *doc_freq.entry(term.clone()).or_insert(0) += 1;
+      }
+    }
+
+    let avg_doc_length = if doc_count > 0 {
+      total_length as f32 / doc_count as f32
+    } else {
+      0.0
+    };
+
+    CorpusStats {
+      doc_freq,
+      avg_doc_length,
+      total_docs: documents.len(),
+    }
+  }
+
+  /// Calculate corpus statistics for BM25 and TF-IDF, in parallel if the
+  /// context allows it.
+  fn calculate_corpus_stats<T>(&self, context: &SearchContext<T>) -> CorpusStats
   where
     T: serde::Serialize + Searchable,
   {
+    let items = context.items;
+
     // --- Parallel version ---
     #[cfg(feature = "parallel")]
-    {
+    if context.is_parallel() {
       // Each thread computes partial stats
       let doc_freq: DashMap<String, AtomicUsize> = DashMap::new();
       let total_length = AtomicUsize::new(0);
       let doc_count = AtomicUsize::new(0);
 
-      items.par_iter().for_each(|item| {
-        let mut terms = std::collections::HashSet::new();
+      items
+        .par_iter()
+        .with_min_len(context.chunk_size())
+        .for_each(|item| {
+          let mut terms = HashSet::new();
 
-        for (field_name, _) in &rules.fields {
-          if let Some(text) = Self::extract_field(item, field_name) {
-            let tokens = tokenize(&text);
+          let language = self.document_language(item);
+          for field in self.scored_fields() {
+            for (_, text) in Self::extract_fields(item, &field.path) {
+              let tokens = self
+                .analyzer_for(field.rule, language.as_deref())
+                .analyze(&text);
 
-            total_length.fetch_add(tokens.len(), Ordering::Relaxed);
-            doc_count.fetch_add(1, Ordering::Relaxed);
+              total_length.fetch_add(tokens.len(), Ordering::Relaxed);
+              doc_count.fetch_add(1, Ordering::Relaxed);
 
-            for t in tokens {
-              terms.insert(t);
+              for t in tokens {
+                terms.insert(t);
+              }
             }
           }
-        }
 
-        for t in terms {
-          doc_freq
-            .entry(t)
-            .or_insert_with(|| AtomicUsize::new(0))
-            .fetch_add(1, Ordering::Relaxed);
-        }
-      });
+          for t in terms {
+            doc_freq
+              .entry(t)
+              .or_insert_with(|| AtomicUsize::new(0))
+              .fetch_add(1, Ordering::Relaxed);
+          }
+        });
 
       let df_map: HashMap<String, usize> = doc_freq
         .into_iter()
@@ -138,7 +634,6 @@ impl SemanticSearch {
     }
 
     // --- Sequential version ---
-    #[cfg(not(feature = "parallel"))]
     {
       let mut doc_freq: HashMap<String, usize> = HashMap::new();
       let mut total_length = 0;
@@ -147,9 +642,12 @@ impl SemanticSearch {
       for item in items {
         let mut doc_terms = HashSet::new();
 
-        for (field_name, _) in &rules.fields {
-          if let Some(text) = Self::extract_field(item, field_name) {
-            let tokens = tokenize(&text);
+        let language = self.document_language(item);
+        for field in self.scored_fields() {
+          for (_, text) in Self::extract_fields(item, &field.path) {
+            let tokens = self
+              .analyzer_for(field.rule, language.as_deref())
+              .analyze(&text);
             total_length += tokens.len();
             doc_count += 1;
 
@@ -185,6 +683,126 @@ pub struct CorpusStats {
   total_docs: usize,
 }
 
+/// A field scored by the searcher, either at the top level of the rules or
+/// in a nested object.
+struct ScoredField<'a> {
+  /// The path of the field, with a `*` segment for the elements of arrays.
+  path: String,
+  /// The rule of the field.
+  rule: &'a FieldRule,
+  /// The name of the array object the field belongs to, if any.
+  array: Option<&'a str>,
+}
+
+/// A document analyzed for scoring.
+struct AnalyzedDocument {
+  /// The language of the document, if it is known.
+  language: Option<String>,
+  /// The values of each scored field, in the order of `scored_fields`.
+  fields: Vec<Vec<AnalyzedValue>>,
+}
+
+/// A value of a field, with its tokens.
+struct AnalyzedValue {
+  /// The concrete path of the value.
+  path: String,
+  /// The text of the value.
+  text: String,
+  /// The tokens of the text, produced by the analyzer of the field.
+  tokens: Vec<String>,
+}
+
+/// The corpus precomputed by `warm_up`: its statistics and every analyzed
+/// document, by item index.
+struct WarmCorpus {
+  stats: CorpusStats,
+  documents: Vec<AnalyzedDocument>,
+}
+
+/// A synonym map analyzed by an analyzer of query terms.
+struct AnalyzedSynonyms {
+  /// The analyzed terms of the map, mapped to their analyzed synonyms and
+  /// weights.
+  entries: HashMap<Vec<String>, Vec<(Vec<String>, f32)>>,
+  /// The length of the longest analyzed term.
+  longest: usize,
+}
+
+impl AnalyzedSynonyms {
+  /// Analyzes the terms and synonyms of a map. Synonyms analyzed like their
+  /// term, or into nothing, are left out.
+  fn new(synonyms: &SynonymMap, analyzer: &Analyzer) -> Self {
+    let mut entries: HashMap<Vec<String>, Vec<(Vec<String>, f32)>> = HashMap::new();
+    for (term, synonyms) in synonyms.iter() {
+      let term = analyzer.analyze(term);
+      for (synonym, &weight) in synonyms {
+        let synonym = analyzer.analyze(synonym);
+        if !term.is_empty() && !synonym.is_empty() && synonym != term {
+          entries
+            .entry(term.clone())
+            .or_default()
+            .push((synonym, weight));
+        }
+      }
+    }
+
+    let longest = entries.keys().map(Vec::len).max().unwrap_or(0);
+    Self { entries, longest }
+  }
+
+  /// Returns the synonyms of every run of consecutive query terms, except
+  /// those whose terms are all in the query, sorted. If a synonym is
+  /// reachable from several runs, the highest weight is kept.
+  fn expand(&self, terms: &[String]) -> Vec<(Vec<String>, f32)> {
+    let mut expanded: HashMap<&[String], f32> = HashMap::new();
+
+    for start in 0..terms.len() {
+      for end in start + 1..=terms.len().min(start + self.longest) {
+        let Some(synonyms) = self.entries.get(&terms[start..end]) else {
+          continue;
+        };
+        for (synonym, weight) in synonyms {
+          if synonym.iter().all(|term| terms.contains(term)) {
+            continue;
+          }
+          expanded
+            .entry(synonym)
+            .and_modify(|w| *w = w.max(*weight))
+            .or_insert(*weight);
+        }
+      }
+    }
+
+    let mut expanded: Vec<(Vec<String>, f32)> = expanded
+      .into_iter()
+      .map(|(synonym, weight)| (synonym.to_vec(), weight))
+      .collect();
+    expanded.sort_by(|a, b| a.0.cmp(&b.0));
+    expanded
+  }
+}
+
+/// Returns `true` if a synonym of several terms occurs as a phrase in a list
+/// of tokens. Single terms are left to the matcher of the field.
+fn contains_phrase(tokens: &[String], synonym: &[String]) -> bool {
+  synonym.len() < 2
+    || tokens
+      .windows(synonym.len())
+      .any(|window| window == synonym)
+}
+
+/// Returns the index of the element of an array object a concrete path
+/// (e.g., `comments.2.text`) belongs to.
+fn element_index(object: &str, path: &str) -> Option<usize> {
+  path
+    .strip_prefix(object)?
+    .strip_prefix('.')?
+    .split('.')
+    .next()?
+    .parse()
+    .ok()
+}
+
 impl<T> Searcher<T> for SemanticSearch
 where
   T: SemanticSearchable,
@@ -206,77 +824,91 @@ where
     }
 
     // Tokenize query
-    let query_terms = tokenize(query_text);
+    let query_terms = self.query_analyzer(query).analyze(query_text);
     if query_terms.is_empty() {
       return Vec::new();
     }
 
-    // Calculate corpus statistics
-    let stats = Self::calculate_corpus_stats(items, &self.rules);
-
-    // Score each item
-    #[cfg(feature = "parallel")]
-    let mut results: Vec<SearusMatch<T>> = {
-      // OPTIMIZATION: Collect into pre-allocated vector
-      let matches: Vec<_> = items
-        .par_iter()
-        .enumerate()
-        .filter(|(_, item)| {
-          if let Some(filters) = &query.filters {
-            filters.evaluate(item)
-          } else {
-            true
-          }
-        })
-        .filter_map(|(index, item)| self.match_entity(item, index, query, &stats, &query_terms))
-        .collect();
-
-      let mut results = Vec::with_capacity(matches.len());
-      results.extend(matches);
-      results
-    };
+    let mut results = self.warm.with(items, |warm| {
+      // Calculate corpus statistics, unless they were precomputed
+      let cold_stats;
+      let stats = match warm {
+        Some(warm) => &warm.stats,
+        None => {
+          cold_stats = self.calculate_corpus_stats(context);
+          &cold_stats
+        }
+      };
+      let match_item = |index: usize, item: &T| match warm {
+        Some(warm) => self.match_document(
+          item,
+          index,
+          &warm.documents[index],
+          query,
+          stats,
+          &query_terms,
+        ),
+        None => self.match_entity(item, index, query, stats, &query_terms),
+      };
 
-    #[cfg(not(feature = "parallel"))]
-    let mut results: Vec<SearusMatch<T>> = {
-      // OPTIMIZATION: Pre-allocate with estimated capacity
-      let mut results = Vec::with_capacity(items.len() / 10); // Assume ~10% match rate
-      results.extend(
-        items
-          .iter()
-          .enumerate()
-          .filter(|(_, item)| {
-            if let Some(filters) = &query.filters {
-              filters.evaluate(item)
-            } else {
-              true
-            }
-          })
-          .filter_map(|(index, item)| self.match_entity(item, index, query, &stats, &query_terms)),
-      );
-      results
-    };
+      // Score each item
+      context.scan(None, query, match_item)
+    });
 
     // Sort by score descending
     self.sort_results(&mut results);
 
     results
   }
+
+  /// Analyzes every item and computes the corpus statistics, which searches
+  /// over the same items then reuse.
+  fn warm_up(&self, items: &[T]) {
+    #[cfg(feature = "parallel")]
+    let documents: Vec<AnalyzedDocument> = items
+      .par_iter()
+      .map(|item| self.analyze_document(item, self.document_language(item)))
+      .collect();
+    #[cfg(not(feature = "parallel"))]
+    let documents: Vec<AnalyzedDocument> = items
+      .iter()
+      .map(|item| self.analyze_document(item, self.document_language(item)))
+      .collect();
+
+    let stats = Self::document_stats(&documents);
+    self.warm.set(items, WarmCorpus { stats, documents });
+  }
+
+  fn cool_down(&self, items: &[T]) {
+    self.warm.clear(items);
+  }
 }
 
 impl SemanticSearch {
+  /// Returns the BM25 scorer of a field, with the field's variant if it has one.
+  fn bm25_for(&self, rule: &FieldRule) -> BM25Scorer {
+    match rule.bm25_variant {
+      Some(variant) => self.bm25.with_variant(variant),
+      None => self.bm25,
+    }
+  }
+
   /// Score a single field.
   fn score_field(
     &self,
     query_terms: &[String],
-    text: &str,
+    analyzed: &AnalyzedValue,
     rule: &FieldRule,
+    analyzer: &Analyzer,
     stats: &CorpusStats,
     matched_terms: &mut Vec<String>,
   ) -> f32 {
+    let (text, tokens) = (analyzed.text.as_str(), analyzed.tokens.as_slice());
+
     match rule.matcher {
       Matcher::Exact => {
-        // Exact match (case-insensitive)
-        let text_lower = text.to_lowercase();
+        // Exact phrase match (case-insensitive) over the analyzed tokens
+        let text_lower = tokens.join(" ");
         let query_lower = query_terms.join(" ");
         if text_lower.contains(&query_lower) {
           matched_terms.extend(query_terms.iter().cloned());
@@ -287,10 +919,10 @@ impl SemanticSearch {
       }
       Matcher::BM25 => {
         // BM25 scoring
-        let doc_terms = term_frequencies(text);
-        let doc_length = tokenize(text).len();
+        let doc_terms = count_terms(tokens);
+        let doc_length = tokens.len();
 
-        let score = self.bm25.score(
+        let score = self.bm25_for(rule).score(
           query_terms,
           &doc_terms,
           doc_length,
@@ -308,9 +940,22 @@ impl SemanticSearch {
 
         score
       }
+      Matcher::TfIdf => {
+        let doc_terms = count_terms(tokens);
+        let score =
+          TfIdfScorer::new().score(query_terms, &doc_terms, &stats.doc_freq, stats.total_docs);
+
+        for term in query_terms {
+          if doc_terms.contains_key(term) {
+            matched_terms.push(term.clone());
+          }
+        }
+
+        score
+      }
       Matcher::Tokenized => {
         // Simple token matching with term frequency
-        let doc_terms = term_frequencies(text);
+        let doc_terms = count_terms(tokens);
         let mut score = 0.0;
 
         for term in query_terms {
@@ -326,52 +971,559 @@ impl SemanticSearch {
         // Fuzzy matching handled by FuzzySearch
         0.0
       }
+      Matcher::Prefix => {
+        let mut score = 0.0;
+
+        for term in query_terms {
+          for token in tokens
+            .iter()
+            .filter(|token| token.starts_with(term.as_str()))
+          {
+            score += 1.0;
+            if !matched_terms.contains(token) {
+              matched_terms.push(token.clone());
+            }
+          }
+        }
+
+        score
+      }
+      Matcher::Numeric => {
+        // Each number of the query scores the closeness of the closest
+        // number in the field.
+        let mut score = 0.0;
+
+        for target in query_terms.iter().filter_map(|term| parse_number(term)) {
+          let closest = tokens
+            .iter()
+            .filter_map(|token| Some((token, numeric_closeness(parse_number(token)?, target))))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+          if let Some((token, closeness)) = closest {
+            score += closeness;
+            if !matched_terms.contains(token) {
+              matched_terms.push(token.clone());
+            }
+          }
+        }
+
+        score
+      }
+      Matcher::Wildcard => {
+        // Each pattern scores the number of tokens it matches, or one if it
+        // only matches the whole value.
+        let value = text.to_lowercase();
+        let mut score = 0.0;
+
+        for pattern in query_terms {
+          let matches: Vec<&String> = tokens
+            .iter()
+            .filter(|token| wildcard_match(pattern, token))
+            .collect();
+          if !matches.is_empty() {
+            score += matches.len() as f32;
+            for token in matches {
+              if !matched_terms.contains(token) {
+                matched_terms.push(token.clone());
+              }
+            }
+          } else if wildcard_match(pattern, &value) {
+            score += 1.0;
+          }
+        }
+
+        score
+      }
+      Matcher::Regex => {
+        #[cfg(feature = "regex")]
+        {
+          self.score_regex(query_terms, text, analyzer, matched_terms)
+        }
+        #[cfg(not(feature = "regex"))]
+        {
+          let _ = analyzer;
+          0.0
+        }
+      }
     }
   }
+
+  /// Scores the number of matches of regular expressions in a text.
+  ///
+  /// The tokens covered by a match are added to the matched terms.
+  #[cfg(feature = "regex")]
+  fn score_regex(
+    &self,
+    patterns: &[String],
+    text: &str,
+    analyzer: &Analyzer,
+    matched_terms: &mut Vec<String>,
+  ) -> f32 {
+    let mut score = 0.0;
+
+    for pattern in patterns {
+      let Some(regex) = self.regexes.get(pattern) else {
+        continue;
+      };
+      let ranges: Vec<_> = regex
+        .find_iter(text)
+        .filter(|m| !m.is_empty())
+        .map(|m| m.range())
+        .collect();
+      if ranges.is_empty() {
+        continue;
+      }
+
+      score += ranges.len() as f32;
+      for (token, range) in analyzer.analyze_with_offsets(text) {
+        let covered = ranges
+          .iter()
+          .any(|r| r.start <= range.start && range.end <= r.end);
+        if covered && !matched_terms.contains(&token) {
+          matched_terms.push(token);
+        }
+      }
+    }
+
+    score
+  }
 }
 
 impl SemanticSearch {
+  /// Explains the score of a single field, mirroring `score_field`.
+  fn explain_field(
+    &self,
+    query_terms: &[String],
+    analyzed: &AnalyzedValue,
+    rule: &FieldRule,
+    analyzer: &Analyzer,
+    stats: &CorpusStats,
+  ) -> Explanation {
+    let tokens = analyzed.tokens.as_slice();
+
+    match rule.matcher {
+      Matcher::Exact => {
+        let value = self.score_field(
+          query_terms,
+          analyzed,
+          rule,
+          analyzer,
+          stats,
+          &mut Vec::new(),
+        );
+        Explanation::new(value, format!("exact phrase \"{}\"", query_terms.join(" ")))
+      }
+      Matcher::BM25 => self.bm25_for(rule).explain(
+        query_terms,
+        &count_terms(tokens),
+        tokens.len(),
+        stats.avg_doc_length,
+        &stats.doc_freq,
+        stats.total_docs,
+      ),
+      Matcher::TfIdf => TfIdfScorer::new().explain(
+        query_terms,
+        &count_terms(tokens),
+        &stats.doc_freq,
+        stats.total_docs,
+      ),
+      Matcher::Tokenized => {
+        let doc_terms = count_terms(tokens);
+        let mut explanation = Explanation::new(0.0, "term frequencies, sum of:");
+        for term in query_terms {
+          if let Some(&freq) = doc_terms.get(term) {
+            explanation.value += freq as f32;
+            explanation
+              .details
+              .push(Explanation::new(freq as f32, format!("term \"{}\"", term)));
+          }
+        }
+        explanation
+      }
+      Matcher::Fuzzy => Explanation::new(0.0, "fuzzy matching is handled by FuzzySearch"),
+      Matcher::Prefix => {
+        let mut explanation = Explanation::new(0.0, "prefixes, sum of:");
+        for term in query_terms {
+          let count = tokens
+            .iter()
+            .filter(|token| token.starts_with(term.as_str()))
+            .count();
+          if count > 0 {
+            explanation.value += count as f32;
+            explanation.details.push(Explanation::new(
+              count as f32,
+              format!("prefix \"{}\", matching tokens", term),
+            ));
+          }
+        }
+        explanation
+      }
+      Matcher::Numeric => {
+        let mut explanation = Explanation::new(0.0, "numeric closeness, sum of:");
+        for term in query_terms {
+          let mut closest = Vec::new();
+          let value = self.score_field(
+            std::slice::from_ref(term),
+            analyzed,
+            rule,
+            analyzer,
+            stats,
+            &mut closest,
+          );
+          if let Some(closest) = closest.first() {
+            explanation.value += value;
+            explanation.details.push(Explanation::new(
+              value,
+              format!("closest value {} to {}", closest, term),
+            ));
+          }
+        }
+        explanation
+      }
+      Matcher::Wildcard => {
+        let mut explanation = Explanation::new(0.0, "wildcard patterns, sum of:");
+        for pattern in query_terms {
+          let value = self.score_field(
+            std::slice::from_ref(pattern),
+            analyzed,
+            rule,
+            analyzer,
+            stats,
+            &mut Vec::new(),
+          );
+          if value > 0.0 {
+            explanation.value += value;
+            explanation
+              .details
+              .push(Explanation::new(value, format!("pattern \"{}\"", pattern)));
+          }
+        }
+        explanation
+      }
+      Matcher::Regex => {
+        let mut explanation = Explanation::new(0.0, "regular expressions, sum of:");
+        for pattern in query_terms {
+          let value = self.score_field(
+            std::slice::from_ref(pattern),
+            analyzed,
+            rule,
+            analyzer,
+            stats,
+            &mut Vec::new(),
+          );
+          if value > 0.0 {
+            explanation.value += value;
+            explanation.details.push(Explanation::new(
+              value,
+              format!("pattern /{}/, number of matches", pattern),
+            ));
+          }
+        }
+        explanation
+      }
+    }
+  }
+
+  /// Collects the frequency and token positions of each distinct matched term
+  /// in a field, in order of first occurrence.
+  fn term_matches(&self, tokens: Vec<Token>, terms: &[String]) -> Vec<TermMatch> {
+    let mut matches: Vec<TermMatch> = Vec::new();
+
+    for (position, (token, _)) in tokens.into_iter().enumerate() {
+      if !terms.contains(&token) {
+        continue;
+      }
+      match matches.iter_mut().find(|m| m.term == token) {
+        Some(m) => {
+          m.frequency += 1;
+          m.positions.push(position);
+        }
+        None => matches.push(TermMatch {
+          term: token,
+          frequency: 1,
+          positions: vec![position],
+        }),
+      }
+    }
+
+    matches
+  }
+
+  /// Explains the raw (unweighted) score of a single value of a field,
+  /// including its synonym matches.
+  fn explain_value(
+    &self,
+    analyzed: &AnalyzedValue,
+    rule: &FieldRule,
+    analyzer: &Analyzer,
+    query_terms: &[String],
+    synonyms: &[(Vec<String>, f32)],
+    stats: &CorpusStats,
+  ) -> Explanation {
+    let mut explanation = Explanation::new(0.0, "sum of:").with_detail(self.explain_field(
+      query_terms,
+      analyzed,
+      rule,
+      analyzer,
+      stats,
+    ));
+
+    for (synonym, weight) in synonyms {
+      if !contains_phrase(&analyzed.tokens, synonym) {
+        continue;
+      }
+      let matched = self.explain_field(synonym, analyzed, rule, analyzer, stats);
+      if matched.value > 0.0 {
+        explanation.details.push(
+          Explanation::new(
+            weight * matched.value,
+            format!(
+              "synonym \"{}\", weight {} times:",
+              synonym.join(" "),
+              weight
+            ),
+          )
+          .with_detail(matched),
+        );
+      }
+    }
+
+    explanation.value = explanation.details.iter().map(|detail| detail.value).sum();
+    explanation
+  }
+
+  /// Builds the explanation of a matched document's raw score.
+  fn explain_document(
+    &self,
+    document: &AnalyzedDocument,
+    query: &Query,
+    stats: &CorpusStats,
+    query_terms: &[String],
+    field_scores: &HashMap<String, f32>,
+  ) -> Explanation {
+    let total_score = field_scores.values().sum();
+    let mut explanation = Explanation::new(total_score, "semantic, sum of fields:");
+    let language = document.language.as_deref();
+    // The weighted field explanations of each element of array objects.
+    let mut elements: HashMap<&str, HashMap<usize, Vec<Explanation>>> = HashMap::new();
+
+    for (field, values) in self.scored_fields().into_iter().zip(&document.fields) {
+      let rule = field.rule;
+      let analyzer = self.analyzer_for(rule, language);
+      let field_query_terms = self.field_query_terms(rule, language, query, query_terms);
+      let synonyms = &self.field_synonyms(rule, language, &field_query_terms);
+      let weight = |path: &str| {
+        format!(
+          "field \"{}\", boost {} times priority {} times:",
+          path, rule.boost, rule.priority
+        )
+      };
+
+      if let Some(object) = field.array {
+        for value in values {
+          let raw = self.explain_value(value, rule, analyzer, &field_query_terms, synonyms, stats);
+          if let (true, Some(element)) = (raw.value > 0.0, element_index(object, &value.path)) {
+            let weighted = raw.value * rule.boost * rule.priority as f32;
+            elements
+              .entry(object)
+              .or_default()
+              .entry(element)
+              .or_default()
+              .push(Explanation::new(weighted, weight(&value.path)).with_detail(raw));
+          }
+        }
+        continue;
+      }
+
+      let Some(&weighted_score) = field_scores.get(&field.path) else {
+        continue;
+      };
+
+      let mut raw = Explanation::new(0.0, "sum of:");
+      for value in values {
+        let explained =
+          self.explain_value(value, rule, analyzer, &field_query_terms, synonyms, stats);
+        if value.path == field.path {
+          // A single value needs no extra level.
+          raw.details.extend(explained.details);
+        } else if explained.value > 0.0 {
+          raw.details.push(Explanation {
+            description: format!("value of \"{}\", sum of:", value.path),
+            ..explained
+          });
+        }
+      }
+
+      raw.value = raw.details.iter().map(|detail| detail.value).sum();
+      explanation
+        .details
+        .push(Explanation::new(weighted_score, weight(&field.path)).with_detail(raw));
+    }
+
+    for (object, elements) in elements {
+      let Some(&score) = field_scores.get(object) else {
+        continue;
+      };
+      let score_mode = match self.rules.objects[object].score_mode {
+        ScoreMode::Sum => "sum",
+        ScoreMode::Max => "max",
+        ScoreMode::Avg => "average",
+      };
+
+      let mut elements: Vec<_> = elements.into_iter().collect();
+      elements.sort_by_key(|(element, _)| *element);
+      let mut combined = Explanation::new(
+        score,
+        format!("object \"{}\", {} of elements:", object, score_mode),
+      );
+      for (element, fields) in elements {
+        let mut detail = Explanation::new(0.0, format!("element {}, sum of:", element));
+        detail.value = fields.iter().map(|field| field.value).sum();
+        detail.details = fields;
+        combined.details.push(detail);
+      }
+      explanation.details.push(combined);
+    }
+
+    explanation
+  }
+
   /// Match a single entity against the query.
+  ///
+  /// If the query is restricted to a language, entities in other languages,
+  /// or whose language is unknown, do not match.
   pub fn match_entity<T>(
     &self,
     item: &T,
     index: usize,
-    _query: &Query,
+    query: &Query,
+    stats: &CorpusStats,
+    query_terms: &[String],
+  ) -> Option<SearusMatch<T>>
+  where
+    T: SemanticSearchable,
+  {
+    // The language is checked before the entity is analyzed.
+    let language = self.document_language(item);
+    if query.language.is_some() && language != query.language {
+      return None;
+    }
+    let document = self.analyze_document(item, language);
+    self.match_document(item, index, &document, query, stats, query_terms)
+  }
+
+  /// Matches an analyzed entity against the query.
+  fn match_document<T>(
+    &self,
+    item: &T,
+    index: usize,
+    document: &AnalyzedDocument,
+    query: &Query,
     stats: &CorpusStats,
     query_terms: &[String],
   ) -> Option<SearusMatch<T>>
   where
     T: SemanticSearchable,
   {
-    let mut total_score = 0.0;
     let mut field_scores = HashMap::new();
     let mut matched_terms = Vec::new();
+    let mut highlights = Vec::new();
+    let mut details = Vec::new();
+    // The summed scores of each element of array objects.
+    let mut elements: HashMap<&str, HashMap<usize, f32>> = HashMap::new();
 
-    // Score each configured field
-    for (field_name, field_rule) in &self.rules.fields {
-      if let Some(text) = Self::extract_field(item, field_name) {
-        let field_score =
-          self.score_field(query_terms, &text, field_rule, stats, &mut matched_terms);
+    let language = document.language.as_deref();
+    if query.language.is_some() && language != query.language.as_deref() {
+      return None;
+    }
+
+    // Score each configured field, summing the scores of its values.
+    for (field, values) in self.scored_fields().into_iter().zip(&document.fields) {
+      let field_rule = field.rule;
+      let analyzer = self.analyzer_for(field_rule, language);
+      let field_query_terms = self.field_query_terms(field_rule, language, query, query_terms);
+      let synonyms = self.field_synonyms(field_rule, language, &field_query_terms);
+
+      for analyzed in values {
+        let AnalyzedValue { path, text, .. } = analyzed;
+        let field_terms_start = matched_terms.len();
+        let mut field_score = self.score_field(
+          &field_query_terms,
+          analyzed,
+          field_rule,
+          analyzer,
+          stats,
+          &mut matched_terms,
+        );
+
+        // Synonym matches count with their reduced weight. Synonyms of
+        // several terms only match as a phrase.
+        for (synonym, weight) in &synonyms {
+          if !contains_phrase(&analyzed.tokens, synonym) {
+            continue;
+          }
+          field_score += weight
+            * self.score_field(
+              synonym,
+              analyzed,
+              field_rule,
+              analyzer,
+              stats,
+              &mut matched_terms,
+            );
+        }
 
         if field_score > 0.0 {
           let weighted_score = field_score * field_rule.boost * field_rule.priority as f32;
-          field_scores.insert(field_name.clone(), weighted_score);
-          total_score += weighted_score;
+          match field.array {
+            Some(object) => {
+              if let Some(element) = element_index(object, path) {
+                *elements
+                  .entry(object)
+                  .or_default()
+                  .entry(element)
+                  .or_insert(0.0) += weighted_score;
+              }
+            }
+            None => *field_scores.entry(field.path.clone()).or_insert(0.0) += weighted_score,
+          }
+
+          let field_terms = &matched_terms[field_terms_start..];
+          let tokens = analyzer.analyze_with_offsets(text);
+          if query.options.highlight {
+            highlights.extend(find_terms(path, text, tokens.clone(), field_terms));
+          }
+
+          let terms = self.term_matches(tokens, field_terms);
+          if !terms.is_empty() {
+            details.push(SearchDetail::Semantic {
+              matched_terms: terms.iter().map(|t| t.term.clone()).collect(),
+              field: path.clone(),
+              weight: weighted_score,
+              terms,
+            });
+          }
         }
       }
     }
 
+    // Array objects score the combination of their elements.
+    for (object, elements) in elements {
+      let scores: Vec<f32> = elements.into_values().collect();
+      let score = self.rules.objects[object].score_mode.combine(&scores);
+      field_scores.insert(object.to_string(), score);
+    }
+
+    let total_score: f32 = field_scores.values().sum();
     if total_score > 0.0 {
       let mut m = SearusMatch::new(item.clone(), total_score, index);
-      m.field_scores = field_scores;
-
-      if !matched_terms.is_empty() {
-        m.details.push(SearchDetail::Semantic {
-          matched_terms: matched_terms.clone(),
-          field: "multiple".to_string(),
-          weight: total_score,
-        });
+      if query.options.explain {
+        m.explanation =
+          Some(self.explain_document(document, query, stats, query_terms, &field_scores));
       }
+      m.field_scores = field_scores;
+      m.highlights = highlights;
+      m.details = details;
 
       Some(m)
     } else {
@@ -381,7 +1533,7 @@ impl SemanticSearch {
 
   /// Sort the search results.
   #[cfg(feature = "parallel")]
-  pub fn sort_results<T: Send + Sync>(&self, results: &mut Vec<SearusMatch<T>>) {
+  pub fn sort_results<T: Send + Sync>(&self, results: &mut [SearusMatch<T>]) {
     results.par_sort_by(|a, b| {
       b.score
         .partial_cmp(&a.score)
@@ -390,7 +1542,7 @@ impl SemanticSearch {
   }
 
   #[cfg(not(feature = "parallel"))]
-  pub fn sort_results<T>(&self, results: &mut Vec<SearusMatch<T>>) {
+  pub fn sort_results<T>(&self, results: &mut [SearusMatch<T>]) {
     results.sort_by(|a, b| {
       b.score
         .partial_cmp(&a.score)
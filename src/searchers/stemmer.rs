@@ -0,0 +1,86 @@
+//! Snowball stemming of token streams.
+//!
+//! Stemming reduces words to a common root, so that morphological variants
+//! such as "searches", "searching", and "searched" all match "search". It is
+//! available behind the `stemming` feature and uses the Snowball algorithms
+//! of the `rust-stemmers` crate.
+
+pub use rust_stemmers::Algorithm as Language;
+
+/// A Snowball stemmer for a single language.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::stemmer::{Language, Stemmer};
+///
+/// let stemmer = Stemmer::new(Language::English);
+///
+/// assert_eq!(stemmer.stem("searches"), "search");
+/// assert_eq!(
+///     stemmer.stem_tokens(vec!["running".to_string(), "fast".to_string()]),
+///     vec!["run".to_string(), "fast".to_string()]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stemmer {
+  /// The language of the stemming algorithm.
+  language: Language,
+}
+
+impl Stemmer {
+  /// Creates a stemmer for the given language.
+  pub fn new(language: Language) -> Self {
+    Self { language }
+  }
+
+  /// Creates the stemmer for a language, if one is available.
+  ///
+  /// Languages are identified by their ISO 639-1 code or English name
+  /// (e.g., `"en"` or `"english"`).
+  pub fn for_language(language: &str) -> Option<Self> {
+    let language = match language.to_lowercase().as_str() {
+      "ar" | "arabic" => Language::Arabic,
+      "da" | "danish" => Language::Danish,
+      "nl" | "dutch" => Language::Dutch,
+      "en" | "english" => Language::English,
+      "fi" | "finnish" => Language::Finnish,
+      "fr" | "french" => Language::French,
+      "de" | "german" => Language::German,
+      "el" | "greek" => Language::Greek,
+      "hu" | "hungarian" => Language::Hungarian,
+      "it" | "italian" => Language::Italian,
+      "no" | "norwegian" => Language::Norwegian,
+      "pt" | "portuguese" => Language::Portuguese,
+      "ro" | "romanian" => Language::Romanian,
+      "ru" | "russian" => Language::Russian,
+      "es" | "spanish" => Language::Spanish,
+      "sv" | "swedish" => Language::Swedish,
+      "ta" | "tamil" => Language::Tamil,
+      "tr" | "turkish" => Language::Turkish,
+      _ => return None,
+    };
+    Some(Self::new(language))
+  }
+
+  /// Returns the language of the stemmer.
+  pub fn language(&self) -> Language {
+    self.language
+  }
+
+  /// Reduces a (lowercase) token to its stem.
+  pub fn stem(&self, token: &str) -> String {
+    rust_stemmers::Stemmer::create(self.language)
+      .stem(token)
+      .into_owned()
+  }
+
+  /// Reduces every token of a list to its stem.
+  pub fn stem_tokens(&self, tokens: Vec<String>) -> Vec<String> {
+    let stemmer = rust_stemmers::Stemmer::create(self.language);
+    tokens
+      .into_iter()
+      .map(|token| stemmer.stem(&token).into_owned())
+      .collect()
+  }
+}
@@ -0,0 +1,103 @@
+//! Stopword lists for filtering common words out of token streams.
+//!
+//! Stopwords such as "the" or "and" carry little meaning but inflate token
+//! counts, which dilutes length-normalized scores like BM25 on short fields.
+
+use std::collections::HashSet;
+
+/// The bundled English stopword list.
+///
+/// This is the classic list used by many search engines. It is intentionally
+/// short, so that meaningful words are never removed.
+pub const ENGLISH: &[&str] = &[
+  "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+  "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these", "they",
+  "this", "to", "was", "will", "with",
+];
+
+/// A set of words to be removed from token streams.
+///
+/// Words are stored in lowercase, matching the output of the built-in
+/// tokenizer.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::stopwords::StopWords;
+///
+/// let stopwords = StopWords::english().with("rust");
+///
+/// let tokens = vec!["the".to_string(), "rust".to_string(), "book".to_string()];
+/// assert_eq!(stopwords.filter(tokens), vec!["book".to_string()]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StopWords {
+  /// The lowercase stopwords.
+  words: HashSet<String>,
+}
+
+impl StopWords {
+  /// Creates a stopword list from user-supplied words.
+  pub fn new<I, S>(words: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+  {
+    Self {
+      words: words
+        .into_iter()
+        .map(|w| w.as_ref().to_lowercase())
+        .collect(),
+    }
+  }
+
+  /// Creates the bundled English stopword list.
+  pub fn english() -> Self {
+    Self::new(ENGLISH)
+  }
+
+  /// Returns the bundled stopword list for a language, if there is one.
+  ///
+  /// Languages are identified by their ISO 639-1 code or English name
+  /// (e.g., `"en"` or `"english"`). Lists for other languages can be supplied
+  /// through [`StopWords::new`].
+  pub fn for_language(language: &str) -> Option<Self> {
+    match language.to_lowercase().as_str() {
+      "en" | "english" => Some(Self::english()),
+      _ => None,
+    }
+  }
+
+  /// Adds a word to the list in a chained manner.
+  pub fn with(mut self, word: impl AsRef<str>) -> Self {
+    self.words.insert(word.as_ref().to_lowercase());
+    self
+  }
+
+  /// Removes a word from the list in a chained manner.
+  pub fn without(mut self, word: impl AsRef<str>) -> Self {
+    self.words.remove(&word.as_ref().to_lowercase());
+    self
+  }
+
+  /// Returns `true` if the (lowercase) token is a stopword.
+  pub fn contains(&self, token: &str) -> bool {
+    self.words.contains(token)
+  }
+
+  /// Returns the number of stopwords in the list.
+  pub fn len(&self) -> usize {
+    self.words.len()
+  }
+
+  /// Returns `true` if the list is empty.
+  pub fn is_empty(&self) -> bool {
+    self.words.is_empty()
+  }
+
+  /// Removes all stopwords from a list of tokens.
+  pub fn filter(&self, mut tokens: Vec<String>) -> Vec<String> {
+    tokens.retain(|t| !self.contains(t));
+    tokens
+  }
+}
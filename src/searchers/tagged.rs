@@ -1,10 +1,13 @@
 //! A `Searcher` implementation for matching tags.
 
 use crate::context::SearchContext;
+use crate::document::{get_field_value, to_document};
 use crate::prelude::*;
+use crate::searcher::WarmState;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -23,18 +26,31 @@ impl<T: serde::Serialize + Clone> TaggedSearchable for T {}
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TagNode {
   pub tag: String,
-  /// Map of related tags to their relationship strength (0 < strength <= 1)
+  /// Map of related tags to their relationship strength (0 < strength <= 1),
+  /// or a negative strength (-1 <= strength < 0) to demote items with them
   pub relationships: HashMap<String, f32>,
 }
 
+/// Tags mapped to their relationship strength to an expanded tag.
+type Expansion = HashMap<String, f32>;
+
 /// A Tag Relationship Tree that defines hierarchical/semantic relationships between tags.
 ///
 /// TRT enables expansion of tag queries to include related tags with weighted scoring
 /// based on relationship strength and distance in the tree.
-#[derive(Debug, Clone, Default)]
+///
+/// The tree serializes as a map of tags to their related tags and strengths,
+/// which is the format read by [`from_json`](Self::from_json) and
+/// [`from_yaml`](Self::from_yaml).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct TagRelationshipTree {
   /// Internal representation: tag -> (related_tag -> strength)
   nodes: HashMap<String, HashMap<String, f32>>,
+  /// Precomputed expansions of each lowercase tag, and the depth they were
+  /// computed for.
+  #[serde(skip)]
+  cache: Option<(usize, HashMap<String, Expansion>)>,
 }
 
 impl TagRelationshipTree {
@@ -44,7 +60,402 @@ impl TagRelationshipTree {
     for node in nodes {
       tree.insert(node.tag, node.relationships);
     }
-    Self { nodes: tree }
+    Self {
+      nodes: tree,
+      cache: None,
+    }
+  }
+
+  /// Adds a one-way relationship from a tag to a related tag, replacing any
+  /// existing strength.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::searchers::tagged::TagRelationshipTree;
+  ///
+  /// let trt = TagRelationshipTree::default()
+  ///     .with_relationship("rust", "programming", 0.8)
+  ///     .with_symmetric_relationship("async", "concurrency", 0.9);
+  ///
+  /// assert!(trt.expand_tags(&["concurrency".to_string()], 1).contains_key("async"));
+  /// assert!(!trt.expand_tags(&["programming".to_string()], 1).contains_key("rust"));
+  /// ```
+  pub fn with_relationship(
+    mut self,
+    tag: impl Into<String>,
+    related_tag: impl Into<String>,
+    strength: f32,
+  ) -> Self {
+    self
+      .nodes
+      .entry(tag.into())
+      .or_default()
+      .insert(related_tag.into(), strength);
+    self.invalidate_cache();
+    self
+  }
+
+  /// Adds a relationship in both directions between two tags, with the same
+  /// strength.
+  pub fn with_symmetric_relationship(
+    self,
+    tag: impl Into<String>,
+    related_tag: impl Into<String>,
+    strength: f32,
+  ) -> Self {
+    let (tag, related_tag) = (tag.into(), related_tag.into());
+    self
+      .with_relationship(tag.clone(), related_tag.clone(), strength)
+      .with_relationship(related_tag, tag, strength)
+  }
+
+  /// Loads and validates a tree from JSON.
+  ///
+  /// The JSON is an object mapping each tag to an object of its related
+  /// tags and their relationship strengths. Strengths must be in (0, 1], or
+  /// in [-1, 0) for relationships that demote, and tags must not be empty.
+  ///
+  /// Related tags that have no relationships of their own are allowed, as
+  /// they are usually the leaves of the tree; use
+  /// [`unknown_tags`](Self::unknown_tags) to review them for typos.
+  ///
+  /// # Returns
+  ///
+  /// The tree, or a description of the first syntax or validation error.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::searchers::tagged::TagRelationshipTree;
+  ///
+  /// let trt = TagRelationshipTree::from_json(r#"{
+  ///     "rust": { "programming": 0.8, "systems": 0.9 },
+  ///     "python": { "programming": 0.8 }
+  /// }"#).unwrap();
+  ///
+  /// let expanded = trt.expand_tags(&["rust".to_string()], 1);
+  /// assert_eq!(expanded["systems"], 0.9);
+  /// assert_eq!(trt.unknown_tags(), vec!["programming", "systems"]);
+  ///
+  /// let error = TagRelationshipTree::from_json(r#"{ "rust": { "programming": 1.5 } }"#);
+  /// assert!(error.unwrap_err().contains("outside [-1, 0) and (0, 1]"));
+  /// ```
+  pub fn from_json(json: &str) -> Result<Self, String> {
+    let tree: Self =
+      serde_json::from_str(json).map_err(|e| format!("Invalid tag relationship tree: {}", e))?;
+    tree.check()?;
+    Ok(tree)
+  }
+
+  /// Loads and validates a tree from YAML, in the same format as
+  /// [`from_json`](Self::from_json). Requires the `yaml` feature.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::searchers::tagged::TagRelationshipTree;
+  ///
+  /// let trt = TagRelationshipTree::from_yaml("
+  /// rust:
+  ///   programming: 0.8
+  /// programming:
+  ///   software: 0.5
+  /// ").unwrap();
+  ///
+  /// let expanded = trt.expand_tags(&["rust".to_string()], 2);
+  /// assert_eq!(expanded["software"], 0.4);
+  /// ```
+  #[cfg(feature = "yaml")]
+  pub fn from_yaml(yaml: &str) -> Result<Self, String> {
+    let tree: Self =
+      serde_yaml::from_str(yaml).map_err(|e| format!("Invalid tag relationship tree: {}", e))?;
+    tree.check()?;
+    Ok(tree)
+  }
+
+  /// Derives a tree from how often tags occur together on items.
+  ///
+  /// Tags that often share items are related in both directions, with a
+  /// strength measured by [`CooccurrenceMeasure`]. Tags are lowercased, and
+  /// each tag is counted once per item. This lets collections without a
+  /// curated taxonomy benefit from tag expansion.
+  ///
+  /// # Arguments
+  ///
+  /// * `items` - The items to collect tags from.
+  /// * `tag_field` - The field holding the tags of each item, as an array of
+  ///   tags (strings or `{"tag": ...}` objects) or an object keyed by tag.
+  /// * `options` - The measure and thresholds used to keep relationships.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::searchers::tagged::{CooccurrenceOptions, TagRelationshipTree};
+  ///
+  /// #[derive(serde::Serialize)]
+  /// struct Post { tags: Vec<&'static str> }
+  ///
+  /// let posts = vec![
+  ///     Post { tags: vec!["rust", "systems"] },
+  ///     Post { tags: vec!["rust", "systems", "async"] },
+  ///     Post { tags: vec!["python", "async"] },
+  ///     Post { tags: vec!["python", "data"] },
+  /// ];
+  ///
+  /// let trt = TagRelationshipTree::from_corpus(&posts, "tags", CooccurrenceOptions::new());
+  /// let expanded = trt.expand_tags(&["rust".to_string()], 1);
+  ///
+  /// // "rust" and "systems" always occur together.
+  /// assert_eq!(expanded["systems"], 1.0);
+  /// // Pairs seen on a single item are dropped by default.
+  /// assert!(!expanded.contains_key("async"));
+  /// ```
+  pub fn from_corpus<T: Serialize>(
+    items: &[T],
+    tag_field: &str,
+    options: CooccurrenceOptions,
+  ) -> Self {
+    let mut tag_counts: HashMap<String, usize> = HashMap::new();
+    let mut pair_counts: HashMap<(String, String), usize> = HashMap::new();
+    let mut tagged_items = 0;
+
+    for item in items {
+      let Ok(value) = to_document(item) else {
+        continue;
+      };
+      let mut tags: Vec<String> = match get_field_value(&value, tag_field) {
+        Some(Value::Array(arr)) => arr
+          .iter()
+          .filter_map(weighted_tag)
+          .map(|(tag, _)| tag.trim().to_lowercase())
+          .collect(),
+        Some(Value::Object(map)) => map.keys().map(|tag| tag.trim().to_lowercase()).collect(),
+        _ => continue,
+      };
+      tags.retain(|tag| !tag.is_empty());
+      tags.sort_unstable();
+      tags.dedup();
+      if tags.is_empty() {
+        continue;
+      }
+
+      tagged_items += 1;
+      for (i, tag) in tags.iter().enumerate() {
+        *tag_counts.entry(tag.clone()).or_insert(0) += 1;
+        for other in &tags[i + 1..] {
+          *pair_counts.entry((tag.clone(), other.clone())).or_insert(0) += 1;
+        }
+      }
+    }
+
+    let mut nodes: HashMap<String, HashMap<String, f32>> = HashMap::new();
+    for ((a, b), count) in pair_counts {
+      if count < options.min_count {
+        continue;
+      }
+      let strength = options
+        .measure
+        .strength(count, tag_counts[&a], tag_counts[&b], tagged_items);
+      if strength <= 0.0 || strength < options.min_strength {
+        continue;
+      }
+      nodes
+        .entry(a.clone())
+        .or_default()
+        .insert(b.clone(), strength);
+      nodes.entry(b).or_default().insert(a, strength);
+    }
+
+    if let Some(max_related) = options.max_related {
+      for relationships in nodes.values_mut() {
+        let mut strongest: Vec<(String, f32)> = relationships.drain().collect();
+        strongest.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        strongest.truncate(max_related);
+        relationships.extend(strongest);
+      }
+    }
+
+    Self { nodes, cache: None }
+  }
+
+  /// Returns the relationships of the tree as `(tag, related_tag, strength)`
+  /// edges, sorted by tag and then related tag.
+  pub fn edges(&self) -> Vec<(&str, &str, f32)> {
+    let mut edges: Vec<(&str, &str, f32)> = self
+      .nodes
+      .iter()
+      .flat_map(|(tag, relationships)| {
+        relationships
+          .iter()
+          .map(move |(related_tag, &strength)| (tag.as_str(), related_tag.as_str(), strength))
+      })
+      .collect();
+    edges.sort_by(|a, b| a.0.cmp(b.0).then_with(|| a.1.cmp(b.1)));
+    edges
+  }
+
+  /// Renders the tree in the Graphviz DOT format, for review.
+  ///
+  /// Each relationship is an edge labeled with its strength, drawn thicker
+  /// for stronger relationships, and dashed red for negative ones. Render it
+  /// with `dot -Tsvg trt.dot`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::searchers::tagged::TagRelationshipTree;
+  ///
+  /// let trt = TagRelationshipTree::default()
+  ///     .with_relationship("rust", "programming", 0.8)
+  ///     .with_relationship("python", "programming", 0.5);
+  ///
+  /// assert_eq!(
+  ///     trt.to_dot(),
+  ///     "digraph trt {\n  \
+  ///        \"python\" -> \"programming\" [label=\"0.5\", penwidth=2.0];\n  \
+  ///        \"rust\" -> \"programming\" [label=\"0.8\", penwidth=2.6];\n\
+  ///      }\n"
+  /// );
+  /// ```
+  pub fn to_dot(&self) -> String {
+    let mut dot = String::from("digraph trt {\n");
+    for (tag, related_tag, strength) in self.edges() {
+      dot.push_str(&format!(
+        "  {} -> {} [label=\"{}\", penwidth={:.1}{}];\n",
+        dot_id(tag),
+        dot_id(related_tag),
+        strength,
+        1.0 + strength.abs().min(1.0) * 2.0,
+        if strength < 0.0 {
+          ", style=dashed, color=red"
+        } else {
+          ""
+        }
+      ));
+    }
+    dot.push_str("}\n");
+    dot
+  }
+
+  /// Returns the related tags that have no relationships of their own,
+  /// sorted alphabetically.
+  ///
+  /// These are either leaves of the tree or misspelled tags, which expansion
+  /// would silently stop at.
+  pub fn unknown_tags(&self) -> Vec<&str> {
+    let mut unknown: Vec<&str> = self
+      .nodes
+      .values()
+      .flat_map(|relationships| relationships.keys())
+      .filter(|tag| !self.nodes.contains_key(*tag))
+      .map(String::as_str)
+      .collect::<HashSet<_>>()
+      .into_iter()
+      .collect();
+    unknown.sort_unstable();
+    unknown
+  }
+
+  /// Reviews the tree for likely mistakes.
+  ///
+  /// Strengths of 0 or outside [-1, 1] and self-loops are errors (see
+  /// [`TagTreeIssue::is_error`]). Asymmetric and dangling relationships are
+  /// reported for review, as they are often intended: a tag usually implies
+  /// its parent more strongly than the parent implies it.
+  ///
+  /// # Returns
+  ///
+  /// The issues found, errors first, sorted by tag. An empty list means the
+  /// tree has nothing to review.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::searchers::tagged::{TagRelationshipTree, TagTreeIssue};
+  ///
+  /// let trt = TagRelationshipTree::default()
+  ///     .with_symmetric_relationship("rust", "systems", 0.9)
+  ///     .with_relationship("rust", "rust", 1.0)
+  ///     .with_relationship("systems", "rust", 0.7);
+  ///
+  /// let issues = trt.validate();
+  /// assert_eq!(issues.len(), 2);
+  /// assert_eq!(issues[0], TagTreeIssue::SelfLoop { tag: "rust".into() });
+  /// assert!(issues[0].is_error());
+  /// assert_eq!(
+  ///     issues[1].to_string(),
+  ///     "\"rust\" -> \"systems\" has strength 0.9, but \"systems\" -> \"rust\" has 0.7"
+  /// );
+  /// ```
+  pub fn validate(&self) -> Vec<TagTreeIssue> {
+    let mut issues = Vec::new();
+    for (tag, relationships) in &self.nodes {
+      for (related_tag, &strength) in relationships {
+        if !valid_strength(strength) {
+          issues.push(TagTreeIssue::InvalidStrength {
+            tag: tag.clone(),
+            related_tag: related_tag.clone(),
+            strength,
+          });
+        }
+        if tag == related_tag {
+          issues.push(TagTreeIssue::SelfLoop { tag: tag.clone() });
+          continue;
+        }
+
+        let Some(reverse) = self.nodes.get(related_tag) else {
+          // Reported once per tag as dangling below.
+          continue;
+        };
+        let reverse_strength = reverse.get(tag).copied();
+        // Pairs with both directions are reported once.
+        if reverse_strength.is_none() || (reverse_strength != Some(strength) && tag < related_tag) {
+          issues.push(TagTreeIssue::Asymmetric {
+            tag: tag.clone(),
+            related_tag: related_tag.clone(),
+            strength,
+            reverse_strength,
+          });
+        }
+      }
+    }
+    for tag in self.unknown_tags() {
+      issues.push(TagTreeIssue::Dangling {
+        tag: tag.to_string(),
+      });
+    }
+
+    issues.sort_by(|a, b| {
+      b.is_error()
+        .cmp(&a.is_error())
+        .then_with(|| a.tags().cmp(&b.tags()))
+    });
+    issues
+  }
+
+  /// Checks that tags are not empty and strengths are in [-1, 0) or (0, 1].
+  fn check(&self) -> Result<(), String> {
+    for (tag, relationships) in &self.nodes {
+      if tag.is_empty() {
+        return Err("Tags must not be empty".to_string());
+      }
+      for (related_tag, &strength) in relationships {
+        if related_tag.is_empty() {
+          return Err(format!(
+            "Tag \"{}\" has a relationship to an empty tag",
+            tag
+          ));
+        }
+        if !valid_strength(strength) {
+          return Err(format!(
+            "Relationship from \"{}\" to \"{}\" has strength {}, outside [-1, 0) and (0, 1]",
+            tag, related_tag, strength
+          ));
+        }
+      }
+    }
+    Ok(())
   }
 
   /// Expands query tags using the relationship tree up to a specified depth.
@@ -53,6 +464,11 @@ impl TagRelationshipTree {
   /// The strength is calculated as the product of all edge strengths along the path
   /// from the original query tag.
   ///
+  /// A negative relationship yields a negative strength, which demotes
+  /// items with the related tag instead of matching them. Expansion does not
+  /// continue past negative relationships, and a tag that is both related
+  /// and demoted keeps its positive strength.
+  ///
   /// # Arguments
   ///
   /// * `query_tags` - The original tags to expand
@@ -60,7 +476,7 @@ impl TagRelationshipTree {
   ///
   /// # Returns
   ///
-  /// HashMap mapping expanded tags to their relationship strengths (0 < strength <= 1)
+  /// HashMap mapping expanded tags to their relationship strengths (-1 <= strength <= 1)
   pub fn expand_tags(&self, query_tags: &[String], max_depth: usize) -> HashMap<String, f32> {
     let mut expanded = HashMap::new();
 
@@ -73,43 +489,501 @@ impl TagRelationshipTree {
       return expanded;
     }
 
-    // BFS traversal for each query tag
     for query_tag in query_tags {
       let query_tag_lower = query_tag.to_lowercase();
-      let mut queue = VecDeque::new();
-      let mut visited = HashSet::new();
+      match &self.cache {
+        Some((depth, cache)) if *depth == max_depth => {
+          // Tags without relationships expand to themselves only.
+          for (tag, &strength) in cache.get(&query_tag_lower).into_iter().flatten() {
+            expanded
+              .entry(tag.clone())
+              .and_modify(|e| merge_strength(e, strength))
+              .or_insert(strength);
+          }
+        }
+        _ => self.expand_tag(query_tag_lower, max_depth, &mut expanded),
+      }
+    }
 
-      queue.push_back((query_tag_lower.clone(), 0, 1.0)); // (tag, depth, strength)
-      visited.insert(query_tag_lower.clone());
+    expanded
+  }
 
-      while let Some((current_tag, depth, current_strength)) = queue.pop_front() {
-        if depth >= max_depth {
-          continue;
+  /// Precomputes the expansion of every tag of the tree up to a depth, so
+  /// that [`expand_tags`](Self::expand_tags) looks them up instead of
+  /// traversing the tree on every query.
+  ///
+  /// Expansions to other depths are still computed on demand. The cache is
+  /// cleared when relationships are added with
+  /// [`with_relationship`](Self::with_relationship), and it is not
+  /// serialized.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::searchers::tagged::TagRelationshipTree;
+  ///
+  /// let mut trt = TagRelationshipTree::default()
+  ///     .with_relationship("tokio", "async", 0.9)
+  ///     .with_relationship("async", "concurrency", 0.8);
+  /// let uncached = trt.expand_tags(&["tokio".to_string()], 3);
+  ///
+  /// trt.precompute(3);
+  /// assert!(trt.is_precomputed(3));
+  /// assert_eq!(trt.expand_tags(&["tokio".to_string()], 3), uncached);
+  /// ```
+  pub fn precompute(&mut self, max_depth: usize) {
+    let cache = self
+      .nodes
+      .keys()
+      .map(|tag| {
+        let tag = tag.to_lowercase();
+        let mut expanded = HashMap::from([(tag.clone(), 1.0)]);
+        self.expand_tag(tag.clone(), max_depth, &mut expanded);
+        (tag, expanded)
+      })
+      .collect();
+    self.cache = Some((max_depth, cache));
+  }
+
+  /// Expands every tag of the tree at each depth, up to the depth past which
+  /// its expansion no longer changes.
+  ///
+  /// The expansion of a lowercase tag to depth `d` is at index `d - 1` of
+  /// its expansions, and the last one holds for any greater depth.
+  fn expansions_by_depth(&self) -> HashMap<String, Vec<Expansion>> {
+    self
+      .nodes
+      .keys()
+      .map(|tag| {
+        let tag = tag.to_lowercase();
+        let mut expansions: Vec<Expansion> = Vec::new();
+        for depth in 1.. {
+          let mut expanded = HashMap::from([(tag.clone(), 1.0)]);
+          self.expand_tag(tag.clone(), depth, &mut expanded);
+          // Once a depth reaches no new tag, deeper traversals stop there too.
+          if expansions.last() == Some(&expanded) {
+            break;
+          }
+          expansions.push(expanded);
         }
+        (tag, expansions)
+      })
+      .collect()
+  }
+
+  /// Expands query tags as [`expand_tags`](Self::expand_tags) does, looking
+  /// the expansions up in those of
+  /// [`expansions_by_depth`](Self::expansions_by_depth).
+  fn expand_tags_by_depth(
+    expansions: &HashMap<String, Vec<Expansion>>,
+    query_tags: &[String],
+    max_depth: usize,
+  ) -> HashMap<String, f32> {
+    let mut expanded: HashMap<String, f32> = query_tags
+      .iter()
+      .map(|tag| (tag.to_lowercase(), 1.0))
+      .collect();
+    if max_depth == 0 {
+      return expanded;
+    }
 
-        // Find relationships for current tag
-        if let Some(relationships) = self.nodes.get(&current_tag) {
-          for (related_tag, edge_strength) in relationships {
-            let related_tag_lower = related_tag.to_lowercase();
-            let new_strength = current_strength * edge_strength;
+    for query_tag in query_tags {
+      let Some(by_depth) = expansions.get(&query_tag.to_lowercase()) else {
+        continue;
+      };
+      for (tag, &strength) in &by_depth[max_depth.min(by_depth.len()) - 1] {
+        expanded
+          .entry(tag.clone())
+          .and_modify(|e| merge_strength(e, strength))
+          .or_insert(strength);
+      }
+    }
 
-            // Update or insert the expanded tag with maximum strength found
-            expanded
-              .entry(related_tag_lower.clone())
-              .and_modify(|e| *e = e.max(new_strength))
-              .or_insert(new_strength);
-
-            // Continue BFS if not visited at this depth
-            if !visited.contains(&related_tag_lower) {
-              visited.insert(related_tag_lower.clone());
-              queue.push_back((related_tag_lower, depth + 1, new_strength));
-            }
+    expanded
+  }
+
+  /// Returns `true` if expansions up to `max_depth` are precomputed.
+  pub fn is_precomputed(&self, max_depth: usize) -> bool {
+    matches!(&self.cache, Some((depth, _)) if *depth == max_depth)
+  }
+
+  /// Clears the precomputed expansions, if any.
+  ///
+  /// Call [`precompute`](Self::precompute) again to rebuild them after the
+  /// tree changed.
+  pub fn invalidate_cache(&mut self) {
+    self.cache = None;
+  }
+
+  /// Adds the tags reachable from a lowercase tag within `max_depth` steps
+  /// to `expanded`, keeping the strongest path to each tag.
+  ///
+  /// Tags reached through a negative relationship are recorded, but not
+  /// expanded further.
+  fn expand_tag(&self, tag: String, max_depth: usize, expanded: &mut HashMap<String, f32>) {
+    // BFS traversal from the tag
+    let mut queue = VecDeque::new();
+    let mut visited = HashSet::new();
+
+    visited.insert(tag.clone());
+    queue.push_back((tag, 0, 1.0)); // (tag, depth, strength)
+
+    while let Some((current_tag, depth, current_strength)) = queue.pop_front() {
+      if depth >= max_depth {
+        continue;
+      }
+
+      // Find relationships for current tag
+      if let Some(relationships) = self.nodes.get(&current_tag) {
+        for (related_tag, edge_strength) in relationships {
+          let related_tag_lower = related_tag.to_lowercase();
+          let new_strength = current_strength * edge_strength;
+
+          // Update or insert the expanded tag with maximum strength found
+          expanded
+            .entry(related_tag_lower.clone())
+            .and_modify(|e| merge_strength(e, new_strength))
+            .or_insert(new_strength);
+
+          // Continue BFS if not visited at this depth
+          if new_strength > 0.0 && !visited.contains(&related_tag_lower) {
+            visited.insert(related_tag_lower.clone());
+            queue.push_back((related_tag_lower, depth + 1, new_strength));
           }
         }
       }
     }
+  }
+}
 
-    expanded
+/// A likely mistake in a Tag Relationship Tree, found by
+/// [`TagRelationshipTree::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagTreeIssue {
+  /// A relationship strength is 0 or outside [-1, 1].
+  InvalidStrength {
+    tag: String,
+    related_tag: String,
+    strength: f32,
+  },
+  /// A tag is related to itself.
+  SelfLoop { tag: String },
+  /// A relationship has no reverse relationship, or one with a different
+  /// strength.
+  Asymmetric {
+    tag: String,
+    related_tag: String,
+    strength: f32,
+    reverse_strength: Option<f32>,
+  },
+  /// A related tag has no relationships of its own, so expansion stops at
+  /// it. Leaf tags are dangling by design; misspelled tags are too.
+  Dangling { tag: String },
+}
+
+impl TagTreeIssue {
+  /// Returns `true` for issues that make expansion misbehave, rather than
+  /// ones that may be intended.
+  pub fn is_error(&self) -> bool {
+    matches!(
+      self,
+      TagTreeIssue::InvalidStrength { .. } | TagTreeIssue::SelfLoop { .. }
+    )
+  }
+
+  /// Returns the tags the issue is about, for sorting.
+  fn tags(&self) -> (&str, &str) {
+    match self {
+      TagTreeIssue::InvalidStrength {
+        tag, related_tag, ..
+      }
+      | TagTreeIssue::Asymmetric {
+        tag, related_tag, ..
+      } => (tag, related_tag),
+      TagTreeIssue::SelfLoop { tag } | TagTreeIssue::Dangling { tag } => (tag, ""),
+    }
+  }
+}
+
+impl fmt::Display for TagTreeIssue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      TagTreeIssue::InvalidStrength {
+        tag,
+        related_tag,
+        strength,
+      } => write!(
+        f,
+        "\"{}\" -> \"{}\" has strength {}, outside [-1, 0) and (0, 1]",
+        tag, related_tag, strength
+      ),
+      TagTreeIssue::SelfLoop { tag } => write!(f, "\"{}\" is related to itself", tag),
+      TagTreeIssue::Asymmetric {
+        tag,
+        related_tag,
+        strength,
+        reverse_strength: Some(reverse_strength),
+      } => write!(
+        f,
+        "\"{}\" -> \"{}\" has strength {}, but \"{}\" -> \"{}\" has {}",
+        tag, related_tag, strength, related_tag, tag, reverse_strength
+      ),
+      TagTreeIssue::Asymmetric {
+        tag,
+        related_tag,
+        strength,
+        reverse_strength: None,
+      } => write!(
+        f,
+        "\"{}\" -> \"{}\" has strength {}, but there is no reverse relationship",
+        tag, related_tag, strength
+      ),
+      TagTreeIssue::Dangling { tag } => write!(f, "\"{}\" has no relationships of its own", tag),
+    }
+  }
+}
+
+/// The statistic used to turn tag co-occurrences into relationship strengths
+/// in [`TagRelationshipTree::from_corpus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CooccurrenceMeasure {
+  /// The number of items with both tags divided by the number of items with
+  /// either tag.
+  #[default]
+  Jaccard,
+  /// Normalized pointwise mutual information: how much more often two tags
+  /// occur together than if they were independent, scaled to at most 1.
+  /// Pairs that occur together no more often than by chance are dropped.
+  Pmi,
+}
+
+impl CooccurrenceMeasure {
+  /// Computes the strength of the relationship between two tags.
+  ///
+  /// # Arguments
+  ///
+  /// * `both` - The number of items with both tags.
+  /// * `a` - The number of items with the first tag.
+  /// * `b` - The number of items with the second tag.
+  /// * `total` - The number of items with any tag.
+  fn strength(self, both: usize, a: usize, b: usize, total: usize) -> f32 {
+    match self {
+      CooccurrenceMeasure::Jaccard => both as f32 / (a + b - both) as f32,
+      CooccurrenceMeasure::Pmi => {
+        let p_both = both as f64 / total as f64;
+        if p_both >= 1.0 {
+          return 1.0;
+        }
+        let p_a = a as f64 / total as f64;
+        let p_b = b as f64 / total as f64;
+        ((p_both / (p_a * p_b)).ln() / -p_both.ln()) as f32
+      }
+    }
+  }
+}
+
+/// Options for deriving a Tag Relationship Tree from tag co-occurrences.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::tagged::{CooccurrenceMeasure, CooccurrenceOptions};
+///
+/// let options = CooccurrenceOptions::new()
+///     .measure(CooccurrenceMeasure::Pmi)
+///     .min_count(5)
+///     .min_strength(0.2)
+///     .max_related(10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CooccurrenceOptions {
+  /// The statistic used for relationship strengths.
+  pub measure: CooccurrenceMeasure,
+  /// The minimum number of items two tags must share to be related.
+  pub min_count: usize,
+  /// The minimum strength of a relationship.
+  pub min_strength: f32,
+  /// The maximum number of related tags kept per tag, strongest first.
+  pub max_related: Option<usize>,
+}
+
+impl Default for CooccurrenceOptions {
+  fn default() -> Self {
+    Self {
+      measure: CooccurrenceMeasure::Jaccard,
+      min_count: 2,
+      min_strength: 0.0,
+      max_related: None,
+    }
+  }
+}
+
+impl CooccurrenceOptions {
+  /// Creates options using Jaccard similarity, relating tags that share at
+  /// least two items.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the statistic used for relationship strengths.
+  pub fn measure(mut self, measure: CooccurrenceMeasure) -> Self {
+    self.measure = measure;
+    self
+  }
+
+  /// Sets the minimum number of items two tags must share to be related.
+  pub fn min_count(mut self, min_count: usize) -> Self {
+    self.min_count = min_count.max(1);
+    self
+  }
+
+  /// Sets the minimum strength of a relationship.
+  pub fn min_strength(mut self, min_strength: f32) -> Self {
+    self.min_strength = min_strength;
+    self
+  }
+
+  /// Keeps only the strongest relationships of each tag.
+  pub fn max_related(mut self, max_related: usize) -> Self {
+    self.max_related = Some(max_related);
+    self
+  }
+}
+
+/// Normalizes tags before they are matched, so that superficial variations
+/// of a tag don't fragment matching.
+///
+/// Tags are always trimmed and lowercased. With slugs enabled, each run of
+/// whitespace and punctuation is also folded into a single `-`, so that
+/// `Machine Learning`, `machine_learning` and `machine-learning` are the same
+/// tag. Aliases then map the normalized tag to a canonical one, such as `js`
+/// to `javascript`.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::tagged::TagNormalizer;
+///
+/// let normalizer = TagNormalizer::new()
+///     .with_slugs()
+///     .with_alias("js", "JavaScript")
+///     .with_alias("ML", "machine learning");
+///
+/// assert_eq!(normalizer.normalize("  Machine_Learning! "), "machine-learning");
+/// assert_eq!(normalizer.normalize("ml"), "machine-learning");
+/// assert_eq!(normalizer.normalize("JS"), "javascript");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TagNormalizer {
+  /// Whether runs of whitespace and punctuation are folded into `-`.
+  slugs: bool,
+  /// A character kept as is when folding, such as the separator of path tags.
+  keep: Option<char>,
+  /// The aliases and their canonical tags, as given.
+  aliases: Vec<(String, String)>,
+  /// The normalized aliases mapped to their normalized canonical tags.
+  resolved: HashMap<String, String>,
+}
+
+impl TagNormalizer {
+  /// Creates a normalizer that trims and lowercases tags.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Folds each run of whitespace and punctuation into a single `-`, and
+  /// removes it from the start and end of tags.
+  pub fn with_slugs(mut self) -> Self {
+    self.slugs = true;
+    self.resolve();
+    self
+  }
+
+  /// Maps an alias to its canonical tag. Both are normalized, and aliases
+  /// are not chained: the canonical tag of an alias is never looked up.
+  ///
+  /// # Arguments
+  ///
+  /// * `alias` - The tag to replace, such as `js`.
+  /// * `tag` - The tag to replace it with, such as `javascript`.
+  pub fn with_alias(mut self, alias: impl Into<String>, tag: impl Into<String>) -> Self {
+    self.aliases.push((alias.into(), tag.into()));
+    self.resolve();
+    self
+  }
+
+  /// Maps several aliases to their canonical tags.
+  ///
+  /// See [`TagNormalizer::with_alias`].
+  pub fn with_aliases<I, A, S>(mut self, aliases: I) -> Self
+  where
+    I: IntoIterator<Item = (A, S)>,
+    A: Into<String>,
+    S: Into<String>,
+  {
+    self.aliases.extend(
+      aliases
+        .into_iter()
+        .map(|(alias, tag)| (alias.into(), tag.into())),
+    );
+    self.resolve();
+    self
+  }
+
+  /// Normalizes a tag.
+  ///
+  /// # Returns
+  ///
+  /// The canonical form of the tag, which may be empty if the tag only had
+  /// whitespace or punctuation.
+  pub fn normalize(&self, tag: &str) -> String {
+    let folded = self.fold(tag);
+    match self.resolved.get(&folded) {
+      Some(canonical) => canonical.clone(),
+      None => folded,
+    }
+  }
+
+  /// Keeps a character when folding slugs, so that the levels of path tags
+  /// stay apart.
+  fn keeping(mut self, keep: Option<char>) -> Self {
+    self.keep = keep;
+    self.resolve();
+    self
+  }
+
+  /// Lowercases and trims a tag, and folds it into a slug if enabled.
+  fn fold(&self, tag: &str) -> String {
+    let lower = tag.trim().to_lowercase();
+    if !self.slugs {
+      return lower;
+    }
+
+    let mut slug = String::with_capacity(lower.len());
+    let mut pending_dash = false;
+    for c in lower.chars() {
+      if c.is_alphanumeric() || Some(c) == self.keep {
+        // Dashes don't pile up against kept characters.
+        let kept = Some(c) == self.keep || slug.ends_with(|last| Some(last) == self.keep);
+        if pending_dash && !slug.is_empty() && !kept {
+          slug.push('-');
+        }
+        pending_dash = false;
+        slug.push(c);
+      } else {
+        pending_dash = true;
+      }
+    }
+    slug
+  }
+
+  /// Normalizes the aliases again after the folding rules changed.
+  fn resolve(&mut self) {
+    self.resolved = self
+      .aliases
+      .iter()
+      .map(|(alias, tag)| (self.fold(alias), self.fold(tag)))
+      .collect();
   }
 }
 
@@ -132,20 +1006,45 @@ impl TagRelationshipTree {
 ///
 /// // Or specify a custom field
 /// let custom_searcher = TaggedSearch::with_field("categories");
+///
+/// // Or read several weighted fields
+/// let weighted_searcher = TaggedSearch::with_field("categories")
+///     .with_tag_field("categories", 2.0)
+///     .with_tag_field("keywords", 1.0);
 /// ```
 pub struct TaggedSearch {
-  /// The name of the field that contains the tags in the item.
-  tag_field: String,
+  /// The names of the fields that contain the tags in the item, with their
+  /// weights.
+  tag_fields: Vec<(String, f32)>,
   /// Optional Tag Relationship Tree for semantic tag expansion
   trt: Option<TagRelationshipTree>,
+  /// Whether items must have all query tags or any of them, unless the
+  /// query overrides it.
+  mode: TagMode,
+  /// The separator of hierarchical path tags, if query tags should match
+  /// the descendants of their path.
+  path_separator: Option<char>,
+  /// The factor applied to the strength of a match for each level between
+  /// a query tag and a descendant item tag.
+  path_decay: f32,
+  /// The normalizer applied to item, query, and excluded tags.
+  normalizer: TagNormalizer,
+  /// The item tags, tag postings, and tag expansions precomputed by
+  /// `warm_up`.
+  warm: WarmState<WarmTags>,
 }
 
 impl TaggedSearch {
   /// Creates a new `TaggedSearch` instance with the default tag field ("tags").
   pub fn new() -> Self {
     Self {
-      tag_field: "tags".to_string(),
+      tag_fields: vec![("tags".to_string(), 1.0)],
       trt: None,
+      mode: TagMode::Any,
+      path_separator: None,
+      path_decay: 1.0,
+      normalizer: TagNormalizer::new(),
+      warm: WarmState::new(),
     }
   }
 
@@ -156,11 +1055,56 @@ impl TaggedSearch {
   /// * `tag_field` - The name of the field to extract tags from.
   pub fn with_field(tag_field: impl Into<String>) -> Self {
     Self {
-      tag_field: tag_field.into(),
-      trt: None,
+      tag_fields: vec![(tag_field.into(), 1.0)],
+      ..Self::new()
     }
   }
 
+  /// Adds a field to extract tags from, or changes the weight of a field.
+  ///
+  /// The strength of each matched tag is multiplied by the weight of the
+  /// field it occurs in. A tag present in several fields is only counted
+  /// once, in the field with the highest weight. The contribution of each
+  /// field is reported in [`SearusMatch::field_scores`].
+  ///
+  /// # Arguments
+  ///
+  /// * `field` - The name of the field to extract tags from.
+  /// * `weight` - The weight of the field's tags.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::TaggedSearch;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Post { title: String, categories: Vec<String>, keywords: Vec<String> }
+  ///
+  /// let posts = vec![
+  ///     Post { title: "Ownership".into(), categories: vec![], keywords: vec!["rust".into()] },
+  ///     Post { title: "The Book".into(), categories: vec!["rust".into()], keywords: vec![] },
+  /// ];
+  ///
+  /// let searcher = TaggedSearch::with_field("categories")
+  ///     .with_tag_field("categories", 2.0)
+  ///     .with_tag_field("keywords", 1.0);
+  /// let engine = SearusEngine::builder().with(Box::new(searcher)).build();
+  ///
+  /// let results = engine.search(&posts, &Query::builder().tags(vec!["rust".into()]).build());
+  /// assert_eq!(results[0].item.title, "The Book");
+  /// assert!(results[0].field_scores.contains_key("categories"));
+  /// assert!(results[1].field_scores.contains_key("keywords"));
+  /// ```
+  pub fn with_tag_field(mut self, field: impl Into<String>, weight: f32) -> Self {
+    let field = field.into();
+    match self.tag_fields.iter_mut().find(|(name, _)| *name == field) {
+      Some((_, existing)) => *existing = weight,
+      None => self.tag_fields.push((field, weight)),
+    }
+    self
+  }
+
   /// Adds a Tag Relationship Tree to enable hierarchical tag expansion.
   ///
   /// # Arguments
@@ -190,34 +1134,322 @@ impl TaggedSearch {
     self
   }
 
-  /// Extracts a list of tags from a specified field in a serializable item.
+  /// Sets whether items must have all query tags or any of them.
+  ///
+  /// Queries can override the mode with [`SearchOptions::tag_mode`]. In
+  /// [`TagMode::All`], a query tag is also satisfied by a related tag when
+  /// Tag Relationship Tree expansion is enabled, and matching items are
+  /// scored as in [`TagMode::Any`].
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::TaggedSearch;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Post { title: String, tags: Vec<String> }
+  ///
+  /// let posts = vec![
+  ///     Post { title: "Async Rust".into(), tags: vec!["rust".into(), "async".into()] },
+  ///     Post { title: "Rust basics".into(), tags: vec!["rust".into()] },
+  /// ];
+  ///
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(TaggedSearch::new().with_mode(TagMode::All)))
+  ///     .build();
+  ///
+  /// let query = Query::builder().tags(vec!["rust".into(), "async".into()]).build();
+  /// let results = engine.search(&posts, &query);
+  /// assert_eq!(results.len(), 1);
+  /// assert_eq!(results[0].item.title, "Async Rust");
+  ///
+  /// // The query can switch back to matching any tag.
+  /// let query = Query::builder()
+  ///     .tags(vec!["rust".into(), "async".into()])
+  ///     .options(SearchOptions::default().tag_mode(TagMode::Any))
+  ///     .build();
+  /// assert_eq!(engine.search(&posts, &query).len(), 2);
+  /// ```
+  pub fn with_mode(mut self, mode: TagMode) -> Self {
+    self.mode = mode;
+    self
+  }
+
+  /// Enables hierarchical path tags, such as `lang/rust/async`.
+  ///
+  /// A query tag then also matches the item tags below it in the hierarchy:
+  /// `lang/rust` matches `lang/rust/async` and `lang/rust/async/tokio`, but
+  /// not `lang/rustic`. The strength of a match is multiplied by `decay` for
+  /// each level between the query tag and the item tag, so closer tags score
+  /// higher.
+  ///
+  /// # Arguments
+  ///
+  /// * `separator` - The character separating the levels of a path.
+  /// * `decay` - The factor applied per level, from 0.0 to 1.0.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::TaggedSearch;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Post { title: String, tags: Vec<String> }
+  ///
+  /// let posts = vec![
+  ///     Post { title: "Rust".into(), tags: vec!["lang/rust".into()] },
+  ///     Post { title: "Tokio".into(), tags: vec!["lang/rust/async".into()] },
+  ///     Post { title: "Go".into(), tags: vec!["lang/go".into()] },
+  /// ];
+  ///
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(TaggedSearch::new().with_path_tags('/', 0.5)))
+  ///     .build();
+  ///
+  /// let results = engine.search(&posts, &Query::builder().tags(vec!["lang/rust".into()]).build());
+  /// assert_eq!(results.len(), 2);
+  /// assert_eq!(results[0].item.title, "Rust");
+  /// assert_eq!(results[1].field_scores["tags"], 0.5);
+  /// ```
+  pub fn with_path_tags(mut self, separator: char, decay: f32) -> Self {
+    self.path_separator = Some(separator);
+    self.path_decay = decay;
+    self.normalizer = self.normalizer.keeping(Some(separator));
+    self
+  }
+
+  /// Sets the normalizer applied to item tags, query tags, and excluded tags
+  /// before matching. The default one trims and lowercases tags.
+  ///
+  /// The tags of a Tag Relationship Tree should be written in their
+  /// normalized form; the tags it expands to are normalized as well. With
+  /// path tags, the separator is kept when folding slugs.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::TaggedSearch;
+  /// use searus::searchers::tagged::TagNormalizer;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Post { title: String, tags: Vec<String> }
+  ///
+  /// let posts = vec![
+  ///     Post { title: "Closures".into(), tags: vec!["JavaScript".into()] },
+  ///     Post { title: "Backprop".into(), tags: vec!["Machine Learning".into()] },
+  /// ];
+  ///
+  /// let normalizer = TagNormalizer::new().with_slugs().with_alias("js", "javascript");
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(TaggedSearch::new().with_normalizer(normalizer)))
+  ///     .build();
+  ///
+  /// let results = engine.search(&posts, &Query::builder().tags(vec!["JS".into()]).build());
+  /// assert_eq!(results[0].item.title, "Closures");
+  ///
+  /// let query = Query::builder().tags(vec!["machine-learning".into()]).build();
+  /// assert_eq!(engine.search(&posts, &query)[0].item.title, "Backprop");
+  /// ```
+  pub fn with_normalizer(mut self, normalizer: TagNormalizer) -> Self {
+    self.normalizer = normalizer.keeping(self.path_separator);
+    self
+  }
+
+  /// Returns the strength with which an item tag matches expanded query
+  /// tags: directly, or through one of its ancestors with path tags.
+  fn tag_strength(&self, expanded_tags: &HashMap<String, f32>, tag: &str) -> Option<f32> {
+    let mut strength = expanded_tags.get(tag).copied();
+    let Some(separator) = self.path_separator else {
+      return strength;
+    };
+
+    let mut factor = 1.0;
+    for (end, _) in tag.rmatch_indices(separator) {
+      factor *= self.path_decay;
+      if let Some(&ancestor) = expanded_tags.get(&tag[..end]) {
+        let decayed = ancestor * factor;
+        strength = Some(match strength {
+          Some(mut s) => {
+            merge_strength(&mut s, decayed);
+            s
+          }
+          None => decayed,
+        });
+      }
+    }
+    strength
+  }
+
+  /// Expands query tags with the Tag Relationship Tree, if it is enabled
+  /// for the query.
+  ///
+  /// # Returns
+  ///
+  /// The normalized tags mapped to their relationship strength, 1.0 for the
+  /// query tags themselves.
+  fn expand(
+    &self,
+    query_tags: &[String],
+    query: &Query,
+    warm: Option<&WarmTags>,
+  ) -> HashMap<String, f32> {
+    let query_tags: Vec<String> = query_tags
+      .iter()
+      .map(|tag| self.normalizer.normalize(tag))
+      .collect();
+    match (&self.trt, query.options.trt_depth) {
+      (Some(trt), Some(depth)) if depth > 0 => {
+        let expansion = match warm {
+          Some(warm) => {
+            TagRelationshipTree::expand_tags_by_depth(&warm.expansions, &query_tags, depth)
+          }
+          None => trt.expand_tags(&query_tags, depth),
+        };
+        let mut expanded = HashMap::new();
+        for (tag, strength) in expansion {
+          expanded
+            .entry(self.normalizer.normalize(&tag))
+            .and_modify(|e| merge_strength(e, strength))
+            .or_insert(strength);
+        }
+        expanded
+      }
+      // No expansion, just original tags at strength 1.0
+      _ => query_tags.into_iter().map(|t| (t, 1.0)).collect(),
+    }
+  }
+
+  /// Extracts the tags of an item from the configured fields.
   ///
   /// This helper function serializes the item to a `serde_json::Value` and
-  /// expects the specified field to contain an array of strings.
-  fn extract_tags<T>(item: &T, field: &str) -> Vec<String>
+  /// expects each field to contain either an array of tags, where each tag
+  /// is a string or a `{"tag": ..., "weight": ...}` object, or an object
+  /// mapping tags to their weights. Tags present in several fields are kept
+  /// once, with the highest weight.
+  fn extract_tags<T>(&self, item: &T) -> Vec<ItemTag>
   where
     T: serde::Serialize,
   {
-    let value = match serde_json::to_value(item) {
+    let value = match to_document(item) {
       Ok(v) => v,
       Err(_) => return Vec::new(),
     };
 
-    let tags_value = match value.get(field) {
-      Some(v) => v,
-      None => return Vec::new(),
-    };
+    let mut tags: Vec<ItemTag> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for (field_index, (field, field_weight)) in self.tag_fields.iter().enumerate() {
+      let field_tags: Vec<(&str, f32)> = match get_field_value(&value, field) {
+        Some(Value::Array(arr)) => arr.iter().filter_map(weighted_tag).collect(),
+        Some(Value::Object(map)) => map
+          .iter()
+          .filter_map(|(tag, weight)| Some((tag.as_str(), weight.as_f64()? as f32)))
+          .collect(),
+        _ => continue,
+      };
 
-    match tags_value {
-      Value::Array(arr) => arr
-        .iter()
-        .filter_map(|v| v.as_str().map(|s| s.to_string()))
-        .collect(),
-      _ => Vec::new(),
+      for (tag, tag_weight) in field_tags {
+        let weight = field_weight * tag_weight;
+        let normalized = self.normalizer.normalize(tag);
+        if normalized.is_empty() {
+          continue;
+        }
+        match positions.get(&normalized) {
+          Some(&position) if tags[position].weight >= weight => {}
+          Some(&position) => {
+            tags[position].field = field_index;
+            tags[position].weight = weight;
+          }
+          None => {
+            positions.insert(normalized.clone(), tags.len());
+            tags.push(ItemTag {
+              tag: tag.to_string(),
+              normalized,
+              field: field_index,
+              weight,
+            });
+          }
+        }
+      }
+    }
+    tags
+  }
+}
+
+/// Returns `true` for the strengths of relationships that relate, in
+/// (0, 1], or demote, in [-1, 0).
+fn valid_strength(strength: f32) -> bool {
+  (-1.0..=1.0).contains(&strength) && strength != 0.0
+}
+
+/// Merges the strength of another path to an expanded tag: the strongest
+/// relationship wins, and among demotions the strongest demotion.
+fn merge_strength(existing: &mut f32, strength: f32) {
+  *existing = if *existing < 0.0 && strength < 0.0 {
+    existing.min(strength)
+  } else {
+    existing.max(strength)
+  };
+}
+
+/// Quotes a tag as a DOT identifier.
+fn dot_id(tag: &str) -> String {
+  format!("\"{}\"", tag.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Reads a tag from an element of a tag array: either a string, with a
+/// weight of 1.0, or a `{"tag": ..., "weight": ...}` object.
+fn weighted_tag(value: &Value) -> Option<(&str, f32)> {
+  match value {
+    Value::String(tag) => Some((tag, 1.0)),
+    Value::Object(object) => {
+      let tag = object.get("tag")?.as_str()?;
+      let weight = match object.get("weight") {
+        Some(weight) => weight.as_f64()? as f32,
+        None => 1.0,
+      };
+      Some((tag, weight))
     }
+    _ => None,
   }
 }
 
+/// A tag of an item, with the field it was read from.
+struct ItemTag {
+  /// The tag as written in the item.
+  tag: String,
+  /// The normalized tag, for matching.
+  normalized: String,
+  /// The index of the tag field the tag was read from.
+  field: usize,
+  /// The weight of the tag in the item times the weight of the field.
+  weight: f32,
+}
+
+/// The tags of a query, normalized and expanded once for all items.
+struct TagQuery {
+  /// The normalized excluded tags.
+  exclude_tags: HashSet<String>,
+  /// The query tags, expanded with the Tag Relationship Tree.
+  expanded_tags: HashMap<String, f32>,
+  /// The expansion of each query tag on its own, if items must have all of
+  /// them.
+  required_tags: Option<Vec<HashMap<String, f32>>>,
+}
+
+/// The items precomputed by `warm_up`.
+struct WarmTags {
+  /// The tags of each item, by item index.
+  tags: Vec<Vec<ItemTag>>,
+  /// The indices of the items with each normalized tag, in order.
+  postings: HashMap<String, Vec<usize>>,
+  /// The expansions of the tags of the Tag Relationship Tree to every
+  /// depth, if the searcher has one.
+  expansions: HashMap<String, Vec<Expansion>>,
+}
+
 impl Default for TaggedSearch {
   fn default() -> Self {
     Self::new()
@@ -235,12 +1467,16 @@ where
   /// Performs a search by matching the query tags against the tags of the items.
   ///
   /// This method checks each item to see if its tags (extracted from the
-  /// configured `tag_field`) contain any of the tags specified in `query.tags`.
-  /// The matching is case-insensitive.
+  /// configured tag fields) contain any of the tags specified in `query.tags`,
+  /// or all of them in [`TagMode::All`]. Items with any of the
+  /// `query.exclude_tags` are left out. Tags are compared once normalized,
+  /// which by default makes the matching case-insensitive.
   ///
   /// The raw score for a matched item is calculated as the ratio of the number
   /// of matching tags to the total number of tags in the query. For example, if
   /// the query has 4 tags and the item matches 2 of them, the score will be 0.5.
+  /// Tags negatively related to the query lower the score, and items whose
+  /// score drops to zero are left out.
   fn search(&self, context: &SearchContext<T>, query: &Query) -> Vec<SearusMatch<T>> {
     let items = context.items;
     let query_tags = match &query.tags {
@@ -252,52 +1488,76 @@ where
       return Vec::new();
     }
 
-    #[cfg(feature = "parallel")]
-    let mut results: Vec<SearusMatch<T>> = {
-      // OPTIMIZATION: Pre-allocate result vector
-      let matches: Vec<_> = items
-        .par_iter()
-        .enumerate()
-        .filter(|(_, item)| {
-           if let Some(filters) = &query.filters {
-             filters.evaluate(item)
-           } else {
-             true
-           }
-        })
-        .filter_map(|(index, item)| self.match_entity(item, index, query, query_tags))
-        .collect();
-
-      let mut results = Vec::with_capacity(matches.len());
-      results.extend(matches);
-      results
-    };
+    let mut results = self.warm.with(items, |warm| {
+      let tag_query = self.tag_query(query_tags, query, warm);
+      // With precomputed postings, only the items with a related tag are
+      // matched.
+      let candidates: Option<Vec<usize>> =
+        warm.map(|warm| self.candidates(warm, &tag_query.expanded_tags));
+      let match_item = |index: usize, item: &T| match warm {
+        Some(warm) => self.match_tags(
+          item,
+          index,
+          &warm.tags[index],
+          query,
+          query_tags,
+          &tag_query,
+        ),
+        None => {
+          let item_tags = self.extract_tags(item);
+          self.match_tags(item, index, &item_tags, query, query_tags, &tag_query)
+        }
+      };
 
-    #[cfg(not(feature = "parallel"))]
-    let mut results: Vec<SearusMatch<T>> = {
-      // OPTIMIZATION: Pre-allocate with estimated capacity
-      let mut results = Vec::with_capacity(items.len() / 5); // Assume ~20% tag match rate
-      results.extend(
-        items
-          .iter()
-          .enumerate()
-          .filter(|(_, item)| {
-             if let Some(filters) = &query.filters {
-               filters.evaluate(item)
-             } else {
-               true
-             }
-          })
-          .filter_map(|(index, item)| self.match_entity(item, index, query, query_tags)),
-      );
-      results
-    };
+      context.scan(candidates.as_deref(), query, match_item)
+    });
 
     // Sort results by score in descending order.
     self.sort_results(&mut results);
 
     results
   }
+
+  /// Extracts the tags of every item and indexes the items by tag, and
+  /// expands the tags of the Tag Relationship Tree to every depth, for
+  /// searches over the same items to reuse.
+  fn warm_up(&self, items: &[T]) {
+    #[cfg(feature = "parallel")]
+    let tags: Vec<Vec<ItemTag>> = items
+      .par_iter()
+      .map(|item| self.extract_tags(item))
+      .collect();
+    #[cfg(not(feature = "parallel"))]
+    let tags: Vec<Vec<ItemTag>> = items.iter().map(|item| self.extract_tags(item)).collect();
+
+    let mut postings: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, item_tags) in tags.iter().enumerate() {
+      for tag in item_tags {
+        postings
+          .entry(tag.normalized.clone())
+          .or_default()
+          .push(index);
+      }
+    }
+
+    let expansions = self
+      .trt
+      .as_ref()
+      .map(TagRelationshipTree::expansions_by_depth)
+      .unwrap_or_default();
+    self.warm.set(
+      items,
+      WarmTags {
+        tags,
+        postings,
+        expansions,
+      },
+    );
+  }
+
+  fn cool_down(&self, items: &[T]) {
+    self.warm.clear(items);
+  }
 }
 
 impl TaggedSearch {
@@ -312,36 +1572,119 @@ impl TaggedSearch {
   where
     T: TaggedSearchable,
   {
-    let item_tags = Self::extract_tags(item, &self.tag_field);
+    let item_tags = self.extract_tags(item);
+    let tag_query = self.tag_query(query_tags, query, None);
+    self.match_tags(item, index, &item_tags, query, query_tags, &tag_query)
+  }
+
+  /// Normalizes and expands the tags of a query.
+  fn tag_query(&self, query_tags: &[String], query: &Query, warm: Option<&WarmTags>) -> TagQuery {
+    let exclude_tags = query
+      .exclude_tags
+      .iter()
+      .flatten()
+      .map(|t| self.normalizer.normalize(t))
+      .collect();
+    let required_tags = (query.options.tag_mode.unwrap_or(self.mode) == TagMode::All).then(|| {
+      query_tags
+        .iter()
+        .map(|query_tag| self.expand(std::slice::from_ref(query_tag), query, warm))
+        .collect()
+    });
+
+    TagQuery {
+      exclude_tags,
+      expanded_tags: self.expand(query_tags, query, warm),
+      required_tags,
+    }
+  }
+
+  /// Returns the indices of the items with a tag related to the expanded
+  /// query tags, in order.
+  fn candidates(&self, warm: &WarmTags, expanded_tags: &HashMap<String, f32>) -> Vec<usize> {
+    let mut candidates: Vec<usize> = match self.path_separator {
+      None => expanded_tags
+        .keys()
+        .filter_map(|tag| warm.postings.get(tag))
+        .flatten()
+        .copied()
+        .collect(),
+      // Item tags may also match through their ancestors.
+      Some(_) => warm
+        .postings
+        .iter()
+        .filter(|(tag, _)| self.tag_strength(expanded_tags, tag).is_some())
+        .flat_map(|(_, indices)| indices.iter().copied())
+        .collect(),
+    };
+    candidates.sort_unstable();
+    candidates.dedup();
+    candidates
+  }
+
+  /// Matches the tags of an entity against a normalized and expanded query.
+  fn match_tags<T>(
+    &self,
+    item: &T,
+    index: usize,
+    item_tags: &[ItemTag],
+    query: &Query,
+    query_tags: &[String],
+    tag_query: &TagQuery,
+  ) -> Option<SearusMatch<T>>
+  where
+    T: TaggedSearchable,
+  {
     if item_tags.is_empty() {
       return None;
     }
 
-    // Check if TRT expansion is enabled
-    let expanded_tags = if let (Some(trt), Some(depth)) = (&self.trt, query.options.trt_depth) {
-      if depth > 0 {
-        trt.expand_tags(query_tags, depth)
-      } else {
-        // No expansion, just original tags at strength 1.0
-        query_tags.iter().map(|t| (t.to_lowercase(), 1.0)).collect()
+    // Excluded tags remove the item, whatever else it matches.
+    if item_tags
+      .iter()
+      .any(|tag| tag_query.exclude_tags.contains(&tag.normalized))
+    {
+      return None;
+    }
+
+    let expanded_tags = &tag_query.expanded_tags;
+
+    // In all mode, every query tag (or one of its related tags) must be present.
+    if let Some(required_tags) = &tag_query.required_tags {
+      let all_present = required_tags.iter().all(|related_tags| {
+        item_tags.iter().any(|tag| {
+          self
+            .tag_strength(related_tags, &tag.normalized)
+            .is_some_and(|strength| strength > 0.0)
+        })
+      });
+      if !all_present {
+        return None;
       }
-    } else {
-      // No TRT, just original tags at strength 1.0
-      query_tags.iter().map(|t| (t.to_lowercase(), 1.0)).collect()
-    };
+    }
 
     // OPTIMIZATION: Pre-allocate with expected capacity
     let mut matched_tags = Vec::with_capacity(query_tags.len().min(item_tags.len()));
     let mut total_strength = 0.0;
-    let mut max_strength: f32 = 0.0;
+    let mut demoted_tags = 0;
+    let mut total_demotion = 0.0;
+    let mut field_strengths: HashMap<&str, f32> = HashMap::new();
 
     // Match item tags against expanded tags
-    for item_tag in &item_tags {
-      let item_tag_lower = item_tag.to_lowercase();
-      if let Some(&strength) = expanded_tags.get(&item_tag_lower) {
-        matched_tags.push(item_tag.clone());
-        total_strength += strength;
-        max_strength = max_strength.max(strength);
+    for item_tag in item_tags {
+      if let Some(strength) = self.tag_strength(expanded_tags, &item_tag.normalized) {
+        let strength = strength * item_tag.weight;
+        if strength > 0.0 {
+          matched_tags.push(item_tag.tag.clone());
+          total_strength += strength;
+        } else {
+          // Negatively related tags demote the item instead of matching it.
+          demoted_tags += 1;
+          total_demotion += strength;
+        }
+        *field_strengths
+          .entry(&self.tag_fields[item_tag.field].0)
+          .or_insert(0.0) += strength;
       }
     }
 
@@ -349,12 +1692,58 @@ impl TaggedSearch {
     if !matched_tags.is_empty() {
       // Score calculation:
       // - Base score is the proportion of matched query tags
-      // - Weighted by the average relationship strength of matched tags
+      // - Weighted by the average relationship strength (times tag and field
+      //   weights) of matched tags
+      // - Minus the demotions of negatively related tags, per query tag
       let base_score = matched_tags.len() as f32 / query_tags.len() as f32;
       let avg_strength = total_strength / matched_tags.len() as f32;
-      let score = base_score * avg_strength;
+      let demotion = total_demotion / query_tags.len() as f32;
+      let score = base_score * avg_strength + demotion;
+      if score <= 0.0 {
+        return None;
+      }
 
       let mut m = SearusMatch::new(item.clone(), score, index);
+      // The score is the sum of the strengths divided by the number of query
+      // tags, so it splits by field.
+      for (field, strength) in field_strengths {
+        m.field_scores
+          .insert(field.to_string(), strength / query_tags.len() as f32);
+      }
+      if query.options.explain {
+        let strength_description = if item_tags.iter().all(|tag| tag.weight == 1.0) {
+          "average relationship strength"
+        } else {
+          "average relationship strength times tag weight"
+        };
+        let matched = Explanation::new(
+          base_score * avg_strength,
+          "tags, matched proportion times average strength:",
+        )
+        .with_detail(Explanation::new(
+          base_score,
+          format!(
+            "{} of {} query tags matched",
+            matched_tags.len(),
+            query_tags.len()
+          ),
+        ))
+        .with_detail(Explanation::new(avg_strength, strength_description));
+        m.explanation = Some(if demoted_tags == 0 {
+          matched
+        } else {
+          Explanation::new(score, "tags, sum of:")
+            .with_detail(matched)
+            .with_detail(Explanation::new(
+              demotion,
+              format!(
+                "{} demoted tags, total strength divided by {} query tags",
+                demoted_tags,
+                query_tags.len()
+              ),
+            ))
+        });
+      }
       m.details.push(SearchDetail::Tag {
         matched_tags,
         total_tags: item_tags.len(),
@@ -368,7 +1757,7 @@ impl TaggedSearch {
 
   /// Sort the search results.
   #[cfg(feature = "parallel")]
-  pub fn sort_results<T: Send + Sync>(&self, results: &mut Vec<SearusMatch<T>>) {
+  pub fn sort_results<T: Send + Sync>(&self, results: &mut [SearusMatch<T>]) {
     results.par_sort_by(|a, b| {
       b.score
         .partial_cmp(&a.score)
@@ -377,7 +1766,7 @@ impl TaggedSearch {
   }
 
   #[cfg(not(feature = "parallel"))]
-  pub fn sort_results<T>(&self, results: &mut Vec<SearusMatch<T>>) {
+  pub fn sort_results<T>(&self, results: &mut [SearusMatch<T>]) {
     results.sort_by(|a, b| {
       b.score
         .partial_cmp(&a.score)
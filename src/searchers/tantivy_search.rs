@@ -0,0 +1,390 @@
+//! A `Searcher` delegating full-text queries to a Tantivy index.
+
+use crate::context::SearchContext;
+use crate::document::{get_field_value, to_document};
+use crate::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
+use crate::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::ops::Bound;
+use tantivy::collector::TopDocs;
+use tantivy::query::{
+  BooleanQuery, Occur, Query as TantivyQuery, QueryParser, RangeQuery, TermQuery,
+};
+use tantivy::schema::{
+  Field, FieldType, IndexRecordOption, Schema, Value as _, STORED, STRING, TEXT,
+};
+use tantivy::{Index, IndexReader, TantivyDocument, Term};
+
+/// The maximum number of hits fetched from Tantivy by default.
+const DEFAULT_LIMIT: usize = 1000;
+
+/// A searcher that runs `query.text` against a Tantivy index and returns the
+/// matching items.
+///
+/// Large corpora can keep their full-text index on disk in Tantivy, while
+/// Searus merges its results with those of the tag, vector, and other
+/// searchers. Each Tantivy document stores the key of the item it indexes,
+/// in a field named like the item field holding that key (e.g., `id`), which
+/// is how hits are mapped back to the searched items. Items missing from the
+/// searched slice are skipped.
+///
+/// The query text is parsed with Tantivy's query parser, leniently, so it
+/// can use its syntax (`"exact phrase"`, `title:rust`, `+must -not`).
+/// Filters are pushed down to Tantivy where the schema allows it (equality
+/// on `STRING` and numeric fields, ranges on numeric fields), narrowing the
+/// hits before the limit applies, and are always checked again on the
+/// matched items.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::searchers::TantivySearch;
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Post {
+///     id: String,
+///     title: String,
+/// }
+///
+/// let posts = vec![
+///     Post { id: "1".into(), title: "Rust ownership".into() },
+///     Post { id: "2".into(), title: "Python typing".into() },
+/// ];
+///
+/// // Index the posts in memory; real deployments open an on-disk index.
+/// let tantivy = TantivySearch::in_ram(&posts, "id", &["title"]).unwrap();
+/// let engine = SearusEngine::builder().with(Box::new(tantivy)).build();
+///
+/// let results = engine.search(&posts, &Query::builder().text("rust").build());
+/// assert_eq!(results[0].item.title, "Rust ownership");
+/// ```
+pub struct TantivySearch {
+  /// The Tantivy index.
+  index: Index,
+  /// The reader searching the index.
+  reader: IndexReader,
+  /// The Tantivy field holding the key of each item.
+  key: Field,
+  /// The item field holding the key of each item.
+  key_field: String,
+  /// The fields searched by query terms without a field.
+  fields: Vec<Field>,
+  /// The maximum number of hits fetched.
+  limit: usize,
+}
+
+impl TantivySearch {
+  /// Creates a searcher over an existing index.
+  ///
+  /// # Arguments
+  ///
+  /// * `index` - The Tantivy index. Its schema must have a stored field named
+  ///   `key_field`.
+  /// * `key_field` - The name of the field holding the key of each item, in
+  ///   both the Tantivy documents and the items.
+  ///
+  /// Query terms without a field search every indexed text field, except the
+  /// key; use [`with_fields`](Self::with_fields) to choose them.
+  pub fn new(index: Index, key_field: &str) -> Result<Self, String> {
+    let schema = index.schema();
+    let key = schema.get_field(key_field).map_err(|e| e.to_string())?;
+    if !schema.get_field_entry(key).is_stored() {
+      return Err(format!("The key field `{}` must be stored", key_field));
+    }
+
+    let fields = schema
+      .fields()
+      .filter(|(field, entry)| {
+        *field != key && entry.is_indexed() && matches!(entry.field_type(), FieldType::Str(_))
+      })
+      .map(|(field, _)| field)
+      .collect();
+    let reader = index.reader().map_err(|e| e.to_string())?;
+
+    Ok(Self {
+      index,
+      reader,
+      key,
+      key_field: key_field.to_string(),
+      fields,
+      limit: DEFAULT_LIMIT,
+    })
+  }
+
+  /// Builds an in-memory index of the given items, with their key in
+  /// `key_field` and the text of `fields`, and creates a searcher over it.
+  ///
+  /// This suits tests and small corpora; large corpora are indexed ahead of
+  /// time and opened with [`new`](Self::new).
+  pub fn in_ram<T: Serialize>(
+    items: &[T],
+    key_field: &str,
+    fields: &[&str],
+  ) -> Result<Self, String> {
+    let mut builder = Schema::builder();
+    let key = builder.add_text_field(key_field, STRING | STORED);
+    let text_fields: Vec<(&str, Field)> = fields
+      .iter()
+      .map(|name| (*name, builder.add_text_field(name, TEXT)))
+      .collect();
+    let index = Index::create_in_ram(builder.build());
+
+    let mut writer = index
+      .writer::<TantivyDocument>(15_000_000)
+      .map_err(|e| e.to_string())?;
+    for item in items {
+      let item = to_document(item).map_err(|e| e.to_string())?;
+      let id = get_field_value(&item, key_field)
+        .and_then(key_of)
+        .ok_or_else(|| format!("An item has no `{}` key", key_field))?;
+
+      let mut document = TantivyDocument::default();
+      document.add_text(key, id);
+      for (name, field) in &text_fields {
+        if let Some(Value::String(text)) = get_field_value(&item, name) {
+          document.add_text(*field, text);
+        }
+      }
+      writer.add_document(document).map_err(|e| e.to_string())?;
+    }
+    writer.commit().map_err(|e| e.to_string())?;
+
+    Self::new(index, key_field)
+  }
+
+  /// Sets the fields searched by query terms without a field.
+  pub fn with_fields(mut self, fields: &[&str]) -> Result<Self, String> {
+    let schema = self.index.schema();
+    self.fields = fields
+      .iter()
+      .map(|name| schema.get_field(name).map_err(|e| e.to_string()))
+      .collect::<Result<_, _>>()?;
+    Ok(self)
+  }
+
+  /// Sets the maximum number of hits fetched from Tantivy, 1000 by default.
+  pub fn with_limit(mut self, limit: usize) -> Self {
+    self.limit = limit.max(1);
+    self
+  }
+
+  /// Returns the Tantivy index.
+  pub fn index(&self) -> &Index {
+    &self.index
+  }
+
+  /// Makes documents committed since the last reload searchable right away,
+  /// rather than after the reader's reload delay.
+  pub fn reload(&self) -> Result<(), String> {
+    self.reader.reload().map_err(|e| e.to_string())
+  }
+
+  /// Runs the query on the Tantivy index, returning the score of each hit
+  /// by item key.
+  fn hits(&self, query: &Query) -> Result<HashMap<String, f32>, String> {
+    let Some(text) = query.text.as_deref().filter(|text| !text.trim().is_empty()) else {
+      return Ok(HashMap::new());
+    };
+
+    let parser = QueryParser::for_index(&self.index, self.fields.clone());
+    let (text_query, _) = parser.parse_query_lenient(text);
+    let mut clauses = vec![(Occur::Must, text_query)];
+    if let Some(filter) = query.filters.as_ref().and_then(|f| self.translate(f)) {
+      clauses.push((Occur::Must, filter));
+    }
+    let tantivy_query = BooleanQuery::new(clauses);
+
+    let searcher = self.reader.searcher();
+    let top = searcher
+      .search(&tantivy_query, &TopDocs::with_limit(self.limit))
+      .map_err(|e| e.to_string())?;
+
+    let mut hits = HashMap::new();
+    for (score, address) in top {
+      let document: TantivyDocument = searcher.doc(address).map_err(|e| e.to_string())?;
+      let key = document.get_first(self.key).and_then(|value| {
+        value
+          .as_str()
+          .map(String::from)
+          .or_else(|| value.as_u64().map(|n| n.to_string()))
+          .or_else(|| value.as_i64().map(|n| n.to_string()))
+      });
+      if let Some(key) = key {
+        hits.entry(key).or_insert(score);
+      }
+    }
+    Ok(hits)
+  }
+
+  /// Translates a filter to a Tantivy query matching at least the documents
+  /// the filter keeps, or `None` if no part of it can be pushed down.
+  fn translate(&self, filter: &FilterExpr) -> Option<Box<dyn TantivyQuery>> {
+    match filter {
+      FilterExpr::Compare {
+        field,
+        op,
+        value,
+        options,
+      } if *options == StringOptions::default() => {
+        let field = self.index.schema().get_field(field).ok()?;
+        match (op, value) {
+          (CompareOp::Eq, value) => self.term_query(field, value),
+          (CompareOp::In, FilterValue::List(values)) => {
+            let clauses = values
+              .iter()
+              .map(|value| Some((Occur::Should, self.term_query(field, value)?)))
+              .collect::<Option<Vec<_>>>()?;
+            Some(Box::new(BooleanQuery::new(clauses)))
+          }
+          (CompareOp::Lt, value) => {
+            self.range_query(field, Bound::Unbounded, Bound::Excluded(value))
+          }
+          (CompareOp::Le, value) => {
+            self.range_query(field, Bound::Unbounded, Bound::Included(value))
+          }
+          (CompareOp::Gt, value) => {
+            self.range_query(field, Bound::Excluded(value), Bound::Unbounded)
+          }
+          (CompareOp::Ge, value) => {
+            self.range_query(field, Bound::Included(value), Bound::Unbounded)
+          }
+          _ => None,
+        }
+      }
+      FilterExpr::Between {
+        field,
+        low,
+        high,
+        inclusive,
+        options,
+      } if *options == StringOptions::default() => {
+        let field = self.index.schema().get_field(field).ok()?;
+        let (low, high) = if *inclusive {
+          (Bound::Included(low), Bound::Included(high))
+        } else {
+          (Bound::Excluded(low), Bound::Excluded(high))
+        };
+        self.range_query(field, low, high)
+      }
+      // Dropping the parts of a conjunction that can't be pushed down only
+      // keeps more documents.
+      FilterExpr::And(filters) => {
+        let clauses: Vec<_> = filters
+          .iter()
+          .filter_map(|filter| Some((Occur::Must, self.translate(filter)?)))
+          .collect();
+        (!clauses.is_empty()).then(|| Box::new(BooleanQuery::new(clauses)) as Box<_>)
+      }
+      FilterExpr::Or(filters) => {
+        let clauses = filters
+          .iter()
+          .map(|filter| Some((Occur::Should, self.translate(filter)?)))
+          .collect::<Option<Vec<_>>>()?;
+        Some(Box::new(BooleanQuery::new(clauses)))
+      }
+      _ => None,
+    }
+  }
+
+  /// Builds a query for documents whose field equals the value.
+  fn term_query(&self, field: Field, value: &FilterValue) -> Option<Box<dyn TantivyQuery>> {
+    let term = self.term(field, value, true)?;
+    Some(Box::new(TermQuery::new(term, IndexRecordOption::Basic)))
+  }
+
+  /// Builds a query for documents whose numeric field is within the bounds.
+  fn range_query(
+    &self,
+    field: Field,
+    low: Bound<&FilterValue>,
+    high: Bound<&FilterValue>,
+  ) -> Option<Box<dyn TantivyQuery>> {
+    let schema = self.index.schema();
+    let entry = schema.get_field_entry(field);
+    if !matches!(
+      entry.field_type(),
+      FieldType::U64(_) | FieldType::I64(_) | FieldType::F64(_)
+    ) {
+      return None;
+    }
+
+    let bound = |bound: Bound<&FilterValue>| match bound {
+      Bound::Included(value) => self.term(field, value, false).map(Bound::Included),
+      Bound::Excluded(value) => self.term(field, value, false).map(Bound::Excluded),
+      Bound::Unbounded => Some(Bound::Unbounded),
+    };
+    Some(Box::new(RangeQuery::new(bound(low)?, bound(high)?)))
+  }
+
+  /// Converts a filter value to a term of the field, if the field is indexed
+  /// and the value has its type. Strings only match untokenized (`STRING`)
+  /// fields, whose terms are the whole values.
+  fn term(&self, field: Field, value: &FilterValue, exact: bool) -> Option<Term> {
+    let schema = self.index.schema();
+    let entry = schema.get_field_entry(field);
+    if !entry.is_indexed() && !entry.is_fast() {
+      return None;
+    }
+
+    match (entry.field_type(), value) {
+      (FieldType::Str(options), FilterValue::String(text)) if exact => {
+        let raw = options
+          .get_indexing_options()
+          .is_some_and(|indexing| indexing.tokenizer() == "raw");
+        raw.then(|| Term::from_field_text(field, text))
+      }
+      (FieldType::U64(_), FilterValue::Number(n)) if n.fract() == 0.0 && *n >= 0.0 => {
+        Some(Term::from_field_u64(field, *n as u64))
+      }
+      (FieldType::I64(_), FilterValue::Number(n)) if n.fract() == 0.0 => {
+        Some(Term::from_field_i64(field, *n as i64))
+      }
+      (FieldType::F64(_), FilterValue::Number(n)) => Some(Term::from_field_f64(field, *n)),
+      (FieldType::Bool(_), FilterValue::Bool(b)) if exact => Some(Term::from_field_bool(field, *b)),
+      _ => None,
+    }
+  }
+}
+
+/// Reads the key of an item from its key field.
+fn key_of(value: &Value) -> Option<String> {
+  match value {
+    Value::String(key) => Some(key.clone()),
+    Value::Number(key) => Some(key.to_string()),
+    _ => None,
+  }
+}
+
+impl<T> Searcher<T> for TantivySearch
+where
+  T: Searchable + Serialize + Clone,
+{
+  fn kind(&self) -> SearcherKind {
+    SearcherKind::Custom
+  }
+
+  fn search(&self, context: &SearchContext<T>, query: &Query) -> Vec<SearusMatch<T>> {
+    // A failing index matches nothing, like a query without text.
+    let hits = self.hits(query).unwrap_or_default();
+    if hits.is_empty() {
+      return Vec::new();
+    }
+
+    context
+      .items
+      .iter()
+      .enumerate()
+      .filter_map(|(index, item)| {
+        let document = context.document(index)?;
+        let key = get_field_value(document, &self.key_field).and_then(key_of)?;
+        let score = *hits.get(&key)?;
+        context
+          .matches_filters(index, query.filters.as_ref())
+          .then(|| SearusMatch::new(item.clone(), score, index))
+      })
+      .collect()
+  }
+}
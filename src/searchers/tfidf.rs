@@ -0,0 +1,147 @@
+//! An implementation of TF-IDF scoring.
+//!
+//! TF-IDF (Term Frequency - Inverse Document Frequency) weighs each query term
+//! by how often it occurs in a document and how rare it is in the corpus.
+//! Unlike BM25, it does not normalize by document length, so the score of a
+//! document does not depend on the average length of the corpus.
+
+use crate::explain::Explanation;
+use std::collections::HashMap;
+
+/// A scorer for ranking documents using TF-IDF.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::tfidf::TfIdfScorer;
+/// use std::collections::HashMap;
+///
+/// let query = vec!["rust".to_string()];
+/// let doc_freq = HashMap::from([("rust".to_string(), 1)]);
+///
+/// let once = HashMap::from([("rust".to_string(), 1)]);
+/// let twice = HashMap::from([("rust".to_string(), 2)]);
+///
+/// let scorer = TfIdfScorer::new();
+/// assert_eq!(
+///     scorer.score(&query, &twice, &doc_freq, 10),
+///     2.0 * scorer.score(&query, &once, &doc_freq, 10)
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TfIdfScorer;
+
+impl TfIdfScorer {
+  /// Creates a new `TfIdfScorer`.
+  pub fn new() -> Self {
+    Self
+  }
+
+  /// Calculates the TF-IDF score of a document for a given query.
+  ///
+  /// # Arguments
+  ///
+  /// * `query_terms` - A slice of the terms in the search query.
+  /// * `doc_terms` - A map of term frequencies for the document being scored.
+  /// * `doc_freq` - A map of document frequencies for each term in the corpus.
+  /// * `total_docs` - The total number of documents in the corpus.
+  ///
+  /// # Returns
+  ///
+  /// The sum over the query terms of their frequency in the document times
+  /// their IDF.
+  pub fn score(
+    &self,
+    query_terms: &[String],
+    doc_terms: &HashMap<String, usize>,
+    doc_freq: &HashMap<String, usize>,
+    total_docs: usize,
+  ) -> f32 {
+    let mut score = 0.0;
+
+    for term in query_terms {
+      let tf = *doc_terms.get(term).unwrap_or(&0) as f32;
+      if tf == 0.0 {
+        continue;
+      }
+
+      let df = *doc_freq.get(term).unwrap_or(&1) as f32;
+      score += tf * self.idf(df, total_docs);
+    }
+
+    score
+  }
+
+  /// Explains the TF-IDF score of a document for a given query.
+  ///
+  /// The returned explanation has the same value as [`score`](Self::score)
+  /// and breaks it down per matched term. The arguments are the same as for
+  /// `score`.
+  pub fn explain(
+    &self,
+    query_terms: &[String],
+    doc_terms: &HashMap<String, usize>,
+    doc_freq: &HashMap<String, usize>,
+    total_docs: usize,
+  ) -> Explanation {
+    let mut explanation = Explanation::new(0.0, "tf-idf, sum of:");
+
+    for term in query_terms {
+      let tf = *doc_terms.get(term).unwrap_or(&0) as f32;
+      if tf == 0.0 {
+        continue;
+      }
+
+      let df = *doc_freq.get(term).unwrap_or(&1) as f32;
+      let idf = self.idf(df, total_docs);
+
+      explanation.value += tf * idf;
+      explanation.details.push(
+        Explanation::new(tf * idf, format!("term \"{}\", tf times idf:", term))
+          .with_detail(Explanation::new(tf, "term frequency"))
+          .with_detail(Explanation::new(
+            idf,
+            format!("idf, {} of {} documents contain the term", df, total_docs),
+          )),
+      );
+    }
+
+    explanation
+  }
+
+  /// Calculates the smoothed Inverse Document Frequency (IDF) for a term.
+  ///
+  /// The smoothing keeps the IDF positive, even for terms that occur in
+  /// every document.
+  fn idf(&self, doc_freq: f32, total_docs: usize) -> f32 {
+    let n = total_docs as f32;
+    ((n + 1.0) / (doc_freq + 1.0)).ln() + 1.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_tfidf_rare_terms_score_higher() {
+    let scorer = TfIdfScorer::new();
+
+    let mut doc_terms = HashMap::new();
+    doc_terms.insert("rust".to_string(), 1);
+    doc_terms.insert("the".to_string(), 1);
+
+    let mut doc_freq = HashMap::new();
+    doc_freq.insert("rust".to_string(), 1);
+    doc_freq.insert("the".to_string(), 10);
+
+    let rare = scorer.score(&["rust".to_string()], &doc_terms, &doc_freq, 10);
+    let common = scorer.score(&["the".to_string()], &doc_terms, &doc_freq, 10);
+    assert!(rare > common);
+    assert!(common > 0.0);
+
+    let query_terms = vec!["rust".to_string(), "the".to_string()];
+    let explanation = scorer.explain(&query_terms, &doc_terms, &doc_freq, 10);
+    assert!((explanation.value - (rare + common)).abs() < 1e-6);
+  }
+}
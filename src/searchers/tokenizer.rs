@@ -3,9 +3,113 @@
 //! This module provides functions for breaking down text into tokens (words)
 //! and for calculating term frequencies, which are essential steps for many
 //! text-based search algorithms.
+//!
+//! Searchers split text through the [`Tokenizer`] trait, so applications can
+//! replace the default [`UnicodeTokenizer`] with a domain-specific one (e.g.,
+//! for code identifiers or SKUs).
 
+use std::ops::Range;
 use unicode_segmentation::UnicodeSegmentation;
 
+/// Splits text into tokens for indexing and matching.
+///
+/// Implementations only need to provide
+/// [`tokenize_with_offsets`](Tokenizer::tokenize_with_offsets); the byte
+/// ranges are used to highlight matches in the original text.
+///
+/// # Examples
+///
+/// A tokenizer that keeps SKUs such as `AB-1234` as single tokens:
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::searchers::tokenizer::Tokenizer;
+/// use std::ops::Range;
+///
+/// struct WhitespaceTokenizer;
+///
+/// impl Tokenizer for WhitespaceTokenizer {
+///     fn tokenize_with_offsets(&self, text: &str) -> Vec<(String, Range<usize>)> {
+///         let mut tokens = Vec::new();
+///         let mut start = None;
+///         for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+///             match (c.is_whitespace(), start) {
+///                 (true, Some(s)) => {
+///                     tokens.push((text[s..i].to_lowercase(), s..i));
+///                     start = None;
+///                 }
+///                 (false, None) => start = Some(i),
+///                 _ => {}
+///             }
+///         }
+///         tokens
+///     }
+/// }
+///
+/// assert_eq!(WhitespaceTokenizer.tokenize("Part AB-1234"), vec!["part", "ab-1234"]);
+///
+/// let rules = SemanticRules::builder().field("sku", FieldRule::exact()).build();
+/// let searcher = SemanticSearch::new(rules).with_tokenizer(Box::new(WhitespaceTokenizer));
+/// ```
+pub trait Tokenizer: Send + Sync {
+  /// Splits a text into tokens, each paired with its byte range in `text`.
+  fn tokenize_with_offsets(&self, text: &str) -> Vec<(String, Range<usize>)>;
+
+  /// Splits a text into tokens.
+  fn tokenize(&self, text: &str) -> Vec<String> {
+    self
+      .tokenize_with_offsets(text)
+      .into_iter()
+      .map(|(token, _)| token)
+      .collect()
+  }
+}
+
+/// The default tokenizer, splitting text on Unicode word boundaries and
+/// lowercasing each word.
+///
+/// See [`tokenize`] for details.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnicodeTokenizer;
+
+impl Tokenizer for UnicodeTokenizer {
+  fn tokenize_with_offsets(&self, text: &str) -> Vec<(String, Range<usize>)> {
+    tokenize_with_offsets(text)
+  }
+
+  fn tokenize(&self, text: &str) -> Vec<String> {
+    tokenize(text)
+  }
+}
+
+/// A tokenizer emitting the whole text as a single lowercase token, with
+/// surrounding whitespace trimmed.
+///
+/// It suits identifier-like fields such as SKUs, email addresses, or codes,
+/// which should only match when the whole value matches.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::searchers::tokenizer::{KeywordTokenizer, Tokenizer};
+///
+/// assert_eq!(KeywordTokenizer.tokenize(" LP-1400/GR "), vec!["lp-1400/gr"]);
+/// assert!(KeywordTokenizer.tokenize("   ").is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeywordTokenizer;
+
+impl Tokenizer for KeywordTokenizer {
+  fn tokenize_with_offsets(&self, text: &str) -> Vec<(String, Range<usize>)> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+      return Vec::new();
+    }
+    let start = trimmed.as_ptr() as usize - text.as_ptr() as usize;
+    vec![(trimmed.to_lowercase(), start..start + trimmed.len())]
+  }
+}
+
 /// Tokenizes a given text into a vector of words.
 ///
 /// This function performs the following steps:
@@ -29,6 +133,23 @@ pub fn tokenize(text: &str) -> Vec<String> {
     .collect()
 }
 
+/// Tokenizes a text like [`tokenize`], also returning the byte range of each
+/// token in the original text.
+///
+/// # Arguments
+///
+/// * `text` - The string slice to be tokenized.
+///
+/// # Returns
+///
+/// A `Vec` of lowercase word tokens, each paired with its byte range in `text`.
+pub fn tokenize_with_offsets(text: &str) -> Vec<(String, Range<usize>)> {
+  text
+    .unicode_word_indices()
+    .map(|(start, word)| (word.to_lowercase(), start..start + word.len()))
+    .collect()
+}
+
 /// Calculates the frequency of each term in a given text.
 ///
 /// This function first tokenizes the text using the `tokenize` function and
@@ -43,11 +164,23 @@ pub fn tokenize(text: &str) -> Vec<String> {
 /// A `HashMap<String, usize>` where the keys are the unique tokens (terms)
 /// and the values are their frequencies (counts) in the text.
 pub fn term_frequencies(text: &str) -> std::collections::HashMap<String, usize> {
-  let tokens = tokenize(text);
+  count_terms(&tokenize(text))
+}
+
+/// Counts the occurrences of each unique token in an already tokenized text.
+///
+/// # Arguments
+///
+/// * `tokens` - The tokens to be counted.
+///
+/// # Returns
+///
+/// A `HashMap<String, usize>` mapping each unique token to its frequency.
+pub fn count_terms(tokens: &[String]) -> std::collections::HashMap<String, usize> {
   let mut freqs = std::collections::HashMap::new();
 
   for token in tokens {
-    *freqs.entry(token).or_insert(0) += 1;
+    *freqs.entry(token.clone()).or_insert(0) += 1;
   }
 
   freqs
@@ -64,6 +197,19 @@ mod tests {
     assert_eq!(tokens, vec!["hello", "world", "this", "is", "a", "test"]);
   }
 
+  #[test]
+  fn test_tokenize_with_offsets() {
+    let tokens = tokenize_with_offsets("Héllo, World!");
+    assert_eq!(tokens[0], ("héllo".to_string(), 0..6));
+    assert_eq!(tokens[1], ("world".to_string(), 8..13));
+  }
+
+  #[test]
+  fn test_keyword_tokenizer_offsets() {
+    let tokens = KeywordTokenizer.tokenize_with_offsets("  AB-12 x ");
+    assert_eq!(tokens, vec![("ab-12 x".to_string(), 2..9)]);
+  }
+
   #[test]
   fn test_term_frequencies() {
     let text = "the quick brown fox jumps over the lazy dog";
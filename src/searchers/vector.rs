@@ -0,0 +1,216 @@
+//! A `Searcher` implementation for finding items similar to a query vector.
+
+use crate::context::SearchContext;
+use crate::embeddings::cosine_similarity;
+use crate::index::DEFAULT_VECTOR;
+use crate::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Reads the embedding of an item, if it has one.
+type VectorFn<T> = Box<dyn Fn(&T) -> Option<Vec<f32>> + Send + Sync>;
+
+/// A searcher that ranks items by the cosine similarity of their embedding
+/// to `query.vector`.
+///
+/// Items carry their own vectors, computed ahead of time with a
+/// [`TextEmbedder`]. The query vector can be given with the query, or
+/// computed from `query.text` by the engine when it has an embedder (see
+/// [`SearusEngineBuilder::embedder`]), using the same model as the items.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::searchers::VectorSearch;
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Post {
+///     title: String,
+///     embedding: Vec<f32>,
+/// }
+///
+/// let embedder = StubTextEmbedder::new(16);
+/// let posts: Vec<Post> = ["Rust search engines", "Baking bread"]
+///     .iter()
+///     .map(|title| Post {
+///         title: title.to_string(),
+///         embedding: embedder.embed(title).unwrap(),
+///     })
+///     .collect();
+///
+/// let engine = SearusEngine::builder()
+///     .with(Box::new(VectorSearch::new(|post: &Post| Some(post.embedding.clone()))))
+///     .embedder(Box::new(embedder))
+///     .build();
+///
+/// // The engine embeds the query text, as no vector is given.
+/// let query = Query::builder().text("Rust search engines").build();
+/// let results = engine.search(&posts, &query);
+/// assert_eq!(results[0].item.title, "Rust search engines");
+/// ```
+///
+/// Items with several embeddings, such as one of their text and one of
+/// their image, register each under a name with
+/// [`with_named_vector`](Self::with_named_vector), and queries pick one with
+/// [`Query::vector_name`].
+pub struct VectorSearch<T> {
+  /// Reads the embedding of an item.
+  vector: VectorFn<T>,
+  /// Reads the other embeddings of an item, by name.
+  named: HashMap<String, VectorFn<T>>,
+  /// The similarity an item must exceed to match.
+  min_similarity: f32,
+}
+
+impl<T> VectorSearch<T> {
+  /// Creates a vector searcher.
+  ///
+  /// # Arguments
+  ///
+  /// * `vector` - Returns the embedding of an item, or `None` for items
+  ///   without one.
+  pub fn new<F>(vector: F) -> Self
+  where
+    F: Fn(&T) -> Option<Vec<f32>> + Send + Sync + 'static,
+  {
+    Self {
+      vector: Box::new(vector),
+      named: HashMap::new(),
+      min_similarity: 0.0,
+    }
+  }
+
+  /// Adds a named embedding of the items, searched by queries with this
+  /// [`vector_name`](Query::vector_name).
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::VectorSearch;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Product {
+  ///     name: String,
+  ///     text_vector: Vec<f32>,
+  ///     image_vector: Vec<f32>,
+  /// }
+  ///
+  /// let products = vec![
+  ///     Product { name: "Red mug".into(), text_vector: vec![1.0, 0.0], image_vector: vec![0.0, 1.0] },
+  ///     Product { name: "Blue mug".into(), text_vector: vec![0.0, 1.0], image_vector: vec![1.0, 0.0] },
+  /// ];
+  ///
+  /// let searcher = VectorSearch::new(|p: &Product| Some(p.text_vector.clone()))
+  ///     .with_named_vector("image", |p: &Product| Some(p.image_vector.clone()));
+  /// let engine = SearusEngine::builder().with(Box::new(searcher)).build();
+  ///
+  /// let query = Query::builder().vector(vec![1.0, 0.0]).build();
+  /// assert_eq!(engine.search(&products, &query)[0].item.name, "Red mug");
+  ///
+  /// let query = Query::builder().vector(vec![1.0, 0.0]).vector_name("image").build();
+  /// assert_eq!(engine.search(&products, &query)[0].item.name, "Blue mug");
+  /// ```
+  pub fn with_named_vector<F>(mut self, name: impl Into<String>, vector: F) -> Self
+  where
+    F: Fn(&T) -> Option<Vec<f32>> + Send + Sync + 'static,
+  {
+    self.named.insert(name.into(), Box::new(vector));
+    self
+  }
+
+  /// Sets the cosine similarity an item must exceed to match (0.0 by default,
+  /// which leaves out unrelated items).
+  pub fn with_min_similarity(mut self, min_similarity: f32) -> Self {
+    self.min_similarity = min_similarity;
+    self
+  }
+
+  /// Returns the embeddings the query searches, and their name if the query
+  /// names them.
+  fn vectors<'a>(&'a self, query: &'a Query) -> Option<(&'a VectorFn<T>, Option<&'a str>)> {
+    match query.vector_name.as_deref() {
+      None => Some((&self.vector, None)),
+      Some(name) => match self.named.get(name) {
+        Some(vector) => Some((vector, Some(name))),
+        None if name == DEFAULT_VECTOR => Some((&self.vector, Some(name))),
+        None => None,
+      },
+    }
+  }
+
+  /// Scores a single item against the query vector.
+  fn match_item(
+    &self,
+    item: &T,
+    index: usize,
+    query: &Query,
+    query_vector: &[f32],
+  ) -> Option<SearusMatch<T>>
+  where
+    T: Searchable + Clone,
+  {
+    let (vector, name) = self.vectors(query)?;
+    let vector = vector(item)?;
+    let similarity = cosine_similarity(query_vector, &vector)?;
+    if similarity <= self.min_similarity {
+      return None;
+    }
+
+    let mut m =
+      SearusMatch::new(item.clone(), similarity, index).with_detail(SearchDetail::Vector {
+        distance: 1.0 - similarity,
+        similarity,
+      });
+    if let Some(name) = name {
+      m.field_scores.insert(name.to_string(), similarity);
+    }
+    if query.options.explain {
+      let description = match name {
+        Some(name) => format!("vector, cosine similarity to the query {} vector", name),
+        None => "vector, cosine similarity to the query vector".to_string(),
+      };
+      m.explanation = Some(Explanation::new(similarity, description));
+    }
+    Some(m)
+  }
+}
+
+impl<T> Searcher<T> for VectorSearch<T>
+where
+  T: Searchable + Serialize + Clone,
+{
+  fn kind(&self) -> SearcherKind {
+    SearcherKind::Vector
+  }
+
+  /// Performs a search by comparing `query.vector` with the embedding of each
+  /// item.
+  ///
+  /// The raw score of a match is its cosine similarity to the query vector,
+  /// and its distance in [`SearchDetail::Vector`] is the cosine distance.
+  /// Items without an embedding, or whose embedding has a different length
+  /// than the query's, don't match. Nothing matches without a query vector,
+  /// or when the query names embeddings the searcher doesn't have. Matches
+  /// of named embeddings record their similarity in `field_scores`.
+  fn search(&self, context: &SearchContext<T>, query: &Query) -> Vec<SearusMatch<T>> {
+    let Some(query_vector) = &query.vector else {
+      return Vec::new();
+    };
+    if self.vectors(query).is_none() {
+      return Vec::new();
+    }
+
+    let mut results = context.scan(None, query, |index, item| {
+      self.match_item(item, index, query, query_vector)
+    });
+
+    results.sort_by(|a, b| {
+      b.score
+        .partial_cmp(&a.score)
+        .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results
+  }
+}
@@ -0,0 +1,184 @@
+//! An HTTP search service, built on axum.
+//!
+//! [`router`] exposes an [`IndexedSearusEngine`] over HTTP, so Searus can be
+//! deployed as a standalone search service:
+//!
+//! | Method   | Path           | Body in      | Body out                     |
+//! |----------|----------------|--------------|------------------------------|
+//! | `POST`   | `/search`      | JSON `Query` | JSON `SearchResponse`        |
+//! | `GET`    | `/suggest`     | `?q=&limit=` | JSON list of `Completion`s   |
+//! | `GET`    | `/index/{id}`  |              | JSON item, or 404            |
+//! | `PUT`    | `/index/{id}`  | JSON item    | 204, or 400 with the error   |
+//! | `DELETE` | `/index/{id}`  |              | 204                          |
+//!
+//! Searches and `GET /index/{id}` run on behalf of the [`Principal`] found
+//! in the request extensions, set by the service's authentication
+//! middleware, and anonymously without one. A principal in the JSON query is
+//! ignored: clients could otherwise claim any identity or role, and see
+//! through an [`AccessControlExtension`](crate::extensions::AccessControlExtension).
+//! Items the principal may not see are reported as missing (see
+//! [`SearusEngine::is_visible`](crate::engine::SearusEngine::is_visible)).
+//!
+//! `PUT` and `DELETE` check no principal: guard them with the service's own
+//! authorization middleware, or expose them only to trusted clients.
+//!
+//! Searches share a read lock on the engine and writes take a write lock, so
+//! the index can be updated while the service runs. Searches run on the
+//! async worker thread that received the request; for large corpora, run
+//! the router on a multi-threaded runtime.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use searus::prelude::*;
+//! use searus::server;
+//! use std::sync::{Arc, RwLock};
+//!
+//! #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+//! struct Post {
+//!     title: String,
+//! }
+//!
+//! # async fn run() {
+//! let rules = SemanticRules::builder().field("title", FieldRule::bm25()).build();
+//! let engine = SearusEngine::builder()
+//!     .with(Box::new(SemanticSearch::new(rules)))
+//!     .build();
+//! let indexed: IndexedSearusEngine<Post> =
+//!     IndexedSearusEngine::new(engine).with_completion_fields(["title"]);
+//!
+//! let app = server::router(Arc::new(RwLock::new(indexed)));
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
+//! axum::serve(listener, app).await.unwrap();
+//! # }
+//! ```
+
+use crate::completion::Completion;
+use crate::index::{InMemIndex, IndexAdapter};
+use crate::indexed::IndexedSearusEngine;
+use crate::types::{Principal, Query, SearchResponse, Searchable};
+use axum::extract::{Path, Query as Params, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Extension, Json, Router};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// An [`IndexedSearusEngine`] shared between the request handlers.
+pub type SharedEngine<T, I = InMemIndex<T>> = Arc<RwLock<IndexedSearusEngine<T, I>>>;
+
+/// The query string of `/suggest`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SuggestParams {
+  /// The prefix to complete.
+  pub q: String,
+  /// The maximum number of completions, 10 by default.
+  #[serde(default = "default_suggest_limit")]
+  pub limit: usize,
+}
+
+/// Returns the default number of completions.
+fn default_suggest_limit() -> usize {
+  10
+}
+
+/// Builds the router exposing the engine's search, suggestion, and index
+/// endpoints.
+pub fn router<T, I>(engine: SharedEngine<T, I>) -> Router
+where
+  T: Searchable + Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+  I: IndexAdapter<T> + 'static,
+{
+  Router::new()
+    .route("/search", post(search::<T, I>))
+    .route("/suggest", get(suggest::<T, I>))
+    .route(
+      "/index/{id}",
+      get(get_item::<T, I>)
+        .put(put_item::<T, I>)
+        .delete(delete_item::<T, I>),
+    )
+    .with_state(engine)
+}
+
+/// Runs a search on behalf of the authenticated principal, if any.
+async fn search<T, I>(
+  State(engine): State<SharedEngine<T, I>>,
+  principal: Option<Extension<Principal>>,
+  Json(mut query): Json<Query>,
+) -> Json<SearchResponse<T>>
+where
+  T: Searchable + Clone + Serialize + Send + Sync,
+  I: IndexAdapter<T>,
+{
+  query.principal = principal.map(|Extension(principal)| principal);
+  let engine = engine.read().unwrap_or_else(PoisonError::into_inner);
+  Json(engine.search(&query))
+}
+
+/// Completes a search-box prefix.
+async fn suggest<T, I>(
+  State(engine): State<SharedEngine<T, I>>,
+  Params(params): Params<SuggestParams>,
+) -> Json<Vec<Completion>>
+where
+  T: Searchable + Send + Sync,
+  I: IndexAdapter<T>,
+{
+  let engine = engine.read().unwrap_or_else(PoisonError::into_inner);
+  Json(engine.suggest(&params.q, params.limit))
+}
+
+/// Returns an item by id, if the authenticated principal may see it.
+async fn get_item<T, I>(
+  State(engine): State<SharedEngine<T, I>>,
+  principal: Option<Extension<Principal>>,
+  Path(id): Path<String>,
+) -> Result<Json<T>, StatusCode>
+where
+  T: Searchable + Clone + Serialize + Send + Sync,
+  I: IndexAdapter<T>,
+{
+  let principal = principal.map(|Extension(principal)| principal);
+  let engine = engine.read().unwrap_or_else(PoisonError::into_inner);
+  engine
+    .get(&id)
+    .filter(|item| engine.engine().is_visible(item, principal.as_ref()))
+    .cloned()
+    .map(Json)
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Adds or replaces an item.
+async fn put_item<T, I>(
+  State(engine): State<SharedEngine<T, I>>,
+  Path(id): Path<String>,
+  Json(item): Json<T>,
+) -> Result<StatusCode, (StatusCode, String)>
+where
+  T: Searchable + Serialize + Send + Sync,
+  I: IndexAdapter<T>,
+{
+  let mut engine = engine.write().unwrap_or_else(PoisonError::into_inner);
+  engine
+    .put(id, item)
+    .map(|()| StatusCode::NO_CONTENT)
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+/// Removes an item.
+async fn delete_item<T, I>(
+  State(engine): State<SharedEngine<T, I>>,
+  Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)>
+where
+  T: Searchable + Serialize + Send + Sync,
+  I: IndexAdapter<T>,
+{
+  let mut engine = engine.write().unwrap_or_else(PoisonError::into_inner);
+  engine
+    .remove(&id)
+    .map(|()| StatusCode::NO_CONTENT)
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
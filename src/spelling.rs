@@ -0,0 +1,527 @@
+//! Spell correction based on the vocabulary of a corpus.
+//!
+//! This module implements a SymSpell-style corrector: every vocabulary term is
+//! indexed under all strings obtainable by deleting up to `max_distance`
+//! characters from it. At lookup time, the same deletions are generated for
+//! the input and matched against the index, which yields candidate corrections
+//! without comparing the input to every term in the vocabulary.
+
+use crate::document::{get_field_value, to_document};
+use crate::searchers::tokenizer::{tokenize, tokenize_with_offsets};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+/// A candidate correction for a term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+  /// The suggested vocabulary term.
+  pub term: String,
+  /// The edit distance between the input and the suggested term.
+  pub distance: usize,
+  /// How often the suggested term occurs in the corpus.
+  pub frequency: usize,
+}
+
+/// An alternative query text produced by [`SpellCorrector::suggest_text`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSuggestion {
+  /// The corrected text.
+  pub text: String,
+  /// The total edit distance of the corrections made to the text.
+  pub distance: usize,
+  /// The sum of the log frequencies of the corrected terms, used to rank
+  /// alternatives with the same distance.
+  pub weight: f64,
+}
+
+/// A SymSpell-style spell corrector built from a corpus vocabulary.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::spelling::SpellCorrector;
+///
+/// #[derive(serde::Serialize)]
+/// struct Post {
+///     title: String,
+/// }
+///
+/// let posts = vec![
+///     Post { title: "Getting started with Rust".into() },
+///     Post { title: "Rust programming patterns".into() },
+/// ];
+///
+/// let corrector = SpellCorrector::from_corpus(&posts, &["title"]);
+///
+/// assert_eq!(corrector.correct("progamming", 2), Some("programming".to_string()));
+/// assert_eq!(
+///     corrector.correct_text("rust progamming", 2),
+///     Some("rust programming".to_string())
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpellCorrector {
+  /// The maximum edit distance supported by the delete index.
+  max_distance: usize,
+  /// Terms shorter than this are never corrected.
+  min_term_length: usize,
+  /// Vocabulary terms and their frequencies.
+  words: HashMap<String, usize>,
+  /// Delete variants mapped to the vocabulary terms they originate from.
+  deletes: HashMap<String, Vec<String>>,
+}
+
+impl Default for SpellCorrector {
+  /// Creates an empty `SpellCorrector` with a maximum edit distance of 2.
+  fn default() -> Self {
+    Self::new(2)
+  }
+}
+
+impl SpellCorrector {
+  /// Creates an empty `SpellCorrector`.
+  ///
+  /// # Arguments
+  ///
+  /// * `max_distance` - The largest edit distance corrections may have.
+  ///   Larger values find more distant corrections at the cost of memory.
+  pub fn new(max_distance: usize) -> Self {
+    Self {
+      max_distance,
+      min_term_length: 3,
+      words: HashMap::new(),
+      deletes: HashMap::new(),
+    }
+  }
+
+  /// Builds a corrector from the text of the given fields of a corpus.
+  ///
+  /// Fields may be nested (dot notation) and may contain strings or arrays
+  /// of strings.
+  pub fn from_corpus<T: serde::Serialize>(items: &[T], fields: &[&str]) -> Self {
+    let mut corrector = Self::default();
+    corrector.add_corpus(items, fields);
+    corrector
+  }
+
+  /// Adds the text of the given fields of a corpus to the vocabulary.
+  pub fn add_corpus<T: serde::Serialize>(&mut self, items: &[T], fields: &[&str]) {
+    for item in items {
+      let value = match to_document(item) {
+        Ok(v) => v,
+        Err(_) => continue,
+      };
+
+      for field in fields {
+        match get_field_value(&value, field) {
+          Some(Value::String(text)) => self.add_text(text),
+          Some(Value::Array(values)) => {
+            for text in values.iter().filter_map(Value::as_str) {
+              self.add_text(text);
+            }
+          }
+          _ => {}
+        }
+      }
+    }
+  }
+
+  /// Tokenizes a text and adds each token to the vocabulary.
+  pub fn add_text(&mut self, text: &str) {
+    for token in tokenize(text) {
+      self.add_term(&token, 1);
+    }
+  }
+
+  /// Adds a term to the vocabulary, incrementing its frequency by `count`.
+  pub fn add_term(&mut self, term: &str, count: usize) {
+    let term = term.to_lowercase();
+    if let Some(frequency) = self.words.get_mut(&term) {
+      *frequency += count;
+      return;
+    }
+
+    for variant in deletes(&term, self.max_distance) {
+      self.deletes.entry(variant).or_default().push(term.clone());
+    }
+    self.words.insert(term, count);
+  }
+
+  /// Sets the minimum length a term must have to be corrected.
+  ///
+  /// Short terms have too many close neighbors for corrections to be
+  /// reliable. The default is 3.
+  pub fn min_term_length(mut self, min_term_length: usize) -> Self {
+    self.min_term_length = min_term_length;
+    self
+  }
+
+  /// Returns `true` if the term is part of the vocabulary.
+  pub fn contains(&self, term: &str) -> bool {
+    self.words.contains_key(&term.to_lowercase())
+  }
+
+  /// Returns the number of distinct terms in the vocabulary.
+  pub fn len(&self) -> usize {
+    self.words.len()
+  }
+
+  /// Returns `true` if the vocabulary is empty.
+  pub fn is_empty(&self) -> bool {
+    self.words.is_empty()
+  }
+
+  /// Finds vocabulary terms within `max_distance` edits of the input.
+  ///
+  /// # Returns
+  ///
+  /// The candidates sorted by edit distance, then by descending frequency.
+  pub fn lookup(&self, term: &str, max_distance: usize) -> Vec<Suggestion> {
+    let term = term.to_lowercase();
+    let max_distance = max_distance.min(self.max_distance);
+
+    if let Some(&frequency) = self.words.get(&term) {
+      return vec![Suggestion {
+        term,
+        distance: 0,
+        frequency,
+      }];
+    }
+
+    let mut seen = HashSet::new();
+    let mut suggestions = Vec::new();
+
+    for variant in deletes(&term, max_distance) {
+      let candidates = self
+        .words
+        .get_key_value(&variant)
+        .map(|(word, _)| word)
+        .into_iter()
+        .chain(self.deletes.get(&variant).into_iter().flatten());
+
+      for candidate in candidates {
+        if !seen.insert(candidate.clone()) {
+          continue;
+        }
+
+        let distance = edit_distance(&term, candidate);
+        if distance <= max_distance {
+          suggestions.push(Suggestion {
+            term: candidate.clone(),
+            distance,
+            frequency: self.words[candidate],
+          });
+        }
+      }
+    }
+
+    suggestions.sort_by(|a, b| {
+      a.distance
+        .cmp(&b.distance)
+        .then(b.frequency.cmp(&a.frequency))
+        .then(a.term.cmp(&b.term))
+    });
+    suggestions
+  }
+
+  /// Returns the best correction for a term that is not in the vocabulary.
+  ///
+  /// Returns `None` if the term is known, too short, contains digits, or has
+  /// no vocabulary term within `max_distance` edits.
+  pub fn correct(&self, term: &str, max_distance: usize) -> Option<String> {
+    self
+      .candidates(term, max_distance)
+      .into_iter()
+      .next()
+      .map(|s| s.term)
+  }
+
+  /// Returns the corrections of a term that is not in the vocabulary, or an
+  /// empty list if the term should not be corrected.
+  fn candidates(&self, term: &str, max_distance: usize) -> Vec<Suggestion> {
+    if term.chars().count() < self.min_term_length
+      || term.chars().any(|c| c.is_numeric())
+      || self.contains(term)
+    {
+      return Vec::new();
+    }
+
+    self.lookup(term, max_distance)
+  }
+
+  /// Corrects every misspelled term of a text.
+  ///
+  /// Only the misspelled words are replaced, so the rest of the text keeps
+  /// its case, punctuation, and quotes.
+  ///
+  /// # Returns
+  ///
+  /// The corrected text if at least one term was corrected, or `None` if the
+  /// text needs no correction.
+  pub fn correct_text(&self, text: &str, max_distance: usize) -> Option<String> {
+    let tokens = tokenize_with_offsets(text);
+    let mut changed = false;
+    let terms: Vec<String> = tokens
+      .iter()
+      .map(|(token, _)| match self.correct(token, max_distance) {
+        Some(correction) => {
+          changed = true;
+          correction
+        }
+        None => token.clone(),
+      })
+      .collect();
+
+    changed.then(|| replace_terms(text, &tokens, &terms))
+  }
+
+  /// Produces up to `count` alternative corrections of a text.
+  ///
+  /// Each misspelled term is replaced by one of its candidate corrections,
+  /// and the combinations are ranked by total edit distance, then by the
+  /// frequency of the corrected terms in the corpus.
+  ///
+  /// # Arguments
+  ///
+  /// * `text` - The text to correct.
+  /// * `max_distance` - The largest edit distance of a single correction.
+  /// * `count` - The maximum number of alternatives to return.
+  ///
+  /// # Returns
+  ///
+  /// The alternatives, best first. The list is empty if the text needs no
+  /// correction.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::spelling::SpellCorrector;
+  ///
+  /// let mut corrector = SpellCorrector::default();
+  /// corrector.add_text("a fast cat, a fast car, a cat nap, a cat, a car, a cap");
+  ///
+  /// let alternatives: Vec<String> = corrector
+  ///     .suggest_text("fast cay", 1, 3)
+  ///     .into_iter()
+  ///     .map(|s| s.text)
+  ///     .collect();
+  ///
+  /// assert_eq!(alternatives, vec!["fast cat", "fast car", "fast cap"]);
+  /// ```
+  pub fn suggest_text(&self, text: &str, max_distance: usize, count: usize) -> Vec<TextSuggestion> {
+    if count == 0 {
+      return Vec::new();
+    }
+
+    let tokens = tokenize_with_offsets(text);
+    let mut beam = vec![(Vec::new(), 0, 0.0)];
+    let mut changed = false;
+
+    for (token, _) in &tokens {
+      let mut options: Vec<(String, usize, f64)> = self
+        .candidates(token, max_distance)
+        .into_iter()
+        .take(count)
+        .map(|s| (s.term, s.distance, (s.frequency as f64).ln_1p()))
+        .collect();
+      if options.is_empty() {
+        options.push((token.clone(), 0, 0.0));
+      } else {
+        changed = true;
+      }
+
+      let mut next = Vec::with_capacity(beam.len() * options.len());
+      for (terms, distance, weight) in &beam {
+        for (term, term_distance, term_weight) in &options {
+          let mut terms: Vec<String> = terms.clone();
+          terms.push(term.clone());
+          next.push((terms, distance + term_distance, weight + term_weight));
+        }
+      }
+
+      next.sort_by(|a, b| {
+        a.1
+          .cmp(&b.1)
+          .then(b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal))
+      });
+      next.truncate(count);
+      beam = next;
+    }
+
+    if !changed {
+      return Vec::new();
+    }
+
+    beam
+      .into_iter()
+      .map(|(terms, distance, weight)| TextSuggestion {
+        text: replace_terms(text, &tokens, &terms),
+        distance,
+        weight,
+      })
+      .collect()
+  }
+}
+
+/// Replaces the tokens of a text whose term was corrected, keeping the rest
+/// of the text as it is. Each correction takes the case of the word it
+/// replaces.
+fn replace_terms(text: &str, tokens: &[(String, Range<usize>)], terms: &[String]) -> String {
+  let mut result = String::with_capacity(text.len());
+  let mut end = 0;
+  for ((token, range), term) in tokens.iter().zip(terms) {
+    if term == token {
+      continue;
+    }
+    result.push_str(&text[end..range.start]);
+    result.push_str(&match_case(&text[range.clone()], term));
+    end = range.end;
+  }
+  result.push_str(&text[end..]);
+  result
+}
+
+/// Gives a term the case of a word: upper case for an upper-case word, a
+/// capital for a capitalized one, and lower case otherwise.
+fn match_case(word: &str, term: &str) -> String {
+  let mut chars = word.chars();
+  if !chars.next().is_some_and(char::is_uppercase) {
+    return term.to_string();
+  }
+  if chars.any(char::is_lowercase) {
+    let mut term_chars = term.chars();
+    return term_chars
+      .next()
+      .map(|first| first.to_uppercase().chain(term_chars).collect())
+      .unwrap_or_default();
+  }
+  term.to_uppercase()
+}
+
+/// Generates all strings obtainable by deleting up to `max_distance`
+/// characters from a term, including the term itself.
+fn deletes(term: &str, max_distance: usize) -> HashSet<String> {
+  let mut result = HashSet::new();
+  result.insert(term.to_string());
+
+  let mut frontier = vec![term.chars().collect::<Vec<char>>()];
+  for _ in 0..max_distance {
+    let mut next = Vec::new();
+    for chars in &frontier {
+      for i in 0..chars.len() {
+        let mut variant = chars.clone();
+        variant.remove(i);
+        if result.insert(variant.iter().collect()) {
+          next.push(variant);
+        }
+      }
+    }
+    frontier = next;
+  }
+
+  result
+}
+
+/// Calculates the optimal string alignment distance between two strings.
+///
+/// This is the Levenshtein distance extended with transpositions of adjacent
+/// characters, which covers the most common typing mistakes.
+fn edit_distance(a: &str, b: &str) -> usize {
+  let a: Vec<char> = a.chars().collect();
+  let b: Vec<char> = b.chars().collect();
+  let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+
+  for (i, row) in d.iter_mut().enumerate() {
+    row[0] = i;
+  }
+  for (j, cell) in d[0].iter_mut().enumerate() {
+    *cell = j;
+  }
+
+  for i in 1..=a.len() {
+    for j in 1..=b.len() {
+      let cost = usize::from(a[i - 1] != b[j - 1]);
+      d[i][j] = (d[i - 1][j] + 1)
+        .min(d[i][j - 1] + 1)
+        .min(d[i - 1][j - 1] + cost);
+      if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+        d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+      }
+    }
+  }
+
+  d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_edit_distance() {
+    assert_eq!(edit_distance("rust", "rust"), 0);
+    assert_eq!(edit_distance("rust", "rsut"), 1);
+    assert_eq!(edit_distance("search", "serch"), 1);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+  }
+
+  #[test]
+  fn test_lookup_prefers_frequent_terms() {
+    let mut corrector = SpellCorrector::new(2);
+    corrector.add_term("search", 10);
+    corrector.add_term("march", 1);
+
+    let suggestions = corrector.lookup("serch", 2);
+    assert_eq!(suggestions[0].term, "search");
+    assert_eq!(suggestions[0].distance, 1);
+  }
+
+  #[test]
+  fn test_suggest_text_ranks_combinations() {
+    let mut corrector = SpellCorrector::new(2);
+    corrector.add_term("rust", 5);
+    corrector.add_term("rash", 1);
+    corrector.add_term("language", 3);
+
+    let alternatives = corrector.suggest_text("rast langauge", 1, 2);
+    assert_eq!(alternatives.len(), 2);
+    assert_eq!(alternatives[0].text, "rust language");
+    assert_eq!(alternatives[0].distance, 2);
+    assert_eq!(alternatives[1].text, "rash language");
+
+    assert!(corrector.suggest_text("rust language", 1, 2).is_empty());
+  }
+
+  #[test]
+  fn test_correct_text_keeps_the_rest_of_the_text() {
+    let mut corrector = SpellCorrector::new(2);
+    corrector.add_text("rust programming language");
+
+    assert_eq!(
+      corrector.correct_text("Rust \"progamming\"  for the LANGAUGE-curious!", 2),
+      Some("Rust \"programming\"  for the LANGUAGE-curious!".to_string())
+    );
+    assert_eq!(
+      corrector.correct_text("Progamming in rust", 2),
+      Some("Programming in rust".to_string())
+    );
+    assert_eq!(corrector.correct_text("Rust, programming!", 2), None);
+
+    let alternatives = corrector.suggest_text("\"Rust progamming\"", 2, 1);
+    assert_eq!(alternatives[0].text, "\"Rust programming\"");
+  }
+
+  #[test]
+  fn test_correct_skips_known_and_short_terms() {
+    let mut corrector = SpellCorrector::new(2);
+    corrector.add_text("rust is a systems language");
+
+    assert_eq!(corrector.correct("rust", 2), None);
+    assert_eq!(corrector.correct("iz", 2), None);
+    assert_eq!(
+      corrector.correct("langauge", 2),
+      Some("language".to_string())
+    );
+  }
+}
@@ -0,0 +1,269 @@
+//! A weighted synonym dictionary for query expansion.
+//!
+//! A [`SynonymMap`] maps a term to a list of synonyms, each with a weight in
+//! `(0, 1]` describing how strongly a match on the synonym should count
+//! compared to a match on the original term. The map can be used inside
+//! [`SemanticSearch`](crate::searchers::SemanticSearch) (weighted scoring) or
+//! as a query rewrite through
+//! [`SynonymExtension`](crate::extensions::SynonymExtension).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A dictionary of terms to their weighted synonyms.
+///
+/// All terms are stored in lowercase, matching the output of the built-in
+/// tokenizer.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::synonyms::SynonymMap;
+///
+/// let synonyms = SynonymMap::new()
+///     .with("car", "automobile", 0.8)
+///     .with("car", "auto", 0.5);
+///
+/// let expanded = synonyms.expand(&["car".to_string()]);
+/// assert!(expanded.contains(&("automobile".to_string(), 0.8)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "SynonymEntries")]
+pub struct SynonymMap {
+  /// Internal representation: term -> (synonym -> weight)
+  entries: HashMap<String, HashMap<String, f32>>,
+}
+
+/// The serialized form of a [`SynonymMap`], whose entries are inserted one
+/// by one when it is deserialized, so their weights are checked.
+#[derive(Deserialize)]
+struct SynonymEntries {
+  entries: HashMap<String, HashMap<String, f32>>,
+}
+
+impl From<SynonymEntries> for SynonymMap {
+  fn from(serialized: SynonymEntries) -> Self {
+    let mut map = Self::new();
+    for (term, synonyms) in serialized.entries {
+      for (synonym, weight) in synonyms {
+        map.insert(&term, synonym, weight);
+      }
+    }
+    map
+  }
+}
+
+/// The accepted shapes of a single entry in the JSON format.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonEntry {
+  /// A list of synonyms, all at full weight.
+  List(Vec<String>),
+  /// A map of synonyms to their weights.
+  Weighted(HashMap<String, f32>),
+}
+
+impl SynonymMap {
+  /// Creates a new, empty `SynonymMap`.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a one-way synonym with the given weight.
+  ///
+  /// Weights above `1.0` are lowered to `1.0`, and synonyms whose weight is
+  /// not positive are ignored, as they could never match. If the synonym
+  /// already exists for the term, the higher weight is kept.
+  pub fn insert(&mut self, term: impl AsRef<str>, synonym: impl AsRef<str>, weight: f32) {
+    let term = term.as_ref().to_lowercase();
+    let synonym = synonym.as_ref().to_lowercase();
+    if term == synonym || weight.is_nan() || weight <= 0.0 {
+      return;
+    }
+    let weight = weight.min(1.0);
+
+    self
+      .entries
+      .entry(term)
+      .or_default()
+      .entry(synonym)
+      .and_modify(|w| *w = w.max(weight))
+      .or_insert(weight);
+  }
+
+  /// Adds a one-way synonym in a chained manner.
+  pub fn with(mut self, term: impl AsRef<str>, synonym: impl AsRef<str>, weight: f32) -> Self {
+    self.insert(term, synonym, weight);
+    self
+  }
+
+  /// Declares a group of terms that are all synonyms of each other.
+  pub fn with_group(mut self, terms: &[&str], weight: f32) -> Self {
+    for term in terms {
+      for other in terms {
+        self.insert(term, other, weight);
+      }
+    }
+    self
+  }
+
+  /// Returns the synonyms of a term, if any.
+  pub fn get(&self, term: &str) -> Option<&HashMap<String, f32>> {
+    self.entries.get(&term.to_lowercase())
+  }
+
+  /// Returns an iterator over the terms of the map and their synonyms.
+  #[cfg(feature = "semantic")]
+  pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &HashMap<String, f32>)> {
+    self.entries.iter()
+  }
+
+  /// Returns `true` if the map contains no synonyms.
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  /// Expands a list of terms into their weighted synonyms.
+  ///
+  /// Terms that are already part of the input are not returned. If a synonym
+  /// is reachable from several input terms, the highest weight is kept.
+  ///
+  /// # Returns
+  ///
+  /// A vector of `(synonym, weight)` pairs, sorted by synonym.
+  pub fn expand(&self, terms: &[String]) -> Vec<(String, f32)> {
+    let mut expanded: HashMap<String, f32> = HashMap::new();
+
+    for term in terms {
+      if let Some(synonyms) = self.entries.get(term) {
+        for (synonym, &weight) in synonyms {
+          if terms.contains(synonym) {
+            continue;
+          }
+          expanded
+            .entry(synonym.clone())
+            .and_modify(|w| *w = w.max(weight))
+            .or_insert(weight);
+        }
+      }
+    }
+
+    let mut expanded: Vec<(String, f32)> = expanded.into_iter().collect();
+    expanded.sort_by(|a, b| a.0.cmp(&b.0));
+    expanded
+  }
+
+  /// Parses a `SynonymMap` from a simple line-based text format.
+  ///
+  /// Each non-empty line that does not start with `#` is either:
+  ///
+  /// - An equivalence group: `car, automobile, auto` (all terms are synonyms
+  ///   of each other at full weight).
+  /// - A one-way mapping: `car => automobile:0.8, auto:0.5` (the weight is
+  ///   optional and defaults to `1.0`).
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::synonyms::SynonymMap;
+  ///
+  /// let synonyms = SynonymMap::from_text(
+  ///     "# vehicles\n\
+  ///      car => automobile:0.8, auto\n\
+  ///      tv, television",
+  /// ).unwrap();
+  ///
+  /// assert_eq!(synonyms.get("car").unwrap()["automobile"], 0.8);
+  /// assert_eq!(synonyms.get("television").unwrap()["tv"], 1.0);
+  /// ```
+  pub fn from_text(text: &str) -> Result<Self, String> {
+    let mut map = Self::new();
+
+    for (number, line) in text.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      if let Some((term, synonyms)) = line.split_once("=>") {
+        let term = term.trim();
+        if term.is_empty() {
+          return Err(format!("line {}: missing term before '=>'", number + 1));
+        }
+
+        for entry in synonyms.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+          let (synonym, weight) = match entry.rsplit_once(':') {
+            Some((synonym, weight)) => {
+              let weight: f32 = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("line {}: invalid weight '{}'", number + 1, weight))?;
+              (synonym.trim(), weight)
+            }
+            None => (entry, 1.0),
+          };
+          validate_weight(weight).map_err(|e| format!("line {}: {}", number + 1, e))?;
+          map.insert(term, synonym, weight);
+        }
+      } else {
+        let group: Vec<&str> = line
+          .split(',')
+          .map(str::trim)
+          .filter(|t| !t.is_empty())
+          .collect();
+        map = map.with_group(&group, 1.0);
+      }
+    }
+
+    Ok(map)
+  }
+
+  /// Parses a `SynonymMap` from JSON.
+  ///
+  /// The JSON must be an object mapping each term either to a list of
+  /// synonyms (full weight) or to an object of synonyms and their weights.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::synonyms::SynonymMap;
+  ///
+  /// let synonyms = SynonymMap::from_json(
+  ///     r#"{ "car": { "automobile": 0.8 }, "tv": ["television"] }"#,
+  /// ).unwrap();
+  ///
+  /// assert_eq!(synonyms.get("tv").unwrap()["television"], 1.0);
+  /// ```
+  pub fn from_json(json: &str) -> Result<Self, String> {
+    let entries: HashMap<String, JsonEntry> =
+      serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+    let mut map = Self::new();
+    for (term, entry) in entries {
+      match entry {
+        JsonEntry::List(synonyms) => {
+          for synonym in synonyms {
+            map.insert(&term, synonym, 1.0);
+          }
+        }
+        JsonEntry::Weighted(synonyms) => {
+          for (synonym, weight) in synonyms {
+            validate_weight(weight).map_err(|e| format!("'{}': {}", term, e))?;
+            map.insert(&term, synonym, weight);
+          }
+        }
+      }
+    }
+
+    Ok(map)
+  }
+}
+
+/// Checks that a synonym weight lies within `(0, 1]`.
+fn validate_weight(weight: f32) -> Result<(), String> {
+  if weight > 0.0 && weight <= 1.0 {
+    Ok(())
+  } else {
+    Err(format!("weight {} must be within (0, 1]", weight))
+  }
+}
@@ -0,0 +1,248 @@
+//! Helpers for writing regression tests of ranking behavior.
+//!
+//! [`assert_results`] wraps a result set and lets tests declare properties it
+//! must satisfy: required and forbidden members, ordering constraints between
+//! items, exact top-n orderings, and score ranges. All expectations are checked
+//! together, and a failure reports every violated expectation along with the
+//! actual ranking, so a change in relevance is easy to diagnose.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use searus::prelude::*;
+//! use searus::testing::assert_results;
+//!
+//! #[derive(Debug, Clone, serde::Serialize)]
+//! struct Doc {
+//!     id: String,
+//!     title: String,
+//! }
+//!
+//! let docs = vec![
+//!     Doc { id: "intro".into(), title: "Rust".into() },
+//!     Doc { id: "guide".into(), title: "Rust and more rust".into() },
+//!     Doc { id: "python".into(), title: "Python".into() },
+//! ];
+//!
+//! let rules = SemanticRules::builder().field("title", FieldRule::tokenized()).build();
+//! let engine = SearusEngine::builder()
+//!     .with(Box::new(SemanticSearch::new(rules)))
+//!     .build();
+//! let results = engine.search(&docs, &Query::builder().text("rust").build());
+//!
+//! assert_results(&results, |doc: &Doc| doc.id.clone())
+//!     .len(2)
+//!     .top(&["guide"])
+//!     .ranks_before("guide", "intro")
+//!     .excludes("python")
+//!     .score_between("guide", 0.9, 1.0)
+//!     .assert();
+//! ```
+
+use crate::types::{Searchable, SearusMatch};
+use std::fmt::Write;
+
+/// Starts a set of expectations on a result set.
+///
+/// # Arguments
+///
+/// * `results` - The results to check, in ranked order.
+/// * `key` - A function returning a readable identifier for an item.
+pub fn assert_results<T, F>(results: &[SearusMatch<T>], key: F) -> ResultAssertions
+where
+  T: Searchable,
+  F: Fn(&T) -> String,
+{
+  ResultAssertions {
+    ranking: results.iter().map(|m| (key(&m.item), m.score)).collect(),
+    failures: Vec::new(),
+  }
+}
+
+/// A set of expectations on a result set, built with [`assert_results`].
+///
+/// Expectations are evaluated as they are added; call [`assert`](Self::assert)
+/// to panic if any of them failed, or [`check`](Self::check) to get the
+/// failure report as an error instead.
+#[must_use = "expectations are only reported by `assert` or `check`"]
+pub struct ResultAssertions {
+  /// The key and score of each result, in ranked order.
+  ranking: Vec<(String, f32)>,
+  /// A description of each failed expectation.
+  failures: Vec<String>,
+}
+
+impl ResultAssertions {
+  /// Returns the rank (0-based) of the item with the given key.
+  fn rank(&self, key: &str) -> Option<usize> {
+    self.ranking.iter().position(|(k, _)| k == key)
+  }
+
+  /// Records a failure unless the condition holds.
+  fn expect(mut self, condition: bool, failure: impl FnOnce() -> String) -> Self {
+    if !condition {
+      self.failures.push(failure());
+    }
+    self
+  }
+
+  /// Expects the result set to have exactly `len` results.
+  pub fn len(self, len: usize) -> Self {
+    let actual = self.ranking.len();
+    self.expect(actual == len, || {
+      format!("expected {} results, found {}", len, actual)
+    })
+  }
+
+  /// Expects an item to be part of the results.
+  pub fn contains(self, key: &str) -> Self {
+    let found = self.rank(key).is_some();
+    self.expect(found, || format!("expected \"{}\" in the results", key))
+  }
+
+  /// Expects an item not to be part of the results.
+  pub fn excludes(self, key: &str) -> Self {
+    let rank = self.rank(key);
+    self.expect(rank.is_none(), || {
+      format!(
+        "expected \"{}\" not to be in the results, found at rank {}",
+        key,
+        rank.unwrap_or_default() + 1
+      )
+    })
+  }
+
+  /// Expects item `a` to be ranked above item `b`.
+  ///
+  /// Both items must be part of the results.
+  pub fn ranks_before(self, a: &str, b: &str) -> Self {
+    let (rank_a, rank_b) = (self.rank(a), self.rank(b));
+    self.expect(
+      matches!((rank_a, rank_b), (Some(x), Some(y)) if x < y),
+      || {
+        format!(
+          "expected \"{}\" (rank {}) before \"{}\" (rank {})",
+          a,
+          display_rank(rank_a),
+          b,
+          display_rank(rank_b)
+        )
+      },
+    )
+  }
+
+  /// Expects the results to start with the given items, in this order.
+  pub fn top(self, keys: &[&str]) -> Self {
+    let actual: Vec<String> = self
+      .ranking
+      .iter()
+      .take(keys.len())
+      .map(|(key, _)| key.clone())
+      .collect();
+    self.expect(actual == keys, || {
+      let mut diff = format!("expected top {} results to be:\n", keys.len());
+      for (rank, &expected) in keys.iter().enumerate() {
+        let found = actual.get(rank).map(String::as_str).unwrap_or("<none>");
+        let marker = if expected == found { ' ' } else { '!' };
+        let _ = writeln!(
+          diff,
+          "  {} {:>3}. {:<24} | {}",
+          marker,
+          rank + 1,
+          expected,
+          found
+        );
+      }
+      diff
+    })
+  }
+
+  /// Expects the score of an item to lie within `[min, max]`.
+  pub fn score_between(self, key: &str, min: f32, max: f32) -> Self {
+    let score = self.rank(key).map(|rank| self.ranking[rank].1);
+    self.expect(
+      matches!(score, Some(s) if s >= min && s <= max),
+      || match score {
+        Some(score) => format!(
+          "expected the score of \"{}\" to be in [{}, {}], found {}",
+          key, min, max, score
+        ),
+        None => format!("expected \"{}\" in the results to check its score", key),
+      },
+    )
+  }
+
+  /// Returns an error describing every failed expectation and the actual ranking.
+  pub fn check(self) -> Result<(), String> {
+    if self.failures.is_empty() {
+      return Ok(());
+    }
+
+    let mut report = format!("{} result expectation(s) failed:\n", self.failures.len());
+    for failure in &self.failures {
+      let _ = writeln!(report, "- {}", failure.trim_end());
+    }
+
+    let _ = writeln!(report, "actual ranking:");
+    for (rank, (key, score)) in self.ranking.iter().enumerate() {
+      let _ = writeln!(report, "  {:>3}. {:<24} {:.4}", rank + 1, key, score);
+    }
+
+    Err(report)
+  }
+
+  /// Panics with a readable report if any expectation failed.
+  #[track_caller]
+  pub fn assert(self) {
+    if let Err(report) = self.check() {
+      panic!("{}", report);
+    }
+  }
+}
+
+/// Formats an optional 0-based rank as a 1-based rank.
+fn display_rank(rank: Option<usize>) -> String {
+  rank
+    .map(|rank| (rank + 1).to_string())
+    .unwrap_or_else(|| "missing".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn results() -> Vec<SearusMatch<String>> {
+    vec![
+      SearusMatch::new("a".to_string(), 1.0, 0),
+      SearusMatch::new("b".to_string(), 0.5, 1),
+    ]
+  }
+
+  #[test]
+  fn test_passing_expectations() {
+    let results = results();
+    assert_results(&results, |s: &String| s.clone())
+      .len(2)
+      .contains("a")
+      .excludes("c")
+      .ranks_before("a", "b")
+      .top(&["a", "b"])
+      .score_between("b", 0.4, 0.6)
+      .assert();
+  }
+
+  #[test]
+  fn test_failure_report() {
+    let results = results();
+    let report = assert_results(&results, |s: &String| s.clone())
+      .ranks_before("b", "a")
+      .top(&["b"])
+      .contains("c")
+      .check()
+      .unwrap_err();
+
+    assert!(report.starts_with("3 result expectation(s) failed"));
+    assert!(report.contains("expected \"b\" (rank 2) before \"a\" (rank 1)"));
+    assert!(report.contains("actual ranking:"));
+  }
+}
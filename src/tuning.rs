@@ -0,0 +1,428 @@
+//! Relevance tuning from click logs.
+//!
+//! This module closes the loop between the [`eval`](crate::eval) metrics and
+//! the knobs of an engine: it turns logged clicks into judged queries, and
+//! searches for the searcher weights and field boosts that rank the clicked
+//! results highest.
+//!
+//! Each clicked item is graded by the number of times it was clicked for its
+//! query. The most recent queries of the log are held out: the parameters are
+//! chosen on the others, and the report gives their metrics on the held-out
+//! queries, so that a tuning that only fits the training clicks shows up as
+//! no improvement over the baseline.
+//!
+//! The search is a coordinate ascent over a grid of candidate values: each
+//! parameter in turn is set to the candidate with the best mean NDCG, the
+//! others being fixed, until a round changes no parameter.
+
+use crate::engine::SearusEngine;
+use crate::eval::{Evaluator, JudgedQuery, Metrics};
+use crate::types::{Query, Searchable, SearcherKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A logged search and the result clicked for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Click {
+  /// The query that was searched.
+  pub query: Query,
+  /// The key of the clicked item, as produced by the tuner's key function.
+  pub clicked: String,
+}
+
+impl Click {
+  /// Creates a new `Click`.
+  pub fn new(query: Query, clicked: impl Into<String>) -> Self {
+    Self {
+      query,
+      clicked: clicked.into(),
+    }
+  }
+}
+
+/// Groups clicks by query into judged queries.
+///
+/// Each clicked item is graded by its number of clicks for the query.
+/// Queries are compared by their JSON form and returned in the order of
+/// their first click.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::tuning::{judgments_from_clicks, Click};
+///
+/// let rust = Query::builder().text("rust").build();
+/// let clicks = vec![
+///     Click::new(rust.clone(), "a"),
+///     Click::new(Query::builder().text("python").build(), "c"),
+///     Click::new(rust, "a"),
+/// ];
+///
+/// let judged = judgments_from_clicks(&clicks);
+/// assert_eq!(judged.len(), 2);
+/// assert_eq!(judged[0].grade("a"), 2.0);
+/// assert_eq!(judged[1].grade("c"), 1.0);
+/// ```
+pub fn judgments_from_clicks(clicks: &[Click]) -> Vec<JudgedQuery> {
+  let mut judged: Vec<JudgedQuery> = Vec::new();
+  let mut positions: HashMap<String, usize> = HashMap::new();
+
+  for click in clicks {
+    let key = serde_json::to_string(&click.query).unwrap_or_default();
+    let position = *positions.entry(key).or_insert_with(|| {
+      judged.push(JudgedQuery::new(click.query.clone()));
+      judged.len() - 1
+    });
+    *judged[position]
+      .judgments
+      .entry(click.clicked.clone())
+      .or_insert(0.0) += 1.0;
+  }
+
+  judged
+}
+
+/// The parameters found by a [`Tuner`], and how well they rank.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TuningReport {
+  /// The tuned weight of each searcher kind. They can be set on an engine
+  /// with [`SearusEngineBuilder::default_weights`](crate::engine::SearusEngineBuilder::default_weights).
+  pub weights: HashMap<SearcherKind, f32>,
+  /// The tuned boost of each field.
+  pub boosts: HashMap<String, f32>,
+  /// The mean metrics of the starting parameters on the held-out queries.
+  pub baseline: Metrics,
+  /// The mean metrics of the tuned parameters on the training queries.
+  pub train: Metrics,
+  /// The mean metrics of the tuned parameters on the held-out queries.
+  pub held_out: Metrics,
+  /// The number of queries held out.
+  pub held_out_queries: usize,
+  /// The number of parameter settings evaluated.
+  pub evaluations: usize,
+}
+
+impl TuningReport {
+  /// Returns the change of the held-out NDCG from the baseline to the tuned
+  /// parameters. Positive values mean the tuned parameters rank better.
+  pub fn improvement(&self) -> f32 {
+    self.held_out.ndcg - self.baseline.ndcg
+  }
+}
+
+/// A tuned parameter and its candidate values.
+enum Parameter {
+  /// The weight of a kind of searcher, set in the options of each query.
+  Weight(SearcherKind, Vec<f32>),
+  /// The boost of a field, passed to the function building the engine.
+  Boost(String, Vec<f32>),
+}
+
+impl Parameter {
+  fn candidates(&self) -> &[f32] {
+    match self {
+      Parameter::Weight(_, candidates) | Parameter::Boost(_, candidates) => candidates,
+    }
+  }
+}
+
+/// Searches for the searcher weights and field boosts that best rank the
+/// clicked results of a click log.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use searus::tuning::{Click, Tuner};
+///
+/// #[derive(Debug, Clone, serde::Serialize)]
+/// struct Doc {
+///     id: String,
+///     title: String,
+///     body: String,
+/// }
+///
+/// let docs = vec![
+///     Doc { id: "a".into(), title: "Rust".into(), body: "Python tips".into() },
+///     Doc { id: "b".into(), title: "Python".into(), body: "Rust tips".into() },
+/// ];
+///
+/// // Users click the documents whose body matches.
+/// let clicks = vec![
+///     Click::new(Query::builder().text("rust").build(), "b"),
+///     Click::new(Query::builder().text("python").build(), "a"),
+///     Click::new(Query::builder().text("tips rust").build(), "b"),
+/// ];
+///
+/// let report = Tuner::new(|doc: &Doc| doc.id.clone())
+///     .k(2)
+///     .boost("title", [2.0, 0.1])
+///     .tune(&docs, &clicks, |boosts| {
+///         let rules = SemanticRules::builder()
+///             .field("title", FieldRule::tokenized().boost(boosts["title"]))
+///             .field("body", FieldRule::tokenized())
+///             .build();
+///         SearusEngine::builder()
+///             .with(Box::new(SemanticSearch::new(rules)))
+///             .build()
+///     });
+///
+/// assert_eq!(report.boosts["title"], 0.1);
+/// assert!(report.improvement() > 0.0);
+/// ```
+pub struct Tuner<T> {
+  /// Ranks the queries and computes their metrics.
+  evaluator: Evaluator<T>,
+  /// The tuned parameters.
+  parameters: Vec<Parameter>,
+  /// The fraction of the queries held out.
+  holdout: f32,
+  /// The maximum number of rounds over the parameters.
+  max_rounds: usize,
+}
+
+impl<T: Searchable> Tuner<T> {
+  /// Creates a new `Tuner` with a cutoff of 10, holding out 20% of the
+  /// queries.
+  ///
+  /// # Arguments
+  ///
+  /// * `key` - A function returning the key identifying an item in the
+  ///   results and in the clicks.
+  pub fn new<F>(key: F) -> Self
+  where
+    F: Fn(&T) -> String + Send + Sync + 'static,
+  {
+    Self {
+      evaluator: Evaluator::new(key),
+      parameters: Vec::new(),
+      holdout: 0.2,
+      max_rounds: 10,
+    }
+  }
+
+  /// Sets the cutoff `k` the rankings are evaluated at.
+  pub fn k(mut self, k: usize) -> Self {
+    self.evaluator = self.evaluator.k(k);
+    self
+  }
+
+  /// Tunes the weight of a kind of searcher among the candidate values.
+  ///
+  /// The weight is set in the options of every query, so it overrides the
+  /// weights the logged queries had. The first candidate is the starting
+  /// value.
+  pub fn weight<I>(mut self, kind: SearcherKind, candidates: I) -> Self
+  where
+    I: IntoIterator<Item = f32>,
+  {
+    let candidates: Vec<f32> = candidates.into_iter().collect();
+    if !candidates.is_empty() {
+      self.parameters.push(Parameter::Weight(kind, candidates));
+    }
+    self
+  }
+
+  /// Tunes the boost of a field among the candidate values.
+  ///
+  /// Boosts are passed by field to the function building the engine, which
+  /// applies them to its rules. The first candidate is the starting value.
+  pub fn boost<I>(mut self, field: impl Into<String>, candidates: I) -> Self
+  where
+    I: IntoIterator<Item = f32>,
+  {
+    let candidates: Vec<f32> = candidates.into_iter().collect();
+    if !candidates.is_empty() {
+      self
+        .parameters
+        .push(Parameter::Boost(field.into(), candidates));
+    }
+    self
+  }
+
+  /// Sets the fraction of the queries held out, 0.2 by default.
+  ///
+  /// The held-out queries are the last ones of the log. At least one query
+  /// is kept for training, and none is held out if the log has a single
+  /// query.
+  pub fn holdout(mut self, fraction: f32) -> Self {
+    self.holdout = fraction.clamp(0.0, 1.0);
+    self
+  }
+
+  /// Sets the maximum number of rounds over the parameters, 10 by default.
+  pub fn max_rounds(mut self, rounds: usize) -> Self {
+    self.max_rounds = rounds.max(1);
+    self
+  }
+
+  /// Finds the parameters that best rank the clicked results.
+  ///
+  /// # Arguments
+  ///
+  /// * `items` - The items searched.
+  /// * `clicks` - The click log, in chronological order.
+  /// * `build` - Builds an engine applying the given field boosts.
+  pub fn tune<F>(&self, items: &[T], clicks: &[Click], build: F) -> TuningReport
+  where
+    T: Clone + Serialize,
+    F: Fn(&HashMap<String, f32>) -> SearusEngine<T>,
+  {
+    let judged = judgments_from_clicks(clicks);
+    let held_out_queries = self.held_out_count(judged.len());
+    let (train, held_out) = judged.split_at(judged.len() - held_out_queries);
+
+    let mut values: Vec<f32> = self.parameters.iter().map(|p| p.candidates()[0]).collect();
+    let mut evaluations = 0;
+    let mut evaluate = |values: &[f32], engine: &SearusEngine<T>, queries: &[JudgedQuery]| {
+      evaluations += 1;
+      self.evaluate(values, engine, items, queries)
+    };
+
+    let engine = build(&self.boosts(&values));
+    let baseline = evaluate(&values, &engine, held_out);
+    let mut best = evaluate(&values, &engine, train);
+
+    for _ in 0..self.max_rounds {
+      let mut changed = false;
+      for (i, parameter) in self.parameters.iter().enumerate() {
+        let mut candidate_values = values.clone();
+        // Weights don't change the engine, so it is built once for all of
+        // their candidates.
+        let engine =
+          matches!(parameter, Parameter::Weight(..)).then(|| build(&self.boosts(&values)));
+        for &candidate in parameter.candidates() {
+          if candidate == values[i] {
+            continue;
+          }
+          candidate_values[i] = candidate;
+          let metrics = match &engine {
+            Some(engine) => evaluate(&candidate_values, engine, train),
+            None => evaluate(
+              &candidate_values,
+              &build(&self.boosts(&candidate_values)),
+              train,
+            ),
+          };
+          if metrics.ndcg > best.ndcg + 1e-6 {
+            best = metrics;
+            values[i] = candidate;
+            changed = true;
+          }
+        }
+      }
+      if !changed {
+        break;
+      }
+    }
+
+    let engine = build(&self.boosts(&values));
+    let held_out = evaluate(&values, &engine, held_out);
+
+    TuningReport {
+      weights: self.weights(&values),
+      boosts: self.boosts(&values),
+      baseline,
+      train: best,
+      held_out,
+      held_out_queries,
+      evaluations,
+    }
+  }
+
+  /// Returns the number of queries held out of `len`.
+  fn held_out_count(&self, len: usize) -> usize {
+    if len < 2 {
+      return 0;
+    }
+    ((len as f32 * self.holdout).round() as usize).clamp(usize::from(self.holdout > 0.0), len - 1)
+  }
+
+  /// Returns the mean metrics of the queries, with the weights of the given
+  /// parameter values.
+  fn evaluate(
+    &self,
+    values: &[f32],
+    engine: &SearusEngine<T>,
+    items: &[T],
+    queries: &[JudgedQuery],
+  ) -> Metrics
+  where
+    T: Clone + Serialize,
+  {
+    let weights = self.weights(values);
+    let queries: Vec<JudgedQuery> = queries
+      .iter()
+      .map(|judged| {
+        let mut judged = judged.clone();
+        judged.query.options.weights.extend(&weights);
+        judged
+      })
+      .collect();
+    self.evaluator.evaluate(engine, items, &queries).mean
+  }
+
+  /// Returns the searcher weights of the given parameter values.
+  fn weights(&self, values: &[f32]) -> HashMap<SearcherKind, f32> {
+    self
+      .parameters
+      .iter()
+      .zip(values)
+      .filter_map(|(parameter, &value)| match parameter {
+        Parameter::Weight(kind, _) => Some((*kind, value)),
+        Parameter::Boost(..) => None,
+      })
+      .collect()
+  }
+
+  /// Returns the field boosts of the given parameter values.
+  fn boosts(&self, values: &[f32]) -> HashMap<String, f32> {
+    self
+      .parameters
+      .iter()
+      .zip(values)
+      .filter_map(|(parameter, &value)| match parameter {
+        Parameter::Boost(field, _) => Some((field.clone(), value)),
+        Parameter::Weight(..) => None,
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_held_out_count() {
+    let tuner = Tuner::<String>::new(|item| item.clone());
+    assert_eq!(tuner.held_out_count(0), 0);
+    assert_eq!(tuner.held_out_count(1), 0);
+    assert_eq!(tuner.held_out_count(2), 1);
+    assert_eq!(tuner.held_out_count(10), 2);
+
+    let tuner = tuner.holdout(0.0);
+    assert_eq!(tuner.held_out_count(10), 0);
+    let tuner = tuner.holdout(1.0);
+    assert_eq!(tuner.held_out_count(10), 9);
+  }
+
+  #[test]
+  fn test_parameters_are_split_by_kind() {
+    let tuner = Tuner::<String>::new(|item| item.clone())
+      .boost("title", [1.0, 2.0])
+      .weight(SearcherKind::Custom, [0.5])
+      .boost("body", []);
+    let values = [2.0, 0.5];
+
+    assert_eq!(
+      tuner.boosts(&values),
+      HashMap::from([("title".to_string(), 2.0)])
+    );
+    assert_eq!(
+      tuner.weights(&values),
+      HashMap::from([(SearcherKind::Custom, 0.5)])
+    );
+  }
+}
@@ -3,7 +3,12 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::filter::{CompareOp, FilterExpr, FilterValue};
+use crate::aggregations::{
+  Bucket, FacetCount, FieldStats, Histogram, NumericRange, RangeAggregation,
+};
+use crate::explain::Explanation;
+use crate::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
+use crate::highlight::Highlight;
 
 #[cfg(feature = "parallel")]
 pub trait Searchable: Send + Sync {}
@@ -26,6 +31,10 @@ pub type EntityId = String;
 /// This struct represents a single item returned from a search query. It includes
 /// the item itself, a normalized score indicating the relevance of the match,
 /// and detailed metadata about why this item was matched.
+///
+/// The item is a copy of the searched one. Engines over `Arc<T>` items
+/// return matches that share the searched items, for items that are
+/// expensive or impossible to clone.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg(not(feature = "parallel"))]
 pub struct SearusMatch<T> {
@@ -46,6 +55,18 @@ pub struct SearusMatch<T> {
   /// produced this match.
   #[serde(skip_serializing_if = "Vec::is_empty")]
   pub details: Vec<SearchDetail>,
+  /// The normalized (unweighted) score given by each searcher that matched
+  /// the item. Populated by the engine when merging results.
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub searcher_scores: HashMap<SearcherKind, f32>,
+  /// The positions of matched terms in the matched fields. Only populated
+  /// when [`SearchOptions::highlight`] is enabled.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub highlights: Vec<Highlight>,
+  /// A tree explaining how the score was computed. Only populated when
+  /// [`SearchOptions::explain`] is enabled.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub explanation: Option<Explanation>,
 
   pub id: usize,
 }
@@ -73,6 +94,18 @@ where
   /// produced this match.
   #[serde(skip_serializing_if = "Vec::is_empty")]
   pub details: Vec<SearchDetail>,
+  /// The normalized (unweighted) score given by each searcher that matched
+  /// the item. Populated by the engine when merging results.
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub searcher_scores: HashMap<SearcherKind, f32>,
+  /// The positions of matched terms in the matched fields. Only populated
+  /// when [`SearchOptions::highlight`] is enabled.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub highlights: Vec<Highlight>,
+  /// A tree explaining how the score was computed. Only populated when
+  /// [`SearchOptions::explain`] is enabled.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub explanation: Option<Explanation>,
 
   pub id: usize,
 }
@@ -89,6 +122,9 @@ impl<T: Searchable> SearusMatch<T> {
       score,
       field_scores: HashMap::new(),
       details: Vec::new(),
+      searcher_scores: HashMap::new(),
+      highlights: Vec::new(),
+      explanation: None,
     }
   }
 
@@ -107,6 +143,95 @@ impl<T: Searchable> SearusMatch<T> {
     self.details.push(detail);
     self
   }
+
+  /// Converts the item of the match, keeping its score and metadata.
+  pub fn map_item<U: Searchable>(self, f: impl FnOnce(T) -> U) -> SearusMatch<U> {
+    SearusMatch {
+      item: f(self.item),
+      score: self.score,
+      field_scores: self.field_scores,
+      details: self.details,
+      searcher_scores: self.searcher_scores,
+      highlights: self.highlights,
+      explanation: self.explanation,
+      id: self.id,
+    }
+  }
+
+  /// Returns the explanation of how the score was computed, if available.
+  ///
+  /// Explanations are only built when [`SearchOptions::explain`] is enabled
+  /// for the query.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  /// use searus::searchers::SemanticSearch;
+  ///
+  /// #[derive(Debug, Clone, serde::Serialize)]
+  /// struct Post { title: String }
+  ///
+  /// let posts = vec![Post { title: "Rust search engines".into() }];
+  /// let rules = SemanticRules::builder().field("title", FieldRule::bm25()).build();
+  /// let engine = SearusEngine::builder()
+  ///     .with(Box::new(SemanticSearch::new(rules)))
+  ///     .build();
+  ///
+  /// let query = Query::builder()
+  ///     .text("rust")
+  ///     .options(SearchOptions::default().explain(true))
+  ///     .build();
+  ///
+  /// let results = engine.search(&posts, &query);
+  /// let explanation = results[0].explain().unwrap();
+  /// assert_eq!(explanation.value, results[0].score);
+  /// println!("{}", explanation);
+  /// ```
+  pub fn explain(&self) -> Option<&Explanation> {
+    self.explanation.as_ref()
+  }
+}
+
+/// The complete outcome of a search, including metadata about the result set.
+///
+/// Returned by [`SearusEngine::search_response`](crate::engine::SearusEngine::search_response).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResponse<T: Searchable> {
+  /// The ranked and paginated matches.
+  pub results: Vec<SearusMatch<T>>,
+  /// The number of matches before pagination was applied.
+  pub total_matches: usize,
+  /// Whether matches were dropped to stay within the
+  /// [`max_matches_per_searcher`](SearchOptions::max_matches_per_searcher)
+  /// or [`max_matches`](SearchOptions::max_matches) of the search. The
+  /// results are then the best of the matches kept, and `total_matches`
+  /// counts the matches kept.
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub truncated: bool,
+  /// A corrected query text, produced when the query matched few items and a
+  /// spell corrector is configured on the engine.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub suggestion: Option<String>,
+  /// Alternative corrected query texts, best first. The first alternative is
+  /// also reported as [`suggestion`](Self::suggestion).
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub suggestions: Vec<String>,
+  /// The value counts of each field requested with [`SearchOptions::facets`],
+  /// computed over all matches before pagination.
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub facets: HashMap<String, Vec<FacetCount>>,
+  /// The buckets of each field requested with [`SearchOptions::histogram`],
+  /// in ascending order. Empty buckets are omitted.
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub histograms: HashMap<String, Vec<Bucket>>,
+  /// The buckets of each field requested with [`SearchOptions::range`], in
+  /// the order the ranges were given.
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub ranges: HashMap<String, Vec<Bucket>>,
+  /// The statistics of each field requested with [`SearchOptions::stats`].
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub stats: HashMap<String, FieldStats>,
 }
 
 /// Searcher-specific metadata that provides detailed insight into a match.
@@ -125,6 +250,9 @@ pub enum SearchDetail {
     field: String,
     /// The weight of this particular match.
     weight: f32,
+    /// The frequency and positions of each matched term in the field.
+    #[serde(default)]
+    terms: Vec<TermMatch>,
   },
   /// Details for a vector similarity search.
   Vector {
@@ -150,6 +278,9 @@ pub enum SearchDetail {
     original_term: String,
     /// The similarity score between the matched term and the original term.
     similarity: f32,
+    /// The field in which the match occurred.
+    #[serde(default)]
+    field: String,
   },
   /// Details for an image-based similarity match.
   Image {
@@ -158,6 +289,18 @@ pub enum SearchDetail {
   },
 }
 
+/// The occurrences of a matched term within a field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TermMatch {
+  /// The (analyzed) term.
+  pub term: String,
+  /// The number of times the term occurs in the field.
+  pub frequency: usize,
+  /// The positions of the term in the field's analyzed token stream
+  /// (0-based, after stopword removal).
+  pub positions: Vec<usize>,
+}
+
 /// Represents a search query that can combine multiple search modes.
 ///
 /// A `Query` can include a text query, a vector for similarity search, tags,
@@ -169,13 +312,36 @@ pub struct Query {
   pub text: Option<String>,
   /// A pre-computed embedding vector for vector similarity search.
   pub vector: Option<Vec<f32>>,
+  /// The name of the item vectors that `vector` is compared with, for items
+  /// with several vectors (e.g., `"image"`). Vector searchers compare with
+  /// their default vector when unset.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub vector_name: Option<String>,
   /// A list of tags to filter or score results by.
   pub tags: Option<Vec<String>>,
+  /// Tags that exclude an item from the results of tag searchers, even
+  /// when a related tag would match it.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub exclude_tags: Option<Vec<String>>,
   /// Image data to be used for image similarity search.
   pub image: Option<ImageData>,
   /// A filter expression to apply to the search results, allowing for
   /// structured filtering based on item attributes.
   pub filters: Option<crate::filter::FilterExpr>,
+  /// The principal (user) on whose behalf the search is executed. Used by
+  /// access-control extensions to restrict the visible items.
+  pub principal: Option<Principal>,
+  /// Restricts the search to documents in this language (e.g., `"en"`), and
+  /// analyzes the query text for it. Only searchers that know the language
+  /// of their documents apply the restriction.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub language: Option<String>,
+  /// Free-form values that extensions and searchers share during a search,
+  /// keyed by name. For example, a rewrite extension can record the original
+  /// text of the query for a later extension to read. Prefix keys with the
+  /// name of the extension to avoid clashes.
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub metadata: HashMap<String, serde_json::Value>,
   /// Additional options for the search, such as pagination, timeouts, and
   /// searcher-specific weights.
   #[serde(default)]
@@ -193,109 +359,400 @@ impl Query {
     QueryBuilder::default()
   }
 
-  /// Creates a new `FilterBuild` to construct a `FilterExpr` in a chained manner.
+  /// Creates a new `FilterBuilder` to construct a `FilterExpr` in a chained manner.
+  ///
+  /// The filter type sets how chained conditions are joined by default:
+  /// `Query::AND` and `Query::COMPARE` join them with AND, `Query::OR` with
+  /// OR, and `Query::NOT` negates the conditions joined with AND. See
+  /// [`FilterBuilder`] for the full DSL.
   pub fn filter(filter_type: u8) -> FilterBuilder {
-    match filter_type {
-      1 => FilterBuilder {
-        filter: FilterExpr::And(Vec::new()),
-      },
-      2 => FilterBuilder {
-        filter: FilterExpr::Or(Vec::new()),
-      },
-      3 => FilterBuilder {
-        filter: FilterExpr::Not(Box::new(FilterExpr::Or(Vec::new()))),
-      },
-      _ => FilterBuilder {
-        filter: FilterExpr::Compare {
-          field: "".to_string(),
-          op: crate::filter::CompareOp::Contains,
-          value: FilterValue::String("".to_string()),
-        },
-      },
+    let kind = match filter_type {
+      1 => FilterKind::And,
+      2 => FilterKind::Or,
+      3 => FilterKind::Not,
+      _ => FilterKind::Compare,
+    };
+    FilterBuilder {
+      kind,
+      conditions: Vec::new(),
+      next: None,
     }
   }
 }
 
+/// The kind of filter a [`FilterBuilder`] was created for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterKind {
+  Compare,
+  And,
+  Or,
+  Not,
+}
+
+/// A logical connective between two chained conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Connective {
+  And,
+  Or,
+}
+
+/// A fluent builder for [`FilterExpr`] trees, created with [`Query::filter`].
+///
+/// Each condition method (`eq`, `gt`, `is_in`, `between`, ...) adds a
+/// condition to the chain. Adjacent conditions are joined with the default
+/// connective of the filter type, unless [`and`](Self::and) or
+/// [`or`](Self::or) is called between them. AND binds tighter than OR, so
+/// `a.or().b.and().c` means `a OR (b AND c)`; use [`group`](Self::group) for
+/// other groupings and [`not`](Self::not) to negate a group.
+///
+/// # Examples
+///
+/// ```rust
+/// use searus::prelude::*;
+/// use serde_json::json;
+///
+/// // (category == "Books" OR price > 100) AND NOT (stock == 0 OR discontinued == true)
+/// let filter = Query::filter(Query::COMPARE)
+///     .group(|g| g.eq("category", "Books").or().gt("price", 100))
+///     .and()
+///     .not(|n| n.eq("stock", 0).or().eq("discontinued", true))
+///     .build();
+///
+/// assert!(filter.evaluate(&json!({ "category": "Books", "price": 20, "stock": 3 })));
+/// assert!(filter.evaluate(&json!({ "category": "Games", "price": 150, "stock": 1 })));
+/// assert!(!filter.evaluate(&json!({ "category": "Games", "price": 20, "stock": 1 })));
+/// assert!(!filter.evaluate(&json!({ "category": "Books", "price": 20, "stock": 0 })));
+///
+/// // Containers can also be filled with prebuilt expressions.
+/// let filter = Query::filter(Query::OR)
+///     .with(Query::filter(Query::COMPARE).ge("views", 1000))
+///     .with(Query::filter(Query::COMPARE).eq("author", "Bob"))
+///     .build();
+/// assert!(filter.evaluate(&json!({ "views": 10, "author": "Bob" })));
+/// ```
+#[derive(Debug, Clone)]
 pub struct FilterBuilder {
-  filter: FilterExpr,
+  /// The filter type, which sets the default connective.
+  kind: FilterKind,
+  /// The chained conditions, each with the connective joining it to the
+  /// previous one.
+  conditions: Vec<(Connective, FilterExpr)>,
+  /// The connective requested for the next condition, if any.
+  next: Option<Connective>,
 }
 
 impl FilterBuilder {
-  pub fn with(mut self, value: FilterExpr) -> Self {
-    match self.filter {
-      FilterExpr::And(ref mut items) => items.push(value),
-      FilterExpr::Not(ref mut item) => *item = Box::new(value),
-      FilterExpr::Or(ref mut items) => items.push(value),
-      _ => {}
-    }
+  /// Adds a prebuilt expression (or builder) as the next condition.
+  pub fn with(mut self, value: impl Into<FilterExpr>) -> Self {
+    let connective = self.next.take().unwrap_or(match self.kind {
+      FilterKind::Or => Connective::Or,
+      _ => Connective::And,
+    });
+    self.conditions.push((connective, value.into()));
     self
   }
 
-  pub fn eq(mut self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
-    self.filter = FilterExpr::Compare {
-      field: field.into(),
-      op: CompareOp::Eq,
-      value: value.into(),
-    };
+  /// Joins the previous and the next condition with AND.
+  pub fn and(mut self) -> Self {
+    self.next = Some(Connective::And);
     self
   }
 
-  pub fn ne(mut self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
-    self.filter = FilterExpr::Compare {
-      field: field.into(),
-      op: CompareOp::Ne,
-      value: value.into(),
-    };
+  /// Joins the previous and the next condition with OR.
+  pub fn or(mut self) -> Self {
+    self.next = Some(Connective::Or);
     self
   }
 
-  pub fn lt(mut self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
-    self.filter = FilterExpr::Compare {
-      field: field.into(),
-      op: CompareOp::Lt,
-      value: value.into(),
-    };
-    self
+  /// Adds a parenthesized group of conditions, joined with AND by default.
+  pub fn group(self, build: impl FnOnce(FilterBuilder) -> FilterBuilder) -> Self {
+    self.with(build(Query::filter(Query::COMPARE)))
   }
 
-  pub fn le(mut self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
-    self.filter = FilterExpr::Compare {
-      field: field.into(),
-      op: CompareOp::Le,
-      value: value.into(),
-    };
+  /// Adds the negation of a group of conditions, joined with AND by default.
+  pub fn not(self, build: impl FnOnce(FilterBuilder) -> FilterBuilder) -> Self {
+    let group = build(Query::filter(Query::COMPARE)).build();
+    self.with(FilterExpr::Not(Box::new(group)))
+  }
+
+  /// Matches items whose field equals a value.
+  pub fn eq(self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
+    self.compare(field, CompareOp::Eq, value)
+  }
+
+  /// Matches items whose field is present and differs from a value.
+  pub fn ne(self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
+    self.compare(field, CompareOp::Ne, value)
+  }
+
+  /// Matches items whose field is less than a value.
+  pub fn lt(self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
+    self.compare(field, CompareOp::Lt, value)
+  }
+
+  /// Matches items whose field is less than or equal to a value.
+  pub fn le(self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
+    self.compare(field, CompareOp::Le, value)
+  }
+
+  /// Matches items whose field is greater than a value.
+  pub fn gt(self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
+    self.compare(field, CompareOp::Gt, value)
+  }
+
+  /// Matches items whose field is greater than or equal to a value.
+  pub fn ge(self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
+    self.compare(field, CompareOp::Ge, value)
+  }
+
+  /// Matches items whose string field contains a value, ignoring case, or
+  /// whose array field has an element equal to it.
+  pub fn contains(self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
+    self.compare(field, CompareOp::Contains, value)
+  }
+
+  /// Matches items whose string field (or one of the strings of an array
+  /// field) starts with a prefix.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let filter = Query::filter(Query::COMPARE).starts_with("sku", "EL-").build();
+  /// assert!(filter.evaluate(&serde_json::json!({ "sku": "EL-1042" })));
+  ///
+  /// let filter = Query::filter(Query::COMPARE).ends_with_ignore_case("file", ".rs").build();
+  /// assert!(filter.evaluate(&serde_json::json!({ "file": "src/MAIN.RS" })));
+  /// ```
+  pub fn starts_with(self, field: impl Into<String>, prefix: impl Into<FilterValue>) -> Self {
+    self.compare(field, CompareOp::StartsWith, prefix)
+  }
+
+  /// Matches items whose string field ends with a suffix.
+  pub fn ends_with(self, field: impl Into<String>, suffix: impl Into<FilterValue>) -> Self {
+    self.compare(field, CompareOp::EndsWith, suffix)
+  }
+
+  /// Matches items whose string field starts with a prefix, ignoring case.
+  pub fn starts_with_ignore_case(
+    self,
+    field: impl Into<String>,
+    prefix: impl Into<FilterValue>,
+  ) -> Self {
+    self.compare(field, CompareOp::StartsWithIgnoreCase, prefix)
+  }
+
+  /// Matches items whose string field ends with a suffix, ignoring case.
+  pub fn ends_with_ignore_case(
+    self,
+    field: impl Into<String>,
+    suffix: impl Into<FilterValue>,
+  ) -> Self {
+    self.compare(field, CompareOp::EndsWithIgnoreCase, suffix)
+  }
+
+  /// Sets how strings are compared by the last condition added (every
+  /// comparison in it, for groups).
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let filter = Query::filter(Query::COMPARE)
+  ///     .eq("city", "sao paulo")
+  ///     .string_options(StringOptions::new().case_sensitive(false).fold_diacritics(true))
+  ///     .build();
+  ///
+  /// assert!(filter.evaluate(&serde_json::json!({ "city": "São Paulo" })));
+  ///
+  /// // Substrings ignore case by default, which can be turned off.
+  /// let filter = Query::filter(Query::COMPARE)
+  ///     .contains("name", "pro")
+  ///     .string_options(StringOptions::new().case_sensitive(true))
+  ///     .build();
+  ///
+  /// assert!(!filter.evaluate(&serde_json::json!({ "name": "MacBook Pro" })));
+  /// ```
+  pub fn string_options(mut self, options: StringOptions) -> Self {
+    if let Some((connective, condition)) = self.conditions.pop() {
+      let condition = condition.with_string_options(options);
+      self.conditions.push((connective, condition));
+    }
     self
   }
 
-  pub fn gt(mut self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
-    self.filter = FilterExpr::Compare {
+  /// Adds a comparison as the next condition.
+  fn compare(self, field: impl Into<String>, op: CompareOp, value: impl Into<FilterValue>) -> Self {
+    self.with(FilterExpr::Compare {
       field: field.into(),
-      op: CompareOp::Gt,
+      op,
       value: value.into(),
-    };
-    self
+      options: StringOptions::default(),
+    })
   }
 
-  pub fn ge(mut self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
-    self.filter = FilterExpr::Compare {
+  /// Matches items whose array field has an element matching `expr`, with
+  /// field names relative to the element.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let filter = Query::filter(Query::COMPARE)
+  ///     .any("reviews", Query::filter(Query::COMPARE).ge("rating", 4))
+  ///     .build();
+  ///
+  /// let product = serde_json::json!({ "reviews": [{ "rating": 2 }, { "rating": 5 }] });
+  /// assert!(filter.evaluate(&product));
+  ///
+  /// let filter = Query::filter(Query::COMPARE)
+  ///     .all("reviews", Query::filter(Query::COMPARE).ge("rating", 4))
+  ///     .build();
+  /// assert!(!filter.evaluate(&product));
+  /// ```
+  pub fn any(self, field: impl Into<String>, expr: impl Into<FilterExpr>) -> Self {
+    self.with(FilterExpr::Any(field.into(), Box::new(expr.into())))
+  }
+
+  /// Matches items whose array field only has elements matching `expr`,
+  /// with field names relative to the element.
+  pub fn all(self, field: impl Into<String>, expr: impl Into<FilterExpr>) -> Self {
+    self.with(FilterExpr::All(field.into(), Box::new(expr.into())))
+  }
+
+  /// Matches items whose field is between two values, bounds included.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let filter = Query::filter(Query::COMPARE).between("price", 10, 50).build();
+  ///
+  /// assert!(filter.evaluate(&serde_json::json!({ "price": 50 })));
+  /// assert!(!filter.evaluate(&serde_json::json!({ "price": 60 })));
+  /// ```
+  pub fn between(
+    self,
+    field: impl Into<String>,
+    low: impl Into<FilterValue>,
+    high: impl Into<FilterValue>,
+  ) -> Self {
+    self.with(FilterExpr::Between {
       field: field.into(),
-      op: CompareOp::Ge,
-      value: value.into(),
-    };
-    self
+      low: low.into(),
+      high: high.into(),
+      inclusive: true,
+      options: StringOptions::default(),
+    })
   }
 
-  pub fn contains(mut self, field: impl Into<String>, value: impl Into<FilterValue>) -> Self {
-    self.filter = FilterExpr::Compare {
+  /// Matches items whose field is strictly between two values.
+  pub fn between_exclusive(
+    self,
+    field: impl Into<String>,
+    low: impl Into<FilterValue>,
+    high: impl Into<FilterValue>,
+  ) -> Self {
+    self.with(FilterExpr::Between {
       field: field.into(),
-      op: CompareOp::Contains,
-      value: value.into(),
-    };
-    self
+      low: low.into(),
+      high: high.into(),
+      inclusive: false,
+      options: StringOptions::default(),
+    })
+  }
+
+  /// Matches items whose field equals one of the values (or, for array
+  /// fields, has an element equal to one of them).
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// let filter = Query::filter(Query::COMPARE)
+  ///     .is_in("category", ["Electronics", "Home"])
+  ///     .build();
+  ///
+  /// assert!(filter.evaluate(&serde_json::json!({ "category": "Home" })));
+  /// assert!(!filter.evaluate(&serde_json::json!({ "category": "Garden" })));
+  /// ```
+  pub fn is_in<I, V>(self, field: impl Into<String>, values: I) -> Self
+  where
+    I: IntoIterator<Item = V>,
+    V: Into<FilterValue>,
+  {
+    let values = FilterValue::List(values.into_iter().map(Into::into).collect());
+    self.compare(field, CompareOp::In, values)
   }
 
+  /// Matches items whose field is present and equals none of the values.
+  pub fn not_in<I, V>(self, field: impl Into<String>, values: I) -> Self
+  where
+    I: IntoIterator<Item = V>,
+    V: Into<FilterValue>,
+  {
+    let values = FilterValue::List(values.into_iter().map(Into::into).collect());
+    self.compare(field, CompareOp::NotIn, values)
+  }
+
+  /// Builds the filter expression.
+  ///
+  /// A single condition is returned as is with `Query::COMPARE`, while
+  /// `Query::AND` and `Query::OR` filters are always wrapped in their
+  /// container, even when empty.
   pub fn build(self) -> FilterExpr {
-    self.filter
+    let kind = self.kind;
+
+    // AND binds tighter than OR: split the chain into AND groups.
+    let mut groups: Vec<Vec<FilterExpr>> = Vec::new();
+    for (connective, condition) in self.conditions {
+      match groups.last_mut() {
+        Some(group) if connective == Connective::And => group.push(condition),
+        _ => groups.push(vec![condition]),
+      }
+    }
+
+    let conjunction = |mut group: Vec<FilterExpr>| {
+      if group.len() == 1 && kind != FilterKind::And {
+        group.remove(0)
+      } else {
+        FilterExpr::And(group)
+      }
+    };
+
+    let combined = if groups.is_empty() {
+      match kind {
+        FilterKind::And => FilterExpr::And(Vec::new()),
+        FilterKind::Or | FilterKind::Not => FilterExpr::Or(Vec::new()),
+        // An empty comparison, which matches nothing.
+        FilterKind::Compare => FilterExpr::Compare {
+          field: "".to_string(),
+          op: CompareOp::Contains,
+          value: FilterValue::String("".to_string()),
+          options: StringOptions::default(),
+        },
+      }
+    } else if kind == FilterKind::Or || groups.len() > 1 {
+      FilterExpr::Or(groups.into_iter().map(conjunction).collect())
+    } else {
+      conjunction(groups.remove(0))
+    };
+
+    match kind {
+      FilterKind::Not => FilterExpr::Not(Box::new(combined)),
+      _ => combined,
+    }
+  }
+}
+
+impl From<FilterBuilder> for FilterExpr {
+  fn from(builder: FilterBuilder) -> Self {
+    builder.build()
   }
 }
 
@@ -307,9 +764,14 @@ impl FilterBuilder {
 pub struct QueryBuilder {
   text: Option<String>,
   vector: Option<Vec<f32>>,
+  vector_name: Option<String>,
   tags: Option<Vec<String>>,
+  exclude_tags: Option<Vec<String>>,
   image: Option<ImageData>,
   filters: Option<crate::filter::FilterExpr>,
+  principal: Option<Principal>,
+  language: Option<String>,
+  metadata: HashMap<String, serde_json::Value>,
   options: SearchOptions,
 }
 
@@ -326,12 +788,31 @@ impl QueryBuilder {
     self
   }
 
+  /// Sets the name of the item vectors the query vector is compared with.
+  pub fn vector_name(mut self, name: impl Into<String>) -> Self {
+    self.vector_name = Some(name.into());
+    self
+  }
+
   /// Sets the tags component of the query.
   pub fn tags(mut self, tags: Vec<String>) -> Self {
     self.tags = Some(tags);
     self
   }
 
+  /// Sets the tags that exclude an item from the results of tag searchers.
+  ///
+  /// Exclusions are matched case-insensitively against the tags of each
+  /// item, and take precedence over the query tags and their related tags.
+  pub fn exclude_tags<I, S>(mut self, tags: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.exclude_tags = Some(tags.into_iter().map(Into::into).collect());
+    self
+  }
+
   /// Sets the image component of the query.
   pub fn image(mut self, image: ImageData) -> Self {
     self.image = Some(image);
@@ -339,8 +820,27 @@ impl QueryBuilder {
   }
 
   /// Sets the filter expression for the query.
-  pub fn filters(mut self, filters: crate::filter::FilterExpr) -> Self {
-    self.filters = Some(filters);
+  pub fn filters(mut self, filters: impl Into<crate::filter::FilterExpr>) -> Self {
+    self.filters = Some(filters.into());
+    self
+  }
+
+  /// Sets the principal on whose behalf the query is executed.
+  pub fn principal(mut self, principal: Principal) -> Self {
+    self.principal = Some(principal);
+    self
+  }
+
+  /// Restricts the query to documents in the given language.
+  pub fn language(mut self, language: impl Into<String>) -> Self {
+    self.language = Some(language.into().to_lowercase());
+    self
+  }
+
+  /// Adds a metadata value to the query, for extensions and searchers to
+  /// read.
+  pub fn metadata(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+    self.metadata.insert(key.into(), value.into());
     self
   }
 
@@ -367,14 +867,53 @@ impl QueryBuilder {
     Query {
       text: self.text,
       vector: self.vector,
+      vector_name: self.vector_name,
       tags: self.tags,
+      exclude_tags: self.exclude_tags,
       image: self.image,
       filters: self.filters,
+      principal: self.principal,
+      language: self.language,
+      metadata: self.metadata,
       options: self.options,
     }
   }
 }
 
+/// The identity of the user or service a query is executed for.
+///
+/// A `Principal` carries just enough information for access-control
+/// extensions to decide which items are visible to the requester.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Principal {
+  /// The unique identifier of the principal (e.g., a user id).
+  pub id: String,
+  /// The roles granted to the principal (e.g., "admin", "editor").
+  #[serde(default)]
+  pub roles: Vec<String>,
+}
+
+impl Principal {
+  /// Creates a new principal with the given id and no roles.
+  pub fn new(id: impl Into<String>) -> Self {
+    Self {
+      id: id.into(),
+      roles: Vec::new(),
+    }
+  }
+
+  /// Adds a role to the principal.
+  pub fn with_role(mut self, role: impl Into<String>) -> Self {
+    self.roles.push(role.into());
+    self
+  }
+
+  /// Returns `true` if the principal has the given role.
+  pub fn has_role(&self, role: &str) -> bool {
+    self.roles.iter().any(|r| r == role)
+  }
+}
+
 /// Represents image data for an image-based search.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageData {
@@ -388,6 +927,18 @@ pub struct ImageData {
   pub height: Option<u32>,
 }
 
+impl ImageData {
+  /// Creates image data from the contents of an image file.
+  pub fn new(bytes: impl Into<Vec<u8>>) -> Self {
+    Self {
+      bytes: bytes.into(),
+      mime_type: None,
+      width: None,
+      height: None,
+    }
+  }
+}
+
 /// Defines options for controlling a search operation.
 ///
 /// This includes settings for pagination, timeouts, and weighting of different
@@ -406,16 +957,63 @@ pub struct SearchOptions {
   /// no timeout.
   #[serde(default)]
   pub timeout_ms: u64,
+  /// The maximum number of matches kept from each searcher. Past it, only
+  /// the best-scoring matches are kept, and the response is marked as
+  /// [`truncated`](SearchResponse::truncated). `None` keeps every match.
+  #[serde(default)]
+  pub max_matches_per_searcher: Option<usize>,
+  /// The maximum number of merged matches kept, before pagination. Past it,
+  /// only the best-scoring matches are kept, and the response is marked as
+  /// [`truncated`](SearchResponse::truncated). `None` keeps every match.
+  #[serde(default)]
+  pub max_matches: Option<usize>,
   /// A map of weights to apply to the scores from different types of searchers.
   /// This allows for fine-tuning the relevance blending between, for example,
   /// semantic search and tag-based search.
   #[serde(default)]
   pub weights: HashMap<SearcherKind, f32>,
+  /// A named set of weights applied to the kinds of searchers without a
+  /// weight in `weights`. Kinds weighted by neither fall back to the
+  /// engine's default weights.
+  #[serde(default)]
+  pub preset: Option<WeightPreset>,
   /// Optional maximum depth for Tag Relationship Tree (TRT) expansion.
   /// Only applies to TaggedSearch when a TRT is configured.
   /// A value of None or 0 means no TRT expansion.
   #[serde(default)]
   pub trt_depth: Option<usize>,
+  /// Whether items must have all query tags or any of them. Overrides the
+  /// mode of the tag searcher when set.
+  #[serde(default)]
+  pub tag_mode: Option<TagMode>,
+  /// Whether to build an [`Explanation`] of the score of every match.
+  /// This is meant for debugging relevance and adds overhead to the search.
+  #[serde(default)]
+  pub explain: bool,
+  /// Whether text searchers should record the positions of matched terms in
+  /// [`SearusMatch::highlights`].
+  #[serde(default)]
+  pub highlight: bool,
+  /// The fields to compute value counts for. The counts are returned in
+  /// [`SearchResponse::facets`].
+  #[serde(default)]
+  pub facets: Vec<String>,
+  /// The fields returned by [`SearusEngine::search_projected`](crate::engine::SearusEngine::search_projected).
+  /// An empty list returns whole items.
+  #[serde(default)]
+  pub select: Vec<String>,
+  /// The numeric fields to compute fixed-width histograms for. The buckets
+  /// are returned in [`SearchResponse::histograms`].
+  #[serde(default)]
+  pub histograms: Vec<Histogram>,
+  /// The numeric fields to count within ranges. The buckets are returned in
+  /// [`SearchResponse::ranges`].
+  #[serde(default)]
+  pub ranges: Vec<RangeAggregation>,
+  /// The numeric fields to compute min/max/sum/mean statistics for. The
+  /// statistics are returned in [`SearchResponse::stats`].
+  #[serde(default)]
+  pub stats: Vec<String>,
 }
 
 /// Returns the default limit for search results.
@@ -430,8 +1028,19 @@ impl Default for SearchOptions {
       skip: 0,
       limit: default_limit(),
       timeout_ms: 0,
+      max_matches_per_searcher: None,
+      max_matches: None,
       weights: HashMap::new(),
+      preset: None,
       trt_depth: None,
+      tag_mode: None,
+      explain: false,
+      highlight: false,
+      facets: Vec::new(),
+      select: Vec::new(),
+      histograms: Vec::new(),
+      ranges: Vec::new(),
+      stats: Vec::new(),
     }
   }
 }
@@ -455,17 +1064,181 @@ impl SearchOptions {
     self
   }
 
+  /// Sets the maximum number of matches kept from each searcher.
+  pub fn max_matches_per_searcher(mut self, max_matches: usize) -> Self {
+    self.max_matches_per_searcher = Some(max_matches);
+    self
+  }
+
+  /// Sets the maximum number of merged matches kept before pagination.
+  pub fn max_matches(mut self, max_matches: usize) -> Self {
+    self.max_matches = Some(max_matches);
+    self
+  }
+
   /// Sets a weight for a specific kind of searcher.
   pub fn weight(mut self, kind: SearcherKind, weight: f32) -> Self {
     self.weights.insert(kind, weight);
     self
   }
 
+  /// Sets the preset weighting the kinds of searchers without a weight of
+  /// their own.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use searus::prelude::*;
+  ///
+  /// // Text matches count most, but fuzzy matches are still ranked below.
+  /// let options = SearchOptions::default()
+  ///     .preset(WeightPreset::TextHeavy)
+  ///     .weight(SearcherKind::Fuzzy, 0.2);
+  /// ```
+  pub fn preset(mut self, preset: WeightPreset) -> Self {
+    self.preset = Some(preset);
+    self
+  }
+
   /// Sets the maximum depth for Tag Relationship Tree expansion.
   pub fn trt_depth(mut self, depth: usize) -> Self {
     self.trt_depth = Some(depth);
     self
   }
+
+  /// Sets whether items must have all query tags or any of them.
+  pub fn tag_mode(mut self, mode: TagMode) -> Self {
+    self.tag_mode = Some(mode);
+    self
+  }
+
+  /// Enables or disables score explanations for every match.
+  pub fn explain(mut self, explain: bool) -> Self {
+    self.explain = explain;
+    self
+  }
+
+  /// Enables or disables recording the positions of matched terms.
+  pub fn highlight(mut self, highlight: bool) -> Self {
+    self.highlight = highlight;
+    self
+  }
+
+  /// Sets the fields to compute facet (value) counts for.
+  ///
+  /// Array fields, such as tags, count each of their elements.
+  pub fn facets<I, S>(mut self, fields: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.facets = fields.into_iter().map(Into::into).collect();
+    self
+  }
+
+  /// Sets the fields returned by
+  /// [`SearusEngine::search_projected`](crate::engine::SearusEngine::search_projected).
+  ///
+  /// Nested fields can be selected with dotted paths (e.g., `author.name`).
+  pub fn select<I, S>(mut self, fields: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.select = fields.into_iter().map(Into::into).collect();
+    self
+  }
+
+  /// Adds a histogram of a numeric field, with buckets of the given width.
+  ///
+  /// Bucket boundaries are multiples of the interval, e.g., an interval of
+  /// 50 produces buckets `[0, 50)`, `[50, 100)`, and so on.
+  pub fn histogram(mut self, field: impl Into<String>, interval: f64) -> Self {
+    self.histograms.push(Histogram {
+      field: field.into(),
+      interval,
+    });
+    self
+  }
+
+  /// Adds counts of a numeric field within the given ranges.
+  pub fn range<I>(mut self, field: impl Into<String>, ranges: I) -> Self
+  where
+    I: IntoIterator<Item = NumericRange>,
+  {
+    self.ranges.push(RangeAggregation {
+      field: field.into(),
+      ranges: ranges.into_iter().collect(),
+    });
+    self
+  }
+
+  /// Sets the numeric fields to compute statistics (count, min, max, sum,
+  /// and mean) for.
+  pub fn stats<I, S>(mut self, fields: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.stats = fields.into_iter().map(Into::into).collect();
+    self
+  }
+}
+
+/// How the tags of a query are matched against the tags of an item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMode {
+  /// Items match if they have at least one of the query tags.
+  #[default]
+  Any,
+  /// Items match only if they have every query tag (or, with tag
+  /// relationship expansion, a tag related to it).
+  All,
+}
+
+/// A named set of searcher weights, selected with [`SearchOptions::preset`].
+///
+/// Presets are named in kebab case when serialized, as in `"text-heavy"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WeightPreset {
+  /// Every kind of searcher has a weight of 1.0.
+  Balanced,
+  /// Text matches (semantic, then fuzzy) outweigh tag, vector, and image
+  /// matches.
+  TextHeavy,
+  /// Tag matches outweigh text, vector, and image matches.
+  TagHeavy,
+  /// Vector and image similarity outweigh text and tag matches.
+  VectorHeavy,
+}
+
+impl WeightPreset {
+  /// Returns the weight the preset gives a kind of searcher.
+  ///
+  /// Range, geospatial, and custom searchers have a weight of 1.0 in every
+  /// preset.
+  pub fn weight(&self, kind: SearcherKind) -> f32 {
+    // The text, fuzzy, and tag weights go unused without their searchers.
+    #[allow(unused_variables)]
+    let (text, fuzzy, tags, vector) = match self {
+      WeightPreset::Balanced => (1.0, 1.0, 1.0, 1.0),
+      WeightPreset::TextHeavy => (1.0, 0.6, 0.3, 0.3),
+      WeightPreset::TagHeavy => (0.4, 0.2, 1.0, 0.3),
+      WeightPreset::VectorHeavy => (0.4, 0.2, 0.3, 1.0),
+    };
+    match kind {
+      #[cfg(feature = "semantic")]
+      SearcherKind::Semantic => text,
+      #[cfg(feature = "fuzzy")]
+      SearcherKind::Fuzzy => fuzzy,
+      #[cfg(feature = "tagged")]
+      SearcherKind::Tags => tags,
+      SearcherKind::Vector | SearcherKind::Image => vector,
+      SearcherKind::Range | SearcherKind::Geospatial | SearcherKind::Custom => 1.0,
+    }
+  }
 }
 
 /// An enumeration of the different kinds of searchers available.
@@ -0,0 +1,102 @@
+//! wasm-bindgen bindings for searching in the browser.
+//!
+//! With the `wasm` feature, the crate exports a `SearusEngine` class to
+//! JavaScript. It is built from an [`EngineConfig`] object, stores plain
+//! JavaScript objects by id, and searches them with a JSON [`Query`]:
+//!
+//! ```js
+//! import init, { SearusEngine } from "searus";
+//!
+//! await init();
+//! const engine = new SearusEngine(
+//!   { semantic: { fields: { title: { matcher: "bm25" } } }, tags: { field: "tags" } },
+//!   ["title"],
+//! );
+//! engine.put("1", { title: "Rust ownership", tags: ["rust"] });
+//!
+//! const response = engine.search({ text: "rust", options: { limit: 10 } });
+//! const completions = engine.suggest("ru", 5);
+//! ```
+//!
+//! Searches run on the calling thread, so the bindings suit the small
+//! corpora a page holds in memory. Build without the `parallel` feature;
+//! browsers without shared memory can't run the rayon thread pool.
+
+use crate::config::EngineConfig;
+use crate::indexed::IndexedSearusEngine;
+use crate::types::Query;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use serde_wasm_bindgen::Serializer;
+use wasm_bindgen::prelude::*;
+
+/// A search engine over JavaScript objects, exported to JavaScript as
+/// `SearusEngine`.
+#[wasm_bindgen(js_name = SearusEngine)]
+pub struct WasmEngine {
+  /// The engine and the items it searches.
+  inner: IndexedSearusEngine<Value>,
+}
+
+#[wasm_bindgen(js_class = SearusEngine)]
+impl WasmEngine {
+  /// Creates an engine from an `EngineConfig` object, offering the values
+  /// of `completion_fields` as completions.
+  #[wasm_bindgen(constructor)]
+  pub fn new(
+    config: JsValue,
+    completion_fields: Option<Vec<String>>,
+  ) -> Result<WasmEngine, JsError> {
+    let config: EngineConfig = from_js(config)?;
+    let mut inner = IndexedSearusEngine::new(config.build());
+    if let Some(fields) = completion_fields {
+      inner = inner.with_completion_fields(fields);
+    }
+    Ok(Self { inner })
+  }
+
+  /// Adds or replaces the item with the given id.
+  pub fn put(&mut self, id: String, item: JsValue) -> Result<(), JsError> {
+    let item = from_js(item)?;
+    self.inner.put(id, item).map_err(|e| JsError::new(&e))
+  }
+
+  /// Returns the item with the given id, or `undefined`.
+  pub fn get(&self, id: &str) -> Result<JsValue, JsError> {
+    match self.inner.get(id) {
+      Some(item) => to_js(item),
+      None => Ok(JsValue::UNDEFINED),
+    }
+  }
+
+  /// Removes the item with the given id.
+  pub fn remove(&mut self, id: &str) -> Result<(), JsError> {
+    self.inner.remove(id).map_err(|e| JsError::new(&e))
+  }
+
+  /// Searches the items with a `Query` object, returning a `SearchResponse`
+  /// object.
+  pub fn search(&self, query: JsValue) -> Result<JsValue, JsError> {
+    let query: Query = from_js(query)?;
+    to_js(&self.inner.search(&query))
+  }
+
+  /// Returns up to `limit` completions of a search-box prefix.
+  pub fn suggest(&self, prefix: &str, limit: usize) -> Result<JsValue, JsError> {
+    to_js(&self.inner.suggest(prefix, limit))
+  }
+}
+
+/// Converts a JavaScript value to a Rust value.
+fn from_js<T: DeserializeOwned>(value: JsValue) -> Result<T, JsError> {
+  serde_wasm_bindgen::from_value(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Converts a Rust value to a plain JavaScript value, with maps as objects
+/// rather than `Map`s.
+fn to_js<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, JsError> {
+  value
+    .serialize(&Serializer::json_compatible())
+    .map_err(|e| JsError::new(&e.to_string()))
+}
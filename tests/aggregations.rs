@@ -0,0 +1,133 @@
+use searus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Product {
+  name: String,
+  brand: String,
+  price: f64,
+  tags: Vec<String>,
+}
+
+fn products() -> Vec<Product> {
+  let product = |name: &str, brand: &str, price: f64, tags: &[&str]| Product {
+    name: name.to_string(),
+    brand: brand.to_string(),
+    price,
+    tags: tags.iter().map(|t| t.to_string()).collect(),
+  };
+
+  vec![
+    product("Running shoes", "Acme", 89.0, &["sports", "shoes"]),
+    product(
+      "Trail running shoes",
+      "Peak",
+      129.0,
+      &["sports", "shoes", "outdoor"],
+    ),
+    product("Running socks", "Acme", 12.5, &["sports"]),
+    product("Office chair", "Acme", 250.0, &["furniture"]),
+  ]
+}
+
+fn engine() -> SearusEngine<Product> {
+  SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("name", FieldRule::tokenized())
+        .build(),
+    )))
+    .build()
+}
+
+#[test]
+fn test_facets_count_matched_set_before_pagination() {
+  let query = Query::builder()
+    .text("running")
+    .options(SearchOptions::default().limit(1).facets(["brand", "tags"]))
+    .build();
+
+  let response = engine().search_response(&products(), &query);
+
+  assert_eq!(response.results.len(), 1);
+  assert_eq!(response.total_matches, 3);
+
+  let brands = &response.facets["brand"];
+  assert_eq!(brands.len(), 2);
+  assert_eq!((brands[0].value.as_str(), brands[0].count), ("Acme", 2));
+  assert_eq!((brands[1].value.as_str(), brands[1].count), ("Peak", 1));
+
+  let tags = &response.facets["tags"];
+  assert_eq!((tags[0].value.as_str(), tags[0].count), ("sports", 3));
+  assert_eq!((tags[1].value.as_str(), tags[1].count), ("shoes", 2));
+  assert_eq!((tags[2].value.as_str(), tags[2].count), ("outdoor", 1));
+}
+
+#[test]
+fn test_no_aggregations_unless_requested() {
+  let response = engine().search_response(&products(), &Query::builder().text("running").build());
+
+  assert!(response.facets.is_empty());
+  assert!(response.histograms.is_empty());
+  assert!(response.ranges.is_empty());
+  assert!(response.stats.is_empty());
+}
+
+#[test]
+fn test_histogram_and_range_buckets() {
+  let query = Query::builder()
+    .text("running")
+    .options(SearchOptions::default().histogram("price", 50.0).range(
+      "price",
+      [
+        NumericRange::below(50.0),
+        NumericRange::between(50.0, 100.0),
+        NumericRange::above(100.0),
+      ],
+    ))
+    .build();
+
+  let response = engine().search_response(&products(), &query);
+
+  let histogram: Vec<(Option<f64>, usize)> = response.histograms["price"]
+    .iter()
+    .map(|bucket| (bucket.from, bucket.count))
+    .collect();
+  assert_eq!(
+    histogram,
+    vec![(Some(0.0), 1), (Some(50.0), 1), (Some(100.0), 1)]
+  );
+
+  let ranges: Vec<usize> = response.ranges["price"]
+    .iter()
+    .map(|bucket| bucket.count)
+    .collect();
+  assert_eq!(ranges, vec![1, 1, 1]);
+  assert_eq!(response.ranges["price"][0].from, None);
+  assert_eq!(response.ranges["price"][0].to, Some(50.0));
+}
+
+#[test]
+fn test_stats() {
+  let query = Query::builder()
+    .text("running")
+    .options(
+      SearchOptions::default()
+        .limit(1)
+        .stats(["price", "missing"]),
+    )
+    .build();
+
+  let response = engine().search_response(&products(), &query);
+
+  let price = &response.stats["price"];
+  assert_eq!(price.count, 3);
+  assert_eq!(price.min, Some(12.5));
+  assert_eq!(price.max, Some(129.0));
+  assert_eq!(price.sum, 230.5);
+  assert!((price.avg.unwrap() - 230.5 / 3.0).abs() < 1e-9);
+
+  let missing = &response.stats["missing"];
+  assert_eq!(missing.count, 0);
+  assert_eq!(missing.avg, None);
+}
@@ -0,0 +1,163 @@
+use searus::prelude::*;
+use serde_json::{json, Value};
+
+/// Posts tagged "rust", the later ones with more matching tags.
+fn posts() -> Vec<Value> {
+  (0..50)
+    .map(|i| {
+      let mut tags = vec!["rust".to_string()];
+      if i >= 40 {
+        tags.push("async".to_string());
+      }
+      if i >= 45 {
+        tags.push("tokio".to_string());
+      }
+      json!({ "id": i, "title": format!("Rust post {}", i), "tags": tags })
+    })
+    .collect()
+}
+
+fn engine(builder: SearusEngineBuilder<Value>) -> SearusEngine<Value> {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25())
+    .build();
+  builder
+    .with(Box::new(SemanticSearch::new(rules)))
+    .with(Box::new(TaggedSearch::new()))
+    .build()
+}
+
+fn ids(response: &SearchResponse<Value>) -> Vec<u64> {
+  let mut ids: Vec<u64> = response
+    .results
+    .iter()
+    .map(|m| m.item["id"].as_u64().unwrap())
+    .collect();
+  ids.sort_unstable();
+  ids
+}
+
+fn tags(budget: SearchOptions) -> Query {
+  Query::builder()
+    .tags(vec!["rust".into(), "async".into(), "tokio".into()])
+    .options(budget.limit(100))
+    .build()
+}
+
+#[test]
+fn test_searchers_keep_their_best_matches() {
+  let items = posts();
+  let engine = engine(SearusEngine::builder());
+
+  let response = engine.search_response(&items, &tags(SearchOptions::default()));
+  assert_eq!(response.total_matches, 50);
+  assert!(!response.truncated);
+
+  let response = engine.search_response(
+    &items,
+    &tags(SearchOptions::default().max_matches_per_searcher(5)),
+  );
+  assert_eq!(response.total_matches, 5);
+  assert!(response.truncated);
+  assert_eq!(ids(&response), [45, 46, 47, 48, 49]);
+
+  // The budget applies to each searcher.
+  let query = Query::builder()
+    .text("post 3")
+    .tags(vec!["tokio".into()])
+    .options(SearchOptions::default().max_matches_per_searcher(5))
+    .build();
+  let response = engine.search_response(&items, &query);
+  assert!(response.truncated);
+  assert!(response.total_matches > 5 && response.total_matches <= 10);
+}
+
+#[test]
+fn test_merged_matches_are_limited() {
+  let items = posts();
+  let engine = engine(SearusEngine::builder());
+
+  let response = engine.search_response(&items, &tags(SearchOptions::default().max_matches(10)));
+  assert_eq!(response.total_matches, 10);
+  assert!(response.truncated);
+  assert_eq!(ids(&response), (40..50).collect::<Vec<_>>());
+
+  // Budgets that keep every match don't truncate the response.
+  let response = engine.search_response(
+    &items,
+    &tags(
+      SearchOptions::default()
+        .max_matches(50)
+        .max_matches_per_searcher(50),
+    ),
+  );
+  assert_eq!(response.total_matches, 50);
+  assert!(!response.truncated);
+}
+
+#[test]
+fn test_engine_budgets_cap_the_query() {
+  let items = posts();
+  let engine = engine(SearusEngine::builder().max_matches_per_searcher(5));
+
+  for options in [
+    SearchOptions::default(),
+    SearchOptions::default().max_matches_per_searcher(20),
+  ] {
+    let response = engine.search_response(&items, &tags(options));
+    assert_eq!(response.total_matches, 5);
+    assert!(response.truncated);
+  }
+  let response = engine.search_response(
+    &items,
+    &tags(SearchOptions::default().max_matches_per_searcher(2)),
+  );
+  // Matches with the same score are kept in the order of their ids.
+  assert_eq!(ids(&response), [45, 46]);
+
+  let engine = engine_with_total(3);
+  let response = engine.search_response(&items, &tags(SearchOptions::default().max_matches(20)));
+  assert_eq!(response.total_matches, 3);
+}
+
+fn engine_with_total(max_matches: usize) -> SearusEngine<Value> {
+  engine(SearusEngine::builder().max_matches(max_matches))
+}
+
+/// Matches every item, ignoring the budget of the query.
+struct Everything;
+
+impl Searcher<Value> for Everything {
+  fn kind(&self) -> SearcherKind {
+    SearcherKind::Custom
+  }
+
+  fn search(&self, context: &SearchContext<Value>, _query: &Query) -> Vec<SearusMatch<Value>> {
+    context
+      .items
+      .iter()
+      .enumerate()
+      .map(|(i, item)| SearusMatch::new(item.clone(), i as f32, i))
+      .collect()
+  }
+}
+
+#[test]
+fn test_custom_searchers_are_held_to_the_budget() {
+  let items = posts();
+  let engine = SearusEngine::builder().with(Box::new(Everything)).build();
+  let query = Query::builder()
+    .options(SearchOptions::default().max_matches_per_searcher(3))
+    .build();
+
+  let response = engine.search_response(&items, &query);
+  assert!(response.truncated);
+  assert_eq!(ids(&response), [47, 48, 49]);
+
+  // The flag is only serialized when set.
+  let json = serde_json::to_value(&response).unwrap();
+  assert_eq!(json["truncated"], true);
+  let response = engine.search_response(&items, &Query::builder().build());
+  let json = serde_json::to_value(&response).unwrap();
+  assert!(json.get("truncated").is_none());
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "cli")]
+
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Creates an empty scratch directory for a test.
+fn scratch(name: &str) -> PathBuf {
+  let dir = std::env::temp_dir().join(format!("searus-cli-{}-{}", name, std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  dir
+}
+
+/// Runs the binary, asserting it succeeds, and returns its stdout.
+fn searus(args: &[&str]) -> String {
+  let output = Command::new(env!("CARGO_BIN_EXE_searus"))
+    .args(args)
+    .output()
+    .unwrap();
+  assert!(
+    output.status.success(),
+    "searus {:?} failed: {}",
+    args,
+    String::from_utf8_lossy(&output.stderr)
+  );
+  String::from_utf8(output.stdout).unwrap()
+}
+
+/// Runs a query with `--json` and returns the titles of the results.
+fn titles(index: &str, args: &[&str]) -> Vec<String> {
+  let mut all = vec!["query", index, "--json"];
+  all.extend_from_slice(args);
+  let response: Value = serde_json::from_str(&searus(&all)).unwrap();
+  response["results"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|m| m["item"]["title"].as_str().unwrap().to_string())
+    .collect()
+}
+
+#[test]
+fn test_index_and_query_mixed_files() {
+  let dir = scratch("mixed");
+  let csv = dir.join("books.csv");
+  let jsonl = dir.join("tools.jsonl");
+  let index = dir.join("index.json");
+  fs::write(
+    &csv,
+    "title,price,tags\nRust in Action,40,\"programming,rust\"\nPython Basics,20,programming\n",
+  )
+  .unwrap();
+  fs::write(
+    &jsonl,
+    "{\"title\": \"Garden Shears\", \"price\": 15, \"tags\": [\"garden\"]}\n",
+  )
+  .unwrap();
+
+  let (csv, jsonl, index) = (
+    csv.to_str().unwrap(),
+    jsonl.to_str().unwrap(),
+    index.to_str().unwrap(),
+  );
+  searus(&["index", csv, jsonl, "--out", index]);
+
+  assert_eq!(titles(index, &["--text", "rust"]), ["Rust in Action"]);
+  assert_eq!(titles(index, &["--tags", "garden"]), ["Garden Shears"]);
+  assert_eq!(
+    titles(index, &["--tags", "programming", "--filter", "price<30"]),
+    ["Python Basics"]
+  );
+
+  // Filters alone list the matching documents.
+  let mut cheap = titles(index, &["--filter", "price<=20"]);
+  cheap.sort();
+  assert_eq!(cheap, ["Garden Shears", "Python Basics"]);
+
+  let pretty = searus(&["query", index, "--text", "python"]);
+  assert!(pretty.contains("Python Basics"));
+  assert!(pretty.contains("1 of 1 matches"));
+}
+
+#[test]
+fn test_rejects_invalid_filters() {
+  let dir = scratch("invalid");
+  let json = dir.join("items.json");
+  let index = dir.join("index.json");
+  fs::write(&json, "[{\"title\": \"One\"}]").unwrap();
+  searus(&[
+    "index",
+    json.to_str().unwrap(),
+    "-o",
+    index.to_str().unwrap(),
+  ]);
+
+  let output = Command::new(env!("CARGO_BIN_EXE_searus"))
+    .args(["query", index.to_str().unwrap(), "--filter", "title"])
+    .output()
+    .unwrap();
+  assert!(!output.status.success());
+  assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid filter"));
+}
@@ -0,0 +1,119 @@
+use searus::document::{get_field_value, to_document};
+use searus::prelude::*;
+use serde_json::{json, Value};
+use std::borrow::Cow;
+
+fn products() -> Vec<Value> {
+  vec![
+    json!({
+      "name": "Trail running shoes",
+      "specs": { "brand": "Fleet", "colors": ["red", "black"], "weight": 280 },
+      "labels": ["outdoor", "sport"],
+    }),
+    json!({
+      "name": "Leather boots",
+      "specs": { "brand": "Oakline", "colors": ["brown"], "weight": 900 },
+      "labels": ["outdoor"],
+    }),
+    json!({
+      "name": "Canvas sneakers",
+      "specs": { "brand": "Fleet", "colors": ["white", "red"] },
+    }),
+    json!("not an object"),
+  ]
+}
+
+fn names(results: &[SearusMatch<Value>]) -> Vec<&str> {
+  results
+    .iter()
+    .map(|m| m.item["name"].as_str().unwrap())
+    .collect()
+}
+
+#[test]
+fn test_values_are_borrowed() {
+  let product = json!({ "name": "Shoes" });
+  assert!(matches!(to_document(&product), Ok(Cow::Borrowed(_))));
+
+  let letters = vec!["a", "b"];
+  let document = to_document(&letters).unwrap();
+  assert!(matches!(document, Cow::Owned(_)));
+  assert_eq!(get_field_value(&document, "1"), Some(&json!("b")));
+}
+
+#[test]
+fn test_semantic_search_over_nested_fields_and_arrays() {
+  let items = products();
+  let rules = SemanticRules::builder()
+    .field("name", FieldRule::bm25().priority(2))
+    .field("specs.colors", FieldRule::bm25())
+    .build();
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .build();
+
+  // Every element of an array field is searched.
+  let results = engine.search(&items, &Query::builder().text("red").build());
+  let mut found = names(&results);
+  found.sort_unstable();
+  assert_eq!(found, ["Canvas sneakers", "Trail running shoes"]);
+
+  let query = Query::builder()
+    .text("red")
+    .filters(FilterExpr::And(vec![
+      Query::filter(Query::COMPARE)
+        .eq("specs.brand", "Fleet")
+        .build(),
+      Query::filter(Query::COMPARE)
+        .contains("specs.colors", "black")
+        .build(),
+    ]))
+    .options(SearchOptions::default().facets(["labels", "specs.brand"]))
+    .build();
+  let response = engine.search_response(&items, &query);
+  assert_eq!(names(&response.results), ["Trail running shoes"]);
+  let labels: Vec<(&str, usize)> = response.facets["labels"]
+    .iter()
+    .map(|facet| (facet.value.as_str(), facet.count))
+    .collect();
+  assert_eq!(labels.len(), 2);
+  assert!(labels.contains(&("outdoor", 1)) && labels.contains(&("sport", 1)));
+}
+
+#[test]
+fn test_fuzzy_and_tagged_search_over_nested_fields() {
+  let items = products();
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(FuzzySearch::new(vec!["specs.brand".to_string()])))
+    .build();
+  let results = engine.search(&items, &Query::builder().text("okline").build());
+  assert_eq!(names(&results), ["Leather boots"]);
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(TaggedSearch::with_field("specs.colors")))
+    .build();
+  let query = Query::builder().tags(vec!["brown".to_string()]).build();
+  assert_eq!(names(&engine.search(&items, &query)), ["Leather boots"]);
+}
+
+#[test]
+fn test_scoring_and_filters_on_missing_fields() {
+  let items = products();
+  let filter = Query::filter(Query::COMPARE)
+    .lt("specs.weight", 500)
+    .build();
+  let kept: Vec<bool> = items.iter().map(|item| filter.evaluate(item)).collect();
+  assert_eq!(kept, [true, false, false, false]);
+
+  let rules = SemanticRules::builder()
+    .field("name", FieldRule::bm25())
+    .build();
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .score_modifier(Box::new(FieldValueBoost::new("specs.weight").weight(0.01)))
+    .build();
+  let query = Query::builder().text("shoes boots sneakers").build();
+  let results = engine.search(&items, &query);
+  assert_eq!(results[0].item["name"], "Leather boots");
+}
@@ -0,0 +1,248 @@
+use searus::embeddings::{Chunker, Pooling, StubTextEmbedder, TextEmbedder};
+
+#[test]
+fn test_chunk_sentences() {
+  let text = "He said \"Stop.\" Then he left!\n\nA heading\nwraps here\n\n  Last one?  ";
+  assert_eq!(
+    Chunker::sentences(1).chunk(text),
+    vec![
+      "He said \"Stop.\"",
+      "Then he left!",
+      "A heading\nwraps here",
+      "Last one?"
+    ]
+  );
+
+  // Full-width terminators end sentences without spaces after them.
+  assert_eq!(
+    Chunker::sentences(1).chunk("今日は晴れ。「そうですね。」明日は？"),
+    vec!["今日は晴れ。", "「そうですね。」", "明日は？"]
+  );
+
+  // Versions and decimals don't end sentences.
+  assert_eq!(
+    Chunker::sentences(1).chunk("Version 1.2 is out. Upgrade."),
+    vec!["Version 1.2 is out.", "Upgrade."]
+  );
+}
+
+#[test]
+fn test_chunk_overlap() {
+  let text = "a b c d e f g";
+  assert_eq!(
+    Chunker::words(3).with_overlap(1).chunk(text),
+    vec!["a b c", "c d e", "e f g"]
+  );
+  assert_eq!(Chunker::words(3).chunk(text), vec!["a b c", "d e f", "g"]);
+
+  // Overlap is capped so that chunks move forward one word at a time.
+  assert_eq!(
+    Chunker::words(2).with_overlap(5).chunk("a b c"),
+    vec!["a b", "b c"]
+  );
+  assert_eq!(Chunker::words(10).chunk(text), vec![text]);
+  assert!(Chunker::words(3).chunk(" \n ").is_empty());
+}
+
+#[test]
+fn test_pooled_embeddings() {
+  let embedder = StubTextEmbedder::new(4);
+  let text = "one two three four five";
+  let vectors = Chunker::words(2).embed(&embedder, text).unwrap();
+  assert_eq!(vectors.len(), 3);
+  assert_eq!(vectors[2], embedder.embed("five").unwrap());
+
+  let mean = Chunker::words(2)
+    .embed_pooled(&embedder, text, Pooling::Mean)
+    .unwrap();
+  for (i, x) in mean.iter().enumerate() {
+    let expected = vectors.iter().map(|v| v[i]).sum::<f32>() / 3.0;
+    assert!((x - expected).abs() < 1e-6);
+  }
+
+  assert!(Chunker::words(2).embed(&embedder, "").unwrap().is_empty());
+  let error = Chunker::words(2)
+    .embed_pooled(&embedder, "", Pooling::Max)
+    .unwrap_err();
+  assert_eq!(error, "Cannot pool an empty list of vectors");
+  let error = Pooling::Mean
+    .pool(&[vec![1.0, 2.0], vec![1.0]])
+    .unwrap_err();
+  assert_eq!(error, "Expected a vector of 2 dimensions, got 1");
+}
+
+#[cfg(feature = "candle")]
+mod candle {
+  use candle_core::{DType, Device};
+  use candle_nn::{VarBuilder, VarMap};
+  use candle_transformers::models::bert::{BertModel, Config};
+  use searus::embeddings::{CandleTextEmbedder, TextEmbedder};
+  use serde_json::json;
+  use std::path::PathBuf;
+
+  /// Writes a tiny, randomly initialized BERT model with a word-level tokenizer.
+  fn tiny_model() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("searus-tiny-bert-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let config = json!({
+      "vocab_size": 8,
+      "hidden_size": 16,
+      "num_hidden_layers": 2,
+      "num_attention_heads": 2,
+      "intermediate_size": 32,
+      "hidden_act": "gelu",
+      "hidden_dropout_prob": 0.0,
+      "max_position_embeddings": 32,
+      "type_vocab_size": 2,
+      "initializer_range": 0.02,
+      "layer_norm_eps": 1e-12,
+      "pad_token_id": 0
+    });
+    std::fs::write(dir.join("config.json"), config.to_string()).unwrap();
+
+    let tokenizer = json!({
+      "version": "1.0",
+      "truncation": null,
+      "padding": null,
+      "added_tokens": [],
+      "normalizer": null,
+      "pre_tokenizer": { "type": "Whitespace" },
+      "post_processor": null,
+      "decoder": null,
+      "model": {
+        "type": "WordLevel",
+        "vocab": { "[PAD]": 0, "[UNK]": 1, "rust": 2, "search": 3, "engine": 4, "fast": 5 },
+        "unk_token": "[UNK]"
+      }
+    });
+    std::fs::write(dir.join("tokenizer.json"), tokenizer.to_string()).unwrap();
+
+    let config: Config = serde_json::from_value(config).unwrap();
+    let varmap = VarMap::new();
+    BertModel::load(
+      VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu),
+      &config,
+    )
+    .unwrap();
+    varmap.save(dir.join("model.safetensors")).unwrap();
+
+    dir
+  }
+
+  #[test]
+  fn test_candle_embedder_pools_batches() {
+    let dir = tiny_model();
+    let embedder = CandleTextEmbedder::builder()
+      .model_dir(&dir)
+      .batch_size(2)
+      .build()
+      .unwrap();
+    assert_eq!(embedder.dimension(), 16);
+
+    let texts = ["rust", "fast rust search engine", "search"];
+    let batch = embedder.embed_batch(&texts).unwrap();
+    assert_eq!(batch.len(), 3);
+
+    for (text, vector) in texts.iter().zip(&batch) {
+      assert_eq!(vector.len(), 16);
+      let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+      assert!((norm - 1.0).abs() < 1e-4);
+
+      // Padding a text to the longest in its batch doesn't change its vector.
+      let single = embedder.embed(text).unwrap();
+      let diff = single
+        .iter()
+        .zip(vector)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f32::max);
+      assert!(diff < 1e-4, "{} differs by {}", text, diff);
+    }
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
+
+#[cfg(feature = "ollama")]
+mod ollama {
+  use searus::embeddings::{OllamaEmbedder, TextEmbedder};
+  use serde_json::{json, Value};
+  use std::io::{BufRead, BufReader, Read, Write};
+  use std::net::TcpListener;
+  use std::sync::mpsc::{self, Receiver};
+  use std::thread;
+
+  /// Serves one canned response per request, and sends back the request bodies.
+  fn serve(responses: Vec<(u16, Value)>) -> (String, Receiver<Value>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, requests) = mpsc::channel();
+
+    thread::spawn(move || {
+      for (status, body) in responses {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut length = 0;
+        loop {
+          let mut line = String::new();
+          reader.read_line(&mut line).unwrap();
+          if line.trim().is_empty() {
+            break;
+          }
+          if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+              length = value.trim().parse().unwrap();
+            }
+          }
+        }
+        let mut request = vec![0; length];
+        reader.read_exact(&mut request).unwrap();
+        sender
+          .send(serde_json::from_slice(&request).unwrap())
+          .unwrap();
+
+        let body = body.to_string();
+        write!(
+          reader.get_mut(),
+          "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+          status,
+          body.len(),
+          body
+        )
+        .unwrap();
+      }
+    });
+
+    (url, requests)
+  }
+
+  #[test]
+  fn test_ollama_embedder_batches_requests() {
+    let (url, requests) = serve(vec![
+      (
+        200,
+        json!({ "model": "nomic-embed-text", "embeddings": [[0.1, 0.2], [0.3, 0.4]] }),
+      ),
+      (
+        404,
+        json!({ "error": "model \"missing\" not found, try pulling it first" }),
+      ),
+    ]);
+
+    let embedder = OllamaEmbedder::new("nomic-embed-text")
+      .with_url(format!("{}/", url))
+      .with_dimensions(2);
+    let vectors = embedder.embed_batch(&["rust", "search"]).unwrap();
+    assert_eq!(vectors, vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+    assert_eq!(
+      requests.recv().unwrap(),
+      json!({ "model": "nomic-embed-text", "input": ["rust", "search"], "dimensions": 2 })
+    );
+
+    let error = OllamaEmbedder::new("missing")
+      .with_url(url)
+      .embed("rust")
+      .unwrap_err();
+    assert!(error.contains("not found"), "{}", error);
+  }
+}
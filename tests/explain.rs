@@ -0,0 +1,115 @@
+use searus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Post {
+  title: String,
+  content: String,
+  tags: Vec<String>,
+}
+
+fn posts() -> Vec<Post> {
+  vec![
+    Post {
+      title: "Rust search engines".to_string(),
+      content: "Building a search engine in rust".to_string(),
+      tags: vec!["rust".to_string()],
+    },
+    Post {
+      title: "Python tips".to_string(),
+      content: "Some rust and python interop".to_string(),
+      tags: vec!["python".to_string()],
+    },
+    Post {
+      title: "Cooking pasta".to_string(),
+      content: "Boil water".to_string(),
+      tags: vec!["food".to_string()],
+    },
+  ]
+}
+
+fn engine() -> SearusEngine<Post> {
+  SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("title", FieldRule::bm25().priority(2))
+        .field("content", FieldRule::bm25())
+        .build(),
+    )))
+    .with(Box::new(TaggedSearch::new()))
+    .score_fn(|_: &Post, score| score * 2.0)
+    .build()
+}
+
+#[test]
+fn test_explain_matches_final_score() {
+  let query = Query::builder()
+    .text("rust")
+    .tags(vec!["rust".to_string()])
+    .options(
+      SearchOptions::default()
+        .weight(SearcherKind::Tags, 0.5)
+        .explain(true),
+    )
+    .build();
+
+  let results = engine().search(&posts(), &query);
+  assert_eq!(results.len(), 2);
+
+  for result in &results {
+    let explanation = result.explain().expect("explanation should be present");
+    assert!((explanation.value - result.score).abs() < 1e-5);
+  }
+
+  // The top result is explained by the modifier, then both searchers.
+  let top = results[0].explain().unwrap();
+  assert_eq!(top.description, "adjusted by score modifier:");
+  let sum = &top.details[0];
+  assert_eq!(sum.details.len(), 2);
+  assert!(top.to_string().contains("term \"rust\""));
+}
+
+#[test]
+fn test_explain_disabled_by_default() {
+  let results = engine().search(&posts(), &Query::builder().text("rust").build());
+
+  assert!(!results.is_empty());
+  assert!(results.iter().all(|result| result.explain().is_none()));
+}
+
+#[test]
+fn test_fuzzy_field_weights() {
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      FuzzySearch::new(vec!["content".to_string()]).with_field_weight("title", 3.0),
+    ))
+    .build();
+
+  let query = Query::builder()
+    .text("rusty")
+    .options(SearchOptions::default().explain(true))
+    .build();
+  let results = engine.search(&posts(), &query);
+  assert_eq!(results.len(), 2);
+
+  // Both fields of the first post match; its title match is tripled.
+  let first = &results[0];
+  assert_eq!(first.item.title, "Rust search engines");
+  let title = first.field_scores["title"];
+  let content = first.field_scores["content"];
+  assert!((title - 3.0 * content).abs() < 1e-6);
+
+  let fields: Vec<&str> = first
+    .details
+    .iter()
+    .filter_map(|detail| match detail {
+      SearchDetail::Fuzzy { field, .. } => Some(field.as_str()),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(fields, vec!["content", "title"]);
+
+  let explanation = first.explain().unwrap().to_string();
+  assert!(explanation.contains("field \"title\", weight 3 times:"));
+  assert!(explanation.contains("jaro-winkler similarity of \"rusty\" and \"rust\""));
+}
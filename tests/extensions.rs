@@ -10,7 +10,7 @@ struct Item {
 struct QueryRewriteExt;
 
 impl SearusExtension<Item> for QueryRewriteExt {
-  fn before_query(&self, query: &mut Query) {
+  fn before_query(&self, query: &mut Query, _state: &mut SearchState) {
     if let Some(text) = &query.text {
       if text == "ml" {
         query.text = Some("machine learning".to_string());
@@ -22,7 +22,12 @@ impl SearusExtension<Item> for QueryRewriteExt {
 struct ResultModifyExt;
 
 impl SearusExtension<Item> for ResultModifyExt {
-  fn after_limit(&self, _query: &Query, results: &mut Vec<SearusMatch<Item>>) {
+  fn after_limit(
+    &self,
+    _query: &Query,
+    results: &mut Vec<SearusMatch<Item>>,
+    _state: &mut SearchState,
+  ) {
     for m in results {
       m.score += 0.1; // Boost score
     }
@@ -32,7 +37,7 @@ impl SearusExtension<Item> for ResultModifyExt {
 struct AddItemExt;
 
 impl SearusExtension<Item> for AddItemExt {
-  fn before_items(&self, _query: &Query, items: &mut Vec<Item>) {
+  fn before_items(&self, _query: &Query, items: &mut Vec<Item>, _state: &mut SearchState) {
     items.push(Item {
       id: 999,
       name: "Added by extension".to_string(),
@@ -77,7 +82,7 @@ fn test_extensions() {
   assert!(ml_match.is_some(), "Query rewrite failed");
 
   // Check score boost
-  if let Some(_) = ml_match {
+  if ml_match.is_some() {
     // BM25 score is > 0. With boost, it should be higher.
     // Exact score depends on BM25 implementation, but we know it's boosted by 0.1
     // Let's just check if we got results.
@@ -97,3 +102,550 @@ fn test_extensions() {
     assert!(m.score >= 0.1, "Score boost failed");
   }
 }
+
+#[test]
+fn test_extensions_search_each_item_once() {
+  let items = vec![
+    Item {
+      id: 1,
+      name: "rust".to_string(),
+    },
+    Item {
+      id: 2,
+      name: "python".to_string(),
+    },
+  ];
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("name", FieldRule::tokenized())
+        .build(),
+    )))
+    .with_extension(Box::new(AddItemExt))
+    .build();
+
+  let results = engine.search(&items, &Query::builder().text("rust").build());
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].id, 0);
+}
+
+#[test]
+fn test_dedup_extension() {
+  // The best match of the duplicates comes last.
+  let items = vec![
+    Item {
+      id: 1,
+      name: "rust search engine".to_string(),
+    },
+    Item {
+      id: 2,
+      name: "rust".to_string(),
+    },
+    Item {
+      id: 1,
+      name: "rust search".to_string(),
+    },
+  ];
+  let builder = || {
+    SearusEngine::builder().with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("name", FieldRule::bm25())
+        .build(),
+    )))
+  };
+  let query = Query::builder().text("rust search").build();
+
+  let results = builder().build().search(&items, &query);
+  let names: Vec<&str> = results.iter().map(|m| m.item.name.as_str()).collect();
+  assert_eq!(names, ["rust search", "rust search engine", "rust"]);
+  assert!(results[0].score > results[1].score);
+
+  let engine = builder()
+    .with_extension(Box::new(DedupExtension::by_key(|item: &Item| {
+      Some(item.id.to_string())
+    })))
+    .build();
+  let results = engine.search(&items, &query);
+
+  let names: Vec<&str> = results.iter().map(|m| m.item.name.as_str()).collect();
+  assert_eq!(names, ["rust search", "rust"]);
+}
+
+#[test]
+fn test_dedup_by_field_compares_json_values() {
+  #[derive(Debug, Clone, Serialize)]
+  struct Page {
+    key: serde_json::Value,
+    title: String,
+  }
+
+  let pages: Vec<Page> = [
+    serde_json::json!("1"),
+    serde_json::json!(1),
+    serde_json::json!(null),
+    serde_json::json!(null),
+  ]
+  .into_iter()
+  .map(|key| Page {
+    key,
+    title: "rust".to_string(),
+  })
+  .collect();
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("title", FieldRule::default())
+        .build(),
+    )))
+    .with_extension(Box::new(DedupExtension::by_field("key")))
+    .build();
+  let results = engine.search(&pages, &Query::builder().text("rust").build());
+
+  // The string "1" and the number 1 differ, and null keys are never removed.
+  let mut ids: Vec<usize> = results.iter().map(|m| m.id).collect();
+  ids.sort();
+  assert_eq!(ids, [0, 1, 2, 3]);
+}
+
+/// Appends the original query text, recorded by a rewrite pipeline, to the
+/// name of every result.
+struct OriginalTextExt;
+
+impl SearusExtension<Item> for OriginalTextExt {
+  fn after_limit(
+    &self,
+    query: &Query,
+    results: &mut Vec<SearusMatch<Item>>,
+    _state: &mut SearchState,
+  ) {
+    let original = query.metadata.get(ORIGINAL_TEXT).and_then(|v| v.as_str());
+    let source = query.metadata.get("source").and_then(|v| v.as_str());
+    for m in results {
+      m.item.name = format!("{} ({:?}, {:?})", m.item.name, original, source);
+    }
+  }
+}
+
+#[test]
+fn test_query_metadata_is_shared_between_extensions() {
+  let items = vec![Item {
+    id: 1,
+    name: "machine learning".to_string(),
+  }];
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("name", FieldRule::default())
+        .build(),
+    )))
+    .with_extension(Box::new(
+      RewritePipeline::new().then(AcronymExpansion::new().with("ml", "machine learning")),
+    ))
+    .with_extension(Box::new(OriginalTextExt))
+    .build();
+
+  let query = Query::builder()
+    .text("ML")
+    .metadata("source", "api")
+    .build();
+  let results = engine.search(&items, &query);
+  assert_eq!(
+    results[0].item.name,
+    r#"machine learning (Some("ML"), Some("api"))"#
+  );
+
+  // Nothing is recorded when the pipeline leaves the text unchanged.
+  let query = Query::builder().text("machine").build();
+  let results = engine.search(&items, &query);
+  assert_eq!(results[0].item.name, "machine learning (None, None)");
+}
+
+/// Scores every item by its id, for the query text "ids".
+struct IdSearcher;
+
+impl Searcher<Item> for IdSearcher {
+  fn kind(&self) -> SearcherKind {
+    SearcherKind::Custom
+  }
+
+  fn search(&self, context: &SearchContext<Item>, query: &Query) -> Vec<SearusMatch<Item>> {
+    if query.text.as_deref() != Some("ids") {
+      return Vec::new();
+    }
+    context
+      .items
+      .iter()
+      .enumerate()
+      .map(|(i, item)| SearusMatch::new(item.clone(), item.id as f32, i))
+      .collect()
+  }
+}
+
+/// Configures the id searcher from the query metadata.
+struct IdSearcherConfigExt;
+
+impl SearusExtension<Item> for IdSearcherConfigExt {
+  fn before_searcher(&self, query: &Query, config: &mut SearcherConfig, _state: &mut SearchState) {
+    if config.kind != SearcherKind::Custom {
+      return;
+    }
+    config.query.text = Some("ids".to_string());
+    config.min_score = query
+      .metadata
+      .get("min_id")
+      .and_then(|v| v.as_f64())
+      .map(|v| v as f32);
+    config.enabled = !query.metadata.contains_key("disable_ids");
+  }
+}
+
+#[test]
+fn test_before_searcher_configures_searchers_per_query() {
+  let items: Vec<Item> = (1..=3)
+    .map(|id| Item {
+      id,
+      name: format!("item {}", id),
+    })
+    .collect();
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(IdSearcher))
+    .with_extension(Box::new(IdSearcherConfigExt))
+    .build();
+  let ids = |query: &Query| -> Vec<usize> {
+    engine
+      .search(&items, query)
+      .iter()
+      .map(|m| m.item.id)
+      .collect()
+  };
+
+  // The searcher sees its own rewritten query.
+  let query = Query::builder().text("anything").build();
+  assert_eq!(ids(&query), vec![3, 2, 1]);
+
+  let query = Query::builder().metadata("min_id", 2).build();
+  assert_eq!(ids(&query), vec![3, 2]);
+
+  let query = Query::builder().metadata("disable_ids", true).build();
+  assert!(ids(&query).is_empty());
+}
+
+/// Drops the matches of custom searchers.
+struct DropCustomExt;
+
+impl SearusExtension<Item> for DropCustomExt {
+  fn after_searcher(
+    &self,
+    _query: &Query,
+    kind: SearcherKind,
+    results: &mut Vec<SearusMatch<Item>>,
+    _state: &mut SearchState,
+  ) {
+    if kind == SearcherKind::Custom {
+      results.clear();
+    }
+  }
+}
+
+#[test]
+fn test_after_searcher_receives_searcher_kind() {
+  let items = vec![Item {
+    id: 1,
+    name: "ids".to_string(),
+  }];
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(IdSearcher))
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("name", FieldRule::default())
+        .build(),
+    )))
+    .with_extension(Box::new(DropCustomExt))
+    .build();
+
+  let query = Query::builder().text("ids").build();
+  let results = engine.search(&items, &query);
+  assert_eq!(results.len(), 1);
+  assert!(results[0]
+    .searcher_scores
+    .contains_key(&SearcherKind::Semantic));
+  assert!(!results[0]
+    .searcher_scores
+    .contains_key(&SearcherKind::Custom));
+}
+
+/// The hooks that ran during a search, in order.
+#[derive(Default)]
+struct HookLog(Vec<String>);
+
+/// Records every hook in the search state, prefixed by the `String` seeded
+/// in the state, if any.
+struct HookLogExt;
+
+impl HookLogExt {
+  fn log(&self, state: &mut SearchState, hook: &str) {
+    let prefix = state.get::<String>().cloned().unwrap_or_default();
+    state.get_or_default::<HookLog>().0.push(prefix + hook);
+  }
+}
+
+impl SearusExtension<Item> for HookLogExt {
+  fn before_query(&self, _query: &mut Query, state: &mut SearchState) {
+    self.log(state, "before_query");
+  }
+
+  fn before_items(&self, _query: &Query, _items: &mut Vec<Item>, state: &mut SearchState) {
+    self.log(state, "before_items");
+  }
+
+  fn before_searcher(&self, _query: &Query, _config: &mut SearcherConfig, state: &mut SearchState) {
+    self.log(state, "before_searcher");
+  }
+
+  fn after_searcher(
+    &self,
+    _query: &Query,
+    _kind: SearcherKind,
+    _results: &mut Vec<SearusMatch<Item>>,
+    state: &mut SearchState,
+  ) {
+    self.log(state, "after_searcher");
+  }
+
+  fn before_merge(
+    &self,
+    _query: &Query,
+    _results: &mut Vec<(SearcherKind, Vec<SearusMatch<Item>>)>,
+    state: &mut SearchState,
+  ) {
+    self.log(state, "before_merge");
+  }
+
+  fn after_merge(
+    &self,
+    _query: &Query,
+    _results: &mut Vec<SearusMatch<Item>>,
+    state: &mut SearchState,
+  ) {
+    self.log(state, "after_merge");
+  }
+
+  fn before_limit(
+    &self,
+    _query: &Query,
+    _results: &mut Vec<SearusMatch<Item>>,
+    state: &mut SearchState,
+  ) {
+    self.log(state, "before_limit");
+  }
+
+  fn after_limit(
+    &self,
+    _query: &Query,
+    _results: &mut Vec<SearusMatch<Item>>,
+    state: &mut SearchState,
+  ) {
+    self.log(state, "after_limit");
+  }
+}
+
+#[test]
+fn test_search_state_is_shared_by_all_hooks() {
+  let items = vec![Item {
+    id: 1,
+    name: "ids".to_string(),
+  }];
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(IdSearcher))
+    .with_extension(Box::new(HookLogExt))
+    .build();
+
+  let mut state = SearchState::new();
+  state.insert("1:".to_string());
+  let query = Query::builder().text("ids").build();
+  let results = engine.search_with_state(&items, &query, &mut state);
+  assert_eq!(results.len(), 1);
+  assert_eq!(
+    state.get::<HookLog>().unwrap().0,
+    vec![
+      "1:before_query",
+      "1:before_items",
+      "1:before_searcher",
+      "1:after_searcher",
+      "1:before_merge",
+      "1:after_merge",
+      "1:before_limit",
+      "1:after_limit",
+    ]
+  );
+
+  // Every search starts from its own state.
+  state.remove::<HookLog>();
+  engine.search(&items, &query);
+  assert!(state.get::<HookLog>().is_none());
+}
+
+mod access {
+  use searus::extensions::AccessControlExtension;
+  use searus::prelude::*;
+  use serde_json::{json, Value};
+
+  fn docs() -> Vec<Value> {
+    vec![
+      json!({ "title": "Rust notes", "owner": "alice", "visibility": "private", "lang": "en" }),
+      json!({ "title": "Rust guide", "owner": "bob", "visibility": "public", "lang": "en" }),
+      json!({ "title": "Rust drafts", "owner": "bob", "visibility": "private", "lang": "en" }),
+      json!({ "title": "Rust Leitfaden", "owner": "carol", "visibility": "public", "lang": "de" }),
+    ]
+  }
+
+  /// Returns the titles visible to a query, in item order.
+  fn visible(query: Query) -> Vec<String> {
+    let engine = SearusEngine::builder()
+      .with(Box::new(SemanticSearch::new(
+        SemanticRules::builder()
+          .field("title", FieldRule::tokenized())
+          .build(),
+      )))
+      .with_extension(Box::new(
+        AccessControlExtension::owner_or_public("owner", "visibility", "public")
+          .bypass_role("admin"),
+      ))
+      .build();
+    let mut results = engine.search(&docs(), &query);
+    results.sort_by_key(|m| m.id);
+    results
+      .into_iter()
+      .map(|m| m.item["title"].as_str().unwrap().to_string())
+      .collect()
+  }
+
+  fn rust() -> QueryBuilder {
+    Query::builder().text("rust")
+  }
+
+  #[test]
+  fn test_anonymous_queries_only_see_public_items() {
+    assert_eq!(visible(rust().build()), ["Rust guide", "Rust Leitfaden"]);
+  }
+
+  #[test]
+  fn test_owners_also_see_their_private_items() {
+    assert_eq!(
+      visible(rust().principal(Principal::new("alice")).build()),
+      ["Rust notes", "Rust guide", "Rust Leitfaden"]
+    );
+    assert_eq!(
+      visible(rust().principal(Principal::new("bob")).build()),
+      ["Rust guide", "Rust drafts", "Rust Leitfaden"]
+    );
+    // Other roles don't widen access.
+    let editor = Principal::new("carol").with_role("editor");
+    assert_eq!(
+      visible(rust().principal(editor).build()),
+      ["Rust guide", "Rust Leitfaden"]
+    );
+  }
+
+  #[test]
+  fn test_bypass_role_sees_every_item() {
+    let admin = Principal::new("dave").with_role("admin");
+    assert_eq!(visible(rust().principal(admin).build()).len(), 4);
+  }
+
+  #[test]
+  fn test_security_filter_is_anded_with_query_filters() {
+    let english = Query::filter(Query::COMPARE).eq("lang", "en").build();
+    assert_eq!(
+      visible(rust().filters(english.clone()).build()),
+      ["Rust guide"]
+    );
+    assert_eq!(
+      visible(
+        rust()
+          .filters(english)
+          .principal(Principal::new("bob"))
+          .build()
+      ),
+      ["Rust guide", "Rust drafts"]
+    );
+
+    // Queries can't widen access with their own filters.
+    let private = Query::filter(Query::COMPARE)
+      .eq("visibility", "private")
+      .build();
+    assert_eq!(
+      visible(
+        rust()
+          .filters(private)
+          .principal(Principal::new("alice"))
+          .build()
+      ),
+      ["Rust notes"]
+    );
+  }
+
+  #[test]
+  fn test_security_filter_holds_for_every_searcher() {
+    /// Drops the filters of every searcher's query.
+    struct DropFilters;
+
+    impl SearusExtension<Value> for DropFilters {
+      fn before_searcher(
+        &self,
+        _query: &Query,
+        config: &mut SearcherConfig,
+        _state: &mut SearchState,
+      ) {
+        config.query.filters = None;
+      }
+    }
+
+    /// Matches every item, ignoring the filters.
+    struct MatchAll;
+
+    impl Searcher<Value> for MatchAll {
+      fn kind(&self) -> SearcherKind {
+        SearcherKind::Custom
+      }
+
+      fn search(&self, context: &SearchContext<Value>, _query: &Query) -> Vec<SearusMatch<Value>> {
+        context
+          .items
+          .iter()
+          .enumerate()
+          .map(|(i, item)| SearusMatch::new(item.clone(), 1.0, i))
+          .collect()
+      }
+    }
+
+    let engine = SearusEngine::builder()
+      .with(Box::new(MatchAll))
+      .with(Box::new(SemanticSearch::new(
+        SemanticRules::builder()
+          .field("title", FieldRule::tokenized())
+          .build(),
+      )))
+      .with_extension(Box::new(AccessControlExtension::owner_or_public(
+        "owner",
+        "visibility",
+        "public",
+      )))
+      .with_extension(Box::new(DropFilters))
+      .build();
+
+    let query = rust().principal(Principal::new("alice")).build();
+    let mut results = engine.search(&docs(), &query);
+    results.sort_by_key(|m| m.id);
+    let titles: Vec<&str> = results
+      .iter()
+      .map(|m| m.item["title"].as_str().unwrap())
+      .collect();
+    assert_eq!(titles, ["Rust notes", "Rust guide", "Rust Leitfaden"]);
+  }
+}
@@ -0,0 +1,441 @@
+use searus::prelude::*;
+use serde::{Serialize, Serializer};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn products() -> Vec<serde_json::Value> {
+  vec![
+    json!({ "name": "Laptop", "category": "Electronics", "price": 1200, "tags": ["work", "portable"] }),
+    json!({ "name": "Lamp", "category": "Home", "price": 40, "tags": ["light"] }),
+    json!({ "name": "Shovel", "category": "Garden", "price": 25, "tags": [] }),
+    json!({ "name": "Gift card", "price": 50 }),
+  ]
+}
+
+fn names(filter: &FilterExpr) -> Vec<String> {
+  products()
+    .iter()
+    .filter(|item| filter.evaluate(item))
+    .map(|item| item["name"].as_str().unwrap().to_string())
+    .collect()
+}
+
+#[test]
+fn test_in_and_not_in() {
+  let filter = Query::filter(Query::COMPARE)
+    .is_in("category", ["Electronics", "Home"])
+    .build();
+  assert_eq!(names(&filter), vec!["Laptop", "Lamp"]);
+
+  // Items without the field are neither in nor out of the list.
+  let filter = Query::filter(Query::COMPARE)
+    .not_in("category", ["Electronics", "Home"])
+    .build();
+  assert_eq!(names(&filter), vec!["Shovel"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .is_in("price", [40, 50])
+    .build();
+  assert_eq!(names(&filter), vec!["Lamp", "Gift card"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .is_in("tags", ["light", "portable"])
+    .build();
+  assert_eq!(names(&filter), vec!["Laptop", "Lamp"]);
+}
+
+#[test]
+fn test_list_values_deserialize() {
+  let filter: FilterExpr = serde_json::from_value(json!({
+    "Compare": { "field": "category", "op": "In", "value": ["Garden", "Home"] }
+  }))
+  .unwrap();
+  assert_eq!(names(&filter), vec!["Lamp", "Shovel"]);
+}
+
+#[test]
+fn test_between() {
+  let filter = Query::filter(Query::COMPARE)
+    .between("price", 40, 1200)
+    .build();
+  assert_eq!(names(&filter), vec!["Laptop", "Lamp", "Gift card"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .between_exclusive("price", 40, 1200)
+    .build();
+  assert_eq!(names(&filter), vec!["Gift card"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .between("name", "L", "M")
+    .build();
+  assert_eq!(names(&filter), vec!["Laptop", "Lamp"]);
+
+  // Bounds are inclusive unless stated otherwise.
+  let filter: FilterExpr = serde_json::from_value(json!({
+    "Between": { "field": "price", "low": 25, "high": 40 }
+  }))
+  .unwrap();
+  assert_eq!(names(&filter), vec!["Lamp", "Shovel"]);
+}
+
+#[test]
+fn test_starts_with_and_ends_with() {
+  let filter = Query::filter(Query::COMPARE)
+    .starts_with("name", "La")
+    .build();
+  assert_eq!(names(&filter), vec!["Laptop", "Lamp"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .starts_with("name", "la")
+    .build();
+  assert!(names(&filter).is_empty());
+
+  let filter = Query::filter(Query::COMPARE)
+    .starts_with_ignore_case("name", "la")
+    .build();
+  assert_eq!(names(&filter), vec!["Laptop", "Lamp"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .ends_with_ignore_case("name", "CARD")
+    .build();
+  assert_eq!(names(&filter), vec!["Gift card"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .ends_with("tags", "able")
+    .build();
+  assert_eq!(names(&filter), vec!["Laptop"]);
+
+  // Numbers have no prefixes.
+  let filter = Query::filter(Query::COMPARE)
+    .starts_with("price", "4")
+    .build();
+  assert!(names(&filter).is_empty());
+}
+
+#[test]
+fn test_dates_compare_chronologically() {
+  let posts = [
+    json!({ "title": "Late night", "published": "2024-05-01T23:00:00-02:00" }),
+    json!({ "title": "Morning", "published": "2024-05-02T00:30:00Z" }),
+    json!({ "title": "Epoch", "published": 1714608000 }),
+  ];
+  let titles = |filter: &FilterExpr| -> Vec<&str> {
+    posts
+      .iter()
+      .filter(|post| filter.evaluate(post))
+      .map(|post| post["title"].as_str().unwrap())
+      .collect()
+  };
+
+  // "Late night" is 2024-05-02T01:00:00Z, although it sorts first as text.
+  let filter = Query::filter(Query::COMPARE)
+    .gt("published", "2024-05-02T00:45:00Z")
+    .build();
+  assert_eq!(titles(&filter), vec!["Late night"]);
+
+  // Date values also compare with epoch seconds (1714608000 is 2024-05-02).
+  let filter = Query::filter(Query::COMPARE)
+    .between(
+      "published",
+      FilterValue::Date("2024-05-02".into()),
+      FilterValue::Date("2024-05-02T00:30:00Z".into()),
+    )
+    .build();
+  assert_eq!(titles(&filter), vec!["Morning", "Epoch"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .eq(
+      "published",
+      FilterValue::Date("2024-05-02T01:00:00+00:00".into()),
+    )
+    .build();
+  assert_eq!(titles(&filter), vec!["Late night"]);
+
+  let value = serde_json::to_value(FilterValue::Date("2024-05-02".into())).unwrap();
+  assert_eq!(value, json!({ "Date": "2024-05-02" }));
+  let value: FilterValue = serde_json::from_value(value).unwrap();
+  assert!(matches!(value, FilterValue::Date(_)));
+  let value = serde_json::to_value(FilterValue::from("2024-05-02")).unwrap();
+  assert_eq!(value, json!("2024-05-02"));
+}
+
+#[test]
+fn test_any_and_all_elements() {
+  let products = [
+    json!({ "name": "Kettle", "scores": [5, 4], "reviews": [{ "rating": 5, "verified": true }, { "rating": 2, "verified": false }] }),
+    json!({ "name": "Toaster", "scores": [3], "reviews": [{ "rating": 4, "verified": true }] }),
+    json!({ "name": "Blender", "scores": [], "reviews": [] }),
+    json!({ "name": "Mixer" }),
+  ];
+  let names = |filter: &FilterExpr| -> Vec<&str> {
+    products
+      .iter()
+      .filter(|product| filter.evaluate(product))
+      .map(|product| product["name"].as_str().unwrap())
+      .collect()
+  };
+
+  let good_and_verified = Query::filter(Query::AND)
+    .with(Query::filter(Query::COMPARE).ge("rating", 4).build())
+    .with(Query::filter(Query::COMPARE).eq("verified", true).build())
+    .build();
+  let filter = Query::filter(Query::COMPARE)
+    .any("reviews", good_and_verified.clone())
+    .build();
+  assert_eq!(names(&filter), vec!["Kettle", "Toaster"]);
+
+  // Empty arrays satisfy "all", missing fields don't.
+  let filter = Query::filter(Query::COMPARE)
+    .all("reviews", good_and_verified)
+    .build();
+  assert_eq!(names(&filter), vec!["Toaster", "Blender"]);
+
+  // An empty field name is the element itself.
+  let filter = Query::filter(Query::COMPARE)
+    .all("scores", Query::filter(Query::COMPARE).ge("", 4).build())
+    .build();
+  assert_eq!(names(&filter), vec!["Kettle", "Blender"]);
+
+  let filter: FilterExpr = serde_json::from_value(json!({
+    "Any": ["scores", { "Compare": { "field": "", "op": "Lt", "value": 4 } }]
+  }))
+  .unwrap();
+  assert_eq!(names(&filter), vec!["Toaster"]);
+}
+
+#[test]
+fn test_builder_chaining_and_precedence() {
+  let shape = |filter: FilterExpr| serde_json::to_value(filter).unwrap();
+  let eq =
+    |field: &str, value: f64| json!({ "Compare": { "field": field, "op": "Eq", "value": value } });
+
+  // AND binds tighter than OR.
+  let filter = Query::filter(Query::COMPARE)
+    .eq("a", 1)
+    .or()
+    .eq("b", 2)
+    .and()
+    .eq("c", 3)
+    .build();
+  assert_eq!(
+    shape(filter),
+    json!({ "Or": [eq("a", 1.0), { "And": [eq("b", 2.0), eq("c", 3.0)] }] })
+  );
+
+  // Adjacent conditions use the connective of the filter type.
+  let filter = Query::filter(Query::COMPARE).eq("a", 1).eq("b", 2).build();
+  assert_eq!(
+    shape(filter),
+    json!({ "And": [eq("a", 1.0), eq("b", 2.0)] })
+  );
+  let filter = Query::filter(Query::OR).eq("a", 1).eq("b", 2).build();
+  assert_eq!(shape(filter), json!({ "Or": [eq("a", 1.0), eq("b", 2.0)] }));
+
+  let filter = Query::filter(Query::COMPARE)
+    .group(|g| g.eq("a", 1).or().eq("b", 2))
+    .not(|n| n.eq("c", 3))
+    .build();
+  assert_eq!(
+    shape(filter),
+    json!({ "And": [{ "Or": [eq("a", 1.0), eq("b", 2.0)] }, { "Not": eq("c", 3.0) }] })
+  );
+
+  // Containers keep their shape, as before chaining existed.
+  let a = || Query::filter(Query::COMPARE).eq("a", 1);
+  assert_eq!(shape(a().build()), eq("a", 1.0));
+  assert_eq!(
+    shape(Query::filter(Query::OR).with(a()).build()),
+    json!({ "Or": [eq("a", 1.0)] })
+  );
+  assert_eq!(
+    shape(Query::filter(Query::AND).build()),
+    json!({ "And": [] })
+  );
+  assert_eq!(
+    shape(Query::filter(Query::NOT).with(a()).build()),
+    json!({ "Not": eq("a", 1.0) })
+  );
+
+  let filter = Query::filter(Query::COMPARE)
+    .group(|g| {
+      g.is_in("category", ["Books", "Games"])
+        .or()
+        .gt("price", 100)
+    })
+    .and()
+    .not(|n| n.eq("category", "Garden"))
+    .build();
+  assert_eq!(names(&filter), vec!["Laptop"]);
+}
+
+#[test]
+fn test_string_options() {
+  let cities = [
+    json!({ "name": "Malmö" }),
+    json!({ "name": "malmo" }),
+    json!({ "name": "Zürich" }),
+    json!({ "name": "Æbeltoft" }),
+    json!({ "name": "Ｏｓｌｏ" }),
+  ];
+  let names = |filter: &FilterExpr| -> Vec<&str> {
+    cities
+      .iter()
+      .filter(|city| filter.evaluate(city))
+      .map(|city| city["name"].as_str().unwrap())
+      .collect()
+  };
+  let ignore_case = || StringOptions::new().case_sensitive(false);
+  let folded = || StringOptions::new().fold_diacritics(true);
+
+  // Equality is exact by default, and substrings ignore case.
+  let filter = Query::filter(Query::COMPARE).eq("name", "malmo").build();
+  assert_eq!(names(&filter), vec!["malmo"]);
+  let filter = Query::filter(Query::COMPARE)
+    .contains("name", "MAL")
+    .build();
+  assert_eq!(names(&filter), vec!["Malmö", "malmo"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .contains("name", "MAL")
+    .string_options(StringOptions::new().case_sensitive(true))
+    .build();
+  assert!(names(&filter).is_empty());
+
+  let filter = Query::filter(Query::COMPARE)
+    .eq("name", "MALMO")
+    .string_options(ignore_case().fold_diacritics(true))
+    .build();
+  assert_eq!(names(&filter), vec!["Malmö", "malmo"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .is_in("name", ["Zurich", "AEbeltoft", "Oslo"])
+    .string_options(folded())
+    .build();
+  assert_eq!(names(&filter), vec!["Zürich", "Æbeltoft", "Ｏｓｌｏ"]);
+
+  let filter = Query::filter(Query::COMPARE)
+    .starts_with("name", "Zu")
+    .string_options(folded())
+    .build();
+  assert_eq!(names(&filter), vec!["Zürich"]);
+
+  // Code point order puts "Zürich" before "malmo" and "Æbeltoft" last.
+  let filter = Query::filter(Query::COMPARE).lt("name", "n").build();
+  assert_eq!(names(&filter), vec!["Malmö", "malmo", "Zürich"]);
+  let filter = Query::filter(Query::COMPARE)
+    .lt("name", "n")
+    .string_options(StringOptions::new().collate(true))
+    .build();
+  assert_eq!(names(&filter), vec!["Malmö", "malmo", "Æbeltoft"]);
+
+  // Letters come first, and case and diacritics only break ties.
+  let filter = Query::filter(Query::COMPARE)
+    .between("name", "malmn", "malmp")
+    .string_options(StringOptions::new().collate(true))
+    .build();
+  assert_eq!(names(&filter), vec!["Malmö", "malmo"]);
+  let filter = Query::filter(Query::COMPARE)
+    .ge("name", "malmo")
+    .string_options(StringOptions::new().collate(true))
+    .build();
+  assert_eq!(names(&filter), vec!["malmo", "Zürich", "Ｏｓｌｏ"]);
+
+  // Options apply to every comparison of a group, and round-trip.
+  let filter = Query::filter(Query::COMPARE)
+    .group(|g| g.eq("name", "zurich").or().eq("name", "oslo"))
+    .string_options(ignore_case().fold_diacritics(true))
+    .build();
+  assert_eq!(names(&filter), vec!["Zürich", "Ｏｓｌｏ"]);
+  let value = serde_json::to_value(&filter).unwrap();
+  assert_eq!(
+    value["Or"][0]["Compare"]["options"],
+    json!({ "case_sensitive": false, "fold_diacritics": true })
+  );
+  let filter: FilterExpr = serde_json::from_value(value).unwrap();
+  assert_eq!(names(&filter), vec!["Zürich", "Ｏｓｌｏ"]);
+}
+
+static SERIALIZATIONS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone)]
+struct Counted(serde_json::Value);
+
+impl Serialize for Counted {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    SERIALIZATIONS.fetch_add(1, Ordering::SeqCst);
+    self.0.serialize(serializer)
+  }
+}
+
+#[test]
+fn test_searchers_share_serialized_items() {
+  let items: Vec<Counted> = products().into_iter().map(Counted).collect();
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("name", FieldRule::bm25())
+        .build(),
+    )))
+    .with(Box::new(FuzzySearch::new(vec!["name".to_string()])))
+    .with(Box::new(TaggedSearch::new()))
+    .build();
+  let query = || {
+    Query::builder()
+      .text("lamp")
+      .tags(vec!["light".to_string()])
+  };
+  let serializations = |query: Query| {
+    SERIALIZATIONS.store(0, Ordering::SeqCst);
+    let results = engine.search(&items, &query);
+    (results.len(), SERIALIZATIONS.load(Ordering::SeqCst))
+  };
+
+  // The filter keeps every item, so scoring serializes the same items either way.
+  let (unfiltered_matches, unfiltered) = serializations(query().build());
+  let filter = Query::filter(Query::COMPARE).lt("price", 10_000).build();
+  let (matches, filtered) = serializations(query().filters(filter).build());
+
+  // Three searchers apply the filter, but each item is serialized for it once.
+  assert_eq!(matches, unfiltered_matches);
+  assert_eq!(filtered - unfiltered, items.len());
+}
+
+#[test]
+fn test_strings_that_are_not_dates_compare_as_strings() {
+  let items = [
+    json!({ "name": "version", "value": "1-10-0" }),
+    json!({ "name": "short date", "value": "12-05-07" }),
+    json!({ "name": "no such day", "value": "2024-02-31" }),
+    json!({ "name": "no such hour", "value": "2024-05-01T99:00:00Z" }),
+  ];
+  let names = |filter: &FilterExpr| -> Vec<&str> {
+    items
+      .iter()
+      .filter(|item| filter.evaluate(item))
+      .map(|item| item["name"].as_str().unwrap())
+      .collect()
+  };
+
+  // As dates, "1-10-0" would come after "1-9-0", and "12-05-07" after "2-01-01".
+  let filter = Query::filter(Query::COMPARE).lt("value", "1-9-0").build();
+  assert_eq!(names(&filter), vec!["version"]);
+  let filter = Query::filter(Query::COMPARE).lt("value", "2-01-01").build();
+  assert_eq!(names(&filter), vec!["version", "short date"]);
+
+  // As dates, 2024-02-31 would be 2024-03-02, and hour 99 four days later.
+  let filter = Query::filter(Query::COMPARE)
+    .lt("value", "2024-05-02T00:00:00Z")
+    .gt("value", "2024-03-01")
+    .build();
+  assert_eq!(names(&filter), vec!["no such hour"]);
+  let filter = Query::filter(Query::COMPARE)
+    .lt("value", "2024-03-01")
+    .build();
+  assert_eq!(names(&filter), vec!["version", "short date", "no such day"]);
+
+  // Nor are they dates for date values.
+  let filter = Query::filter(Query::COMPARE)
+    .lt("value", FilterValue::Date("2030-01-01".into()))
+    .build();
+  assert!(names(&filter).is_empty());
+}
@@ -0,0 +1,70 @@
+use searus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Post {
+  title: String,
+  content: String,
+}
+
+fn posts() -> Vec<Post> {
+  [
+    ("Rust programming", "Ownership and borrowing"),
+    ("Python programming", "Dynamic typing and duck typing"),
+    ("Cooking pasta", "Boil the water and add salt"),
+    ("Programming in Go", "Goroutines and channels"),
+  ]
+  .into_iter()
+  .map(|(title, content)| Post {
+    title: title.to_string(),
+    content: content.to_string(),
+  })
+  .collect()
+}
+
+fn search(searcher: FuzzySearch, text: &str) -> Vec<(String, f32)> {
+  let engine = SearusEngine::builder().with(Box::new(searcher)).build();
+  let mut results: Vec<(String, f32)> = engine
+    .search(&posts(), &Query::builder().text(text).build())
+    .into_iter()
+    .map(|result| (result.item.title, result.score))
+    .collect();
+  // Results with equal scores come in no particular order.
+  results.sort_by(|a, b| a.0.cmp(&b.0));
+  results
+}
+
+#[test]
+fn test_index_matches_pairwise_comparison() {
+  let fields = vec!["title".to_string(), "content".to_string()];
+
+  for text in ["progrmaming", "pyhton typnig", "bole", "rust", "xyz"] {
+    for max_edits in 0..3 {
+      let pairwise = FuzzySearch::new(fields.clone()).with_max_edits(max_edits);
+      let indexed = FuzzySearch::new(fields.clone())
+        .with_max_edits(max_edits)
+        .build_index(&posts());
+      assert_eq!(search(indexed, text), search(pairwise, text), "{text}");
+    }
+  }
+}
+
+#[test]
+fn test_index_ignored_with_threshold() {
+  let fields = vec!["title".to_string()];
+  // An empty index would match nothing in max-edit-distance mode.
+  let searcher = FuzzySearch::new(fields).with_index(Default::default());
+
+  assert_eq!(search(searcher, "progrmaming").len(), 3);
+}
+
+#[test]
+fn test_ngram_prefilter_with_threshold() {
+  let fields = vec!["title".to_string(), "content".to_string()];
+
+  for text in ["progrmaming", "pyhton typnig", "chanels", "xyz"] {
+    let pairwise = FuzzySearch::new(fields.clone());
+    let indexed = FuzzySearch::new(fields.clone()).build_index(&posts());
+    assert_eq!(search(indexed, text), search(pairwise, text), "{text}");
+  }
+}
@@ -0,0 +1,149 @@
+use searus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Post {
+  title: String,
+  content: String,
+}
+
+fn posts() -> Vec<Post> {
+  vec![Post {
+    title: "Café programming".to_string(),
+    content: "Programming notes, mostly about programming in cafés".to_string(),
+  }]
+}
+
+#[test]
+fn test_semantic_highlights() {
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("title", FieldRule::bm25())
+        .field("content", FieldRule::tokenized())
+        .build(),
+    )))
+    .build();
+
+  let query = Query::builder()
+    .text("programming")
+    .options(SearchOptions::default().highlight(true))
+    .build();
+
+  let results = engine.search(&posts(), &query);
+  let highlights = &results[0].highlights;
+
+  let title: Vec<&Highlight> = highlights.iter().filter(|h| h.field == "title").collect();
+  assert_eq!(title.len(), 1);
+  // "Café " is 6 bytes but 5 characters.
+  assert_eq!((title[0].start, title[0].end), (6, 17));
+  assert_eq!((title[0].char_start, title[0].char_end), (5, 16));
+
+  let content = highlights.iter().filter(|h| h.field == "content").count();
+  assert_eq!(content, 2);
+}
+
+#[test]
+fn test_fuzzy_highlights() {
+  let engine = SearusEngine::builder()
+    .with(Box::new(FuzzySearch::new(vec!["title".to_string()])))
+    .build();
+
+  let query = Query::builder()
+    .text("programing")
+    .options(SearchOptions::default().highlight(true))
+    .build();
+
+  let results = engine.search(&posts(), &query);
+  let html = highlight_text(
+    &posts()[0].title,
+    "title",
+    &results[0].highlights,
+    "<b>",
+    "</b>",
+  );
+
+  assert_eq!(html, "Café <b>programming</b>");
+}
+
+#[test]
+fn test_no_highlights_by_default() {
+  let engine = SearusEngine::builder()
+    .with(Box::new(FuzzySearch::new(vec!["title".to_string()])))
+    .build();
+
+  let results = engine.search(&posts(), &Query::builder().text("programing").build());
+
+  assert!(results[0].highlights.is_empty());
+}
+
+#[test]
+fn test_snippet_around_matches() {
+  let post = Post {
+    title: "Notes".to_string(),
+    content: format!(
+      "{} The rust compiler checks rust code. {}",
+      "Filler text. ".repeat(20),
+      "More filler. ".repeat(20)
+    ),
+  };
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("content", FieldRule::tokenized())
+        .build(),
+    )))
+    .build();
+
+  let query = Query::builder()
+    .text("rust")
+    .options(SearchOptions::default().highlight(true))
+    .build();
+  let results = engine.search(std::slice::from_ref(&post), &query);
+
+  let snippet = SnippetExtractor::new()
+    .length(50)
+    .ellipsis("...")
+    .markup("<em>", "</em>")
+    .extract(&post.content, "content", &results[0].highlights);
+
+  assert!(snippet.starts_with("..."));
+  assert!(snippet.ends_with("..."));
+  assert!(snippet.contains("<em>rust</em> compiler checks <em>rust</em> code"));
+
+  // Short texts are returned whole.
+  let short = SnippetExtractor::new().extract("Short text", "content", &[]);
+  assert_eq!(short, "Short text");
+}
+
+#[test]
+fn test_semantic_detail_term_positions() {
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("title", FieldRule::bm25())
+        .field("content", FieldRule::tokenized())
+        .build(),
+    )))
+    .build();
+
+  let results = engine.search(&posts(), &Query::builder().text("programming").build());
+
+  let mut details: Vec<(&str, &TermMatch)> = results[0]
+    .details
+    .iter()
+    .filter_map(|detail| match detail {
+      SearchDetail::Semantic { field, terms, .. } => Some((field.as_str(), &terms[0])),
+      _ => None,
+    })
+    .collect();
+  details.sort_by_key(|(field, _)| *field);
+
+  assert_eq!(details.len(), 2);
+  assert_eq!(details[0].0, "content");
+  assert_eq!(details[0].1.frequency, 2);
+  assert_eq!(details[0].1.positions, vec![0, 4]);
+  assert_eq!(details[1].0, "title");
+  assert_eq!(details[1].1.positions, vec![1]);
+}
@@ -0,0 +1,162 @@
+use searus::history::{SearchHistory, SearchRecord};
+use searus::prelude::*;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+const DAY: f64 = 24.0 * 60.0 * 60.0;
+
+fn now() -> f64 {
+  SearchRecord::new("", 0).timestamp
+}
+
+fn texts(history: &SearchHistory, prefix: &str) -> Vec<String> {
+  history
+    .suggest_queries(prefix, 10)
+    .into_iter()
+    .map(|suggestion| suggestion.text)
+    .collect()
+}
+
+/// Searches "rust async" three times ten days ago, and "rust macros" once
+/// today.
+fn searches() -> Vec<SearchRecord> {
+  let now = now();
+  let mut records = vec![SearchRecord::new("rust macros", 3).at(now)];
+  for _ in 0..3 {
+    records.push(SearchRecord::new("rust async", 5).at(now - 10.0 * DAY));
+  }
+  records
+}
+
+#[test]
+fn test_suggestions_are_ranked_by_frequency_and_recency() {
+  let history = SearchHistory::new().half_life(DAY);
+  history.replay(searches());
+  assert_eq!(texts(&history, "rust"), ["rust macros", "rust async"]);
+
+  let history = SearchHistory::new().half_life(100.0 * DAY);
+  history.replay(searches());
+  assert_eq!(texts(&history, "rust"), ["rust async", "rust macros"]);
+
+  let history = SearchHistory::new().half_life(0.0);
+  history.replay(searches());
+  let suggestions = history.suggest_queries("RUST A", 10);
+  assert_eq!(suggestions.len(), 1);
+  assert_eq!(suggestions[0].count, 3);
+  assert_eq!(suggestions[0].score, 3.0);
+  assert_eq!(texts(&history, ""), ["rust async", "rust macros"]);
+  assert!(texts(&history, "python").is_empty());
+}
+
+#[test]
+fn test_replayed_searches_may_come_out_of_order() {
+  let searches = searches();
+  let history = SearchHistory::new().half_life(DAY);
+  history.replay(searches.clone());
+  let reversed = SearchHistory::new().half_life(DAY);
+  reversed.replay(searches.into_iter().rev());
+
+  let (a, b) = (
+    history.suggest_queries("", 10),
+    reversed.suggest_queries("", 10),
+  );
+  assert_eq!(a.len(), b.len());
+  for (a, b) in a.iter().zip(&b) {
+    assert_eq!(
+      (&a.text, a.count, a.last_searched),
+      (&b.text, b.count, b.last_searched)
+    );
+    assert!((a.score - b.score).abs() < 1e-9);
+  }
+}
+
+#[test]
+fn test_history_keeps_the_top_queries() {
+  let history = SearchHistory::new().capacity(2).half_life(0.0);
+  history.record(SearchRecord::new("rust", 1));
+  history.record(SearchRecord::new("rust", 1));
+  history.record(SearchRecord::new("python", 1));
+  history.record(SearchRecord::new("go", 1));
+  assert_eq!(history.len(), 2);
+  assert_eq!(texts(&history, "")[0], "rust");
+
+  let history = SearchHistory::new().min_matches(3);
+  history.record(SearchRecord::new("rust", 2));
+  history.record(SearchRecord::new("   ", 10));
+  assert!(history.is_empty());
+  history.record(SearchRecord::new("rust", 3));
+  assert_eq!(texts(&history, "r"), ["rust"]);
+  history.clear();
+  assert!(history.is_empty());
+}
+
+#[test]
+fn test_engine_records_its_searches() {
+  let log = Arc::new(Mutex::new(Vec::new()));
+  let sink = log.clone();
+  let history = SearchHistory::new().sink(move |record: &SearchRecord| {
+    sink
+      .lock()
+      .unwrap()
+      .push(serde_json::to_string(record).unwrap())
+  });
+
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25())
+    .build();
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .with_extension(Box::new(RewritePipeline::new().then(
+      |query: &mut Query| {
+        query.text = query
+          .text
+          .as_ref()
+          .map(|text| text.to_lowercase().replace("rs", "rust"))
+      },
+    )))
+    .history(history)
+    .build();
+
+  let items = vec![
+    json!({ "title": "Rust ownership" }),
+    json!({ "title": "Python typing" }),
+  ];
+  for text in ["Rs  ownership", "rs", "haskell"] {
+    engine.search(&items, &Query::builder().text(text).build());
+  }
+  engine.search(&items, &Query::builder().tags(vec!["rust".into()]).build());
+
+  // Queries are recorded as they were given, before they are rewritten.
+  let suggestions = engine.suggest_queries("rs", 10);
+  let texts: Vec<&str> = suggestions.iter().map(|s| s.text.as_str()).collect();
+  assert_eq!(texts.len(), 2);
+  assert!(texts.contains(&"Rs ownership") && texts.contains(&"rs"));
+  assert!(engine.suggest_queries("haskell", 10).is_empty());
+  assert_eq!(engine.history().unwrap().len(), 2);
+
+  // Sinks receive every search with text, successful or not.
+  let records: Vec<SearchRecord> = log
+    .lock()
+    .unwrap()
+    .iter()
+    .map(|line| serde_json::from_str(line).unwrap())
+    .collect();
+  let recorded: Vec<(&str, usize)> = records
+    .iter()
+    .map(|record| (record.text.as_str(), record.total_matches))
+    .collect();
+  assert_eq!(recorded, [("Rs  ownership", 1), ("rs", 1), ("haskell", 0)]);
+
+  // The sink's records restore the history.
+  let restored = SearchHistory::new();
+  restored.replay(records);
+  assert_eq!(restored.len(), 2);
+}
+
+#[test]
+fn test_engine_without_history_suggests_nothing() {
+  let engine: SearusEngine<Value> = SearusEngine::builder().build();
+  engine.search(&[], &Query::builder().text("rust").build());
+  assert!(engine.history().is_none());
+  assert!(engine.suggest_queries("", 10).is_empty());
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "image")]
+
+use searus::embeddings::ImageEmbedder;
+use searus::prelude::*;
+use searus::searchers::ImageSearch;
+use serde::Serialize;
+
+/// Embeds an image as its bytes, so tests can pick the query vector.
+struct BytesEmbedder;
+
+impl ImageEmbedder for BytesEmbedder {
+  fn embed(&self, image_data: &[u8]) -> Result<Vec<f32>, String> {
+    if image_data.is_empty() {
+      return Err("Empty image".to_string());
+    }
+    Ok(image_data.iter().map(|&b| b as f32).collect())
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Photo {
+  name: String,
+  album: String,
+  embedding: Option<Vec<f32>>,
+}
+
+fn photos() -> Vec<Photo> {
+  let photo = |name: &str, album: &str, embedding: Option<Vec<f32>>| Photo {
+    name: name.to_string(),
+    album: album.to_string(),
+    embedding,
+  };
+  vec![
+    photo("beach", "summer", Some(vec![1.0, 0.0, 0.0])),
+    photo("dunes", "summer", Some(vec![1.0, 1.0, 0.0])),
+    photo("forest", "autumn", Some(vec![0.0, 1.0, 0.0])),
+    photo("sunset", "autumn", Some(vec![1.0, 0.2, 0.0])),
+    photo("untitled", "summer", None),
+    photo("panorama", "summer", Some(vec![1.0, 0.0])),
+  ]
+}
+
+fn engine() -> SearusEngine<Photo> {
+  SearusEngine::builder()
+    .with(Box::new(
+      ImageSearch::new(Box::new(BytesEmbedder), |photo: &Photo| {
+        photo.embedding.clone()
+      })
+      .with_min_similarity(0.1),
+    ))
+    .build()
+}
+
+fn names(results: &[SearusMatch<Photo>]) -> Vec<&str> {
+  results.iter().map(|m| m.item.name.as_str()).collect()
+}
+
+#[test]
+fn test_image_search_ranks_by_similarity() {
+  let query = Query::builder()
+    .image(ImageData::new(vec![1, 0, 0]))
+    .options(SearchOptions::default().explain(true))
+    .build();
+  let results = engine().search(&photos(), &query);
+
+  // Photos without an embedding, or of another length, don't match, and
+  // orthogonal photos fall under the minimum similarity.
+  assert_eq!(names(&results), vec!["beach", "sunset", "dunes"]);
+  assert_eq!(results[0].details.len(), 1);
+  let SearchDetail::Image { similarity } = results[2].details[0] else {
+    panic!("expected an image detail");
+  };
+  assert!((similarity - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+  let explanation = results[2].explain().unwrap().to_string();
+  assert!(explanation.contains("cosine similarity"), "{}", explanation);
+}
+
+#[test]
+fn test_image_search_filters_and_missing_images() {
+  let engine = engine();
+
+  let query = Query::builder()
+    .image(ImageData::new(vec![1, 1, 0]))
+    .filters(Query::filter(Query::COMPARE).eq("album", "autumn").build())
+    .build();
+  assert_eq!(
+    names(&engine.search(&photos(), &query)),
+    vec!["sunset", "forest"]
+  );
+
+  // Nothing matches without an image, or when it can't be embedded.
+  let query = Query::builder().text("beach").build();
+  assert!(engine.search(&photos(), &query).is_empty());
+  let query = Query::builder().image(ImageData::new(Vec::new())).build();
+  assert!(engine.search(&photos(), &query).is_empty());
+}
@@ -0,0 +1,229 @@
+use searus::index::{BinaryVector, InMemIndex, IndexAdapter, VectorStorage, DEFAULT_VECTOR};
+use std::collections::HashMap;
+
+#[test]
+fn test_vectors_keep_the_first_dimension() {
+  let mut index = InMemIndex::new();
+  assert_eq!(index.dimension(), None);
+
+  // Items without vectors don't fix the dimension.
+  index.put("a".to_string(), 'a', None, None).unwrap();
+  index
+    .put("b".to_string(), 'b', Some(vec![0.0, 1.0, 0.0]), None)
+    .unwrap();
+  assert_eq!(index.dimension(), Some(3));
+
+  let error = index
+    .put("c".to_string(), 'c', Some(vec![1.0, 0.0]), None)
+    .unwrap_err();
+  assert_eq!(
+    error,
+    "Invalid vector for c: Expected a vector of 3 dimensions, got 2"
+  );
+  assert!(index.get(&"c".to_string()).is_none());
+  assert_eq!(index.all().len(), 2);
+
+  // Replacing an item keeps its previous vector if the new one is rejected.
+  assert!(index
+    .put("b".to_string(), 'B', Some(vec![1.0]), None)
+    .is_err());
+  assert_eq!(index.get(&"b".to_string()), Some(&'b'));
+  assert_eq!(index.knn(&[0.0, 1.0, 0.0], 5), vec![("b".to_string(), 0.0)]);
+
+  // Queries of another dimension have no neighbors.
+  assert!(index.knn(&[0.0, 1.0], 5).is_empty());
+}
+
+#[test]
+fn test_normalization_rejects_zero_vectors() {
+  let mut index = InMemIndex::new().with_normalization(true);
+  index
+    .put("near".to_string(), 1, Some(vec![5.0, 0.5]), None)
+    .unwrap();
+  index
+    .put("far".to_string(), 2, Some(vec![0.5, 0.5]), None)
+    .unwrap();
+
+  let error = index
+    .put("zero".to_string(), 3, Some(vec![0.0, 0.0]), None)
+    .unwrap_err();
+  assert!(error.contains("Cannot normalize"), "{}", error);
+
+  // Without normalization, "far" would be closer to the query.
+  let neighbors = index.knn(&[1.0, 0.0], 2);
+  assert_eq!(neighbors[0].0, "near");
+  assert!(index.knn(&[0.0, 0.0], 2).is_empty());
+}
+
+#[test]
+fn test_binary_storage() {
+  let mut index = InMemIndex::new();
+  let vectors = [
+    ("close", vec![0.9, 0.9, 0.9, -0.01]),
+    ("signs", vec![0.01, 0.01, 0.01, 0.01]),
+    ("far", vec![-1.0, -1.0, -1.0, -1.0]),
+  ];
+  for (id, vector) in &vectors {
+    index
+      .put(id.to_string(), *id, Some(vector.clone()), None)
+      .unwrap();
+  }
+  let query = [1.0, 1.0, 1.0, 1.0];
+  assert_eq!(index.knn(&query, 1)[0].0, "close");
+
+  // Hamming distance only sees signs, so "signs" looks like an exact match.
+  let mut index = index.with_storage(VectorStorage::Binary);
+  assert_eq!(
+    index.knn(&query, 3),
+    vec![
+      ("signs".to_string(), 0.0),
+      ("close".to_string(), 1.0),
+      ("far".to_string(), 4.0)
+    ]
+  );
+  index.remove(&"signs".to_string()).unwrap();
+  assert_eq!(index.knn(&query, 1), vec![("close".to_string(), 1.0)]);
+
+  // Rescoring the candidates restores the exact order and distances.
+  let mut index = InMemIndex::new().with_storage(VectorStorage::BinaryRescored { oversample: 2 });
+  for (id, vector) in &vectors {
+    index
+      .put(id.to_string(), *id, Some(vector.clone()), None)
+      .unwrap();
+  }
+  let neighbors = index.knn(&query, 1);
+  assert_eq!(neighbors[0].0, "close");
+  assert!((neighbors[0].1 - 1.0247).abs() < 1e-3);
+
+  // With too few candidates, rescoring can't recover what Hamming missed.
+  let index = index.with_storage(VectorStorage::BinaryRescored { oversample: 1 });
+  assert_eq!(index.knn(&query, 1)[0].0, "signs");
+}
+
+#[test]
+fn test_binary_vector_words() {
+  let vector: Vec<f32> = (0..70)
+    .map(|i| if i % 3 == 0 { 1.0 } else { -1.0 })
+    .collect();
+  let binary = BinaryVector::quantize(&vector);
+  assert_eq!(binary.as_words().len(), 2);
+  assert_eq!(binary.as_words()[1], 0b100100);
+
+  let copy = BinaryVector::from_words(binary.as_words().to_vec(), 70).unwrap();
+  assert_eq!(copy, binary);
+  assert_eq!(copy.hamming_distance(&BinaryVector::quantize(&[])), 24);
+
+  let error = BinaryVector::from_words(vec![0], 70).unwrap_err();
+  assert_eq!(error, "Expected 2 words for 70 dimensions, got 1");
+  let error = BinaryVector::from_words(vec![0, 1 << 6], 70).unwrap_err();
+  assert_eq!(error, "Bits are set past dimension 70");
+}
+
+#[test]
+fn test_named_vectors() {
+  let mut index = InMemIndex::new().with_vector_dimension("image", 3);
+  let vectors = |text: Vec<f32>, image: Vec<f32>| {
+    HashMap::from([
+      (DEFAULT_VECTOR.to_string(), text),
+      ("image".to_string(), image),
+    ])
+  };
+  index
+    .put_named(
+      "mug".to_string(),
+      "Mug",
+      vectors(vec![1.0, 0.0], vec![0.0, 0.0, 1.0]),
+      None,
+    )
+    .unwrap();
+  index
+    .put_named(
+      "cup".to_string(),
+      "Cup",
+      vectors(vec![0.0, 1.0], vec![1.0, 0.0, 0.0]),
+      None,
+    )
+    .unwrap();
+  assert_eq!(index.dimension(), Some(2));
+  assert_eq!(index.vector_dimension("image"), Some(3));
+
+  // Each name is searched, and validated, on its own.
+  assert_eq!(index.knn(&[1.0, 0.0], 1)[0].0, "mug");
+  assert_eq!(index.knn_named("image", &[1.0, 0.0, 0.0], 1)[0].0, "cup");
+  assert!(index.knn_named("audio", &[1.0, 0.0], 1).is_empty());
+  let error = index
+    .put_named(
+      "bowl".to_string(),
+      "Bowl",
+      vectors(vec![1.0, 1.0], vec![1.0]),
+      None,
+    )
+    .unwrap_err();
+  assert_eq!(
+    error,
+    "Invalid image vector for bowl: Expected a vector of 3 dimensions, got 1"
+  );
+  assert!(index.get(&"bowl".to_string()).is_none());
+
+  // Updating one vector keeps the others.
+  index
+    .put("mug".to_string(), "Mug", Some(vec![0.0, 1.0]), None)
+    .unwrap();
+  assert_eq!(index.knn_named("image", &[0.0, 0.0, 1.0], 1)[0].0, "mug");
+  index.remove(&"mug".to_string()).unwrap();
+  assert_eq!(index.knn_named("image", &[0.0, 0.0, 1.0], 2).len(), 1);
+}
+
+/// An adapter with a single vector per item, relying on the default methods.
+#[derive(Default)]
+struct SingleVector(Vec<(String, Vec<f32>)>);
+
+impl IndexAdapter<()> for SingleVector {
+  fn put(
+    &mut self,
+    id: String,
+    _: (),
+    vectors: Option<Vec<f32>>,
+    _: Option<Vec<String>>,
+  ) -> Result<(), String> {
+    self.0.extend(vectors.map(|v| (id, v)));
+    Ok(())
+  }
+
+  fn remove(&mut self, _: &String) -> Result<(), String> {
+    Ok(())
+  }
+
+  fn get(&self, _: &String) -> Option<&()> {
+    None
+  }
+
+  fn knn(&self, _: &[f32], k: usize) -> Vec<(String, f32)> {
+    self
+      .0
+      .iter()
+      .take(k)
+      .map(|(id, _)| (id.clone(), 0.0))
+      .collect()
+  }
+
+  fn all(&self) -> Vec<&()> {
+    Vec::new()
+  }
+}
+
+#[test]
+fn test_named_vectors_default_to_single_vector() {
+  let mut index = SingleVector::default();
+  let vectors = HashMap::from([(DEFAULT_VECTOR.to_string(), vec![1.0])]);
+  index.put_named("a".to_string(), (), vectors, None).unwrap();
+  assert_eq!(index.knn_named(DEFAULT_VECTOR, &[1.0], 1).len(), 1);
+  assert!(index.knn_named("image", &[1.0], 1).is_empty());
+
+  let vectors = HashMap::from([("image".to_string(), vec![1.0])]);
+  let error = index.put_named("b".to_string(), (), vectors, None);
+  assert_eq!(
+    error.unwrap_err(),
+    "Named vectors are not supported, got image"
+  );
+}
@@ -0,0 +1,145 @@
+use searus::history::SearchHistory;
+use searus::prelude::*;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Matches every item, counting its searches and warm-ups.
+#[derive(Default)]
+struct Counter {
+  searches: AtomicUsize,
+  warm_ups: AtomicUsize,
+  warmed_items: AtomicUsize,
+}
+
+struct CountingSearcher(Arc<Counter>);
+
+impl Searcher<Value> for CountingSearcher {
+  fn kind(&self) -> SearcherKind {
+    SearcherKind::Custom
+  }
+
+  fn search(&self, context: &SearchContext<Value>, _query: &Query) -> Vec<SearusMatch<Value>> {
+    self.0.searches.fetch_add(1, Ordering::SeqCst);
+    context
+      .items
+      .iter()
+      .enumerate()
+      .map(|(i, item)| SearusMatch::new(item.clone(), 1.0, i))
+      .collect()
+  }
+
+  fn warm_up(&self, items: &[Value]) {
+    self.0.warm_ups.fetch_add(1, Ordering::SeqCst);
+    self.0.warmed_items.store(items.len(), Ordering::SeqCst);
+  }
+}
+
+fn indexed(builder: SearusEngineBuilder<Value>) -> (IndexedSearusEngine<Value>, Arc<Counter>) {
+  let counter = Arc::new(Counter::default());
+  let engine = builder
+    .with(Box::new(CountingSearcher(counter.clone())))
+    .build();
+  (IndexedSearusEngine::new(engine), counter)
+}
+
+#[test]
+fn test_generation_counts_changes_to_the_items() {
+  let (mut indexed, _) = indexed(SearusEngine::builder());
+  assert_eq!(indexed.generation(), 0);
+
+  indexed.put("1", json!({ "title": "Rust" })).unwrap();
+  indexed.put("2", json!({ "title": "Python" })).unwrap();
+  indexed.put("1", json!({ "title": "Rust 2024" })).unwrap();
+  assert_eq!(indexed.generation(), 3);
+
+  indexed.remove("2").unwrap();
+  assert_eq!(indexed.generation(), 4);
+  // Removing a missing item changes nothing.
+  indexed.remove("2").unwrap();
+  assert_eq!(indexed.generation(), 4);
+}
+
+#[test]
+fn test_searchers_are_warmed_up_once_per_generation() {
+  let (mut indexed, counter) = indexed(SearusEngine::builder());
+  indexed.put("1", json!({ "title": "Rust" })).unwrap();
+
+  let query = Query::builder().text("rust").build();
+  for _ in 0..3 {
+    assert_eq!(indexed.search(&query).total_matches, 1);
+    assert_eq!(indexed.search_matches(&query).len(), 1);
+  }
+  assert_eq!(counter.warm_ups.load(Ordering::SeqCst), 1);
+  assert_eq!(counter.searches.load(Ordering::SeqCst), 6);
+
+  indexed.put("2", json!({ "title": "Rust" })).unwrap();
+  assert_eq!(indexed.search(&query).total_matches, 2);
+  assert_eq!(counter.warm_ups.load(Ordering::SeqCst), 2);
+  assert_eq!(counter.warmed_items.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_cached_responses_are_dropped_on_writes() {
+  let (indexed, counter) = indexed(SearusEngine::builder().history(SearchHistory::new()));
+  let mut indexed = indexed.with_result_cache(2);
+  indexed.put("1", json!({ "title": "Rust" })).unwrap();
+
+  let rust = Query::builder().text("rust").build();
+  let first = indexed.search(&rust);
+  let second = indexed.search(&rust);
+  assert_eq!(counter.searches.load(Ordering::SeqCst), 1);
+  assert_eq!(
+    serde_json::to_value(&first).unwrap(),
+    serde_json::to_value(&second).unwrap()
+  );
+  // Cached responses are still recorded in the history.
+  assert_eq!(indexed.engine().suggest_queries("rust", 5)[0].count, 2);
+
+  indexed.put("2", json!({ "title": "Python" })).unwrap();
+  assert_eq!(indexed.search(&rust).total_matches, 2);
+  assert_eq!(counter.searches.load(Ordering::SeqCst), 2);
+
+  // The oldest response is dropped once the cache is full.
+  for text in ["python", "go", "rust"] {
+    indexed.search(&Query::builder().text(text).build());
+  }
+  assert_eq!(counter.searches.load(Ordering::SeqCst), 5);
+  indexed.search(&Query::builder().text("go").build());
+  assert_eq!(counter.searches.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn test_responses_are_not_cached_by_default() {
+  let (mut indexed, counter) = indexed(SearusEngine::builder());
+  indexed.put("1", json!({ "title": "Rust" })).unwrap();
+
+  let query = Query::builder().text("rust").build();
+  indexed.search(&query);
+  indexed.search(&query);
+  assert_eq!(counter.searches.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_equal_queries_share_cached_responses() {
+  let (indexed, counter) = indexed(SearusEngine::builder());
+  let mut indexed = indexed.with_result_cache(2);
+  indexed.put("1", json!({ "title": "Rust" })).unwrap();
+
+  // Maps iterate in a different order in every query.
+  let keys: Vec<String> = (0..16).map(|i| format!("key-{}", i)).collect();
+  let query = |keys: Vec<&String>| {
+    keys
+      .into_iter()
+      .fold(Query::builder().text("rust"), |query, key| {
+        query.metadata(key.as_str(), key.as_str())
+      })
+      .build()
+  };
+  indexed.search(&query(keys.iter().collect()));
+  indexed.search(&query(keys.iter().rev().collect()));
+  for _ in 0..8 {
+    indexed.search(&query(keys.iter().collect()));
+  }
+  assert_eq!(counter.searches.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,160 @@
+#![cfg(feature = "ingest")]
+
+use searus::index::{InMemIndex, IndexAdapter};
+use searus::ingest::{FieldMapping, FieldType, Format, Loader, RowError};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[test]
+fn test_csv_columns_get_inferred_types() {
+  let csv = "\
+id,title,price,stock,in_print,zip,tags
+1,Programming Rust,39.5,3,true,01234,\"programming, rust\"
+2,Rust in Action,25,,FALSE,98765,rust
+";
+  let loader = Loader::new(
+    FieldMapping::new()
+      .id("id")
+      .tags("tags")
+      .field_type("zip", FieldType::String),
+  );
+  let mut index = InMemIndex::new();
+  let report = loader
+    .load(Format::Csv, csv.as_bytes(), &mut index)
+    .unwrap();
+
+  assert_eq!(report.loaded, 2);
+  assert!(report.errors.is_empty());
+  assert_eq!(
+    report.schema.fields,
+    vec![
+      ("id".to_string(), FieldType::Integer),
+      ("title".to_string(), FieldType::String),
+      ("price".to_string(), FieldType::Float),
+      ("stock".to_string(), FieldType::Integer),
+      ("in_print".to_string(), FieldType::Bool),
+      ("zip".to_string(), FieldType::String),
+      ("tags".to_string(), FieldType::List),
+    ]
+  );
+
+  let item: &Value = index.get(&"1".to_string()).unwrap();
+  assert_eq!(
+    *item,
+    json!({
+      "id": 1,
+      "title": "Programming Rust",
+      "price": 39.5,
+      "stock": 3,
+      "in_print": true,
+      "zip": "01234",
+      "tags": ["programming", "rust"],
+    })
+  );
+  // Empty cells are left out.
+  let item = index.get(&"2".to_string()).unwrap();
+  assert_eq!(item["price"], 25.0);
+  assert!(item.get("stock").is_none());
+  assert_eq!(item["in_print"], false);
+}
+
+#[test]
+fn test_rows_with_errors_are_reported() {
+  // Types are inferred from the first two rows only.
+  let csv = "\
+sku,name,price
+A1,Mug,12
+A2,Pot,8
+A3,Cup,cheap
+,Bowl,4
+A5,Plate,5
+";
+  let loader = Loader::new(FieldMapping::new().id("sku").sample_size(2));
+  let mut index: InMemIndex<Value> = InMemIndex::new();
+  let report = loader
+    .load(Format::Csv, csv.as_bytes(), &mut index)
+    .unwrap();
+
+  assert_eq!(report.loaded, 3);
+  assert_eq!(
+    report.errors,
+    vec![
+      RowError {
+        row: 4,
+        message: "Field `price`: expected an integer, got \"cheap\"".to_string(),
+      },
+      RowError {
+        row: 5,
+        message: "Missing id `sku`".to_string(),
+      },
+    ]
+  );
+  assert_eq!(
+    report.errors[0].to_string(),
+    format!("row 4: {}", report.errors[0].message)
+  );
+  assert!(index.get(&"A5".to_string()).is_some());
+
+  assert!(loader
+    .load::<Value, _, _>(Format::Json, "[{".as_bytes(), &mut index)
+    .is_err());
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Post {
+  title: String,
+  year: u32,
+  tags: Vec<String>,
+}
+
+#[test]
+fn test_jsonl_loads_typed_items_with_vectors() {
+  let jsonl = r#"{"title": "Rust ownership", "year": 2024, "tags": "rust,memory", "embedding": [0.1, 0.9]}
+
+{"title": "Python typing", "year": "2023", "tags": ["python"], "embedding": [0.8, 0.2]}
+{"title": "No year", "tags": []}
+not json
+[1, 2]
+"#;
+  let loader = Loader::new(
+    FieldMapping::new()
+      .tags("tags")
+      .vector("embedding")
+      .field_type("year", FieldType::Integer),
+  );
+  let mut index = InMemIndex::new();
+  let report = loader
+    .load(Format::Jsonl, jsonl.as_bytes(), &mut index)
+    .unwrap();
+
+  assert_eq!(report.loaded, 2);
+  let rows: Vec<usize> = report.errors.iter().map(|e| e.row).collect();
+  assert_eq!(rows, [4, 5, 6]);
+  assert!(report.errors[0].message.contains("year"));
+  assert_eq!(report.errors[2].message, "Expected a JSON object");
+  assert!(report.schema.get("embedding").is_none());
+
+  // Rows are numbered from 0, counting rows with errors.
+  let post: &Post = index.get(&"0".to_string()).unwrap();
+  assert_eq!(post.tags, ["rust", "memory"]);
+  let post: &Post = index.get(&"1".to_string()).unwrap();
+  assert_eq!(post.year, 2023);
+  assert_eq!(post.title, "Python typing");
+  assert_eq!(index.knn(&[1.0, 0.0], 1)[0].0, "1");
+}
+
+#[test]
+fn test_mapping_from_json() {
+  let mapping: FieldMapping =
+    serde_json::from_value(json!({ "id": "sku", "types": { "zip": "string" } })).unwrap();
+  assert_eq!(mapping.id.as_deref(), Some("sku"));
+  assert_eq!(mapping.types["zip"], FieldType::String);
+  assert_eq!(mapping.sample_size, 100);
+
+  let records = Loader::new(mapping)
+    .records(Format::Json, r#"{"sku": 7, "zip": 2100}"#.as_bytes())
+    .unwrap();
+  let records: Vec<_> = records.map(Result::unwrap).collect();
+  assert_eq!(records[0].id, "7");
+  assert_eq!(records[0].item, json!({ "sku": 7, "zip": "2100" }));
+}
@@ -0,0 +1,122 @@
+use searus::prelude::*;
+use searus::searchers::analyzer::Analyzer;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Post {
+  title: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  lang: Option<String>,
+}
+
+fn post(title: &str, lang: Option<&str>) -> Post {
+  Post {
+    title: title.to_string(),
+    lang: lang.map(str::to_string),
+  }
+}
+
+fn posts() -> Vec<Post> {
+  vec![
+    post("The art of war", Some("en")),
+    post("El arte de la guerra", Some("ES")),
+    post("Art without a language", None),
+  ]
+}
+
+fn spanish() -> Analyzer {
+  Analyzer::default().filter(Box::new(StopWords::new(["de", "del", "el", "en", "la"])))
+}
+
+fn searcher() -> SemanticSearch {
+  SemanticSearch::new(
+    SemanticRules::builder()
+      .field("title", FieldRule::tokenized())
+      .build(),
+  )
+  .with_language_field("lang")
+  .with_language_analyzer("en", Analyzer::for_language("en"))
+  .with_language_analyzer("es", spanish())
+}
+
+fn engine(searcher: SemanticSearch) -> SearusEngine<Post> {
+  SearusEngine::builder().with(Box::new(searcher)).build()
+}
+
+#[test]
+fn test_documents_use_their_language_analyzer() {
+  let engine = engine(searcher());
+
+  // "the" is an English stopword, but "la" is only a Spanish one.
+  let results = engine.search(&posts(), &Query::builder().text("the").build());
+  assert!(results.is_empty());
+
+  let results = engine.search(&posts(), &Query::builder().text("la").build());
+  assert!(results.is_empty());
+
+  let results = engine.search(&posts(), &Query::builder().text("art").build());
+  assert_eq!(results.len(), 2);
+}
+
+#[test]
+fn test_query_restricted_to_language() {
+  let engine = engine(searcher());
+
+  let query = Query::builder().text("art arte").language("es").build();
+  let results = engine.search(&posts(), &query);
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.title, "El arte de la guerra");
+
+  // Documents without a known language never match a language filter.
+  let query = Query::builder().text("language").language("en").build();
+  assert!(engine.search(&posts(), &query).is_empty());
+}
+
+#[test]
+fn test_named_field_analyzer_takes_precedence() {
+  let searcher = SemanticSearch::new(
+    SemanticRules::builder()
+      .field("title", FieldRule::tokenized().analyzer("plain"))
+      .build(),
+  )
+  .with_language_field("lang")
+  .with_language_analyzer("en", Analyzer::for_language("en"))
+  .with_named_analyzer("plain", Analyzer::default());
+
+  let results = engine(searcher).search(&posts(), &Query::builder().text("the").build());
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.title, "The art of war");
+}
+
+#[cfg(feature = "language-detection")]
+#[test]
+fn test_detected_language() {
+  let posts = vec![
+    post(
+      "The history of the art of cooking, from the old world to the kitchens of today",
+      None,
+    ),
+    post(
+      "La historia del arte de la cocina, desde el viejo mundo hasta las cocinas de hoy",
+      None,
+    ),
+  ];
+  let engine = engine(
+    SemanticSearch::new(
+      SemanticRules::builder()
+        .field("title", FieldRule::tokenized())
+        .build(),
+    )
+    .with_language_detection()
+    .with_language_analyzer("es", spanish()),
+  );
+
+  let query = Query::builder().text("historia").language("es").build();
+  let results = engine.search(&posts, &query);
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.title, posts[1].title);
+
+  // "el" is a stopword in the Spanish document only.
+  let results = engine.search(&posts, &Query::builder().text("el").build());
+  assert!(results.is_empty());
+}
@@ -0,0 +1,113 @@
+use searus::ltr::FeatureExtractor;
+use searus::prelude::*;
+use searus::scoring::FieldValueBoost;
+use serde_json::{json, Value};
+
+fn posts() -> Vec<Value> {
+  vec![
+    json!({ "title": "Rust ownership", "tags": ["rust", "memory"], "views": 0 }),
+    json!({ "title": "Python typing", "tags": ["python"] }),
+    json!({ "title": "Async Rust", "tags": ["rust", "async", "memory"], "views": 9 }),
+  ]
+}
+
+fn engine() -> SearusEngine<Value> {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25())
+    .build();
+  SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .with(Box::new(TaggedSearch::new()))
+    .build()
+}
+
+fn extractor() -> FeatureExtractor<Value> {
+  FeatureExtractor::new()
+    .score()
+    .searcher_score(SearcherKind::Semantic)
+    .searcher_score(SearcherKind::Tags)
+    .searcher_score(SearcherKind::Fuzzy)
+    .field_score("title")
+    .tag_overlap()
+    .modifier(
+      "views",
+      FieldValueBoost::new("views").scaling(searus::scoring::ValueScaling::Linear),
+    )
+    .custom("tag_count", |post: &Value| {
+      post["tags"].as_array().map_or(0, Vec::len) as f32
+    })
+}
+
+/// Returns the feature vectors of the results, by item index.
+fn features(query: &Query) -> Vec<(usize, Vec<f32>)> {
+  let extractor = extractor();
+  let mut features: Vec<(usize, Vec<f32>)> = engine()
+    .search(&posts(), query)
+    .iter()
+    .map(|m| (m.id, extractor.extract(m)))
+    .collect();
+  features.sort_by_key(|(id, _)| *id);
+  features
+}
+
+#[test]
+fn test_feature_names_follow_the_extraction_order() {
+  assert_eq!(
+    extractor().names(),
+    [
+      "score",
+      "searcher:Semantic",
+      "searcher:Tags",
+      "searcher:Fuzzy",
+      "field:title",
+      "tag_overlap",
+      "views",
+      "tag_count",
+    ]
+  );
+}
+
+#[test]
+fn test_features_of_each_searcher_kind() {
+  let query = Query::builder()
+    .text("ownership")
+    .tags(vec!["rust".into(), "memory".into()])
+    .build();
+  let features = features(&query);
+  let ids: Vec<usize> = features.iter().map(|(id, _)| *id).collect();
+  assert_eq!(ids, [0, 2]);
+
+  let (_, owned) = &features[0];
+  assert_eq!(owned.len(), extractor().len());
+  // Matched by both the text and the tags.
+  assert!(owned[1] > 0.0 && owned[2] > 0.0);
+  assert!(owned[4] > 0.0);
+  assert_eq!(owned[5], 2.0);
+
+  let (_, async_rust) = &features[1];
+  // Matched by the tags only: the text features are 0.
+  assert_eq!(async_rust[1], 0.0);
+  assert!(async_rust[2] > 0.0);
+  assert_eq!(async_rust[4], 0.0);
+  assert_eq!(async_rust[5], 2.0);
+
+  // Searchers that are not registered give 0.
+  assert_eq!(owned[3], 0.0);
+  assert_eq!(async_rust[3], 0.0);
+
+  // Modifier and custom features come from the item.
+  assert_eq!((owned[6], owned[7]), (1.0, 2.0));
+  assert_eq!((async_rust[6], async_rust[7]), (10.0, 3.0));
+}
+
+#[test]
+fn test_text_only_matches_have_no_tag_overlap() {
+  let features = features(&Query::builder().text("python").build());
+  assert_eq!(features.len(), 1);
+  let (id, python) = &features[0];
+  assert_eq!(*id, 1);
+  assert_eq!(python[2], 0.0);
+  assert_eq!(python[5], 0.0);
+  // Items without the field use the modifier's default.
+  assert_eq!(python[6], 1.0);
+}
@@ -0,0 +1,190 @@
+use searus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Product {
+  name: String,
+  sku: String,
+}
+
+fn products() -> Vec<Product> {
+  vec![
+    Product {
+      name: "Laptop Pro 14".to_string(),
+      sku: "LP-1400-GR".to_string(),
+    },
+    Product {
+      name: "Lapel pin".to_string(),
+      sku: "PN-0020-BK".to_string(),
+    },
+    Product {
+      name: "Desktop tower".to_string(),
+      sku: "DT-9000-BK".to_string(),
+    },
+  ]
+}
+
+fn engine(field: &str, rule: FieldRule) -> SearusEngine<Product> {
+  SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder().field(field, rule).build(),
+    )))
+    .build()
+}
+
+fn names(results: &[SearusMatch<Product>]) -> Vec<&str> {
+  let mut names: Vec<&str> = results.iter().map(|r| r.item.name.as_str()).collect();
+  names.sort();
+  names
+}
+
+#[test]
+fn test_wildcard_matches_tokens() {
+  let engine = engine("name", FieldRule::wildcard());
+
+  let results = engine.search(&products(), &Query::builder().text("lap*").build());
+  assert_eq!(names(&results), vec!["Lapel pin", "Laptop Pro 14"]);
+
+  let results = engine.search(&products(), &Query::builder().text("*top").build());
+  assert_eq!(names(&results), vec!["Desktop tower", "Laptop Pro 14"]);
+
+  let results = engine.search(&products(), &Query::builder().text("lap?").build());
+  assert!(results.is_empty());
+}
+
+#[test]
+fn test_wildcard_matches_whole_value() {
+  let engine = engine("sku", FieldRule::wildcard());
+
+  let results = engine.search(&products(), &Query::builder().text("??-*-bk").build());
+  assert_eq!(names(&results), vec!["Desktop tower", "Lapel pin"]);
+}
+
+#[test]
+fn test_wildcard_highlights_matched_tokens() {
+  let query = Query::builder()
+    .text("desk*")
+    .options(SearchOptions::default().highlight(true))
+    .build();
+
+  let results = engine("name", FieldRule::wildcard()).search(&products(), &query);
+  let html = highlight_text(
+    &results[0].item.name,
+    "name",
+    &results[0].highlights,
+    "<b>",
+    "</b>",
+  );
+  assert_eq!(html, "<b>Desktop</b> tower");
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_regex_matches_query_pattern() {
+  let engine = engine("sku", FieldRule::regex());
+
+  let results = engine.search(
+    &products(),
+    &Query::builder().text(r"^[a-z]{2}-\d{4}-bk$").build(),
+  );
+  assert_eq!(names(&results), vec!["Desktop tower", "Lapel pin"]);
+
+  // Invalid patterns match nothing.
+  let results = engine.search(&products(), &Query::builder().text("lp-(").build());
+  assert!(results.is_empty());
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_regex_rule_pattern() {
+  let engine = engine("name", FieldRule::regex().pattern(r"\b\d+\b"));
+  let query = Query::builder()
+    .text("any")
+    .options(SearchOptions::default().highlight(true))
+    .build();
+
+  let results = engine.search(&products(), &query);
+  assert_eq!(names(&results), vec!["Laptop Pro 14"]);
+  assert_eq!(
+    highlight_text(
+      &results[0].item.name,
+      "name",
+      &results[0].highlights,
+      "<b>",
+      "</b>"
+    ),
+    "Laptop Pro <b>14</b>"
+  );
+}
+
+#[test]
+fn test_prefix_matches_partial_identifiers() {
+  let engine = engine("sku", FieldRule::prefix());
+
+  let results = engine.search(&products(), &Query::builder().text("lp-14").build());
+  assert_eq!(names(&results), vec!["Laptop Pro 14"]);
+
+  // Unlike fuzzy matching, near misses do not match.
+  let results = engine.search(&products(), &Query::builder().text("lq").build());
+  assert!(results.is_empty());
+}
+
+#[test]
+fn test_numeric_ranks_by_closeness() {
+  #[derive(Debug, Clone, Serialize)]
+  struct Laptop {
+    name: &'static str,
+    price: f64,
+  }
+
+  let laptops = vec![
+    Laptop {
+      name: "Workstation",
+      price: 5000.0,
+    },
+    Laptop {
+      name: "Ultrabook",
+      price: 999.0,
+    },
+    Laptop {
+      name: "Netbook",
+      price: 1250.0,
+    },
+  ];
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("price", FieldRule::numeric())
+        .build(),
+    )))
+    .build();
+
+  let results = engine.search(
+    &laptops,
+    &Query::builder().text("laptop under $1,000").build(),
+  );
+  let names: Vec<&str> = results.iter().map(|r| r.item.name).collect();
+  assert_eq!(names, vec!["Ultrabook", "Netbook", "Workstation"]);
+
+  assert!(engine
+    .search(&laptops, &Query::builder().text("laptop").build())
+    .is_empty());
+}
+
+#[test]
+fn test_exact_matches_phrases_of_terms() {
+  let terms = engine("sku", FieldRule::exact());
+
+  let results = terms.search(&products(), &Query::builder().text("1400 gr").build());
+  assert_eq!(names(&results), vec!["Laptop Pro 14"]);
+  let results = terms.search(&products(), &Query::builder().text("gr 1400").build());
+  assert!(results.is_empty());
+
+  // The keyword analyzer matches whole values.
+  let keyword = engine("sku", FieldRule::exact().analyzer("keyword"));
+  let results = keyword.search(&products(), &Query::builder().text("1400 gr").build());
+  assert!(results.is_empty());
+  let results = keyword.search(&products(), &Query::builder().text("lp-1400-gr").build());
+  assert_eq!(names(&results), vec!["Laptop Pro 14"]);
+}
@@ -0,0 +1,240 @@
+use searus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Comment {
+  author: String,
+  text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Post {
+  title: String,
+  comments: Vec<Comment>,
+}
+
+fn comment(author: &str, text: &str) -> Comment {
+  Comment {
+    author: author.to_string(),
+    text: text.to_string(),
+  }
+}
+
+fn posts() -> Vec<Post> {
+  vec![
+    Post {
+      title: "Release notes".to_string(),
+      comments: vec![
+        comment("alice", "Great release"),
+        comment("bob", "The search is much faster"),
+      ],
+    },
+    Post {
+      title: "Roadmap".to_string(),
+      comments: vec![comment("carol", "Search please")],
+    },
+  ]
+}
+
+fn engine(field: &str) -> SearusEngine<Post> {
+  SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field(field, FieldRule::tokenized())
+        .build(),
+    )))
+    .build()
+}
+
+#[test]
+fn test_wildcard_path_searches_every_element() {
+  let query = Query::builder()
+    .text("search")
+    .options(SearchOptions::default().highlight(true))
+    .build();
+
+  let results = engine("comments.*.text").search(&posts(), &query);
+  assert_eq!(results.len(), 2);
+
+  let release = results
+    .iter()
+    .find(|r| r.item.title == "Release notes")
+    .unwrap();
+  assert_eq!(release.field_scores["comments.*.text"], 1.0);
+  assert_eq!(release.highlights.len(), 1);
+  assert_eq!(release.highlights[0].field, "comments.1.text");
+}
+
+#[test]
+fn test_wildcard_path_sums_element_scores() {
+  let results =
+    engine("comments.*.text").search(&posts(), &Query::builder().text("release search").build());
+  assert_eq!(results[0].item.title, "Release notes");
+  assert_eq!(results[0].field_scores["comments.*.text"], 2.0);
+}
+
+#[test]
+fn test_index_path_searches_one_element() {
+  let results =
+    engine("comments.0.author").search(&posts(), &Query::builder().text("bob carol").build());
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.title, "Roadmap");
+}
+
+#[test]
+fn test_explanation_lists_matched_elements() {
+  let query = Query::builder()
+    .text("release search")
+    .options(SearchOptions::default().explain(true))
+    .build();
+
+  let results = engine("comments.*.text").search(&posts(), &query);
+  let explanation = results[0].explain().unwrap().to_string();
+  assert!(explanation.contains("value of \"comments.0.text\""));
+  assert!(explanation.contains("value of \"comments.1.text\""));
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Author {
+  username: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Article {
+  title: String,
+  author: Author,
+  comments: Vec<Comment>,
+}
+
+fn articles() -> Vec<Article> {
+  vec![
+    Article {
+      title: "Fast search".to_string(),
+      author: Author {
+        username: "alice".to_string(),
+      },
+      comments: vec![comment("bob", "search search"), comment("carol", "search")],
+    },
+    Article {
+      title: "Slow search".to_string(),
+      author: Author {
+        username: "bob".to_string(),
+      },
+      comments: vec![comment("dave", "search search search")],
+    },
+  ]
+}
+
+fn object_engine(score_mode: ScoreMode) -> SearusEngine<Article> {
+  SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .object(
+          "author",
+          ObjectRule::direct()
+            .field("username", FieldRule::exact())
+            .build(),
+        )
+        .object(
+          "comments",
+          ObjectRule::array()
+            .field("author", FieldRule::tokenized())
+            .field("text", FieldRule::tokenized())
+            .score_mode(score_mode)
+            .build(),
+        )
+        .build(),
+    )))
+    .build()
+}
+
+#[test]
+fn test_direct_object_rule() {
+  let results =
+    object_engine(ScoreMode::Sum).search(&articles(), &Query::builder().text("alice").build());
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.title, "Fast search");
+  assert!(results[0].field_scores.contains_key("author.username"));
+}
+
+#[test]
+fn test_array_object_score_modes() {
+  let query = Query::builder().text("search").build();
+
+  let results = object_engine(ScoreMode::Sum).search(&articles(), &query);
+  assert_eq!(results[0].field_scores["comments"], 3.0);
+  assert_eq!(results[1].field_scores["comments"], 3.0);
+
+  let results = object_engine(ScoreMode::Max).search(&articles(), &query);
+  assert_eq!(results[0].item.title, "Slow search");
+  assert_eq!(results[0].field_scores["comments"], 3.0);
+  assert_eq!(results[1].field_scores["comments"], 2.0);
+
+  let results = object_engine(ScoreMode::Avg).search(&articles(), &query);
+  let fast = results
+    .iter()
+    .find(|r| r.item.title == "Fast search")
+    .unwrap();
+  assert_eq!(fast.field_scores["comments"], 1.5);
+}
+
+#[test]
+fn test_array_object_sums_fields_per_element() {
+  let query = Query::builder()
+    .text("bob search")
+    .options(SearchOptions::default().explain(true))
+    .build();
+
+  let results = object_engine(ScoreMode::Max).search(&articles(), &query);
+  let fast = results
+    .iter()
+    .find(|r| r.item.title == "Fast search")
+    .unwrap();
+  // The first comment matches both its author and its text.
+  assert_eq!(fast.field_scores["comments"], 3.0);
+
+  let explanation = fast.explain().unwrap();
+  assert!(explanation
+    .to_string()
+    .contains("object \"comments\", max of elements:"));
+  assert!((explanation.value - fast.score).abs() < 1e-6);
+}
+
+#[test]
+fn test_field_glob_patterns_expand_against_documents() {
+  let documents = vec![
+    serde_json::json!({
+      "en_title": "Search engines",
+      "fr_title": "Moteurs de recherche",
+      "metadata": { "author": "alice", "editor": "bob" }
+    }),
+    serde_json::json!({
+      "en_title": "Cooking",
+      "subtitle": "search for recipes",
+      "metadata": { "author": "bob" }
+    }),
+  ];
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("*_title", FieldRule::tokenized())
+        .field("metadata.*", FieldRule::tokenized())
+        .build(),
+    )))
+    .build();
+
+  // "subtitle" does not match "*_title".
+  let results = engine.search(
+    &documents,
+    &Query::builder().text("search recherche").build(),
+  );
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].field_scores["*_title"], 2.0);
+
+  let results = engine.search(&documents, &Query::builder().text("bob").build());
+  assert_eq!(results.len(), 2);
+  assert!(results
+    .iter()
+    .all(|r| r.field_scores.contains_key("metadata.*")));
+}
@@ -0,0 +1,202 @@
+use searus::prelude::*;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+/// Matches every item, noting whether each search ran in parallel and on
+/// which thread.
+#[derive(Default)]
+struct Probe {
+  parallel: Mutex<Vec<bool>>,
+  threads: Mutex<Vec<Option<String>>>,
+}
+
+struct ProbeSearcher(Arc<Probe>);
+
+impl Searcher<Value> for ProbeSearcher {
+  fn kind(&self) -> SearcherKind {
+    SearcherKind::Custom
+  }
+
+  fn search(&self, context: &SearchContext<Value>, query: &Query) -> Vec<SearusMatch<Value>> {
+    self.0.parallel.lock().unwrap().push(context.is_parallel());
+    let thread = std::thread::current().name().map(String::from);
+    self.0.threads.lock().unwrap().push(thread);
+    context.scan(None, query, |i, item| {
+      Some(SearusMatch::new(item.clone(), 1.0, i))
+    })
+  }
+}
+
+fn posts(count: usize) -> Vec<Value> {
+  (0..count)
+    .map(|i| {
+      json!({
+        "title": format!("Rust post {} about {}", i, if i % 3 == 0 { "async" } else { "ownership" }),
+        "tags": if i % 2 == 0 { vec!["rust"] } else { vec!["rust", "async"] },
+      })
+    })
+    .collect()
+}
+
+fn engine(builder: SearusEngineBuilder<Value>) -> SearusEngine<Value> {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25())
+    .build();
+  builder
+    .with(Box::new(SemanticSearch::new(rules)))
+    .with(Box::new(TaggedSearch::new()))
+    .build()
+}
+
+fn probed(builder: SearusEngineBuilder<Value>) -> (SearusEngine<Value>, Arc<Probe>) {
+  let probe = Arc::new(Probe::default());
+  let engine = builder.with(Box::new(ProbeSearcher(probe.clone()))).build();
+  (engine, probe)
+}
+
+#[test]
+fn test_sequential_searches_match_parallel_searches() {
+  let items = posts(60);
+  let parallel = engine(SearusEngine::builder());
+  let sequential = engine(SearusEngine::builder().parallel_threshold(1_000));
+
+  let query = Query::builder()
+    .text("async")
+    .tags(vec!["async".into()])
+    .options(SearchOptions::default().limit(100).explain(true))
+    .build();
+  // Items with the same score come in any order.
+  let search = |engine: &SearusEngine<Value>| {
+    let mut results = engine.search(&items, &query);
+    results.sort_by_key(|m| m.id);
+    serde_json::to_value(results).unwrap()
+  };
+
+  let expected = search(&parallel);
+  assert!(!expected.as_array().unwrap().is_empty());
+  assert_eq!(search(&sequential), expected);
+}
+
+#[test]
+fn test_searches_below_the_threshold_run_sequentially() {
+  let (engine, probe) = probed(SearusEngine::builder().parallel_threshold(10));
+  let query = Query::builder().text("rust").build();
+
+  assert_eq!(engine.search(&posts(5), &query).len(), 5);
+  assert_eq!(engine.search(&posts(20), &query).len(), 20);
+  assert_eq!(
+    *probe.parallel.lock().unwrap(),
+    [false, cfg!(feature = "parallel")]
+  );
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_searches_run_in_the_thread_pool() {
+  let pool = rayon::ThreadPoolBuilder::new()
+    .num_threads(2)
+    .thread_name(|i| format!("search-{}", i))
+    .build()
+    .unwrap();
+  let (engine, probe) = probed(SearusEngine::builder().thread_pool(Arc::new(pool)));
+  let query = Query::builder().text("rust").build();
+
+  engine.search(&posts(5), &query);
+  engine.search_response(&posts(5), &query);
+  let threads = probe.threads.lock().unwrap();
+  assert_eq!(threads.len(), 2);
+  assert!(threads.iter().all(|name| name
+    .as_deref()
+    .is_some_and(|name| name.starts_with("search-"))));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_max_threads_bounds_the_thread_pool() {
+  struct ThreadCount(Arc<Mutex<Vec<usize>>>);
+
+  impl Searcher<Value> for ThreadCount {
+    fn kind(&self) -> SearcherKind {
+      SearcherKind::Custom
+    }
+
+    fn search(&self, _context: &SearchContext<Value>, _query: &Query) -> Vec<SearusMatch<Value>> {
+      self.0.lock().unwrap().push(rayon::current_num_threads());
+      Vec::new()
+    }
+  }
+
+  let counts = Arc::new(Mutex::new(Vec::new()));
+  let engine = SearusEngine::builder()
+    .with(Box::new(ThreadCount(counts.clone())))
+    .max_threads(3)
+    .build();
+
+  engine.search(&posts(5), &Query::builder().text("rust").build());
+  assert_eq!(*counts.lock().unwrap(), [3]);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_chunks_are_matched_on_one_thread() {
+  let pool = Arc::new(
+    rayon::ThreadPoolBuilder::new()
+      .num_threads(4)
+      .build()
+      .unwrap(),
+  );
+  // Notes the thread matching each item.
+  let threads = Arc::new(Mutex::new(std::collections::HashSet::new()));
+
+  struct ThreadSearcher(Arc<Mutex<std::collections::HashSet<usize>>>);
+
+  impl Searcher<Value> for ThreadSearcher {
+    fn kind(&self) -> SearcherKind {
+      SearcherKind::Custom
+    }
+
+    fn search(&self, context: &SearchContext<Value>, query: &Query) -> Vec<SearusMatch<Value>> {
+      context.scan(None, query, |i, item| {
+        let thread = rayon::current_thread_index().unwrap();
+        self.0.lock().unwrap().insert(thread);
+        Some(SearusMatch::new(item.clone(), 1.0, i))
+      })
+    }
+  }
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(ThreadSearcher(threads.clone())))
+    .thread_pool(pool)
+    .parallel_chunk_size(1_000)
+    .build();
+
+  let query = Query::builder().text("rust").build();
+  let response = engine.search_response(&posts(500), &query);
+  assert_eq!(response.total_matches, 500);
+  assert_eq!(threads.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_chunk_size_does_not_change_results() {
+  let items = posts(60);
+  let query = Query::builder()
+    .text("async")
+    .tags(vec!["async".into()])
+    .options(
+      SearchOptions::default()
+        .limit(100)
+        .max_matches_per_searcher(20),
+    )
+    .build();
+  let search = |engine: SearusEngine<Value>| {
+    let mut results = engine.search(&items, &query);
+    results.sort_by_key(|m| m.id);
+    serde_json::to_value(results).unwrap()
+  };
+
+  let expected = search(engine(SearusEngine::builder()));
+  for chunk_size in [1, 7, 64] {
+    let chunked = engine(SearusEngine::builder().parallel_chunk_size(chunk_size));
+    assert_eq!(search(chunked), expected, "chunk size {}", chunk_size);
+  }
+}
@@ -0,0 +1,203 @@
+#![cfg(feature = "postgres")]
+
+use postgres::{Client, NoTls};
+use searus::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
+use searus::postgres::PostgresSource;
+use searus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+fn compare(field: &str, op: CompareOp, value: impl Into<FilterValue>) -> FilterExpr {
+  FilterExpr::Compare {
+    field: field.to_string(),
+    op,
+    value: value.into(),
+    options: StringOptions::default(),
+  }
+}
+
+fn list(values: &[&str]) -> FilterValue {
+  FilterValue::List(values.iter().map(|value| (*value).into()).collect())
+}
+
+#[test]
+fn test_filters_are_translated_to_sql() {
+  let source = PostgresSource::new("public.books").with_limit(100);
+  assert_eq!(
+    source.sql(None).0,
+    r#"SELECT to_jsonb(t) FROM "public"."books" AS t LIMIT 100"#
+  );
+
+  let filter = FilterExpr::Or(vec![
+    FilterExpr::And(vec![
+      compare("category", CompareOp::In, list(&["tech", "science"])),
+      FilterExpr::Between {
+        field: "price".to_string(),
+        low: 10.into(),
+        high: 20.5.into(),
+        inclusive: false,
+        options: StringOptions::default(),
+      },
+    ]),
+    FilterExpr::Not(Box::new(FilterExpr::Or(vec![
+      compare("in_print", CompareOp::Eq, true),
+      compare("title", CompareOp::Ne, "Dune"),
+    ]))),
+  ]);
+  let (sql, params) = source.sql(Some(&filter));
+  assert_eq!(
+    sql,
+    concat!(
+      r#"SELECT to_jsonb(t) FROM "public"."books" AS t WHERE (("#,
+      r#"t."category" IN ($1::text, $2::text) AND (t."price" > 10 AND t."price" < 20.5)) OR "#,
+      r#"((t."in_print" = TRUE OR t."title" <> $3::text)) IS NOT TRUE) LIMIT 100"#,
+    )
+  );
+  assert_eq!(params, ["tech", "science", "Dune"]);
+
+  // Rows with null values are kept, as the engine keeps them.
+  let filter = compare("category", CompareOp::NotIn, list(&["tech"]));
+  let (sql, params) = source.sql(Some(&filter));
+  assert_eq!(
+    sql,
+    concat!(
+      r#"SELECT to_jsonb(t) FROM "public"."books" AS t "#,
+      r#"WHERE (t."category" NOT IN ($1::text) OR t."category" IS NULL) LIMIT 100"#,
+    )
+  );
+  assert_eq!(params, ["tech"]);
+}
+
+#[test]
+fn test_untranslatable_filters_are_dropped() {
+  let source = PostgresSource::new("books");
+
+  // Conjunctions keep the parts that can be translated.
+  let filter = FilterExpr::And(vec![
+    compare("title", CompareOp::Contains, "rust"),
+    compare("author.name", CompareOp::Eq, "Jim"),
+    compare("year", CompareOp::Ge, 2020),
+  ]);
+  assert_eq!(
+    source.sql(Some(&filter)).0,
+    r#"SELECT to_jsonb(t) FROM "books" AS t WHERE (t."year" >= 2020)"#
+  );
+
+  // Disjunctions and negations are dropped whole, with their parameters.
+  let filter = FilterExpr::And(vec![
+    FilterExpr::Or(vec![
+      compare("category", CompareOp::Eq, "tech"),
+      compare("title", CompareOp::StartsWith, "Rust"),
+    ]),
+    FilterExpr::Not(Box::new(FilterExpr::And(vec![
+      compare("category", CompareOp::Eq, "history"),
+      compare(
+        "price",
+        CompareOp::Lt,
+        FilterValue::Date("2024-01-01".into()),
+      ),
+    ]))),
+    compare("title", CompareOp::Eq, "Dune"),
+  ]);
+  let (sql, params) = source.sql(Some(&filter));
+  assert_eq!(
+    sql,
+    r#"SELECT to_jsonb(t) FROM "books" AS t WHERE (t."title" = $1::text)"#
+  );
+  assert_eq!(params, ["Dune"]);
+
+  let filter = compare("title", CompareOp::Eq, "Dune")
+    .with_string_options(StringOptions::new().case_sensitive(false));
+  assert_eq!(
+    source.sql(Some(&filter)).0,
+    r#"SELECT to_jsonb(t) FROM "books" AS t"#
+  );
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Book {
+  id: i32,
+  title: String,
+  category: String,
+  price: f64,
+  in_print: bool,
+  series: Option<String>,
+}
+
+/// Runs against the database at `SEARUS_POSTGRES_URL`, when it is set.
+#[test]
+fn test_search_fetches_candidates() {
+  let Ok(url) = std::env::var("SEARUS_POSTGRES_URL") else {
+    return;
+  };
+  let mut client = Client::connect(&url, NoTls).unwrap();
+  let mut transaction = client.transaction().unwrap();
+  transaction
+    .batch_execute(
+      "CREATE TEMPORARY TABLE books (
+        id integer PRIMARY KEY,
+        title text NOT NULL,
+        category varchar(32) NOT NULL,
+        price double precision NOT NULL,
+        in_print boolean NOT NULL,
+        series text
+      );
+      INSERT INTO books VALUES
+        (1, 'Programming Rust', 'tech', 40, TRUE, 'Animal'),
+        (2, 'Rust in Action', 'tech', 25.5, TRUE, 'In Action'),
+        (3, 'The Rust Belt', 'history', 15, FALSE, NULL),
+        (4, 'Python Crash Course', 'tech', 30, TRUE, 'Crash Course');",
+    )
+    .unwrap();
+
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25())
+    .build();
+  let engine: SearusEngine<Book> = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .build();
+  let source = PostgresSource::new("books");
+
+  let filter = FilterExpr::And(vec![
+    compare("category", CompareOp::In, list(&["tech"])),
+    compare("price", CompareOp::Lt, 35),
+    compare("in_print", CompareOp::Eq, true),
+  ]);
+  let books: Vec<Book> = source.fetch(&mut transaction, Some(&filter)).unwrap();
+  assert_eq!(books.len(), 2);
+
+  // Null columns aren't in any list.
+  let filter = compare("series", CompareOp::NotIn, list(&["Animal", "In Action"]));
+  let books: Vec<Book> = source.fetch(&mut transaction, Some(&filter)).unwrap();
+  let mut ids: Vec<_> = books.iter().map(|book| book.id).collect();
+  ids.sort();
+  assert_eq!(ids, [3, 4]);
+
+  // Filters that only run locally are checked by the engine.
+  let query = Query::builder()
+    .text("rust")
+    .filters(FilterExpr::And(vec![
+      compare("category", CompareOp::Eq, "tech"),
+      compare("title", CompareOp::Contains, "action"),
+    ]))
+    .build();
+  let response = source.search(&mut transaction, &engine, &query).unwrap();
+  assert_eq!(response.total_matches, 1);
+  assert_eq!(response.results[0].item.title, "Rust in Action");
+
+  let query = Query::builder()
+    .text("rust")
+    .filters(FilterExpr::Not(Box::new(compare(
+      "category",
+      CompareOp::Eq,
+      "tech",
+    ))))
+    .build();
+  let response = source.search(&mut transaction, &engine, &query).unwrap();
+  assert_eq!(response.results.len(), 1);
+  assert_eq!(response.results[0].item.id, 3);
+
+  let error = PostgresSource::new("missing")
+    .fetch::<Book, _>(&mut transaction, None)
+    .unwrap_err();
+  assert!(error.contains("\"missing\""), "{}", error);
+}
@@ -0,0 +1,91 @@
+use searus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Author {
+  name: String,
+  email: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Article {
+  id: u32,
+  title: String,
+  body: String,
+  author: Author,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Summary {
+  id: u32,
+  title: String,
+}
+
+fn articles() -> Vec<Article> {
+  vec![Article {
+    id: 1,
+    title: "Rust search engines".to_string(),
+    body: "A very long body. ".repeat(100),
+    author: Author {
+      name: "Ada".to_string(),
+      email: "ada@example.com".to_string(),
+    },
+  }]
+}
+
+fn engine() -> SearusEngine<Article> {
+  SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(
+      SemanticRules::builder()
+        .field("title", FieldRule::tokenized())
+        .build(),
+    )))
+    .build()
+}
+
+fn query(fields: &[&str]) -> Query {
+  Query::builder()
+    .text("rust")
+    .options(SearchOptions::default().select(fields.iter().copied()))
+    .build()
+}
+
+#[test]
+fn test_project_into_partial_struct() {
+  let results = engine()
+    .search_projected::<Summary>(&articles(), &query(&["id", "title"]))
+    .unwrap();
+
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.id, 1);
+  assert_eq!(results[0].item.title, "Rust search engines");
+  assert!(results[0].score > 0.0);
+}
+
+#[test]
+fn test_project_nested_fields_into_json() {
+  let results = engine()
+    .search_projected::<serde_json::Value>(&articles(), &query(&["id", "author.name", "missing"]))
+    .unwrap();
+
+  assert_eq!(
+    results[0].item,
+    serde_json::json!({ "id": 1, "author": { "name": "Ada" } })
+  );
+}
+
+#[test]
+fn test_project_without_selection_returns_whole_item() {
+  let results = engine()
+    .search_projected::<serde_json::Value>(&articles(), &query(&[]))
+    .unwrap();
+
+  assert_eq!(results[0].item["author"]["email"], "ada@example.com");
+}
+
+#[test]
+fn test_project_reports_missing_fields() {
+  let result = engine().search_projected::<Summary>(&articles(), &query(&["id"]));
+
+  assert!(result.is_err());
+}
@@ -0,0 +1,318 @@
+#![cfg(feature = "qdrant")]
+
+use searus::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
+use searus::index::{QdrantCollection, QdrantIndex};
+use searus::prelude::*;
+use searus::searchers::QdrantSearch;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// Serves one canned response per request, and sends back the request line
+/// and body of each request.
+fn serve(responses: Vec<(u16, Value)>) -> (String, Receiver<(String, Value)>) {
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let url = format!("http://{}", listener.local_addr().unwrap());
+  let (sender, requests) = mpsc::channel();
+
+  thread::spawn(move || {
+    for (status, body) in responses {
+      let (stream, _) = listener.accept().unwrap();
+      let mut reader = BufReader::new(stream);
+      let mut request_line = String::new();
+      reader.read_line(&mut request_line).unwrap();
+      let mut length = 0;
+      loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim().is_empty() {
+          break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+          if name.eq_ignore_ascii_case("content-length") {
+            length = value.trim().parse().unwrap();
+          }
+        }
+      }
+      let mut request = vec![0; length];
+      reader.read_exact(&mut request).unwrap();
+      let request_line = request_line.rsplit_once(' ').unwrap().0.to_string();
+      sender
+        .send((request_line, serde_json::from_slice(&request).unwrap()))
+        .unwrap();
+
+      let body = body.to_string();
+      write!(
+        reader.get_mut(),
+        "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+      )
+      .unwrap();
+    }
+  });
+
+  (url, requests)
+}
+
+fn ok(result: Value) -> (u16, Value) {
+  (
+    200,
+    json!({ "result": result, "status": "ok", "time": 0.001 }),
+  )
+}
+
+fn point(id: &str, score: f32) -> Value {
+  json!({ "id": 1, "version": 0, "score": score, "payload": { "_searus_id": id } })
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Book {
+  id: String,
+  title: String,
+  category: String,
+  price: f64,
+}
+
+fn books() -> Vec<Book> {
+  [
+    ("1", "Programming Rust", "tech", 40.0),
+    ("rust-2", "Rust in Action", "tech", 25.0),
+    ("3", "The Rust Belt", "history", 15.0),
+  ]
+  .into_iter()
+  .map(|(id, title, category, price)| Book {
+    id: id.to_string(),
+    title: title.to_string(),
+    category: category.to_string(),
+    price,
+  })
+  .collect()
+}
+
+fn compare(field: &str, op: CompareOp, value: impl Into<FilterValue>) -> FilterExpr {
+  FilterExpr::Compare {
+    field: field.to_string(),
+    op,
+    value: value.into(),
+    options: StringOptions::default(),
+  }
+}
+
+#[test]
+fn test_index_writes_points() {
+  let (url, requests) = serve(vec![
+    ok(json!(true)),
+    ok(json!({ "operation_id": 1, "status": "completed" })),
+    ok(json!({ "operation_id": 2, "status": "completed" })),
+    ok(json!([point("rust-2", 0.9)])),
+    ok(json!({ "operation_id": 3, "status": "completed" })),
+    (
+      404,
+      json!({ "status": { "error": "Not found: Collection `books` doesn't exist!" } }),
+    ),
+  ]);
+  let books = books();
+  let collection = QdrantCollection::new(format!("{}/", url), "books").with_api_key("secret");
+
+  collection.create(&[("default", 2), ("cover", 4)]).unwrap();
+  let (line, body) = requests.recv().unwrap();
+  assert_eq!(line, "PUT /collections/books");
+  assert_eq!(
+    body,
+    json!({ "vectors": {
+      "default": { "size": 2, "distance": "Cosine" },
+      "cover": { "size": 4, "distance": "Cosine" },
+    } })
+  );
+
+  let mut index = QdrantIndex::new(collection);
+  index
+    .put(
+      "1".to_string(),
+      books[0].clone(),
+      Some(vec![1.0, 0.0]),
+      None,
+    )
+    .unwrap();
+  let (line, body) = requests.recv().unwrap();
+  assert_eq!(line, "PUT /collections/books/points?wait=true");
+  assert_eq!(body["points"][0]["id"], json!(1));
+  assert_eq!(
+    body["points"][0]["vector"],
+    json!({ "default": [1.0, 0.0] })
+  );
+  assert_eq!(body["points"][0]["payload"]["_searus_id"], "1");
+  assert_eq!(body["points"][0]["payload"]["title"], "Programming Rust");
+
+  // Ids that aren't integers get a stable UUID.
+  let tags = Some(vec!["rust".to_string()]);
+  index
+    .put(
+      "rust-2".to_string(),
+      books[1].clone(),
+      Some(vec![0.6, 0.8]),
+      tags,
+    )
+    .unwrap();
+  let (_, body) = requests.recv().unwrap();
+  let id = body["points"][0]["id"].as_str().unwrap().to_string();
+  assert_eq!(id.len(), 36);
+  assert_eq!(&id[14..15], "8");
+  assert_eq!(
+    body["points"][0]["payload"]["_searus_tags"],
+    json!(["rust"])
+  );
+  assert_eq!(index.all().len(), 2);
+
+  // Qdrant's similarities are returned as distances.
+  let neighbors = index.knn(&[0.5, 0.5], 1);
+  assert_eq!(neighbors.len(), 1);
+  assert_eq!(neighbors[0].0, "rust-2");
+  assert!((neighbors[0].1 - 0.1).abs() < 1e-6);
+  let (line, body) = requests.recv().unwrap();
+  assert_eq!(line, "POST /collections/books/points/search");
+  assert_eq!(
+    body,
+    json!({
+      "vector": { "name": "default", "vector": [0.5, 0.5] },
+      "limit": 1,
+      "with_payload": ["_searus_id"],
+    })
+  );
+
+  index.remove(&"rust-2".to_string()).unwrap();
+  let (line, body) = requests.recv().unwrap();
+  assert_eq!(line, "POST /collections/books/points/delete?wait=true");
+  assert_eq!(body, json!({ "points": [id] }));
+  assert!(index.get(&"rust-2".to_string()).is_none());
+
+  // A failed write leaves the local items unchanged.
+  let error = index
+    .put("3".to_string(), books[2].clone(), None, None)
+    .unwrap_err();
+  assert!(error.contains("doesn't exist"), "{}", error);
+  assert!(index.get(&"3".to_string()).is_none());
+}
+
+#[test]
+fn test_filters_are_translated_and_checked() {
+  let mut responses = vec![ok(json!({ "status": "completed" })); 3];
+  responses.push(ok(json!([point("rust-2", 0.9), point("1", 0.8)])));
+  let (url, requests) = serve(responses);
+
+  let mut index = QdrantIndex::new(QdrantCollection::new(url, "books"));
+  for book in books() {
+    index
+      .put(book.id.clone(), book, Some(vec![1.0, 0.0]), None)
+      .unwrap();
+  }
+  for _ in 0..3 {
+    requests.recv().unwrap();
+  }
+
+  let filter = FilterExpr::And(vec![
+    compare("category", CompareOp::Eq, "tech"),
+    FilterExpr::Not(Box::new(compare(
+      "category",
+      CompareOp::In,
+      FilterValue::List(vec!["history".into(), "travel".into()]),
+    ))),
+    FilterExpr::Between {
+      field: "price".to_string(),
+      low: 10.0.into(),
+      high: 50.0.into(),
+      inclusive: true,
+      options: StringOptions::default(),
+    },
+    // Payloads may store whole numbers as floats, so numbers match ranges.
+    compare("price", CompareOp::Eq, 25),
+    compare(
+      "price",
+      CompareOp::In,
+      FilterValue::List(vec![25.into(), 30.into()]),
+    ),
+    // Qdrant has no substring match, so this only runs locally.
+    compare("title", CompareOp::Contains, "Action"),
+    // Qdrant would drop arrays with any element equal to "Dune", which the
+    // engine keeps, so this only runs locally too.
+    FilterExpr::Not(Box::new(compare("title", CompareOp::Eq, "Dune"))),
+  ]);
+  let neighbors = index
+    .knn_filtered("default", &[1.0, 0.0], 2, Some(&filter))
+    .unwrap();
+  assert_eq!(neighbors.len(), 1);
+  assert_eq!(neighbors[0].0, "rust-2");
+
+  let (_, body) = requests.recv().unwrap();
+  assert_eq!(
+    body["filter"],
+    json!({ "must": [{ "must": [
+      { "key": "category", "match": { "value": "tech" } },
+      { "must_not": [{ "key": "category", "match": { "any": ["history", "travel"] } }] },
+      { "key": "price", "range": { "gte": 10.0, "lte": 50.0 } },
+      { "key": "price", "range": { "gte": 25.0, "lte": 25.0 } },
+      { "should": [
+        { "key": "price", "range": { "gte": 25.0, "lte": 25.0 } },
+        { "key": "price", "range": { "gte": 30.0, "lte": 30.0 } },
+      ] },
+    ] }] })
+  );
+}
+
+#[test]
+fn test_search_maps_neighbors_to_items() {
+  let (url, requests) = serve(vec![
+    ok(json!([
+      point("3", 0.95),
+      point("1", 0.7),
+      point("missing", 0.6)
+    ])),
+    ok(json!([point("1", 0.8)])),
+    (
+      500,
+      json!({ "status": { "error": "Service internal error" } }),
+    ),
+  ]);
+  let books = books();
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      QdrantSearch::new(QdrantCollection::new(url, "books"), "id").with_limit(3),
+    ))
+    .build();
+
+  let query = Query::builder().vector(vec![0.1, 0.9]).build();
+  let results = engine.search(&books, &query);
+  let titles: Vec<&str> = results.iter().map(|m| m.item.title.as_str()).collect();
+  assert_eq!(titles, ["The Rust Belt", "Programming Rust"]);
+  let (_, body) = requests.recv().unwrap();
+  assert_eq!(body["limit"], 3);
+
+  let query = Query::builder()
+    .vector(vec![0.1, 0.9])
+    .vector_name("cover")
+    .filters(compare("price", CompareOp::Gt, 30))
+    .build();
+  let results = engine.search(&books, &query);
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].field_scores.get("cover"), Some(&0.8));
+  let (_, body) = requests.recv().unwrap();
+  assert_eq!(body["vector"]["name"], "cover");
+  assert_eq!(
+    body["filter"],
+    json!({ "must": [{ "key": "price", "range": { "gt": 30.0 } }] })
+  );
+
+  // A failing server matches nothing.
+  assert!(engine.search(&books, &query).is_empty());
+  requests.recv().unwrap();
+
+  // Nothing is requested without a query vector.
+  let query = Query::builder().text("rust").build();
+  assert!(engine.search(&books, &query).is_empty());
+  assert!(requests.try_recv().is_err());
+}
@@ -0,0 +1,110 @@
+use searus::prelude::*;
+
+#[test]
+fn test_serialized_rules_round_trip() {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25().priority(2).boost(1.5))
+    .field("sku", FieldRule::regex().pattern(r"^\w+-\d+$"))
+    .object(
+      "comments",
+      ObjectRule::array()
+        .field("text", FieldRule::tfidf())
+        .score_mode(ScoreMode::Avg)
+        .build(),
+    )
+    .build();
+
+  let json = serde_json::to_string(&rules).unwrap();
+  let loaded = SemanticRules::from_json(&json).unwrap();
+
+  assert_eq!(loaded.fields["title"].matcher, Matcher::BM25);
+  assert_eq!(loaded.fields["title"].priority, 2);
+  assert_eq!(loaded.fields["sku"].pattern.as_deref(), Some(r"^\w+-\d+$"));
+  assert_eq!(loaded.objects["comments"].access, ObjectAccess::Array);
+  assert_eq!(loaded.objects["comments"].score_mode, ScoreMode::Avg);
+}
+
+#[test]
+fn test_invalid_rules_are_rejected() {
+  let errors = [
+    (
+      r#"{ "fields": { "title": { "matcher": "bm52" } } }"#,
+      "unknown variant",
+    ),
+    (
+      r#"{ "fields": { "title": { "boost": -1 } } }"#,
+      "invalid boost",
+    ),
+    (
+      r#"{ "fields": { "title": { "pattern": "a+" } } }"#,
+      "regex matcher",
+    ),
+    (r#"{ "objects": { "author": {} } }"#, "has no fields"),
+    (r#"{ "field": {} }"#, "unknown field `field`"),
+  ];
+
+  for (json, expected) in errors {
+    let error = SemanticRules::from_json(json).unwrap_err();
+    assert!(
+      error.contains(expected),
+      "{:?} should contain {:?}",
+      error,
+      expected
+    );
+  }
+}
+
+#[test]
+fn test_loaded_rules_drive_search() {
+  #[derive(Debug, Clone, serde::Serialize)]
+  struct Post {
+    title: &'static str,
+  }
+
+  let rules =
+    SemanticRules::from_json(r#"{ "fields": { "title": { "matcher": "prefix" } } }"#).unwrap();
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .build();
+
+  let posts = vec![Post { title: "Searching" }, Post { title: "Sorting" }];
+  let results = engine.search(&posts, &Query::builder().text("sea").build());
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.title, "Searching");
+}
+
+#[test]
+fn test_engine_config_builds_searchers() {
+  use searus::config::EngineConfig;
+  use serde_json::json;
+
+  let config = EngineConfig::from_json(
+    r#"{
+      "semantic": { "fields": { "title": { "matcher": "bm25" } } },
+      "fuzzy": { "fields": ["title"], "threshold": 0.7 },
+      "tags": {}
+    }"#,
+  )
+  .unwrap();
+  assert_eq!(config.tags.as_ref().unwrap().field, "tags");
+
+  let engine = config.build();
+  let items = vec![
+    json!({ "title": "Rust ownership", "tags": ["systems"] }),
+    json!({ "title": "Python typing", "tags": ["scripting"] }),
+  ];
+
+  let by_text = engine.search(&items, &Query::builder().text("rust").build());
+  assert_eq!(by_text[0].item["title"], "Rust ownership");
+
+  let by_typo = engine.search(&items, &Query::builder().text("pyhton").build());
+  assert_eq!(by_typo[0].item["title"], "Python typing");
+
+  let by_tag = engine.search(
+    &items,
+    &Query::builder().tags(vec!["scripting".into()]).build(),
+  );
+  assert_eq!(by_tag.len(), 1);
+
+  assert!(EngineConfig::from_json(r#"{ "fuzzy": { "threshold": 0.7 } }"#).is_err());
+}
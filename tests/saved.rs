@@ -0,0 +1,205 @@
+use searus::prelude::*;
+use searus::saved::{SavedQueries, SavedQuery};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+fn posts() -> Vec<Value> {
+  vec![
+    json!({ "title": "Rust 2024 edition", "tags": ["rust", "news"], "published": 1_000 }),
+    json!({ "title": "Rust 2015 edition", "tags": ["rust", "news"], "published": 10 }),
+    json!({ "title": "Python typing", "tags": ["python", "news"], "published": 950 }),
+    json!({ "title": "Rust ownership", "tags": ["rust", "guide"], "published": 900 }),
+  ]
+}
+
+fn engine() -> SearusEngine<Value> {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25())
+    .build();
+  SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .with(Box::new(TaggedSearch::new()))
+    .build()
+}
+
+fn saved() -> SavedQueries {
+  SavedQueries::new()
+    .with(
+      "new-this-week",
+      SavedQuery::new(
+        &Query::builder()
+          .tags(vec!["{{topic}}".into()])
+          .filters(
+            Query::filter(Query::COMPARE)
+              .ge("published", "{{since}}")
+              .build(),
+          )
+          .build(),
+      )
+      .description("Posts on a topic published since a timestamp")
+      .default_value("topic", "news"),
+    )
+    .with(
+      "about",
+      SavedQuery::new(&Query::builder().text("{{subject}} edition").build()),
+    )
+}
+
+fn params(params: Value) -> HashMap<String, Value> {
+  serde_json::from_value(params).unwrap()
+}
+
+fn titles(results: &[SearusMatch<Value>]) -> Vec<&str> {
+  let mut titles: Vec<&str> = results
+    .iter()
+    .map(|m| m.item["title"].as_str().unwrap())
+    .collect();
+  titles.sort_unstable();
+  titles
+}
+
+#[test]
+fn test_saved_queries_are_listed_by_name() {
+  let mut saved = saved();
+  assert_eq!(saved.names(), ["about", "new-this-week"]);
+  assert_eq!(saved.len(), 2);
+  assert_eq!(
+    saved.get("new-this-week").unwrap().placeholders(),
+    ["since", "topic"]
+  );
+  assert_eq!(saved.get("about").unwrap().placeholders(), ["subject"]);
+
+  let descriptions: Vec<Option<&str>> = saved
+    .iter()
+    .map(|(_, query)| query.description.as_deref())
+    .collect();
+  assert_eq!(
+    descriptions,
+    [None, Some("Posts on a topic published since a timestamp")]
+  );
+
+  assert!(saved.remove("about").is_some());
+  assert!(saved.remove("about").is_none());
+  assert_eq!(saved.names(), ["new-this-week"]);
+}
+
+#[test]
+fn test_placeholders_are_filled_in_from_params_and_defaults() {
+  let saved = saved();
+
+  let query = saved
+    .query("new-this-week", &params(json!({ "since": 900 })))
+    .unwrap();
+  assert_eq!(query.tags, Some(vec!["news".to_string()]));
+  let filter = query.filters.unwrap();
+  assert!(filter.evaluate(&json!({ "published": 900 })));
+  assert!(!filter.evaluate(&json!({ "published": 899 })));
+
+  // Placeholders inside longer strings are replaced by their text.
+  let query = saved
+    .query("about", &params(json!({ "subject": "Rust" })))
+    .unwrap();
+  assert_eq!(query.text.as_deref(), Some("Rust edition"));
+  let query = saved
+    .query("about", &params(json!({ "subject": 2024 })))
+    .unwrap();
+  assert_eq!(query.text.as_deref(), Some("2024 edition"));
+}
+
+#[test]
+fn test_binding_errors() {
+  let saved = saved();
+
+  let error = saved.query("trending", &HashMap::new()).unwrap_err();
+  assert_eq!(error, "No saved query named \"trending\"");
+  let error = saved.query("new-this-week", &HashMap::new()).unwrap_err();
+  assert_eq!(
+    error,
+    "Saved query \"new-this-week\": Missing parameter \"since\""
+  );
+  let error = saved
+    .query(
+      "about",
+      &params(json!({ "subject": "Rust", "sort": "new" })),
+    )
+    .unwrap_err();
+  assert_eq!(error, "Saved query \"about\": Unknown parameter \"sort\"");
+
+  // Values that do not fit the query are rejected.
+  let error = saved
+    .query("new-this-week", &params(json!({ "since": 1, "topic": 3 })))
+    .unwrap_err();
+  assert!(error.starts_with("Saved query \"new-this-week\": Invalid query:"));
+}
+
+#[test]
+fn test_saved_queries_run_by_name() {
+  let items = posts();
+  let engine = engine();
+  let saved = saved();
+
+  let results = saved
+    .search(
+      &engine,
+      &items,
+      "new-this-week",
+      &params(json!({ "since": 900 })),
+    )
+    .unwrap();
+  assert_eq!(titles(&results), ["Python typing", "Rust 2024 edition"]);
+
+  let response = saved
+    .search_response(
+      &engine,
+      &items,
+      "new-this-week",
+      &params(json!({ "since": 900, "topic": "rust" })),
+    )
+    .unwrap();
+  assert_eq!(
+    titles(&response.results),
+    ["Rust 2024 edition", "Rust ownership"]
+  );
+
+  assert!(saved
+    .search(&engine, &items, "trending", &HashMap::new())
+    .is_err());
+}
+
+#[test]
+fn test_saved_queries_round_trip() {
+  let saved = saved();
+  let json = serde_json::to_string(&saved).unwrap();
+  assert_eq!(SavedQueries::from_json(&json).unwrap(), saved);
+
+  let value: Value = serde_json::from_str(&json).unwrap();
+  assert_eq!(value["about"]["query"]["text"], "{{subject}} edition");
+  assert_eq!(value["new-this-week"]["defaults"]["topic"], "news");
+  assert!(value["about"].get("defaults").is_none());
+
+  let error = SavedQueries::from_json(r#"{ "about": { "text": "rust" } }"#).unwrap_err();
+  assert!(error.starts_with("Invalid saved queries:"));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_saved_queries_from_yaml() {
+  let saved = SavedQueries::from_yaml(
+    r#"
+trending:
+  description: Popular tags
+  query:
+    tags: ["{{tag}}"]
+    options:
+      limit: "{{limit}}"
+  defaults:
+    limit: 2
+"#,
+  )
+  .unwrap();
+  let query = saved
+    .query("trending", &params(json!({ "tag": "rust" })))
+    .unwrap();
+  assert_eq!(query.tags, Some(vec!["rust".to_string()]));
+  assert_eq!(query.options.limit, 2);
+}
@@ -0,0 +1,119 @@
+use searus::prelude::*;
+use searus::scoring::{DecayFunction, FieldValueBoost, RecencyDecay, ValueScaling};
+use serde_json::{json, Value};
+
+/// Returns the score of each result, by item index.
+fn scores_by_id(results: &[SearusMatch<Value>]) -> Vec<(usize, f32)> {
+  let mut scores: Vec<(usize, f32)> = results.iter().map(|m| (m.id, m.score)).collect();
+  scores.sort_by_key(|(id, _)| *id);
+  scores
+}
+
+fn title_engine(builder: SearusEngineBuilder<Value>) -> SearusEngine<Value> {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25())
+    .build();
+  builder.with(Box::new(SemanticSearch::new(rules))).build()
+}
+
+#[test]
+fn test_recency_decay_ranks_newer_items_first() {
+  // Every post has the same title, so the same text score.
+  let posts: Vec<Value> = [
+    json!("2024-01-01"),
+    json!("2024-05-14T12:00:00Z"),
+    json!("2024-04-01"),
+    Value::Null,
+    json!("last tuesday"),
+  ]
+  .into_iter()
+  .map(|date| json!({ "title": "Rust release notes", "date": date }))
+  .chain([json!({ "title": "Python release notes" })])
+  .collect();
+
+  let decay = RecencyDecay::new("date", 30.0 * 24.0 * 3600.0)
+    .function(DecayFunction::Exponential)
+    .origin_from_str("2024-05-15T00:00:00Z");
+  let engine = title_engine(SearusEngine::builder().score_modifier(Box::new(decay)));
+
+  let results = engine.search(&posts, &Query::builder().text("rust").build());
+  let ids: Vec<usize> = results.iter().map(|m| m.id).collect();
+  // Undated items keep their score, and outrank the decayed ones.
+  assert_eq!(&ids[2..], [1, 2, 0]);
+
+  let scores = scores_by_id(&results);
+  assert!(scores[1].1 > scores[2].1 && scores[2].1 > scores[0].1);
+  // Missing and unparsable dates get the neutral multiplier.
+  assert_eq!(scores[3].1, 1.0);
+  assert_eq!(scores[4].1, 1.0);
+}
+
+#[test]
+fn test_field_value_boost_ignores_missing_and_non_numeric_values() {
+  let repos: Vec<Value> = [json!(100), Value::Null, json!("many"), json!(-5), json!(3)]
+    .into_iter()
+    .map(|stars| json!({ "title": "Rust search engine", "stars": stars }))
+    .collect();
+  let query = Query::builder().text("rust").build();
+
+  let boost = FieldValueBoost::new("stars").scaling(ValueScaling::Linear);
+  let engine = title_engine(SearusEngine::builder().score_modifier(Box::new(boost)));
+  let scores = scores_by_id(&engine.search(&repos, &query));
+  assert_eq!(scores[0].1, 101.0);
+  assert_eq!(scores[4].1, 4.0);
+  // Missing, non-numeric, and negative values don't boost.
+  assert_eq!(scores[1].1, 1.0);
+  assert_eq!(scores[2].1, 1.0);
+  assert_eq!(scores[3].1, 1.0);
+
+  // Items without a numeric value can use a default instead.
+  let boost = FieldValueBoost::new("stars")
+    .scaling(ValueScaling::Linear)
+    .missing(9.0);
+  let engine = title_engine(SearusEngine::builder().score_modifier(Box::new(boost)));
+  let scores = scores_by_id(&engine.search(&repos, &query));
+  assert_eq!(scores[1].1, 10.0);
+  assert_eq!(scores[2].1, 10.0);
+  assert_eq!(scores[3].1, 1.0);
+}
+
+#[test]
+fn test_score_functions_per_engine_and_per_search() {
+  let products: Vec<Value> = [("Phone", true, false), ("Phone", false, true)]
+    .into_iter()
+    .map(|(name, in_stock, sponsored)| {
+      json!({ "title": name, "in_stock": in_stock, "sponsored": sponsored })
+    })
+    .chain([json!({ "title": "Laptop" })])
+    .collect();
+  let query = Query::builder().text("phone").build();
+
+  // The engine's function applies to every search.
+  let engine = title_engine(SearusEngine::builder().score_fn(|item: &Value, score| {
+    if item["in_stock"] == true {
+      score * 2.0
+    } else {
+      score
+    }
+  }));
+  assert_eq!(
+    scores_by_id(&engine.search(&products, &query)),
+    [(0, 2.0), (1, 1.0)]
+  );
+
+  // A per-search function applies to that search only, after the engine's.
+  let pin_sponsored = |item: &Value, score: f32| {
+    if item["sponsored"] == true {
+      score + 1.5
+    } else {
+      score + 1.0
+    }
+  };
+  let results = engine.search_with_modifier(&products, &query, &pin_sponsored);
+  assert_eq!(scores_by_id(&results), [(0, 3.0), (1, 2.5)]);
+  assert_eq!(results[0].id, 0);
+  assert_eq!(
+    scores_by_id(&engine.search(&products, &query)),
+    [(0, 2.0), (1, 1.0)]
+  );
+}
@@ -0,0 +1,186 @@
+#![cfg(feature = "server")]
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::{Extension, Router};
+use searus::extensions::AccessControlExtension;
+use searus::prelude::*;
+use searus::server;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, RwLock};
+use tower::ServiceExt;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Post {
+  title: String,
+}
+
+fn app() -> Router {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::tokenized())
+    .build();
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .build();
+  let indexed: IndexedSearusEngine<Post> =
+    IndexedSearusEngine::new(engine).with_completion_fields(["title"]);
+  server::router(Arc::new(RwLock::new(indexed)))
+}
+
+/// Sends a request and returns its status and JSON body (null if empty).
+async fn send(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+  let request = Request::builder()
+    .method(method)
+    .uri(uri)
+    .header("content-type", "application/json");
+  let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+  let response = app
+    .clone()
+    .oneshot(request.body(body).unwrap())
+    .await
+    .unwrap();
+
+  let status = response.status();
+  let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+  let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+  (status, body)
+}
+
+#[tokio::test]
+async fn test_index_and_search() {
+  let app = app();
+
+  for (id, title) in [
+    ("1", "Rust ownership"),
+    ("2", "Rust traits"),
+    ("3", "Python"),
+  ] {
+    let uri = format!("/index/{}", id);
+    let (status, _) = send(&app, Method::PUT, &uri, Some(json!({ "title": title }))).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+  }
+
+  let (status, body) = send(&app, Method::GET, "/index/2", None).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body, json!({ "title": "Rust traits" }));
+
+  let query = json!({ "text": "rust", "options": { "limit": 1 } });
+  let (status, body) = send(&app, Method::POST, "/search", Some(query.clone())).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["total_matches"], 2);
+  assert_eq!(body["results"].as_array().unwrap().len(), 1);
+
+  let (status, _) = send(&app, Method::DELETE, "/index/1", None).await;
+  assert_eq!(status, StatusCode::NO_CONTENT);
+  let (status, _) = send(&app, Method::GET, "/index/1", None).await;
+  assert_eq!(status, StatusCode::NOT_FOUND);
+  let (_, body) = send(&app, Method::POST, "/search", Some(query)).await;
+  assert_eq!(body["results"][0]["item"]["title"], "Rust traits");
+}
+
+#[tokio::test]
+async fn test_suggest() {
+  let app = app();
+  send(
+    &app,
+    Method::PUT,
+    "/index/1",
+    Some(json!({ "title": "Rust ownership" })),
+  )
+  .await;
+  send(
+    &app,
+    Method::PUT,
+    "/index/2",
+    Some(json!({ "title": "Ruby" })),
+  )
+  .await;
+
+  let (status, body) = send(&app, Method::GET, "/suggest?q=ru&limit=1", None).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body.as_array().unwrap().len(), 1);
+
+  // Replaced items no longer complete their old values.
+  send(
+    &app,
+    Method::PUT,
+    "/index/2",
+    Some(json!({ "title": "Go" })),
+  )
+  .await;
+  let (_, body) = send(&app, Method::GET, "/suggest?q=rub", None).await;
+  assert_eq!(body, json!([]));
+}
+
+#[tokio::test]
+async fn test_rejects_invalid_items() {
+  let app = app();
+  let (status, _) = send(&app, Method::PUT, "/index/1", Some(json!({ "name": 1 }))).await;
+  assert!(status.is_client_error());
+}
+
+/// A router over a private note of alice's and a public guide of bob's,
+/// which only their owners and admins may see when private.
+fn restricted_app() -> Router {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::tokenized())
+    .build();
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .with_extension(Box::new(
+      AccessControlExtension::owner_or_public("owner", "visibility", "public").bypass_role("admin"),
+    ))
+    .build();
+  let mut indexed: IndexedSearusEngine<Value> = IndexedSearusEngine::new(engine);
+  let notes = json!({ "title": "Rust notes", "owner": "alice", "visibility": "private" });
+  let guide = json!({ "title": "Rust guide", "owner": "bob", "visibility": "public" });
+  indexed.put("1", notes).unwrap();
+  indexed.put("2", guide).unwrap();
+  server::router(Arc::new(RwLock::new(indexed)))
+}
+
+#[tokio::test]
+async fn test_searches_ignore_principals_in_the_query() {
+  let app = restricted_app();
+
+  // Clients cannot claim an identity or a role in the query.
+  let query = json!({
+    "text": "rust",
+    "principal": { "id": "alice", "roles": ["admin"] },
+  });
+  let (status, body) = send(&app, Method::POST, "/search", Some(query.clone())).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["total_matches"], 1);
+  assert_eq!(body["results"][0]["item"]["title"], "Rust guide");
+
+  // The principal set by the authentication middleware is used instead.
+  let app = app.layer(Extension(Principal::new("alice")));
+  let (_, body) = send(&app, Method::POST, "/search", Some(query)).await;
+  assert_eq!(body["total_matches"], 2);
+}
+
+#[tokio::test]
+async fn test_items_are_fetched_on_behalf_of_the_principal() {
+  let app = restricted_app();
+
+  // Hidden items are reported as missing.
+  let (status, _) = send(&app, Method::GET, "/index/1", None).await;
+  assert_eq!(status, StatusCode::NOT_FOUND);
+  let (status, body) = send(&app, Method::GET, "/index/2", None).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["title"], "Rust guide");
+
+  let bob = app.clone().layer(Extension(Principal::new("bob")));
+  let (status, _) = send(&bob, Method::GET, "/index/1", None).await;
+  assert_eq!(status, StatusCode::NOT_FOUND);
+
+  let alice = app.clone().layer(Extension(Principal::new("alice")));
+  let (status, body) = send(&alice, Method::GET, "/index/1", None).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["title"], "Rust notes");
+
+  let admin = app.layer(Extension(Principal::new("carol").with_role("admin")));
+  let (status, _) = send(&admin, Method::GET, "/index/1", None).await;
+  assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,70 @@
+use searus::prelude::*;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// An item that cannot be cloned.
+#[derive(Debug, Serialize)]
+struct Doc {
+  title: String,
+  tags: Vec<String>,
+}
+
+fn docs() -> Vec<Doc> {
+  [
+    ("Rust ownership", "rust"),
+    ("Async Rust with Tokio", "async"),
+    ("Python typing", "python"),
+  ]
+  .into_iter()
+  .map(|(title, tag)| Doc {
+    title: title.into(),
+    tags: vec![tag.into()],
+  })
+  .collect()
+}
+
+fn engine() -> SearusEngine<Arc<Doc>> {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25())
+    .build();
+  SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .with(Box::new(TaggedSearch::new()))
+    .with(Box::new(FuzzySearch::new(vec!["title".into()])))
+    .build()
+}
+
+fn query() -> Query {
+  Query::builder()
+    .text("ownership")
+    .tags(vec!["rust".into()])
+    .build()
+}
+
+#[test]
+fn test_matches_share_the_searched_items() {
+  let items: Vec<Arc<Doc>> = docs().into_iter().map(Arc::new).collect();
+  let engine = engine();
+
+  let response = engine.search_response(&items, &query());
+  assert_eq!(response.results[0].item.title, "Rust ownership");
+  assert!(Arc::ptr_eq(&response.results[0].item, &items[0]));
+
+  // The matches serialize like the items.
+  let json = serde_json::to_value(&response.results[0]).unwrap();
+  assert_eq!(json["item"]["tags"], serde_json::json!(["rust"]));
+}
+
+#[test]
+fn test_shared_matches_map_to_owned_items() {
+  let items: Vec<Arc<Doc>> = docs().into_iter().map(Arc::new).collect();
+  let engine = engine();
+
+  let titles: Vec<SearusMatch<String>> = engine
+    .search(&items, &query())
+    .into_iter()
+    .map(|m| m.map_item(|doc| doc.title.clone()))
+    .collect();
+  assert_eq!(titles[0].item, "Rust ownership");
+  assert_eq!(titles[0].id, 0);
+}
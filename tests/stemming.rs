@@ -0,0 +1,63 @@
+#![cfg(feature = "stemming")]
+
+use searus::prelude::*;
+use searus::searchers::stemmer::{Language, Stemmer};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Post {
+  title: String,
+}
+
+fn posts() -> Vec<Post> {
+  vec![
+    Post {
+      title: "Running a search engine".to_string(),
+    },
+    Post {
+      title: "Cooking for beginners".to_string(),
+    },
+  ]
+}
+
+fn engine(stemmer: Option<Stemmer>) -> SearusEngine<Post> {
+  let mut searcher = SemanticSearch::new(
+    SemanticRules::builder()
+      .field("title", FieldRule::tokenized())
+      .build(),
+  );
+  if let Some(stemmer) = stemmer {
+    searcher = searcher.with_stemmer(stemmer);
+  }
+  SearusEngine::builder().with(Box::new(searcher)).build()
+}
+
+#[test]
+fn test_stemming_matches_variants() {
+  let query = Query::builder().text("run searches").build();
+
+  assert!(engine(None).search(&posts(), &query).is_empty());
+
+  let results = engine(Some(Stemmer::new(Language::English))).search(&posts(), &query);
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.title, "Running a search engine");
+}
+
+#[test]
+fn test_stemmed_highlights_cover_original_words() {
+  let query = Query::builder()
+    .text("searches")
+    .options(SearchOptions::default().highlight(true))
+    .build();
+
+  let results = engine(Stemmer::for_language("en")).search(&posts(), &query);
+  let html = highlight_text(
+    &posts()[0].title,
+    "title",
+    &results[0].highlights,
+    "<b>",
+    "</b>",
+  );
+
+  assert_eq!(html, "Running a <b>search</b> engine");
+}
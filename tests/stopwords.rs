@@ -0,0 +1,63 @@
+use searus::prelude::*;
+use searus::searchers::stopwords::StopWords;
+use serde_json::{json, Value};
+
+fn posts() -> Vec<Value> {
+  vec![
+    json!({ "title": "Rust guide" }),
+    json!({ "title": "The Rust guide" }),
+    json!({ "title": "Python typing" }),
+    json!({ "title": "Go channels" }),
+  ]
+}
+
+/// Returns the raw BM25 score of each matched post, by index.
+fn scores(searcher: &SemanticSearch, text: &str) -> Vec<(usize, f32)> {
+  let items = posts();
+  let context = SearchContext::new(&items);
+  let query = Query::builder().text(text).build();
+  let mut scores: Vec<(usize, f32)> = searcher
+    .search(&context, &query)
+    .into_iter()
+    .map(|m| (m.id, m.score))
+    .collect();
+  scores.sort_by_key(|(id, _)| *id);
+  scores
+}
+
+fn searcher() -> SemanticSearch {
+  SemanticSearch::new(
+    SemanticRules::builder()
+      .field("title", FieldRule::bm25())
+      .build(),
+  )
+}
+
+#[test]
+fn test_stopwords_are_dropped_from_queries() {
+  let plain = searcher();
+  let filtered = searcher().with_stopwords(StopWords::english());
+
+  // Stopwords alone match nothing.
+  assert_eq!(scores(&plain, "the").len(), 1);
+  assert!(scores(&filtered, "the").is_empty());
+
+  // A query scores as if its stopwords were not there.
+  assert_eq!(
+    scores(&filtered, "the guide to rust"),
+    scores(&filtered, "guide rust")
+  );
+}
+
+#[test]
+fn test_stopwords_are_dropped_from_documents() {
+  // "The Rust guide" is longer than "Rust guide", so BM25 ranks it lower.
+  let plain = scores(&searcher(), "rust");
+  assert_eq!(plain.len(), 2);
+  assert!(plain[0].1 > plain[1].1);
+
+  // Without "the", both titles have the same length and score the same.
+  let filtered = scores(&searcher().with_stopwords(StopWords::english()), "rust");
+  assert_eq!(filtered.len(), 2);
+  assert_eq!(filtered[0].1, filtered[1].1);
+}
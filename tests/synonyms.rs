@@ -0,0 +1,140 @@
+use searus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Listing {
+  title: String,
+}
+
+#[test]
+fn test_weighted_synonyms() {
+  let listings = vec![
+    Listing {
+      title: "used automobile for sale".to_string(),
+    },
+    Listing {
+      title: "used car for sale".to_string(),
+    },
+    Listing {
+      title: "used bike for sale".to_string(),
+    },
+  ];
+
+  let synonyms = SynonymMap::from_text("car => automobile:0.5").unwrap();
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      SemanticSearch::new(
+        SemanticRules::builder()
+          .field("title", FieldRule::tokenized())
+          .build(),
+      )
+      .with_synonyms(synonyms),
+    ))
+    .build();
+
+  let results = engine.search(&listings, &Query::builder().text("car").build());
+
+  assert_eq!(results.len(), 2);
+  assert_eq!(results[0].item.title, "used car for sale");
+  assert_eq!(results[1].item.title, "used automobile for sale");
+}
+
+fn listings(titles: &[&str]) -> Vec<Listing> {
+  titles
+    .iter()
+    .map(|title| Listing {
+      title: title.to_string(),
+    })
+    .collect()
+}
+
+#[test]
+fn test_multi_word_synonyms_match_as_phrases() {
+  let listings = listings(&[
+    "intro to machine learning",
+    "learning to fix a washing machine",
+    "gardening for beginners",
+  ]);
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      SemanticSearch::new(
+        SemanticRules::builder()
+          .field("title", FieldRule::bm25())
+          .build(),
+      )
+      .with_synonyms(SynonymMap::new().with("ML", "Machine Learning", 0.8)),
+    ))
+    .build();
+
+  let results = engine.search(&listings, &Query::builder().text("ml").build());
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.title, "intro to machine learning");
+
+  // Terms of several words match as phrases too.
+  let synonyms = SynonymMap::new().with("machine learning", "ml", 0.8);
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      SemanticSearch::new(
+        SemanticRules::builder()
+          .field("title", FieldRule::bm25())
+          .build(),
+      )
+      .with_synonyms(synonyms),
+    ))
+    .build();
+  let listings = vec![Listing {
+    title: "ML for everyone".to_string(),
+  }];
+  let results = engine.search(
+    &listings,
+    &Query::builder().text("machine learning").build(),
+  );
+  assert_eq!(results.len(), 1);
+  let results = engine.search(
+    &listings,
+    &Query::builder().text("learning machine").build(),
+  );
+  assert!(results.is_empty());
+}
+
+#[cfg(feature = "stemming")]
+#[test]
+fn test_synonyms_are_stemmed_like_the_fields() {
+  use searus::searchers::stemmer::{Language, Stemmer};
+
+  let listings = listings(&["jogs around the park", "running shoes", "swimming pool"]);
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      SemanticSearch::new(
+        SemanticRules::builder()
+          .field("title", FieldRule::tokenized())
+          .build(),
+      )
+      .with_synonyms(SynonymMap::new().with("running", "jogging", 0.5))
+      .with_stemmer(Stemmer::new(Language::English)),
+    ))
+    .build();
+
+  let results = engine.search(&listings, &Query::builder().text("runs").build());
+  assert_eq!(results.len(), 2);
+  assert_eq!(results[0].item.title, "running shoes");
+  assert_eq!(results[1].item.title, "jogs around the park");
+}
+
+#[test]
+fn test_weights_are_checked() {
+  let synonyms = SynonymMap::new()
+    .with("car", "automobile", 2.0)
+    .with("car", "wagon", 0.0)
+    .with("car", "cart", f32::NAN)
+    .with_group(&["tv", "television"], -1.0);
+  assert_eq!(synonyms.get("car").unwrap().len(), 1);
+  assert_eq!(synonyms.get("car").unwrap()["automobile"], 1.0);
+  assert!(synonyms.get("tv").is_none());
+
+  let synonyms: SynonymMap =
+    serde_json::from_str(r#"{ "entries": { "car": { "automobile": 5.0, "wagon": -1.0 } } }"#)
+      .unwrap();
+  assert_eq!(synonyms, SynonymMap::new().with("car", "automobile", 1.0));
+}
@@ -0,0 +1,469 @@
+use searus::prelude::*;
+use searus::searchers::tagged::{
+  CooccurrenceMeasure, CooccurrenceOptions, TagNode, TagNormalizer, TagRelationshipTree,
+  TagTreeIssue,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Post {
+  title: String,
+  tags: Vec<String>,
+}
+
+fn posts() -> Vec<Post> {
+  [
+    ("Async Rust", vec!["rust", "async"]),
+    ("Rust basics", vec!["rust"]),
+    ("Tokio in depth", vec!["rust", "tokio"]),
+    ("Async Python", vec!["python", "async"]),
+  ]
+  .into_iter()
+  .map(|(title, tags)| Post {
+    title: title.to_string(),
+    tags: tags.into_iter().map(String::from).collect(),
+  })
+  .collect()
+}
+
+fn titles(results: Vec<SearusMatch<Post>>) -> Vec<String> {
+  let mut titles: Vec<String> = results.into_iter().map(|r| r.item.title).collect();
+  titles.sort();
+  titles
+}
+
+fn tags(tags: &[&str]) -> Vec<String> {
+  tags.iter().map(|tag| tag.to_string()).collect()
+}
+
+#[test]
+fn test_all_mode_requires_every_tag() {
+  let engine = SearusEngine::builder()
+    .with(Box::new(TaggedSearch::new()))
+    .build();
+
+  let any = Query::builder().tags(tags(&["Rust", "async"])).build();
+  assert_eq!(engine.search(&posts(), &any).len(), 4);
+
+  let all = Query::builder()
+    .tags(tags(&["Rust", "async"]))
+    .options(SearchOptions::default().tag_mode(TagMode::All))
+    .build();
+  assert_eq!(titles(engine.search(&posts(), &all)), vec!["Async Rust"]);
+}
+
+#[test]
+fn test_all_mode_accepts_related_tags() {
+  let trt = TagRelationshipTree::new(vec![TagNode {
+    tag: "async".to_string(),
+    relationships: HashMap::from([("tokio".to_string(), 0.9)]),
+  }]);
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      TaggedSearch::new().with_trt(trt).with_mode(TagMode::All),
+    ))
+    .build();
+
+  let query = Query::builder().tags(tags(&["rust", "async"])).build();
+  assert_eq!(titles(engine.search(&posts(), &query)), vec!["Async Rust"]);
+
+  // With expansion, "tokio" stands in for "async".
+  let query = Query::builder()
+    .tags(tags(&["rust", "async"]))
+    .with_trt(1)
+    .build();
+  assert_eq!(
+    titles(engine.search(&posts(), &query)),
+    vec!["Async Rust", "Tokio in depth"]
+  );
+}
+
+#[test]
+fn test_excluded_tags_override_expansion() {
+  let trt = TagRelationshipTree::new(vec![TagNode {
+    tag: "rust".to_string(),
+    relationships: HashMap::from([("tokio".to_string(), 0.9)]),
+  }]);
+  let engine = SearusEngine::builder()
+    .with(Box::new(TaggedSearch::new().with_trt(trt)))
+    .build();
+
+  let query = Query::builder()
+    .tags(tags(&["rust"]))
+    .exclude_tags(["ASYNC"])
+    .with_trt(1)
+    .build();
+  assert_eq!(
+    titles(engine.search(&posts(), &query)),
+    vec!["Rust basics", "Tokio in depth"]
+  );
+
+  // An excluded tag is not brought back by a related query tag.
+  let query = Query::builder()
+    .tags(tags(&["rust"]))
+    .exclude_tags(["tokio"])
+    .with_trt(1)
+    .build();
+  assert_eq!(
+    titles(engine.search(&posts(), &query)),
+    vec!["Async Rust", "Rust basics"]
+  );
+}
+
+#[test]
+fn test_tag_fields_are_not_double_counted() {
+  #[derive(Debug, Clone, Serialize, Deserialize)]
+  struct Article {
+    categories: Vec<String>,
+    keywords: Vec<String>,
+  }
+
+  let articles = vec![Article {
+    categories: tags(&["rust"]),
+    keywords: tags(&["Rust", "async"]),
+  }];
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      TaggedSearch::with_field("categories")
+        .with_tag_field("categories", 2.0)
+        .with_tag_field("keywords", 0.5),
+    ))
+    .build();
+
+  let query = Query::builder().tags(tags(&["rust", "async"])).build();
+  let results = engine.search(&articles, &query);
+  assert_eq!(results.len(), 1);
+
+  // "rust" counts once, in the heavier field.
+  assert_eq!(results[0].field_scores["categories"], 1.0);
+  assert_eq!(results[0].field_scores["keywords"], 0.25);
+  match &results[0].details[0] {
+    SearchDetail::Tag {
+      matched_tags,
+      total_tags,
+    } => {
+      assert_eq!(matched_tags, &tags(&["rust", "async"]));
+      assert_eq!(*total_tags, 2);
+    }
+    detail => panic!("unexpected detail {:?}", detail),
+  }
+}
+
+#[test]
+fn test_weighted_item_tags() {
+  let items = vec![
+    serde_json::json!({"id": 1, "tags": [{"tag": "rust", "weight": 0.4}, "web"]}),
+    serde_json::json!({"id": 2, "tags": {"rust": 0.9, "cli": 0.7}}),
+    serde_json::json!({"id": 3, "tags": [{"tag": "Rust"}]}),
+  ];
+  let engine = SearusEngine::builder()
+    .with(Box::new(TaggedSearch::new()))
+    .build();
+
+  let query = Query::builder()
+    .tags(tags(&["rust"]))
+    .options(SearchOptions::default().explain(true))
+    .build();
+  let results = engine.search(&items, &query);
+
+  let ids: Vec<u64> = results
+    .iter()
+    .map(|result| result.item["id"].as_u64().unwrap())
+    .collect();
+  assert_eq!(ids, vec![3, 2, 1]);
+  assert_eq!(results[1].field_scores["tags"], 0.9);
+  assert_eq!(results[2].field_scores["tags"], 0.4);
+  assert!(results[2]
+    .explain()
+    .unwrap()
+    .to_string()
+    .contains("times tag weight"));
+}
+
+#[test]
+fn test_path_tags_decay_with_depth() {
+  let items = vec![
+    serde_json::json!({"id": 1, "tags": ["Lang/Rust/Async/Tokio"]}),
+    serde_json::json!({"id": 2, "tags": ["lang/rust/async"]}),
+    serde_json::json!({"id": 3, "tags": ["lang/rustic"]}),
+  ];
+  let engine = SearusEngine::builder()
+    .with(Box::new(TaggedSearch::new().with_path_tags('/', 0.5)))
+    .build();
+
+  let query = Query::builder().tags(tags(&["lang/rust"])).build();
+  let results = engine.search(&items, &query);
+
+  assert_eq!(results.len(), 2);
+  assert_eq!(results[0].item["id"], 2);
+  assert_eq!(results[0].field_scores["tags"], 0.5);
+  assert_eq!(results[1].field_scores["tags"], 0.25);
+
+  // Without path tags, only exact tags match.
+  let engine = SearusEngine::builder()
+    .with(Box::new(TaggedSearch::new()))
+    .build();
+  assert!(engine.search(&items, &query).is_empty());
+}
+
+#[test]
+fn test_normalizer_folds_variants_and_aliases() {
+  let posts: Vec<Post> = [
+    ("Closures", vec!["JavaScript"]),
+    ("Backprop", vec!["Machine Learning"]),
+    ("Transformers", vec!["machine_learning", "ML"]),
+    ("Tokio", vec!["Lang / Rust / Async"]),
+  ]
+  .into_iter()
+  .map(|(title, tags)| Post {
+    title: title.to_string(),
+    tags: tags.into_iter().map(String::from).collect(),
+  })
+  .collect();
+
+  let normalizer = TagNormalizer::new()
+    .with_slugs()
+    .with_aliases([("js", "javascript"), ("ml", "machine learning")]);
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      TaggedSearch::new()
+        .with_normalizer(normalizer)
+        .with_path_tags('/', 0.5),
+    ))
+    .build();
+
+  let query = Query::builder().tags(tags(&["js"])).build();
+  assert_eq!(titles(engine.search(&posts, &query)), vec!["Closures"]);
+
+  // Variants of a tag in the same item count once.
+  let query = Query::builder().tags(tags(&["machine-learning"])).build();
+  let results = engine.search(&posts, &query);
+  assert_eq!(titles(results.clone()), vec!["Backprop", "Transformers"]);
+  assert!(results.iter().all(|r| r.field_scores["tags"] == 1.0));
+
+  let query = Query::builder().tags(tags(&["lang/rust"])).build();
+  assert_eq!(titles(engine.search(&posts, &query)), vec!["Tokio"]);
+
+  let query = Query::builder()
+    .tags(tags(&["ml"]))
+    .exclude_tags(["Machine_Learning"])
+    .build();
+  assert!(engine.search(&posts, &query).is_empty());
+}
+
+#[test]
+fn test_trt_round_trips_through_json() {
+  let trt = TagRelationshipTree::from_json(
+    r#"{
+      "async": { "tokio": 0.9 },
+      "python": { "async": 0.5 }
+    }"#,
+  )
+  .unwrap();
+  assert_eq!(trt.unknown_tags(), vec!["tokio"]);
+
+  let json = serde_json::to_string(&trt).unwrap();
+  let reloaded = TagRelationshipTree::from_json(&json).unwrap();
+  assert_eq!(
+    reloaded.expand_tags(&tags(&["python"]), 2),
+    trt.expand_tags(&tags(&["python"]), 2)
+  );
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(TaggedSearch::new().with_trt(reloaded)))
+    .build();
+  let query = Query::builder()
+    .tags(tags(&["async"]))
+    .options(SearchOptions::default().trt_depth(1))
+    .build();
+  assert_eq!(
+    titles(engine.search(&posts(), &query)),
+    vec!["Async Python", "Async Rust", "Tokio in depth"]
+  );
+
+  for invalid in [
+    r#"{ "async": { "tokio": 0 } }"#,
+    r#"{ "async": { "tokio": -1.5 } }"#,
+    r#"{ "": { "tokio": 0.5 } }"#,
+    r#"{ "async": ["tokio"] }"#,
+  ] {
+    assert!(
+      TagRelationshipTree::from_json(invalid).is_err(),
+      "{}",
+      invalid
+    );
+  }
+}
+
+#[test]
+fn test_trt_from_corpus_measures() {
+  let corpus: Vec<Post> = [
+    vec!["rust", "systems"],
+    vec!["Rust", "systems", "async"],
+    vec!["rust", "async"],
+    vec!["python", "async"],
+    vec!["python", "data"],
+    vec!["python", "data", "async"],
+  ]
+  .into_iter()
+  .map(|tags| Post {
+    title: String::new(),
+    tags: tags.into_iter().map(String::from).collect(),
+  })
+  .collect();
+
+  let jaccard = TagRelationshipTree::from_corpus(&corpus, "tags", CooccurrenceOptions::new());
+  let expanded = jaccard.expand_tags(&tags(&["rust"]), 1);
+  // 2 shared items out of 3 with either tag.
+  assert!((expanded["systems"] - 2.0 / 3.0).abs() < 1e-6);
+  // 2 shared items out of 5 with either tag.
+  assert!((expanded["async"] - 0.4).abs() < 1e-6);
+  assert!(!expanded.contains_key("python"));
+
+  // "async" is on most items, so it tells little about "rust" by PMI.
+  let pmi = TagRelationshipTree::from_corpus(
+    &corpus,
+    "tags",
+    CooccurrenceOptions::new().measure(CooccurrenceMeasure::Pmi),
+  );
+  let expanded = pmi.expand_tags(&tags(&["rust"]), 1);
+  assert!(expanded["systems"] > 0.5);
+  assert!(!expanded.contains_key("async"));
+
+  let top =
+    TagRelationshipTree::from_corpus(&corpus, "tags", CooccurrenceOptions::new().max_related(1));
+  let expanded = top.expand_tags(&tags(&["rust"]), 1);
+  assert_eq!(expanded.len(), 2);
+  assert!(expanded.contains_key("systems"));
+}
+
+#[test]
+fn test_trt_validate_reports_issues() {
+  let trt = TagRelationshipTree::default()
+    .with_symmetric_relationship("rust", "systems", 0.9)
+    .with_relationship("rust", "progamming", 0.8)
+    .with_relationship("async", "tokio", 1.5)
+    .with_relationship("tokio", "rust", 0.6);
+  assert_eq!(
+    trt.validate(),
+    vec![
+      TagTreeIssue::InvalidStrength {
+        tag: "async".to_string(),
+        related_tag: "tokio".to_string(),
+        strength: 1.5,
+      },
+      TagTreeIssue::Asymmetric {
+        tag: "async".to_string(),
+        related_tag: "tokio".to_string(),
+        strength: 1.5,
+        reverse_strength: None,
+      },
+      TagTreeIssue::Dangling {
+        tag: "progamming".to_string(),
+      },
+      TagTreeIssue::Asymmetric {
+        tag: "tokio".to_string(),
+        related_tag: "rust".to_string(),
+        strength: 0.6,
+        reverse_strength: None,
+      },
+    ]
+  );
+
+  let trt = TagRelationshipTree::default().with_symmetric_relationship("rust", "systems", 0.9);
+  assert!(trt.validate().is_empty());
+}
+
+#[test]
+fn test_trt_precompute_matches_traversal() {
+  let mut trt = TagRelationshipTree::default()
+    .with_symmetric_relationship("rust", "systems", 0.9)
+    .with_relationship("tokio", "Async", 0.8)
+    .with_relationship("async", "rust", 0.5)
+    .with_relationship("systems", "c", 0.7);
+  let queries = [tags(&["tokio"]), tags(&["Rust", "unknown"]), tags(&["c"])];
+  let uncached: Vec<_> = queries.iter().map(|q| trt.expand_tags(q, 2)).collect();
+
+  trt.precompute(2);
+  for (query, expected) in queries.iter().zip(&uncached) {
+    assert_eq!(&trt.expand_tags(query, 2), expected);
+  }
+  // Other depths are still traversed.
+  assert!(trt.expand_tags(&tags(&["tokio"]), 4).contains_key("c"));
+
+  let trt = trt.with_relationship("c", "systems", 0.7);
+  assert!(!trt.is_precomputed(2));
+}
+
+#[test]
+fn test_trt_to_dot_quotes_tags() {
+  let trt = TagRelationshipTree::default()
+    .with_symmetric_relationship("c++", "say \"hi\"", 1.0)
+    .with_relationship("a\\b", "c++", 0.25);
+  assert_eq!(
+    trt.edges(),
+    vec![
+      ("a\\b", "c++", 0.25),
+      ("c++", "say \"hi\"", 1.0),
+      ("say \"hi\"", "c++", 1.0)
+    ]
+  );
+  assert_eq!(
+    trt.to_dot(),
+    r#"digraph trt {
+  "a\\b" -> "c++" [label="0.25", penwidth=1.5];
+  "c++" -> "say \"hi\"" [label="1", penwidth=3.0];
+  "say \"hi\"" -> "c++" [label="1", penwidth=3.0];
+}
+"#
+  );
+}
+
+#[test]
+fn test_negative_relationships_demote() {
+  let trt = TagRelationshipTree::from_json(
+    r#"{
+      "async": { "tokio": 0.8, "beginner": -0.5 },
+      "beginner": { "basics": 1.0 }
+    }"#,
+  )
+  .unwrap();
+  let expanded = trt.expand_tags(&tags(&["async"]), 2);
+  assert_eq!(expanded["beginner"], -0.5);
+  assert!(!expanded.contains_key("basics"));
+
+  let posts: Vec<Post> = [
+    ("Async for beginners", vec!["async", "beginner"]),
+    ("Async internals", vec!["async"]),
+    ("First steps", vec!["beginner"]),
+    ("Tokio in depth", vec!["tokio"]),
+  ]
+  .into_iter()
+  .map(|(title, tags)| Post {
+    title: title.to_string(),
+    tags: tags.into_iter().map(String::from).collect(),
+  })
+  .collect();
+
+  let engine = SearusEngine::builder()
+    .with(Box::new(TaggedSearch::new().with_trt(trt)))
+    .build();
+  let query = Query::builder()
+    .tags(tags(&["async"]))
+    .options(SearchOptions::default().trt_depth(1).explain(true))
+    .build();
+  let results = engine.search(&posts, &query);
+
+  let ranked: Vec<&str> = results.iter().map(|r| r.item.title.as_str()).collect();
+  assert_eq!(
+    ranked,
+    vec!["Async internals", "Tokio in depth", "Async for beginners"]
+  );
+  assert_eq!(results[2].field_scores["tags"], 0.5);
+  assert!(results[2]
+    .explain()
+    .unwrap()
+    .to_string()
+    .contains("1 demoted tags"));
+}
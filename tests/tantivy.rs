@@ -0,0 +1,151 @@
+#![cfg(feature = "tantivy")]
+
+use searus::filter::{CompareOp, FilterExpr, FilterValue, StringOptions};
+use searus::prelude::*;
+use searus::searchers::TantivySearch;
+use serde::Serialize;
+use tantivy::schema::{Schema, FAST, INDEXED, STORED, STRING, TEXT};
+use tantivy::{doc, Index};
+
+#[derive(Debug, Clone, Serialize)]
+struct Book {
+  id: u64,
+  title: String,
+  category: String,
+  price: f64,
+  tags: Vec<String>,
+}
+
+fn books() -> Vec<Book> {
+  [
+    (1, "Programming Rust", "tech", 40.0, "rust"),
+    (2, "Rust in Action", "tech", 25.0, "rust"),
+    (3, "The Rust Belt", "history", 15.0, "travel"),
+    (4, "Python Crash Course", "tech", 30.0, "python"),
+  ]
+  .into_iter()
+  .map(|(id, title, category, price, tag)| Book {
+    id,
+    title: title.to_string(),
+    category: category.to_string(),
+    price,
+    tags: vec![tag.to_string()],
+  })
+  .collect()
+}
+
+/// Indexes the books in a schema with typed fields, as an application would.
+fn searcher(books: &[Book]) -> TantivySearch {
+  let mut schema = Schema::builder();
+  let id = schema.add_u64_field("id", INDEXED | STORED);
+  let title = schema.add_text_field("title", TEXT);
+  let category = schema.add_text_field("category", STRING);
+  let price = schema.add_f64_field("price", INDEXED | FAST);
+  let index = Index::create_in_ram(schema.build());
+
+  let mut writer = index.writer(15_000_000).unwrap();
+  for book in books {
+    writer
+      .add_document(doc!(
+        id => book.id,
+        title => book.title.clone(),
+        category => book.category.clone(),
+        price => book.price,
+      ))
+      .unwrap();
+  }
+  writer.commit().unwrap();
+
+  TantivySearch::new(index, "id").unwrap()
+}
+
+fn compare(field: &str, op: CompareOp, value: impl Into<FilterValue>) -> FilterExpr {
+  FilterExpr::Compare {
+    field: field.to_string(),
+    op,
+    value: value.into(),
+    options: StringOptions::default(),
+  }
+}
+
+fn titles(results: &[SearusMatch<Book>]) -> Vec<&str> {
+  results.iter().map(|m| m.item.title.as_str()).collect()
+}
+
+#[test]
+fn test_text_queries_use_tantivy() {
+  let books = books();
+  let engine = SearusEngine::builder()
+    .with(Box::new(searcher(&books)))
+    .build();
+
+  let results = engine.search(&books, &Query::builder().text("rust").build());
+  assert_eq!(results.len(), 3);
+
+  // Tantivy's query syntax is available.
+  let results = engine.search(&books, &Query::builder().text("rust -belt").build());
+  assert_eq!(results.len(), 2);
+  assert!(!titles(&results).contains(&"The Rust Belt"));
+
+  assert!(engine
+    .search(&books, &Query::builder().text("").build())
+    .is_empty());
+}
+
+#[test]
+fn test_filters_are_pushed_down_and_checked() {
+  let books = books();
+  // With one hit fetched, only a pushed-down filter leaves the right one.
+  let engine = SearusEngine::builder()
+    .with(Box::new(searcher(&books).with_limit(1)))
+    .build();
+
+  let cheap_tech = FilterExpr::And(vec![
+    compare("category", CompareOp::Eq, "tech"),
+    compare("price", CompareOp::Lt, 30.0),
+  ]);
+  let query = Query::builder().text("rust").filters(cheap_tech).build();
+  assert_eq!(titles(&engine.search(&books, &query)), ["Rust in Action"]);
+
+  // Filters Tantivy can't run are still applied to the matched items.
+  let engine = SearusEngine::builder()
+    .with(Box::new(searcher(&books)))
+    .build();
+  let not_history = FilterExpr::Not(Box::new(compare("category", CompareOp::Eq, "history")));
+  let query = Query::builder().text("rust").filters(not_history).build();
+  let results = engine.search(&books, &query);
+  assert_eq!(results.len(), 2);
+  assert!(!titles(&results).contains(&"The Rust Belt"));
+}
+
+#[test]
+fn test_merges_with_other_searchers() {
+  let books = books();
+  let engine = SearusEngine::builder()
+    .with(Box::new(searcher(&books)))
+    .with(Box::new(TaggedSearch::new()))
+    .build();
+
+  let query = Query::builder()
+    .text("python")
+    .tags(vec!["rust".to_string()])
+    .build();
+  assert_eq!(engine.search(&books, &query).len(), 3);
+
+  // Hits for items outside the searched slice are skipped.
+  let subset = &books[..1];
+  let results = engine.search(subset, &Query::builder().text("rust").build());
+  assert_eq!(titles(&results), ["Programming Rust"]);
+}
+
+#[test]
+fn test_in_ram_index() {
+  let books = books();
+  let searcher = TantivySearch::in_ram(&books, "id", &["title"]).unwrap();
+  let engine = SearusEngine::builder().with(Box::new(searcher)).build();
+
+  let results = engine.search(&books, &Query::builder().text("python").build());
+  assert_eq!(titles(&results), ["Python Crash Course"]);
+
+  assert!(TantivySearch::in_ram(&books, "isbn", &["title"]).is_err());
+}
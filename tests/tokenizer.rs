@@ -0,0 +1,199 @@
+use searus::prelude::*;
+use searus::searchers::analyzer::Analyzer;
+use searus::searchers::tokenizer::Tokenizer;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Splits code identifiers such as `parseHttpRequest` into their words.
+struct IdentifierTokenizer;
+
+impl Tokenizer for IdentifierTokenizer {
+  fn tokenize_with_offsets(&self, text: &str) -> Vec<(String, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (i, c) in text.char_indices() {
+      let boundary = !c.is_alphanumeric() || c.is_uppercase();
+      if let (true, Some(s)) = (boundary, start) {
+        tokens.push((text[s..i].to_lowercase(), s..i));
+        start = None;
+      }
+      if c.is_alphanumeric() && start.is_none() {
+        start = Some(i);
+      }
+    }
+    if let Some(s) = start {
+      tokens.push((text[s..].to_lowercase(), s..text.len()));
+    }
+
+    tokens
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Symbol {
+  name: String,
+}
+
+fn symbols() -> Vec<Symbol> {
+  vec![
+    Symbol {
+      name: "parseHttpRequest".to_string(),
+    },
+    Symbol {
+      name: "render_template".to_string(),
+    },
+  ]
+}
+
+#[test]
+fn test_semantic_search_with_custom_tokenizer() {
+  let rules = SemanticRules::builder()
+    .field("name", FieldRule::tokenized())
+    .build();
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      SemanticSearch::new(rules.clone()).with_tokenizer(Box::new(IdentifierTokenizer)),
+    ))
+    .build();
+
+  let query = Query::builder()
+    .text("http request")
+    .options(SearchOptions::default().highlight(true))
+    .build();
+
+  let results = engine.search(&symbols(), &query);
+  assert_eq!(results.len(), 1);
+
+  let html = highlight_text(&symbols()[0].name, "name", &results[0].highlights, "[", "]");
+  assert_eq!(html, "parse[Http][Request]");
+
+  // The default tokenizer keeps the identifier as a single word.
+  let default = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .build();
+  assert!(default.search(&symbols(), &query).is_empty());
+}
+
+#[test]
+fn test_fuzzy_search_with_custom_tokenizer() {
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      FuzzySearch::new(vec!["name".to_string()]).with_tokenizer(Box::new(IdentifierTokenizer)),
+    ))
+    .build();
+
+  let results = engine.search(&symbols(), &Query::builder().text("templte").build());
+
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.name, "render_template");
+}
+
+#[test]
+fn test_named_analyzer_per_field() {
+  #[derive(Debug, Clone, Serialize, Deserialize)]
+  struct Function {
+    name: String,
+    doc: String,
+  }
+
+  let functions = vec![Function {
+    name: "parseHttpRequest".to_string(),
+    doc: "Reads an HTTP request from a stream".to_string(),
+  }];
+
+  let rules = SemanticRules::builder()
+    .field("name", FieldRule::tokenized().analyzer("identifier"))
+    .field("doc", FieldRule::tokenized())
+    .build();
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules).with_named_analyzer(
+      "identifier",
+      Analyzer::new(Box::new(IdentifierTokenizer)),
+    )))
+    .build();
+
+  let results = engine.search(&functions, &Query::builder().text("parse").build());
+  assert_eq!(results.len(), 1);
+  assert!(results[0].field_scores.contains_key("name"));
+
+  let results = engine.search(&functions, &Query::builder().text("stream").build());
+  assert_eq!(results.len(), 1);
+  assert!(!results[0].field_scores.contains_key("name"));
+}
+
+#[test]
+fn test_builtin_analyzers_per_field() {
+  #[derive(Debug, Clone, Serialize, Deserialize)]
+  struct Product {
+    sku: String,
+    name: String,
+  }
+
+  let products = vec![
+    Product {
+      sku: "LP-1400".to_string(),
+      name: "Laptop".to_string(),
+    },
+    Product {
+      sku: "LP-1400-GR".to_string(),
+      name: "Laptop sleeve".to_string(),
+    },
+  ];
+
+  let rules = SemanticRules::builder()
+    .field("sku", FieldRule::tokenized().analyzer("keyword"))
+    .field(
+      "name",
+      FieldRule::tokenized()
+        .analyzer("edge_ngram")
+        .search_analyzer("standard"),
+    )
+    .build();
+  let engine = SearusEngine::builder()
+    .with(Box::new(SemanticSearch::new(rules)))
+    .build();
+
+  // The keyword analyzer only matches the whole SKU.
+  let results = engine.search(&products, &Query::builder().text("lp-1400").build());
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.sku, "LP-1400");
+
+  // The n-gram analyzer matches the prefix of any word.
+  let results = engine.search(&products, &Query::builder().text("slee").build());
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.name, "Laptop sleeve");
+}
+
+#[cfg(feature = "cjk")]
+#[test]
+fn test_cjk_segmentation() {
+  use searus::searchers::cjk::CjkTokenizer;
+
+  #[derive(Debug, Clone, Serialize, Deserialize)]
+  struct Article {
+    title: String,
+  }
+
+  let articles = vec![
+    Article {
+      title: "我们学习编程语言".to_string(),
+    },
+    Article {
+      title: "今天天气很好".to_string(),
+    },
+  ];
+
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::tokenized())
+    .build();
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      SemanticSearch::new(rules).with_tokenizer(Box::new(CjkTokenizer::new())),
+    ))
+    .build();
+
+  let results = engine.search(&articles, &Query::builder().text("编程").build());
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item.title, "我们学习编程语言");
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "vector")]
+
+use searus::index::DEFAULT_VECTOR;
+use searus::prelude::*;
+use searus::searchers::VectorSearch;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Embeds texts with a stub embedder, counting the calls.
+struct CountingEmbedder {
+  inner: StubTextEmbedder,
+  calls: Arc<AtomicUsize>,
+}
+
+impl TextEmbedder for CountingEmbedder {
+  fn embed(&self, text: &str) -> Result<Vec<f32>, String> {
+    self.calls.fetch_add(1, Ordering::SeqCst);
+    if text == "unembeddable" {
+      return Err("Cannot embed".to_string());
+    }
+    self.inner.embed(text)
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Doc {
+  title: String,
+  embedding: Option<Vec<f32>>,
+}
+
+fn docs() -> Vec<Doc> {
+  let embedder = StubTextEmbedder::new(8);
+  ["rust", "python", "cooking"]
+    .iter()
+    .map(|title| Doc {
+      title: title.to_string(),
+      embedding: Some(embedder.embed(title).unwrap()),
+    })
+    .chain([Doc {
+      title: "draft".to_string(),
+      embedding: None,
+    }])
+    .collect()
+}
+
+fn embedder() -> (Box<dyn TextEmbedder>, Arc<AtomicUsize>) {
+  let calls = Arc::new(AtomicUsize::new(0));
+  let embedder = CountingEmbedder {
+    inner: StubTextEmbedder::new(8),
+    calls: calls.clone(),
+  };
+  (Box::new(embedder), calls)
+}
+
+fn vector_search() -> Box<VectorSearch<Doc>> {
+  Box::new(VectorSearch::new(|doc: &Doc| doc.embedding.clone()).with_min_similarity(-1.0))
+}
+
+#[test]
+fn test_engine_embeds_query_text() {
+  let (embedder, calls) = embedder();
+  let engine = SearusEngine::builder()
+    .with(vector_search())
+    .embedder(embedder)
+    .build();
+  let docs = docs();
+
+  let query = Query::builder()
+    .text("python")
+    .options(SearchOptions::default().explain(true))
+    .build();
+  let results = engine.search(&docs, &query);
+  assert_eq!(calls.load(Ordering::SeqCst), 1);
+  assert_eq!(results.len(), 3);
+  assert_eq!(results[0].item.title, "python");
+  let SearchDetail::Vector {
+    distance,
+    similarity,
+  } = results[0].details[0]
+  else {
+    panic!("expected a vector detail");
+  };
+  assert!((similarity - 1.0).abs() < 1e-6);
+  assert!(distance.abs() < 1e-6);
+
+  // A given vector is used as is.
+  let vector = StubTextEmbedder::new(8).embed("cooking").unwrap();
+  let query = Query::builder().text("python").vector(vector).build();
+  let results = engine.search(&docs, &query);
+  assert_eq!(calls.load(Ordering::SeqCst), 1);
+  assert_eq!(results[0].item.title, "cooking");
+
+  // Texts that can't be embedded match nothing, without failing the search.
+  let query = Query::builder().text("unembeddable").build();
+  assert!(engine.search(&docs, &query).is_empty());
+  let query = Query::builder().text("  ").build();
+  assert!(engine.search(&docs, &query).is_empty());
+  assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_engine_skips_embedding_without_vector_searcher() {
+  let (embedder, calls) = embedder();
+  let engine = SearusEngine::builder()
+    .with(Box::new(FuzzySearch::new(vec!["title".to_string()])))
+    .embedder(embedder)
+    .build();
+
+  let query = Query::builder().text("rust").build();
+  let results = engine.search(&docs(), &query);
+  assert_eq!(results[0].item.title, "rust");
+  assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_query_targets_named_vector() {
+  #[derive(Debug, Clone, Serialize)]
+  struct Product {
+    name: &'static str,
+    text: Vec<f32>,
+    image: Option<Vec<f32>>,
+  }
+  let products = vec![
+    Product {
+      name: "mug",
+      text: vec![1.0, 0.0],
+      image: Some(vec![0.0, 1.0, 0.0]),
+    },
+    Product {
+      name: "cup",
+      text: vec![0.8, 0.2],
+      image: Some(vec![1.0, 0.0, 0.0]),
+    },
+    Product {
+      name: "plate",
+      text: vec![0.0, 1.0],
+      image: None,
+    },
+  ];
+  let engine = SearusEngine::builder()
+    .with(Box::new(
+      VectorSearch::new(|p: &Product| Some(p.text.clone()))
+        .with_named_vector("image", |p: &Product| p.image.clone()),
+    ))
+    .build();
+  let names = |query: &Query| -> Vec<&str> {
+    engine
+      .search(&products, query)
+      .iter()
+      .map(|m| m.item.name)
+      .collect()
+  };
+
+  let query = Query::builder().vector(vec![1.0, 0.0]).build();
+  assert_eq!(names(&query), vec!["mug", "cup"]);
+  let query = Query::builder()
+    .vector(vec![1.0, 0.0])
+    .vector_name(DEFAULT_VECTOR)
+    .build();
+  assert_eq!(names(&query), vec!["mug", "cup"]);
+
+  let query = Query::builder()
+    .vector(vec![1.0, 0.2, 0.0])
+    .vector_name("image")
+    .build();
+  let results = engine.search(&products, &query);
+  assert_eq!(results[0].item.name, "cup");
+  assert!(results[0].field_scores["image"] > 0.9);
+
+  // Vectors the searcher doesn't know match nothing.
+  let query = Query::builder()
+    .vector(vec![1.0, 0.0])
+    .vector_name("audio")
+    .build();
+  assert!(names(&query).is_empty());
+}
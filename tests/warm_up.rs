@@ -0,0 +1,222 @@
+use searus::prelude::*;
+use searus::searchers::tagged::TagRelationshipTree;
+use serde_json::{json, Value};
+
+fn posts() -> Vec<Value> {
+  vec![
+    json!({
+      "title": "Async Rust with Tokio",
+      "body": "Futures and executors",
+      "lang": "en",
+      "tags": ["lang/rust/async", "tokio"],
+      "comments": [{ "text": "Great intro to async" }, { "text": "More tokio please" }],
+    }),
+    json!({
+      "title": "Rust ownership",
+      "body": "Borrowing rules explained",
+      "lang": "en",
+      "tags": ["lang/rust", { "tag": "memory", "weight": 0.5 }],
+      "comments": [{ "text": "Ownership finally clicked" }],
+    }),
+    json!({
+      "title": "Python typing",
+      "body": "Gradual types for async code",
+      "lang": "en",
+      "tags": ["lang/python", "typing"],
+    }),
+    json!({
+      "title": "Asynchrone Programmierung",
+      "body": "Async in Rust",
+      "lang": "de",
+      "tags": ["lang/rust/async", "deprecated"],
+    }),
+  ]
+}
+
+fn engine() -> SearusEngine<Value> {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25().priority(2))
+    .field("body", FieldRule::tfidf())
+    .object(
+      "comments",
+      ObjectRule::array()
+        .field("text", FieldRule::tokenized())
+        .build(),
+    )
+    .build();
+  let trt = TagRelationshipTree::default()
+    .with_relationship("tokio", "lang/rust/async", 0.9)
+    .with_relationship("lang/rust/async", "concurrency", 0.8)
+    .with_relationship("concurrency", "lang/python", 0.5)
+    .with_relationship("memory", "deprecated", -0.5);
+  SearusEngine::builder()
+    .with(Box::new(
+      SemanticSearch::new(rules)
+        .with_language_field("lang")
+        .with_synonyms(SynonymMap::new().with("async", "futures", 0.5)),
+    ))
+    .with(Box::new(
+      TaggedSearch::new().with_trt(trt).with_path_tags('/', 0.5),
+    ))
+    .build()
+}
+
+fn queries() -> Vec<Query> {
+  let options = || SearchOptions::default().explain(true).highlight(true);
+  let mut queries = vec![
+    Query::builder()
+      .text("async rust")
+      .options(options())
+      .build(),
+    Query::builder()
+      .text("ownership")
+      .language("en")
+      .options(options())
+      .build(),
+    Query::builder()
+      .tags(vec!["lang".into(), "memory".into()])
+      .exclude_tags(["typing"])
+      .options(options())
+      .build(),
+    Query::builder()
+      .tags(vec!["lang/rust".into(), "tokio".into()])
+      .options(options().tag_mode(TagMode::All))
+      .build(),
+  ];
+  for depth in 1..=4 {
+    queries.push(
+      Query::builder()
+        .text("tokio")
+        .tags(vec!["tokio".into(), "memory".into()])
+        .options(options().trt_depth(depth))
+        .build(),
+    );
+  }
+  queries
+}
+
+#[test]
+fn test_warm_searches_match_cold_searches() {
+  let items = posts();
+  let engine = engine();
+
+  // Items with the same score come in any order.
+  let search = |query: &Query| {
+    let mut results = engine.search(&items, query);
+    results.sort_by_key(|m| m.id);
+    serde_json::to_value(results).unwrap()
+  };
+  let cold: Vec<Value> = queries().iter().map(search).collect();
+  let _warm = engine.warm_up(&items);
+
+  for (query, expected) in queries().iter().zip(cold) {
+    assert!(!expected.as_array().unwrap().is_empty());
+    assert_eq!(search(query), expected, "{:?}", query);
+  }
+}
+
+#[test]
+fn test_warm_state_is_tied_to_the_warmed_up_slice() {
+  let mut items = posts();
+  let engine = engine();
+  let warm = engine.warm_up(&items);
+
+  // Other slices are searched as if the engine was never warmed up.
+  let others = vec![json!({ "title": "Rust ownership", "tags": ["memory"] })];
+  let query = Query::builder()
+    .text("ownership")
+    .tags(vec!["memory".into()])
+    .build();
+  let results = engine.search(&others, &query);
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].id, 0);
+  assert_eq!(engine.search(&items[..2], &query)[0].id, 1);
+
+  // Changed items are picked up once warmed up again.
+  drop(warm);
+  items[0] = json!({ "title": "Ownership in depth", "tags": ["memory"] });
+  let _warm = engine.warm_up(&items);
+  let results = engine.search(&items, &query);
+  assert_eq!(results.len(), 2);
+  assert!(results
+    .iter()
+    .any(|m| m.item["title"] == "Ownership in depth"));
+}
+
+#[test]
+fn test_freed_slices_leave_no_warm_state() {
+  let engine = engine();
+  let query = Query::builder().text("haskell").build();
+  {
+    let items = posts();
+    let _warm = engine.warm_up(&items);
+    assert!(engine.search(&items, &query).is_empty());
+  }
+
+  // A slice of the same length, usually allocated where the first one was.
+  let mut items = posts();
+  items[3] = json!({ "title": "Haskell monads", "tags": ["lang/haskell"] });
+  let results = engine.search(&items, &query);
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].item["title"], "Haskell monads");
+  let tagged = Query::builder().tags(vec!["lang/haskell".into()]).build();
+  assert_eq!(engine.search(&items, &tagged).len(), 1);
+}
+
+#[test]
+fn test_warm_tagged_searches_serialize_only_the_candidates() {
+  use serde::ser::{Serialize, SerializeStruct, Serializer};
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::sync::Arc;
+
+  /// A post counting how many times it was serialized.
+  #[derive(Clone)]
+  struct Post {
+    title: &'static str,
+    tags: Vec<&'static str>,
+    serialized: Arc<AtomicUsize>,
+  }
+
+  impl Serialize for Post {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+      self.serialized.fetch_add(1, Ordering::Relaxed);
+      let mut post = serializer.serialize_struct("Post", 2)?;
+      post.serialize_field("title", self.title)?;
+      post.serialize_field("tags", &self.tags)?;
+      post.end()
+    }
+  }
+
+  let serialized = Arc::new(AtomicUsize::new(0));
+  let post = |title, tags| Post {
+    title,
+    tags,
+    serialized: serialized.clone(),
+  };
+  let items = vec![
+    post("Rust ownership", vec!["rust"]),
+    post("Python typing", vec!["python"]),
+    post("Async Rust", vec!["rust", "async"]),
+    post("Go channels", vec!["go"]),
+    post("Java streams", vec!["java"]),
+  ];
+  let engine = SearusEngine::builder()
+    .with(Box::new(TaggedSearch::new()))
+    .build();
+  let _warm = engine.warm_up(&items);
+  serialized.store(0, Ordering::Relaxed);
+
+  let query = Query::builder()
+    .tags(vec!["rust".into()])
+    .filters(
+      Query::filter(Query::COMPARE)
+        .eq("title", "Rust ownership")
+        .build(),
+    )
+    .build();
+  let results = engine.search(&items, &query);
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0].id, 0);
+  // Only the two posts tagged `rust` were serialized to apply the filter.
+  assert_eq!(serialized.load(Ordering::Relaxed), 2);
+}
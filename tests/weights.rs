@@ -0,0 +1,109 @@
+use searus::config::EngineConfig;
+use searus::prelude::*;
+use serde_json::{json, Value};
+
+fn items() -> Vec<Value> {
+  vec![
+    json!({ "title": "Rust ownership", "tags": ["rust"] }),
+    json!({ "title": "Python typing", "tags": ["python"] }),
+  ]
+}
+
+/// Returns the weights applied to the searchers matching the first result.
+fn weights(engine: &SearusEngine<Value>, options: SearchOptions) -> Vec<String> {
+  let query = Query::builder()
+    .text("rust")
+    .tags(vec!["rust".to_string()])
+    .options(options.explain(true))
+    .build();
+  let results = engine.search(&items(), &query);
+  let mut weights: Vec<String> = results[0]
+    .explain()
+    .unwrap()
+    .details
+    .iter()
+    .map(|detail| detail.description.clone())
+    .collect();
+  weights.sort();
+  weights
+}
+
+fn engine(builder: SearusEngineBuilder<Value>) -> SearusEngine<Value> {
+  let rules = SemanticRules::builder()
+    .field("title", FieldRule::bm25())
+    .build();
+  builder
+    .with(Box::new(SemanticSearch::new(rules)))
+    .with(Box::new(TaggedSearch::new()))
+    .build()
+}
+
+#[test]
+fn test_weights_fall_back_from_query_to_preset_to_engine() {
+  let engine = engine(
+    SearusEngine::builder()
+      .default_weight(SearcherKind::Semantic, 0.5)
+      .default_weights([(SearcherKind::Tags, 0.25)]),
+  );
+
+  assert_eq!(
+    weights(&engine, SearchOptions::default()),
+    ["weight 0.25 times:", "weight 0.5 times:"]
+  );
+  assert_eq!(
+    weights(
+      &engine,
+      SearchOptions::default().weight(SearcherKind::Tags, 2.0)
+    ),
+    ["weight 0.5 times:", "weight 2 times:"]
+  );
+  // A preset replaces the engine's defaults, but not the query's weights.
+  assert_eq!(
+    weights(
+      &engine,
+      SearchOptions::default()
+        .preset(WeightPreset::TagHeavy)
+        .weight(SearcherKind::Semantic, 0.1)
+    ),
+    ["weight 0.1 times:", "weight 1 times:"]
+  );
+  assert_eq!(
+    weights(
+      &engine,
+      SearchOptions::default().preset(WeightPreset::Balanced)
+    ),
+    ["weight 1 times:", "weight 1 times:"]
+  );
+}
+
+#[test]
+fn test_presets_are_named_in_kebab_case() {
+  let options: SearchOptions = serde_json::from_value(json!({ "preset": "text-heavy" })).unwrap();
+  assert_eq!(options.preset, Some(WeightPreset::TextHeavy));
+  assert!(serde_json::from_value::<SearchOptions>(json!({ "preset": "heavy" })).is_err());
+
+  let preset = WeightPreset::TextHeavy;
+  assert_eq!(preset.weight(SearcherKind::Semantic), 1.0);
+  assert!(preset.weight(SearcherKind::Fuzzy) > preset.weight(SearcherKind::Tags));
+  assert_eq!(preset.weight(SearcherKind::Custom), 1.0);
+  assert_eq!(
+    WeightPreset::VectorHeavy.weight(SearcherKind::Image),
+    WeightPreset::VectorHeavy.weight(SearcherKind::Vector)
+  );
+}
+
+#[test]
+fn test_engine_config_sets_default_weights() {
+  let config = EngineConfig::from_json(
+    r#"{
+      "semantic": { "fields": { "title": { "matcher": "bm25" } } },
+      "tags": {},
+      "weights": { "Semantic": 0.75 }
+    }"#,
+  )
+  .unwrap();
+  assert_eq!(
+    weights(&config.build(), SearchOptions::default()),
+    ["weight 0.75 times:", "weight 1 times:"]
+  );
+}